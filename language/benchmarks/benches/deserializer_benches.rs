@@ -0,0 +1,82 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use criterion::{
+    criterion_group, criterion_main, measurement::Measurement, BenchmarkId, Criterion, Throughput,
+};
+use language_benchmarks::measurement::cpu_time_measurement;
+use move_stdlib::{move_stdlib_bundle, substrate_stdlib_bundle};
+
+//
+// Deserializer benchmarks
+//
+// Every input is deserialized both by the local no_std deserializer and by the upstream one, so
+// regressions show up against a stable baseline rather than only against the previous run.
+//
+
+/// Number of the largest modules benchmarked one by one.
+const WORST_CASE_MODULES: usize = 3;
+
+fn bundle_modules(bundle: &[u8]) -> Vec<Vec<u8>> {
+    bcs::from_bytes(bundle).expect("stdlib bundles are valid")
+}
+
+fn deserialize_local(modules: &[Vec<u8>]) {
+    for module in modules {
+        move_binary_format::CompiledModule::deserialize(module).expect("valid module");
+    }
+}
+
+fn deserialize_upstream(modules: &[Vec<u8>]) {
+    for module in modules {
+        upstream_move_binary_format::CompiledModule::deserialize(module).expect("valid module");
+    }
+}
+
+fn bench_modules<M: Measurement>(c: &mut Criterion<M>, name: &str, modules: &[Vec<u8>]) {
+    let bytes: usize = modules.iter().map(Vec::len).sum();
+
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(bytes as u64));
+    group.bench_with_input(BenchmarkId::new("local", bytes), modules, |b, modules| {
+        b.iter(|| deserialize_local(modules))
+    });
+    group.bench_with_input(
+        BenchmarkId::new("upstream", bytes),
+        modules,
+        |b, modules| b.iter(|| deserialize_upstream(modules)),
+    );
+    group.finish();
+}
+
+fn move_stdlib<M: Measurement>(c: &mut Criterion<M>) {
+    bench_modules(c, "move_stdlib", &bundle_modules(move_stdlib_bundle()));
+}
+
+fn substrate_stdlib<M: Measurement>(c: &mut Criterion<M>) {
+    bench_modules(
+        c,
+        "substrate_stdlib",
+        &bundle_modules(substrate_stdlib_bundle()),
+    );
+}
+
+fn worst_case_modules<M: Measurement>(c: &mut Criterion<M>) {
+    let mut modules = bundle_modules(move_stdlib_bundle());
+    modules.extend(bundle_modules(substrate_stdlib_bundle()));
+    modules.sort_by_key(|module| core::cmp::Reverse(module.len()));
+
+    for (i, module) in modules.into_iter().take(WORST_CASE_MODULES).enumerate() {
+        bench_modules(c, &format!("largest_module_{i}"), &[module]);
+    }
+}
+
+criterion_group!(
+    name = deserializer_benches;
+    config = cpu_time_measurement();
+    targets = move_stdlib,
+    substrate_stdlib,
+    worst_case_modules
+);
+
+criterion_main!(deserializer_benches);
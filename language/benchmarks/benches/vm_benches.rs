@@ -21,12 +21,22 @@ fn natives<M: Measurement + 'static>(c: &mut Criterion<M>) {
     bench(c, "natives");
 }
 
+fn tight_loop<M: Measurement + 'static>(c: &mut Criterion<M>) {
+    bench(c, "tight_loop");
+}
+
+fn unrolled_loop<M: Measurement + 'static>(c: &mut Criterion<M>) {
+    bench(c, "unrolled_loop");
+}
+
 criterion_group!(
     name = vm_benches;
     config = cpu_time_measurement();
     targets = arith,
     call,
-    natives
+    natives,
+    tight_loop,
+    unrolled_loop
 );
 
 criterion_main!(vm_benches);
@@ -72,6 +72,35 @@ module 0x1::Bench {
         100 + 300
     }
 
+    //
+    // `tight_loop` and `unrolled_loop` benchmarks
+    //
+    // Both run the same 10000 increments, but the unrolled loop takes 10 times fewer iterations.
+    // The time difference divided by the 9000 saved iterations is the overhead of a single loop
+    // iteration, which the back-edge gas cost is based on.
+    public fun tight_loop() {
+        let i = 0;
+        while (i < 10000) {
+            i = i + 1;
+        };
+    }
+
+    public fun unrolled_loop() {
+        let i = 0;
+        while (i < 10000) {
+            i = i + 1;
+            i = i + 1;
+            i = i + 1;
+            i = i + 1;
+            i = i + 1;
+            i = i + 1;
+            i = i + 1;
+            i = i + 1;
+            i = i + 1;
+            i = i + 1;
+        };
+    }
+
     //
     // `natives` benchmark
     //
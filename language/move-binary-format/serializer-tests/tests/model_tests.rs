@@ -0,0 +1,362 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Model-based roundtrip tests of the serializer and the deserializer.
+//!
+//! Instead of generating arbitrary tables, the tests generate a small model of a module - structs,
+//! functions and constants - and build the tables from it the way the compiler does, so every
+//! index is in bounds. The built module is then serialized with every supported version and has
+//! to come back unchanged.
+
+use move_binary_format::{
+    check_bounds::BoundsChecker,
+    file_format::{
+        empty_module, AbilitySet, Bytecode, CodeUnit, CompiledModule, Constant, ConstantPoolIndex,
+        FieldDefinition, FunctionDefinition, FunctionHandle, FunctionHandleIndex, IdentifierIndex,
+        ModuleHandleIndex, Signature, SignatureIndex, SignatureToken, StructDefinition,
+        StructFieldInformation, StructHandle, StructHandleIndex, TableIndex, TypeSignature,
+        Visibility,
+    },
+    file_format_common::{VERSION_6, VERSION_MAX, VERSION_MIN},
+};
+use move_core_types::{identifier::Identifier, metadata::Metadata, u256::U256};
+use proptest::{collection::vec, prelude::*};
+
+/// Value type of the model, the struct types refer to the structs of the model by position.
+#[derive(Clone, Debug)]
+enum ModelType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    Address,
+    Vector(Box<ModelType>),
+    Struct(usize),
+}
+
+impl ModelType {
+    fn strategy() -> impl Strategy<Value = Self> {
+        let leaf = prop_oneof![
+            Just(Self::Bool),
+            Just(Self::U8),
+            Just(Self::U16),
+            Just(Self::U32),
+            Just(Self::U64),
+            Just(Self::U128),
+            Just(Self::U256),
+            Just(Self::Address),
+            any::<usize>().prop_map(Self::Struct),
+        ];
+        leaf.prop_recursive(2, 4, 1, |inner| {
+            inner.prop_map(|t| Self::Vector(Box::new(t)))
+        })
+    }
+
+    fn token(&self, struct_count: usize) -> SignatureToken {
+        match self {
+            Self::Bool => SignatureToken::Bool,
+            Self::U8 => SignatureToken::U8,
+            Self::U16 => SignatureToken::U16,
+            Self::U32 => SignatureToken::U32,
+            Self::U64 => SignatureToken::U64,
+            Self::U128 => SignatureToken::U128,
+            Self::U256 => SignatureToken::U256,
+            Self::Address => SignatureToken::Address,
+            Self::Vector(t) => SignatureToken::Vector(Box::new(t.token(struct_count))),
+            Self::Struct(_) if struct_count == 0 => SignatureToken::U64,
+            Self::Struct(idx) => {
+                SignatureToken::Struct(StructHandleIndex((idx % struct_count) as TableIndex))
+            }
+        }
+    }
+}
+
+/// Instruction of the model, the indices are wrapped into the bounds of the built tables.
+#[derive(Clone, Debug)]
+enum ModelInstr {
+    LdTrue,
+    LdU64(u64),
+    LdU16(u16),
+    LdU256(u128),
+    CastU16,
+    LdConst(usize),
+    CopyLoc(usize),
+    Branch(usize),
+    Pop,
+}
+
+impl ModelInstr {
+    fn strategy() -> impl Strategy<Value = Self> {
+        prop_oneof![
+            Just(Self::LdTrue),
+            any::<u64>().prop_map(Self::LdU64),
+            any::<u16>().prop_map(Self::LdU16),
+            any::<u128>().prop_map(Self::LdU256),
+            Just(Self::CastU16),
+            any::<usize>().prop_map(Self::LdConst),
+            any::<usize>().prop_map(Self::CopyLoc),
+            any::<usize>().prop_map(Self::Branch),
+            Just(Self::Pop),
+        ]
+    }
+
+    fn bytecode(&self, code_len: usize, locals_count: usize, constants: usize) -> Bytecode {
+        match self {
+            Self::LdTrue => Bytecode::LdTrue,
+            Self::LdU64(v) => Bytecode::LdU64(*v),
+            Self::LdU16(v) => Bytecode::LdU16(*v),
+            Self::LdU256(v) => Bytecode::LdU256(U256::from(*v)),
+            Self::CastU16 => Bytecode::CastU16,
+            Self::LdConst(_) if constants == 0 => Bytecode::LdTrue,
+            Self::LdConst(idx) => {
+                Bytecode::LdConst(ConstantPoolIndex((idx % constants) as TableIndex))
+            }
+            Self::CopyLoc(_) if locals_count == 0 => Bytecode::LdTrue,
+            Self::CopyLoc(idx) => Bytecode::CopyLoc((idx % locals_count) as u8),
+            Self::Branch(idx) => Bytecode::Branch((idx % code_len) as u16),
+            Self::Pop => Bytecode::Pop,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct StructModel {
+    abilities: AbilitySet,
+    fields: Vec<ModelType>,
+}
+
+#[derive(Clone, Debug)]
+struct FunctionModel {
+    visibility: Visibility,
+    is_entry: bool,
+    parameters: Vec<ModelType>,
+    returns: Vec<ModelType>,
+    locals: Vec<ModelType>,
+    body: Vec<ModelInstr>,
+}
+
+#[derive(Clone, Debug)]
+struct ModuleModel {
+    structs: Vec<StructModel>,
+    functions: Vec<FunctionModel>,
+    constants: Vec<u64>,
+    metadata: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl ModuleModel {
+    fn strategy() -> impl Strategy<Value = Self> {
+        let struct_model = (
+            (0u8..16).prop_map(|b| AbilitySet::from_u8(b).expect("valid abilities")),
+            vec(ModelType::strategy(), 1..4),
+        )
+            .prop_map(|(abilities, fields)| StructModel { abilities, fields });
+        let visibility = prop_oneof![
+            Just(Visibility::Private),
+            Just(Visibility::Public),
+            Just(Visibility::Friend),
+        ];
+        let function_model = (
+            visibility,
+            any::<bool>(),
+            vec(ModelType::strategy(), 0..3),
+            vec(ModelType::strategy(), 0..3),
+            vec(ModelType::strategy(), 0..3),
+            vec(ModelInstr::strategy(), 0..8),
+        )
+            .prop_map(
+                |(visibility, is_entry, parameters, returns, locals, body)| FunctionModel {
+                    visibility,
+                    is_entry,
+                    parameters,
+                    returns,
+                    locals,
+                    body,
+                },
+            );
+
+        (
+            vec(struct_model, 0..4),
+            vec(function_model, 0..4),
+            vec(any::<u64>(), 0..4),
+            vec((vec(any::<u8>(), 0..8), vec(any::<u8>(), 0..8)), 0..2),
+        )
+            .prop_map(|(structs, functions, constants, metadata)| Self {
+                structs,
+                functions,
+                constants,
+                metadata,
+            })
+    }
+
+    /// Whether the model uses the types or instructions introduced in version 6.
+    fn requires_v6(&self) -> bool {
+        fn is_v6(t: &ModelType) -> bool {
+            match t {
+                ModelType::U16 | ModelType::U32 | ModelType::U256 => true,
+                ModelType::Vector(t) => is_v6(t),
+                _ => false,
+            }
+        }
+
+        self.structs.iter().flat_map(|s| &s.fields).any(is_v6)
+            || self.functions.iter().any(|f| {
+                f.parameters
+                    .iter()
+                    .chain(&f.returns)
+                    .chain(&f.locals)
+                    .any(is_v6)
+                    || f.body.iter().any(|i| {
+                        matches!(
+                            i,
+                            ModelInstr::LdU16(_) | ModelInstr::LdU256(_) | ModelInstr::CastU16
+                        )
+                    })
+            })
+    }
+
+    /// Build the module tables from the model.
+    fn build(&self) -> CompiledModule {
+        let mut module = empty_module();
+        let struct_count = self.structs.len();
+
+        for (idx, model) in self.structs.iter().enumerate() {
+            let name = identifier(&mut module, format!("S{}", idx));
+            module.struct_handles.push(StructHandle {
+                module: ModuleHandleIndex(0),
+                name,
+                abilities: model.abilities,
+                type_parameters: vec![],
+            });
+            let fields = model
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(field, ty)| FieldDefinition {
+                    name: identifier(&mut module, format!("f{}", field)),
+                    signature: TypeSignature(ty.token(struct_count)),
+                })
+                .collect();
+            module.struct_defs.push(StructDefinition {
+                struct_handle: StructHandleIndex(idx as TableIndex),
+                field_information: StructFieldInformation::Declared(fields),
+            });
+        }
+
+        for value in &self.constants {
+            module.constant_pool.push(Constant {
+                type_: SignatureToken::U64,
+                data: value.to_le_bytes().to_vec(),
+            });
+        }
+
+        for (idx, model) in self.functions.iter().enumerate() {
+            let name = identifier(&mut module, format!("fun{}", idx));
+            let parameters = signature(&mut module, &model.parameters, struct_count);
+            let return_ = signature(&mut module, &model.returns, struct_count);
+            let locals = signature(&mut module, &model.locals, struct_count);
+            module.function_handles.push(FunctionHandle {
+                module: ModuleHandleIndex(0),
+                name,
+                parameters,
+                return_,
+                type_parameters: vec![],
+            });
+
+            let code_len = model.body.len() + 1;
+            let locals_count = model.parameters.len() + model.locals.len();
+            let mut code: Vec<_> = model
+                .body
+                .iter()
+                .map(|i| i.bytecode(code_len, locals_count, self.constants.len()))
+                .collect();
+            code.push(Bytecode::Ret);
+            module.function_defs.push(FunctionDefinition {
+                function: FunctionHandleIndex(idx as TableIndex),
+                visibility: model.visibility,
+                is_entry: model.is_entry,
+                acquires_global_resources: vec![],
+                code: Some(CodeUnit { locals, code }),
+            });
+        }
+
+        module.metadata = self
+            .metadata
+            .iter()
+            .map(|(key, value)| Metadata {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect();
+        module
+    }
+}
+
+fn identifier(module: &mut CompiledModule, name: String) -> IdentifierIndex {
+    let name = Identifier::new(name).expect("valid identifier");
+    let idx = match module.identifiers.iter().position(|i| *i == name) {
+        Some(idx) => idx,
+        None => {
+            module.identifiers.push(name);
+            module.identifiers.len() - 1
+        }
+    };
+    IdentifierIndex(idx as TableIndex)
+}
+
+fn signature(module: &mut CompiledModule, types: &[ModelType], structs: usize) -> SignatureIndex {
+    let sig = Signature(types.iter().map(|t| t.token(structs)).collect());
+    let idx = match module.signatures.iter().position(|s| *s == sig) {
+        Some(idx) => idx,
+        None => {
+            module.signatures.push(sig);
+            module.signatures.len() - 1
+        }
+    };
+    SignatureIndex(idx as TableIndex)
+}
+
+/// Versions the built modules are serialized with.
+fn versions() -> Vec<u32> {
+    #[allow(unused_mut)]
+    let mut versions: Vec<_> = (VERSION_MIN..=VERSION_MAX).collect();
+    #[cfg(feature = "extended-format")]
+    versions.push(move_binary_format::file_format_common::VERSION_EXTENDED);
+    versions
+}
+
+proptest! {
+    #[test]
+    fn model_roundtrip(model in ModuleModel::strategy()) {
+        let module = model.build();
+        prop_assert!(BoundsChecker::verify_module(&module).is_ok());
+
+        for version in versions() {
+            let mut serialized = Vec::new();
+            let result = module
+                .serialize_for_version(Some(version), &mut serialized)
+                .map_err(|e| e.to_string())
+                .and_then(|_| {
+                    CompiledModule::deserialize_with_max_version(&serialized, version)
+                        .map_err(|e| e.to_string())
+                });
+
+            if version < VERSION_6 && model.requires_v6() {
+                prop_assert!(result.is_err(), "v6 features accepted by version {}", version);
+                continue;
+            }
+
+            let deserialized = result.expect("roundtrip should work");
+            prop_assert_eq!(deserialized.version, version);
+            prop_assert_eq!(&deserialized, &CompiledModule { version, ..module.clone() });
+
+            if version > VERSION_MIN {
+                prop_assert!(
+                    CompiledModule::deserialize_with_max_version(&serialized, version - 1).is_err()
+                );
+            }
+        }
+    }
+}
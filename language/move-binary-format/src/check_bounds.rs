@@ -662,7 +662,7 @@ impl<'a> BoundsChecker<'a> {
             None => Err(bounds_error(
                 StatusCode::INDEX_OUT_OF_BOUNDS,
                 IndexKind::Signature,
-                code_unit.locals.into_index() as u16,
+                code_unit.locals.into_index() as TableIndex,
                 self.view.signatures().len(),
             )),
         }
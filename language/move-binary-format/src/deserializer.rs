@@ -89,15 +89,18 @@ impl CompiledModule {
 
 /// Table info: table type, offset where the table content starts from, count of bytes for
 /// the table content.
+///
+/// The table headers are read with the raw table type, as the experimental tables have no
+/// `TableType`, and the known tables are picked after the layout check.
 #[derive(Clone, Debug)]
-struct Table {
-    kind: TableType,
+struct Table<K = TableType> {
+    kind: K,
     offset: u32,
     count: u32,
 }
 
-impl Table {
-    fn new(kind: TableType, offset: u32, count: u32) -> Table {
+impl<K> Table<K> {
+    fn new(kind: K, offset: u32, count: u32) -> Table<K> {
         Table {
             kind,
             offset,
@@ -170,31 +173,40 @@ where
     })
 }
 
+/// Returns the table index limit, which is raised for the extended binary format.
+fn table_index_max(cursor: &VersionedCursor, max: u64) -> u64 {
+    if cursor.version() == VERSION_EXTENDED {
+        EXTENDED_TABLE_INDEX_MAX
+    } else {
+        max
+    }
+}
+
 fn load_signature_index(cursor: &mut VersionedCursor) -> BinaryLoaderResult<SignatureIndex> {
     Ok(SignatureIndex(read_uleb_internal(
         cursor,
-        SIGNATURE_INDEX_MAX,
+        table_index_max(cursor, SIGNATURE_INDEX_MAX),
     )?))
 }
 
 fn load_module_handle_index(cursor: &mut VersionedCursor) -> BinaryLoaderResult<ModuleHandleIndex> {
     Ok(ModuleHandleIndex(read_uleb_internal(
         cursor,
-        MODULE_HANDLE_INDEX_MAX,
+        table_index_max(cursor, MODULE_HANDLE_INDEX_MAX),
     )?))
 }
 
 fn load_identifier_index(cursor: &mut VersionedCursor) -> BinaryLoaderResult<IdentifierIndex> {
     Ok(IdentifierIndex(read_uleb_internal(
         cursor,
-        IDENTIFIER_INDEX_MAX,
+        table_index_max(cursor, IDENTIFIER_INDEX_MAX),
     )?))
 }
 
 fn load_struct_handle_index(cursor: &mut VersionedCursor) -> BinaryLoaderResult<StructHandleIndex> {
     Ok(StructHandleIndex(read_uleb_internal(
         cursor,
-        STRUCT_HANDLE_INDEX_MAX,
+        table_index_max(cursor, STRUCT_HANDLE_INDEX_MAX),
     )?))
 }
 
@@ -203,7 +215,7 @@ fn load_address_identifier_index(
 ) -> BinaryLoaderResult<AddressIdentifierIndex> {
     Ok(AddressIdentifierIndex(read_uleb_internal(
         cursor,
-        ADDRESS_INDEX_MAX,
+        table_index_max(cursor, ADDRESS_INDEX_MAX),
     )?))
 }
 
@@ -212,7 +224,7 @@ fn load_struct_def_index(
 ) -> BinaryLoaderResult<StructDefinitionIndex> {
     Ok(StructDefinitionIndex(read_uleb_internal(
         cursor,
-        STRUCT_DEF_INDEX_MAX,
+        table_index_max(cursor, STRUCT_DEF_INDEX_MAX),
     )?))
 }
 
@@ -221,14 +233,14 @@ fn load_function_handle_index(
 ) -> BinaryLoaderResult<FunctionHandleIndex> {
     Ok(FunctionHandleIndex(read_uleb_internal(
         cursor,
-        FUNCTION_HANDLE_INDEX_MAX,
+        table_index_max(cursor, FUNCTION_HANDLE_INDEX_MAX),
     )?))
 }
 
 fn load_field_handle_index(cursor: &mut VersionedCursor) -> BinaryLoaderResult<FieldHandleIndex> {
     Ok(FieldHandleIndex(read_uleb_internal(
         cursor,
-        FIELD_HANDLE_INDEX_MAX,
+        table_index_max(cursor, FIELD_HANDLE_INDEX_MAX),
     )?))
 }
 
@@ -237,7 +249,7 @@ fn load_field_inst_index(
 ) -> BinaryLoaderResult<FieldInstantiationIndex> {
     Ok(FieldInstantiationIndex(read_uleb_internal(
         cursor,
-        FIELD_INST_INDEX_MAX,
+        table_index_max(cursor, FIELD_INST_INDEX_MAX),
     )?))
 }
 
@@ -246,7 +258,7 @@ fn load_function_inst_index(
 ) -> BinaryLoaderResult<FunctionInstantiationIndex> {
     Ok(FunctionInstantiationIndex(read_uleb_internal(
         cursor,
-        FUNCTION_INST_INDEX_MAX,
+        table_index_max(cursor, FUNCTION_INST_INDEX_MAX),
     )?))
 }
 
@@ -255,14 +267,14 @@ fn load_struct_def_inst_index(
 ) -> BinaryLoaderResult<StructDefInstantiationIndex> {
     Ok(StructDefInstantiationIndex(read_uleb_internal(
         cursor,
-        STRUCT_DEF_INST_INDEX_MAX,
+        table_index_max(cursor, STRUCT_DEF_INST_INDEX_MAX),
     )?))
 }
 
 fn load_constant_pool_index(cursor: &mut VersionedCursor) -> BinaryLoaderResult<ConstantPoolIndex> {
     Ok(ConstantPoolIndex(read_uleb_internal(
         cursor,
-        CONSTANT_INDEX_MAX,
+        table_index_max(cursor, CONSTANT_INDEX_MAX),
     )?))
 }
 
@@ -338,9 +350,10 @@ fn deserialize_compiled_script(
     let binary_len = binary.len();
     let mut cursor = VersionedCursor::new(binary, max_binary_format_version)?;
     let table_count = load_table_count(&mut cursor)?;
-    let mut tables: Vec<Table> = Vec::new();
+    let mut tables: Vec<Table<u8>> = Vec::new();
     read_tables(&mut cursor, table_count, &mut tables)?;
     let content_len = check_tables(&mut tables, binary_len)?;
+    let tables = known_tables(tables)?;
 
     let mut table_contents_buffer = Vec::new();
     let table_contents = read_table_contents(
@@ -372,9 +385,10 @@ fn deserialize_compiled_module(
     let binary_len = binary.len();
     let mut cursor = VersionedCursor::new(binary, max_binary_format_version)?;
     let table_count = load_table_count(&mut cursor)?;
-    let mut tables: Vec<Table> = Vec::new();
+    let mut tables: Vec<Table<u8>> = Vec::new();
     read_tables(&mut cursor, table_count, &mut tables)?;
     let content_len = check_tables(&mut tables, binary_len)?;
+    let tables = known_tables(tables)?;
 
     let mut table_contents_buffer = Vec::new();
     let table_contents = read_table_contents(
@@ -400,7 +414,7 @@ fn deserialize_compiled_module(
 fn read_tables(
     cursor: &mut VersionedCursor,
     table_count: u8,
-    tables: &mut Vec<Table>,
+    tables: &mut Vec<Table<u8>>,
 ) -> BinaryLoaderResult<()> {
     for _count in 0..table_count {
         tables.push(read_table(cursor)?);
@@ -410,7 +424,7 @@ fn read_tables(
 
 /// Reads a table from a slice at a given offset.
 /// If a table is not recognized an error is returned.
-fn read_table(cursor: &mut VersionedCursor) -> BinaryLoaderResult<Table> {
+fn read_table(cursor: &mut VersionedCursor) -> BinaryLoaderResult<Table<u8>> {
     let kind = match cursor.read_u8() {
         Ok(kind) => kind,
         Err(_) => {
@@ -420,7 +434,30 @@ fn read_table(cursor: &mut VersionedCursor) -> BinaryLoaderResult<Table> {
     };
     let table_offset = load_table_offset(cursor)?;
     let count = load_table_size(cursor)?;
-    Ok(Table::new(TableType::from_u8(kind)?, table_offset, count))
+    if !is_experimental_table(cursor.version(), kind) {
+        TableType::from_u8(kind)?;
+    }
+    Ok(Table::new(kind, table_offset, count))
+}
+
+/// Whether the table is an experimental one, skipped when loading the binary.
+fn is_experimental_table(version: u32, kind: u8) -> bool {
+    version == VERSION_7 && EXPERIMENTAL_TABLE_TYPES.contains(&kind)
+}
+
+/// Picks the known tables, skipping the experimental ones.
+fn known_tables(tables: Vec<Table<u8>>) -> BinaryLoaderResult<Vec<Table>> {
+    let mut known = Vec::with_capacity(tables.len());
+    for table in tables {
+        if !EXPERIMENTAL_TABLE_TYPES.contains(&table.kind) {
+            known.push(Table::new(
+                TableType::from_u8(table.kind)?,
+                table.offset,
+                table.count,
+            ));
+        }
+    }
+    Ok(known)
 }
 
 fn read_table_contents<'a>(
@@ -436,7 +473,7 @@ fn read_table_contents<'a>(
 /// Verify correctness of tables.
 ///
 /// Tables cannot have duplicates, must cover the entire blob and must be disjoint.
-fn check_tables(tables: &mut Vec<Table>, binary_len: usize) -> BinaryLoaderResult<u32> {
+fn check_tables(tables: &mut Vec<Table<u8>>, binary_len: usize) -> BinaryLoaderResult<u32> {
     // there is no real reason to pass a mutable reference but we are sorting next line
     tables.sort_by(|t1, t2| t1.offset.cmp(&t2.offset));
 
@@ -969,6 +1006,17 @@ pub fn load_signature_token_test_entry(
     load_signature_token(&mut VersionedCursor::new_for_test(VERSION_MAX, cursor))
 }
 
+/// Deserializes a single `SignatureToken` of the given bytecode version, e.g. for the fuzzers and
+/// the regression corpus.
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn load_signature_token_for_version(
+    version: u32,
+    bytes: &[u8],
+) -> BinaryLoaderResult<SignatureToken> {
+    let cursor = crate::cursor::Cursor::new(bytes);
+    load_signature_token(&mut VersionedCursor::new_for_test(version, cursor))
+}
+
 /// Deserializes a `SignatureToken`.
 fn load_signature_token(cursor: &mut VersionedCursor) -> BinaryLoaderResult<SignatureToken> {
     // The following algorithm works by storing partially constructed types on a stack.
@@ -1456,6 +1504,14 @@ fn load_code(cursor: &mut VersionedCursor, code: &mut Vec<Bytecode>) -> BinaryLo
         let byte = cursor.read_u8().map_err(|_| {
             PartialVMError::new(StatusCode::MALFORMED).with_message("Unexpected EOF".to_string())
         })?;
+        if cursor.version() == VERSION_7 && EXPERIMENTAL_OPCODES.contains(&byte) {
+            return Err(
+                PartialVMError::new(StatusCode::UNKNOWN_OPCODE).with_message(format!(
+                    "Experimental opcode {:#04x} of bytecode version {} not supported",
+                    byte, VERSION_7
+                )),
+            );
+        }
         let opcode = Opcodes::from_u8(byte)?;
         // version checking
         match opcode {
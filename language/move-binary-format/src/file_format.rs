@@ -53,15 +53,22 @@ use serde::{Deserialize, Serialize};
 use variant_count::VariantCount;
 
 /// Generic index into one of the tables in the binary format.
+#[cfg(not(feature = "extended-format"))]
 pub type TableIndex = u16;
 
+/// Generic index into one of the tables in the binary format.
+///
+/// The extended binary format allows tables with more than `u16::MAX` entries.
+#[cfg(feature = "extended-format")]
+pub type TableIndex = u32;
+
 macro_rules! define_index {
     {
         name: $name: ident,
         kind: $kind: ident,
         doc: $comment: literal,
     } => {
-        #[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        #[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
         #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
         #[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
         #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -218,7 +225,7 @@ pub const NO_TYPE_ARGUMENTS: SignatureIndex = SignatureIndex(0);
 /// Modules introduce a scope made of all types defined in the module and all functions.
 /// Type definitions (fields) are private to the module. Outside the module a
 /// Type is an opaque handle.
-#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -242,7 +249,7 @@ pub struct ModuleHandle {
 ///
 /// At link time ability/constraint checking is performed and an error is reported if there is a
 /// mismatch with the definition.
-#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -284,7 +291,7 @@ pub struct StructTypeParameter {
 /// and the verifier enforces that property. The signature of the function is used at link time to
 /// ensure the function reference is valid and it is also used by the verifier to type check
 /// function calls.
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(params = "usize"))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -302,7 +309,7 @@ pub struct FunctionHandle {
 }
 
 /// A field access info (owner type and offset)
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -315,7 +322,7 @@ pub struct FieldHandle {
 // Definitions are the module code. So the set of types and functions in the module.
 
 /// `StructFieldInformation` indicates whether a struct is native or has user-specified fields
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -333,7 +340,7 @@ pub enum StructFieldInformation {
 // `StructInstantiation`s
 
 /// A complete or partial instantiation of a generic struct
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -343,7 +350,7 @@ pub struct StructDefInstantiation {
 }
 
 /// A complete or partial instantiation of a function
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -358,7 +365,7 @@ pub struct FunctionInstantiation {
 /// of the owner type.
 /// E.g. for `S<u8, bool>.f` where `f` is a field of any type, `instantiation`
 /// would be `[u8, bool]`
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -369,7 +376,7 @@ pub struct FieldInstantiation {
 
 /// A `StructDefinition` is a type definition. It either indicates it is native or defines all the
 /// user-specified fields declared on the type.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -389,7 +396,7 @@ impl StructDefinition {
             // TODO we might want a more informative error here
             StructFieldInformation::Native => Err(PartialVMError::new(StatusCode::LINKER_ERROR)
                 .with_message("Looking for field in native structure".to_string())),
-            StructFieldInformation::Declared(fields) => Ok(fields.len() as u16),
+            StructFieldInformation::Declared(fields) => Ok(fields.len() as MemberCount),
         }
     }
 
@@ -402,7 +409,7 @@ impl StructDefinition {
 }
 
 /// A `FieldDefinition` is the definition of a field: its name and the field type.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -457,7 +464,7 @@ impl core::convert::TryFrom<u8> for Visibility {
 
 /// A `FunctionDefinition` is the implementation of a function. It defines
 /// the *prototype* of the function and the function body.
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(params = "usize"))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -510,7 +517,7 @@ impl FunctionDefinition {
 
 /// A type definition. `SignatureToken` allows the definition of the set of known types and their
 /// composition.
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -544,7 +551,7 @@ pub struct FunctionSignature {
 ///
 /// Locals include the arguments to the function from position `0` to argument `count - 1`.
 /// The remaining elements are the type of each local.
-#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(params = "usize"))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -840,7 +847,7 @@ impl Arbitrary for AbilitySet {
 ///
 /// A SignatureToken can express more types than the VM can handle safely, and correctness is
 /// enforced by the verifier.
-#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
 pub enum SignatureToken {
     /// Boolean, `true` or `false`.
@@ -1118,7 +1125,7 @@ impl SignatureToken {
 
 /// A `Constant` is a serialized value along with its type. That type will be deserialized by the
 /// loader/evauluator
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
 pub struct Constant {
     pub type_: SignatureToken,
@@ -1126,7 +1133,7 @@ pub struct Constant {
 }
 
 /// A `CodeUnit` is the body of a function. It has the function header and the instruction stream.
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(params = "usize"))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -1146,7 +1153,7 @@ pub struct CodeUnit {
 ///
 /// Bytecodes operate on a stack machine and each bytecode has side effect on the stack and the
 /// instruction stream.
-#[derive(Clone, Hash, Eq, VariantCount, PartialEq)]
+#[derive(Clone, Hash, Eq, VariantCount, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 #[cfg_attr(any(test, feature = "fuzzing"), proptest(no_params))]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
@@ -1834,7 +1841,7 @@ impl CompiledScript {
 /// It is a unit of code that can be used by transactions or other modules.
 ///
 /// A module is published as a single entry and it is retrieved as a single blob.
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
 pub struct CompiledModule {
     /// Version number found during deserialization
@@ -2079,7 +2086,7 @@ pub fn basic_test_module() -> CompiledModule {
 
     m.function_handles.push(FunctionHandle {
         module: ModuleHandleIndex(0),
-        name: IdentifierIndex(m.identifiers.len() as u16),
+        name: IdentifierIndex(m.identifiers.len() as TableIndex),
         parameters: SignatureIndex(0),
         return_: SignatureIndex(0),
         type_parameters: vec![],
@@ -2100,7 +2107,7 @@ pub fn basic_test_module() -> CompiledModule {
 
     m.struct_handles.push(StructHandle {
         module: ModuleHandleIndex(0),
-        name: IdentifierIndex(m.identifiers.len() as u16),
+        name: IdentifierIndex(m.identifiers.len() as TableIndex),
         abilities: AbilitySet::EMPTY,
         type_parameters: vec![],
     });
@@ -2110,7 +2117,7 @@ pub fn basic_test_module() -> CompiledModule {
     m.struct_defs.push(StructDefinition {
         struct_handle: StructHandleIndex(0),
         field_information: StructFieldInformation::Declared(vec![FieldDefinition {
-            name: IdentifierIndex(m.identifiers.len() as u16),
+            name: IdentifierIndex(m.identifiers.len() as TableIndex),
             signature: TypeSignature(SignatureToken::U64),
         }]),
     });
@@ -16,7 +16,7 @@ use crate::file_format::Bytecode;
 use alloc::string::ToString;
 use alloc::vec::Vec;
 use anyhow::{bail, Result};
-use core::mem::size_of;
+use core::{mem::size_of, ops::RangeInclusive};
 
 /// Constant values for the binary format header.
 ///
@@ -40,6 +40,8 @@ pub const TABLE_SIZE_MAX: u64 = 0xffff_ffff;
 pub const TABLE_CONTENT_SIZE_MAX: u64 = 0xffff_ffff;
 
 pub const TABLE_INDEX_MAX: u64 = 65535;
+/// Table index limit for the extended binary format.
+pub const EXTENDED_TABLE_INDEX_MAX: u64 = 0xffff_ffff;
 pub const SIGNATURE_INDEX_MAX: u64 = TABLE_INDEX_MAX;
 pub const ADDRESS_INDEX_MAX: u64 = TABLE_INDEX_MAX;
 pub const IDENTIFIER_INDEX_MAX: u64 = TABLE_INDEX_MAX;
@@ -103,6 +105,11 @@ pub enum TableType {
     METADATA                = 0x10,
 }
 
+/// Table types reserved for the tables of the future versions, e.g. the variant handles of enums.
+///
+/// The experimental version binaries may contain these tables, which are skipped when loading.
+pub const EXPERIMENTAL_TABLE_TYPES: RangeInclusive<u8> = 0x11..=0x1F;
+
 /// Constants for signature blob values.
 #[rustfmt::skip]
 #[allow(non_camel_case_types)]
@@ -220,13 +227,27 @@ pub enum Opcodes {
     CAST_U256                   = 0x4D,
 }
 
+/// Opcodes reserved for the bytecodes of the future versions, e.g. packing and matching of enum
+/// variants.
+///
+/// The VM can't execute these bytecodes, so they're rejected in all versions, but with a precise
+/// message for the experimental version binaries.
+pub const EXPERIMENTAL_OPCODES: RangeInclusive<u8> = 0x4E..=0x5F;
+
 /// Upper limit on the binary size
 pub const BINARY_SIZE_LIMIT: usize = usize::max_value();
 
 /// A wrapper for the binary vector
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub(crate) struct BinaryData {
     _binary: Vec<u8>,
+    version: u32,
+}
+
+impl Default for BinaryData {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// The wrapper mirrors Vector operations but provides additional checks against overflow
@@ -234,9 +255,20 @@ impl BinaryData {
     pub fn new() -> Self {
         BinaryData {
             _binary: Vec::new(),
+            version: VERSION_MAX,
         }
     }
 
+    /// Sets the version the binary is written for, which determines e.g. the table index limit.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
     pub fn as_inner(&self) -> &[u8] {
         &self._binary
     }
@@ -290,7 +322,10 @@ impl BinaryData {
 
 impl From<Vec<u8>> for BinaryData {
     fn from(vec: Vec<u8>) -> Self {
-        BinaryData { _binary: vec }
+        BinaryData {
+            _binary: vec,
+            version: VERSION_MAX,
+        }
     }
 }
 
@@ -408,10 +443,39 @@ pub const VERSION_6: u32 = 6;
 // Mark which version is the latest version
 pub const VERSION_MAX: u32 = VERSION_6;
 
+/// Extended version: changes compared with version 6
+///  + table indices can exceed u16 and go up to u32 for machine-generated modules
+///
+/// The extended version is not part of the regular version sequence - it's only supported with the
+/// `extended-format` feature and only when the requested max version is set to this version.
+pub const VERSION_EXTENDED: u32 = 0x8000_0000 | VERSION_6;
+
+/// Experimental version: changes compared with version 6
+///  + tables of the [`EXPERIMENTAL_TABLE_TYPES`] are allowed and skipped
+///
+/// Lets the binaries compiled for version 7 by the upstream compilers load, as long as they only
+/// use the version 6 bytecodes - the code can't refer to the skipped tables without the
+/// [`EXPERIMENTAL_OPCODES`]. The experimental version is not part of the regular version sequence -
+/// it's only supported with the `experimental-format` feature and only when the requested max
+/// version is set to this version or above.
+pub const VERSION_7: u32 = 7;
+
 // Mark which oldest version is supported.
 // TODO(#145): finish v4 compatibility; as of now, only metadata is implemented
 pub const VERSION_MIN: u32 = VERSION_5;
 
+/// Check whether the binary format version can be loaded up to the specified max version.
+pub fn is_version_supported(version: u32, max_version: u32) -> bool {
+    if version == VERSION_EXTENDED {
+        return cfg!(feature = "extended-format") && max_version >= VERSION_EXTENDED;
+    }
+    if version == VERSION_7 {
+        return cfg!(feature = "experimental-format") && max_version >= VERSION_7;
+    }
+
+    version != 0 && version <= u32::min(max_version, VERSION_MAX)
+}
+
 pub(crate) mod versioned_data {
     use crate::cursor::Cursor;
     use crate::{errors::*, file_format_common::*};
@@ -446,7 +510,7 @@ pub(crate) mod versioned_data {
                         .with_message("Bad binary header".to_string()));
                 }
             };
-            if version == 0 || version > u32::min(max_version, VERSION_MAX) {
+            if !is_version_supported(version, max_version) {
                 return Err(PartialVMError::new(StatusCode::UNKNOWN_VERSION));
             }
             Ok((Self { version, binary }, cursor))
@@ -529,7 +593,7 @@ pub(crate) mod versioned_data {
             }
         }
 
-        #[cfg(test)]
+        #[cfg(any(test, feature = "fuzzing"))]
         pub fn new_for_test(version: u32, cursor: Cursor<&'a [u8]>) -> Self {
             Self { version, cursor }
         }
@@ -0,0 +1,226 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Human-readable JSON dump of the compiled modules.
+//!
+//! The dump holds all tables of the module under the `module` key, exactly as they are stored in
+//! the binary, so editing the indices in the dump and loading it back with
+//! [`CompiledModule::from_json`] is a convenient way to craft malformed modules for the verifier
+//! and deserializer tests. The `resolved` key shows the handles and the function bodies with the
+//! names resolved, for reading only - it is ignored when the dump is loaded.
+//!
+//! The names of invalid indices are resolved as `<invalid>`, so even the malformed modules can be
+//! dumped.
+
+use crate::file_format::{
+    Bytecode, CompiledModule, FieldHandleIndex, FieldInstantiationIndex, FunctionHandleIndex,
+    FunctionInstantiationIndex, IdentifierIndex, ModuleHandleIndex, StructDefInstantiationIndex,
+    StructDefinitionIndex, StructFieldInformation, StructHandleIndex,
+};
+use serde::{Deserialize, Serialize};
+
+const INVALID: &str = "<invalid>";
+
+#[derive(Serialize)]
+struct ModuleDump<'a> {
+    resolved: ResolvedNames,
+    module: &'a CompiledModule,
+}
+
+#[derive(Deserialize)]
+struct ModuleInput {
+    module: CompiledModule,
+}
+
+/// Resolved names of the module handles and function bodies.
+#[derive(Serialize)]
+struct ResolvedNames {
+    self_id: String,
+    module_handles: Vec<String>,
+    struct_handles: Vec<String>,
+    function_handles: Vec<String>,
+    field_handles: Vec<String>,
+    functions: Vec<ResolvedFunction>,
+}
+
+#[derive(Serialize)]
+struct ResolvedFunction {
+    name: String,
+    /// Missing for the native functions.
+    code: Option<Vec<String>>,
+}
+
+impl CompiledModule {
+    /// Dump the module as pretty-printed JSON - see [`crate::json`].
+    pub fn to_json(&self) -> String {
+        let names = Names(self);
+        let resolved = ResolvedNames {
+            self_id: names.module_handle(self.self_module_handle_idx),
+            module_handles: (0..self.module_handles.len())
+                .map(|idx| names.module_handle(ModuleHandleIndex(idx as _)))
+                .collect(),
+            struct_handles: (0..self.struct_handles.len())
+                .map(|idx| names.struct_handle(StructHandleIndex(idx as _)))
+                .collect(),
+            function_handles: (0..self.function_handles.len())
+                .map(|idx| names.function_handle(FunctionHandleIndex(idx as _)))
+                .collect(),
+            field_handles: (0..self.field_handles.len())
+                .map(|idx| names.field_handle(FieldHandleIndex(idx as _)))
+                .collect(),
+            functions: self
+                .function_defs
+                .iter()
+                .map(|def| ResolvedFunction {
+                    name: names.function_handle(def.function),
+                    code: def.code.as_ref().map(|code| {
+                        code.code
+                            .iter()
+                            .map(|instr| names.instruction(instr))
+                            .collect()
+                    }),
+                })
+                .collect(),
+        };
+
+        let dump = ModuleDump {
+            resolved,
+            module: self,
+        };
+        serde_json::to_string_pretty(&dump).expect("module tables are serializable")
+    }
+
+    /// Load the module from the JSON dump created by [`CompiledModule::to_json`].
+    ///
+    /// The tables aren't checked, so the loaded module may be malformed.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let input: ModuleInput = serde_json::from_str(json)?;
+        Ok(input.module)
+    }
+}
+
+/// Name resolution tolerating the invalid indices.
+struct Names<'a>(&'a CompiledModule);
+
+impl<'a> Names<'a> {
+    fn identifier(&self, idx: IdentifierIndex) -> String {
+        self.0
+            .identifiers
+            .get(idx.0 as usize)
+            .map_or_else(|| INVALID.to_owned(), |ident| ident.to_string())
+    }
+
+    fn module_handle(&self, idx: ModuleHandleIndex) -> String {
+        let Some(handle) = self.0.module_handles.get(idx.0 as usize) else {
+            return INVALID.to_owned();
+        };
+        let address = self
+            .0
+            .address_identifiers
+            .get(handle.address.0 as usize)
+            .map_or_else(|| INVALID.to_owned(), |address| address.to_hex_literal());
+        format!("{}::{}", address, self.identifier(handle.name))
+    }
+
+    fn struct_handle(&self, idx: StructHandleIndex) -> String {
+        match self.0.struct_handles.get(idx.0 as usize) {
+            Some(handle) => format!(
+                "{}::{}",
+                self.module_handle(handle.module),
+                self.identifier(handle.name)
+            ),
+            None => INVALID.to_owned(),
+        }
+    }
+
+    fn function_handle(&self, idx: FunctionHandleIndex) -> String {
+        match self.0.function_handles.get(idx.0 as usize) {
+            Some(handle) => format!(
+                "{}::{}",
+                self.module_handle(handle.module),
+                self.identifier(handle.name)
+            ),
+            None => INVALID.to_owned(),
+        }
+    }
+
+    fn function_instantiation(&self, idx: FunctionInstantiationIndex) -> String {
+        match self.0.function_instantiations.get(idx.0 as usize) {
+            Some(inst) => self.function_handle(inst.handle),
+            None => INVALID.to_owned(),
+        }
+    }
+
+    fn struct_def(&self, idx: StructDefinitionIndex) -> String {
+        match self.0.struct_defs.get(idx.0 as usize) {
+            Some(def) => self.struct_handle(def.struct_handle),
+            None => INVALID.to_owned(),
+        }
+    }
+
+    fn struct_instantiation(&self, idx: StructDefInstantiationIndex) -> String {
+        match self.0.struct_def_instantiations.get(idx.0 as usize) {
+            Some(inst) => self.struct_def(inst.def),
+            None => INVALID.to_owned(),
+        }
+    }
+
+    fn field_handle(&self, idx: FieldHandleIndex) -> String {
+        let Some(handle) = self.0.field_handles.get(idx.0 as usize) else {
+            return INVALID.to_owned();
+        };
+        let field = match self
+            .0
+            .struct_defs
+            .get(handle.owner.0 as usize)
+            .map(|def| &def.field_information)
+        {
+            Some(StructFieldInformation::Declared(fields)) => fields
+                .get(handle.field as usize)
+                .map_or_else(|| INVALID.to_owned(), |field| self.identifier(field.name)),
+            _ => INVALID.to_owned(),
+        };
+        format!("{}.{}", self.struct_def(handle.owner), field)
+    }
+
+    fn field_instantiation(&self, idx: FieldInstantiationIndex) -> String {
+        match self.0.field_instantiations.get(idx.0 as usize) {
+            Some(inst) => self.field_handle(inst.handle),
+            None => INVALID.to_owned(),
+        }
+    }
+
+    /// The instruction with the referenced handle replaced by its name.
+    fn instruction(&self, instr: &Bytecode) -> String {
+        use Bytecode::*;
+
+        let (op, name) = match instr {
+            Call(idx) => ("Call", self.function_handle(*idx)),
+            CallGeneric(idx) => ("CallGeneric", self.function_instantiation(*idx)),
+            Pack(idx) => ("Pack", self.struct_def(*idx)),
+            PackGeneric(idx) => ("PackGeneric", self.struct_instantiation(*idx)),
+            Unpack(idx) => ("Unpack", self.struct_def(*idx)),
+            UnpackGeneric(idx) => ("UnpackGeneric", self.struct_instantiation(*idx)),
+            MutBorrowField(idx) => ("MutBorrowField", self.field_handle(*idx)),
+            MutBorrowFieldGeneric(idx) => ("MutBorrowFieldGeneric", self.field_instantiation(*idx)),
+            ImmBorrowField(idx) => ("ImmBorrowField", self.field_handle(*idx)),
+            ImmBorrowFieldGeneric(idx) => ("ImmBorrowFieldGeneric", self.field_instantiation(*idx)),
+            MutBorrowGlobal(idx) => ("MutBorrowGlobal", self.struct_def(*idx)),
+            MutBorrowGlobalGeneric(idx) => {
+                ("MutBorrowGlobalGeneric", self.struct_instantiation(*idx))
+            }
+            ImmBorrowGlobal(idx) => ("ImmBorrowGlobal", self.struct_def(*idx)),
+            ImmBorrowGlobalGeneric(idx) => {
+                ("ImmBorrowGlobalGeneric", self.struct_instantiation(*idx))
+            }
+            Exists(idx) => ("Exists", self.struct_def(*idx)),
+            ExistsGeneric(idx) => ("ExistsGeneric", self.struct_instantiation(*idx)),
+            MoveFrom(idx) => ("MoveFrom", self.struct_def(*idx)),
+            MoveFromGeneric(idx) => ("MoveFromGeneric", self.struct_instantiation(*idx)),
+            MoveTo(idx) => ("MoveTo", self.struct_def(*idx)),
+            MoveToGeneric(idx) => ("MoveToGeneric", self.struct_instantiation(*idx)),
+            _ => return format!("{:?}", instr),
+        };
+        format!("{}({})", op, name)
+    }
+}
@@ -23,10 +23,14 @@ pub mod deserializer;
 pub mod file_format;
 pub mod file_format_common;
 pub mod internals;
+#[cfg(feature = "std")]
+pub mod json;
 pub mod normalized;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod proptest_types;
 pub mod serializer;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod signature_token_corpus;
 pub mod views;
 
 #[cfg(test)]
@@ -53,7 +53,7 @@ impl SignatureState {
         if let Some(idx) = self.signature_map.get(&sig) {
             return *idx;
         }
-        let idx = SignatureIndex(self.signatures.len() as u16);
+        let idx = SignatureIndex(self.signatures.len() as TableIndex);
         self.signatures.push(sig.clone());
         self.signature_map.insert(sig, idx);
         idx
@@ -79,7 +79,7 @@ impl FieldHandleState {
         if let Some(idx) = self.field_map.get(&fh) {
             return *idx;
         }
-        let idx = FieldHandleIndex(self.field_handles.len() as u16);
+        let idx = FieldHandleIndex(self.field_handles.len() as TableIndex);
         self.field_handles.push(fh.clone());
         self.field_map.insert(fh, idx);
         idx
@@ -52,7 +52,7 @@ impl StDefnMaterializeState {
     fn add_struct_handle(&mut self, handle: StructHandle) -> Option<StructHandleIndex> {
         if self.new_handles.insert((handle.module, handle.name)) {
             self.struct_handles.push(handle);
-            Some(StructHandleIndex((self.struct_handles.len() - 1) as u16))
+            Some(StructHandleIndex((self.struct_handles.len() - 1) as TableIndex))
         } else {
             None
         }
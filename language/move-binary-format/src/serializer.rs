@@ -38,9 +38,22 @@ impl CompiledScript {
     ) -> Result<()> {
         let version = bytecode_version.unwrap_or(VERSION_MAX);
         validate_version(version)?;
-        let mut binary_data = BinaryData::from(binary.clone());
+        validate_table_sizes(
+            version,
+            &[
+                self.module_handles.len(),
+                self.struct_handles.len(),
+                self.function_handles.len(),
+                self.function_instantiations.len(),
+                self.signatures.len(),
+                self.identifiers.len(),
+                self.address_identifiers.len(),
+                self.constant_pool.len(),
+            ],
+        )?;
+        let mut binary_data = BinaryData::from(binary.clone()).with_version(version);
         let mut ser = ScriptSerializer::new(version);
-        let mut temp = BinaryData::new();
+        let mut temp = BinaryData::new().with_version(version);
 
         ser.common.serialize_common_tables(&mut temp, self)?;
         if temp.len() > TABLE_CONTENT_SIZE_MAX as usize {
@@ -76,62 +89,73 @@ where
 }
 
 fn serialize_signature_index(binary: &mut BinaryData, idx: &SignatureIndex) -> Result<()> {
-    write_as_uleb128(binary, idx.0, SIGNATURE_INDEX_MAX)
+    let max = table_index_max(binary, SIGNATURE_INDEX_MAX);
+    write_as_uleb128(binary, idx.0, max)
 }
 
 fn serialize_module_handle_index(binary: &mut BinaryData, idx: &ModuleHandleIndex) -> Result<()> {
-    write_as_uleb128(binary, idx.0, MODULE_HANDLE_INDEX_MAX)
+    let max = table_index_max(binary, MODULE_HANDLE_INDEX_MAX);
+    write_as_uleb128(binary, idx.0, max)
 }
 
 fn serialize_identifier_index(binary: &mut BinaryData, idx: &IdentifierIndex) -> Result<()> {
-    write_as_uleb128(binary, idx.0, IDENTIFIER_INDEX_MAX)
+    let max = table_index_max(binary, IDENTIFIER_INDEX_MAX);
+    write_as_uleb128(binary, idx.0, max)
 }
 
 fn serialize_struct_handle_index(binary: &mut BinaryData, idx: &StructHandleIndex) -> Result<()> {
-    write_as_uleb128(binary, idx.0, STRUCT_HANDLE_INDEX_MAX)
+    let max = table_index_max(binary, STRUCT_HANDLE_INDEX_MAX);
+    write_as_uleb128(binary, idx.0, max)
 }
 
 fn serialize_address_identifier_index(
     binary: &mut BinaryData,
     idx: &AddressIdentifierIndex,
 ) -> Result<()> {
-    write_as_uleb128(binary, idx.0, ADDRESS_INDEX_MAX)
+    let max = table_index_max(binary, ADDRESS_INDEX_MAX);
+    write_as_uleb128(binary, idx.0, max)
 }
 
 fn serialize_struct_def_index(binary: &mut BinaryData, idx: &StructDefinitionIndex) -> Result<()> {
-    write_as_uleb128(binary, idx.0, STRUCT_DEF_INDEX_MAX)
+    let max = table_index_max(binary, STRUCT_DEF_INDEX_MAX);
+    write_as_uleb128(binary, idx.0, max)
 }
 
 fn serialize_function_handle_index(
     binary: &mut BinaryData,
     idx: &FunctionHandleIndex,
 ) -> Result<()> {
-    write_as_uleb128(binary, idx.0, FUNCTION_HANDLE_INDEX_MAX)
+    let max = table_index_max(binary, FUNCTION_HANDLE_INDEX_MAX);
+    write_as_uleb128(binary, idx.0, max)
 }
 
 fn serialize_field_handle_index(binary: &mut BinaryData, idx: &FieldHandleIndex) -> Result<()> {
-    write_as_uleb128(binary, idx.0, FIELD_HANDLE_INDEX_MAX)
+    let max = table_index_max(binary, FIELD_HANDLE_INDEX_MAX);
+    write_as_uleb128(binary, idx.0, max)
 }
 
 fn serialize_field_inst_index(
     binary: &mut BinaryData,
     idx: &FieldInstantiationIndex,
 ) -> Result<()> {
-    write_as_uleb128(binary, idx.0, FIELD_INST_INDEX_MAX)
+    let max = table_index_max(binary, FIELD_INST_INDEX_MAX);
+    write_as_uleb128(binary, idx.0, max)
 }
 
 fn serialize_function_inst_index(
     binary: &mut BinaryData,
     idx: &FunctionInstantiationIndex,
 ) -> Result<()> {
-    write_as_uleb128(binary, idx.0, FUNCTION_INST_INDEX_MAX)
+    let max = table_index_max(binary, FUNCTION_INST_INDEX_MAX);
+    write_as_uleb128(binary, idx.0, max)
 }
 
 fn serialize_struct_def_inst_index(
     binary: &mut BinaryData,
     idx: &StructDefInstantiationIndex,
 ) -> Result<()> {
-    write_as_uleb128(binary, idx.0, STRUCT_DEF_INST_INDEX_MAX)
+    let max = table_index_max(binary, STRUCT_DEF_INST_INDEX_MAX);
+    write_as_uleb128(binary, idx.0, max)
 }
 
 fn seiralize_table_offset(binary: &mut BinaryData, offset: u32) -> Result<()> {
@@ -143,7 +167,8 @@ fn serialize_table_size(binary: &mut BinaryData, size: u32) -> Result<()> {
 }
 
 fn serialize_constant_pool_index(binary: &mut BinaryData, idx: &ConstantPoolIndex) -> Result<()> {
-    write_as_uleb128(binary, idx.0, CONSTANT_INDEX_MAX)
+    let max = table_index_max(binary, CONSTANT_INDEX_MAX);
+    write_as_uleb128(binary, idx.0, max)
 }
 
 fn serialize_bytecode_count(binary: &mut BinaryData, len: usize) -> Result<()> {
@@ -202,7 +227,46 @@ fn serialize_local_index(binary: &mut BinaryData, idx: u8) -> Result<()> {
     write_as_uleb128(binary, idx, LOCAL_INDEX_MAX)
 }
 
+/// Returns the table index limit for the version the binary is written for, which is raised for
+/// the extended binary format - mirrors the deserializer.
+fn table_index_max(binary: &BinaryData, max: u64) -> u64 {
+    if binary.version() == VERSION_EXTENDED {
+        EXTENDED_TABLE_INDEX_MAX
+    } else {
+        max
+    }
+}
+
+/// Verifies that all tables can be indexed within the limits of the requested version.
+fn validate_table_sizes(version: u32, table_sizes: &[usize]) -> Result<()> {
+    if version == VERSION_EXTENDED {
+        return Ok(());
+    }
+
+    // Indices start from zero, so the table can hold one more element than the index limit.
+    if let Some(size) = table_sizes
+        .iter()
+        .find(|size| **size as u64 > TABLE_INDEX_MAX + 1)
+    {
+        bail!(
+            "table size ({}) exceeds the index limit ({}), use the extended version ({})",
+            size,
+            TABLE_INDEX_MAX,
+            VERSION_EXTENDED
+        )
+    }
+
+    Ok(())
+}
+
 fn validate_version(version: u32) -> Result<()> {
+    if version == VERSION_EXTENDED && cfg!(feature = "extended-format") {
+        return Ok(());
+    }
+    if version == VERSION_7 && cfg!(feature = "experimental-format") {
+        return Ok(());
+    }
+
     if !(VERSION_MIN..=VERSION_MAX).contains(&version) {
         bail!(
             "The requested bytecode version {} is not supported. Only {} to {} are.",
@@ -230,9 +294,28 @@ impl CompiledModule {
     ) -> Result<()> {
         let version = bytecode_version.unwrap_or(VERSION_MAX);
         validate_version(version)?;
-        let mut binary_data = BinaryData::from(binary.clone());
+        validate_table_sizes(
+            version,
+            &[
+                self.module_handles.len(),
+                self.struct_handles.len(),
+                self.function_handles.len(),
+                self.field_handles.len(),
+                self.friend_decls.len(),
+                self.struct_def_instantiations.len(),
+                self.function_instantiations.len(),
+                self.field_instantiations.len(),
+                self.signatures.len(),
+                self.identifiers.len(),
+                self.address_identifiers.len(),
+                self.constant_pool.len(),
+                self.struct_defs.len(),
+                self.function_defs.len(),
+            ],
+        )?;
+        let mut binary_data = BinaryData::from(binary.clone()).with_version(version);
         let mut ser = ModuleSerializer::new(version);
-        let mut temp = BinaryData::new();
+        let mut temp = BinaryData::new().with_version(version);
         ser.serialize_tables(&mut temp, self)?;
         if temp.len() > u32::max_value() as usize {
             bail!(
@@ -0,0 +1,120 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Regression corpus of the adversarial signature token streams.
+//!
+//! The corpus locks in the outcome of `load_signature_token` for the inputs which exercise its
+//! edge cases - deep nesting, unsaturated type builders, struct instantiations with no type
+//! arguments, index limits and the version gated types. The unit tests replay the whole corpus,
+//! and the `grow_signature_token_corpus` tool of the bytecode verifier fuzzing crate proposes new
+//! entries for the fuzzer inputs with outcomes the corpus doesn't cover yet.
+//!
+//! Each corpus line holds the bytecode version, the expected outcome and the token bytes:
+//! ```text
+//! # vector<bool> nested 255 times
+//! 6 OK 0a*255 01
+//! ```
+//! The outcome is either `OK` or the name of the expected status code. The bytes are hex-encoded
+//! groups, where `XX*N` repeats the byte `N` times and `-` stands for no bytes at all. Empty lines
+//! and the lines starting with `#` are ignored.
+
+use crate::deserializer::load_signature_token_for_version;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// The corpus kept in the repository.
+pub const CORPUS: &str = include_str!("../corpus/signature_tokens.txt");
+
+/// Single corpus entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusEntry {
+    /// Line of the entry in the corpus, starting from one.
+    pub line: usize,
+    pub version: u32,
+    pub bytes: Vec<u8>,
+    /// `OK` or the name of the expected status code.
+    pub expected: String,
+}
+
+/// Parses the corpus.
+pub fn parse(corpus: &str) -> Result<Vec<CorpusEntry>, String> {
+    let mut entries = Vec::new();
+    for (idx, line) in corpus.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid = |reason: &str| format!("line {}: {}", idx + 1, reason);
+        let mut parts = line.split_whitespace();
+        let version = parts
+            .next()
+            .and_then(|version| version.parse().ok())
+            .ok_or_else(|| invalid("invalid version"))?;
+        let expected = parts.next().ok_or_else(|| invalid("missing outcome"))?;
+
+        let groups: Vec<_> = parts.collect();
+        if groups.is_empty() {
+            return Err(invalid("missing bytes"));
+        }
+        let mut bytes = Vec::new();
+        for group in groups {
+            parse_group(group, &mut bytes).ok_or_else(|| invalid("invalid bytes"))?;
+        }
+
+        entries.push(CorpusEntry {
+            line: idx + 1,
+            version,
+            bytes,
+            expected: expected.to_string(),
+        });
+    }
+    Ok(entries)
+}
+
+fn parse_group(group: &str, bytes: &mut Vec<u8>) -> Option<()> {
+    if group == "-" {
+        return Some(());
+    }
+
+    let (hex, count) = match group.split_once('*') {
+        Some((hex, count)) => (hex, count.parse().ok()?),
+        None => (group, 1),
+    };
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    let mut chunk = Vec::with_capacity(hex.len() / 2);
+    for i in (0..hex.len()).step_by(2) {
+        chunk.push(u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()?);
+    }
+
+    for _ in 0..count {
+        bytes.extend_from_slice(&chunk);
+    }
+    Some(())
+}
+
+/// Outcome of loading the token bytes, in the corpus format.
+pub fn outcome(version: u32, bytes: &[u8]) -> String {
+    match load_signature_token_for_version(version, bytes) {
+        Ok(_) => "OK".to_string(),
+        Err(err) => format!("{:?}", err.major_status()),
+    }
+}
+
+/// Formats the corpus line of the token bytes.
+pub fn format_entry(version: u32, bytes: &[u8], outcome: &str) -> String {
+    let mut line = format!("{} {}", version, outcome);
+    if bytes.is_empty() {
+        line.push_str(" -");
+    } else {
+        line.push(' ');
+        for byte in bytes {
+            line.push_str(&format!("{:02x}", byte));
+        }
+    }
+    line
+}
@@ -2,10 +2,19 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(feature = "extended-format")]
+use crate::file_format::{IdentifierIndex, TableIndex};
+#[cfg(feature = "experimental-format")]
 use crate::{
-    file_format::{CompiledModule, CompiledScript},
+    cursor::Cursor,
+    file_format::{basic_test_module, Bytecode},
+};
+use crate::{
+    file_format::{empty_module, CompiledModule, CompiledScript},
     file_format_common::*,
 };
+#[cfg(feature = "extended-format")]
+use move_core_types::identifier::Identifier;
 use move_core_types::vm_status::StatusCode;
 
 fn malformed_simple_versioned_test(version: u32) {
@@ -257,3 +266,197 @@ fn deserialize_invalid_script_no_signature() {
         StatusCode::INDEX_OUT_OF_BOUNDS
     );
 }
+
+#[test]
+fn extended_version_requires_explicit_max_version() {
+    let mut module = empty_module();
+    module.version = VERSION_EXTENDED;
+    let mut binary = vec![];
+    let res = module.serialize_for_version(Some(VERSION_EXTENDED), &mut binary);
+
+    if !cfg!(feature = "extended-format") {
+        assert!(
+            res.is_err(),
+            "extended version serialized without the feature"
+        );
+        return;
+    }
+    res.expect("extended version should serialize");
+
+    // The regular max version doesn't allow the extended format.
+    let res = CompiledModule::deserialize(&binary);
+    assert_eq!(
+        res.expect_err("Expected unknown version").major_status(),
+        StatusCode::UNKNOWN_VERSION
+    );
+
+    let res = CompiledModule::deserialize_with_max_version(&binary, VERSION_EXTENDED);
+    assert_eq!(res.expect("extended version should deserialize"), module);
+}
+
+#[cfg(feature = "extended-format")]
+#[test]
+fn extended_version_allows_indices_beyond_u16() {
+    let mut module = empty_module();
+    module.version = VERSION_EXTENDED;
+    for i in 0..=TABLE_INDEX_MAX {
+        module
+            .identifiers
+            .push(Identifier::new(format!("ident_{}", i)).unwrap());
+    }
+    let last_identifier = IdentifierIndex((module.identifiers.len() - 1) as TableIndex);
+    assert!(last_identifier.0 as u64 > TABLE_INDEX_MAX);
+
+    // The regular version can't address all of the identifiers.
+    let mut binary = vec![];
+    assert!(module.serialize_for_version(None, &mut binary).is_err());
+
+    let mut binary = vec![];
+    module
+        .serialize_for_version(Some(VERSION_EXTENDED), &mut binary)
+        .expect("extended version should serialize");
+    let res = CompiledModule::deserialize_with_max_version(&binary, VERSION_EXTENDED);
+    assert_eq!(res.expect("extended version should deserialize"), module);
+}
+
+#[cfg(feature = "extended-format")]
+#[test]
+fn regular_version_limits_serialized_indices() {
+    // The index is out of bounds, but the serializer only checks the encoding limit.
+    let mut module = empty_module();
+    module.module_handles[0].name = IdentifierIndex(TABLE_INDEX_MAX as TableIndex + 1);
+
+    let mut binary = vec![];
+    assert!(module.serialize_for_version(None, &mut binary).is_err());
+
+    let mut binary = vec![];
+    module.version = VERSION_EXTENDED;
+    assert!(module
+        .serialize_for_version(Some(VERSION_EXTENDED), &mut binary)
+        .is_ok());
+}
+
+#[test]
+fn experimental_version_requires_explicit_max_version() {
+    let mut module = empty_module();
+    module.version = VERSION_7;
+    let mut binary = vec![];
+    let res = module.serialize_for_version(Some(VERSION_7), &mut binary);
+
+    if !cfg!(feature = "experimental-format") {
+        assert!(
+            res.is_err(),
+            "experimental version serialized without the feature"
+        );
+        return;
+    }
+    res.expect("experimental version should serialize");
+
+    // The regular max version doesn't allow the experimental format.
+    let res = CompiledModule::deserialize(&binary);
+    assert_eq!(
+        res.expect_err("Expected unknown version").major_status(),
+        StatusCode::UNKNOWN_VERSION
+    );
+
+    let res = CompiledModule::deserialize_with_max_version(&binary, VERSION_7);
+    assert_eq!(
+        res.expect("experimental version should deserialize"),
+        module
+    );
+}
+
+/// Appends a table with the given type and contents to the serialized module.
+#[cfg(feature = "experimental-format")]
+fn append_table(binary: &[u8], kind: u8, contents: &[u8]) -> Vec<u8> {
+    let mut cursor = Cursor::new(binary);
+    let mut header = [0; BinaryConstants::HEADER_SIZE - 1];
+    cursor.read_exact(&mut header).unwrap();
+    let table_count = read_u8(&mut cursor).unwrap();
+    let mut content_len = 0;
+    for _ in 0..table_count {
+        read_u8(&mut cursor).unwrap();
+        read_uleb128_as_u64(&mut cursor).unwrap();
+        content_len += read_uleb128_as_u64(&mut cursor).unwrap();
+    }
+    let headers_end = cursor.position() as usize;
+    let contents_end = headers_end + content_len as usize;
+
+    let mut patched = BinaryData::new();
+    patched
+        .extend(&binary[..BinaryConstants::HEADER_SIZE - 1])
+        .unwrap();
+    patched.push(table_count + 1).unwrap();
+    patched
+        .extend(&binary[BinaryConstants::HEADER_SIZE..headers_end])
+        .unwrap();
+    patched.push(kind).unwrap();
+    write_u64_as_uleb128(&mut patched, content_len).unwrap();
+    write_u64_as_uleb128(&mut patched, contents.len() as u64).unwrap();
+    patched.extend(&binary[headers_end..contents_end]).unwrap();
+    patched.extend(contents).unwrap();
+    patched.extend(&binary[contents_end..]).unwrap();
+    patched.into_inner()
+}
+
+#[cfg(feature = "experimental-format")]
+#[test]
+fn experimental_tables_are_skipped() {
+    let mut module = empty_module();
+    let mut binary = vec![];
+    module.serialize(&mut binary).unwrap();
+
+    // Experimental tables are unknown in the regular versions.
+    let patched = append_table(&binary, *EXPERIMENTAL_TABLE_TYPES.start(), &[0xAB; 3]);
+    let res = CompiledModule::deserialize_with_max_version(&patched, VERSION_7);
+    assert_eq!(
+        res.expect_err("Expected unknown table type").major_status(),
+        StatusCode::UNKNOWN_TABLE_TYPE
+    );
+
+    module.version = VERSION_7;
+    let mut binary = vec![];
+    module
+        .serialize_for_version(Some(VERSION_7), &mut binary)
+        .unwrap();
+    let patched = append_table(&binary, *EXPERIMENTAL_TABLE_TYPES.start(), &[0xAB; 3]);
+    let patched = append_table(&patched, *EXPERIMENTAL_TABLE_TYPES.end(), &[0xCD]);
+    let res = CompiledModule::deserialize_with_max_version(&patched, VERSION_7);
+    assert_eq!(res.expect("experimental tables should be skipped"), module);
+
+    // The table types beyond the reserved ones are still unknown.
+    let patched = append_table(&binary, *EXPERIMENTAL_TABLE_TYPES.end() + 1, &[0xAB]);
+    let res = CompiledModule::deserialize_with_max_version(&patched, VERSION_7);
+    assert_eq!(
+        res.expect_err("Expected unknown table type").major_status(),
+        StatusCode::UNKNOWN_TABLE_TYPE
+    );
+}
+
+#[cfg(feature = "experimental-format")]
+#[test]
+fn experimental_opcodes_are_rejected() {
+    let mut module = basic_test_module();
+    module.function_defs[0].code.as_mut().unwrap().code = vec![
+        Bytecode::LdU64(0xDEAD_BEEF_CAFE_F00D),
+        Bytecode::Pop,
+        Bytecode::Ret,
+    ];
+    module.version = VERSION_7;
+    let mut binary = vec![];
+    module
+        .serialize_for_version(Some(VERSION_7), &mut binary)
+        .unwrap();
+
+    // Replace the `LdU64` of the function with an experimental opcode.
+    let value = 0xDEAD_BEEF_CAFE_F00D_u64.to_le_bytes();
+    let idx = binary
+        .windows(value.len())
+        .position(|window| window == value)
+        .unwrap();
+    binary[idx - 1] = *EXPERIMENTAL_OPCODES.start();
+
+    let err = CompiledModule::deserialize_with_max_version(&binary, VERSION_7)
+        .expect_err("Expected unknown opcode");
+    assert_eq!(err.major_status(), StatusCode::UNKNOWN_OPCODE);
+}
@@ -0,0 +1,59 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    check_bounds::BoundsChecker,
+    file_format::{
+        basic_test_module, Bytecode, CompiledModule, FieldHandle, FieldHandleIndex,
+        FunctionHandleIndex, StructDefinitionIndex,
+    },
+};
+
+fn test_module() -> CompiledModule {
+    let mut m = basic_test_module();
+    m.field_handles.push(FieldHandle {
+        owner: StructDefinitionIndex(0),
+        field: 0,
+    });
+    m.function_defs[0].code.as_mut().unwrap().code = vec![
+        Bytecode::Call(FunctionHandleIndex(0)),
+        Bytecode::ImmBorrowField(FieldHandleIndex(0)),
+        Bytecode::Ret,
+    ];
+    m
+}
+
+#[test]
+fn json_roundtrip() {
+    let module = test_module();
+    let json = module.to_json();
+    assert_eq!(CompiledModule::from_json(&json).unwrap(), module);
+}
+
+#[test]
+fn json_resolves_names() {
+    let json: serde_json::Value = serde_json::from_str(&test_module().to_json()).unwrap();
+    let resolved = &json["resolved"];
+
+    assert_eq!(resolved["struct_handles"][0], "0x0::<SELF>::Bar");
+    assert_eq!(resolved["field_handles"][0], "0x0::<SELF>::Bar.x");
+    assert_eq!(
+        resolved["functions"][0]["code"],
+        serde_json::json!([
+            "Call(0x0::<SELF>::foo)",
+            "ImmBorrowField(0x0::<SELF>::Bar.x)",
+            "Ret"
+        ])
+    );
+}
+
+#[test]
+fn json_crafts_malformed_modules() {
+    let mut json: serde_json::Value = serde_json::from_str(&test_module().to_json()).unwrap();
+    json["module"]["field_handles"][0]["owner"] = serde_json::json!(7);
+
+    let module = CompiledModule::from_json(&json.to_string()).unwrap();
+    assert!(BoundsChecker::verify_module(&module).is_err());
+    // The invalid indices are still dumped.
+    assert!(module.to_json().contains("<invalid>.<invalid>"));
+}
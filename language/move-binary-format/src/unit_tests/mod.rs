@@ -6,5 +6,8 @@ mod binary_tests;
 mod compatibility_tests;
 mod control_flow_graph_tests;
 mod deserializer_tests;
+#[cfg(feature = "std")]
+mod json_tests;
 mod number_tests;
+mod signature_token_corpus_tests;
 mod signature_token_tests;
@@ -0,0 +1,54 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::signature_token_corpus::{format_entry, outcome, parse, CORPUS};
+
+#[test]
+fn corpus_outcomes_match() {
+    let entries = parse(CORPUS).expect("corpus should parse");
+    assert!(!entries.is_empty());
+
+    let mismatches: Vec<_> = entries
+        .iter()
+        .filter_map(|entry| {
+            let actual = outcome(entry.version, &entry.bytes);
+            (actual != entry.expected).then(|| {
+                format!(
+                    "line {}: expected {}, got {}",
+                    entry.line, entry.expected, actual
+                )
+            })
+        })
+        .collect();
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
+
+#[test]
+fn corpus_groups_are_expanded() {
+    let entries = parse("# comment\n\n6 OK 0a*3 01\n5 MALFORMED -\n").unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].line, 3);
+    assert_eq!(entries[0].bytes, vec![0x0a, 0x0a, 0x0a, 0x01]);
+    assert_eq!(entries[1].version, 5);
+    assert_eq!(entries[1].expected, "MALFORMED");
+    assert!(entries[1].bytes.is_empty());
+}
+
+#[test]
+fn invalid_corpus_lines_are_rejected() {
+    assert!(parse("six OK 01").is_err());
+    assert!(parse("6 OK").is_err());
+    assert!(parse("6 OK 0").is_err());
+    assert!(parse("6 OK 0g").is_err());
+    assert!(parse("6 OK 01*x").is_err());
+}
+
+#[test]
+fn formatted_entries_round_trip() {
+    for bytes in [vec![], vec![0x0b, 0x00, 0x01, 0x01]] {
+        let line = format_entry(6, &bytes, &outcome(6, &bytes));
+        let entries = parse(&line).unwrap();
+        assert_eq!(entries[0].bytes, bytes);
+        assert_eq!(entries[0].expected, outcome(6, &bytes));
+    }
+}
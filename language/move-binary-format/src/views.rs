@@ -242,7 +242,7 @@ impl<'a, T: ModuleAccess> StructHandleView<'a, T> {
     pub fn handle_idx(&self) -> StructHandleIndex {
         for (idx, handle) in self.module.struct_handles().iter().enumerate() {
             if handle == self.handle() {
-                return StructHandleIndex::new(idx as u16);
+                return StructHandleIndex::new(idx as TableIndex);
             }
         }
         unreachable!("Cannot resolve StructHandle {:?} in module {:?}. This should never happen in a well-formed `StructHandleView`. Perhaps this handle came from a different module?", self.handle(), self.module().name())
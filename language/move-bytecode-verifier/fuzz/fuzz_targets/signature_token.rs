@@ -0,0 +1,15 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use move_binary_format::{
+    deserializer::load_signature_token_for_version,
+    file_format_common::{VERSION_5, VERSION_MAX},
+};
+
+fuzz_target!(|bytes: &[u8]| {
+    for version in [VERSION_5, VERSION_MAX] {
+        let _ = load_signature_token_for_version(version, bytes);
+    }
+});
@@ -0,0 +1,80 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proposes new entries of the signature token regression corpus.
+//!
+//! Replays the inputs found by the `signature_token` fuzz target and prints the corpus lines of
+//! the inputs whose outcome (the version, the status code and the error message) isn't covered by
+//! the corpus yet. The printed lines can be reviewed and appended to the corpus.
+//!
+//! Usage: `grow_signature_token_corpus <corpus file> <fuzz corpus dir>...`
+
+use move_binary_format::{
+    deserializer::load_signature_token_for_version,
+    errors::Location,
+    file_format_common::{VERSION_5, VERSION_MAX},
+    signature_token_corpus::{format_entry, outcome, parse},
+};
+use std::{collections::BTreeSet, env, fs, path::PathBuf, process};
+
+/// Outcome of loading the token, including the error message.
+fn class(version: u32, bytes: &[u8]) -> (u32, String, Option<String>) {
+    let message = load_signature_token_for_version(version, bytes)
+        .err()
+        .and_then(|err| err.finish(Location::Undefined).message().cloned());
+    (version, outcome(version, bytes), message)
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let Some(corpus_path) = args.next() else {
+        eprintln!("usage: grow_signature_token_corpus <corpus file> <fuzz corpus dir>...");
+        process::exit(1);
+    };
+
+    let corpus = fs::read_to_string(&corpus_path).unwrap_or_else(|err| {
+        eprintln!("cannot read {}: {}", corpus_path, err);
+        process::exit(1);
+    });
+    let entries = parse(&corpus).unwrap_or_else(|err| {
+        eprintln!("invalid corpus {}: {}", corpus_path, err);
+        process::exit(1);
+    });
+    let mut covered: BTreeSet<_> = entries
+        .iter()
+        .map(|entry| class(entry.version, &entry.bytes))
+        .collect();
+
+    let mut inputs: Vec<PathBuf> = Vec::new();
+    for dir in args {
+        let dir_entries = fs::read_dir(&dir).unwrap_or_else(|err| {
+            eprintln!("cannot read {}: {}", dir, err);
+            process::exit(1);
+        });
+        inputs.extend(dir_entries.filter_map(|entry| Some(entry.ok()?.path())));
+    }
+    // Shorter inputs first, so the proposed entries are the minimal ones found.
+    inputs.sort_by_key(|path| {
+        (
+            fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            path.clone(),
+        )
+    });
+
+    for path in inputs {
+        let Ok(bytes) = fs::read(&path) else {
+            continue;
+        };
+        for version in [VERSION_5, VERSION_MAX] {
+            let class = class(version, &bytes);
+            if covered.contains(&class) {
+                continue;
+            }
+            if let Some(message) = &class.2 {
+                println!("# {}", message);
+            }
+            println!("{}", format_entry(version, &bytes, &class.1));
+            covered.insert(class);
+        }
+    }
+}
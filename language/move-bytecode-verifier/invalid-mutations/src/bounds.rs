@@ -329,7 +329,7 @@ impl ApplyOutOfBoundsContext {
             .signatures()
             .enumerate()
             .flat_map(|(idx, signature)| {
-                let idx = SignatureIndex(idx as u16);
+                let idx = SignatureIndex(idx as TableIndex);
                 Self::find_struct_tokens(signature.tokens(), move |arg_idx| (idx, arg_idx))
             })
     }
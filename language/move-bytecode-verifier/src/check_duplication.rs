@@ -247,7 +247,7 @@ impl<'a> DuplicationChecker<'a> {
             .map(|x| x.struct_handle)
             .collect();
         if let Some(idx) = (0..self.module.struct_handles().len()).position(|x| {
-            let y = StructHandleIndex::new(x as u16);
+            let y = StructHandleIndex::new(x as TableIndex);
             self.module.struct_handle_at(y).module == self.module.self_handle_idx()
                 && !implemented_struct_handles.contains(&y)
         }) {
@@ -300,7 +300,7 @@ impl<'a> DuplicationChecker<'a> {
             .map(|x| x.function)
             .collect();
         if let Some(idx) = (0..self.module.function_handles().len()).position(|x| {
-            let y = FunctionHandleIndex::new(x as u16);
+            let y = FunctionHandleIndex::new(x as TableIndex);
             self.module.function_handle_at(y).module == self.module.self_handle_idx()
                 && !implemented_function_handles.contains(&y)
         }) {
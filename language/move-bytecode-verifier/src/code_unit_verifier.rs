@@ -51,7 +51,7 @@ impl<'a> CodeUnitVerifier<'a> {
         let mut name_def_map = HashMap::new();
         for (idx, func_def) in module.function_defs().iter().enumerate() {
             let fh = module.function_handle_at(func_def.function);
-            name_def_map.insert(fh.name, FunctionDefinitionIndex(idx as u16));
+            name_def_map.insert(fh.name, FunctionDefinitionIndex(idx as TableIndex));
         }
         let mut total_back_edges = 0;
         for (idx, function_definition) in module.function_defs().iter().enumerate() {
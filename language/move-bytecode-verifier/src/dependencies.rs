@@ -205,7 +205,7 @@ fn verify_imported_modules(context: &Context) -> PartialVMResult<()> {
     let self_module = context.resolver.self_handle_idx();
     for (idx, module_handle) in context.resolver.module_handles().iter().enumerate() {
         let module_id = context.resolver.module_id_for_handle(module_handle);
-        if Some(ModuleHandleIndex(idx as u16)) != self_module
+        if Some(ModuleHandleIndex(idx as TableIndex)) != self_module
             && !context.dependency_map.contains_key(&module_id)
         {
             return Err(verification_error(
@@ -13,6 +13,7 @@
 //! instances. We do reject recursive functions that create a new type upon each call but do
 //! terminate eventually.
 
+use alloc::collections::BTreeSet;
 use alloc::string::String;
 use alloc::vec::Vec;
 use hashbrown::{hash_map, HashMap, HashSet};
@@ -21,7 +22,7 @@ use move_binary_format::{
     errors::{Location, PartialVMError, PartialVMResult, VMResult},
     file_format::{
         Bytecode, CompiledModule, FunctionDefinition, FunctionDefinitionIndex, FunctionHandleIndex,
-        SignatureIndex, SignatureToken, TypeParameterIndex,
+        SignatureIndex, SignatureToken, TableIndex, TypeParameterIndex,
     },
 };
 use move_core_types::vm_status::StatusCode;
@@ -82,7 +83,12 @@ impl<'a> InstantiationLoopChecker<'a> {
                 .function_defs()
                 .iter()
                 .enumerate()
-                .map(|(def_idx, def)| (def.function, FunctionDefinitionIndex::new(def_idx as u16)))
+                .map(|(def_idx, def)| {
+                    (
+                        def.function,
+                        FunctionDefinitionIndex::new(def_idx as TableIndex),
+                    )
+                })
                 .collect(),
         }
     }
@@ -137,13 +143,14 @@ impl<'a> InstantiationLoopChecker<'a> {
     }
 
     /// Helper function that extracts type parameters from a given type.
-    /// Duplicated entries are removed.
-    fn extract_type_parameters(&self, ty: &SignatureToken) -> HashSet<TypeParameterIndex> {
+    /// Duplicated entries are removed. The parameters are ordered, so the graph nodes (and the
+    /// reported loops) don't depend on the hashing.
+    fn extract_type_parameters(&self, ty: &SignatureToken) -> BTreeSet<TypeParameterIndex> {
         use SignatureToken::*;
 
-        let mut type_params = HashSet::new();
+        let mut type_params = BTreeSet::new();
 
-        fn rec(type_params: &mut HashSet<TypeParameterIndex>, ty: &SignatureToken) {
+        fn rec(type_params: &mut BTreeSet<TypeParameterIndex>, ty: &SignatureToken) {
             match ty {
                 Bool | Address | U8 | U16 | U32 | U64 | U128 | U256 | Signer | Struct(_) => (),
                 TypeParameter(idx) => {
@@ -238,7 +245,10 @@ impl<'a> InstantiationLoopChecker<'a> {
             .enumerate()
             .filter(|(_, def)| !def.is_native())
         {
-            self.build_graph_function_def(FunctionDefinitionIndex::new(def_idx as u16), func_def)
+            self.build_graph_function_def(
+                FunctionDefinitionIndex::new(def_idx as TableIndex),
+                func_def,
+            )
         }
     }
 
@@ -5,7 +5,9 @@ use crate::VerifierConfig;
 use move_binary_format::{
     binary_views::BinaryIndexedView,
     errors::{Location, PartialVMError, PartialVMResult, VMResult},
-    file_format::{CompiledModule, CompiledScript, SignatureToken, StructFieldInformation},
+    file_format::{
+        CompiledModule, CompiledScript, SignatureToken, StructFieldInformation, TableIndex,
+    },
     IndexKind,
 };
 use move_core_types::vm_status::StatusCode;
@@ -54,7 +56,7 @@ impl<'a> LimitsVerifier<'a> {
             for (idx, struct_handle) in self.resolver.struct_handles().iter().enumerate() {
                 if struct_handle.type_parameters.len() > limit {
                     return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_PARAMETERS)
-                        .at_index(IndexKind::StructHandle, idx as u16));
+                        .at_index(IndexKind::StructHandle, idx as TableIndex));
                 }
             }
         }
@@ -66,7 +68,7 @@ impl<'a> LimitsVerifier<'a> {
             if let Some(limit) = config.max_generic_instantiation_length {
                 if function_handle.type_parameters.len() > limit {
                     return Err(PartialVMError::new(StatusCode::TOO_MANY_TYPE_PARAMETERS)
-                        .at_index(IndexKind::FunctionHandle, idx as u16));
+                        .at_index(IndexKind::FunctionHandle, idx as TableIndex));
                 }
             };
             if let Some(limit) = config.max_function_parameters {
@@ -78,7 +80,7 @@ impl<'a> LimitsVerifier<'a> {
                     > limit
                 {
                     return Err(PartialVMError::new(StatusCode::TOO_MANY_PARAMETERS)
-                        .at_index(IndexKind::FunctionHandle, idx as u16));
+                        .at_index(IndexKind::FunctionHandle, idx as TableIndex));
                 }
             };
         }
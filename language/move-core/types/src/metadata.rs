@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
 
 /// Representation of metadata,
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
 pub struct Metadata {
     /// The key identifying the type of metadata.
@@ -25,6 +25,18 @@ pub trait ModuleResolver {
     type Error: Debug;
 
     fn get_module(&self, id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error>;
+
+    /// Check if the module bytecode already passed the bytecode verifier whose config has the
+    /// given hash. Such modules skip the bytecode verification when loaded.
+    ///
+    /// Storage backends which don't keep the verification markers never skip the verification.
+    fn is_module_verified(&self, _bytes: &[u8], _config_hash: &[u8; 32]) -> bool {
+        false
+    }
+
+    /// Record that the module bytecode passed the bytecode verifier whose config has the given
+    /// hash.
+    fn set_module_verified(&self, _bytes: &[u8], _config_hash: &[u8; 32]) {}
 }
 
 /// A persistent storage backend that can resolve resources by address + type
@@ -75,6 +87,16 @@ pub trait BalanceResolver {
     /// The total amount is the amount the account owns.
     /// Account is allowed to transfer a bit of the total amount with the cheque.
     fn total_amount(&self, account: AccountAddress) -> Result<u128, Self::Error>;
+
+    /// Resolver should return the total issuance of the currency.
+    fn total_issuance(&self) -> Result<u128, Self::Error>;
+
+    /// Resolver should return whether the account exists, i.e. it isn't reaped.
+    fn account_exists(&self, account: AccountAddress) -> Result<bool, Self::Error>;
+
+    /// Resolver should return the minimum balance an account must keep to exist (the existential
+    /// deposit).
+    fn minimum_balance(&self) -> Result<u128, Self::Error>;
 }
 
 /// A persistent storage implementation that can resolve both resources and modules
@@ -117,6 +139,12 @@ impl<T: ModuleResolver + ?Sized> ModuleResolver for &T {
     fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
         (**self).get_module(module_id)
     }
+    fn is_module_verified(&self, bytes: &[u8], config_hash: &[u8; 32]) -> bool {
+        (**self).is_module_verified(bytes, config_hash)
+    }
+    fn set_module_verified(&self, bytes: &[u8], config_hash: &[u8; 32]) {
+        (**self).set_module_verified(bytes, config_hash)
+    }
 }
 
 impl<T: BalanceResolver + ?Sized> BalanceResolver for &T {
@@ -135,6 +163,15 @@ impl<T: BalanceResolver + ?Sized> BalanceResolver for &T {
     fn total_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
         (**self).total_amount(account)
     }
+    fn total_issuance(&self) -> Result<u128, Self::Error> {
+        (**self).total_issuance()
+    }
+    fn account_exists(&self, account: AccountAddress) -> Result<bool, Self::Error> {
+        (**self).account_exists(account)
+    }
+    fn minimum_balance(&self) -> Result<u128, Self::Error> {
+        (**self).minimum_balance()
+    }
 }
 
 // Most existing tests won't need this Resolver so here's a quick solution for simple structs to make those test work.
@@ -160,6 +197,18 @@ macro_rules! quick_balance_resolver_impl {
             fn total_amount(&self, _account: AccountAddress) -> Result<u128, Self::Error> {
                 unimplemented!("shouldn't be used");
             }
+
+            fn total_issuance(&self) -> Result<u128, Self::Error> {
+                unimplemented!("shouldn't be used");
+            }
+
+            fn account_exists(&self, _account: AccountAddress) -> Result<bool, Self::Error> {
+                unimplemented!("shouldn't be used");
+            }
+
+            fn minimum_balance(&self) -> Result<u128, Self::Error> {
+                unimplemented!("shouldn't be used");
+            }
         }
     };
 }
@@ -682,13 +682,23 @@ pub enum StatusCode {
     STORAGE_LIMIT_REACHED = 4032,
     // Reserved error code for future use
     RESERVED_RUNTIME_ERROR_1 = 4033,
-    RESERVED_RUNTIME_ERROR_2 = 4034,
+    // The execution was interrupted by the embedder.
+    EXECUTION_INTERRUPTED = 4034,
+    // Reserved error code for future use
     RESERVED_RUNTIME_ERROR_3 = 4035,
     RESERVED_RUNTIME_ERROR_4 = 4036,
     RESERVED_RUNTIME_ERROR_5 = 4037,
 
     // Substrate related codes: 9000-9999
     INSUFFICIENT_BALANCE = 9000,
+    // The transaction writes a resource frozen by the governance.
+    RESOURCE_FROZEN = 9001,
+    // The transaction writes to an account frozen by the governance.
+    ACCOUNT_FROZEN = 9002,
+    // The host was re-entered while handling a balance call.
+    REENTRANT_BALANCE_CALL = 9003,
+    // The transaction emitted an event with the data exceeding the size limit.
+    EVENT_DATA_TOO_LARGE = 9004,
 
     // A reserved status to represent an unknown vm status.
     // this is core::u64::MAX, but we can't pattern match on that, so put the hardcoded value in
@@ -0,0 +1,275 @@
+/// Ownership and role-based permissions for the contracts.
+///
+/// The `AccessControl<T>` resource is kept under the contract address, where `T` is a type
+/// defined by the contract module, so a single address can hold independent access controls of
+/// multiple modules. Only the module defining `T` can create the resource, since it has to
+/// provide the `T` witness.
+///
+/// The ownership is transferred in two steps - the owner nominates the new owner, who has to
+/// accept it - so it can never be handed over to a mistyped address. The owner grants and revokes
+/// the roles, while the members can renounce their roles themselves. Roles are arbitrary byte
+/// strings, e.g. `b"MINTER"`.
+///
+/// All changes emit indexed events. The `move-vm-backend-common` crate can decode the resource to
+/// query the roles from the host.
+module std::access_control {
+    use std::indexed_event;
+    use std::option::{Self, Option};
+    use std::signer;
+    use std::vector;
+
+    /// The access control already exists under the address.
+    const EALREADY_INITIALIZED: u64 = 1;
+    /// The access control doesn't exist under the address.
+    const ENOT_INITIALIZED: u64 = 2;
+    /// The signer isn't the owner.
+    const ENOT_OWNER: u64 = 3;
+    /// The signer isn't the nominated owner.
+    const ENOT_PENDING_OWNER: u64 = 4;
+    /// The account doesn't have the role.
+    const EMISSING_ROLE: u64 = 5;
+
+    /// Members of a single role.
+    struct RoleMembers has copy, drop, store {
+        role: vector<u8>,
+        members: vector<address>,
+    }
+
+    /// Access control of the contract module defining `T`.
+    struct AccessControl<phantom T> has key {
+        owner: address,
+        /// Nominated owner which has to accept the ownership.
+        pending_owner: Option<address>,
+        /// Roles with at least one member, in the order they were first granted.
+        roles: vector<RoleMembers>,
+    }
+
+    /// The owner nominated the new owner.
+    struct OwnershipTransferStarted has drop, store {
+        holder: address,
+        owner: address,
+        pending_owner: address,
+    }
+
+    /// The ownership was transferred to the new owner.
+    struct OwnershipTransferred has drop, store {
+        holder: address,
+        previous_owner: address,
+        new_owner: address,
+    }
+
+    /// The role was granted to the account.
+    struct RoleGranted has drop, store {
+        holder: address,
+        role: vector<u8>,
+        account: address,
+    }
+
+    /// The role was revoked from the account, or renounced by it.
+    struct RoleRevoked has drop, store {
+        holder: address,
+        role: vector<u8>,
+        account: address,
+    }
+
+    /// Creates the access control under the `account` address, owned by the `account`.
+    public fun initialize<T: drop>(account: &signer, _witness: T) {
+        let holder = signer::address_of(account);
+        assert!(!exists<AccessControl<T>>(holder), EALREADY_INITIALIZED);
+
+        move_to(account, AccessControl<T> {
+            owner: holder,
+            pending_owner: option::none(),
+            roles: vector::empty(),
+        });
+    }
+
+    /// Checks if the access control exists under the address.
+    public fun is_initialized<T>(holder: address): bool {
+        exists<AccessControl<T>>(holder)
+    }
+
+    public fun owner<T>(holder: address): address acquires AccessControl {
+        borrow<T>(holder).owner
+    }
+
+    public fun pending_owner<T>(holder: address): Option<address> acquires AccessControl {
+        borrow<T>(holder).pending_owner
+    }
+
+    /// Aborts unless the `account` is the owner.
+    public fun assert_owner<T>(holder: address, account: &signer) acquires AccessControl {
+        assert!(borrow<T>(holder).owner == signer::address_of(account), ENOT_OWNER);
+    }
+
+    /// Nominates the new owner, replacing the previous nomination.
+    public fun transfer_ownership<T>(
+        owner: &signer,
+        holder: address,
+        new_owner: address,
+    ) acquires AccessControl {
+        let control = borrow_mut_as_owner<T>(owner, holder);
+        control.pending_owner = option::some(new_owner);
+
+        let event = OwnershipTransferStarted {
+            holder,
+            owner: control.owner,
+            pending_owner: new_owner,
+        };
+        indexed_event::emit(event, 3);
+    }
+
+    /// Cancels the nomination of the new owner.
+    public fun cancel_ownership_transfer<T>(
+        owner: &signer,
+        holder: address,
+    ) acquires AccessControl {
+        let control = borrow_mut_as_owner<T>(owner, holder);
+        control.pending_owner = option::none();
+    }
+
+    /// Accepts the ownership nominated to the `account`.
+    public fun accept_ownership<T>(account: &signer, holder: address) acquires AccessControl {
+        let new_owner = signer::address_of(account);
+        let control = borrow_mut<T>(holder);
+        assert!(control.pending_owner == option::some(new_owner), ENOT_PENDING_OWNER);
+
+        let previous_owner = control.owner;
+        control.owner = new_owner;
+        control.pending_owner = option::none();
+
+        let event = OwnershipTransferred { holder, previous_owner, new_owner };
+        indexed_event::emit(event, 3);
+    }
+
+    /// Checks if the `account` has the role.
+    public fun has_role<T>(
+        holder: address,
+        role: vector<u8>,
+        account: address,
+    ): bool acquires AccessControl {
+        let roles = &borrow<T>(holder).roles;
+        let (found, index) = find_role(roles, &role);
+        found && vector::contains(&vector::borrow(roles, index).members, &account)
+    }
+
+    /// Aborts unless the signer has the role.
+    public fun assert_role<T>(
+        holder: address,
+        role: vector<u8>,
+        account: &signer,
+    ) acquires AccessControl {
+        assert!(has_role<T>(holder, role, signer::address_of(account)), EMISSING_ROLE);
+    }
+
+    /// Members of the role, in the order they were granted it.
+    public fun members<T>(
+        holder: address,
+        role: vector<u8>,
+    ): vector<address> acquires AccessControl {
+        let roles = &borrow<T>(holder).roles;
+        let (found, index) = find_role(roles, &role);
+        if (found) {
+            vector::borrow(roles, index).members
+        } else {
+            vector::empty()
+        }
+    }
+
+    /// Grants the role to the `account`. Granting the role the account already has does nothing.
+    public fun grant_role<T>(
+        owner: &signer,
+        holder: address,
+        role: vector<u8>,
+        account: address,
+    ) acquires AccessControl {
+        let roles = &mut borrow_mut_as_owner<T>(owner, holder).roles;
+        let (found, index) = find_role(roles, &role);
+        if (!found) {
+            vector::push_back(roles, RoleMembers { role: copy role, members: vector::empty() });
+            index = vector::length(roles) - 1;
+        };
+
+        let members = &mut vector::borrow_mut(roles, index).members;
+        if (vector::contains(members, &account)) {
+            return
+        };
+        vector::push_back(members, account);
+
+        indexed_event::emit(RoleGranted { holder, role, account }, 3);
+    }
+
+    /// Revokes the role from the `account`. Aborts if the account doesn't have the role.
+    public fun revoke_role<T>(
+        owner: &signer,
+        holder: address,
+        role: vector<u8>,
+        account: address,
+    ) acquires AccessControl {
+        let control = borrow_mut_as_owner<T>(owner, holder);
+        remove_member(control, holder, role, account);
+    }
+
+    /// Gives up the role of the signer. Aborts if the signer doesn't have the role.
+    public fun renounce_role<T>(
+        account: &signer,
+        holder: address,
+        role: vector<u8>,
+    ) acquires AccessControl {
+        let control = borrow_mut<T>(holder);
+        remove_member(control, holder, role, signer::address_of(account));
+    }
+
+    fun borrow<T>(holder: address): &AccessControl<T> acquires AccessControl {
+        assert!(exists<AccessControl<T>>(holder), ENOT_INITIALIZED);
+        borrow_global<AccessControl<T>>(holder)
+    }
+
+    fun borrow_mut<T>(holder: address): &mut AccessControl<T> acquires AccessControl {
+        assert!(exists<AccessControl<T>>(holder), ENOT_INITIALIZED);
+        borrow_global_mut<AccessControl<T>>(holder)
+    }
+
+    fun borrow_mut_as_owner<T>(
+        owner: &signer,
+        holder: address,
+    ): &mut AccessControl<T> acquires AccessControl {
+        let control = borrow_mut<T>(holder);
+        assert!(control.owner == signer::address_of(owner), ENOT_OWNER);
+        control
+    }
+
+    /// Finds the index of the role.
+    fun find_role(roles: &vector<RoleMembers>, role: &vector<u8>): (bool, u64) {
+        let i = 0;
+        let len = vector::length(roles);
+        while (i < len) {
+            if (&vector::borrow(roles, i).role == role) {
+                return (true, i)
+            };
+            i = i + 1;
+        };
+        (false, 0)
+    }
+
+    /// Removes the account from the role members, dropping the roles without any members.
+    fun remove_member<T>(
+        control: &mut AccessControl<T>,
+        holder: address,
+        role: vector<u8>,
+        account: address,
+    ) {
+        let (found, index) = find_role(&control.roles, &role);
+        assert!(found, EMISSING_ROLE);
+
+        let members = &mut vector::borrow_mut(&mut control.roles, index).members;
+        let (is_member, member_index) = vector::index_of(members, &account);
+        assert!(is_member, EMISSING_ROLE);
+        vector::remove(members, member_index);
+        if (vector::is_empty(members)) {
+            vector::remove(&mut control.roles, index);
+        };
+
+        indexed_event::emit(RoleRevoked { holder, role, account }, 3);
+    }
+}
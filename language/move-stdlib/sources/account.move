@@ -0,0 +1,30 @@
+/// Existence and creation of the host chain accounts.
+///
+/// Host chains reap the accounts which fall below the existential deposit, and the funds sent to
+/// a missing account may be lost or rejected. Contracts check the destination account with
+/// `exists_at` (`exists` is a reserved builtin) and create it with `create` or `ensure_exists`
+/// before moving resources or funds to it, with the payer covering the creation costs.
+module std::account {
+    /// The account already exists.
+    const EACCOUNT_EXISTS: u64 = 1;
+    /// The host refused to create the account, e.g. the payer can't afford it.
+    const ECREATE_FAILED: u64 = 2;
+
+    /// Checks if the account exists on the host chain.
+    native public fun exists_at(account: address): bool;
+
+    /// Creates the missing account, paid for by the `payer`.
+    public fun create(payer: &signer, account: address) {
+        assert!(!exists_at(account), EACCOUNT_EXISTS);
+        assert!(create_account(payer, account), ECREATE_FAILED);
+    }
+
+    /// Creates the account, paid for by the `payer`, unless it exists already.
+    public fun ensure_exists(payer: &signer, account: address) {
+        if (!exists_at(account)) {
+            assert!(create_account(payer, account), ECREATE_FAILED);
+        }
+    }
+
+    native fun create_account(payer: &signer, account: address): bool;
+}
@@ -0,0 +1,148 @@
+/// Arbitrary-length unsigned integers for the math which overflows `u256`, e.g. the cryptographic
+/// or the AMM pool computations.
+///
+/// The integers are limited to 512 bytes (4096 bits) - all operations abort with
+/// `EOPERAND_TOO_LONG` instead of producing a longer integer. The gas cost of the operations is
+/// proportional to the operand size.
+module std::bigint {
+    use std::bcs;
+    use std::vector;
+
+    /// The operand or the result exceeds the maximum integer length.
+    const EOPERAND_TOO_LONG: u64 = 1;
+    /// Division or modulo by zero.
+    const EDIVISION_BY_ZERO: u64 = 2;
+    /// The subtraction result is negative.
+    const EUNDERFLOW: u64 = 3;
+    /// The integer doesn't fit into the requested type.
+    const EOVERFLOW: u64 = 4;
+
+    /// Result of the `compare`: the first integer is smaller.
+    const LESS_THAN: u8 = 0;
+    /// Result of the `compare`: the integers are equal.
+    const EQUAL: u8 = 1;
+    /// Result of the `compare`: the first integer is greater.
+    const GREATER_THAN: u8 = 2;
+
+    /// Unsigned integer of arbitrary length.
+    ///
+    /// Holds the little-endian magnitude without the trailing zero bytes, so equal integers have
+    /// equal representations.
+    struct BigInt has copy, drop, store {
+        bytes: vector<u8>,
+    }
+
+    public fun zero(): BigInt {
+        BigInt { bytes: vector::empty() }
+    }
+
+    public fun from_u64(value: u64): BigInt {
+        from_le_bytes(bcs::to_bytes(&value))
+    }
+
+    public fun from_u128(value: u128): BigInt {
+        from_le_bytes(bcs::to_bytes(&value))
+    }
+
+    public fun from_u256(value: u256): BigInt {
+        from_le_bytes(bcs::to_bytes(&value))
+    }
+
+    /// Creates the integer from its little-endian bytes.
+    public fun from_le_bytes(bytes: vector<u8>): BigInt {
+        BigInt { bytes: normalize(bytes) }
+    }
+
+    /// Creates the integer from its big-endian bytes.
+    public fun from_be_bytes(bytes: vector<u8>): BigInt {
+        vector::reverse(&mut bytes);
+        from_le_bytes(bytes)
+    }
+
+    /// Returns the little-endian bytes of the integer - zero has no bytes.
+    public fun to_le_bytes(value: &BigInt): vector<u8> {
+        value.bytes
+    }
+
+    /// Returns the big-endian bytes of the integer - zero has no bytes.
+    public fun to_be_bytes(value: &BigInt): vector<u8> {
+        let bytes = value.bytes;
+        vector::reverse(&mut bytes);
+        bytes
+    }
+
+    /// Converts the integer into `u256`. Aborts if it doesn't fit.
+    public fun to_u256(value: &BigInt): u256 {
+        let len = vector::length(&value.bytes);
+        assert!(len <= 32, EOVERFLOW);
+
+        let result = 0u256;
+        while (len > 0) {
+            len = len - 1;
+            result = (result << 8) | (*vector::borrow(&value.bytes, len) as u256);
+        };
+        result
+    }
+
+    public fun is_zero(value: &BigInt): bool {
+        vector::is_empty(&value.bytes)
+    }
+
+    public fun add(a: &BigInt, b: &BigInt): BigInt {
+        BigInt { bytes: add_raw(a.bytes, b.bytes) }
+    }
+
+    /// Subtracts `b` from `a`. Aborts if `b` is greater than `a`.
+    public fun sub(a: &BigInt, b: &BigInt): BigInt {
+        BigInt { bytes: sub_raw(a.bytes, b.bytes) }
+    }
+
+    public fun mul(a: &BigInt, b: &BigInt): BigInt {
+        BigInt { bytes: mul_raw(a.bytes, b.bytes) }
+    }
+
+    /// Divides `a` by `b`, rounding down. Aborts if `b` is zero.
+    public fun div(a: &BigInt, b: &BigInt): BigInt {
+        BigInt { bytes: div_raw(a.bytes, b.bytes) }
+    }
+
+    /// Remainder of dividing `a` by `b`. Aborts if `b` is zero.
+    public fun mod(a: &BigInt, b: &BigInt): BigInt {
+        BigInt { bytes: mod_raw(a.bytes, b.bytes) }
+    }
+
+    /// Raises the `base` to the `exponent` - zero to the zero is one.
+    public fun pow(base: &BigInt, exponent: u64): BigInt {
+        BigInt { bytes: pow_raw(base.bytes, exponent) }
+    }
+
+    /// Compares the integers, returning `LESS_THAN`, `EQUAL` or `GREATER_THAN`.
+    public fun compare(a: &BigInt, b: &BigInt): u8 {
+        compare_raw(a.bytes, b.bytes)
+    }
+
+    public fun lt(a: &BigInt, b: &BigInt): bool {
+        compare(a, b) == LESS_THAN
+    }
+
+    public fun gt(a: &BigInt, b: &BigInt): bool {
+        compare(a, b) == GREATER_THAN
+    }
+
+    public fun less_than(): u8 { LESS_THAN }
+
+    public fun equal(): u8 { EQUAL }
+
+    public fun greater_than(): u8 { GREATER_THAN }
+
+    native fun add_raw(a: vector<u8>, b: vector<u8>): vector<u8>;
+    native fun sub_raw(a: vector<u8>, b: vector<u8>): vector<u8>;
+    native fun mul_raw(a: vector<u8>, b: vector<u8>): vector<u8>;
+    native fun div_raw(a: vector<u8>, b: vector<u8>): vector<u8>;
+    native fun mod_raw(a: vector<u8>, b: vector<u8>): vector<u8>;
+    native fun pow_raw(base: vector<u8>, exponent: u64): vector<u8>;
+    native fun compare_raw(a: vector<u8>, b: vector<u8>): u8;
+
+    /// Strips the trailing zero bytes. Aborts if the integer is too long.
+    native fun normalize(bytes: vector<u8>): vector<u8>;
+}
@@ -0,0 +1,48 @@
+/// Constants of the chain the contracts run on.
+///
+/// The host writes the `ChainConfig` resource under `@std` at genesis, so the portable packages
+/// can adapt to the chain - e.g. scale the amounts by the token decimals. Nothing else can create
+/// or modify the resource.
+module std::chain_config {
+    /// The chain configuration wasn't written at genesis.
+    const ENOT_CONFIGURED: u64 = 1;
+
+    /// Chain constants written by the host at genesis.
+    struct ChainConfig has key {
+        /// Identifier of the chain.
+        chain_id: u64,
+        /// Number of the decimals of the native token.
+        token_decimals: u8,
+        /// Length of the account addresses in bytes.
+        address_length: u8,
+    }
+
+    /// Checks if the chain configuration was written at genesis.
+    public fun is_configured(): bool {
+        exists<ChainConfig>(@std)
+    }
+
+    /// Identifier of the chain.
+    public fun chain_id(): u64 acquires ChainConfig {
+        assert!(is_configured(), ENOT_CONFIGURED);
+        borrow_global<ChainConfig>(@std).chain_id
+    }
+
+    /// Number of the decimals of the native token.
+    public fun token_decimals(): u8 acquires ChainConfig {
+        assert!(is_configured(), ENOT_CONFIGURED);
+        borrow_global<ChainConfig>(@std).token_decimals
+    }
+
+    /// Length of the account addresses in bytes.
+    public fun address_length(): u8 acquires ChainConfig {
+        assert!(is_configured(), ENOT_CONFIGURED);
+        borrow_global<ChainConfig>(@std).address_length
+    }
+
+    #[test_only]
+    /// Writes the chain configuration for the tests.
+    public fun set_for_testing(std: &signer, chain_id: u64, token_decimals: u8, address_length: u8) {
+        move_to(std, ChainConfig { chain_id, token_decimals, address_length });
+    }
+}
@@ -0,0 +1,14 @@
+/// Expiry of the stored resources.
+///
+/// A resource with an expiry is removed from the storage by the host once the given block is
+/// reached. The removal happens outside of any transaction, so the resource doesn't need to have
+/// the `drop` ability - use this for data which would otherwise accumulate forever, like the
+/// stale orders or the session data.
+module std::expiry {
+    /// Marks the resource `T` stored under the account of the signer to be removed at the block
+    /// `expires_at`. Setting the expiry again replaces the previous one.
+    native public fun set_expiry<T: key>(account: &signer, expires_at: u64);
+
+    /// Removes the expiry of the resource `T` stored under the account of the signer.
+    native public fun clear_expiry<T: key>(account: &signer);
+}
@@ -0,0 +1,16 @@
+/// Calls of the host-side functions, e.g. the bridges into other smart contract VMs or the
+/// cross-chain message senders.
+///
+/// The host registers the callable functions under numeric targets. Both the payload and the
+/// response are BCS-encoded, so their format is agreed between the Move code and the host
+/// function.
+module std::foreign {
+    /// Calls the host function registered under the `target` with the BCS-encoded `payload` and
+    /// returns its BCS-encoded response.
+    ///
+    /// The host function can use at most `gas_limit` gas, which must be covered by the remaining
+    /// gas of the transaction - only the gas actually used is charged. Aborts with the abort code
+    /// of the failed host function. The host function can't execute any Move code, so the call
+    /// can't re-enter the calling module.
+    native public fun call(target: u64, payload: vector<u8>, gas_limit: u64): vector<u8>;
+}
@@ -0,0 +1,13 @@
+/// Events with indexed topics, which can be filtered by the indexers.
+///
+/// Each emitted event gets up to four topics: the hash of the event type, followed by the hashes
+/// of its first `indexed_fields` fields in the declaration order. The hashing rules are defined
+/// in the `move-vm-backend-common` crate.
+module std::indexed_event {
+    /// The event type is not a struct, it has fewer fields than indexed or more than three fields
+    /// are indexed.
+    const EINVALID_INDEXED_FIELDS: u64 = 1;
+
+    /// Emit the event `msg` with its first `indexed_fields` fields (at most three) as topics.
+    native public fun emit<T: drop + store>(msg: T, indexed_fields: u64);
+}
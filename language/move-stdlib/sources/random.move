@@ -0,0 +1,27 @@
+/// Randomness provided by the host chain.
+///
+/// The seeds come from the randomness source of the host, e.g. the randomness beacon of the
+/// block, and are the same on all nodes executing the transaction. They are only as unpredictable
+/// as that source - the block authors may be able to influence them, so they shouldn't decide
+/// anything worth more than the cost of manipulating the source.
+module std::random {
+    use std::hash;
+    use std::vector;
+
+    /// Random seed of 32 bytes for the `subject`.
+    ///
+    /// Different subjects give independent seeds within the same block.
+    native public fun seed(subject: vector<u8>): vector<u8>;
+
+    /// Random number in the range `[0, bound)` for the `subject`.
+    public fun u64_below(subject: vector<u8>, bound: u64): u64 {
+        let seed = hash::sha3_256(seed(subject));
+        let value = 0u64;
+        let i = 0;
+        while (i < 8) {
+            value = (value << 8) | (*vector::borrow(&seed, i) as u64);
+            i = i + 1;
+        };
+        value % bound
+    }
+}
@@ -0,0 +1,13 @@
+/// Time provided by the host chain.
+///
+/// The time is the timestamp of the block the transaction is executed in, so it is the same on
+/// all nodes executing the transaction and doesn't change within the block.
+module std::timestamp {
+    /// Timestamp of the current block in milliseconds since the Unix epoch.
+    native public fun now_milliseconds(): u64;
+
+    /// Timestamp of the current block in seconds since the Unix epoch.
+    public fun now_seconds(): u64 {
+        now_milliseconds() / 1000
+    }
+}
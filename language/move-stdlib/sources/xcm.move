@@ -0,0 +1,168 @@
+/// Construction and dispatch of the cross-chain messages.
+///
+/// Only a limited subset of XCM is supported - a message is sent to a single destination chain
+/// and carries exactly one instruction, either an asset transfer or a transact of a runtime call.
+/// The messages are versioned, so the subset can grow without changing the meaning of the
+/// messages built by the already published modules.
+///
+/// The host converts the messages into the XCM of its runtime and dispatches them with the sender
+/// as the origin.
+module std::xcm {
+    use std::bcs;
+    use std::option::{Self, Option};
+
+    /// Version of the message format, following the XCM version it's modelled on.
+    const XCM_VERSION: u8 = 3;
+
+    /// The transacted call is dispatched by the sovereign account of the sender.
+    const ORIGIN_SOVEREIGN_ACCOUNT: u8 = 1;
+    /// The transacted call is dispatched by the sender location itself.
+    const ORIGIN_XCM: u8 = 3;
+
+    /// The origin kind can't be requested by the Move code.
+    const EINVALID_ORIGIN_KIND: u64 = 1;
+    /// The host refused to send the message.
+    const ESEND_FAILED: u64 = 2;
+
+    /// Chain or account location relative to the sending chain.
+    struct Location has copy, drop, store {
+        parents: u8,
+        parachain: Option<u32>,
+        account: Option<address>,
+    }
+
+    /// Asset location relative to the sending chain.
+    struct AssetId has copy, drop, store {
+        parents: u8,
+        parachain: Option<u32>,
+        pallet_instance: Option<u8>,
+        general_index: Option<u128>,
+    }
+
+    /// Fungible asset with its amount.
+    struct Asset has copy, drop, store {
+        id: AssetId,
+        amount: u128,
+    }
+
+    struct TransferAsset has copy, drop, store {
+        asset: Asset,
+        beneficiary: Location,
+    }
+
+    struct Transact has copy, drop, store {
+        origin_kind: u8,
+        ref_time: u64,
+        proof_size: u64,
+        call: vector<u8>,
+    }
+
+    /// Message with a single instruction - exactly one of the instruction fields is set.
+    struct Message has copy, drop, store {
+        version: u8,
+        destination: Location,
+        transfer_asset: Option<TransferAsset>,
+        transact: Option<Transact>,
+    }
+
+    /// The relay chain of the sending parachain.
+    public fun relay_chain(): Location {
+        Location { parents: 1, parachain: option::none(), account: option::none() }
+    }
+
+    /// A sibling parachain of the sending parachain.
+    public fun sibling_parachain(parachain: u32): Location {
+        Location { parents: 1, parachain: option::some(parachain), account: option::none() }
+    }
+
+    /// A 32-byte account on the destination chain.
+    public fun account(account: address): Location {
+        Location { parents: 0, parachain: option::none(), account: option::some(account) }
+    }
+
+    /// The native asset of the sending chain.
+    public fun native_asset(amount: u128): Asset {
+        let id = AssetId {
+            parents: 0,
+            parachain: option::none(),
+            pallet_instance: option::none(),
+            general_index: option::none(),
+        };
+        Asset { id, amount }
+    }
+
+    /// The native asset of the relay chain.
+    public fun relay_asset(amount: u128): Asset {
+        let id = AssetId {
+            parents: 1,
+            parachain: option::none(),
+            pallet_instance: option::none(),
+            general_index: option::none(),
+        };
+        Asset { id, amount }
+    }
+
+    /// An asset held by the assets pallet of the given parachain, e.g. the asset hub.
+    public fun parachain_asset(
+        parachain: u32,
+        pallet_instance: u8,
+        asset_id: u128,
+        amount: u128,
+    ): Asset {
+        let id = AssetId {
+            parents: 1,
+            parachain: option::some(parachain),
+            pallet_instance: option::some(pallet_instance),
+            general_index: option::some(asset_id),
+        };
+        Asset { id, amount }
+    }
+
+    /// Message transferring the `asset` to the `beneficiary` on the `destination` chain.
+    public fun transfer_asset(destination: Location, asset: Asset, beneficiary: Location): Message {
+        Message {
+            version: XCM_VERSION,
+            destination,
+            transfer_asset: option::some(TransferAsset { asset, beneficiary }),
+            transact: option::none(),
+        }
+    }
+
+    /// Message dispatching the SCALE-encoded runtime `call` on the `destination` chain with the
+    /// given origin kind and weight limit.
+    public fun transact(
+        destination: Location,
+        origin_kind: u8,
+        ref_time: u64,
+        proof_size: u64,
+        call: vector<u8>,
+    ): Message {
+        assert!(
+            origin_kind == ORIGIN_SOVEREIGN_ACCOUNT || origin_kind == ORIGIN_XCM,
+            EINVALID_ORIGIN_KIND
+        );
+        Message {
+            version: XCM_VERSION,
+            destination,
+            transfer_asset: option::none(),
+            transact: option::some(Transact { origin_kind, ref_time, proof_size, call }),
+        }
+    }
+
+    /// Origin kind of the sovereign account of the sender.
+    public fun origin_sovereign_account(): u8 {
+        ORIGIN_SOVEREIGN_ACCOUNT
+    }
+
+    /// Origin kind of the sender location.
+    public fun origin_xcm(): u8 {
+        ORIGIN_XCM
+    }
+
+    /// Sends the message with the `sender` as the origin.
+    public fun send(sender: &signer, message: Message) {
+        assert!(send_encoded(sender, bcs::to_bytes(&message)), ESEND_FAILED);
+    }
+
+    native fun send_encoded(sender: &signer, message: vector<u8>): bool;
+}
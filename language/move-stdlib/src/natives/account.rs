@@ -0,0 +1,164 @@
+// Copyright (c) Eiger, Equilibrium Group
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::natives::helpers::make_module_natives;
+use crate::natives::host_gas::charge_host_weight;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{collections::VecDeque, sync::Arc};
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    account_address::AccountAddress, gas_algebra::InternalGas, vm_status::StatusCode,
+};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::SignerRef,
+    values::Value,
+};
+
+/// Host-side account lifecycle used by the `std::account` module.
+pub trait AccountHandler {
+    /// Checks if the account exists on the host chain.
+    fn account_exists(&self, account: AccountAddress) -> PartialVMResult<bool>;
+
+    /// Creates the account on the host chain, with the `payer` paying for the creation.
+    ///
+    /// Returns `false` if the account can't be created, e.g. the payer can't afford it.
+    fn create_account(
+        &self,
+        payer: AccountAddress,
+        account: AccountAddress,
+    ) -> PartialVMResult<bool>;
+}
+
+/// Native context extension which gives the `std::account` module access to the host accounts.
+///
+/// The host has to register it for every session - without a handler, the account natives fail.
+#[derive(Tid)]
+pub struct NativeAccountContext<'a> {
+    handler: Option<&'a dyn AccountHandler>,
+}
+
+impl<'a> NativeAccountContext<'a> {
+    pub fn new(handler: &'a dyn AccountHandler) -> Self {
+        Self {
+            handler: Some(handler),
+        }
+    }
+
+    /// Context for the environments without any host accounts, e.g. the unit tests.
+    pub fn unavailable() -> Self {
+        Self { handler: None }
+    }
+}
+
+fn account_handler<'a>(context: &'a NativeContext) -> PartialVMResult<&'a dyn AccountHandler> {
+    context
+        .extensions()
+        .get::<NativeAccountContext>()
+        .handler
+        .ok_or_else(|| {
+            PartialVMError::new(StatusCode::VM_EXTENSION_ERROR)
+                .with_message("Host accounts aren't available".into())
+        })
+}
+
+/***************************************************************************************************
+ * native fun exists_at
+ *
+ *   gas cost: base_cost
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct ExistsAtGasParameters {
+    pub base: InternalGas,
+}
+
+fn native_exists_at(
+    gas_params: &ExistsAtGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 1);
+
+    let account = pop_arg!(args, AccountAddress);
+
+    context.charge(gas_params.base)?;
+    let ret = account_handler(context)?.account_exists(account)?;
+    charge_host_weight(context)?;
+
+    NativeResult::map_partial_vm_result_one(InternalGas::zero(), Ok(Value::bool(ret)))
+}
+
+pub fn make_native_exists_at(gas_params: ExistsAtGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_exists_at(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * native fun create_account
+ *
+ *   gas cost: base_cost
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct CreateAccountGasParameters {
+    pub base: InternalGas,
+}
+
+fn native_create_account(
+    gas_params: &CreateAccountGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 2);
+
+    let account = pop_arg!(args, AccountAddress);
+    let payer = pop_arg!(args, SignerRef);
+
+    // Charge upfront, so the account is never created by a call which runs out of gas.
+    context.charge(gas_params.base)?;
+
+    let payer = payer.address()?;
+    let ret = account_handler(context)?.create_account(payer, account)?;
+    charge_host_weight(context)?;
+
+    NativeResult::map_partial_vm_result_one(InternalGas::zero(), Ok(Value::bool(ret)))
+}
+
+pub fn make_native_create_account(gas_params: CreateAccountGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_create_account(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * module
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct GasParameters {
+    pub exists_at: ExistsAtGasParameters,
+    pub create_account: CreateAccountGasParameters,
+}
+
+pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [
+        ("exists_at", make_native_exists_at(gas_params.exists_at)),
+        (
+            "create_account",
+            make_native_create_account(gas_params.create_account),
+        ),
+    ];
+
+    make_module_natives(natives)
+}
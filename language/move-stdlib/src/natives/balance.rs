@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::natives::helpers::make_module_natives;
+use crate::natives::host_gas::charge_host_weight;
 use alloc::string::String;
 use alloc::vec::Vec;
 use alloc::{collections::VecDeque, sync::Arc};
 use move_binary_format::errors::PartialVMResult;
 use move_core_types::account_address::AccountAddress;
-use move_core_types::gas_algebra::InternalGas;
+use move_core_types::gas_algebra::{InternalGas, InternalGasPerArg, NumArgs};
 use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
 use move_vm_types::values::SignerRef;
 use move_vm_types::{
@@ -17,12 +18,22 @@ use move_vm_types::{
 /***************************************************************************************************
  * native fun transfer
  *
- *   gas cost: base_cost
+ *   gas cost: base_cost + per_account * 2
+ *
+ *   Both the source and the destination accounts are touched. The weight reported by the host is
+ *   charged on top, as for all balance natives.
+ *
+ *   Returns false if the transfer can't be made. The backend defines the edge cases:
+ *   - zero transfers succeed without moving any funds,
+ *   - transfers to self succeed without any changes if the source can afford them,
+ *   - transfers overflowing the destination balance fail,
+ *   - transfers below the minimum balance to an account which doesn't exist fail.
  *
  **************************************************************************************************/
 #[derive(Debug, Clone)]
 pub struct TransferGasParameters {
     pub base: InternalGas,
+    pub per_account: InternalGasPerArg,
 }
 
 pub fn native_transfer(
@@ -38,10 +49,14 @@ pub fn native_transfer(
     let dst = pop_arg!(args, AccountAddress);
     let src = pop_arg!(args, SignerRef);
 
+    // Charge upfront, so the funds are never moved by a call which runs out of gas.
+    context.charge(gas_params.base + gas_params.per_account * NumArgs::new(2))?;
+
     let src = src.address()?;
     let ret = context.transfer(src, dst, amount)?;
+    charge_host_weight(context)?;
 
-    NativeResult::map_partial_vm_result_one(gas_params.base, Ok(Value::bool(ret)))
+    NativeResult::map_partial_vm_result_one(InternalGas::zero(), Ok(Value::bool(ret)))
 }
 
 pub fn make_native_transfer(gas_params: TransferGasParameters) -> NativeFunction {
@@ -55,12 +70,13 @@ pub fn make_native_transfer(gas_params: TransferGasParameters) -> NativeFunction
 /***************************************************************************************************
  * native fun cheque_amount
  *
- *   gas cost: base_cost
+ *   gas cost: base_cost + per_account
  *
  **************************************************************************************************/
 #[derive(Debug, Clone)]
 pub struct ChequeAmountGasParameters {
     pub base: InternalGas,
+    pub per_account: InternalGasPerArg,
 }
 
 pub fn native_cheque_amount(
@@ -74,9 +90,11 @@ pub fn native_cheque_amount(
 
     let account_addr = pop_arg!(args, AccountAddress);
 
+    let cost = gas_params.base + gas_params.per_account * NumArgs::new(1);
     let ret = context.cheque_amount(account_addr)?;
+    charge_host_weight(context)?;
 
-    NativeResult::map_partial_vm_result_one(gas_params.base, Ok(Value::u128(ret)))
+    NativeResult::map_partial_vm_result_one(cost, Ok(Value::u128(ret)))
 }
 
 pub fn make_native_cheque_amount(gas_params: ChequeAmountGasParameters) -> NativeFunction {
@@ -90,12 +108,13 @@ pub fn make_native_cheque_amount(gas_params: ChequeAmountGasParameters) -> Nativ
 /***************************************************************************************************
  * native fun total_amount
  *
- *   gas cost: base_cost
+ *   gas cost: base_cost + per_account
  *
  **************************************************************************************************/
 #[derive(Debug, Clone)]
 pub struct TotalAmountGasParameters {
     pub base: InternalGas,
+    pub per_account: InternalGasPerArg,
 }
 
 pub fn native_total_amount(
@@ -109,9 +128,11 @@ pub fn native_total_amount(
 
     let account_addr = pop_arg!(args, AccountAddress);
 
+    let cost = gas_params.base + gas_params.per_account * NumArgs::new(1);
     let ret = context.total_amount(account_addr)?;
+    charge_host_weight(context)?;
 
-    NativeResult::map_partial_vm_result_one(gas_params.base, Ok(Value::u128(ret)))
+    NativeResult::map_partial_vm_result_one(cost, Ok(Value::u128(ret)))
 }
 
 pub fn make_native_total_amount(gas_params: TotalAmountGasParameters) -> NativeFunction {
@@ -122,6 +143,114 @@ pub fn make_native_total_amount(gas_params: TotalAmountGasParameters) -> NativeF
     )
 }
 
+/***************************************************************************************************
+ * native fun total_issuance
+ *
+ *   gas cost: base_cost
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct TotalIssuanceGasParameters {
+    pub base: InternalGas,
+}
+
+pub fn native_total_issuance(
+    gas_params: &TotalIssuanceGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    let ret = context.total_issuance()?;
+    charge_host_weight(context)?;
+
+    NativeResult::map_partial_vm_result_one(gas_params.base, Ok(Value::u128(ret)))
+}
+
+pub fn make_native_total_issuance(gas_params: TotalIssuanceGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_total_issuance(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * native fun account_exists
+ *
+ *   gas cost: base_cost + per_account
+ *
+ *   Named `account_exists` since `exists` is a reserved builtin in Move.
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct AccountExistsGasParameters {
+    pub base: InternalGas,
+    pub per_account: InternalGasPerArg,
+}
+
+pub fn native_account_exists(
+    gas_params: &AccountExistsGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 1);
+
+    let account_addr = pop_arg!(args, AccountAddress);
+
+    let cost = gas_params.base + gas_params.per_account * NumArgs::new(1);
+    let ret = context.account_exists(account_addr)?;
+    charge_host_weight(context)?;
+
+    NativeResult::map_partial_vm_result_one(cost, Ok(Value::bool(ret)))
+}
+
+pub fn make_native_account_exists(gas_params: AccountExistsGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_account_exists(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * native fun minimum_balance
+ *
+ *   gas cost: base_cost
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct MinimumBalanceGasParameters {
+    pub base: InternalGas,
+}
+
+pub fn native_minimum_balance(
+    gas_params: &MinimumBalanceGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    let ret = context.minimum_balance()?;
+    charge_host_weight(context)?;
+
+    NativeResult::map_partial_vm_result_one(gas_params.base, Ok(Value::u128(ret)))
+}
+
+pub fn make_native_minimum_balance(gas_params: MinimumBalanceGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_minimum_balance(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
 /***************************************************************************************************
  * module
  **************************************************************************************************/
@@ -130,6 +259,9 @@ pub struct GasParameters {
     pub transfer: TransferGasParameters,
     pub cheque_amount: ChequeAmountGasParameters,
     pub total_amount: TotalAmountGasParameters,
+    pub total_issuance: TotalIssuanceGasParameters,
+    pub account_exists: AccountExistsGasParameters,
+    pub minimum_balance: MinimumBalanceGasParameters,
 }
 
 pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
@@ -143,6 +275,18 @@ pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, Nati
             "total_amount",
             make_native_total_amount(gas_params.total_amount),
         ),
+        (
+            "total_issuance",
+            make_native_total_issuance(gas_params.total_issuance),
+        ),
+        (
+            "account_exists",
+            make_native_account_exists(gas_params.account_exists),
+        ),
+        (
+            "minimum_balance",
+            make_native_minimum_balance(gas_params.minimum_balance),
+        ),
     ];
 
     make_module_natives(natives)
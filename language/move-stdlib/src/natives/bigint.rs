@@ -0,0 +1,443 @@
+// Copyright (c) Eiger, Equilibrium Group
+// SPDX-License-Identifier: Apache-2.0
+
+//! Natives of the `std::bigint` module.
+//!
+//! Integers are passed as their little-endian magnitude without the trailing zero bytes, so zero
+//! is the empty vector. Operands and results are limited to [`MAX_OPERAND_BYTES`] - the natives
+//! abort with [`EOPERAND_TOO_LONG`] instead of producing a longer integer.
+
+use crate::natives::helpers::make_module_natives;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{collections::VecDeque, sync::Arc};
+use core::cmp::{self, Ordering};
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::gas_algebra::{InternalGas, InternalGasPerByte, NumBytes};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::Value,
+};
+use num_bigint::BigUint;
+use smallvec::smallvec;
+
+/// Maximum length of the operands and the results in bytes (4096 bits).
+pub const MAX_OPERAND_BYTES: usize = 512;
+
+// Abort codes, matching the `std::bigint` error constants.
+pub const EOPERAND_TOO_LONG: u64 = 1;
+pub const EDIVISION_BY_ZERO: u64 = 2;
+pub const EUNDERFLOW: u64 = 3;
+
+/// Converts the little-endian magnitude into the integer.
+fn to_integer(bytes: &[u8]) -> Result<BigUint, u64> {
+    if bytes.len() > MAX_OPERAND_BYTES {
+        return Err(EOPERAND_TOO_LONG);
+    }
+    Ok(BigUint::from_bytes_le(bytes))
+}
+
+/// Converts the integer into the little-endian magnitude without the trailing zero bytes.
+fn to_bytes(value: BigUint) -> Result<Vec<u8>, u64> {
+    if value.bits() == 0 {
+        return Ok(Vec::new());
+    }
+
+    let bytes = value.to_bytes_le();
+    if bytes.len() > MAX_OPERAND_BYTES {
+        return Err(EOPERAND_TOO_LONG);
+    }
+    Ok(bytes)
+}
+
+/// Applies the operation to the operands and returns its result, or aborts with its error code.
+fn binary_op(
+    cost: InternalGas,
+    a: &[u8],
+    b: &[u8],
+    op: impl FnOnce(BigUint, BigUint) -> Result<BigUint, u64>,
+) -> PartialVMResult<NativeResult> {
+    let result = to_integer(a)
+        .and_then(|a| Ok((a, to_integer(b)?)))
+        .and_then(|(a, b)| op(a, b))
+        .and_then(to_bytes);
+
+    match result {
+        Ok(bytes) => Ok(NativeResult::ok(cost, smallvec![Value::vector_u8(bytes)])),
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+/// Number of bytes the linear operations are charged for.
+fn total_len(a: &[u8], b: &[u8]) -> NumBytes {
+    NumBytes::new((a.len() + b.len()) as u64)
+}
+
+/// Number of bytes the quadratic operations are charged for - empty operands count as a byte.
+fn product_len(a: &[u8], b: &[u8]) -> NumBytes {
+    NumBytes::new((cmp::max(a.len(), 1) * cmp::max(b.len(), 1)) as u64)
+}
+
+/***************************************************************************************************
+ * gas parameters
+ *
+ *   Linear operations (add, sub, compare, normalize):
+ *     gas cost: base_cost + per_byte * total operand length
+ *
+ *   Quadratic operations (mul, div, mod):
+ *     gas cost: base_cost + per_byte_squared * product of the operand lengths
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct LinearGasParameters {
+    pub base: InternalGas,
+    pub per_byte: InternalGasPerByte,
+}
+
+impl LinearGasParameters {
+    fn cost(&self, len: NumBytes) -> InternalGas {
+        self.base + self.per_byte * len
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QuadraticGasParameters {
+    pub base: InternalGas,
+    pub per_byte_squared: InternalGasPerByte,
+}
+
+impl QuadraticGasParameters {
+    fn cost(&self, len: NumBytes) -> InternalGas {
+        self.base + self.per_byte_squared * len
+    }
+}
+
+/***************************************************************************************************
+ * native fun add_raw
+ *
+ *   gas cost: linear
+ *
+ **************************************************************************************************/
+fn native_add(
+    gas_params: &LinearGasParameters,
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 2);
+
+    let b = pop_arg!(args, Vec<u8>);
+    let a = pop_arg!(args, Vec<u8>);
+
+    let cost = gas_params.cost(total_len(&a, &b));
+    binary_op(cost, &a, &b, |a, b| Ok(a + b))
+}
+
+pub fn make_native_add(gas_params: LinearGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_add(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * native fun sub_raw
+ *
+ *   gas cost: linear
+ *
+ **************************************************************************************************/
+fn native_sub(
+    gas_params: &LinearGasParameters,
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 2);
+
+    let b = pop_arg!(args, Vec<u8>);
+    let a = pop_arg!(args, Vec<u8>);
+
+    let cost = gas_params.cost(total_len(&a, &b));
+    binary_op(cost, &a, &b, |a, b| {
+        if a < b {
+            return Err(EUNDERFLOW);
+        }
+        Ok(a - b)
+    })
+}
+
+pub fn make_native_sub(gas_params: LinearGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_sub(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * native fun mul_raw
+ *
+ *   gas cost: quadratic
+ *
+ **************************************************************************************************/
+fn native_mul(
+    gas_params: &QuadraticGasParameters,
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 2);
+
+    let b = pop_arg!(args, Vec<u8>);
+    let a = pop_arg!(args, Vec<u8>);
+
+    let cost = gas_params.cost(product_len(&a, &b));
+    binary_op(cost, &a, &b, |a, b| Ok(a * b))
+}
+
+pub fn make_native_mul(gas_params: QuadraticGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_mul(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * native fun div_raw
+ *
+ *   gas cost: quadratic
+ *
+ **************************************************************************************************/
+fn native_div(
+    gas_params: &QuadraticGasParameters,
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 2);
+
+    let b = pop_arg!(args, Vec<u8>);
+    let a = pop_arg!(args, Vec<u8>);
+
+    let cost = gas_params.cost(product_len(&a, &b));
+    binary_op(cost, &a, &b, |a, b| {
+        if b.bits() == 0 {
+            return Err(EDIVISION_BY_ZERO);
+        }
+        Ok(a / b)
+    })
+}
+
+pub fn make_native_div(gas_params: QuadraticGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_div(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * native fun mod_raw
+ *
+ *   gas cost: quadratic
+ *
+ **************************************************************************************************/
+fn native_mod(
+    gas_params: &QuadraticGasParameters,
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 2);
+
+    let b = pop_arg!(args, Vec<u8>);
+    let a = pop_arg!(args, Vec<u8>);
+
+    let cost = gas_params.cost(product_len(&a, &b));
+    binary_op(cost, &a, &b, |a, b| {
+        if b.bits() == 0 {
+            return Err(EDIVISION_BY_ZERO);
+        }
+        Ok(a % b)
+    })
+}
+
+pub fn make_native_mod(gas_params: QuadraticGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_mod(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * native fun pow_raw
+ *
+ *   gas cost: base_cost + per_byte_squared * size_of(result)^2
+ *
+ *   The result size is estimated from the operands, so the results over the limit are rejected
+ *   before they are computed.
+ *
+ **************************************************************************************************/
+fn native_pow(
+    gas_params: &QuadraticGasParameters,
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 2);
+
+    let exponent = pop_arg!(args, u64);
+    let base = pop_arg!(args, Vec<u8>);
+
+    let max_bits = (MAX_OPERAND_BYTES * 8) as u64;
+    let base_bits = match to_integer(&base) {
+        Ok(base) => base.bits(),
+        Err(_) => max_bits + 1,
+    };
+    // Bounds of the result bit length - zero and one never grow.
+    let (min_result_bits, max_result_bits) = match base_bits {
+        0 | 1 => (base_bits, base_bits),
+        _ => (
+            (base_bits - 1).saturating_mul(exponent).saturating_add(1),
+            base_bits.saturating_mul(exponent),
+        ),
+    };
+
+    let result_len = cmp::min(max_result_bits.div_ceil(8), MAX_OPERAND_BYTES as u64);
+    let cost = gas_params.cost(NumBytes::new(cmp::max(result_len, 1).pow(2)));
+
+    binary_op(cost, &base, &[], |base, _| {
+        if min_result_bits > max_bits {
+            return Err(EOPERAND_TOO_LONG);
+        }
+        // Bases above one have the exponent bounded by the maximum bit length, while zero and one
+        // stay the same for any positive exponent.
+        let exponent = u32::try_from(cmp::min(exponent, max_bits)).expect("bounded exponent");
+        Ok(base.pow(exponent))
+    })
+}
+
+pub fn make_native_pow(gas_params: QuadraticGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_pow(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * native fun compare_raw
+ *
+ *   gas cost: linear
+ *
+ **************************************************************************************************/
+fn native_compare(
+    gas_params: &LinearGasParameters,
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 2);
+
+    let b = pop_arg!(args, Vec<u8>);
+    let a = pop_arg!(args, Vec<u8>);
+
+    let cost = gas_params.cost(total_len(&a, &b));
+    let ordering = to_integer(&a).and_then(|a| Ok(a.cmp(&to_integer(&b)?)));
+
+    match ordering {
+        Ok(ordering) => {
+            let ordering = match ordering {
+                Ordering::Less => 0,
+                Ordering::Equal => 1,
+                Ordering::Greater => 2,
+            };
+            Ok(NativeResult::ok(cost, smallvec![Value::u8(ordering)]))
+        }
+        Err(abort_code) => Ok(NativeResult::err(cost, abort_code)),
+    }
+}
+
+pub fn make_native_compare(gas_params: LinearGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_compare(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * native fun normalize
+ *
+ *   gas cost: linear
+ *
+ **************************************************************************************************/
+fn native_normalize(
+    gas_params: &LinearGasParameters,
+    _context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 1);
+
+    let mut bytes = pop_arg!(args, Vec<u8>);
+
+    let cost = gas_params.cost(NumBytes::new(bytes.len() as u64));
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+
+    if bytes.len() > MAX_OPERAND_BYTES {
+        return Ok(NativeResult::err(cost, EOPERAND_TOO_LONG));
+    }
+    Ok(NativeResult::ok(cost, smallvec![Value::vector_u8(bytes)]))
+}
+
+pub fn make_native_normalize(gas_params: LinearGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_normalize(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * module
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct GasParameters {
+    pub add: LinearGasParameters,
+    pub sub: LinearGasParameters,
+    pub mul: QuadraticGasParameters,
+    pub div: QuadraticGasParameters,
+    pub modulo: QuadraticGasParameters,
+    pub pow: QuadraticGasParameters,
+    pub compare: LinearGasParameters,
+    pub normalize: LinearGasParameters,
+}
+
+pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [
+        ("add_raw", make_native_add(gas_params.add)),
+        ("sub_raw", make_native_sub(gas_params.sub)),
+        ("mul_raw", make_native_mul(gas_params.mul)),
+        ("div_raw", make_native_div(gas_params.div)),
+        ("mod_raw", make_native_mod(gas_params.modulo)),
+        ("pow_raw", make_native_pow(gas_params.pow)),
+        ("compare_raw", make_native_compare(gas_params.compare)),
+        ("normalize", make_native_normalize(gas_params.normalize)),
+    ];
+
+    make_module_natives(natives)
+}
@@ -0,0 +1,154 @@
+// Copyright (c) Eiger, Equilibrium Group
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::natives::helpers::make_module_natives;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+};
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    account_address::AccountAddress, gas_algebra::InternalGas, language_storage::StructTag,
+    language_storage::TypeTag, vm_status::StatusCode,
+};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::SignerRef,
+    values::Value,
+};
+use smallvec::smallvec;
+
+/// Expiry changes made by the transaction: `Some(block)` sets the expiry, `None` clears it.
+pub type ExpiryChanges = BTreeMap<(AccountAddress, StructTag), Option<u64>>;
+
+/// Native context extension which collects the expiry changes of the session.
+///
+/// The host has to register it for every session and persist the changes once the session
+/// finishes successfully.
+#[derive(Default, Tid)]
+pub struct NativeExpiryContext {
+    changes: ExpiryChanges,
+}
+
+impl NativeExpiryContext {
+    pub fn into_changes(self) -> ExpiryChanges {
+        self.changes
+    }
+}
+
+fn resource_key(
+    context: &NativeContext,
+    ty: &Type,
+    account: SignerRef,
+) -> PartialVMResult<(AccountAddress, StructTag)> {
+    let TypeTag::Struct(tag) = context.type_to_type_tag(ty)? else {
+        return Err(PartialVMError::new(StatusCode::TYPE_MISMATCH));
+    };
+
+    Ok((account.address()?, *tag))
+}
+
+/***************************************************************************************************
+ * native fun set_expiry
+ *
+ *   gas cost: base_cost
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct SetExpiryGasParameters {
+    pub base: InternalGas,
+}
+
+fn native_set_expiry(
+    gas_params: &SetExpiryGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.len() == 1);
+    debug_assert!(args.len() == 2);
+
+    let expires_at = pop_arg!(args, u64);
+    let account = pop_arg!(args, SignerRef);
+    let key = resource_key(context, &ty_args[0], account)?;
+
+    context
+        .extensions_mut()
+        .get_mut::<NativeExpiryContext>()
+        .changes
+        .insert(key, Some(expires_at));
+
+    Ok(NativeResult::ok(gas_params.base, smallvec![]))
+}
+
+pub fn make_native_set_expiry(gas_params: SetExpiryGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_set_expiry(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * native fun clear_expiry
+ *
+ *   gas cost: base_cost
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct ClearExpiryGasParameters {
+    pub base: InternalGas,
+}
+
+fn native_clear_expiry(
+    gas_params: &ClearExpiryGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.len() == 1);
+    debug_assert!(args.len() == 1);
+
+    let account = pop_arg!(args, SignerRef);
+    let key = resource_key(context, &ty_args[0], account)?;
+
+    context
+        .extensions_mut()
+        .get_mut::<NativeExpiryContext>()
+        .changes
+        .insert(key, None);
+
+    Ok(NativeResult::ok(gas_params.base, smallvec![]))
+}
+
+pub fn make_native_clear_expiry(gas_params: ClearExpiryGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_clear_expiry(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * module
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct GasParameters {
+    pub set_expiry: SetExpiryGasParameters,
+    pub clear_expiry: ClearExpiryGasParameters,
+}
+
+pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [
+        ("set_expiry", make_native_set_expiry(gas_params.set_expiry)),
+        (
+            "clear_expiry",
+            make_native_clear_expiry(gas_params.clear_expiry),
+        ),
+    ];
+
+    make_module_natives(natives)
+}
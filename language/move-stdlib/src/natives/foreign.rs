@@ -0,0 +1,153 @@
+// Copyright (c) Eiger, Equilibrium Group
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::natives::helpers::make_module_natives;
+use crate::natives::host_gas::charge_host_weight;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{collections::VecDeque, sync::Arc};
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    gas_algebra::{InternalGas, InternalGasPerByte, NumBytes},
+    vm_status::StatusCode,
+};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::Value,
+};
+use smallvec::smallvec;
+
+/// Response of the host function invoked by a foreign call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignCallResponse {
+    /// Internal gas used by the host function - must not exceed the gas limit of the call.
+    pub gas_used: u64,
+    /// BCS-encoded response, or the abort code if the host function failed.
+    pub result: Result<Vec<u8>, u64>,
+}
+
+/// Host-side functions which the Move code can invoke with the `std::foreign::call`.
+pub trait ForeignCallHandler {
+    /// Invokes the host function registered under the `target` with the BCS-encoded `payload`.
+    ///
+    /// The host function may use at most `gas_limit` internal gas units.
+    fn foreign_call(
+        &self,
+        target: u64,
+        payload: &[u8],
+        gas_limit: u64,
+    ) -> PartialVMResult<ForeignCallResponse>;
+}
+
+/// Native context extension which gives the foreign call native access to the host functions.
+///
+/// The host has to register it for every session - without a handler, all foreign calls fail.
+#[derive(Tid)]
+pub struct NativeForeignCallContext<'a> {
+    handler: Option<&'a dyn ForeignCallHandler>,
+}
+
+impl<'a> NativeForeignCallContext<'a> {
+    pub fn new(handler: &'a dyn ForeignCallHandler) -> Self {
+        Self {
+            handler: Some(handler),
+        }
+    }
+
+    /// Context for the environments without any host functions, e.g. the unit tests.
+    pub fn unavailable() -> Self {
+        Self { handler: None }
+    }
+}
+
+/***************************************************************************************************
+ * native fun call
+ *
+ *   gas cost: base_cost +
+ *             (size_of(payload) + size_of(response)) * per_byte +
+ *             gas used by the host function +
+ *             weight reported by the host, e.g. for the call dispatch
+ *
+ *   The gas limit is reserved upfront, so the host function is never called with more gas than
+ *   the transaction has left.
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct CallGasParameters {
+    pub base: InternalGas,
+    pub per_byte: InternalGasPerByte,
+}
+
+fn native_call(
+    gas_params: &CallGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 3);
+
+    let gas_limit = pop_arg!(args, u64);
+    let payload = pop_arg!(args, Vec<u8>);
+    let target = pop_arg!(args, u64);
+
+    // Charge upfront, so the host function is never called by a call which runs out of gas.
+    context.charge(gas_params.base + gas_params.per_byte * NumBytes::new(payload.len() as u64))?;
+
+    let gas_left = context.gas_balance().checked_sub(context.gas_used());
+    if gas_left.map_or(true, |gas_left| u64::from(gas_left) < gas_limit) {
+        return Err(PartialVMError::new(StatusCode::OUT_OF_GAS)
+            .with_message("Foreign call gas limit exceeds the remaining gas".into()));
+    }
+
+    let handler = context
+        .extensions()
+        .get::<NativeForeignCallContext>()
+        .handler
+        .ok_or_else(|| {
+            PartialVMError::new(StatusCode::VM_EXTENSION_ERROR)
+                .with_message("Foreign calls are not available".into())
+        })?;
+
+    let response = handler.foreign_call(target, &payload, gas_limit)?;
+    charge_host_weight(context)?;
+    if response.gas_used > gas_limit {
+        return Err(PartialVMError::new(StatusCode::VM_EXTENSION_ERROR)
+            .with_message("Foreign call used more gas than its limit".into()));
+    }
+    context.charge(InternalGas::new(response.gas_used))?;
+
+    match response.result {
+        Ok(bytes) => {
+            context.charge(gas_params.per_byte * NumBytes::new(bytes.len() as u64))?;
+            Ok(NativeResult::ok(
+                InternalGas::zero(),
+                smallvec![Value::vector_u8(bytes)],
+            ))
+        }
+        Err(abort_code) => Ok(NativeResult::err(InternalGas::zero(), abort_code)),
+    }
+}
+
+pub fn make_native_call(gas_params: CallGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_call(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * module
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct GasParameters {
+    pub call: CallGasParameters,
+}
+
+pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [("call", make_native_call(gas_params.call))];
+
+    make_module_natives(natives)
+}
@@ -6,6 +6,7 @@ use crate::natives::helpers::make_module_natives;
 use alloc::string::String;
 use alloc::vec::Vec;
 use alloc::{collections::VecDeque, sync::Arc};
+use better_any::{Tid, TidAble};
 use move_binary_format::errors::PartialVMResult;
 use move_core_types::gas_algebra::{InternalGas, InternalGasPerByte, NumBytes};
 use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
@@ -16,6 +17,47 @@ use sha2::{Digest, Sha256};
 use sha3::Sha3_256;
 use smallvec::smallvec;
 
+/// Host-side hashing used by the `std::hash` module, e.g. the runtime interface of the host chain.
+pub trait HashHandler {
+    /// SHA2-256 digest of the data.
+    fn sha2_256(&self, data: &[u8]) -> [u8; 32];
+
+    /// SHA3-256 digest of the data.
+    fn sha3_256(&self, data: &[u8]) -> [u8; 32];
+}
+
+/// Native context extension which lets the host compute the `std::hash` digests.
+///
+/// Without the extension, the digests are computed by the natives themselves - the results are
+/// the same either way.
+#[derive(Tid)]
+pub struct NativeHashContext<'a> {
+    handler: &'a dyn HashHandler,
+}
+
+impl<'a> NativeHashContext<'a> {
+    pub fn new(handler: &'a dyn HashHandler) -> Self {
+        Self { handler }
+    }
+}
+
+fn hash_handler<'a>(context: &'a NativeContext) -> Option<&'a dyn HashHandler> {
+    context
+        .extensions()
+        .try_get::<NativeHashContext>()
+        .map(|ctx| ctx.handler)
+}
+
+/// SHA2-256 digest computed without the host.
+pub fn sha2_256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// SHA3-256 digest computed without the host.
+pub fn sha3_256(data: &[u8]) -> [u8; 32] {
+    Sha3_256::digest(data).into()
+}
+
 /***************************************************************************************************
  * native fun sha2_256
  *
@@ -32,7 +74,7 @@ pub struct Sha2_256GasParameters {
 #[inline]
 fn native_sha2_256(
     gas_params: &Sha2_256GasParameters,
-    _context: &mut NativeContext,
+    context: &mut NativeContext,
     _ty_args: Vec<Type>,
     mut arguments: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
@@ -48,7 +90,11 @@ fn native_sha2_256(
                 gas_params.legacy_min_input_len,
             );
 
-    let hash_vec = Sha256::digest(hash_arg.as_slice()).to_vec();
+    let hash_vec = match hash_handler(context) {
+        Some(handler) => handler.sha2_256(&hash_arg),
+        None => sha2_256(&hash_arg),
+    }
+    .to_vec();
     Ok(NativeResult::ok(
         cost,
         smallvec![Value::vector_u8(hash_vec)],
@@ -79,7 +125,7 @@ pub struct Sha3_256GasParameters {
 #[inline]
 fn native_sha3_256(
     gas_params: &Sha3_256GasParameters,
-    _context: &mut NativeContext,
+    context: &mut NativeContext,
     _ty_args: Vec<Type>,
     mut arguments: VecDeque<Value>,
 ) -> PartialVMResult<NativeResult> {
@@ -95,7 +141,11 @@ fn native_sha3_256(
                 gas_params.legacy_min_input_len,
             );
 
-    let hash_vec = Sha3_256::digest(hash_arg.as_slice()).to_vec();
+    let hash_vec = match hash_handler(context) {
+        Some(handler) => handler.sha3_256(&hash_arg),
+        None => sha3_256(&hash_arg),
+    }
+    .to_vec();
     Ok(NativeResult::ok(
         cost,
         smallvec![Value::vector_u8(hash_vec)],
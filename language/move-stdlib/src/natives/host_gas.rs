@@ -0,0 +1,68 @@
+// Copyright (c) Eiger, Equilibrium Group
+// SPDX-License-Identifier: Apache-2.0
+
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::gas_algebra::InternalGas;
+use move_vm_runtime::native_functions::NativeContext;
+
+/// Host-side report of the weight consumed by the host calls, e.g. the storage reads and writes
+/// of a balance transfer.
+pub trait HostWeightReporter {
+    /// Weight consumed by the host since the last report.
+    ///
+    /// The reported weight is charged only once, so the host has to reset it on every report.
+    fn take_consumed_weight(&self) -> u64;
+}
+
+/// Native context extension which lets the natives calling into the host charge the weight the
+/// host consumed.
+///
+/// Without a reporter, the host work isn't charged beyond the static native costs.
+#[derive(Tid)]
+pub struct NativeHostGasContext<'a> {
+    reporter: Option<&'a dyn HostWeightReporter>,
+    weight_per_gas: u64,
+}
+
+impl<'a> NativeHostGasContext<'a> {
+    /// The reported weight is converted to the internal gas units with `weight_per_gas` weight
+    /// units per one gas unit, rounded up.
+    pub fn new(reporter: &'a dyn HostWeightReporter, weight_per_gas: u64) -> Self {
+        Self {
+            reporter: Some(reporter),
+            weight_per_gas: weight_per_gas.max(1),
+        }
+    }
+
+    /// Context for the environments without any host, e.g. the unit tests.
+    pub fn unavailable() -> Self {
+        Self {
+            reporter: None,
+            weight_per_gas: 1,
+        }
+    }
+
+    /// Internal gas for the weight reported since the last call.
+    fn take_gas(&self) -> InternalGas {
+        let Some(reporter) = self.reporter else {
+            return InternalGas::zero();
+        };
+
+        let weight = reporter.take_consumed_weight();
+        let gas = weight / self.weight_per_gas + u64::from(weight % self.weight_per_gas != 0);
+        InternalGas::new(gas)
+    }
+}
+
+/// Charge the weight the host reported since the last call to the caller.
+///
+/// The natives call it right after every host call. Missing extension is treated as no reporter,
+/// so the natives keep working in the sessions created without it.
+pub(crate) fn charge_host_weight(context: &mut NativeContext) -> PartialVMResult<()> {
+    let gas = context
+        .extensions()
+        .try_get::<NativeHostGasContext>()
+        .map_or(InternalGas::zero(), NativeHostGasContext::take_gas);
+    context.charge(gas)
+}
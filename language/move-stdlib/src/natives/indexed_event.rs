@@ -0,0 +1,119 @@
+// Copyright (c) Eiger, Equilibrium Group
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::natives::helpers::make_module_natives;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{collections::VecDeque, sync::Arc};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    gas_algebra::{InternalGas, InternalGasPerAbstractMemoryUnit, InternalGasPerByte, NumBytes},
+    value::{MoveTypeLayout, MoveValue},
+    vm_status::StatusCode,
+};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type,
+    natives::function::NativeResult,
+    pop_arg,
+    values::{Struct, Value},
+    views::ValueView,
+};
+use smallvec::smallvec;
+
+/// Maximum number of indexed fields - the first topic is always taken by the event type.
+pub const MAX_INDEXED_FIELDS: u64 = 3;
+
+/// Abort code for the event type which is not a struct or has fewer fields than indexed.
+pub const EINVALID_INDEXED_FIELDS: u64 = 1;
+
+/***************************************************************************************************
+ * native fun emit
+ *
+ *   gas cost: base_cost +
+ *             size_of(indexed_fields) * per_byte_indexed +    | serialize the indexed fields
+ *             max(size_of(msg), 1) * unit_cost                | write the event
+ *
+ *   The first `indexed_fields` fields of the event struct are serialized separately and passed
+ *   in the event GUID as a BCS-encoded `vector<vector<u8>>`, so the host can derive the topics.
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct EmitGasParameters {
+    pub base: InternalGas,
+    pub per_byte_indexed: InternalGasPerByte,
+    pub unit_cost: InternalGasPerAbstractMemoryUnit,
+}
+
+#[inline]
+fn native_emit(
+    gas_params: &EmitGasParameters,
+    context: &mut NativeContext,
+    mut ty_args: Vec<Type>,
+    mut arguments: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.len() == 1);
+    debug_assert!(arguments.len() == 2);
+
+    let indexed_fields = pop_arg!(arguments, u64);
+    let msg = arguments.pop_back().unwrap();
+    let ty = ty_args.pop().unwrap();
+
+    let mut cost = gas_params.base
+        + gas_params.unit_cost * core::cmp::max(msg.legacy_abstract_memory_size(), 1.into());
+
+    let field_layouts = match context.type_to_type_layout(&ty)? {
+        Some(MoveTypeLayout::Struct(layout)) => layout.fields().to_vec(),
+        _ => return Ok(NativeResult::err(cost, EINVALID_INDEXED_FIELDS)),
+    };
+    if indexed_fields > MAX_INDEXED_FIELDS || indexed_fields as usize > field_layouts.len() {
+        return Ok(NativeResult::err(cost, EINVALID_INDEXED_FIELDS));
+    }
+
+    let fields: Vec<Value> = msg.value_as::<Struct>()?.unpack()?.collect();
+    let mut indexed = Vec::with_capacity(indexed_fields as usize);
+    for (field, layout) in fields
+        .iter()
+        .zip(&field_layouts)
+        .take(indexed_fields as usize)
+    {
+        let bytes = field
+            .simple_serialize(layout)
+            .ok_or_else(|| PartialVMError::new(StatusCode::VALUE_SERIALIZATION_ERROR))?;
+        cost += gas_params.per_byte_indexed * NumBytes::new(bytes.len() as u64);
+        indexed.push(MoveValue::vector_u8(bytes));
+    }
+
+    let guid = MoveValue::Vector(indexed)
+        .simple_serialize()
+        .ok_or_else(|| PartialVMError::new(StatusCode::VALUE_SERIALIZATION_ERROR))?;
+    let msg = Value::struct_(Struct::pack(fields));
+
+    if !context.save_event(guid, 0, ty, msg)? {
+        return Ok(NativeResult::err(cost, 0));
+    }
+
+    Ok(NativeResult::ok(cost, smallvec![]))
+}
+
+pub fn make_native_emit(gas_params: EmitGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_emit(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * module
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct GasParameters {
+    pub emit: EmitGasParameters,
+}
+
+pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [("emit", make_native_emit(gas_params.emit))];
+
+    make_module_natives(natives)
+}
@@ -2,22 +2,31 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod account;
 pub mod balance;
 pub mod bcs;
+pub mod bigint;
 pub mod debug;
 pub mod event;
+pub mod expiry;
+pub mod foreign;
 pub mod hash;
+pub mod host_gas;
+pub mod indexed_event;
+pub mod random;
 pub mod signer;
 pub mod string;
+pub mod timestamp;
 pub mod type_name;
 #[cfg(feature = "testing")]
 pub mod unit_test;
 pub mod vector;
+pub mod xcm;
 
 mod helpers;
 
 use alloc::string::ToString;
-use move_core_types::account_address::AccountAddress;
+use move_core_types::{account_address::AccountAddress, gas_algebra::InternalGas};
 use move_vm_runtime::native_functions::{make_table_from_iter, NativeFunctionTable};
 
 #[derive(Debug, Clone)]
@@ -29,6 +38,14 @@ pub struct GasParameters {
     pub type_name: type_name::GasParameters,
     pub vector: vector::GasParameters,
     pub balance: balance::GasParameters,
+    pub indexed_event: indexed_event::GasParameters,
+    pub expiry: expiry::GasParameters,
+    pub foreign: foreign::GasParameters,
+    pub xcm: xcm::GasParameters,
+    pub bigint: bigint::GasParameters,
+    pub account: account::GasParameters,
+    pub random: random::GasParameters,
+    pub timestamp: timestamp::GasParameters,
 
     #[cfg(feature = "testing")]
     pub unit_test: unit_test::GasParameters,
@@ -95,9 +112,94 @@ impl GasParameters {
                 swap: vector::SwapGasParameters { base: 0.into() },
             },
             balance: balance::GasParameters {
-                transfer: balance::TransferGasParameters { base: 0.into() },
-                cheque_amount: balance::ChequeAmountGasParameters { base: 0.into() },
-                total_amount: balance::TotalAmountGasParameters { base: 0.into() },
+                transfer: balance::TransferGasParameters {
+                    base: 0.into(),
+                    per_account: 0.into(),
+                },
+                cheque_amount: balance::ChequeAmountGasParameters {
+                    base: 0.into(),
+                    per_account: 0.into(),
+                },
+                total_amount: balance::TotalAmountGasParameters {
+                    base: 0.into(),
+                    per_account: 0.into(),
+                },
+                total_issuance: balance::TotalIssuanceGasParameters { base: 0.into() },
+                account_exists: balance::AccountExistsGasParameters {
+                    base: 0.into(),
+                    per_account: 0.into(),
+                },
+                minimum_balance: balance::MinimumBalanceGasParameters { base: 0.into() },
+            },
+            indexed_event: indexed_event::GasParameters {
+                emit: indexed_event::EmitGasParameters {
+                    base: 0.into(),
+                    per_byte_indexed: 0.into(),
+                    unit_cost: 0.into(),
+                },
+            },
+            expiry: expiry::GasParameters {
+                set_expiry: expiry::SetExpiryGasParameters { base: 0.into() },
+                clear_expiry: expiry::ClearExpiryGasParameters { base: 0.into() },
+            },
+            foreign: foreign::GasParameters {
+                call: foreign::CallGasParameters {
+                    base: 0.into(),
+                    per_byte: 0.into(),
+                },
+            },
+            xcm: xcm::GasParameters {
+                send_encoded: xcm::SendEncodedGasParameters {
+                    base: 0.into(),
+                    per_byte: 0.into(),
+                },
+            },
+            bigint: bigint::GasParameters {
+                add: bigint::LinearGasParameters {
+                    base: 0.into(),
+                    per_byte: 0.into(),
+                },
+                sub: bigint::LinearGasParameters {
+                    base: 0.into(),
+                    per_byte: 0.into(),
+                },
+                mul: bigint::QuadraticGasParameters {
+                    base: 0.into(),
+                    per_byte_squared: 0.into(),
+                },
+                div: bigint::QuadraticGasParameters {
+                    base: 0.into(),
+                    per_byte_squared: 0.into(),
+                },
+                modulo: bigint::QuadraticGasParameters {
+                    base: 0.into(),
+                    per_byte_squared: 0.into(),
+                },
+                pow: bigint::QuadraticGasParameters {
+                    base: 0.into(),
+                    per_byte_squared: 0.into(),
+                },
+                compare: bigint::LinearGasParameters {
+                    base: 0.into(),
+                    per_byte: 0.into(),
+                },
+                normalize: bigint::LinearGasParameters {
+                    base: 0.into(),
+                    per_byte: 0.into(),
+                },
+            },
+            account: account::GasParameters {
+                exists_at: account::ExistsAtGasParameters { base: 0.into() },
+                create_account: account::CreateAccountGasParameters { base: 0.into() },
+            },
+            random: random::GasParameters {
+                seed: random::SeedGasParameters {
+                    base: 0.into(),
+                    per_byte: 0.into(),
+                },
+            },
+            timestamp: timestamp::GasParameters {
+                now_milliseconds: timestamp::NowMillisecondsGasParameters { base: 0.into() },
             },
             #[cfg(feature = "testing")]
             unit_test: unit_test::GasParameters {
@@ -108,6 +210,60 @@ impl GasParameters {
             },
         }
     }
+
+    /// Declared base cost of the native, charged on every call regardless of the arguments.
+    ///
+    /// Natives which are charged only by the size of their arguments have zero base cost. Returns
+    /// `None` for the functions which aren't natives of [`all_natives`].
+    pub fn base_cost(&self, module: &str, function: &str) -> Option<InternalGas> {
+        let cost = match (module, function) {
+            ("bcs", "to_bytes") => InternalGas::zero(),
+            ("hash", "sha2_256") => self.hash.sha2_256.base,
+            ("hash", "sha3_256") => self.hash.sha3_256.base,
+            ("signer", "borrow_address") => self.signer.borrow_address.base,
+            ("string", "internal_check_utf8") => self.string.check_utf8.base,
+            ("string", "internal_is_char_boundary") => self.string.is_char_boundary.base,
+            ("string", "internal_sub_string") => self.string.sub_string.base,
+            ("string", "internal_index_of") => self.string.index_of.base,
+            ("type_name", "get") => self.type_name.get.base,
+            ("vector", "empty") => self.vector.empty.base,
+            ("vector", "length") => self.vector.length.base,
+            ("vector", "push_back") => self.vector.push_back.base,
+            ("vector", "borrow" | "borrow_mut") => self.vector.borrow.base,
+            ("vector", "pop_back") => self.vector.pop_back.base,
+            ("vector", "destroy_empty") => self.vector.destroy_empty.base,
+            ("vector", "swap") => self.vector.swap.base,
+            ("balance", "transfer") => self.balance.transfer.base,
+            ("balance", "cheque_amount") => self.balance.cheque_amount.base,
+            ("balance", "total_amount") => self.balance.total_amount.base,
+            ("balance", "total_issuance") => self.balance.total_issuance.base,
+            ("balance", "account_exists") => self.balance.account_exists.base,
+            ("balance", "minimum_balance") => self.balance.minimum_balance.base,
+            ("indexed_event", "emit") => self.indexed_event.emit.base,
+            ("expiry", "set_expiry") => self.expiry.set_expiry.base,
+            ("expiry", "clear_expiry") => self.expiry.clear_expiry.base,
+            ("foreign", "call") => self.foreign.call.base,
+            ("xcm", "send_encoded") => self.xcm.send_encoded.base,
+            ("bigint", "add_raw") => self.bigint.add.base,
+            ("bigint", "sub_raw") => self.bigint.sub.base,
+            ("bigint", "mul_raw") => self.bigint.mul.base,
+            ("bigint", "div_raw") => self.bigint.div.base,
+            ("bigint", "mod_raw") => self.bigint.modulo.base,
+            ("bigint", "pow_raw") => self.bigint.pow.base,
+            ("bigint", "compare_raw") => self.bigint.compare.base,
+            ("bigint", "normalize") => self.bigint.normalize.base,
+            ("account", "exists_at") => self.account.exists_at.base,
+            ("account", "create_account") => self.account.create_account.base,
+            ("random", "seed") => self.random.seed.base,
+            ("timestamp", "now_milliseconds") => self.timestamp.now_milliseconds.base,
+            #[cfg(feature = "testing")]
+            ("unit_test", "create_signers_for_testing") => {
+                self.unit_test.create_signers_for_testing.base_cost
+            }
+            _ => return None,
+        };
+        Some(cost)
+    }
 }
 
 pub fn all_natives(
@@ -131,6 +287,17 @@ pub fn all_natives(
     add_natives!("type_name", type_name::make_all(gas_params.type_name));
     add_natives!("vector", vector::make_all(gas_params.vector));
     add_natives!("balance", balance::make_all(gas_params.balance));
+    add_natives!(
+        "indexed_event",
+        indexed_event::make_all(gas_params.indexed_event)
+    );
+    add_natives!("expiry", expiry::make_all(gas_params.expiry));
+    add_natives!("foreign", foreign::make_all(gas_params.foreign));
+    add_natives!("xcm", xcm::make_all(gas_params.xcm));
+    add_natives!("bigint", bigint::make_all(gas_params.bigint));
+    add_natives!("account", account::make_all(gas_params.account));
+    add_natives!("random", random::make_all(gas_params.random));
+    add_natives!("timestamp", timestamp::make_all(gas_params.timestamp));
     #[cfg(feature = "testing")]
     {
         add_natives!("unit_test", unit_test::make_all(gas_params.unit_test));
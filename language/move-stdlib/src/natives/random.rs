@@ -0,0 +1,113 @@
+// Copyright (c) Eiger, Equilibrium Group
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::natives::helpers::make_module_natives;
+use crate::natives::host_gas::charge_host_weight;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{collections::VecDeque, sync::Arc};
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    gas_algebra::{InternalGas, InternalGasPerByte, NumBytes},
+    vm_status::StatusCode,
+};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::Value,
+};
+
+/// Host-side randomness used by the `std::random` module.
+pub trait RandomnessHandler {
+    /// Random seed for the `subject`, the same on every node executing the transaction.
+    fn random_seed(&self, subject: &[u8]) -> PartialVMResult<[u8; 32]>;
+}
+
+/// Native context extension which gives the `std::random` module access to the host randomness.
+///
+/// The host has to register it for every session - without a handler, the randomness natives fail.
+#[derive(Tid)]
+pub struct NativeRandomnessContext<'a> {
+    handler: Option<&'a dyn RandomnessHandler>,
+}
+
+impl<'a> NativeRandomnessContext<'a> {
+    pub fn new(handler: &'a dyn RandomnessHandler) -> Self {
+        Self {
+            handler: Some(handler),
+        }
+    }
+
+    /// Context for the environments without any host randomness, e.g. the unit tests.
+    pub fn unavailable() -> Self {
+        Self { handler: None }
+    }
+}
+
+fn randomness_handler<'a>(
+    context: &'a NativeContext,
+) -> PartialVMResult<&'a dyn RandomnessHandler> {
+    context
+        .extensions()
+        .get::<NativeRandomnessContext>()
+        .handler
+        .ok_or_else(|| {
+            PartialVMError::new(StatusCode::VM_EXTENSION_ERROR)
+                .with_message("Host randomness isn't available".into())
+        })
+}
+
+/***************************************************************************************************
+ * native fun seed
+ *
+ *   gas cost: base_cost + size_of(subject) * per_byte
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct SeedGasParameters {
+    pub base: InternalGas,
+    pub per_byte: InternalGasPerByte,
+}
+
+fn native_seed(
+    gas_params: &SeedGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 1);
+
+    let subject = pop_arg!(args, Vec<u8>);
+
+    context.charge(gas_params.base + gas_params.per_byte * NumBytes::new(subject.len() as u64))?;
+    let seed = randomness_handler(context)?.random_seed(&subject)?;
+    charge_host_weight(context)?;
+
+    NativeResult::map_partial_vm_result_one(
+        InternalGas::zero(),
+        Ok(Value::vector_u8(seed.to_vec())),
+    )
+}
+
+pub fn make_native_seed(gas_params: SeedGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_seed(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * module
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct GasParameters {
+    pub seed: SeedGasParameters,
+}
+
+pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [("seed", make_native_seed(gas_params.seed))];
+
+    make_module_natives(natives)
+}
@@ -0,0 +1,105 @@
+// Copyright (c) Eiger, Equilibrium Group
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::natives::helpers::make_module_natives;
+use crate::natives::host_gas::charge_host_weight;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{collections::VecDeque, sync::Arc};
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{gas_algebra::InternalGas, vm_status::StatusCode};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, values::Value,
+};
+
+/// Host-side clock used by the `std::timestamp` module.
+pub trait TimeHandler {
+    /// Timestamp of the current block in milliseconds since the Unix epoch.
+    fn now_milliseconds(&self) -> PartialVMResult<u64>;
+}
+
+/// Native context extension which gives the `std::timestamp` module access to the host clock.
+///
+/// The host has to register it for every session - without a handler, the timestamp natives fail.
+#[derive(Tid)]
+pub struct NativeTimeContext<'a> {
+    handler: Option<&'a dyn TimeHandler>,
+}
+
+impl<'a> NativeTimeContext<'a> {
+    pub fn new(handler: &'a dyn TimeHandler) -> Self {
+        Self {
+            handler: Some(handler),
+        }
+    }
+
+    /// Context for the environments without any host clock, e.g. the unit tests.
+    pub fn unavailable() -> Self {
+        Self { handler: None }
+    }
+}
+
+fn time_handler<'a>(context: &'a NativeContext) -> PartialVMResult<&'a dyn TimeHandler> {
+    context
+        .extensions()
+        .get::<NativeTimeContext>()
+        .handler
+        .ok_or_else(|| {
+            PartialVMError::new(StatusCode::VM_EXTENSION_ERROR)
+                .with_message("Host clock isn't available".into())
+        })
+}
+
+/***************************************************************************************************
+ * native fun now_milliseconds
+ *
+ *   gas cost: base_cost
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct NowMillisecondsGasParameters {
+    pub base: InternalGas,
+}
+
+fn native_now_milliseconds(
+    gas_params: &NowMillisecondsGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    context.charge(gas_params.base)?;
+    let now = time_handler(context)?.now_milliseconds()?;
+    charge_host_weight(context)?;
+
+    NativeResult::map_partial_vm_result_one(InternalGas::zero(), Ok(Value::u64(now)))
+}
+
+pub fn make_native_now_milliseconds(gas_params: NowMillisecondsGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_now_milliseconds(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * module
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct GasParameters {
+    pub now_milliseconds: NowMillisecondsGasParameters,
+}
+
+pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [(
+        "now_milliseconds",
+        make_native_now_milliseconds(gas_params.now_milliseconds),
+    )];
+
+    make_module_natives(natives)
+}
@@ -0,0 +1,117 @@
+// Copyright (c) Eiger, Equilibrium Group
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::natives::helpers::make_module_natives;
+use crate::natives::host_gas::charge_host_weight;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{collections::VecDeque, sync::Arc};
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_algebra::{InternalGas, InternalGasPerByte, NumBytes},
+    vm_status::StatusCode,
+};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::SignerRef,
+    values::Value,
+};
+
+/// Host-side sender of the cross-chain messages built by the `std::xcm` module.
+pub trait XcmSender {
+    /// Sends the BCS-encoded `std::xcm::Message` with the `origin` account as the origin.
+    ///
+    /// Returns `false` if the message can't be sent.
+    fn send_xcm(&self, origin: AccountAddress, message: &[u8]) -> PartialVMResult<bool>;
+}
+
+/// Native context extension which gives the `std::xcm` module access to the host message sender.
+///
+/// The host has to register it for every session - without a sender, sending the messages fails.
+#[derive(Tid)]
+pub struct NativeXcmContext<'a> {
+    sender: Option<&'a dyn XcmSender>,
+}
+
+impl<'a> NativeXcmContext<'a> {
+    pub fn new(sender: &'a dyn XcmSender) -> Self {
+        Self {
+            sender: Some(sender),
+        }
+    }
+
+    /// Context for the environments without any message sender, e.g. the unit tests.
+    pub fn unavailable() -> Self {
+        Self { sender: None }
+    }
+}
+
+/***************************************************************************************************
+ * native fun send_encoded
+ *
+ *   gas cost: base_cost + size_of(message) * per_byte
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct SendEncodedGasParameters {
+    pub base: InternalGas,
+    pub per_byte: InternalGasPerByte,
+}
+
+fn native_send_encoded(
+    gas_params: &SendEncodedGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 2);
+
+    let message = pop_arg!(args, Vec<u8>);
+    let origin = pop_arg!(args, SignerRef);
+
+    // Charge upfront, so the message is never sent by a call which runs out of gas.
+    context.charge(gas_params.base + gas_params.per_byte * NumBytes::new(message.len() as u64))?;
+
+    let sender = context
+        .extensions()
+        .get::<NativeXcmContext>()
+        .sender
+        .ok_or_else(|| {
+            PartialVMError::new(StatusCode::VM_EXTENSION_ERROR)
+                .with_message("XCM messages can't be sent".into())
+        })?;
+
+    let origin = origin.address()?;
+    let ret = sender.send_xcm(origin, &message)?;
+    charge_host_weight(context)?;
+
+    NativeResult::map_partial_vm_result_one(InternalGas::zero(), Ok(Value::bool(ret)))
+}
+
+pub fn make_native_send_encoded(gas_params: SendEncodedGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_send_encoded(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * module
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct GasParameters {
+    pub send_encoded: SendEncodedGasParameters,
+}
+
+pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [(
+        "send_encoded",
+        make_native_send_encoded(gas_params.send_encoded),
+    )];
+
+    make_module_natives(natives)
+}
@@ -0,0 +1,95 @@
+#[test_only]
+module std::access_control_tests {
+    use std::access_control;
+    use std::option;
+    use std::signer;
+
+    struct Contract has drop {}
+
+    const MINTER: vector<u8> = b"MINTER";
+
+    #[test(contract = @0xC, alice = @0xA)]
+    fun two_step_ownership_transfer(contract: signer, alice: signer) {
+        let holder = signer::address_of(&contract);
+        access_control::initialize(&contract, Contract {});
+        assert!(access_control::owner<Contract>(holder) == holder, 0);
+
+        access_control::transfer_ownership<Contract>(&contract, holder, @0xA);
+        assert!(access_control::pending_owner<Contract>(holder) == option::some(@0xA), 1);
+        // The ownership stays until the transfer is accepted.
+        assert!(access_control::owner<Contract>(holder) == holder, 2);
+
+        access_control::accept_ownership<Contract>(&alice, holder);
+        assert!(access_control::owner<Contract>(holder) == @0xA, 3);
+        assert!(option::is_none(&access_control::pending_owner<Contract>(holder)), 4);
+        access_control::assert_owner<Contract>(holder, &alice);
+    }
+
+    #[test(contract = @0xC, alice = @0xA)]
+    #[expected_failure(abort_code = access_control::ENOT_PENDING_OWNER, location = std::access_control)]
+    fun cancelled_transfer_cannot_be_accepted(contract: signer, alice: signer) {
+        let holder = signer::address_of(&contract);
+        access_control::initialize(&contract, Contract {});
+        access_control::transfer_ownership<Contract>(&contract, holder, @0xA);
+        access_control::cancel_ownership_transfer<Contract>(&contract, holder);
+        access_control::accept_ownership<Contract>(&alice, holder);
+    }
+
+    #[test(contract = @0xC, alice = @0xA)]
+    #[expected_failure(abort_code = access_control::ENOT_OWNER, location = std::access_control)]
+    fun only_owner_transfers_ownership(contract: signer, alice: signer) {
+        let holder = signer::address_of(&contract);
+        access_control::initialize(&contract, Contract {});
+        access_control::transfer_ownership<Contract>(&alice, holder, @0xA);
+    }
+
+    #[test(contract = @0xC)]
+    #[expected_failure(abort_code = access_control::EALREADY_INITIALIZED, location = std::access_control)]
+    fun initialize_twice(contract: signer) {
+        access_control::initialize(&contract, Contract {});
+        access_control::initialize(&contract, Contract {});
+    }
+
+    #[test(contract = @0xC, alice = @0xA)]
+    fun grant_and_revoke_roles(contract: signer, alice: signer) {
+        let holder = signer::address_of(&contract);
+        access_control::initialize(&contract, Contract {});
+        assert!(!access_control::has_role<Contract>(holder, MINTER, @0xA), 0);
+
+        access_control::grant_role<Contract>(&contract, holder, MINTER, @0xA);
+        access_control::grant_role<Contract>(&contract, holder, MINTER, @0xA);
+        access_control::grant_role<Contract>(&contract, holder, MINTER, @0xB);
+        access_control::assert_role<Contract>(holder, MINTER, &alice);
+        assert!(access_control::members<Contract>(holder, MINTER) == vector[@0xA, @0xB], 1);
+
+        access_control::revoke_role<Contract>(&contract, holder, MINTER, @0xB);
+        assert!(!access_control::has_role<Contract>(holder, MINTER, @0xB), 2);
+
+        access_control::renounce_role<Contract>(&alice, holder, MINTER);
+        assert!(access_control::members<Contract>(holder, MINTER) == vector[], 3);
+    }
+
+    #[test(contract = @0xC, alice = @0xA)]
+    #[expected_failure(abort_code = access_control::ENOT_OWNER, location = std::access_control)]
+    fun only_owner_grants_roles(contract: signer, alice: signer) {
+        let holder = signer::address_of(&contract);
+        access_control::initialize(&contract, Contract {});
+        access_control::grant_role<Contract>(&alice, holder, MINTER, @0xA);
+    }
+
+    #[test(contract = @0xC, alice = @0xA)]
+    #[expected_failure(abort_code = access_control::EMISSING_ROLE, location = std::access_control)]
+    fun missing_role(contract: signer, alice: signer) {
+        let holder = signer::address_of(&contract);
+        access_control::initialize(&contract, Contract {});
+        access_control::assert_role<Contract>(holder, MINTER, &alice);
+    }
+
+    #[test(contract = @0xC)]
+    #[expected_failure(abort_code = access_control::EMISSING_ROLE, location = std::access_control)]
+    fun revoke_missing_role(contract: signer) {
+        let holder = signer::address_of(&contract);
+        access_control::initialize(&contract, Contract {});
+        access_control::revoke_role<Contract>(&contract, holder, MINTER, @0xA);
+    }
+}
@@ -0,0 +1,105 @@
+#[test_only]
+module std::bigint_tests {
+    use std::bigint;
+    use std::vector;
+
+    #[test]
+    fun bytes_roundtrip() {
+        let value = bigint::from_be_bytes(x"00000102");
+        assert!(bigint::to_be_bytes(&value) == x"0102", 0);
+        assert!(bigint::to_le_bytes(&value) == x"0201", 1);
+        assert!(value == bigint::from_u64(258), 2);
+        assert!(bigint::to_u256(&value) == 258, 3);
+
+        assert!(bigint::is_zero(&bigint::from_le_bytes(x"0000")), 4);
+        assert!(bigint::from_u256(0) == bigint::zero(), 5);
+    }
+
+    #[test]
+    fun arithmetic_beyond_u256() {
+        let max = bigint::from_u256(115792089237316195423570985008687907853269984665640564039457584007913129639935);
+        let one = bigint::from_u64(1);
+
+        // 2^256 = (2^256 - 1) + 1
+        let overflowed = bigint::add(&max, &one);
+        assert!(overflowed == bigint::pow(&bigint::from_u64(2), 256), 0);
+        assert!(bigint::sub(&overflowed, &one) == max, 1);
+
+        // (2^256 - 1)^2 / (2^256 - 1) = 2^256 - 1
+        let squared = bigint::mul(&max, &max);
+        assert!(bigint::div(&squared, &max) == max, 2);
+        assert!(bigint::is_zero(&bigint::mod(&squared, &max)), 3);
+        assert!(bigint::mod(&overflowed, &max) == one, 4);
+    }
+
+    #[test]
+    fun pow_edge_cases() {
+        let zero = bigint::zero();
+        let one = bigint::from_u64(1);
+        assert!(bigint::pow(&zero, 0) == one, 0);
+        assert!(bigint::pow(&zero, 18446744073709551615) == zero, 1);
+        assert!(bigint::pow(&one, 18446744073709551615) == one, 2);
+        assert!(bigint::pow(&bigint::from_u64(10), 3) == bigint::from_u64(1000), 3);
+    }
+
+    #[test]
+    fun compare_integers() {
+        let small = bigint::from_u64(255);
+        let large = bigint::from_u128(256);
+        assert!(bigint::compare(&small, &large) == bigint::less_than(), 0);
+        assert!(bigint::compare(&large, &small) == bigint::greater_than(), 1);
+        assert!(bigint::compare(&small, &small) == bigint::equal(), 2);
+        assert!(bigint::lt(&small, &large), 3);
+        assert!(bigint::gt(&large, &small), 4);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = bigint::EUNDERFLOW)]
+    fun sub_underflow() {
+        bigint::sub(&bigint::from_u64(1), &bigint::from_u64(2));
+    }
+
+    #[test]
+    #[expected_failure(abort_code = bigint::EDIVISION_BY_ZERO)]
+    fun div_by_zero() {
+        bigint::div(&bigint::from_u64(1), &bigint::zero());
+    }
+
+    #[test]
+    #[expected_failure(abort_code = bigint::EDIVISION_BY_ZERO)]
+    fun mod_by_zero() {
+        bigint::mod(&bigint::from_u64(1), &bigint::zero());
+    }
+
+    #[test]
+    #[expected_failure(abort_code = bigint::EOPERAND_TOO_LONG)]
+    fun pow_result_too_long() {
+        // 2^4096 has 4097 bits.
+        bigint::pow(&bigint::from_u64(2), 4096);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = bigint::EOPERAND_TOO_LONG)]
+    fun mul_result_too_long() {
+        let half = bigint::pow(&bigint::from_u64(2), 2048);
+        bigint::mul(&half, &half);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = bigint::EOPERAND_TOO_LONG)]
+    fun from_bytes_too_long() {
+        let bytes = vector::empty();
+        let i = 0;
+        while (i < 513) {
+            vector::push_back(&mut bytes, 1);
+            i = i + 1;
+        };
+        bigint::from_le_bytes(bytes);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = bigint::EOVERFLOW)]
+    fun to_u256_overflow() {
+        bigint::to_u256(&bigint::pow(&bigint::from_u64(2), 256));
+    }
+}
@@ -0,0 +1,20 @@
+#[test_only]
+module std::chain_config_tests {
+    use std::chain_config;
+
+    #[test(std = @std)]
+    fun read_configured_constants(std: signer) {
+        assert!(!chain_config::is_configured(), 0);
+        chain_config::set_for_testing(&std, 42, 12, 32);
+        assert!(chain_config::is_configured(), 1);
+        assert!(chain_config::chain_id() == 42, 2);
+        assert!(chain_config::token_decimals() == 12, 3);
+        assert!(chain_config::address_length() == 32, 4);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = chain_config::ENOT_CONFIGURED, location = std::chain_config)]
+    fun unconfigured_chain_aborts() {
+        chain_config::chain_id();
+    }
+}
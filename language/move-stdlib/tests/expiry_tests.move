@@ -0,0 +1,16 @@
+#[test_only]
+module std::expiry_tests {
+    use std::expiry;
+
+    struct Session has key {
+        id: u64,
+    }
+
+    #[test(account = @0xA)]
+    fun set_and_clear_expiry(account: signer) {
+        move_to(&account, Session { id: 1 });
+        expiry::set_expiry<Session>(&account, 100);
+        expiry::set_expiry<Session>(&account, 200);
+        expiry::clear_expiry<Session>(&account);
+    }
+}
@@ -0,0 +1,33 @@
+#[test_only]
+module std::indexed_event_tests {
+    use std::indexed_event;
+
+    struct Transfer has drop, store {
+        from: address,
+        to: address,
+        amount: u64,
+        memo: vector<u8>,
+    }
+
+    fun transfer(): Transfer {
+        Transfer { from: @0xA, to: @0xB, amount: 10, memo: b"memo" }
+    }
+
+    #[test]
+    fun emit_with_indexed_fields() {
+        indexed_event::emit(transfer(), 0);
+        indexed_event::emit(transfer(), 3);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = indexed_event::EINVALID_INDEXED_FIELDS, location = std::indexed_event)]
+    fun emit_with_too_many_indexed_fields() {
+        indexed_event::emit(transfer(), 4);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = indexed_event::EINVALID_INDEXED_FIELDS, location = std::indexed_event)]
+    fun emit_non_struct() {
+        indexed_event::emit(42u64, 0);
+    }
+}
@@ -12,5 +12,7 @@ mod invariant_violation_tests;
 mod leak_tests;
 mod loader_tests;
 mod mutated_accounts_tests;
+mod native_context_tests;
 mod nested_loop_tests;
+mod peephole_tests;
 mod return_value_tests;
@@ -0,0 +1,90 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_algebra::InternalGas,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::{serialize_values, MoveValue},
+};
+use move_vm_runtime::{
+    move_vm::MoveVM,
+    native_functions::{NativeContext, NativeFunction},
+};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::{
+    gas::UnmeteredGasMeter, loaded_data::runtime_types::Type, natives::function::NativeResult,
+    pop_arg, values::Value,
+};
+use std::{collections::VecDeque, sync::Arc};
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+
+fn native_exists_at(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    let address = pop_arg!(args, AccountAddress);
+    let exists = context.resource_exists(address, &ty_args[0])?;
+    NativeResult::map_partial_vm_result_one(InternalGas::zero(), Ok(Value::bool(exists)))
+}
+
+#[test]
+fn native_context_reads_session_storage() {
+    let code = format!(
+        r#"
+        module 0x{}::M {{
+            struct R has key {{}}
+
+            native fun exists_at<T: key>(addr: address): bool;
+
+            public fun publish_and_check(s: &signer, addr: address): bool {{
+                let before = exists_at<R>(addr);
+                move_to(s, R {{}});
+                !before && exists_at<R>(addr)
+            }}
+        }}
+        "#,
+        TEST_ADDR
+    );
+    let mut units = compile_units(&code).unwrap();
+    let module = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    module.serialize(&mut blob).unwrap();
+
+    let module_id = ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap());
+    let mut storage = InMemoryStorage::new();
+    storage.publish_or_overwrite_module(module_id.clone(), blob);
+
+    let native: NativeFunction = Arc::new(native_exists_at);
+    let natives = vec![(
+        TEST_ADDR,
+        Identifier::new("M").unwrap(),
+        Identifier::new("exists_at").unwrap(),
+        native,
+    )];
+    let vm = MoveVM::new(natives).unwrap();
+    let mut session = vm.new_session(&storage);
+
+    // The resource published by the session is visible to the native.
+    let args = serialize_values(&vec![
+        MoveValue::Signer(TEST_ADDR),
+        MoveValue::Address(TEST_ADDR),
+    ]);
+    let result = session
+        .execute_function_bypass_visibility(
+            &module_id,
+            &Identifier::new("publish_and_check").unwrap(),
+            vec![],
+            args,
+            &mut UnmeteredGasMeter,
+        )
+        .unwrap();
+
+    let (bytes, _) = &result.return_values[0];
+    assert_eq!(bytes, &MoveValue::Bool(true).simple_serialize().unwrap());
+}
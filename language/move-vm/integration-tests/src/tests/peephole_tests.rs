@@ -0,0 +1,101 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::{
+    errors::VMResult,
+    file_format::{
+        empty_script, Bytecode, CodeOffset, FunctionDefinitionIndex, Signature, SignatureIndex,
+        SignatureToken,
+    },
+};
+use move_core_types::{value::MoveValue, vm_status::StatusCode};
+use move_vm_runtime::{config::VMConfig, move_vm::MoveVM};
+use move_vm_test_utils::{gas_schedule::GasStatus, InMemoryStorage};
+use move_vm_types::gas::GasMeter;
+
+const INSTRUCTION_LIMIT: u64 = 1_000;
+
+// Aborts with the argument once the constant branches are taken.
+fn script() -> Vec<u8> {
+    use Bytecode::*;
+
+    let mut script = empty_script();
+    script.signatures.push(Signature(vec![SignatureToken::U64]));
+    script.parameters = SignatureIndex(1);
+    script.code.code = vec![
+        CopyLoc(0),
+        Pop,
+        LdFalse,
+        BrTrue(6),
+        LdTrue,
+        BrTrue(7),
+        Ret,
+        CopyLoc(0),
+        Abort,
+    ];
+
+    let mut blob = vec![];
+    script.serialize(&mut blob).unwrap();
+    blob
+}
+
+type Outcome = (
+    StatusCode,
+    Option<u64>,
+    Vec<(FunctionDefinitionIndex, CodeOffset)>,
+);
+
+// Run the script and return its outcome with the number of the executed instructions.
+fn run(peephole_optimization: bool, arg: u64) -> (Outcome, u64) {
+    let storage = InMemoryStorage::new();
+    let vm = MoveVM::new_with_config(
+        vec![],
+        VMConfig {
+            peephole_optimization,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let mut sess = vm.new_session(&storage);
+    let mut gas_status = GasStatus::new_instruction_counter(INSTRUCTION_LIMIT);
+
+    let result: VMResult<_> = sess.execute_script(
+        script(),
+        vec![],
+        vec![MoveValue::U64(arg).simple_serialize().unwrap()],
+        &mut gas_status,
+    );
+    let err = result.unwrap_err();
+    let outcome = (err.major_status(), err.sub_status(), err.offsets().clone());
+    let executed = INSTRUCTION_LIMIT - u64::from(gas_status.balance_internal());
+    (outcome, executed)
+}
+
+#[test]
+fn optimized_code_has_the_same_outcome() {
+    for arg in [0, 42, u64::MAX] {
+        let (outcome, executed) = run(false, arg);
+        let (optimized_outcome, optimized_executed) = run(true, arg);
+
+        // The abort is reported at its offset in the original code.
+        assert_eq!(
+            outcome,
+            (
+                StatusCode::ABORTED,
+                Some(arg),
+                vec![(FunctionDefinitionIndex(0), 8)]
+            )
+        );
+        assert_eq!(optimized_outcome, outcome);
+        // The constant branches and the popped copy are skipped.
+        assert_eq!(executed - optimized_executed, 5);
+    }
+}
+
+#[test]
+fn optimized_code_is_deterministic() {
+    let first = run(true, 7);
+    for _ in 0..3 {
+        assert_eq!(run(true, 7), first);
+    }
+}
@@ -1,10 +1,15 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use alloc::{format, sync::Arc};
+use core::sync::atomic::{AtomicBool, Ordering};
 use move_binary_format::file_format_common::VERSION_MAX;
 use move_bytecode_verifier::VerifierConfig;
+use sha3::{Digest, Sha3_256};
 
 pub const DEFAULT_MAX_VALUE_NEST_DEPTH: u64 = 128;
+pub const DEFAULT_LAYOUT_CACHE_CAPACITY: usize = 1024;
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1024;
 
 /// Dynamic config options for the Move VM.
 pub struct VMConfig {
@@ -15,6 +20,18 @@ pub struct VMConfig {
     pub paranoid_type_checks: bool,
     /// Maximum value nest depth for structs
     pub max_value_nest_depth: Option<u64>,
+    /// Maximum number of struct layouts cached by the loader, zero disables the cache.
+    pub layout_cache_capacity: usize,
+    /// Maximum number of frames on the call stack, i.e. the callers of the running function.
+    pub max_call_depth: usize,
+    /// Flag aborting the running executions - see [`InterruptHandle`].
+    pub interrupt: Option<InterruptHandle>,
+    /// Rewrite the redundant instruction pairs of the loaded code, e.g. the branches on constant
+    /// conditions or the copies popped right away.
+    ///
+    /// The results and the error locations stay the same, but fewer instructions are executed
+    /// and charged, so all nodes must use the same setting.
+    pub peephole_optimization: bool,
 }
 
 impl Default for VMConfig {
@@ -24,6 +41,53 @@ impl Default for VMConfig {
             max_binary_format_version: VERSION_MAX,
             paranoid_type_checks: false,
             max_value_nest_depth: Some(DEFAULT_MAX_VALUE_NEST_DEPTH),
+            layout_cache_capacity: DEFAULT_LAYOUT_CACHE_CAPACITY,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            interrupt: None,
+            peephole_optimization: false,
         }
     }
 }
+
+impl VMConfig {
+    /// SHA3-256 hash of the options which decide if a module passes the verification - the
+    /// verifier config and the maximum binary format version.
+    ///
+    /// Modules verified under the same hash don't need to be verified again. The hash covers the
+    /// debug representation of the verifier config, so new verifier options change it as well.
+    pub fn verification_hash(&self) -> [u8; 32] {
+        let options = format!("{:?};{}", self.verifier, self.max_binary_format_version);
+        Sha3_256::digest(options.as_bytes()).into()
+    }
+}
+
+/// Shared flag to abort the running executions from another thread.
+///
+/// The interpreter checks the flag whenever a function frame is entered and whenever a branch is
+/// taken, so even the executions within their gas budget stop shortly after the flag is raised.
+/// The interrupted execution fails with the `EXECUTION_INTERRUPTED` status code. The flag stays
+/// raised until reset, so the following executions fail as well.
+#[derive(Debug, Clone, Default)]
+pub struct InterruptHandle(Arc<AtomicBool>);
+
+impl InterruptHandle {
+    /// Create a new handle with the flag lowered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Raise the flag, aborting the running and the following executions.
+    pub fn interrupt(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Lower the flag, allowing the executions again.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+
+    /// Whether the flag is raised.
+    pub fn is_interrupted(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
@@ -5,7 +5,7 @@
 use crate::loader::Loader;
 
 use alloc::borrow::ToOwned;
-use alloc::collections::btree_map::BTreeMap;
+use alloc::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
 use alloc::vec::Vec;
 use move_binary_format::errors::*;
 use move_core_types::{
@@ -56,6 +56,8 @@ pub(crate) struct TransactionDataCache<'r, 'l, S> {
     loader: &'l Loader,
     account_map: BTreeMap<AccountAddress, AccountDataCache>,
     event_data: Vec<(Vec<u8>, u64, Type, MoveTypeLayout, Value)>,
+    // modules already charged for as dependencies within the session
+    charged_dependencies: BTreeSet<ModuleId>,
 }
 
 impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
@@ -67,6 +69,7 @@ impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
             loader,
             account_map: BTreeMap::new(),
             event_data: vec![],
+            charged_dependencies: BTreeSet::new(),
         }
     }
 
@@ -288,6 +291,20 @@ impl<'r, 'l, S: MoveResolver> DataStore for TransactionDataCache<'r, 'l, S> {
             .is_some())
     }
 
+    // Markers are keyed by the bytecode, so they are valid for the modules published within the
+    // transaction as well.
+    fn is_module_verified(&self, bytes: &[u8], config_hash: &[u8; 32]) -> bool {
+        self.remote.is_module_verified(bytes, config_hash)
+    }
+
+    fn set_module_verified(&self, bytes: &[u8], config_hash: &[u8; 32]) {
+        self.remote.set_module_verified(bytes, config_hash)
+    }
+
+    fn mark_dependency_charged(&mut self, module_id: &ModuleId) -> bool {
+        self.charged_dependencies.insert(module_id.clone())
+    }
+
     fn emit_event(
         &mut self,
         guid: Vec<u8>,
@@ -326,4 +343,22 @@ impl<'r, 'l, S: MoveResolver> DataStore for TransactionDataCache<'r, 'l, S> {
             .total_amount(account)
             .map_err(|_| PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))
     }
+
+    fn total_issuance(&self) -> PartialVMResult<u128> {
+        self.remote
+            .total_issuance()
+            .map_err(|_| PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))
+    }
+
+    fn account_exists(&self, account: AccountAddress) -> PartialVMResult<bool> {
+        self.remote
+            .account_exists(account)
+            .map_err(|_| PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))
+    }
+
+    fn minimum_balance(&self) -> PartialVMResult<u128> {
+        self.remote
+            .minimum_balance()
+            .map_err(|_| PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))
+    }
 }
@@ -6,6 +6,7 @@
 use crate::trace;
 
 use crate::{
+    config::InterruptHandle,
     loader::{Function, Loader, Resolver},
     native_functions::NativeContext,
 };
@@ -61,8 +62,11 @@ macro_rules! debug_writeln {
 
 macro_rules! set_err_info {
     ($frame:ident, $e:expr) => {{
-        $e.at_code_offset($frame.function.index(), $frame.pc)
-            .finish($frame.location())
+        $e.at_code_offset(
+            $frame.function.index(),
+            $frame.function.source_offset($frame.pc),
+        )
+        .finish($frame.location())
     }};
 }
 
@@ -77,6 +81,8 @@ pub(crate) struct Interpreter {
     call_stack: CallStack,
     /// Whether to perform a paranoid type safety checks at runtime.
     paranoid_type_checks: bool,
+    /// Flag aborting the execution, checked at the safepoints.
+    interrupt: Option<InterruptHandle>,
 }
 
 struct TypeWithLoader<'a, 'b> {
@@ -104,8 +110,9 @@ impl Interpreter {
     ) -> VMResult<Vec<Value>> {
         Interpreter {
             operand_stack: Stack::new(),
-            call_stack: CallStack::new(),
+            call_stack: CallStack::new(loader.vm_config().max_call_depth),
             paranoid_type_checks: loader.vm_config().paranoid_type_checks,
+            interrupt: loader.vm_config().interrupt.clone(),
         }
         .execute_main(
             loader, data_store, gas_meter, extensions, function, ty_args, args,
@@ -197,6 +204,7 @@ impl Interpreter {
                             data_store,
                             gas_meter,
                             extensions,
+                            &current_frame.function,
                             func,
                             vec![],
                         )?;
@@ -248,7 +256,13 @@ impl Interpreter {
 
                     if func.is_native() {
                         self.call_native(
-                            &resolver, data_store, gas_meter, extensions, func, ty_args,
+                            &resolver,
+                            data_store,
+                            gas_meter,
+                            extensions,
+                            &current_frame.function,
+                            func,
+                            ty_args,
                         )?;
                         current_frame.pc += 1; // advance past the Call instruction in the caller
                         continue;
@@ -341,6 +355,7 @@ impl Interpreter {
         data_store: &mut dyn DataStore,
         gas_meter: &mut impl GasMeter,
         extensions: &mut NativeContextExtensions,
+        caller: &Arc<Function>,
         function: Arc<Function>,
         ty_args: Vec<Type>,
     ) -> VMResult<()> {
@@ -350,6 +365,7 @@ impl Interpreter {
             data_store,
             gas_meter,
             extensions,
+            caller.clone(),
             function.clone(),
             ty_args,
         )
@@ -371,6 +387,7 @@ impl Interpreter {
         data_store: &mut dyn DataStore,
         gas_meter: &mut impl GasMeter,
         extensions: &mut NativeContextExtensions,
+        caller: Arc<Function>,
         function: Arc<Function>,
         ty_args: Vec<Type>,
     ) -> PartialVMResult<()> {
@@ -395,6 +412,7 @@ impl Interpreter {
             data_store,
             resolver,
             extensions,
+            caller,
             gas_meter.balance_internal(),
         );
         let native_function = function.get_native()?;
@@ -407,7 +425,17 @@ impl Interpreter {
             args.iter(),
         )?;
 
-        let result = native_function(&mut native_context, ty_args.clone(), args)?;
+        let result = match native_function(&mut native_context, ty_args.clone(), args) {
+            // The gas charged through the context exceeded the balance, let the gas meter
+            // report it the same way as the natives running out of gas on their own.
+            Err(err)
+                if err.major_status() == StatusCode::OUT_OF_GAS
+                    && native_context.is_out_of_gas() =>
+            {
+                NativeResult::out_of_gas(native_context.gas_used())
+            }
+            result => result?.add_cost(native_context.gas_used()),
+        };
 
         // Note(Gas): The order by which gas is charged / error gets returned MUST NOT be modified
         //            here or otherwise it becomes an incompatible change!!!
@@ -770,7 +798,7 @@ impl Interpreter {
         loader: &Loader,
     ) -> PartialVMResult<()> {
         debug_writeln!(buf, "Call Stack:")?;
-        for (i, frame) in self.call_stack.0.iter().enumerate() {
+        for (i, frame) in self.call_stack.frames.iter().enumerate() {
             self.debug_print_frame(buf, loader, i, frame)?;
         }
         debug_writeln!(buf, "Operand Stack:")?;
@@ -792,7 +820,7 @@ impl Interpreter {
     /// of an execution.
     fn internal_state_str(&self, current_frame: &Frame) -> String {
         let mut internal_state = "Call stack:\n".to_string();
-        for (i, frame) in self.call_stack.0.iter().enumerate() {
+        for (i, frame) in self.call_stack.frames.iter().enumerate() {
             internal_state.push_str(
                 format!(
                     " frame #{}: {} [pc = {}]\n",
@@ -806,7 +834,7 @@ impl Interpreter {
         internal_state.push_str(
             format!(
                 "*frame #{}: {} [pc = {}]:\n",
-                self.call_stack.0.len(),
+                self.call_stack.frames.len(),
                 current_frame.function.pretty_string(),
                 current_frame.pc,
             )
@@ -841,6 +869,16 @@ impl Interpreter {
         err.finish(self.call_stack.current_location())
     }
 
+    /// Fail the execution if the embedder raised the interrupt flag.
+    fn check_interrupt(&self) -> PartialVMResult<()> {
+        match &self.interrupt {
+            Some(interrupt) if interrupt.is_interrupted() => {
+                Err(PartialVMError::new(StatusCode::EXECUTION_INTERRUPTED))
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn get_internal_state(&self) -> ExecutionState {
         self.get_stack_frames(usize::MAX)
     }
@@ -860,7 +898,7 @@ impl Interpreter {
                 (
                     frame.function.module_id().cloned(),
                     frame.function.index(),
-                    frame.pc,
+                    frame.function.source_offset(frame.pc),
                 )
             })
             .collect();
@@ -870,7 +908,6 @@ impl Interpreter {
 
 // TODO Determine stack size limits based on gas limit
 const OPERAND_STACK_SIZE_LIMIT: usize = 1024;
-const CALL_STACK_SIZE_LIMIT: usize = 1024;
 
 /// The operand stack.
 struct Stack {
@@ -976,18 +1013,27 @@ impl Stack {
 
 /// A call stack.
 // #[derive(Debug)]
-struct CallStack(Vec<Frame>);
+struct CallStack {
+    frames: Vec<Frame>,
+    max_depth: usize,
+}
 
 impl CallStack {
-    /// Create a new empty call stack.
-    fn new() -> Self {
-        CallStack(vec![])
+    /// Create a new empty call stack holding at most `max_depth` frames.
+    ///
+    /// The frames are kept on the heap, so the limit behaves the same in the native and the WASM
+    /// builds.
+    fn new(max_depth: usize) -> Self {
+        CallStack {
+            frames: vec![],
+            max_depth,
+        }
     }
 
     /// Push a `Frame` on the call stack.
     fn push(&mut self, frame: Frame) -> core::result::Result<(), Frame> {
-        if self.0.len() < CALL_STACK_SIZE_LIMIT {
-            self.0.push(frame);
+        if self.frames.len() < self.max_depth {
+            self.frames.push(frame);
             Ok(())
         } else {
             Err(frame)
@@ -996,11 +1042,11 @@ impl CallStack {
 
     /// Pop a `Frame` off the call stack.
     fn pop(&mut self) -> Option<Frame> {
-        self.0.pop()
+        self.frames.pop()
     }
 
     fn current_location(&self) -> Location {
-        let location_opt = self.0.last().map(|frame| frame.location());
+        let location_opt = self.frames.last().map(|frame| frame.location());
         location_opt.unwrap_or(Location::Undefined)
     }
 }
@@ -1136,7 +1182,7 @@ impl Frame {
                 } else {
                     e
                 };
-                e.at_code_offset(self.function.index(), self.pc)
+                e.at_code_offset(self.function.index(), self.function.source_offset(self.pc))
                     .finish(self.location())
             })
     }
@@ -1761,6 +1807,9 @@ impl Frame {
 
         let code = self.function.code();
         loop {
+            // Safepoint - reached when the frame is entered or resumed and after taken branches.
+            interpreter.check_interrupt()?;
+
             for instruction in &code[self.pc as usize..] {
                 #[cfg(feature = "debugging")]
                 trace!(
@@ -1813,6 +1862,9 @@ impl Frame {
                     Bytecode::BrTrue(offset) => {
                         gas_meter.charge_simple_instr(S::BrTrue)?;
                         if interpreter.operand_stack.pop_as::<bool>()? {
+                            if *offset <= self.pc {
+                                gas_meter.charge_back_edge()?;
+                            }
                             self.pc = *offset;
                             break;
                         }
@@ -1820,12 +1872,19 @@ impl Frame {
                     Bytecode::BrFalse(offset) => {
                         gas_meter.charge_simple_instr(S::BrFalse)?;
                         if !interpreter.operand_stack.pop_as::<bool>()? {
+                            if *offset <= self.pc {
+                                gas_meter.charge_back_edge()?;
+                            }
                             self.pc = *offset;
                             break;
                         }
                     }
                     Bytecode::Branch(offset) => {
                         gas_meter.charge_simple_instr(S::Branch)?;
+                        // Branching backwards starts another loop iteration.
+                        if *offset <= self.pc {
+                            gas_meter.charge_back_edge()?;
+                        }
                         self.pc = *offset;
                         break;
                     }
@@ -2119,7 +2178,7 @@ impl Frame {
                             .with_message(format!(
                                 "{} at offset {}",
                                 self.function.pretty_string(),
-                                self.pc,
+                                self.function.source_offset(self.pc),
                             ));
                         return Err(error);
                     }
@@ -0,0 +1,104 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bounded cache of the struct layouts requested through the public layout APIs.
+//!
+//! Building the layout of a struct tag loads the type from the compiled modules and walks all its
+//! fields, which adds up for the adapters decoding the resources or validating the arguments over
+//! and over. The cache keeps the most recently used layouts for the lifetime of the loader and
+//! evicts the least recently used ones once it's full.
+
+use alloc::collections::BTreeMap;
+use move_core_types::{language_storage::StructTag, value::MoveTypeLayout};
+
+/// Statistics of the layout cache since the loader was created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LayoutCacheStats {
+    /// Lookups answered from the cache.
+    pub hits: u64,
+    /// Lookups which had to compute the layout.
+    pub misses: u64,
+    /// Layouts dropped to make room for the new ones.
+    pub evictions: u64,
+    /// Layouts currently kept.
+    pub entries: usize,
+    /// Maximum number of the kept layouts.
+    pub capacity: usize,
+}
+
+/// Cache key - the plain and the fully annotated layouts of a struct differ.
+type LayoutKey = (StructTag, bool);
+
+pub(crate) struct LayoutCache {
+    /// Layouts with the tick of their last use.
+    layouts: BTreeMap<LayoutKey, (MoveTypeLayout, u64)>,
+    /// Keys ordered by their last use, the least recently used first.
+    recency: BTreeMap<u64, LayoutKey>,
+    tick: u64,
+    stats: LayoutCacheStats,
+}
+
+impl LayoutCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            layouts: BTreeMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            stats: LayoutCacheStats {
+                capacity,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Returns the cached layout and marks it as the most recently used.
+    pub(crate) fn get(&mut self, tag: &StructTag, annotated: bool) -> Option<MoveTypeLayout> {
+        let key = (tag.clone(), annotated);
+        let Some((layout, last_used)) = self.layouts.get_mut(&key) else {
+            self.stats.misses += 1;
+            return None;
+        };
+
+        self.tick += 1;
+        self.recency.remove(last_used);
+        self.recency.insert(self.tick, key);
+        *last_used = self.tick;
+
+        self.stats.hits += 1;
+        Some(layout.clone())
+    }
+
+    /// Caches the layout, evicting the least recently used one if the cache is full.
+    pub(crate) fn insert(&mut self, tag: StructTag, annotated: bool, layout: MoveTypeLayout) {
+        if self.stats.capacity == 0 {
+            return;
+        }
+
+        let key = (tag, annotated);
+        if let Some((_, last_used)) = self.layouts.remove(&key) {
+            self.recency.remove(&last_used);
+        } else if self.layouts.len() >= self.stats.capacity {
+            if let Some((_, evicted)) = self.recency.pop_first() {
+                self.layouts.remove(&evicted);
+                self.stats.evictions += 1;
+            }
+        }
+
+        self.tick += 1;
+        self.recency.insert(self.tick, key.clone());
+        self.layouts.insert(key, (layout, self.tick));
+    }
+
+    /// Drops all layouts, e.g. after the modules were upgraded. The statistics are kept.
+    pub(crate) fn clear(&mut self) {
+        self.layouts.clear();
+        self.recency.clear();
+    }
+
+    pub(crate) fn stats(&self) -> LayoutCacheStats {
+        LayoutCacheStats {
+            entries: self.layouts.len(),
+            ..self.stats
+        }
+    }
+}
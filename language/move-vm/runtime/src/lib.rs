@@ -17,11 +17,13 @@ extern crate alloc;
 
 pub mod data_cache;
 mod interpreter;
+mod layout_cache;
 mod loader;
 pub mod logging;
 pub mod move_vm;
 pub mod native_extensions;
 pub mod native_functions;
+mod peephole;
 mod runtime;
 pub mod session;
 #[macro_use]
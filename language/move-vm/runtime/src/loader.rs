@@ -4,8 +4,10 @@
 
 use crate::{
     config::VMConfig,
+    layout_cache::{LayoutCache, LayoutCacheStats},
     logging::expect_no_verification_errors,
     native_functions::{NativeFunction, NativeFunctions, UnboxedNativeFunction},
+    peephole,
     session::LoadedFunctionInstantiation,
 };
 use alloc::borrow::ToOwned;
@@ -26,16 +28,17 @@ use move_binary_format::{
     binary_views::BinaryIndexedView,
     errors::{verification_error, Location, PartialVMError, PartialVMResult, VMResult},
     file_format::{
-        AbilitySet, Bytecode, CompiledModule, CompiledScript, Constant, ConstantPoolIndex,
-        FieldHandleIndex, FieldInstantiationIndex, FunctionDefinition, FunctionDefinitionIndex,
-        FunctionHandleIndex, FunctionInstantiationIndex, Signature, SignatureIndex, SignatureToken,
-        StructDefInstantiationIndex, StructDefinition, StructDefinitionIndex,
-        StructFieldInformation, TableIndex, TypeParameterIndex, Visibility,
+        AbilitySet, Bytecode, CodeOffset, CompiledModule, CompiledScript, Constant,
+        ConstantPoolIndex, FieldHandleIndex, FieldInstantiationIndex, FunctionDefinition,
+        FunctionDefinitionIndex, FunctionHandleIndex, FunctionInstantiationIndex, Signature,
+        SignatureIndex, SignatureToken, StructDefInstantiationIndex, StructDefinition,
+        StructDefinitionIndex, StructFieldInformation, TableIndex, TypeParameterIndex, Visibility,
     },
     IndexKind,
 };
 use move_bytecode_verifier::{self, cyclic_dependencies, dependencies};
 use move_core_types::{
+    gas_algebra::NumBytes,
     identifier::{IdentStr, Identifier},
     language_storage::{ModuleId, StructTag, TypeTag},
     metadata::Metadata,
@@ -44,6 +47,7 @@ use move_core_types::{
 };
 use move_vm_types::{
     data_store::DataStore,
+    gas::GasMeter,
     loaded_data::runtime_types::{CachedStructIndex, DepthFormula, StructType, Type},
 };
 use sha3::{Digest, Sha3_256};
@@ -51,6 +55,12 @@ use tracing::error;
 
 type ScriptHash = [u8; 32];
 
+fn script_hash(script_blob: &[u8]) -> ScriptHash {
+    let mut sha3_256 = Sha3_256::new();
+    sha3_256.update(script_blob);
+    sha3_256.finalize().into()
+}
+
 // A simple cache that offers both a HashMap and a Vector lookup.
 // Values are forced into a `Arc` so they can be used from multiple thread.
 // Access to this cache is always under a `RefCell`.
@@ -176,6 +186,8 @@ impl ModuleCache {
         natives: &NativeFunctions,
         id: ModuleId,
         module: CompiledModule,
+        size: usize,
+        optimize: bool,
     ) -> VMResult<Arc<Module>> {
         if let Some(cached) = self.module_at(&id) {
             return Ok(cached);
@@ -183,8 +195,8 @@ impl ModuleCache {
 
         // we need this operation to be transactional, if an error occurs we must
         // leave a clean state
-        self.add_module(natives, &module)?;
-        match Module::new(module, self) {
+        self.add_module(natives, &module, optimize)?;
+        match Module::new(module, size, self) {
             Ok(module) => Ok(Arc::clone(self.modules.insert(id, module))),
             Err((err, module)) => {
                 // remove all structs and functions that have been pushed
@@ -198,10 +210,16 @@ impl ModuleCache {
         }
     }
 
-    fn add_module(&mut self, natives: &NativeFunctions, module: &CompiledModule) -> VMResult<()> {
+    fn add_module(
+        &mut self,
+        natives: &NativeFunctions,
+        module: &CompiledModule,
+        optimize: bool,
+    ) -> VMResult<()> {
         let starting_idx = self.structs.len();
         for (idx, struct_def) in module.struct_defs().iter().enumerate() {
-            let st = self.make_struct_type(module, struct_def, StructDefinitionIndex(idx as u16));
+            let st =
+                self.make_struct_type(module, struct_def, StructDefinitionIndex(idx as TableIndex));
             self.structs.push(Arc::new(st));
         }
         self.load_field_types(module, starting_idx).map_err(|err| {
@@ -236,6 +254,9 @@ impl ModuleCache {
         for (idx, func) in module.function_defs().iter().enumerate() {
             let findex = FunctionDefinitionIndex(idx as TableIndex);
             let mut function = Function::new(natives, findex, func, module);
+            if optimize {
+                function.optimize();
+            }
             function.return_types = function
                 .return_
                 .0
@@ -613,7 +634,18 @@ pub(crate) struct Loader {
     // other transactions.
     module_cache_hits: RefCell<BTreeSet<ModuleId>>,
 
+    // Code which the adapter already ran through the bytecode verifier, e.g. while the
+    // transactions were waiting in the pool. Such code skips the deserialization and the bytecode
+    // verification, but its dependencies are still loaded and linked as usual.
+    verified_scripts: RefCell<BTreeMap<[u8; 32], CompiledScript>>,
+    verified_modules: RefCell<BTreeMap<ModuleId, CompiledModule>>,
+
     vm_config: VMConfig,
+    // Hash of the `vm_config` options which the data store verification markers are bound to.
+    verification_hash: [u8; 32],
+
+    // Layouts of the struct tags requested through the public layout APIs, see `get_type_layout`.
+    layout_cache: RefCell<LayoutCache>,
 }
 
 impl Loader {
@@ -625,6 +657,10 @@ impl Loader {
             natives,
             invalidated: RefCell::new(false),
             module_cache_hits: RefCell::new(BTreeSet::new()),
+            verified_scripts: RefCell::new(BTreeMap::new()),
+            verified_modules: RefCell::new(BTreeMap::new()),
+            verification_hash: vm_config.verification_hash(),
+            layout_cache: RefCell::new(LayoutCache::new(vm_config.layout_cache_capacity)),
             vm_config,
         }
     }
@@ -633,6 +669,32 @@ impl Loader {
         &self.vm_config
     }
 
+    /// Adds the script verified by the adapter under the SHA3-256 hash of its bytecode.
+    pub(crate) fn add_verified_script(&self, hash: [u8; 32], script: CompiledScript) {
+        self.verified_scripts.borrow_mut().insert(hash, script);
+    }
+
+    /// Adds the module verified by the adapter.
+    pub(crate) fn add_verified_module(&self, module: CompiledModule) {
+        self.verified_modules
+            .borrow_mut()
+            .insert(module.self_id(), module);
+    }
+
+    /// Drops the scripts and modules verified by the adapter.
+    pub(crate) fn clear_verified(&self) {
+        self.verified_scripts.borrow_mut().clear();
+        self.verified_modules.borrow_mut().clear();
+    }
+
+    pub(crate) fn is_module_cached(&self, id: &ModuleId) -> bool {
+        self.module_cache.borrow().has_module(id)
+    }
+
+    pub(crate) fn cached_module_count(&self) -> usize {
+        self.module_cache.borrow().modules.binaries.len()
+    }
+
     /// Gets and clears module cache hits. A cache hit may also be caused indirectly by
     /// loading a function or a type. This not only returns the direct hit, but also
     /// indirect ones, that is all dependencies.
@@ -662,6 +724,48 @@ impl Loader {
         }
     }
 
+    // Charges for linking against the given modules and all their transitive dependencies, which
+    // must be loaded already. Each module is charged at most once per session, whether it was
+    // fetched from the data store or found in the code cache, so the gas doesn't depend on the
+    // transactions executed before.
+    pub(crate) fn charge_dependencies(
+        &self,
+        roots: Vec<ModuleId>,
+        data_store: &mut impl DataStore,
+        gas_meter: &mut impl GasMeter,
+    ) -> VMResult<()> {
+        let mut closure = BTreeSet::new();
+        for id in &roots {
+            self.transitive_dep_closure(id, &mut closure);
+        }
+
+        for id in closure {
+            if !data_store.mark_dependency_charged(&id) {
+                continue;
+            }
+            let size = self
+                .module_cache
+                .borrow()
+                .modules
+                .get(&id)
+                .map_or(0, |module| module.size);
+            gas_meter
+                .charge_dependency(&id, NumBytes::new(size as u64))
+                .map_err(|err| err.finish(Location::Module(id.clone())))?;
+        }
+        Ok(())
+    }
+
+    // Immediate dependencies of the loaded script.
+    pub(crate) fn script_dependencies(&self, script_blob: &[u8]) -> Vec<ModuleId> {
+        self.scripts
+            .borrow()
+            .scripts
+            .get(&script_hash(script_blob))
+            .map(|script| script.script.immediate_dependencies())
+            .unwrap_or_default()
+    }
+
     /// Flush this cache if it is marked as invalidated.
     pub(crate) fn flush_if_invalidated(&self) {
         let mut invalidated = self.invalidated.borrow_mut();
@@ -669,6 +773,7 @@ impl Loader {
             *self.scripts.borrow_mut() = ScriptCache::new();
             *self.module_cache.borrow_mut() = ModuleCache::new();
             *self.type_cache.borrow_mut() = TypeCache::new();
+            self.layout_cache.borrow_mut().clear();
             *invalidated = false;
         }
     }
@@ -712,16 +817,26 @@ impl Loader {
         data_store: &impl DataStore,
     ) -> VMResult<(Arc<Function>, LoadedFunctionInstantiation)> {
         // retrieve or load the script
-        let mut sha3_256 = Sha3_256::new();
-        sha3_256.update(script_blob);
-        let hash_value: [u8; 32] = sha3_256.finalize().into();
+        let hash_value = script_hash(script_blob);
 
         let mut scripts = self.scripts.borrow_mut();
         let (main, parameters, return_) = match scripts.get(&hash_value) {
             Some(cached) => cached,
             None => {
-                let ver_script = self.deserialize_and_verify_script(script_blob, data_store)?;
-                let script = Script::new(ver_script, &hash_value, &self.module_cache.borrow())?;
+                let verified = self.verified_scripts.borrow_mut().remove(&hash_value);
+                let ver_script = match verified {
+                    Some(script) => {
+                        self.load_and_verify_script_dependencies(&script, data_store)?;
+                        script
+                    }
+                    None => self.deserialize_and_verify_script(script_blob, data_store)?,
+                };
+                let script = Script::new(
+                    ver_script,
+                    &hash_value,
+                    &self.module_cache.borrow(),
+                    self.vm_config.peephole_optimization,
+                )?;
                 scripts.insert(hash_value, script)
             }
         };
@@ -766,13 +881,7 @@ impl Loader {
 
         match self.verify_script(&script) {
             Ok(_) => {
-                // verify dependencies
-                let loaded_deps = script
-                    .immediate_dependencies()
-                    .into_iter()
-                    .map(|module_id| self.load_module(&module_id, data_store))
-                    .collect::<VMResult<_>>()?;
-                self.verify_script_dependencies(&script, loaded_deps)?;
+                self.load_and_verify_script_dependencies(&script, data_store)?;
                 Ok(script)
             }
             Err(err) => {
@@ -794,6 +903,19 @@ impl Loader {
         move_bytecode_verifier::verify_script_with_config(&self.vm_config.verifier, script)
     }
 
+    fn load_and_verify_script_dependencies(
+        &self,
+        script: &CompiledScript,
+        data_store: &impl DataStore,
+    ) -> VMResult<()> {
+        let loaded_deps = script
+            .immediate_dependencies()
+            .into_iter()
+            .map(|module_id| self.load_module(&module_id, data_store))
+            .collect::<VMResult<_>>()?;
+        self.verify_script_dependencies(script, loaded_deps)
+    }
+
     fn verify_script_dependencies(
         &self,
         script: &CompiledScript,
@@ -919,7 +1041,10 @@ impl Loader {
         // module will NOT show up in `module_cache`. In the module republishing case, it means
         // that the old module is still in the `module_cache`, unless a new Loader is created,
         // which means that a new MoveVM instance needs to be created.
-        move_bytecode_verifier::verify_module_with_config(&self.vm_config.verifier, module)?;
+        let preverified = self.verified_modules.borrow().get(&module.self_id()) == Some(module);
+        if !preverified {
+            move_bytecode_verifier::verify_module_with_config(&self.vm_config.verifier, module)?;
+        }
         self.check_natives(module)?;
 
         let mut visited = BTreeSet::new();
@@ -1125,13 +1250,14 @@ impl Loader {
         Ok(module_ref)
     }
 
-    // Load, deserialize, and check the module with the bytecode verifier, without linking
+    // Load, deserialize, and check the module with the bytecode verifier, without linking.
+    // Returns the module along with the length of its bytecode.
     fn load_and_verify_module(
         &self,
         id: &ModuleId,
         data_store: &impl DataStore,
         allow_loading_failure: bool,
-    ) -> VMResult<CompiledModule> {
+    ) -> VMResult<(CompiledModule, usize)> {
         // bytes fetching, allow loading to fail if the flag is set
         let bytes = match data_store.load_module(id) {
             Ok(bytes) => bytes,
@@ -1157,7 +1283,9 @@ impl Loader {
         .map_err(expect_no_verification_errors)?;
 
         #[cfg(feature = "std")]
-        fail::fail_point!("verifier-failpoint-2", |_| { Ok(module.clone()) });
+        fail::fail_point!("verifier-failpoint-2", |_| {
+            Ok((module.clone(), bytes.len()))
+        });
 
         if self.vm_config.paranoid_type_checks && &module.self_id() != id {
             return Err(
@@ -1167,12 +1295,16 @@ impl Loader {
             );
         }
 
-        // bytecode verifier checks that can be performed with the module itself
-        move_bytecode_verifier::verify_module_with_config(&self.vm_config.verifier, &module)
-            .map_err(expect_no_verification_errors)?;
+        // bytecode verifier checks that can be performed with the module itself, skipped for the
+        // bytecode the data store has already seen passing them under the same config
+        if !data_store.is_module_verified(&bytes, &self.verification_hash) {
+            move_bytecode_verifier::verify_module_with_config(&self.vm_config.verifier, &module)
+                .map_err(expect_no_verification_errors)?;
+            data_store.set_module_verified(&bytes, &self.verification_hash);
+        }
         self.check_natives(&module)
             .map_err(expect_no_verification_errors)?;
-        Ok(module)
+        Ok((module, bytes.len()))
     }
 
     // Everything in `load_and_verify_module` and also recursively load and verify all the
@@ -1194,7 +1326,8 @@ impl Loader {
         }
 
         // module self-check
-        let module = self.load_and_verify_module(id, data_store, allow_module_loading_failure)?;
+        let (module, size) =
+            self.load_and_verify_module(id, data_store, allow_module_loading_failure)?;
         visited.insert(id.clone());
         friends_discovered.extend(module.immediate_friends());
 
@@ -1212,7 +1345,13 @@ impl Loader {
 
         // if linking goes well, insert the module to the code cache
         let mut locked_cache = self.module_cache.borrow_mut();
-        let module_ref = locked_cache.insert(&self.natives, id.clone(), module)?;
+        let module_ref = locked_cache.insert(
+            &self.natives,
+            id.clone(),
+            module,
+            size,
+            self.vm_config.peephole_optimization,
+        )?;
         drop(locked_cache); // explicit unlock
 
         Ok(module_ref)
@@ -1836,6 +1975,8 @@ pub(crate) struct Module {
     id: ModuleId,
     // primitive pools
     module: Arc<CompiledModule>,
+    // length of the bytecode the module was loaded from
+    size: usize,
 
     //
     // types as indexes into the Loader type list
@@ -1882,6 +2023,7 @@ pub(crate) struct Module {
 impl Module {
     fn new(
         module: CompiledModule,
+        size: usize,
         cache: &ModuleCache,
     ) -> Result<Self, (PartialVMError, CompiledModule)> {
         let id = module.self_id();
@@ -1928,7 +2070,7 @@ impl Module {
 
             for struct_def in module.struct_defs() {
                 let idx = struct_refs[struct_def.struct_handle.0 as usize];
-                let field_count = cache.structs[idx.0].fields.len() as u16;
+                let field_count = cache.structs[idx.0].fields.len() as MemberCount;
                 structs.push(StructDef { field_count, idx });
                 let name =
                     module.identifier_at(module.struct_handle_at(struct_def.struct_handle).name);
@@ -2057,6 +2199,7 @@ impl Module {
             Ok(_) => Ok(Self {
                 id,
                 module: Arc::new(module),
+                size,
                 struct_refs,
                 structs,
                 struct_instantiations,
@@ -2154,6 +2297,7 @@ impl Script {
         script: CompiledScript,
         script_hash: &ScriptHash,
         cache: &ModuleCache,
+        optimize: bool,
     ) -> VMResult<Self> {
         let mut struct_refs = vec![];
         for struct_handle in script.struct_handles() {
@@ -2204,7 +2348,11 @@ impl Script {
 
         let scope = Scope::Script(*script_hash);
 
-        let code: Vec<Bytecode> = script.code.code.clone();
+        let (code, source_offsets) = if optimize {
+            peephole::optimize(&script.code.code)
+        } else {
+            (script.code.code.clone(), vec![])
+        };
         let parameters = script.signature_at(script.parameters).clone();
 
         let parameter_tys = parameters
@@ -2242,6 +2390,7 @@ impl Script {
             file_format_version: script.version(),
             index: FunctionDefinitionIndex(0),
             code,
+            source_offsets,
             parameters,
             return_,
             locals,
@@ -2338,6 +2487,8 @@ pub(crate) struct Function {
     file_format_version: u32,
     index: FunctionDefinitionIndex,
     code: Vec<Bytecode>,
+    // Offsets of the instructions in the original code, empty if the code isn't optimized.
+    source_offsets: Vec<CodeOffset>,
     parameters: Signature,
     return_: Signature,
     locals: Signature,
@@ -2401,6 +2552,7 @@ impl Function {
             file_format_version: module.version(),
             index,
             code,
+            source_offsets: vec![],
             parameters,
             return_,
             locals,
@@ -2465,6 +2617,15 @@ impl Function {
         &self.code
     }
 
+    /// Offset of the instruction at `pc` in the original code - see [`peephole`].
+    pub(crate) fn source_offset(&self, pc: CodeOffset) -> CodeOffset {
+        self.source_offsets.get(pc as usize).copied().unwrap_or(pc)
+    }
+
+    fn optimize(&mut self) {
+        (self.code, self.source_offsets) = peephole::optimize(&self.code);
+    }
+
     pub(crate) fn type_parameters(&self) -> &[AbilitySet] {
         &self.type_parameters
     }
@@ -2945,9 +3106,11 @@ impl Loader {
         type_tag: &TypeTag,
         move_storage: &impl DataStore,
     ) -> VMResult<MoveTypeLayout> {
-        let ty = self.load_type(type_tag, move_storage)?;
-        self.type_to_type_layout(&ty)
-            .map_err(|e| e.finish(Location::Undefined))
+        self.get_cached_type_layout(type_tag, false, || {
+            let ty = self.load_type(type_tag, move_storage)?;
+            self.type_to_type_layout(&ty)
+                .map_err(|e| e.finish(Location::Undefined))
+        })
     }
 
     pub(crate) fn get_fully_annotated_type_layout(
@@ -2955,8 +3118,38 @@ impl Loader {
         type_tag: &TypeTag,
         move_storage: &impl DataStore,
     ) -> VMResult<MoveTypeLayout> {
-        let ty = self.load_type(type_tag, move_storage)?;
-        self.type_to_fully_annotated_layout(&ty)
-            .map_err(|e| e.finish(Location::Undefined))
+        self.get_cached_type_layout(type_tag, true, || {
+            let ty = self.load_type(type_tag, move_storage)?;
+            self.type_to_fully_annotated_layout(&ty)
+                .map_err(|e| e.finish(Location::Undefined))
+        })
+    }
+
+    pub(crate) fn layout_cache_stats(&self) -> LayoutCacheStats {
+        self.layout_cache.borrow().stats()
+    }
+
+    // Only the struct layouts are cached, the layouts of the other types are cheap to build.
+    fn get_cached_type_layout(
+        &self,
+        type_tag: &TypeTag,
+        annotated: bool,
+        compute: impl FnOnce() -> VMResult<MoveTypeLayout>,
+    ) -> VMResult<MoveTypeLayout> {
+        let TypeTag::Struct(struct_tag) = type_tag else {
+            return compute();
+        };
+
+        if let Some(layout) = self.layout_cache.borrow_mut().get(struct_tag, annotated) {
+            return Ok(layout);
+        }
+
+        let layout = compute()?;
+        self.layout_cache.borrow_mut().insert(
+            struct_tag.as_ref().clone(),
+            annotated,
+            layout.clone(),
+        );
+        Ok(layout)
     }
 }
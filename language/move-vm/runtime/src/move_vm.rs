@@ -4,12 +4,15 @@
 
 use alloc::{collections::BTreeSet, sync::Arc};
 
+pub use crate::layout_cache::LayoutCacheStats;
+
 use crate::{
     config::VMConfig, data_cache::TransactionDataCache, native_extensions::NativeContextExtensions,
     native_functions::NativeFunction, runtime::VMRuntime, session::Session,
 };
 use move_binary_format::{
     errors::{Location, VMResult},
+    file_format::CompiledScript,
     CompiledModule,
 };
 use move_core_types::{
@@ -80,6 +83,28 @@ impl MoveVM {
             .map(|arc_module| arc_module.arc_module())
     }
 
+    /// Adds a script which the adapter already checked with the bytecode verifier of this VM's
+    /// config, e.g. ahead of the block execution.
+    ///
+    /// Loading the script whose bytecode has the given SHA3-256 hash skips the deserialization and
+    /// the bytecode verification - the dependencies are still loaded and linked.
+    pub fn add_verified_script(&self, hash: [u8; 32], script: CompiledScript) {
+        self.runtime.loader().add_verified_script(hash, script)
+    }
+
+    /// Adds a module which the adapter already checked with the bytecode verifier of this VM's
+    /// config. Publishing the identical module skips the bytecode verification - the dependencies
+    /// and the friends are still linked.
+    pub fn add_verified_module(&self, module: CompiledModule) {
+        self.runtime.loader().add_verified_module(module)
+    }
+
+    /// Drops the scripts and modules added with `add_verified_script` and `add_verified_module`
+    /// which weren't used yet.
+    pub fn clear_verified_code(&self) {
+        self.runtime.loader().clear_verified()
+    }
+
     /// Allows the adapter to announce to the VM that the code loading cache should be considered
     /// outdated. This can happen if the adapter executed a particular code publishing transaction
     /// but decided to not commit the result to the data store. Because the code cache currently
@@ -111,6 +136,21 @@ impl MoveVM {
         self.runtime.loader().get_and_clear_module_cache_hits()
     }
 
+    /// Returns whether the module is in the loader cache, i.e. loading it won't touch the storage.
+    pub fn is_module_cached(&self, module_id: &ModuleId) -> bool {
+        self.runtime.loader().is_module_cached(module_id)
+    }
+
+    /// Returns the number of modules in the loader cache, e.g. for the adapter metrics.
+    pub fn cached_module_count(&self) -> usize {
+        self.runtime.loader().cached_module_count()
+    }
+
+    /// Returns the statistics of the struct layout cache, e.g. for the adapter metrics.
+    pub fn layout_cache_stats(&self) -> LayoutCacheStats {
+        self.runtime.loader().layout_cache_stats()
+    }
+
     /// Attempts to discover metadata in a given module with given key. Availability
     /// of this data may depend on multiple aspects. In general, no hard assumptions of
     /// availability should be made, but typically, one can expect that
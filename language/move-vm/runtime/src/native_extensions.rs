@@ -37,6 +37,13 @@ impl<'a> NativeContextExtensions<'a> {
             .unwrap()
     }
 
+    /// Like [`Self::get`], but returns `None` if the extension isn't registered.
+    pub fn try_get<T: TidAble<'a>>(&self) -> Option<&T> {
+        self.map
+            .get(&T::id())
+            .map(|ext| ext.as_ref().downcast_ref::<T>().unwrap())
+    }
+
     pub fn get_mut<T: TidAble<'a>>(&mut self) -> &mut T {
         self.map
             .get_mut(&T::id())
@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    interpreter::Interpreter, loader::Resolver, native_extensions::NativeContextExtensions,
+    interpreter::Interpreter,
+    loader::{Function, Resolver},
+    native_extensions::NativeContextExtensions,
 };
 use alloc::boxed::Box;
 use alloc::string::String;
@@ -15,7 +17,7 @@ use move_core_types::{
     account_address::AccountAddress,
     gas_algebra::InternalGas,
     identifier::Identifier,
-    language_storage::TypeTag,
+    language_storage::{ModuleId, TypeTag},
     value::MoveTypeLayout,
     vm_status::{StatusCode, StatusType},
 };
@@ -90,12 +92,28 @@ impl NativeFunctions {
     }
 }
 
+/// Everything a native function can access during its execution.
+///
+/// The context is created for every native call and gives access to:
+/// - the gas meter - [`Self::charge`] and [`Self::gas_balance`],
+/// - the storage view - [`Self::resource_exists`] and [`Self::module_exists`],
+/// - the event sink - [`Self::save_event`] and [`Self::events`],
+/// - the caller info - [`Self::caller_module`] and [`Self::caller_function`],
+/// - the per-call scratch space - [`Self::scratch_mut`],
+/// - the host services registered as the [`NativeContextExtensions`].
+///
+/// The gas charged through it and the scratch space never outlive the call. The gas parameters
+/// of the natives are part of the gas schedule rather than the execution state, so they are still
+/// bound once the native table is built.
 pub struct NativeContext<'a, 'b> {
     interpreter: &'a mut Interpreter,
     data_store: &'a mut dyn DataStore,
     resolver: &'a Resolver<'a>,
     extensions: &'a mut NativeContextExtensions<'b>,
+    caller: Arc<Function>,
     gas_balance: InternalGas,
+    gas_used: InternalGas,
+    scratch: Vec<u8>,
 }
 
 impl<'a, 'b> NativeContext<'a, 'b> {
@@ -104,6 +122,7 @@ impl<'a, 'b> NativeContext<'a, 'b> {
         data_store: &'a mut dyn DataStore,
         resolver: &'a Resolver<'a>,
         extensions: &'a mut NativeContextExtensions<'b>,
+        caller: Arc<Function>,
         gas_balance: InternalGas,
     ) -> Self {
         Self {
@@ -111,7 +130,10 @@ impl<'a, 'b> NativeContext<'a, 'b> {
             data_store,
             resolver,
             extensions,
+            caller,
             gas_balance,
+            gas_used: InternalGas::zero(),
+            scratch: Vec::new(),
         }
     }
 }
@@ -140,6 +162,23 @@ impl<'a, 'b> NativeContext<'a, 'b> {
         self.data_store.events()
     }
 
+    /// Checks whether the resource of the given type is published under the address, including
+    /// the changes made by the current session.
+    ///
+    /// The read isn't charged on its own - the native should [`Self::charge`] for it.
+    pub fn resource_exists(&mut self, address: AccountAddress, ty: &Type) -> PartialVMResult<bool> {
+        let (global_value, _) = self.data_store.load_resource(address, ty)?;
+        global_value.exists()
+    }
+
+    /// Checks whether the module is published, including the modules published by the current
+    /// session.
+    pub fn module_exists(&self, module_id: &ModuleId) -> PartialVMResult<bool> {
+        self.data_store
+            .exists_module(module_id)
+            .map_err(|err| err.to_partial())
+    }
+
     pub fn type_to_type_tag(&self, ty: &Type) -> PartialVMResult<TypeTag> {
         self.resolver.loader().type_to_type_tag(ty)
     }
@@ -177,10 +216,47 @@ impl<'a, 'b> NativeContext<'a, 'b> {
         self.interpreter.get_stack_frames(count)
     }
 
+    /// Module of the Move function which called the native function.
+    pub fn caller_module(&self) -> Option<&ModuleId> {
+        self.caller.module_id()
+    }
+
+    /// Name of the Move function which called the native function.
+    pub fn caller_function(&self) -> &str {
+        self.caller.name()
+    }
+
+    /// Gas balance at the start of the native call.
     pub fn gas_balance(&self) -> InternalGas {
         self.gas_balance
     }
 
+    /// Charges gas before doing the work it pays for.
+    ///
+    /// The charged amount is added to the cost returned in the [`NativeResult`]. Once the balance
+    /// is exhausted, an `OUT_OF_GAS` error is returned which the native should propagate.
+    pub fn charge(&mut self, amount: InternalGas) -> PartialVMResult<()> {
+        self.gas_used += amount;
+        if self.is_out_of_gas() {
+            return Err(PartialVMError::new(StatusCode::OUT_OF_GAS));
+        }
+        Ok(())
+    }
+
+    /// Gas charged through [`Self::charge`] so far.
+    pub fn gas_used(&self) -> InternalGas {
+        self.gas_used
+    }
+
+    pub(crate) fn is_out_of_gas(&self) -> bool {
+        self.gas_used > self.gas_balance
+    }
+
+    /// Scratch buffer for the current native call, e.g. for serializing the values.
+    pub fn scratch_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.scratch
+    }
+
     pub fn transfer(
         &self,
         src: AccountAddress,
@@ -197,4 +273,16 @@ impl<'a, 'b> NativeContext<'a, 'b> {
     pub fn total_amount(&self, account: AccountAddress) -> PartialVMResult<u128> {
         self.data_store.total_amount(account)
     }
+
+    pub fn total_issuance(&self) -> PartialVMResult<u128> {
+        self.data_store.total_issuance()
+    }
+
+    pub fn account_exists(&self, account: AccountAddress) -> PartialVMResult<bool> {
+        self.data_store.account_exists(account)
+    }
+
+    pub fn minimum_balance(&self) -> PartialVMResult<u128> {
+        self.data_store.minimum_balance()
+    }
 }
@@ -0,0 +1,109 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Load-time peephole optimization of the function code.
+//!
+//! The compiler emits a few instruction pairs which have no effect or a fixed outcome, e.g. the
+//! constant conditions of the `while (true)` loops or the values discarded by the `_ = x`
+//! assignments. The loader rewrites them once, when the function is cached, so the interpreter
+//! doesn't execute them on every call:
+//! - `LdTrue; BrTrue(t)` and `LdFalse; BrFalse(t)` become `Branch(t)`,
+//! - `LdTrue; BrFalse(_)` and `LdFalse; BrTrue(_)` are removed,
+//! - `CopyLoc(_); Pop` and the constant loads followed by `Pop` are removed.
+//!
+//! A pair is only rewritten if no branch lands on its second instruction, so the rewrite holds on
+//! every path. The remaining branches are moved to the new offsets, and the offsets of the
+//! original code are kept for the error locations and the traces, which stay the same as for the
+//! unoptimized code. Only the instruction counts and the gas charged for them change, so the
+//! optimization must be enabled on all nodes or on none - see
+//! [`VMConfig::peephole_optimization`](crate::config::VMConfig::peephole_optimization).
+
+use alloc::{collections::BTreeSet, vec::Vec};
+use move_binary_format::file_format::{Bytecode, CodeOffset};
+
+/// Result of the pair rewrite.
+enum Rewrite {
+    /// Both instructions are replaced with the single one.
+    Replace(Bytecode),
+    /// Both instructions are removed.
+    Remove,
+}
+
+fn rewrite(first: &Bytecode, second: &Bytecode) -> Option<Rewrite> {
+    use Bytecode::*;
+
+    match (first, second) {
+        (LdTrue, BrTrue(target)) | (LdFalse, BrFalse(target)) => {
+            Some(Rewrite::Replace(Branch(*target)))
+        }
+        (LdTrue, BrFalse(_)) | (LdFalse, BrTrue(_)) => Some(Rewrite::Remove),
+        (
+            CopyLoc(_) | LdTrue | LdFalse | LdU8(_) | LdU16(_) | LdU32(_) | LdU64(_) | LdU128(_)
+            | LdU256(_) | LdConst(_),
+            Pop,
+        ) => Some(Rewrite::Remove),
+        _ => None,
+    }
+}
+
+/// Optimize the code.
+///
+/// Returns the optimized code and the offset of each of its instructions in the original code.
+pub(crate) fn optimize(code: &[Bytecode]) -> (Vec<Bytecode>, Vec<CodeOffset>) {
+    let targets: BTreeSet<CodeOffset> = code
+        .iter()
+        .filter_map(|instr| match instr {
+            Bytecode::Branch(target) | Bytecode::BrTrue(target) | Bytecode::BrFalse(target) => {
+                Some(*target)
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut optimized: Vec<Bytecode> = Vec::with_capacity(code.len());
+    let mut offsets: Vec<CodeOffset> = Vec::with_capacity(code.len());
+    // Offset of the latest branch target - instructions before it can't pair with the later ones.
+    let mut barrier = 0;
+    for (offset, instr) in code.iter().enumerate() {
+        let offset = offset as CodeOffset;
+        if targets.contains(&offset) {
+            barrier = offset;
+        }
+
+        let previous = match (optimized.last(), offsets.last()) {
+            (Some(previous), Some(&previous_offset)) if previous_offset >= barrier => previous,
+            _ => {
+                optimized.push(instr.clone());
+                offsets.push(offset);
+                continue;
+            }
+        };
+
+        match rewrite(previous, instr) {
+            Some(Rewrite::Replace(replacement)) => {
+                *optimized.last_mut().expect("previous instruction exists") = replacement;
+            }
+            Some(Rewrite::Remove) => {
+                optimized.pop();
+                offsets.pop();
+            }
+            None => {
+                optimized.push(instr.clone());
+                offsets.push(offset);
+            }
+        }
+    }
+
+    // The removed instructions are followed by a kept one, as the code ends with an unconditional
+    // jump, so all branches land on a kept instruction.
+    let new_offset = |target: CodeOffset| offsets.partition_point(|offset| *offset < target);
+    for instr in &mut optimized {
+        if let Bytecode::Branch(target) | Bytecode::BrTrue(target) | Bytecode::BrFalse(target) =
+            instr
+        {
+            *target = new_offset(*target) as CodeOffset;
+        }
+    }
+
+    (optimized, offsets)
+}
@@ -72,7 +72,7 @@ impl VMRuntime {
     pub(crate) fn publish_module_bundle(
         &self,
         modules: Vec<Vec<u8>>,
-        sender: AccountAddress,
+        senders: &[AccountAddress],
         data_store: &mut impl DataStore,
         _gas_meter: &mut impl GasMeter,
         compat: Compatibility,
@@ -96,11 +96,11 @@ impl VMRuntime {
             }
         };
 
-        // Make sure all modules' self addresses matches the transaction sender. The self address is
-        // where the module will actually be published. If we did not check this, the sender could
-        // publish a module under anyone's account.
+        // Make sure all modules' self addresses match one of the transaction senders. The self
+        // address is where the module will actually be published. If we did not check this, the
+        // sender could publish a module under anyone's account.
         for module in &compiled_modules {
-            if module.address() != &sender {
+            if !senders.contains(module.address()) {
                 return Err(verification_error(
                     StatusCode::MODULE_ADDRESS_DOES_NOT_MATCH_SENDER,
                     IndexKind::AddressIdentifier,
@@ -416,7 +416,9 @@ impl VMRuntime {
         } else {
             check_is_entry
         };
-        // load the function
+        // load the function and charge for the code it is linked against
+        let mut linked = vec![module.clone()];
+        type_arg_modules(&ty_args, &mut linked);
         let (
             module,
             func,
@@ -428,6 +430,8 @@ impl VMRuntime {
         ) = self
             .loader
             .load_function(module, function_name, &ty_args, data_store)?;
+        self.loader
+            .charge_dependencies(linked, data_store, gas_meter)?;
 
         script_signature::verify_module_function_signature_by_name(
             module.module(),
@@ -469,6 +473,11 @@ impl VMRuntime {
         ) = self
             .loader
             .load_script(script.borrow(), &ty_args, data_store)?;
+        // charge for the code the script is linked against
+        let mut linked = self.loader.script_dependencies(script.borrow());
+        type_arg_modules(&ty_args, &mut linked);
+        self.loader
+            .charge_dependencies(linked, data_store, gas_meter)?;
         // execute the function
         self.execute_function_impl(
             func,
@@ -486,3 +495,19 @@ impl VMRuntime {
         &self.loader
     }
 }
+
+// Collects the modules declaring the structs within the type arguments.
+fn type_arg_modules(ty_args: &[TypeTag], modules: &mut Vec<ModuleId>) {
+    for ty_arg in ty_args {
+        match ty_arg {
+            TypeTag::Struct(tag) => {
+                modules.push(tag.module_id());
+                type_arg_modules(&tag.type_params, modules);
+            }
+            TypeTag::Vector(inner) => {
+                type_arg_modules(core::slice::from_ref(inner.as_ref()), modules)
+            }
+            _ => (),
+        }
+    }
+}
@@ -191,7 +191,7 @@ impl<'r, 'l, S: MoveResolver> Session<'r, 'l, S> {
     ) -> VMResult<()> {
         self.runtime.publish_module_bundle(
             modules,
-            sender,
+            &[sender],
             &mut self.data_cache,
             gas_meter,
             Compatibility::full_check(),
@@ -208,13 +208,30 @@ impl<'r, 'l, S: MoveResolver> Session<'r, 'l, S> {
     ) -> VMResult<()> {
         self.runtime.publish_module_bundle(
             modules,
-            sender,
+            &[sender],
             &mut self.data_cache,
             gas_meter,
             compat_config,
         )
     }
 
+    /// Same like `publish_module_bundle` but the modules can be published under any of the
+    /// senders' addresses, so a bundle spanning several accounts is published atomically.
+    pub fn publish_module_bundle_for_senders(
+        &mut self,
+        modules: Vec<Vec<u8>>,
+        senders: &[AccountAddress],
+        gas_meter: &mut impl GasMeter,
+    ) -> VMResult<()> {
+        self.runtime.publish_module_bundle(
+            modules,
+            senders,
+            &mut self.data_cache,
+            gas_meter,
+            Compatibility::full_check(),
+        )
+    }
+
     pub fn publish_module_bundle_relax_compatibility(
         &mut self,
         modules: Vec<Vec<u8>>,
@@ -223,7 +240,7 @@ impl<'r, 'l, S: MoveResolver> Session<'r, 'l, S> {
     ) -> VMResult<()> {
         self.runtime.publish_module_bundle(
             modules,
-            sender,
+            &[sender],
             &mut self.data_cache,
             gas_meter,
             Compatibility::no_check(),
@@ -77,7 +77,7 @@ pub(crate) fn trace(
             process::id(),
             thread::current().id(),
             function_desc.pretty_string(),
-            pc,
+            function_desc.source_offset(pc),
             instr,
         )
         .unwrap();
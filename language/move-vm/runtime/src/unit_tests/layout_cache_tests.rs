@@ -0,0 +1,78 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::layout_cache::LayoutCache;
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::StructTag,
+    value::MoveTypeLayout,
+};
+
+fn tag(name: &str) -> StructTag {
+    StructTag {
+        address: AccountAddress::ONE,
+        module: Identifier::new("M").unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params: vec![],
+    }
+}
+
+#[test]
+fn least_recently_used_layout_is_evicted() {
+    let mut cache = LayoutCache::new(2);
+    cache.insert(tag("A"), false, MoveTypeLayout::U8);
+    cache.insert(tag("B"), false, MoveTypeLayout::U64);
+
+    // Using `A` makes `B` the least recently used layout.
+    assert!(matches!(
+        cache.get(&tag("A"), false),
+        Some(MoveTypeLayout::U8)
+    ));
+    cache.insert(tag("C"), false, MoveTypeLayout::Bool);
+
+    assert!(cache.get(&tag("B"), false).is_none());
+    assert!(matches!(
+        cache.get(&tag("A"), false),
+        Some(MoveTypeLayout::U8)
+    ));
+    assert!(matches!(
+        cache.get(&tag("C"), false),
+        Some(MoveTypeLayout::Bool)
+    ));
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses), (3, 1));
+    assert_eq!((stats.evictions, stats.entries, stats.capacity), (1, 2, 2));
+}
+
+#[test]
+fn annotated_layouts_are_cached_separately() {
+    let mut cache = LayoutCache::new(2);
+    cache.insert(tag("A"), false, MoveTypeLayout::U8);
+
+    assert!(cache.get(&tag("A"), true).is_none());
+    assert!(matches!(
+        cache.get(&tag("A"), false),
+        Some(MoveTypeLayout::U8)
+    ));
+}
+
+#[test]
+fn cleared_cache_keeps_stats() {
+    let mut cache = LayoutCache::new(2);
+    cache.insert(tag("A"), false, MoveTypeLayout::U8);
+    assert!(cache.get(&tag("A"), false).is_some());
+    cache.clear();
+
+    assert!(cache.get(&tag("A"), false).is_none());
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 0));
+}
+
+#[test]
+fn zero_capacity_disables_cache() {
+    let mut cache = LayoutCache::new(0);
+    cache.insert(tag("A"), false, MoveTypeLayout::U8);
+
+    assert!(cache.get(&tag("A"), false).is_none());
+    assert_eq!(cache.stats().entries, 0);
+}
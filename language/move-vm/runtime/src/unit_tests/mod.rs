@@ -2,4 +2,6 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod layout_cache_tests;
+pub mod peephole_tests;
 pub mod vm_arguments_tests;
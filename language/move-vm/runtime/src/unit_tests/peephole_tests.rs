@@ -0,0 +1,94 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::peephole::optimize;
+use move_binary_format::file_format::{
+    Bytecode::{self, *},
+    ConstantPoolIndex,
+};
+
+#[test]
+fn constant_branches_are_folded() {
+    let code = vec![
+        LdTrue,
+        BrTrue(4),
+        LdFalse,
+        BrFalse(5),
+        Ret,
+        LdTrue,
+        BrFalse(0),
+        Ret,
+    ];
+
+    let (optimized, offsets) = optimize(&code);
+    assert_eq!(optimized, vec![Branch(2), Branch(3), Ret, Ret]);
+    assert_eq!(offsets, vec![0, 2, 4, 7]);
+}
+
+#[test]
+fn popped_values_are_removed() {
+    let code = vec![
+        CopyLoc(0),
+        Pop,
+        LdU64(1),
+        LdConst(ConstantPoolIndex(0)),
+        Pop,
+        Pop,
+        MoveLoc(0),
+        Pop,
+        Ret,
+    ];
+
+    let (optimized, offsets) = optimize(&code);
+    assert_eq!(optimized, vec![MoveLoc(0), Pop, Ret]);
+    assert_eq!(offsets, vec![6, 7, 8]);
+}
+
+#[test]
+fn branch_targets_are_not_merged() {
+    // A branch lands on the `BrTrue`, so the loaded constant isn't the only way to reach it.
+    let code = vec![LdTrue, BrTrue(4), CopyLoc(0), Branch(1), Ret];
+
+    let (optimized, offsets) = optimize(&code);
+    assert_eq!(optimized, code);
+    assert_eq!(offsets, vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn branches_to_removed_instructions_land_on_the_next_one() {
+    let code = vec![Branch(2), Ret, CopyLoc(0), Pop, LdU8(0), Pop, Branch(1)];
+
+    let (optimized, offsets) = optimize(&code);
+    assert_eq!(optimized, vec![Branch(2), Ret, Branch(1)]);
+    assert_eq!(offsets, vec![0, 1, 6]);
+}
+
+#[test]
+fn optimization_is_deterministic() {
+    let code: Vec<Bytecode> = vec![
+        LdFalse,
+        BrTrue(7),
+        CopyLoc(1),
+        LdTrue,
+        Pop,
+        Pop,
+        LdTrue,
+        BrTrue(9),
+        Ret,
+        MoveLoc(1),
+        Ret,
+    ];
+
+    let expected = optimize(&code);
+    for _ in 0..3 {
+        assert_eq!(optimize(&code), expected);
+    }
+    // The removed `BrTrue(7)` still keeps the later pair apart.
+    assert_eq!(
+        expected,
+        (
+            vec![LdTrue, BrTrue(3), Ret, MoveLoc(1), Ret],
+            vec![6, 7, 8, 9, 10]
+        )
+    );
+}
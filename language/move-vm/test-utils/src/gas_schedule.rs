@@ -23,7 +23,7 @@ use move_binary_format::{
 use move_core_types::{
     gas_algebra::{
         AbstractMemorySize, GasQuantity, InternalGas, InternalGasPerAbstractMemoryUnit,
-        InternalGasUnit, NumArgs, NumBytes, ToUnit, ToUnitFractional,
+        InternalGasPerByte, InternalGasUnit, NumArgs, NumBytes, ToUnit, ToUnitFractional,
     },
     language_storage::ModuleId,
     u256,
@@ -66,6 +66,19 @@ pub const MIN_EXISTS_DATA_SIZE: AbstractMemorySize = AbstractMemorySize::new(100
 #[derive(Clone, Debug, Serialize, PartialEq, Eq, Deserialize)]
 pub struct CostTable {
     pub instruction_table: Vec<GasCost>,
+    /// Charged for every taken branch to an earlier instruction, on top of the branch instruction.
+    ///
+    /// Loop iterations cost more than the instructions they are made of, so a separate entry keeps
+    /// the tight loops from being underpriced relative to their execution time.
+    #[serde(default)]
+    pub back_edge_cost: GasCost,
+    /// Charged for every module linked by the execution, i.e. the module of the called function
+    /// and each of its transitive dependencies.
+    #[serde(default)]
+    pub dependency_cost: GasCost,
+    /// Charged per byte of every module linked by the execution, on top of the dependency cost.
+    #[serde(default)]
+    pub dependency_byte_cost: GasCost,
 }
 
 impl CostTable {
@@ -79,14 +92,14 @@ impl CostTable {
 /// The  `GasCost` tracks:
 /// - instruction cost: how much time/computational power is needed to perform the instruction
 /// - memory cost: how much memory is required for the instruction, and storage overhead
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct GasCost {
     pub instruction_gas: u64,
     pub memory_gas: u64,
 }
 
 impl GasCost {
-    pub fn new(instruction_gas: u64, memory_gas: u64) -> Self {
+    pub const fn new(instruction_gas: u64, memory_gas: u64) -> Self {
         Self {
             instruction_gas,
             memory_gas,
@@ -114,6 +127,7 @@ pub struct GasStatus<'a> {
     cost_table: &'a CostTable,
     gas_left: InternalGas,
     charge: bool,
+    count_instructions: bool,
 }
 
 impl<'a> GasStatus<'a> {
@@ -126,6 +140,21 @@ impl<'a> GasStatus<'a> {
             gas_left: gas_left.to_unit(),
             cost_table,
             charge: true,
+            count_instructions: false,
+        }
+    }
+
+    /// Initialize the gas state with instruction counting enabled.
+    ///
+    /// Every executed instruction costs exactly one internal gas unit regardless of the cost table
+    /// and the size of the data, while native functions are not charged at all. Fails once more
+    /// than `instruction_limit` instructions are executed.
+    pub fn new_instruction_counter(instruction_limit: u64) -> Self {
+        Self {
+            gas_left: InternalGas::new(instruction_limit),
+            cost_table: &ZERO_COST_SCHEDULE,
+            charge: true,
+            count_instructions: true,
         }
     }
 
@@ -138,6 +167,7 @@ impl<'a> GasStatus<'a> {
             gas_left: InternalGas::new(0),
             cost_table: &ZERO_COST_SCHEDULE,
             charge: false,
+            count_instructions: false,
         }
     }
 
@@ -170,6 +200,10 @@ impl<'a> GasStatus<'a> {
     }
 
     fn charge_instr(&mut self, opcode: Opcodes) -> PartialVMResult<()> {
+        if self.count_instructions {
+            return self.deduct_gas(InternalGas::new(1));
+        }
+
         self.deduct_gas(
             self.cost_table
                 .instruction_cost(opcode as u8)
@@ -184,6 +218,10 @@ impl<'a> GasStatus<'a> {
         opcode: Opcodes,
         size: AbstractMemorySize,
     ) -> PartialVMResult<()> {
+        if self.count_instructions {
+            return self.deduct_gas(InternalGas::new(1));
+        }
+
         // Make sure that the size is always non-zero
         let size = core::cmp::max(1.into(), size);
         debug_assert!(size > 0.into());
@@ -271,6 +309,15 @@ impl<'b> GasMeter for GasStatus<'b> {
         self.charge_instr(get_simple_instruction_opcode(instr))
     }
 
+    fn charge_back_edge(&mut self) -> PartialVMResult<()> {
+        // Back edges are not instructions.
+        if self.count_instructions {
+            return Ok(());
+        }
+
+        self.deduct_gas(self.cost_table.back_edge_cost.total().into())
+    }
+
     fn charge_pop(&mut self, _popped_val: impl ValueView) -> PartialVMResult<()> {
         self.charge_instr(Opcodes::POP)
     }
@@ -280,6 +327,11 @@ impl<'b> GasMeter for GasStatus<'b> {
         amount: InternalGas,
         _ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView>>,
     ) -> PartialVMResult<()> {
+        // Native functions are not instructions.
+        if self.count_instructions {
+            return Ok(());
+        }
+
         self.deduct_gas(amount)
     }
 
@@ -554,6 +606,16 @@ impl<'b> GasMeter for GasStatus<'b> {
     ) -> PartialVMResult<()> {
         Ok(())
     }
+
+    fn charge_dependency(&mut self, _module_id: &ModuleId, size: NumBytes) -> PartialVMResult<()> {
+        // Linking is not an instruction.
+        if self.count_instructions {
+            return Ok(());
+        }
+
+        let per_byte = InternalGasPerByte::new(self.cost_table.dependency_byte_cost.total());
+        self.deduct_gas(InternalGas::new(self.cost_table.dependency_cost.total()) + per_byte * size)
+    }
 }
 
 pub fn new_from_instructions(mut instrs: Vec<(Bytecode, GasCost)>) -> CostTable {
@@ -576,7 +638,12 @@ pub fn new_from_instructions(mut instrs: Vec<(Bytecode, GasCost)>) -> CostTable
         .into_iter()
         .map(|(_, cost)| cost)
         .collect::<Vec<GasCost>>();
-    CostTable { instruction_table }
+    CostTable {
+        instruction_table,
+        back_edge_cost: GasCost::default(),
+        dependency_cost: GasCost::default(),
+        dependency_byte_cost: GasCost::default(),
+    }
 }
 
 pub fn zero_cost_instruction_table() -> Vec<(Bytecode, GasCost)> {
@@ -129,6 +129,18 @@ impl<'a, 'b, S: BalanceResolver> BalanceResolver for DeltaStorage<'a, 'b, S> {
     fn total_amount(&self, _account: AccountAddress) -> Result<u128, Self::Error> {
         unimplemented!("shouldn't be used");
     }
+
+    fn total_issuance(&self) -> Result<u128, Self::Error> {
+        unimplemented!("shouldn't be used");
+    }
+
+    fn account_exists(&self, _account: AccountAddress) -> Result<bool, Self::Error> {
+        unimplemented!("shouldn't be used");
+    }
+
+    fn minimum_balance(&self) -> Result<u128, Self::Error> {
+        unimplemented!("shouldn't be used");
+    }
 }
 
 #[cfg(feature = "table-extension")]
@@ -47,6 +47,18 @@ pub trait DataStore {
     /// Check if this module exists.
     fn exists_module(&self, module_id: &ModuleId) -> VMResult<bool>;
 
+    /// Check if the module bytecode already passed the bytecode verifier whose config has the
+    /// given hash.
+    fn is_module_verified(&self, bytes: &[u8], config_hash: &[u8; 32]) -> bool;
+
+    /// Record that the module bytecode passed the bytecode verifier whose config has the given
+    /// hash.
+    fn set_module_verified(&self, bytes: &[u8], config_hash: &[u8; 32]);
+
+    /// Record that the module was charged for as a dependency of the executed code. Returns
+    /// `false` if it was charged for already within the session.
+    fn mark_dependency_charged(&mut self, module_id: &ModuleId) -> bool;
+
     // ---
     // EventStore operations
     // ---
@@ -79,4 +91,13 @@ pub trait DataStore {
 
     /// Get the total amount for the address.
     fn total_amount(&self, account: AccountAddress) -> PartialVMResult<u128>;
+
+    /// Get the total issuance of the currency.
+    fn total_issuance(&self) -> PartialVMResult<u128>;
+
+    /// Check if the account exists.
+    fn account_exists(&self, account: AccountAddress) -> PartialVMResult<bool>;
+
+    /// Get the minimum balance an account must keep to exist.
+    fn minimum_balance(&self) -> PartialVMResult<u128>;
 }
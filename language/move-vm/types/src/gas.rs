@@ -75,6 +75,10 @@ pub trait GasMeter {
     /// Charge an instruction and fail if not enough gas units are left.
     fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()>;
 
+    /// Charge a taken branch back to an earlier instruction, i.e. a loop iteration, on top of the
+    /// branch instruction itself.
+    fn charge_back_edge(&mut self) -> PartialVMResult<()>;
+
     fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()>;
 
     fn charge_call(
@@ -232,6 +236,13 @@ pub trait GasMeter {
         &mut self,
         locals: impl Iterator<Item = impl ValueView>,
     ) -> PartialVMResult<()>;
+
+    /// Charges for linking against a module of the given size, i.e. the module of the called
+    /// function and each of its transitive dependencies.
+    ///
+    /// Called once per module and session, regardless of whether the module was already in the
+    /// loader's code cache, so the charge doesn't depend on the previously executed transactions.
+    fn charge_dependency(&mut self, module_id: &ModuleId, size: NumBytes) -> PartialVMResult<()>;
 }
 
 /// A dummy gas meter that does not meter anything.
@@ -247,6 +258,10 @@ impl GasMeter for UnmeteredGasMeter {
         Ok(())
     }
 
+    fn charge_back_edge(&mut self) -> PartialVMResult<()> {
+        Ok(())
+    }
+
     fn charge_pop(&mut self, _popped_val: impl ValueView) -> PartialVMResult<()> {
         Ok(())
     }
@@ -448,4 +463,8 @@ impl GasMeter for UnmeteredGasMeter {
     ) -> PartialVMResult<()> {
         Ok(())
     }
+
+    fn charge_dependency(&mut self, _module_id: &ModuleId, _size: NumBytes) -> PartialVMResult<()> {
+        Ok(())
+    }
 }
@@ -76,6 +76,23 @@ impl NativeResult {
         NativeResult::OutOfGas { partial_cost }
     }
 
+    /// Adds the cost charged outside of the result, e.g. through the native context.
+    pub fn add_cost(self, extra: InternalGas) -> Self {
+        match self {
+            NativeResult::Success { cost, ret_vals } => NativeResult::Success {
+                cost: cost + extra,
+                ret_vals,
+            },
+            NativeResult::Abort { cost, abort_code } => NativeResult::Abort {
+                cost: cost + extra,
+                abort_code,
+            },
+            NativeResult::OutOfGas { partial_cost } => NativeResult::OutOfGas {
+                partial_cost: partial_cost + extra,
+            },
+        }
+    }
+
     /// Convert a PartialVMResult<()> into a PartialVMResult<NativeResult>
     pub fn map_partial_vm_result_empty(
         cost: InternalGas,
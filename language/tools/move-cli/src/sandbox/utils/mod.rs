@@ -512,6 +512,7 @@ pub(crate) fn explain_execution_error(
                     .to_string(),
                 EXECUTION_STACK_OVERFLOW => "an execution stack overflow".to_string(),
                 CALL_STACK_OVERFLOW => "a call stack overflow".to_string(),
+                EXECUTION_INTERRUPTED => "an interruption by the embedder".to_string(),
                 OUT_OF_GAS => "an out of gas error".to_string(),
                 _ => format!("a {} error", status_code.status_type()),
             };
@@ -6,6 +6,11 @@
 //! Such extensions are enabled by cfg features and must be compiled into the test
 //! to be usable.
 
+use move_stdlib::natives::{
+    account::NativeAccountContext, expiry::NativeExpiryContext, foreign::NativeForeignCallContext,
+    host_gas::NativeHostGasContext, random::NativeRandomnessContext, timestamp::NativeTimeContext,
+    xcm::NativeXcmContext,
+};
 use move_vm_runtime::native_extensions::NativeContextExtensions;
 use once_cell::sync::Lazy;
 use std::{fmt::Write, sync::Mutex};
@@ -31,8 +36,8 @@ static EXTENSION_HOOK: Lazy<
 /// (b) Before `cli::run_move_unit_tests` if unit tests are called programmatically from Rust.
 /// You may want to define a new function `my_cli::run_move_unit_tests` which does this.
 ///
-/// Note that the table extension is handled already internally, and does not need to added via
-/// this hook.
+/// Note that the table, the expiry, the foreign call, the XCM, the account, the randomness and the time extensions are handled already internally, and do not need
+/// to added via this hook.
 pub fn set_extension_hook(p: Box<dyn Fn(&mut NativeContextExtensions<'_>) + Send + Sync>) {
     *EXTENSION_HOOK.lock().unwrap() = Some(p)
 }
@@ -41,6 +46,13 @@ pub fn set_extension_hook(p: Box<dyn Fn(&mut NativeContextExtensions<'_>) + Send
 #[allow(unused_mut, clippy::let_and_return)]
 pub(crate) fn new_extensions<'a>() -> NativeContextExtensions<'a> {
     let mut e = NativeContextExtensions::default();
+    e.add(NativeExpiryContext::default());
+    e.add(NativeForeignCallContext::unavailable());
+    e.add(NativeXcmContext::unavailable());
+    e.add(NativeAccountContext::unavailable());
+    e.add(NativeHostGasContext::unavailable());
+    e.add(NativeRandomnessContext::unavailable());
+    e.add(NativeTimeContext::unavailable());
     if let Some(h) = &*EXTENSION_HOOK.lock().unwrap() {
         (*h)(&mut e)
     }
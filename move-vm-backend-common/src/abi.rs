@@ -1,7 +1,9 @@
+use crate::type_tag::{display_address, TagForm};
 use alloc::borrow::ToOwned;
 use alloc::boxed::Box;
 use alloc::vec;
 use alloc::vec::Vec;
+use core::fmt;
 use move_binary_format::access::ModuleAccess;
 use move_binary_format::file_format::{
     Ability, AbilitySet, Signature, SignatureToken, StructFieldInformation, StructHandleIndex,
@@ -113,6 +115,45 @@ pub enum Type {
     TypeParameter(u16),
 }
 
+/// Displays the type in the short type tag form, e.g. `vector<0x1::string::String>`. Type
+/// parameters are shown as `T0`, `T1`, ...
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Bool => write!(f, "bool"),
+            Type::U8 => write!(f, "u8"),
+            Type::U16 => write!(f, "u16"),
+            Type::U32 => write!(f, "u32"),
+            Type::U64 => write!(f, "u64"),
+            Type::U128 => write!(f, "u128"),
+            Type::U256 => write!(f, "u256"),
+            Type::Address => write!(f, "address"),
+            Type::Signer => write!(f, "signer"),
+            Type::Vector(inner) => write!(f, "vector<{inner}>"),
+            Type::Struct(def) => {
+                write!(
+                    f,
+                    "{}::{}::{}",
+                    display_address(def.id.address(), TagForm::Short),
+                    def.id.name(),
+                    def.name
+                )?;
+                if let Some((first, rest)) = def.fields.split_first() {
+                    write!(f, "<{first}")?;
+                    for ty in rest {
+                        write!(f, ", {ty}")?;
+                    }
+                    write!(f, ">")?;
+                }
+                Ok(())
+            }
+            Type::Reference(inner) => write!(f, "&{inner}"),
+            Type::MutableReference(inner) => write!(f, "&mut {inner}"),
+            Type::TypeParameter(idx) => write!(f, "T{idx}"),
+        }
+    }
+}
+
 #[derive(
     Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, TypeInfo, Decode, Encode,
 )]
@@ -145,7 +186,7 @@ pub struct Function {
 }
 
 #[derive(
-    Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, TypeInfo, Decode, Encode,
+    Debug, Clone, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, TypeInfo, Decode, Encode,
 )]
 /// Function visibility.
 // Private not needed since it's not accessible to outer modules.
@@ -168,7 +209,7 @@ impl From<&Visibility> for FunctionVisibility {
 }
 
 #[derive(
-    Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, TypeInfo, Decode, Encode,
+    Debug, Clone, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, TypeInfo, Decode, Encode,
 )]
 /// Friend module.
 pub struct Friend {
@@ -0,0 +1,510 @@
+//! Compact binary encoding of the module ABI.
+//!
+//! The JSON encoding of the [`ModuleAbi`] repeats every identifier and field name, which is too
+//! heavy for the light-client proofs and the on-chain storage. The compact encoding interns the
+//! identifiers and lays the ABI out as tag-length-value records:
+//!
+//! ```text
+//! abi        := magic "MABI" | version | identifiers | record*
+//! identifiers:= ULEB128 count | (ULEB128 length | UTF-8 bytes)*
+//! record     := tag: u8 | ULEB128 length | value
+//! ```
+//!
+//! The records are the module ID (exactly one), the friends, the structs and the functions, in
+//! the order of the [`ModuleAbi`] fields. Identifiers within the records are ULEB128 indices into
+//! the identifier table. Records with an unknown tag are skipped, so the newer encoders can add
+//! records without breaking the older decoders.
+//!
+//! The abilities are encoded as a set, so decoding yields them ordered and without duplicates,
+//! as [`ModuleAbi::from`] produces them.
+
+use crate::abi::{
+    Field, Friend, Function, FunctionVisibility, ModuleAbi, Struct, StructDef, Type, TypeAbilities,
+    TypeAbility,
+};
+use alloc::{boxed::Box, collections::BTreeMap, string::String, vec::Vec};
+use core::fmt;
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::{IdentStr, Identifier},
+    language_storage::ModuleId,
+};
+
+/// Magic prefix of the compact ABI.
+pub const COMPACT_ABI_MAGIC: &[u8; 4] = b"MABI";
+
+/// Version of the compact ABI encoding.
+pub const COMPACT_ABI_VERSION: u8 = 1;
+
+/// Maximum nesting of the decoded types.
+const MAX_TYPE_DEPTH: usize = 128;
+
+const MODULE_TAG: u8 = 0x01;
+const FRIEND_TAG: u8 = 0x02;
+const STRUCT_TAG: u8 = 0x03;
+const FUNCTION_TAG: u8 = 0x04;
+
+/// Error of decoding the compact ABI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompactAbiError {
+    /// The input doesn't start with the [`COMPACT_ABI_MAGIC`].
+    BadMagic,
+    /// The encoding version isn't supported.
+    UnsupportedVersion(u8),
+    /// The input ended in the middle of a value.
+    UnexpectedEnd,
+    /// A record has bytes left over after its value.
+    TrailingBytes,
+    /// The identifier table holds an invalid identifier.
+    InvalidIdentifier,
+    /// The identifier index is out of the identifier table bounds.
+    IdentifierOutOfBounds(u64),
+    /// The type, ability or visibility tag is unknown.
+    InvalidTag(u8),
+    /// The type parameter index doesn't fit `u16`.
+    TypeParameterOutOfRange(u64),
+    /// The types are nested deeper than allowed.
+    TypeTooDeep,
+    /// The module ID record is missing or repeated.
+    InvalidModuleId,
+    /// The JSON ABI can't be parsed.
+    InvalidJson(String),
+}
+
+impl fmt::Display for CompactAbiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BadMagic => write!(f, "Not a compact ABI"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported compact ABI version {version}")
+            }
+            Self::UnexpectedEnd => write!(f, "Unexpected end of the compact ABI"),
+            Self::TrailingBytes => write!(f, "Trailing bytes in the compact ABI record"),
+            Self::InvalidIdentifier => write!(f, "Invalid identifier in the compact ABI"),
+            Self::IdentifierOutOfBounds(idx) => write!(f, "Identifier index {idx} out of bounds"),
+            Self::InvalidTag(tag) => write!(f, "Invalid tag {tag:#x} in the compact ABI"),
+            Self::TypeParameterOutOfRange(idx) => {
+                write!(f, "Type parameter index {idx} out of range")
+            }
+            Self::TypeTooDeep => write!(f, "Types nested deeper than {MAX_TYPE_DEPTH} levels"),
+            Self::InvalidModuleId => write!(f, "Compact ABI needs exactly one module ID"),
+            Self::InvalidJson(msg) => write!(f, "Invalid JSON ABI: {msg}"),
+        }
+    }
+}
+
+/// Encodes the ABI in the compact format.
+pub fn encode(abi: &ModuleAbi) -> Vec<u8> {
+    let mut writer = Writer::default();
+
+    writer.record(MODULE_TAG, |w| w.module_id(&abi.id));
+    for friend in &abi.friends {
+        writer.record(FRIEND_TAG, |w| {
+            w.address(&friend.address);
+            w.identifier(&friend.name);
+        });
+    }
+    for s in &abi.structs {
+        writer.record(STRUCT_TAG, |w| w.struct_(s));
+    }
+    for func in &abi.funcs {
+        writer.record(FUNCTION_TAG, |w| w.function(func));
+    }
+
+    let mut binary = COMPACT_ABI_MAGIC.to_vec();
+    binary.push(COMPACT_ABI_VERSION);
+    write_uleb128(&mut binary, writer.identifiers.len() as u64);
+    for ident in &writer.identifiers {
+        write_uleb128(&mut binary, ident.len() as u64);
+        binary.extend_from_slice(ident.as_bytes());
+    }
+    binary.extend(writer.body);
+    binary
+}
+
+/// Decodes the ABI from the compact format.
+pub fn decode(binary: &[u8]) -> Result<ModuleAbi, CompactAbiError> {
+    let mut reader = Reader::new(binary);
+    if reader.bytes(COMPACT_ABI_MAGIC.len())? != COMPACT_ABI_MAGIC {
+        return Err(CompactAbiError::BadMagic);
+    }
+    let version = reader.u8()?;
+    if version != COMPACT_ABI_VERSION {
+        return Err(CompactAbiError::UnsupportedVersion(version));
+    }
+
+    let mut identifiers = Vec::new();
+    for _ in 0..reader.uleb128()? {
+        let len = reader.len()?;
+        let name = String::from_utf8(reader.bytes(len)?.to_vec())
+            .map_err(|_| CompactAbiError::InvalidIdentifier)?;
+        let ident = Identifier::new(name).map_err(|_| CompactAbiError::InvalidIdentifier)?;
+        identifiers.push(ident);
+    }
+
+    let mut id = None;
+    let mut friends = Vec::new();
+    let mut structs = Vec::new();
+    let mut funcs = Vec::new();
+    while !reader.is_empty() {
+        let tag = reader.u8()?;
+        let len = reader.len()?;
+        let mut record = Reader::new(reader.bytes(len)?);
+        let mut value = RecordReader {
+            reader: &mut record,
+            identifiers: &identifiers,
+        };
+
+        match tag {
+            MODULE_TAG => {
+                if id.replace(value.module_id()?).is_some() {
+                    return Err(CompactAbiError::InvalidModuleId);
+                }
+            }
+            FRIEND_TAG => friends.push(Friend {
+                address: value.address()?,
+                name: value.identifier()?,
+            }),
+            STRUCT_TAG => structs.push(value.struct_()?),
+            FUNCTION_TAG => funcs.push(value.function()?),
+            // Unknown records are skipped.
+            _ => continue,
+        }
+
+        if !record.is_empty() {
+            return Err(CompactAbiError::TrailingBytes);
+        }
+    }
+
+    Ok(ModuleAbi {
+        id: id.ok_or(CompactAbiError::InvalidModuleId)?,
+        friends,
+        structs,
+        funcs,
+    })
+}
+
+/// Converts the JSON ABI to the compact format.
+#[cfg(feature = "std")]
+pub fn from_json(json: &str) -> Result<Vec<u8>, CompactAbiError> {
+    let abi: ModuleAbi =
+        serde_json::from_str(json).map_err(|e| CompactAbiError::InvalidJson(e.to_string()))?;
+    Ok(encode(&abi))
+}
+
+/// Converts the compact ABI to the JSON format.
+#[cfg(feature = "std")]
+pub fn to_json(binary: &[u8]) -> Result<String, CompactAbiError> {
+    let abi = decode(binary)?;
+    Ok(serde_json::to_string(&abi).expect("ABI is always serializable"))
+}
+
+/// Writes the records, interning the identifiers in the order of their first use.
+#[derive(Default)]
+struct Writer<'a> {
+    identifiers: Vec<&'a IdentStr>,
+    indices: BTreeMap<&'a IdentStr, u64>,
+    body: Vec<u8>,
+    record: Vec<u8>,
+}
+
+impl<'a> Writer<'a> {
+    fn record(&mut self, tag: u8, write: impl FnOnce(&mut Self)) {
+        write(self);
+        let value = core::mem::take(&mut self.record);
+        self.body.push(tag);
+        write_uleb128(&mut self.body, value.len() as u64);
+        self.body.extend(value);
+    }
+
+    fn identifier(&mut self, ident: &'a IdentStr) {
+        let next = self.identifiers.len() as u64;
+        let idx = *self.indices.entry(ident).or_insert(next);
+        if idx == next {
+            self.identifiers.push(ident);
+        }
+        write_uleb128(&mut self.record, idx);
+    }
+
+    fn address(&mut self, address: &AccountAddress) {
+        self.record.extend_from_slice(address.as_slice());
+    }
+
+    fn module_id(&mut self, id: &'a ModuleId) {
+        self.address(id.address());
+        self.identifier(id.name());
+    }
+
+    fn abilities(&mut self, abilities: &TypeAbilities) {
+        let mask = abilities
+            .abilities
+            .iter()
+            .fold(0, |mask, ability| mask | ability_bit(ability));
+        self.record.push(mask);
+    }
+
+    fn abilities_list(&mut self, list: &[TypeAbilities]) {
+        write_uleb128(&mut self.record, list.len() as u64);
+        list.iter().for_each(|abilities| self.abilities(abilities));
+    }
+
+    fn types(&mut self, types: &'a [Type]) {
+        write_uleb128(&mut self.record, types.len() as u64);
+        types.iter().for_each(|tp| self.type_(tp));
+    }
+
+    fn type_(&mut self, tp: &'a Type) {
+        match tp {
+            Type::Bool => self.record.push(0),
+            Type::U8 => self.record.push(1),
+            Type::U16 => self.record.push(2),
+            Type::U32 => self.record.push(3),
+            Type::U64 => self.record.push(4),
+            Type::U128 => self.record.push(5),
+            Type::U256 => self.record.push(6),
+            Type::Address => self.record.push(7),
+            Type::Signer => self.record.push(8),
+            Type::Vector(inner) => {
+                self.record.push(9);
+                self.type_(inner);
+            }
+            Type::Struct(def) => {
+                self.record.push(10);
+                self.module_id(&def.id);
+                self.identifier(&def.name);
+                self.types(&def.fields);
+            }
+            Type::Reference(inner) => {
+                self.record.push(11);
+                self.type_(inner);
+            }
+            Type::MutableReference(inner) => {
+                self.record.push(12);
+                self.type_(inner);
+            }
+            Type::TypeParameter(idx) => {
+                self.record.push(13);
+                write_uleb128(&mut self.record, u64::from(*idx));
+            }
+        }
+    }
+
+    fn struct_(&mut self, s: &'a Struct) {
+        self.identifier(&s.name);
+        self.abilities_list(&s.type_parameters);
+        self.abilities(&s.abilities);
+        write_uleb128(&mut self.record, s.fields.len() as u64);
+        for field in &s.fields {
+            self.identifier(&field.name);
+            self.type_(&field.tp);
+        }
+    }
+
+    fn function(&mut self, func: &'a Function) {
+        self.identifier(&func.name);
+        self.record.push(match func.visibility {
+            FunctionVisibility::Public => 0,
+            FunctionVisibility::Friend => 1,
+        });
+        self.abilities_list(&func.type_parameters);
+        self.types(&func.parameters);
+        self.types(&func.returns);
+    }
+}
+
+/// Reads the value of a record.
+struct RecordReader<'a, 'b> {
+    reader: &'b mut Reader<'a>,
+    identifiers: &'b [Identifier],
+}
+
+impl RecordReader<'_, '_> {
+    fn identifier(&mut self) -> Result<Identifier, CompactAbiError> {
+        let idx = self.reader.uleb128()?;
+        usize::try_from(idx)
+            .ok()
+            .and_then(|idx| self.identifiers.get(idx))
+            .cloned()
+            .ok_or(CompactAbiError::IdentifierOutOfBounds(idx))
+    }
+
+    fn address(&mut self) -> Result<AccountAddress, CompactAbiError> {
+        let bytes = self.reader.bytes(AccountAddress::LENGTH)?;
+        Ok(AccountAddress::from_bytes(bytes).expect("address has the right length"))
+    }
+
+    fn module_id(&mut self) -> Result<ModuleId, CompactAbiError> {
+        let address = self.address()?;
+        Ok(ModuleId::new(address, self.identifier()?))
+    }
+
+    fn abilities(&mut self) -> Result<TypeAbilities, CompactAbiError> {
+        let mask = self.reader.u8()?;
+        let abilities: Vec<_> = [
+            TypeAbility::Copy,
+            TypeAbility::Drop,
+            TypeAbility::Store,
+            TypeAbility::Key,
+        ]
+        .into_iter()
+        .filter(|ability| mask & ability_bit(ability) != 0)
+        .collect();
+
+        let known = abilities
+            .iter()
+            .fold(0, |m, ability| m | ability_bit(ability));
+        if known != mask {
+            return Err(CompactAbiError::InvalidTag(mask));
+        }
+        Ok(TypeAbilities { abilities })
+    }
+
+    fn abilities_list(&mut self) -> Result<Vec<TypeAbilities>, CompactAbiError> {
+        (0..self.reader.uleb128()?)
+            .map(|_| self.abilities())
+            .collect()
+    }
+
+    fn types(&mut self, depth: usize) -> Result<Vec<Type>, CompactAbiError> {
+        (0..self.reader.uleb128()?)
+            .map(|_| self.type_(depth))
+            .collect()
+    }
+
+    fn type_(&mut self, depth: usize) -> Result<Type, CompactAbiError> {
+        if depth > MAX_TYPE_DEPTH {
+            return Err(CompactAbiError::TypeTooDeep);
+        }
+
+        let tp = match self.reader.u8()? {
+            0 => Type::Bool,
+            1 => Type::U8,
+            2 => Type::U16,
+            3 => Type::U32,
+            4 => Type::U64,
+            5 => Type::U128,
+            6 => Type::U256,
+            7 => Type::Address,
+            8 => Type::Signer,
+            9 => Type::Vector(Box::new(self.type_(depth + 1)?)),
+            10 => Type::Struct(StructDef {
+                id: self.module_id()?,
+                name: self.identifier()?,
+                fields: self.types(depth + 1)?,
+            }),
+            11 => Type::Reference(Box::new(self.type_(depth + 1)?)),
+            12 => Type::MutableReference(Box::new(self.type_(depth + 1)?)),
+            13 => {
+                let idx = self.reader.uleb128()?;
+                let idx = u16::try_from(idx)
+                    .map_err(|_| CompactAbiError::TypeParameterOutOfRange(idx))?;
+                Type::TypeParameter(idx)
+            }
+            tag => return Err(CompactAbiError::InvalidTag(tag)),
+        };
+        Ok(tp)
+    }
+
+    fn struct_(&mut self) -> Result<Struct, CompactAbiError> {
+        let name = self.identifier()?;
+        let type_parameters = self.abilities_list()?;
+        let abilities = self.abilities()?;
+        let fields = (0..self.reader.uleb128()?)
+            .map(|_| {
+                Ok(Field {
+                    name: self.identifier()?,
+                    tp: self.type_(0)?,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Struct {
+            name,
+            type_parameters,
+            abilities,
+            fields,
+        })
+    }
+
+    fn function(&mut self) -> Result<Function, CompactAbiError> {
+        let name = self.identifier()?;
+        let visibility = match self.reader.u8()? {
+            0 => FunctionVisibility::Public,
+            1 => FunctionVisibility::Friend,
+            tag => return Err(CompactAbiError::InvalidTag(tag)),
+        };
+
+        Ok(Function {
+            name,
+            visibility,
+            type_parameters: self.abilities_list()?,
+            parameters: self.types(0)?,
+            returns: self.types(0)?,
+        })
+    }
+}
+
+fn ability_bit(ability: &TypeAbility) -> u8 {
+    match ability {
+        TypeAbility::Copy => 0x1,
+        TypeAbility::Drop => 0x2,
+        TypeAbility::Store => 0x4,
+        TypeAbility::Key => 0x8,
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn u8(&mut self) -> Result<u8, CompactAbiError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], CompactAbiError> {
+        if self.bytes.len() < len {
+            return Err(CompactAbiError::UnexpectedEnd);
+        }
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    /// Reads a length, which can't exceed the remaining input.
+    fn len(&mut self) -> Result<usize, CompactAbiError> {
+        let len = self.uleb128()?;
+        usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= self.bytes.len())
+            .ok_or(CompactAbiError::UnexpectedEnd)
+    }
+
+    fn uleb128(&mut self) -> Result<u64, CompactAbiError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(CompactAbiError::UnexpectedEnd)
+    }
+}
+
+fn write_uleb128(binary: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        binary.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    binary.push(value as u8);
+}
@@ -0,0 +1,186 @@
+//! Module ABI comparison for module upgrades.
+//!
+//! The [`abi_diff`] function lists all ABI changes between two versions of the module and
+//! classifies each of them as compatible or breaking for the existing dependents:
+//! - added functions, structs and friends are compatible,
+//! - removed or changed public functions and structs are breaking,
+//! - friend functions can only be linked by the declared friends, so their changes are compatible.
+
+use crate::abi::{Friend, Function, FunctionVisibility, ModuleAbi, Struct};
+use alloc::vec::Vec;
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, TypeInfo, Decode, Encode)]
+/// A single change between two module ABI versions.
+pub enum AbiChange {
+    /// The ABIs belong to different modules.
+    ModuleIdChanged {
+        /// Old module ID.
+        old: ModuleId,
+        /// New module ID.
+        new: ModuleId,
+    },
+    /// A new public or friend function was added.
+    FunctionAdded(Identifier),
+    /// A public or friend function was removed.
+    FunctionRemoved {
+        /// Function name.
+        name: Identifier,
+        /// Visibility of the removed function.
+        visibility: FunctionVisibility,
+    },
+    /// Type parameters, parameters or return types of the function were changed.
+    FunctionSignatureChanged {
+        /// Function name.
+        name: Identifier,
+        /// Visibility of the function in the old ABI.
+        visibility: FunctionVisibility,
+    },
+    /// The function visibility was changed.
+    FunctionVisibilityChanged {
+        /// Function name.
+        name: Identifier,
+        /// Old visibility.
+        old: FunctionVisibility,
+        /// New visibility.
+        new: FunctionVisibility,
+    },
+    /// A new struct was added.
+    StructAdded(Identifier),
+    /// A struct was removed.
+    StructRemoved(Identifier),
+    /// Fields, abilities or type parameters of the struct were changed.
+    StructLayoutChanged(Identifier),
+    /// A new friend module was declared.
+    FriendAdded(Friend),
+    /// A friend module declaration was removed.
+    FriendRemoved(Friend),
+}
+
+impl AbiChange {
+    /// Check if the change can break the modules and transactions depending on the old ABI.
+    pub fn is_breaking(&self) -> bool {
+        use FunctionVisibility::{Friend, Public};
+
+        match self {
+            Self::ModuleIdChanged { .. } => true,
+            Self::FunctionRemoved { visibility, .. }
+            | Self::FunctionSignatureChanged { visibility, .. } => *visibility == Public,
+            Self::FunctionVisibilityChanged { old, new, .. } => (old, new) == (&Public, &Friend),
+            Self::StructRemoved(_) | Self::StructLayoutChanged(_) => true,
+            Self::FunctionAdded(_)
+            | Self::StructAdded(_)
+            | Self::FriendAdded(_)
+            | Self::FriendRemoved(_) => false,
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq, TypeInfo, Decode, Encode,
+)]
+/// All changes between two module ABI versions.
+pub struct AbiDiff {
+    /// Changes in the order: module, functions, structs, friends.
+    pub changes: Vec<AbiChange>,
+}
+
+impl AbiDiff {
+    /// Check if the new ABI can replace the old one without breaking any dependents.
+    pub fn is_compatible(&self) -> bool {
+        !self.changes.iter().any(AbiChange::is_breaking)
+    }
+
+    /// Iterates over the breaking changes only.
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &AbiChange> {
+        self.changes.iter().filter(|change| change.is_breaking())
+    }
+}
+
+/// Compares the old and the new module ABI.
+pub fn abi_diff(old: &ModuleAbi, new: &ModuleAbi) -> AbiDiff {
+    let mut changes = Vec::new();
+
+    if old.id != new.id {
+        changes.push(AbiChange::ModuleIdChanged {
+            old: old.id.clone(),
+            new: new.id.clone(),
+        });
+    }
+
+    diff_functions(&old.funcs, &new.funcs, &mut changes);
+    diff_structs(&old.structs, &new.structs, &mut changes);
+    diff_friends(&old.friends, &new.friends, &mut changes);
+
+    AbiDiff { changes }
+}
+
+fn diff_functions(old: &[Function], new: &[Function], changes: &mut Vec<AbiChange>) {
+    for old_func in old {
+        let name = old_func.name.clone();
+        let visibility = old_func.visibility.clone();
+
+        let Some(new_func) = new.iter().find(|func| func.name == old_func.name) else {
+            changes.push(AbiChange::FunctionRemoved { name, visibility });
+            continue;
+        };
+
+        let same_signature = old_func.type_parameters == new_func.type_parameters
+            && old_func.parameters == new_func.parameters
+            && old_func.returns == new_func.returns;
+        if !same_signature {
+            changes.push(AbiChange::FunctionSignatureChanged {
+                name: name.clone(),
+                visibility: visibility.clone(),
+            });
+        }
+
+        if old_func.visibility != new_func.visibility {
+            changes.push(AbiChange::FunctionVisibilityChanged {
+                name,
+                old: visibility,
+                new: new_func.visibility.clone(),
+            });
+        }
+    }
+
+    changes.extend(
+        new.iter()
+            .filter(|new_func| !old.iter().any(|func| func.name == new_func.name))
+            .map(|func| AbiChange::FunctionAdded(func.name.clone())),
+    );
+}
+
+fn diff_structs(old: &[Struct], new: &[Struct], changes: &mut Vec<AbiChange>) {
+    for old_struct in old {
+        match new.iter().find(|st| st.name == old_struct.name) {
+            None => changes.push(AbiChange::StructRemoved(old_struct.name.clone())),
+            Some(new_struct) if new_struct != old_struct => {
+                changes.push(AbiChange::StructLayoutChanged(old_struct.name.clone()))
+            }
+            Some(_) => (),
+        }
+    }
+
+    changes.extend(
+        new.iter()
+            .filter(|new_struct| !old.iter().any(|st| st.name == new_struct.name))
+            .map(|st| AbiChange::StructAdded(st.name.clone())),
+    );
+}
+
+fn diff_friends(old: &[Friend], new: &[Friend], changes: &mut Vec<AbiChange>) {
+    changes.extend(
+        old.iter()
+            .filter(|friend| !new.contains(friend))
+            .map(|friend| AbiChange::FriendRemoved(friend.clone())),
+    );
+    changes.extend(
+        new.iter()
+            .filter(|friend| !old.contains(friend))
+            .map(|friend| AbiChange::FriendAdded(friend.clone())),
+    );
+}
@@ -0,0 +1,86 @@
+//! Host-side queries of the `std::access_control` resources.
+//!
+//! The `AccessControl<T>` resource is kept under the contract address, where `T` is the witness
+//! type defined by the contract module. [`access_control_tag`] builds the resource tag for the
+//! witness and [`AccessControl::decode`] decodes the fetched resource, e.g. with the backend:
+//!
+//! ```ignore
+//! let control = mvm.get_access_control(&holder, witness)?;
+//! let is_minter = control.is_some_and(|control| control.has_role(b"MINTER", &account));
+//! ```
+
+use alloc::{boxed::Box, vec, vec::Vec};
+use move_core_types::{
+    account_address::AccountAddress,
+    ident_str,
+    identifier::IdentStr,
+    language_storage::{StructTag, TypeTag, CORE_CODE_ADDRESS},
+};
+use serde::{Deserialize, Serialize};
+
+/// Name of the module defining the resource.
+pub const ACCESS_CONTROL_MODULE: &IdentStr = ident_str!("access_control");
+
+/// Name of the resource struct.
+pub const ACCESS_CONTROL_STRUCT: &IdentStr = ident_str!("AccessControl");
+
+/// Tag of the `std::access_control::AccessControl<T>` resource for the `witness` type `T`.
+pub fn access_control_tag(witness: StructTag) -> StructTag {
+    StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: ACCESS_CONTROL_MODULE.to_owned(),
+        name: ACCESS_CONTROL_STRUCT.to_owned(),
+        type_params: vec![TypeTag::Struct(Box::new(witness))],
+    }
+}
+
+/// Members of a single role, mirroring the `std::access_control::RoleMembers` struct.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleMembers {
+    pub role: Vec<u8>,
+    pub members: Vec<AccountAddress>,
+}
+
+/// Decoded `std::access_control::AccessControl<T>` resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessControl {
+    pub owner: AccountAddress,
+    /// Nominated owner which has to accept the ownership.
+    pub pending_owner: Option<AccountAddress>,
+    /// Roles with at least one member.
+    pub roles: Vec<RoleMembers>,
+}
+
+impl AccessControl {
+    /// Decodes the BCS-encoded resource.
+    pub fn decode(bytes: &[u8]) -> Result<Self, bcs::Error> {
+        bcs::from_bytes(bytes)
+    }
+
+    /// Checks if the account is the owner.
+    pub fn is_owner(&self, account: &AccountAddress) -> bool {
+        self.owner == *account
+    }
+
+    /// Checks if the account has the role.
+    pub fn has_role(&self, role: &[u8], account: &AccountAddress) -> bool {
+        self.members(role).contains(account)
+    }
+
+    /// Members of the role, in the order they were granted it.
+    pub fn members(&self, role: &[u8]) -> &[AccountAddress] {
+        self.roles
+            .iter()
+            .find(|members| members.role == role)
+            .map_or(&[][..], |members| members.members.as_slice())
+    }
+
+    /// Roles of the account, in the order they were first granted to anyone.
+    pub fn roles_of(&self, account: &AccountAddress) -> Vec<&[u8]> {
+        self.roles
+            .iter()
+            .filter(|members| members.members.contains(account))
+            .map(|members| members.role.as_slice())
+            .collect()
+    }
+}
@@ -0,0 +1,95 @@
+//! Checked conversions between the host account bytes and the Move addresses.
+//!
+//! The Move address length is fixed by the `address20` or `address32` feature of the
+//! `move-core-types`, while the host chain can use accounts of any length. [`AddressCodec`] maps
+//! the `N`-byte host accounts to the Move addresses without patching the `move-core-types`:
+//! - shorter accounts are left-padded with zeros, like the 20-byte Ethereum addresses in the
+//!   32-byte words,
+//! - longer accounts are rejected,
+//! - converting back fails unless the padding bytes are zero, so no address is ever truncated.
+//!
+//! Use the codec at the API boundaries, so a mismatched address length is reported instead of
+//! silently mapping two accounts to the same address.
+
+use core::fmt;
+use move_core_types::account_address::AccountAddress;
+
+/// Error codes for [`AddressCodec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressError {
+    /// The host account doesn't fit the Move address.
+    TooLong { length: usize },
+    /// The host account has a different length than the codec expects.
+    LengthMismatch { expected: usize, provided: usize },
+    /// The Move address has non-zero bytes which don't fit the host account.
+    Truncated,
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooLong { length } => write!(
+                f,
+                "Account of {length} bytes doesn't fit the {}-byte Move address",
+                AccountAddress::LENGTH
+            ),
+            Self::LengthMismatch { expected, provided } => write!(
+                f,
+                "Expected an account of {expected} bytes, got {provided} bytes"
+            ),
+            Self::Truncated => write!(f, "Move address doesn't fit the host account"),
+        }
+    }
+}
+
+/// Converts the `N`-byte host accounts to the Move addresses and back.
+pub struct AddressCodec<const N: usize>;
+
+/// Codec for the 20-byte host accounts, e.g. the Ethereum-style ones.
+pub type Address20Codec = AddressCodec<20>;
+
+/// Codec for the 32-byte host accounts, e.g. the `AccountId32`.
+pub type Address32Codec = AddressCodec<32>;
+
+impl<const N: usize> AddressCodec<N> {
+    /// Number of the zero padding bytes in front of the host account.
+    const PADDING: usize = AccountAddress::LENGTH.saturating_sub(N);
+
+    /// Converts the host account to the Move address.
+    pub fn to_move(account: [u8; N]) -> Result<AccountAddress, AddressError> {
+        if N > AccountAddress::LENGTH {
+            return Err(AddressError::TooLong { length: N });
+        }
+
+        let mut bytes = [0u8; AccountAddress::LENGTH];
+        bytes[Self::PADDING..].copy_from_slice(&account);
+        Ok(AccountAddress::new(bytes))
+    }
+
+    /// Converts the host account of unknown length to the Move address.
+    pub fn to_move_from_slice(account: &[u8]) -> Result<AccountAddress, AddressError> {
+        let account = account
+            .try_into()
+            .map_err(|_| AddressError::LengthMismatch {
+                expected: N,
+                provided: account.len(),
+            })?;
+        Self::to_move(account)
+    }
+
+    /// Converts the Move address back to the host account.
+    pub fn from_move(address: &AccountAddress) -> Result<[u8; N], AddressError> {
+        if N > AccountAddress::LENGTH {
+            return Err(AddressError::TooLong { length: N });
+        }
+
+        let (padding, account) = address.as_slice().split_at(Self::PADDING);
+        if padding.iter().any(|byte| *byte != 0) {
+            return Err(AddressError::Truncated);
+        }
+
+        Ok(account
+            .try_into()
+            .expect("the rest of the address has N bytes"))
+    }
+}
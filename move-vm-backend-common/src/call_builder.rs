@@ -0,0 +1,519 @@
+//! Typed builder for entry function calls.
+//!
+//! The builder collects the call target, type arguments and BCS-encoded arguments and validates
+//! all of them against the module ABI before producing the inputs for the entry function call:
+//! ```ignore
+//! let call = CallBuilder::module("0xCAFE::BasicCoin")
+//!     .function("transfer")
+//!     .arg(&signer_address)?
+//!     .arg(&receiver_address)?
+//!     .arg(&100u64)?
+//!     .build(&abi)?;
+//! ```
+//!
+//! The `String` and `Option<T>` arguments are encoded from the Rust values as well - see
+//! [`crate::std_args`].
+//!
+//! Several calls can be combined into a [`Composition`], where the return values of the earlier
+//! calls are passed as arguments of the later ones:
+//! ```ignore
+//! let mut composition = Composition::new();
+//! let minted = composition.add(CallBuilder::module("0xCAFE::Coin").function("mint")..., &abi)?;
+//! composition.add(
+//!     CallBuilder::module("0xCAFE::Coin")
+//!         .function("deposit")
+//!         .arg(&receiver_address)?
+//!         .result_arg(minted, 0),
+//!     &abi,
+//! )?;
+//! ```
+
+use crate::{
+    abi::{Function, ModuleAbi, Type},
+    std_args,
+};
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+    u256::U256_NUM_BYTES,
+};
+use serde::Serialize;
+
+/// Error codes for [`CallBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallBuilderError {
+    /// The module ID is not in the `address::name` format.
+    InvalidModuleId(String),
+    /// The function name is not a valid identifier.
+    InvalidFunctionName(String),
+    /// The function name was never provided.
+    MissingFunction,
+    /// The ABI belongs to a different module.
+    ModuleMismatch,
+    /// The module has no public function with the given name.
+    FunctionNotFound,
+    /// The number of type arguments doesn't match the function signature.
+    TypeArgumentCountMismatch {
+        /// Number of type parameters in the function signature.
+        expected: usize,
+        /// Number of provided type arguments.
+        provided: usize,
+    },
+    /// The number of arguments doesn't match the function signature.
+    ArgumentCountMismatch {
+        /// Number of parameters in the function signature.
+        expected: usize,
+        /// Number of provided arguments.
+        provided: usize,
+    },
+    /// The argument at the given position doesn't match the parameter type.
+    InvalidArgument(usize),
+    /// The argument at the given position refers to a return value of a call which doesn't
+    /// precede it in the composition.
+    InvalidResultReference(usize),
+    /// The return value passed as the argument at the given position doesn't match the parameter
+    /// type.
+    ResultTypeMismatch(usize),
+    /// The argument cannot be serialized.
+    Serialization,
+}
+
+impl fmt::Display for CallBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidModuleId(id) => write!(f, "Invalid module ID: '{}'", id),
+            Self::InvalidFunctionName(name) => write!(f, "Invalid function name: '{}'", name),
+            Self::MissingFunction => write!(f, "Function name not provided"),
+            Self::ModuleMismatch => write!(f, "ABI belongs to a different module"),
+            Self::FunctionNotFound => write!(f, "Function not found in the module ABI"),
+            Self::TypeArgumentCountMismatch { expected, provided } => write!(
+                f,
+                "Expected {} type arguments, provided {}",
+                expected, provided
+            ),
+            Self::ArgumentCountMismatch { expected, provided } => {
+                write!(f, "Expected {} arguments, provided {}", expected, provided)
+            }
+            Self::InvalidArgument(idx) => write!(f, "Argument {} doesn't match its type", idx),
+            Self::InvalidResultReference(idx) => {
+                write!(f, "Argument {} refers to an unknown return value", idx)
+            }
+            Self::ResultTypeMismatch(idx) => {
+                write!(
+                    f,
+                    "Return value passed as argument {} doesn't match its type",
+                    idx
+                )
+            }
+            Self::Serialization => write!(f, "Argument serialization failed"),
+        }
+    }
+}
+
+/// Validated entry function call inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryCall {
+    /// Module address.
+    pub mod_address: AccountAddress,
+    /// Module name.
+    pub mod_name: Identifier,
+    /// Function name.
+    pub func_name: Identifier,
+    /// Type arguments.
+    pub type_args: Vec<TypeTag>,
+    /// BCS-encoded arguments - signers are represented by their address.
+    pub args: Vec<Vec<u8>>,
+}
+
+impl EntryCall {
+    /// Arguments in the form expected by the entry function execution.
+    pub fn args(&self) -> Vec<&[u8]> {
+        self.args.iter().map(Vec::as_slice).collect()
+    }
+}
+
+/// Argument of a composed call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallArg {
+    /// BCS-encoded value - signers are represented by their address.
+    Value(Vec<u8>),
+    /// Return value of an earlier call in the same composition.
+    Result {
+        /// Index of the call in the composition.
+        call: usize,
+        /// Index of the return value of the call.
+        index: usize,
+    },
+}
+
+/// Validated entry function call inputs within a [`Composition`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComposedCall {
+    /// Module address.
+    pub mod_address: AccountAddress,
+    /// Module name.
+    pub mod_name: Identifier,
+    /// Function name.
+    pub func_name: Identifier,
+    /// Type arguments.
+    pub type_args: Vec<TypeTag>,
+    /// Arguments - the values or references to the return values of the earlier calls.
+    pub args: Vec<CallArg>,
+}
+
+impl ComposedCall {
+    /// Resolves the arguments with the BCS-encoded return values of the earlier calls.
+    ///
+    /// Returns `None` if an argument refers to a missing return value.
+    pub fn resolve_args(&self, results: &[Vec<Vec<u8>>]) -> Option<Vec<Vec<u8>>> {
+        self.args
+            .iter()
+            .map(|arg| match arg {
+                CallArg::Value(value) => Some(value.clone()),
+                CallArg::Result { call, index } => results.get(*call)?.get(*index).cloned(),
+            })
+            .collect()
+    }
+}
+
+impl From<EntryCall> for ComposedCall {
+    fn from(call: EntryCall) -> Self {
+        Self {
+            mod_address: call.mod_address,
+            mod_name: call.mod_name,
+            func_name: call.func_name,
+            type_args: call.type_args,
+            args: call.args.into_iter().map(CallArg::Value).collect(),
+        }
+    }
+}
+
+/// Sequence of entry function calls, where the arguments of a call can refer to the return values
+/// of the earlier calls.
+///
+/// Each call is validated against its module ABI when added - the referenced return values must
+/// have the same type as the parameters they are passed to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Composition {
+    /// Calls in the execution order.
+    calls: Vec<ComposedCall>,
+    /// Return types of the calls - `None` for the values which can't be passed on.
+    returns: Vec<Vec<Option<TypeTag>>>,
+}
+
+impl Composition {
+    /// Creates an empty composition.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validates the call against the module ABI and the earlier calls and appends it.
+    ///
+    /// Returns the index of the call, which can be used to refer to its return values.
+    pub fn add(
+        &mut self,
+        builder: CallBuilder,
+        abi: &ModuleAbi,
+    ) -> Result<usize, CallBuilderError> {
+        let (module_id, func_name, function) = builder.resolve(abi)?;
+        validate_call(function, &builder.type_args, &builder.args, &self.returns)?;
+
+        let returns = function
+            .returns
+            .iter()
+            .map(|tp| passable_type(tp, &builder.type_args))
+            .collect();
+
+        let (mod_address, mod_name) = module_id.into();
+        self.calls.push(ComposedCall {
+            mod_address,
+            mod_name,
+            func_name,
+            type_args: builder.type_args,
+            args: builder.args,
+        });
+        self.returns.push(returns);
+
+        Ok(self.calls.len() - 1)
+    }
+
+    /// Calls in the execution order.
+    pub fn calls(&self) -> &[ComposedCall] {
+        &self.calls
+    }
+
+    /// Consumes the composition and returns the calls in the execution order.
+    pub fn into_calls(self) -> Vec<ComposedCall> {
+        self.calls
+    }
+}
+
+/// Builder for the entry function call inputs.
+#[derive(Debug, Clone, Default)]
+pub struct CallBuilder {
+    /// Module ID in the `address::name` format.
+    module: String,
+    /// Function name.
+    function: Option<String>,
+    /// Type arguments.
+    type_args: Vec<TypeTag>,
+    /// Arguments.
+    args: Vec<CallArg>,
+}
+
+impl CallBuilder {
+    /// Starts building a call to a function in the given module (e.g. `0xCAFE::BasicCoin`).
+    pub fn module(module: &str) -> Self {
+        Self {
+            module: module.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the function name.
+    pub fn function(mut self, name: &str) -> Self {
+        self.function = Some(name.to_string());
+        self
+    }
+
+    /// Appends a type argument.
+    pub fn type_arg(mut self, type_arg: TypeTag) -> Self {
+        self.type_args.push(type_arg);
+        self
+    }
+
+    /// Appends an argument - signers are provided as their [`AccountAddress`].
+    pub fn arg<T: Serialize + ?Sized>(self, arg: &T) -> Result<Self, CallBuilderError> {
+        let arg = bcs::to_bytes(arg).map_err(|_| CallBuilderError::Serialization)?;
+        Ok(self.raw_arg(arg))
+    }
+
+    /// Appends an already BCS-encoded argument.
+    pub fn raw_arg(mut self, arg: Vec<u8>) -> Self {
+        self.args.push(CallArg::Value(arg));
+        self
+    }
+
+    /// Appends the return value of an earlier call as an argument - only valid when the call is
+    /// added to a [`Composition`].
+    pub fn result_arg(mut self, call: usize, index: usize) -> Self {
+        self.args.push(CallArg::Result { call, index });
+        self
+    }
+
+    /// Parses the module ID of the call target.
+    pub fn module_id(&self) -> Result<ModuleId, CallBuilderError> {
+        let invalid_id = || CallBuilderError::InvalidModuleId(self.module.clone());
+
+        let (address, name) = self.module.split_once("::").ok_or_else(invalid_id)?;
+        let address = AccountAddress::from_hex_literal(address).map_err(|_| invalid_id())?;
+        let name = Identifier::new(name).map_err(|_| invalid_id())?;
+
+        Ok(ModuleId::new(address, name))
+    }
+
+    /// Validates the call against the module ABI and produces the entry function call inputs.
+    pub fn build(self, abi: &ModuleAbi) -> Result<EntryCall, CallBuilderError> {
+        let (module_id, func_name, function) = self.resolve(abi)?;
+        validate_call(function, &self.type_args, &self.args, &[])?;
+
+        let args = self
+            .args
+            .into_iter()
+            .enumerate()
+            .map(|(idx, arg)| match arg {
+                CallArg::Value(value) => Ok(value),
+                CallArg::Result { .. } => Err(CallBuilderError::InvalidResultReference(idx)),
+            })
+            .collect::<Result<_, _>>()?;
+
+        let (mod_address, mod_name) = module_id.into();
+        Ok(EntryCall {
+            mod_address,
+            mod_name,
+            func_name,
+            type_args: self.type_args,
+            args,
+        })
+    }
+
+    /// Finds the called function in the module ABI.
+    fn resolve<'a>(
+        &self,
+        abi: &'a ModuleAbi,
+    ) -> Result<(ModuleId, Identifier, &'a Function), CallBuilderError> {
+        let module_id = self.module_id()?;
+        if module_id != abi.id {
+            return Err(CallBuilderError::ModuleMismatch);
+        }
+
+        let func_name = self
+            .function
+            .as_deref()
+            .ok_or(CallBuilderError::MissingFunction)?;
+        let func_name = Identifier::new(func_name)
+            .map_err(|_| CallBuilderError::InvalidFunctionName(func_name.to_string()))?;
+
+        let function = abi
+            .funcs
+            .iter()
+            .find(|func| func.name == func_name)
+            .ok_or(CallBuilderError::FunctionNotFound)?;
+
+        Ok((module_id, func_name, function))
+    }
+}
+
+/// Checks the type arguments and the arguments against the function signature.
+///
+/// The return values are checked against the return types of the earlier calls.
+fn validate_call(
+    function: &Function,
+    type_args: &[TypeTag],
+    args: &[CallArg],
+    returns: &[Vec<Option<TypeTag>>],
+) -> Result<(), CallBuilderError> {
+    if function.type_parameters.len() != type_args.len() {
+        return Err(CallBuilderError::TypeArgumentCountMismatch {
+            expected: function.type_parameters.len(),
+            provided: type_args.len(),
+        });
+    }
+
+    if function.parameters.len() != args.len() {
+        return Err(CallBuilderError::ArgumentCountMismatch {
+            expected: function.parameters.len(),
+            provided: args.len(),
+        });
+    }
+
+    for (idx, (param, arg)) in function.parameters.iter().zip(args).enumerate() {
+        match arg {
+            CallArg::Value(value) => {
+                let mut input = value.as_slice();
+                let is_valid =
+                    check_type(&mut input, param, type_args).is_some() && input.is_empty();
+
+                if !is_valid {
+                    return Err(CallBuilderError::InvalidArgument(idx));
+                }
+            }
+            CallArg::Result { call, index } => {
+                let result = returns
+                    .get(*call)
+                    .and_then(|types| types.get(*index))
+                    .ok_or(CallBuilderError::InvalidResultReference(idx))?;
+
+                let param = passable_type(param, type_args);
+                if param.is_none() || *result != param {
+                    return Err(CallBuilderError::ResultTypeMismatch(idx));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Substitutes the type parameters in the ABI type - `None` for the values which can't be passed
+/// between the calls, i.e. signers and references.
+fn passable_type(tp: &Type, type_args: &[TypeTag]) -> Option<TypeTag> {
+    Some(match tp {
+        Type::Bool => TypeTag::Bool,
+        Type::U8 => TypeTag::U8,
+        Type::U16 => TypeTag::U16,
+        Type::U32 => TypeTag::U32,
+        Type::U64 => TypeTag::U64,
+        Type::U128 => TypeTag::U128,
+        Type::U256 => TypeTag::U256,
+        Type::Address => TypeTag::Address,
+        Type::Vector(inner) => TypeTag::Vector(Box::new(passable_type(inner, type_args)?)),
+        Type::Struct(def) => TypeTag::Struct(Box::new(StructTag {
+            address: *def.id.address(),
+            module: def.id.name().to_owned(),
+            name: def.name.clone(),
+            type_params: def
+                .fields
+                .iter()
+                .map(|tp| passable_type(tp, type_args))
+                .collect::<Option<_>>()?,
+        })),
+        Type::TypeParameter(idx) => match type_args.get(*idx as usize)? {
+            TypeTag::Signer => return None,
+            tag => tag.clone(),
+        },
+        Type::Signer | Type::Reference(_) | Type::MutableReference(_) => return None,
+    })
+}
+
+/// Consumes a BCS-encoded value of the given ABI type from the input.
+fn check_type(input: &mut &[u8], tp: &Type, type_args: &[TypeTag]) -> Option<()> {
+    match tp {
+        Type::Bool => check_bool(input),
+        Type::U8 => take(input, 1),
+        Type::U16 => take(input, 2),
+        Type::U32 => take(input, 4),
+        Type::U64 => take(input, 8),
+        Type::U128 => take(input, 16),
+        Type::U256 => take(input, U256_NUM_BYTES),
+        // Signers are provided as their address.
+        Type::Address | Type::Signer => take(input, AccountAddress::LENGTH),
+        Type::Reference(inner) if matches!(**inner, Type::Signer) => {
+            take(input, AccountAddress::LENGTH)
+        }
+        Type::Vector(inner) => {
+            for _ in 0..read_uleb128(input)? {
+                check_type(input, inner, type_args)?;
+            }
+            Some(())
+        }
+        Type::TypeParameter(idx) => check_type_tag(input, type_args.get(*idx as usize)?),
+        // Only the standard library `String` and `Option<T>` have a known layout.
+        Type::Struct(_) => check_type_tag(input, &passable_type(tp, type_args)?),
+        Type::Reference(_) | Type::MutableReference(_) => None,
+    }
+}
+
+/// Consumes a BCS-encoded value of the given type tag from the input.
+fn check_type_tag(input: &mut &[u8], tag: &TypeTag) -> Option<()> {
+    std_args::check_value(input, tag).ok()
+}
+
+pub(crate) fn check_bool(input: &mut &[u8]) -> Option<()> {
+    let (byte, rest) = input.split_first()?;
+    *input = rest;
+    (*byte <= 1).then_some(())
+}
+
+pub(crate) fn take(input: &mut &[u8], len: usize) -> Option<()> {
+    if input.len() < len {
+        return None;
+    }
+    *input = &input[len..];
+    Some(())
+}
+
+/// Reads the BCS vector length encoded as ULEB128.
+pub(crate) fn read_uleb128(input: &mut &[u8]) -> Option<u64> {
+    let mut value: u64 = 0;
+
+    for shift in (0..64).step_by(7) {
+        let (byte, rest) = input.split_first()?;
+        *input = rest;
+
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,194 @@
+//! Call graph of the compiled modules.
+//!
+//! The graph is built from the `Call` and `CallGeneric` instructions of the function bodies, so
+//! it covers both the calls within a module and the calls into other modules, including the
+//! modules which are not part of the analyzed set. Native functions are nodes without callees.
+//!
+//! Generic calls keep their type arguments in the caller's terms: the caller's type parameters
+//! are written as `T0`, `T1`, ..., and the rest follows the short type tag form, e.g.
+//! `vector<T0>` or `0x1::string::String`.
+
+use crate::type_tag::{display_struct_tag, TagForm};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{
+        Bytecode, CompiledModule, FunctionHandleIndex, SignatureToken, StructHandleIndex,
+    },
+};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag},
+};
+
+/// Function in the call graph.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FunctionId {
+    pub module: ModuleId,
+    pub name: Identifier,
+}
+
+/// Call of the function with the given type arguments.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CallEdge {
+    pub callee: FunctionId,
+    /// Type arguments of the call in the caller's terms - empty for non-generic calls.
+    pub type_args: Vec<String>,
+}
+
+/// Adjacency list of the functions defined in the analyzed modules.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallGraph {
+    /// Distinct calls made by each defined function.
+    pub edges: BTreeMap<FunctionId, BTreeSet<CallEdge>>,
+}
+
+impl CallGraph {
+    /// Builds the call graph of all functions defined in the modules.
+    pub fn from_modules<'a>(modules: impl IntoIterator<Item = &'a CompiledModule>) -> Self {
+        let mut graph = Self::default();
+        for module in modules {
+            graph.add_module(module);
+        }
+        graph
+    }
+
+    /// Adds the functions defined in the module to the graph.
+    pub fn add_module(&mut self, module: &CompiledModule) {
+        for def in module.function_defs() {
+            let caller = function_id(module, def.function);
+            let calls = self.edges.entry(caller).or_default();
+
+            // Native functions don't have any code to inspect.
+            let Some(code) = &def.code else {
+                continue;
+            };
+            for instr in &code.code {
+                match instr {
+                    Bytecode::Call(idx) => {
+                        calls.insert(CallEdge {
+                            callee: function_id(module, *idx),
+                            type_args: Vec::new(),
+                        });
+                    }
+                    Bytecode::CallGeneric(idx) => {
+                        let inst = module.function_instantiation_at(*idx);
+                        let type_args = module
+                            .signature_at(inst.type_parameters)
+                            .0
+                            .iter()
+                            .map(|token| type_name(module, token))
+                            .collect();
+                        calls.insert(CallEdge {
+                            callee: function_id(module, inst.handle),
+                            type_args,
+                        });
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    /// Functions called by the function - empty if the function isn't in the graph.
+    pub fn callees(&self, function: &FunctionId) -> impl Iterator<Item = &CallEdge> {
+        self.edges.get(function).into_iter().flatten()
+    }
+
+    /// Functions in the graph which call the function.
+    pub fn callers<'a>(&'a self, function: &'a FunctionId) -> impl Iterator<Item = &FunctionId> {
+        self.edges
+            .iter()
+            .filter(move |(_, calls)| calls.iter().any(|call| call.callee == *function))
+            .map(|(caller, _)| caller)
+    }
+
+    /// Functions reachable from the function through the calls, excluding the function itself
+    /// unless it's recursive.
+    pub fn reachable(&self, function: &FunctionId) -> BTreeSet<FunctionId> {
+        let mut reachable = BTreeSet::new();
+        let mut pending: Vec<_> = self.callees(function).map(|c| &c.callee).collect();
+
+        while let Some(callee) = pending.pop() {
+            if reachable.insert(callee.clone()) {
+                pending.extend(self.callees(callee).map(|c| &c.callee));
+            }
+        }
+
+        reachable
+    }
+
+    /// Modules called from the functions of the module, excluding the module itself.
+    pub fn module_dependencies(&self, module: &ModuleId) -> BTreeSet<ModuleId> {
+        self.edges
+            .iter()
+            .filter(|(caller, _)| caller.module == *module)
+            .flat_map(|(_, calls)| calls)
+            .map(|call| call.callee.module.clone())
+            .filter(|callee| callee != module)
+            .collect()
+    }
+}
+
+fn function_id(module: &CompiledModule, idx: FunctionHandleIndex) -> FunctionId {
+    let handle = module.function_handle_at(idx);
+
+    FunctionId {
+        module: module.module_id_for_handle(module.module_handle_at(handle.module)),
+        name: module.identifier_at(handle.name).to_owned(),
+    }
+}
+
+/// Formats the signature token in the short type tag form.
+fn type_name(module: &CompiledModule, token: &SignatureToken) -> String {
+    use SignatureToken::*;
+
+    match token {
+        Bool => "bool".to_string(),
+        U8 => "u8".to_string(),
+        U16 => "u16".to_string(),
+        U32 => "u32".to_string(),
+        U64 => "u64".to_string(),
+        U128 => "u128".to_string(),
+        U256 => "u256".to_string(),
+        Address => "address".to_string(),
+        Signer => "signer".to_string(),
+        Vector(inner) => format!("vector<{}>", type_name(module, inner)),
+        Struct(idx) => struct_name(module, *idx, &[]),
+        StructInstantiation(idx, tokens) => struct_name(module, *idx, tokens),
+        TypeParameter(idx) => format!("T{idx}"),
+        Reference(inner) => format!("&{}", type_name(module, inner)),
+        MutableReference(inner) => format!("&mut {}", type_name(module, inner)),
+    }
+}
+
+fn struct_name(
+    module: &CompiledModule,
+    idx: StructHandleIndex,
+    type_params: &[SignatureToken],
+) -> String {
+    let handle = module.struct_handle_at(idx);
+    let defining_module = module.module_handle_at(handle.module);
+    let name = StructTag {
+        address: *module.address_identifier_at(defining_module.address),
+        module: module.identifier_at(defining_module.name).to_owned(),
+        name: module.identifier_at(handle.name).to_owned(),
+        type_params: Vec::new(),
+    };
+    let name = display_struct_tag(&name, TagForm::Short).to_string();
+
+    if type_params.is_empty() {
+        return name;
+    }
+
+    let type_params: Vec<_> = type_params
+        .iter()
+        .map(|token| type_name(module, token))
+        .collect();
+    format!("{}<{}>", name, type_params.join(", "))
+}
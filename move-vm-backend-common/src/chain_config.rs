@@ -0,0 +1,62 @@
+//! Host-side view of the `std::chain_config` resource.
+//!
+//! The [`ChainConfig`] is written under the `std` address at genesis and read by the contracts
+//! through the `std::chain_config` module. Its BCS encoding matches the Move struct, so the
+//! resource can be decoded with [`ChainConfig::decode`] from the storage.
+
+use alloc::{borrow::ToOwned, vec::Vec};
+use move_core_types::{
+    account_address::AccountAddress,
+    ident_str,
+    identifier::IdentStr,
+    language_storage::{StructTag, CORE_CODE_ADDRESS},
+};
+use serde::{Deserialize, Serialize};
+
+/// Name of the module defining the resource.
+pub const CHAIN_CONFIG_MODULE: &IdentStr = ident_str!("chain_config");
+
+/// Name of the resource struct.
+pub const CHAIN_CONFIG_STRUCT: &IdentStr = ident_str!("ChainConfig");
+
+/// Tag of the `std::chain_config::ChainConfig` resource.
+pub fn chain_config_tag() -> StructTag {
+    StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: CHAIN_CONFIG_MODULE.to_owned(),
+        name: CHAIN_CONFIG_STRUCT.to_owned(),
+        type_params: Vec::new(),
+    }
+}
+
+/// Decoded `std::chain_config::ChainConfig` resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainConfig {
+    /// Identifier of the chain.
+    pub chain_id: u64,
+    /// Number of the decimals of the native token.
+    pub token_decimals: u8,
+    /// Length of the account addresses in bytes.
+    pub address_length: u8,
+}
+
+impl ChainConfig {
+    /// Configuration of the chain with the address length of this build.
+    pub fn new(chain_id: u64, token_decimals: u8) -> Self {
+        Self {
+            chain_id,
+            token_decimals,
+            address_length: AccountAddress::LENGTH as u8,
+        }
+    }
+
+    /// Decodes the BCS-encoded resource.
+    pub fn decode(bytes: &[u8]) -> Result<Self, bcs::Error> {
+        bcs::from_bytes(bytes)
+    }
+
+    /// Encodes the resource with BCS.
+    pub fn encode(&self) -> Vec<u8> {
+        bcs::to_bytes(self).expect("chain config serialization can't fail")
+    }
+}
@@ -0,0 +1,51 @@
+//! Stable hashes of the Move code.
+//!
+//! The backend identifies the code by these hashes wherever it needs to - the script allowlist,
+//! the pre-verification queue, the verification markers and the execution receipts - and the
+//! pallets should use the same helpers, so the hashes match on both sides.
+//!
+//! All hashes are Blake2b-256 with a domain separation prefix, so a module and a script with the
+//! same bytes never share a hash:
+//! - a module hash is the hash of `move_code_module::` followed by the module bytecode,
+//! - a script hash is the hash of `move_code_script::` followed by the script bytecode,
+//! - a bundle hash is the hash of `move_code_bundle::` followed by the hashes of its modules in
+//!   the bundle order, so it doesn't depend on the bundle encoding.
+
+use blake2::{digest::consts::U32, Blake2b, Digest};
+
+/// Blake2b-256 hash of a module, a bundle or a script.
+pub type CodeHash = [u8; 32];
+
+const MODULE_HASH_PREFIX: &[u8] = b"move_code_module::";
+const SCRIPT_HASH_PREFIX: &[u8] = b"move_code_script::";
+const BUNDLE_HASH_PREFIX: &[u8] = b"move_code_bundle::";
+
+/// Calculates the hash of the module bytecode.
+pub fn module_hash(bytecode: &[u8]) -> CodeHash {
+    hash_with_prefix(MODULE_HASH_PREFIX, bytecode)
+}
+
+/// Calculates the hash of the script bytecode.
+pub fn script_hash(bytecode: &[u8]) -> CodeHash {
+    hash_with_prefix(SCRIPT_HASH_PREFIX, bytecode)
+}
+
+/// Calculates the hash of the bundle from the bytecode of its modules.
+pub fn bundle_hash<'a>(modules: impl IntoIterator<Item = &'a [u8]>) -> CodeHash {
+    modules
+        .into_iter()
+        .fold(
+            Blake2b::<U32>::new().chain_update(BUNDLE_HASH_PREFIX),
+            |hasher, module| hasher.chain_update(module_hash(module)),
+        )
+        .finalize()
+        .into()
+}
+
+fn hash_with_prefix(prefix: &[u8], bytecode: &[u8]) -> CodeHash {
+    Blake2b::<U32>::new()
+        .chain_update(prefix)
+        .chain_update(bytecode)
+        .finalize()
+        .into()
+}
@@ -0,0 +1,133 @@
+//! Decoding of the canonical abort codes.
+//!
+//! Modules following the `std::error` convention abort with the codes where the third lowest byte
+//! is the [`ErrorCategory`] and the two lowest bytes are the module specific reason, e.g. the
+//! abort code `0x10003` is the reason `3` in the [`ErrorCategory::InvalidArgument`] category.
+//! The upper five bytes are left for the module's own use and are ignored here.
+
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Categories of the canonical abort codes, as defined in `std::error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ErrorCategory {
+    /// Caller specified an invalid argument.
+    InvalidArgument,
+    /// An input or result of a computation is out of range.
+    OutOfRange,
+    /// The system is not in a state where the operation can be performed.
+    InvalidState,
+    /// Request not authenticated.
+    Unauthenticated,
+    /// Caller doesn't have sufficient permission.
+    PermissionDenied,
+    /// A specified resource is not found.
+    NotFound,
+    /// Concurrency conflict, such as read-modify-write conflict.
+    Aborted,
+    /// The resource that a caller tried to create already exists.
+    AlreadyExists,
+    /// Out of gas or other forms of quota.
+    ResourceExhausted,
+    /// Request cancelled by the caller.
+    Cancelled,
+    /// Internal error.
+    Internal,
+    /// Feature not implemented.
+    NotImplemented,
+    /// The service is currently unavailable, a retry could solve the issue.
+    Unavailable,
+}
+
+impl ErrorCategory {
+    /// Category with the given `std::error` code.
+    pub fn from_code(code: u8) -> Option<Self> {
+        let category = match code {
+            0x1 => Self::InvalidArgument,
+            0x2 => Self::OutOfRange,
+            0x3 => Self::InvalidState,
+            0x4 => Self::Unauthenticated,
+            0x5 => Self::PermissionDenied,
+            0x6 => Self::NotFound,
+            0x7 => Self::Aborted,
+            0x8 => Self::AlreadyExists,
+            0x9 => Self::ResourceExhausted,
+            0xA => Self::Cancelled,
+            0xB => Self::Internal,
+            0xC => Self::NotImplemented,
+            0xD => Self::Unavailable,
+            _ => return None,
+        };
+        Some(category)
+    }
+
+    /// The `std::error` code of the category.
+    pub fn code(&self) -> u8 {
+        match self {
+            Self::InvalidArgument => 0x1,
+            Self::OutOfRange => 0x2,
+            Self::InvalidState => 0x3,
+            Self::Unauthenticated => 0x4,
+            Self::PermissionDenied => 0x5,
+            Self::NotFound => 0x6,
+            Self::Aborted => 0x7,
+            Self::AlreadyExists => 0x8,
+            Self::ResourceExhausted => 0x9,
+            Self::Cancelled => 0xA,
+            Self::Internal => 0xB,
+            Self::NotImplemented => 0xC,
+            Self::Unavailable => 0xD,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidArgument => write!(f, "Invalid argument"),
+            Self::OutOfRange => write!(f, "Out of range"),
+            Self::InvalidState => write!(f, "Invalid state"),
+            Self::Unauthenticated => write!(f, "Unauthenticated"),
+            Self::PermissionDenied => write!(f, "Permission denied"),
+            Self::NotFound => write!(f, "Not found"),
+            Self::Aborted => write!(f, "Aborted"),
+            Self::AlreadyExists => write!(f, "Already exists"),
+            Self::ResourceExhausted => write!(f, "Resource exhausted"),
+            Self::Cancelled => write!(f, "Cancelled"),
+            Self::Internal => write!(f, "Internal"),
+            Self::NotImplemented => write!(f, "Not implemented"),
+            Self::Unavailable => write!(f, "Unavailable"),
+        }
+    }
+}
+
+/// Abort code decoded into the category and the reason.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CanonicalError {
+    pub category: ErrorCategory,
+    pub reason: u16,
+}
+
+impl CanonicalError {
+    /// Decodes the abort code, if it follows the `std::error` convention.
+    ///
+    /// Plain module local codes (e.g. `2`) have no category and aren't decoded.
+    pub fn decode(abort_code: u64) -> Option<Self> {
+        let category = ErrorCategory::from_code((abort_code >> 16) as u8)?;
+        Some(Self {
+            category,
+            reason: abort_code as u16,
+        })
+    }
+
+    /// Encodes the error as `std::error::canonical` does.
+    pub fn abort_code(&self) -> u64 {
+        ((self.category.code() as u64) << 16) + self.reason as u64
+    }
+}
+
+impl fmt::Display for CanonicalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (reason {})", self.category, self.reason)
+    }
+}
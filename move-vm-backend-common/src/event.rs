@@ -0,0 +1,138 @@
+//! Canonical event schema and topic hashing rules.
+//!
+//! Events emitted with the `std::indexed_event::emit` native are turned into [`MoveEvent`]s with
+//! topics the indexers can filter on, similarly to the EVM logs:
+//! - the first topic is always the [`event_type_topic`] of the event struct,
+//! - the following topics are the [`event_field_topic`]s of the indexed fields, in the field
+//!   declaration order.
+//!
+//! All topics are Blake2b-256 hashes with a domain separation prefix, so a type topic can never
+//! collide with a field topic.
+//!
+//! Each event also carries its position within the emitting call and its sequence number, which
+//! keeps increasing across the calls and blocks. The consumers can resume from the last seen
+//! sequence number as a cursor.
+
+use alloc::vec::Vec;
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use core::fmt;
+use move_core_types::{effects::Event, language_storage::TypeTag};
+use serde::{Deserialize, Serialize};
+
+/// Blake2b-256 hash identifying the event type or an indexed field value.
+pub type EventTopic = [u8; 32];
+
+/// Maximum number of topics - the event type and up to three indexed fields.
+pub const MAX_EVENT_TOPICS: usize = 4;
+
+/// Domain separation prefix of the event type topics.
+const EVENT_TYPE_TOPIC_PREFIX: &[u8] = b"move_event_type::";
+
+/// Domain separation prefix of the indexed field topics.
+const EVENT_FIELD_TOPIC_PREFIX: &[u8] = b"move_event_field::";
+
+/// Calculates the topic of the event type from its canonical name, where the address is the
+/// full-length lowercase hex without the `0x` prefix (e.g. `00..00cafe::BasicCoin::Minted`).
+pub fn event_type_topic(type_tag: &TypeTag) -> EventTopic {
+    Blake2b::<U32>::new()
+        .chain_update(EVENT_TYPE_TOPIC_PREFIX)
+        .chain_update(type_tag.to_canonical_string().as_bytes())
+        .finalize()
+        .into()
+}
+
+/// Calculates the topic of the BCS-encoded indexed field value.
+pub fn event_field_topic(field: &[u8]) -> EventTopic {
+    Blake2b::<U32>::new()
+        .chain_update(EVENT_FIELD_TOPIC_PREFIX)
+        .chain_update(field)
+        .finalize()
+        .into()
+}
+
+/// Error codes for [`MoveEvent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventError {
+    /// The event doesn't carry the indexed fields in the expected format.
+    InvalidIndexedFields,
+    /// The event has more indexed fields than allowed.
+    TooManyTopics,
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidIndexedFields => write!(f, "Invalid indexed event fields"),
+            Self::TooManyTopics => write!(f, "Too many event topics"),
+        }
+    }
+}
+
+/// Event in the canonical format for the indexers.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct MoveEvent {
+    /// Type of the event struct.
+    pub type_tag: TypeTag,
+    /// The event type topic followed by the indexed field topics.
+    pub topics: Vec<EventTopic>,
+    /// BCS-encoded event struct.
+    pub data: Vec<u8>,
+    /// Position of the event among the events of the emitting call.
+    pub index: u32,
+    /// Position of the event among all events - assigned when the call finishes.
+    pub sequence_number: u64,
+}
+
+impl MoveEvent {
+    /// Creates the event from the BCS-encoded indexed field values.
+    pub fn new(
+        type_tag: TypeTag,
+        indexed_fields: &[Vec<u8>],
+        data: Vec<u8>,
+    ) -> Result<Self, EventError> {
+        if indexed_fields.len() >= MAX_EVENT_TOPICS {
+            return Err(EventError::TooManyTopics);
+        }
+
+        let topics = core::iter::once(event_type_topic(&type_tag))
+            .chain(
+                indexed_fields
+                    .iter()
+                    .map(|field| event_field_topic(field.as_slice())),
+            )
+            .collect();
+
+        Ok(Self {
+            type_tag,
+            topics,
+            data,
+            index: 0,
+            sequence_number: 0,
+        })
+    }
+
+    /// Check if the event matches the topic filter.
+    ///
+    /// Each filter position either requires the exact topic or matches anything if set to
+    /// `None`. The filter can't be longer than the topic list.
+    pub fn matches(&self, filter: &[Option<EventTopic>]) -> bool {
+        filter.len() <= self.topics.len()
+            && filter
+                .iter()
+                .zip(&self.topics)
+                .all(|(expected, topic)| expected.map_or(true, |expected| expected == *topic))
+    }
+}
+
+impl TryFrom<Event> for MoveEvent {
+    type Error = EventError;
+
+    /// Converts the event emitted by the `std::indexed_event::emit` native, which passes the
+    /// indexed field values in the event GUID as a BCS-encoded `vector<vector<u8>>`.
+    fn try_from((guid, _, type_tag, data): Event) -> Result<Self, Self::Error> {
+        let indexed_fields: Vec<Vec<u8>> =
+            bcs::from_bytes(&guid).map_err(|_| EventError::InvalidIndexedFields)?;
+
+        Self::new(type_tag, &indexed_fields, data)
+    }
+}
@@ -0,0 +1,306 @@
+//! Static analysis of the script storage footprint.
+//!
+//! Scripts can't access the global storage directly, so the analyzer follows all function calls
+//! from the script body into the published modules and inspects the global storage instructions
+//! (`MoveTo`, `MoveFrom`, `Exists`, `ImmBorrowGlobal`, `MutBorrowGlobal` and their generic
+//! variants) of every reachable function.
+//!
+//! Type parameters are substituted along the call graph, so the footprint consists of fully
+//! instantiated struct tags. The `acquires` annotations only list the struct definitions without
+//! their instantiations, so the instructions themselves are inspected instead.
+//!
+//! The footprint is an over-approximation - a struct tag might be reported even if the
+//! instruction accessing it never executes.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use core::fmt;
+use move_binary_format::{
+    access::ModuleAccess,
+    binary_views::BinaryIndexedView,
+    file_format::{
+        Bytecode, CompiledModule, CompiledScript, FunctionHandleIndex, FunctionInstantiationIndex,
+        SignatureToken, StructDefInstantiationIndex, StructDefinitionIndex, StructHandleIndex,
+    },
+};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+    vm_status::StatusCode,
+};
+
+/// Maximum number of distinct function instantiations the analyzer visits.
+const MAX_VISITED_FUNCTIONS: usize = 1024;
+
+/// Error codes for the storage footprint analysis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FootprintError {
+    /// The script bytecode can't be deserialized.
+    InvalidScript(StatusCode),
+    /// The called module can't be loaded.
+    ModuleNotFound(ModuleId),
+    /// The called function doesn't exist in the module.
+    FunctionNotFound(ModuleId, Identifier),
+    /// The bytecode refers to an invalid type or index.
+    InvalidBytecode,
+    /// The call graph is too large to be analyzed.
+    TooComplex,
+}
+
+impl fmt::Display for FootprintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidScript(code) => write!(f, "Invalid script: {:?}", code),
+            Self::ModuleNotFound(id) => write!(f, "Module {} not found", id),
+            Self::FunctionNotFound(id, name) => {
+                write!(f, "Function {} not found in module {}", name, id)
+            }
+            Self::InvalidBytecode => write!(f, "Invalid bytecode"),
+            Self::TooComplex => write!(f, "Call graph too large to analyze"),
+        }
+    }
+}
+
+/// Struct tags which the script may access in the global storage.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageFootprint {
+    /// Resources which may only be read (`Exists`, `ImmBorrowGlobal`).
+    pub reads: BTreeSet<StructTag>,
+    /// Resources which may be written (`MoveTo`, `MoveFrom`, `MutBorrowGlobal`).
+    ///
+    /// The written resources are not duplicated in the read set.
+    pub writes: BTreeSet<StructTag>,
+}
+
+impl StorageFootprint {
+    /// Check if the script never touches the global storage.
+    pub fn is_empty(&self) -> bool {
+        self.reads.is_empty() && self.writes.is_empty()
+    }
+
+    fn add_read(&mut self, tag: StructTag) {
+        if !self.writes.contains(&tag) {
+            self.reads.insert(tag);
+        }
+    }
+
+    fn add_write(&mut self, tag: StructTag) {
+        self.reads.remove(&tag);
+        self.writes.insert(tag);
+    }
+}
+
+/// A function instantiation reachable from the script.
+type FunctionCall = (ModuleId, Identifier, Vec<TypeTag>);
+
+/// Analyzes the storage footprint of the script called with the given type arguments.
+///
+/// The `loader` provides the published modules the script calls into.
+pub fn analyze_script_footprint<F>(
+    script_bc: &[u8],
+    type_args: &[TypeTag],
+    mut loader: F,
+) -> Result<StorageFootprint, FootprintError>
+where
+    F: FnMut(&ModuleId) -> Option<CompiledModule>,
+{
+    let script = CompiledScript::deserialize(script_bc)
+        .map_err(|e| FootprintError::InvalidScript(e.major_status()))?;
+
+    let mut footprint = StorageFootprint::default();
+    let mut pending = Vec::new();
+
+    let view = BinaryIndexedView::Script(&script);
+    for instr in &script.code.code {
+        inspect_instruction(&view, instr, type_args, &mut footprint, &mut pending)?;
+    }
+
+    let mut modules: BTreeMap<ModuleId, CompiledModule> = BTreeMap::new();
+    let mut visited: BTreeSet<FunctionCall> = BTreeSet::new();
+
+    while let Some(call) = pending.pop() {
+        if visited.contains(&call) {
+            continue;
+        }
+        if visited.len() >= MAX_VISITED_FUNCTIONS {
+            return Err(FootprintError::TooComplex);
+        }
+
+        let (module_id, func_name, func_type_args) = &call;
+        if !modules.contains_key(module_id) {
+            let module = loader(module_id)
+                .ok_or_else(|| FootprintError::ModuleNotFound(module_id.clone()))?;
+            modules.insert(module_id.clone(), module);
+        }
+        let module = &modules[module_id];
+
+        let func_def = module
+            .function_defs()
+            .iter()
+            .find(|def| {
+                module.identifier_at(module.function_handle_at(def.function).name)
+                    == func_name.as_ident_str()
+            })
+            .ok_or_else(|| {
+                FootprintError::FunctionNotFound(module_id.clone(), func_name.clone())
+            })?;
+
+        // Native functions don't have any code to inspect.
+        if let Some(code) = &func_def.code {
+            let view = BinaryIndexedView::Module(module);
+            for instr in &code.code {
+                inspect_instruction(&view, instr, func_type_args, &mut footprint, &mut pending)?;
+            }
+        }
+
+        visited.insert(call);
+    }
+
+    Ok(footprint)
+}
+
+/// Records the global storage access or the function call of the instruction.
+fn inspect_instruction(
+    view: &BinaryIndexedView,
+    instr: &Bytecode,
+    type_args: &[TypeTag],
+    footprint: &mut StorageFootprint,
+    pending: &mut Vec<FunctionCall>,
+) -> Result<(), FootprintError> {
+    use Bytecode::*;
+
+    match instr {
+        Call(idx) => pending.push(function_call(view, *idx, Vec::new())),
+        CallGeneric(idx) => pending.push(generic_function_call(view, *idx, type_args)?),
+
+        Exists(idx) | ImmBorrowGlobal(idx) => footprint.add_read(struct_def_tag(view, *idx)?),
+        ExistsGeneric(idx) | ImmBorrowGlobalGeneric(idx) => {
+            footprint.add_read(struct_inst_tag(view, *idx, type_args)?)
+        }
+
+        MoveTo(idx) | MoveFrom(idx) | MutBorrowGlobal(idx) => {
+            footprint.add_write(struct_def_tag(view, *idx)?)
+        }
+        MoveToGeneric(idx) | MoveFromGeneric(idx) | MutBorrowGlobalGeneric(idx) => {
+            footprint.add_write(struct_inst_tag(view, *idx, type_args)?)
+        }
+
+        _ => (),
+    }
+
+    Ok(())
+}
+
+fn function_call(
+    view: &BinaryIndexedView,
+    idx: FunctionHandleIndex,
+    type_args: Vec<TypeTag>,
+) -> FunctionCall {
+    let handle = view.function_handle_at(idx);
+    let module_id = view.module_id_for_handle(view.module_handle_at(handle.module));
+    let func_name = view.identifier_at(handle.name).to_owned();
+
+    (module_id, func_name, type_args)
+}
+
+fn generic_function_call(
+    view: &BinaryIndexedView,
+    idx: FunctionInstantiationIndex,
+    type_args: &[TypeTag],
+) -> Result<FunctionCall, FootprintError> {
+    let inst = view.function_instantiation_at(idx);
+    let callee_type_args = view
+        .signature_at(inst.type_parameters)
+        .0
+        .iter()
+        .map(|token| type_tag(view, token, type_args))
+        .collect::<Result<_, _>>()?;
+
+    Ok(function_call(view, inst.handle, callee_type_args))
+}
+
+fn struct_def_tag(
+    view: &BinaryIndexedView,
+    idx: StructDefinitionIndex,
+) -> Result<StructTag, FootprintError> {
+    let def = view
+        .struct_def_at(idx)
+        .map_err(|_| FootprintError::InvalidBytecode)?;
+
+    Ok(struct_tag(view, def.struct_handle, Vec::new()))
+}
+
+fn struct_inst_tag(
+    view: &BinaryIndexedView,
+    idx: StructDefInstantiationIndex,
+    type_args: &[TypeTag],
+) -> Result<StructTag, FootprintError> {
+    let inst = view
+        .struct_instantiation_at(idx)
+        .map_err(|_| FootprintError::InvalidBytecode)?;
+    let def = view
+        .struct_def_at(inst.def)
+        .map_err(|_| FootprintError::InvalidBytecode)?;
+    let type_params = view
+        .signature_at(inst.type_parameters)
+        .0
+        .iter()
+        .map(|token| type_tag(view, token, type_args))
+        .collect::<Result<_, _>>()?;
+
+    Ok(struct_tag(view, def.struct_handle, type_params))
+}
+
+fn struct_tag(
+    view: &BinaryIndexedView,
+    idx: StructHandleIndex,
+    type_params: Vec<TypeTag>,
+) -> StructTag {
+    let handle = view.struct_handle_at(idx);
+    let module = view.module_handle_at(handle.module);
+
+    StructTag {
+        address: *view.address_identifier_at(module.address),
+        module: view.identifier_at(module.name).to_owned(),
+        name: view.identifier_at(handle.name).to_owned(),
+        type_params,
+    }
+}
+
+/// Substitutes the type parameters in the signature token with the concrete type arguments.
+fn type_tag(
+    view: &BinaryIndexedView,
+    token: &SignatureToken,
+    type_args: &[TypeTag],
+) -> Result<TypeTag, FootprintError> {
+    use SignatureToken::*;
+
+    Ok(match token {
+        Bool => TypeTag::Bool,
+        U8 => TypeTag::U8,
+        U16 => TypeTag::U16,
+        U32 => TypeTag::U32,
+        U64 => TypeTag::U64,
+        U128 => TypeTag::U128,
+        U256 => TypeTag::U256,
+        Address => TypeTag::Address,
+        Signer => TypeTag::Signer,
+        Vector(inner) => TypeTag::Vector(Box::new(type_tag(view, inner, type_args)?)),
+        Struct(idx) => TypeTag::Struct(Box::new(struct_tag(view, *idx, Vec::new()))),
+        StructInstantiation(idx, tokens) => {
+            let type_params = tokens
+                .iter()
+                .map(|token| type_tag(view, token, type_args))
+                .collect::<Result<_, _>>()?;
+            TypeTag::Struct(Box::new(struct_tag(view, *idx, type_params)))
+        }
+        TypeParameter(idx) => type_args
+            .get(*idx as usize)
+            .cloned()
+            .ok_or(FootprintError::InvalidBytecode)?,
+        Reference(_) | MutableReference(_) => return Err(FootprintError::InvalidBytecode),
+    })
+}
@@ -5,6 +5,7 @@
 //! This module lays out the basic abstract costing schedule for bytecode instructions and for the
 //! native functions.
 
+use crate::verification_bound::PublishComplexity;
 use alloc::vec;
 use lazy_static::lazy_static;
 use move_binary_format::{
@@ -15,13 +16,159 @@ use move_binary_format::{
     },
     file_format_common::instruction_key,
 };
-use move_core_types::u256;
+use move_core_types::{
+    gas_algebra::{
+        GasQuantity, InternalGas, InternalGasPerByte, InternalGasUnit, NumBytes, UnitDiv,
+    },
+    u256,
+};
 use move_stdlib::natives::GasParameters;
 use move_vm_test_utils::gas_schedule::{new_from_instructions, CostTable, GasCost};
 
+/// Unit for counting the stored resources.
+pub enum Resource {}
+
+/// Unit for counting the nodes of the type arguments.
+pub enum TypeTagNode {}
+
+/// Unit for counting the published function definitions.
+pub enum PublishedFunction {}
+
+/// Unit for counting the published instructions.
+pub enum PublishedInstruction {}
+
+/// Unit for counting the type nodes of the published signatures.
+pub enum SignatureNode {}
+
+/// Unit for counting the nesting levels of the arguments.
+pub enum NestingLevel {}
+
+pub type NumResources = GasQuantity<Resource>;
+
+pub type NumTypeTagNodes = GasQuantity<TypeTagNode>;
+
+pub type InternalGasPerResource = GasQuantity<UnitDiv<InternalGasUnit, Resource>>;
+
+pub type InternalGasPerTypeTagNode = GasQuantity<UnitDiv<InternalGasUnit, TypeTagNode>>;
+
+pub type NumPublishedFunctions = GasQuantity<PublishedFunction>;
+
+pub type NumPublishedInstructions = GasQuantity<PublishedInstruction>;
+
+pub type NumSignatureNodes = GasQuantity<SignatureNode>;
+
+pub type NumNestingLevels = GasQuantity<NestingLevel>;
+
+pub type InternalGasPerPublishedFunction = GasQuantity<UnitDiv<InternalGasUnit, PublishedFunction>>;
+
+pub type InternalGasPerPublishedInstruction =
+    GasQuantity<UnitDiv<InternalGasUnit, PublishedInstruction>>;
+
+pub type InternalGasPerSignatureNode = GasQuantity<UnitDiv<InternalGasUnit, SignatureNode>>;
+
+pub type InternalGasPerNestingLevel = GasQuantity<UnitDiv<InternalGasUnit, NestingLevel>>;
+
 // TODO(rqnsom): tweak the cost
 /// A predefined gas cost to published byte ratio.
-pub const GAS_COST_PER_PUBLISHED_BYTE: u64 = 100;
+pub const GAS_COST_PER_PUBLISHED_BYTE: InternalGasPerByte = InternalGasPerByte::new(100);
+
+// TODO(rqnsom): tweak the cost
+/// A predefined gas cost for each published function definition, which gets verified and linked
+/// on its own.
+pub const GAS_COST_PER_PUBLISHED_FUNCTION: InternalGasPerPublishedFunction =
+    InternalGasPerPublishedFunction::new(1000);
+
+// TODO(rqnsom): tweak the cost
+/// A predefined gas cost for each published instruction, visited by every verifier pass.
+pub const GAS_COST_PER_PUBLISHED_INSTRUCTION: InternalGasPerPublishedInstruction =
+    InternalGasPerPublishedInstruction::new(20);
+
+// TODO(rqnsom): tweak the cost
+/// A predefined gas cost for each type node of the published signatures.
+pub const GAS_COST_PER_SIGNATURE_NODE: InternalGasPerSignatureNode =
+    InternalGasPerSignatureNode::new(50);
+
+// TODO(rqnsom): tweak the refund
+/// A predefined gas refund for each resource deleted from the storage.
+pub const GAS_REFUND_PER_DELETED_RESOURCE: InternalGasPerResource =
+    InternalGasPerResource::new(5000);
+
+// TODO(rqnsom): tweak the refund
+/// A predefined gas refund to freed resource byte ratio.
+///
+/// Resource writes aren't charged per byte, so the ratio doesn't balance any write cost - a
+/// transaction can free more bytes than it paid for. A net gain is only ruled out by capping the
+/// refund of each transaction at the gas it used.
+pub const GAS_REFUND_PER_FREED_BYTE: InternalGasPerByte = InternalGasPerByte::new(50);
+
+// TODO(rqnsom): tweak the cost
+/// A predefined gas cost for each node of the caller-supplied type arguments.
+///
+/// Type arguments are resolved before the execution starts, so they are charged upfront.
+pub const GAS_COST_PER_TYPE_TAG_NODE: InternalGasPerTypeTagNode =
+    InternalGasPerTypeTagNode::new(100);
+
+/// Default maximum nesting depth of the caller-supplied type arguments.
+pub const DEFAULT_MAX_TYPE_TAG_DEPTH: usize = 16;
+
+// TODO(rqnsom): tweak the cost
+/// A predefined gas cost to argument byte ratio, charged when the argument metering is enabled.
+pub const GAS_COST_PER_ARGUMENT_BYTE: InternalGasPerByte = InternalGasPerByte::new(10);
+
+// TODO(rqnsom): tweak the cost
+/// A predefined gas cost for each nesting level of the argument types, charged when the argument
+/// metering is enabled.
+///
+/// Each level is another layer of the layout the argument is deserialized and validated through,
+/// e.g. `vector<vector<u8>>` has three levels.
+pub const GAS_COST_PER_ARGUMENT_NESTING_LEVEL: InternalGasPerNestingLevel =
+    InternalGasPerNestingLevel::new(100);
+
+/// Default amount of memory in bytes a single transaction can allocate for the VM values.
+///
+/// The limit is independent of the gas, since the runtime memory is much scarcer than the time.
+pub const DEFAULT_MEMORY_LIMIT: u64 = 8 * 1024 * 1024;
+
+/// Default number of the host weight units charged as one internal gas unit.
+pub const DEFAULT_HOST_WEIGHT_PER_GAS: u64 = 1;
+
+// TODO(rqnsom): tweak the cost
+/// A predefined gas cost for each loop iteration, charged on top of the branch instruction.
+///
+/// A taken back edge leaves the interpreter's instruction loop and re-enters it at the branch
+/// target, which costs several simple instructions. The cost roughly doubles the price of the
+/// tightest loops, i.e. `while (i < n) { i = i + 1 }`, while the loops with a real body barely
+/// notice it. See the `tight_loop` and `unrolled_loop` benchmarks in `language/benchmarks` - the
+/// time difference per saved iteration is the loop overhead this cost is based on.
+pub const BACK_EDGE_COST: GasCost = GasCost::new(16, 0);
+
+// TODO(rqnsom): tweak the cost
+/// A predefined gas cost for each module the executed code is linked against - the module of the
+/// called function or the modules used by the script, and all their transitive dependencies.
+///
+/// Every module is charged once per execution, even if the loader has it cached from an earlier
+/// one, so long dependency chains are paid for by every caller alike.
+pub const DEPENDENCY_COST: GasCost = GasCost::new(1000, 0);
+
+// TODO(rqnsom): tweak the cost
+/// A predefined gas cost per byte of each module the executed code is linked against, charged on
+/// top of [`DEPENDENCY_COST`].
+pub const DEPENDENCY_BYTE_COST: GasCost = GasCost::new(5, 0);
+
+/// Gas cost of publishing the code of the given length and complexity.
+pub fn publishing_cost(num_bytes: usize, complexity: &PublishComplexity) -> InternalGas {
+    NumBytes::new(num_bytes as u64) * GAS_COST_PER_PUBLISHED_BYTE
+        + NumPublishedFunctions::new(complexity.functions) * GAS_COST_PER_PUBLISHED_FUNCTION
+        + NumPublishedInstructions::new(complexity.instructions)
+            * GAS_COST_PER_PUBLISHED_INSTRUCTION
+        + NumSignatureNodes::new(complexity.signature_nodes) * GAS_COST_PER_SIGNATURE_NODE
+}
+
+/// Gas cost of deserializing and validating the argument of the given length and nesting depth.
+pub fn argument_cost(num_bytes: usize, nesting_levels: u64) -> InternalGas {
+    NumBytes::new(num_bytes as u64) * GAS_COST_PER_ARGUMENT_BYTE
+        + NumNestingLevels::new(nesting_levels) * GAS_COST_PER_ARGUMENT_NESTING_LEVEL
+}
 
 lazy_static! {
     // TODO(rqnsom): tweak the cost for intructions
@@ -149,7 +296,12 @@ lazy_static! {
         // Note that the DiemVM is expecting the table sorted by instruction order.
         instrs.sort_by_key(|cost| instruction_key(&cost.0));
 
-        new_from_instructions(instrs)
+        CostTable {
+            back_edge_cost: BACK_EDGE_COST,
+            dependency_cost: DEPENDENCY_COST,
+            dependency_byte_cost: DEPENDENCY_BYTE_COST,
+            ..new_from_instructions(instrs)
+        }
     };
 }
 
@@ -216,9 +368,96 @@ lazy_static! {
                 swap: move_stdlib::natives::vector::SwapGasParameters { base: 1000.into() },
             },
             balance: move_stdlib::natives::balance::GasParameters {
-                transfer: move_stdlib::natives::balance::TransferGasParameters { base: 1000.into() },
-                cheque_amount: move_stdlib::natives::balance::ChequeAmountGasParameters { base: 1000.into() },
-                total_amount: move_stdlib::natives::balance::TotalAmountGasParameters { base: 1000.into() },
+                // The host balance calls are far more expensive than the VM instructions, so each
+                // touched account is charged on top of the call itself.
+                transfer: move_stdlib::natives::balance::TransferGasParameters {
+                    base: 50_000.into(),
+                    per_account: 25_000.into(),
+                },
+                cheque_amount: move_stdlib::natives::balance::ChequeAmountGasParameters {
+                    base: 1000.into(),
+                    per_account: 5000.into(),
+                },
+                total_amount: move_stdlib::natives::balance::TotalAmountGasParameters {
+                    base: 1000.into(),
+                    per_account: 5000.into(),
+                },
+                total_issuance: move_stdlib::natives::balance::TotalIssuanceGasParameters { base: 1000.into() },
+                account_exists: move_stdlib::natives::balance::AccountExistsGasParameters {
+                    base: 1000.into(),
+                    per_account: 5000.into(),
+                },
+                minimum_balance: move_stdlib::natives::balance::MinimumBalanceGasParameters { base: 1000.into() },
+            },
+            indexed_event: move_stdlib::natives::indexed_event::GasParameters {
+                emit: move_stdlib::natives::indexed_event::EmitGasParameters {
+                    base: 1000.into(),
+                    per_byte_indexed: 1000.into(),
+                    unit_cost: 1000.into(),
+                },
+            },
+            expiry: move_stdlib::natives::expiry::GasParameters {
+                set_expiry: move_stdlib::natives::expiry::SetExpiryGasParameters { base: 1000.into() },
+                clear_expiry: move_stdlib::natives::expiry::ClearExpiryGasParameters { base: 1000.into() },
+            },
+            foreign: move_stdlib::natives::foreign::GasParameters {
+                call: move_stdlib::natives::foreign::CallGasParameters {
+                    base: 1000.into(),
+                    per_byte: 1000.into(),
+                },
+            },
+            xcm: move_stdlib::natives::xcm::GasParameters {
+                send_encoded: move_stdlib::natives::xcm::SendEncodedGasParameters {
+                    base: 1000.into(),
+                    per_byte: 1000.into(),
+                },
+            },
+            bigint: move_stdlib::natives::bigint::GasParameters {
+                add: move_stdlib::natives::bigint::LinearGasParameters {
+                    base: 1000.into(),
+                    per_byte: 1000.into(),
+                },
+                sub: move_stdlib::natives::bigint::LinearGasParameters {
+                    base: 1000.into(),
+                    per_byte: 1000.into(),
+                },
+                mul: move_stdlib::natives::bigint::QuadraticGasParameters {
+                    base: 1000.into(),
+                    per_byte_squared: 10.into(),
+                },
+                div: move_stdlib::natives::bigint::QuadraticGasParameters {
+                    base: 1000.into(),
+                    per_byte_squared: 10.into(),
+                },
+                modulo: move_stdlib::natives::bigint::QuadraticGasParameters {
+                    base: 1000.into(),
+                    per_byte_squared: 10.into(),
+                },
+                pow: move_stdlib::natives::bigint::QuadraticGasParameters {
+                    base: 1000.into(),
+                    per_byte_squared: 10.into(),
+                },
+                compare: move_stdlib::natives::bigint::LinearGasParameters {
+                    base: 1000.into(),
+                    per_byte: 1000.into(),
+                },
+                normalize: move_stdlib::natives::bigint::LinearGasParameters {
+                    base: 1000.into(),
+                    per_byte: 1000.into(),
+                },
+            },
+            account: move_stdlib::natives::account::GasParameters {
+                exists_at: move_stdlib::natives::account::ExistsAtGasParameters { base: 1000.into() },
+                create_account: move_stdlib::natives::account::CreateAccountGasParameters { base: 1000.into() },
+            },
+            random: move_stdlib::natives::random::GasParameters {
+                seed: move_stdlib::natives::random::SeedGasParameters {
+                    base: 1000.into(),
+                    per_byte: 1000.into(),
+                },
+            },
+            timestamp: move_stdlib::natives::timestamp::GasParameters {
+                now_milliseconds: move_stdlib::natives::timestamp::NowMillisecondsGasParameters { base: 1000.into() },
             },
             #[cfg(feature = "testing")]
             unit_test: move_stdlib::natives::unit_test::GasParameters {
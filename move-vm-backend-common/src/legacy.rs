@@ -0,0 +1,286 @@
+//! Re-encoding of the binaries compiled with the 16-byte addresses.
+//!
+//! Older Diem toolchains compile the modules and scripts with the 16-byte addresses, which the
+//! MoveVM configured for the longer addresses can't deserialize. [`upgrade_legacy_binary`]
+//! rewrites such binary for the configured address length, so the historical packages can be
+//! published and executed:
+//! - the addresses in the address identifier pool are left-padded with zeros,
+//! - the address constants, including the ones nested in the vector constants, are re-encoded the
+//!   same way,
+//! - the table headers are updated for the grown tables, everything else is copied unchanged.
+//!
+//! The legacy address arguments of the calls should be converted with the
+//! [`LegacyAddressCodec`], so they match the upgraded addresses.
+
+use crate::address::AddressCodec;
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt;
+use move_binary_format::file_format_common::{BinaryConstants, SerializedType, TableType};
+use move_core_types::account_address::AccountAddress;
+
+/// Address length used by the older Diem toolchains.
+pub const LEGACY_ADDRESS_LENGTH: usize = 16;
+
+/// Converts the legacy addresses to the configured Move addresses and back.
+pub type LegacyAddressCodec = AddressCodec<LEGACY_ADDRESS_LENGTH>;
+
+/// Error codes for [`upgrade_legacy_binary`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegacyBinaryError {
+    /// The binary doesn't start with the Move magic and the version.
+    BadHeader,
+    /// The table headers are malformed or the tables don't cover the table contents.
+    BadTables,
+    /// The address identifier pool isn't made of the legacy addresses.
+    BadAddressPool,
+    /// A constant is malformed or has a type which can't be a constant.
+    BadConstant,
+    /// The configured addresses aren't longer than the legacy ones.
+    Unsupported,
+}
+
+impl fmt::Display for LegacyBinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BadHeader => write!(f, "Invalid binary header"),
+            Self::BadTables => write!(f, "Invalid binary tables"),
+            Self::BadAddressPool => write!(f, "Invalid legacy address pool"),
+            Self::BadConstant => write!(f, "Invalid constant in the constant pool"),
+            Self::Unsupported => write!(
+                f,
+                "Legacy addresses can't be upgraded to {}-byte addresses",
+                AccountAddress::LENGTH
+            ),
+        }
+    }
+}
+
+/// Table header of the binary.
+struct Table {
+    kind: u8,
+    offset: usize,
+    len: usize,
+}
+
+/// Rewrites the module or the script binary compiled with the 16-byte addresses for the
+/// configured address length.
+pub fn upgrade_legacy_binary(binary: &[u8]) -> Result<Vec<u8>, LegacyBinaryError> {
+    if AccountAddress::LENGTH <= LEGACY_ADDRESS_LENGTH {
+        return Err(LegacyBinaryError::Unsupported);
+    }
+
+    // The magic is followed by the version.
+    const PREAMBLE_LEN: usize = BinaryConstants::MOVE_MAGIC_SIZE + 4;
+    if binary.len() < PREAMBLE_LEN || !binary.starts_with(&BinaryConstants::MOVE_MAGIC) {
+        return Err(LegacyBinaryError::BadHeader);
+    }
+
+    let mut reader = Reader::new(binary, PREAMBLE_LEN);
+    let table_count = reader.uleb128().ok_or(LegacyBinaryError::BadHeader)?;
+    let mut tables = Vec::new();
+    for _ in 0..table_count {
+        let kind = reader.u8().ok_or(LegacyBinaryError::BadTables)?;
+        let offset = reader.uleb128().ok_or(LegacyBinaryError::BadTables)?;
+        let len = reader.uleb128().ok_or(LegacyBinaryError::BadTables)?;
+        tables.push(Table {
+            kind,
+            offset: offset as usize,
+            len: len as usize,
+        });
+    }
+
+    // The tables must be contiguous and follow the table headers.
+    tables.sort_by_key(|table| table.offset);
+    let contents_start = reader.position();
+    let mut contents_len = 0;
+    for table in &tables {
+        if table.offset != contents_len {
+            return Err(LegacyBinaryError::BadTables);
+        }
+        contents_len += table.len;
+    }
+    let contents = binary
+        .get(contents_start..contents_start + contents_len)
+        .ok_or(LegacyBinaryError::BadTables)?;
+
+    let mut upgraded_tables = Vec::with_capacity(tables.len());
+    for table in &tables {
+        let data = &contents[table.offset..table.offset + table.len];
+        let data = if table.kind == TableType::ADDRESS_IDENTIFIERS as u8 {
+            upgrade_address_pool(data)?
+        } else if table.kind == TableType::CONSTANT_POOL as u8 {
+            upgrade_constant_pool(data)?
+        } else {
+            data.to_vec()
+        };
+        upgraded_tables.push((table.kind, data));
+    }
+
+    let mut upgraded = binary[..PREAMBLE_LEN].to_vec();
+    write_uleb128(&mut upgraded, table_count);
+    let mut offset = 0;
+    for (kind, data) in &upgraded_tables {
+        upgraded.push(*kind);
+        write_uleb128(&mut upgraded, offset as u64);
+        write_uleb128(&mut upgraded, data.len() as u64);
+        offset += data.len();
+    }
+    for (_, data) in upgraded_tables {
+        upgraded.extend(data);
+    }
+
+    // The module self handle or the script main function follow the tables.
+    upgraded.extend_from_slice(&binary[contents_start + contents_len..]);
+    Ok(upgraded)
+}
+
+fn upgrade_address_pool(data: &[u8]) -> Result<Vec<u8>, LegacyBinaryError> {
+    if data.len() % LEGACY_ADDRESS_LENGTH != 0 {
+        return Err(LegacyBinaryError::BadAddressPool);
+    }
+
+    let mut upgraded =
+        Vec::with_capacity(data.len() / LEGACY_ADDRESS_LENGTH * AccountAddress::LENGTH);
+    for address in data.chunks(LEGACY_ADDRESS_LENGTH) {
+        upgraded.extend_from_slice(upgrade_address(address)?.as_slice());
+    }
+    Ok(upgraded)
+}
+
+fn upgrade_address(address: &[u8]) -> Result<AccountAddress, LegacyBinaryError> {
+    LegacyAddressCodec::to_move_from_slice(address).map_err(|_| LegacyBinaryError::Unsupported)
+}
+
+/// Re-encodes the constants - each is the type, the value length and the BCS-encoded value.
+fn upgrade_constant_pool(data: &[u8]) -> Result<Vec<u8>, LegacyBinaryError> {
+    let mut reader = Reader::new(data, 0);
+    let mut upgraded = Vec::with_capacity(data.len());
+
+    while reader.position() < data.len() {
+        let type_start = reader.position();
+        let ty = constant_type(&mut reader)?;
+        upgraded.extend_from_slice(&data[type_start..reader.position()]);
+
+        let len = reader.uleb128().ok_or(LegacyBinaryError::BadConstant)?;
+        let value = reader
+            .bytes(len as usize)
+            .ok_or(LegacyBinaryError::BadConstant)?;
+
+        let mut value_reader = Reader::new(value, 0);
+        let mut upgraded_value = Vec::with_capacity(value.len());
+        upgrade_value(&ty, &mut value_reader, &mut upgraded_value)?;
+        if value_reader.position() != value.len() {
+            return Err(LegacyBinaryError::BadConstant);
+        }
+
+        write_uleb128(&mut upgraded, upgraded_value.len() as u64);
+        upgraded.extend(upgraded_value);
+    }
+
+    Ok(upgraded)
+}
+
+/// Types which can be constants.
+enum ConstantType {
+    /// Value of the fixed size in bytes.
+    Fixed(usize),
+    Address,
+    Vector(Box<ConstantType>),
+}
+
+fn constant_type(reader: &mut Reader) -> Result<ConstantType, LegacyBinaryError> {
+    let tag = reader.u8().ok_or(LegacyBinaryError::BadConstant)?;
+
+    Ok(match tag {
+        t if t == SerializedType::BOOL as u8 || t == SerializedType::U8 as u8 => {
+            ConstantType::Fixed(1)
+        }
+        t if t == SerializedType::U16 as u8 => ConstantType::Fixed(2),
+        t if t == SerializedType::U32 as u8 => ConstantType::Fixed(4),
+        t if t == SerializedType::U64 as u8 => ConstantType::Fixed(8),
+        t if t == SerializedType::U128 as u8 => ConstantType::Fixed(16),
+        t if t == SerializedType::U256 as u8 => ConstantType::Fixed(32),
+        t if t == SerializedType::ADDRESS as u8 => ConstantType::Address,
+        t if t == SerializedType::VECTOR as u8 => {
+            ConstantType::Vector(Box::new(constant_type(reader)?))
+        }
+        _ => return Err(LegacyBinaryError::BadConstant),
+    })
+}
+
+fn upgrade_value(
+    ty: &ConstantType,
+    reader: &mut Reader,
+    upgraded: &mut Vec<u8>,
+) -> Result<(), LegacyBinaryError> {
+    match ty {
+        ConstantType::Fixed(size) => {
+            let value = reader.bytes(*size).ok_or(LegacyBinaryError::BadConstant)?;
+            upgraded.extend_from_slice(value);
+        }
+        ConstantType::Address => {
+            let address = reader
+                .bytes(LEGACY_ADDRESS_LENGTH)
+                .ok_or(LegacyBinaryError::BadConstant)?;
+            upgraded.extend_from_slice(upgrade_address(address)?.as_slice());
+        }
+        ConstantType::Vector(element) => {
+            let len = reader.uleb128().ok_or(LegacyBinaryError::BadConstant)?;
+            write_uleb128(upgraded, len);
+            for _ in 0..len {
+                upgrade_value(element, reader, upgraded)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Minimal reader of the binary format primitives.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], position: usize) -> Self {
+        Self { bytes, position }
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        let byte = *self.bytes.get(self.position)?;
+        self.position += 1;
+        Some(byte)
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position.checked_add(len)?)?;
+        self.position += len;
+        Some(bytes)
+    }
+
+    fn uleb128(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+fn write_uleb128(binary: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        binary.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    binary.push(value as u8);
+}
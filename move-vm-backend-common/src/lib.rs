@@ -3,8 +3,30 @@
 extern crate alloc;
 
 pub mod abi;
+pub mod abi_compact;
+pub mod abi_diff;
+pub mod access_control;
+pub mod address;
 pub mod bytecode;
+pub mod call_builder;
+pub mod call_graph;
+pub mod chain_config;
+pub mod code_hash;
+pub mod error;
+pub mod event;
+pub mod footprint;
+pub mod legacy;
+pub mod module_deps;
+pub mod move_struct;
+pub mod ordering;
+pub mod receipt;
+pub mod script_lint;
+pub mod std_args;
+pub mod type_tag;
 pub mod types;
+pub mod value;
+pub mod verification_bound;
+pub mod xcm;
 
 #[cfg(feature = "gas_schedule")]
 pub mod gas_schedule;
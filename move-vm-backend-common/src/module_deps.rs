@@ -0,0 +1,115 @@
+//! Dependency checks of the published modules.
+//!
+//! The MoveVM finds the modules depending on themselves or on each other in a cycle only while
+//! linking the bundle, and reports them as the missing dependencies or with a bare
+//! `CYCLIC_MODULE_DEPENDENCY` status. [`check_bundle_dependencies`] looks for them up front and
+//! names the offending modules:
+//! - a self-dependency is a module importing its own handle, e.g. after a manual bytecode edit,
+//! - a cycle is a chain of the bundle modules, each importing the next one, ending where it
+//!   started.
+//!
+//! Only the dependencies among the bundle modules are checked - the published modules can't
+//! depend on the bundle ones, unless the bundle upgrades them, which the MoveVM still catches.
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
+use core::fmt;
+use move_binary_format::{access::ModuleAccess, CompiledModule};
+use move_core_types::language_storage::ModuleId;
+
+/// Error codes for [`check_bundle_dependencies`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyError {
+    /// The module depends on itself.
+    SelfDependency(ModuleId),
+    /// The modules depend on each other in a cycle - each module depends on the next one and the
+    /// last one on the first one.
+    Cycle(Vec<ModuleId>),
+}
+
+impl fmt::Display for DependencyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::SelfDependency(id) => {
+                write!(f, "Module {} depends on itself", id.short_str_lossless())
+            }
+            Self::Cycle(cycle) => {
+                let path = cycle
+                    .iter()
+                    .chain(cycle.first())
+                    .map(ModuleId::short_str_lossless)
+                    .collect::<Vec<String>>()
+                    .join(" -> ");
+                write!(f, "Cyclic dependency between the bundle modules: {path}")
+            }
+        }
+    }
+}
+
+/// Check the modules of the bundle for the self-dependencies and the dependency cycles.
+///
+/// Modules which fail to deserialize are left for the MoveVM to reject.
+pub fn check_bundle_dependencies<'a>(
+    modules: impl IntoIterator<Item = &'a [u8]>,
+) -> Result<(), DependencyError> {
+    let mut graph = BTreeMap::new();
+    let mut order = Vec::new();
+
+    for module in modules {
+        let Ok(module) = CompiledModule::deserialize(module) else {
+            continue;
+        };
+
+        let id = module.self_id();
+        let dependencies: Vec<ModuleId> = module
+            .module_handles()
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != module.self_handle_idx().0 as usize)
+            .map(|(_, handle)| module.module_id_for_handle(handle))
+            .collect();
+
+        if dependencies.contains(&id) {
+            return Err(DependencyError::SelfDependency(id));
+        }
+
+        order.push(id.clone());
+        graph.insert(id, dependencies);
+    }
+
+    let mut done = BTreeSet::new();
+    for id in &order {
+        let mut path = Vec::new();
+        find_cycle(id, &graph, &mut path, &mut done)?;
+    }
+
+    Ok(())
+}
+
+/// Depth-first search of the bundle modules reachable from the given one.
+fn find_cycle(
+    id: &ModuleId,
+    graph: &BTreeMap<ModuleId, Vec<ModuleId>>,
+    path: &mut Vec<ModuleId>,
+    done: &mut BTreeSet<ModuleId>,
+) -> Result<(), DependencyError> {
+    if let Some(start) = path.iter().position(|visited| visited == id) {
+        return Err(DependencyError::Cycle(path[start..].to_vec()));
+    }
+    // Visited already or not in the bundle.
+    let Some(dependencies) = graph.get(id).filter(|_| !done.contains(id)) else {
+        return Ok(());
+    };
+
+    path.push(id.clone());
+    for dependency in dependencies {
+        find_cycle(dependency, graph, path, done)?;
+    }
+    path.pop();
+
+    done.insert(id.clone());
+    Ok(())
+}
@@ -0,0 +1,226 @@
+//! Rust mirror types for Move structs.
+//!
+//! The [`move_struct!`](crate::move_struct) macro declares a Rust struct together with its Move
+//! layout, so the BCS encoding always follows the field order of the declaration:
+//! ```ignore
+//! move_struct! {
+//!     #[derive(Debug, Serialize, Deserialize)]
+//!     pub struct Coin in "0xCAFE::BasicCoin" {
+//!         pub value: u64,
+//!     }
+//! }
+//!
+//! move_struct! {
+//!     #[derive(Debug, Serialize, Deserialize)]
+//!     pub struct Balance in "0xCAFE::BasicCoin" {
+//!         pub coin: Coin,
+//!     }
+//! }
+//! ```
+//!
+//! The Rust struct name must match the Move struct name. Generic Move structs are not supported.
+//! Before decoding the stored resources, the layout should be validated against the published
+//! module ABI with [`MoveStruct::validate`].
+
+use crate::abi::{ModuleAbi, StructDef, Type};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::fmt;
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag},
+    u256::U256,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Error codes for [`MoveStruct`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveStructError {
+    /// The module ID or the struct name is invalid.
+    InvalidStructTag,
+    /// The ABI belongs to a different module.
+    ModuleMismatch,
+    /// The struct doesn't exist in the module ABI.
+    StructNotFound,
+    /// The published struct has a different layout.
+    LayoutMismatch,
+    /// The value can't be encoded or decoded.
+    Serialization,
+}
+
+impl fmt::Display for MoveStructError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidStructTag => write!(f, "Invalid struct tag"),
+            Self::ModuleMismatch => write!(f, "ABI belongs to a different module"),
+            Self::StructNotFound => write!(f, "Struct not found in the module ABI"),
+            Self::LayoutMismatch => write!(f, "Struct layout doesn't match the module ABI"),
+            Self::Serialization => write!(f, "Struct serialization failed"),
+        }
+    }
+}
+
+/// Struct fields in the declaration order.
+pub type Fields = Vec<(&'static str, Type)>;
+
+/// Rust type with a corresponding Move type.
+pub trait MoveType {
+    /// The Move type in the ABI representation.
+    fn move_type() -> Type;
+}
+
+macro_rules! impl_move_type {
+    ($($ty:ty => $move_type:expr),* $(,)?) => {
+        $(
+            impl MoveType for $ty {
+                fn move_type() -> Type {
+                    $move_type
+                }
+            }
+        )*
+    };
+}
+
+impl_move_type!(
+    bool => Type::Bool,
+    u8 => Type::U8,
+    u16 => Type::U16,
+    u32 => Type::U32,
+    u64 => Type::U64,
+    u128 => Type::U128,
+    U256 => Type::U256,
+    AccountAddress => Type::Address,
+);
+
+impl<T: MoveType> MoveType for Vec<T> {
+    fn move_type() -> Type {
+        Type::Vector(Box::new(T::move_type()))
+    }
+}
+
+/// Rust struct mirroring a Move struct - implemented by the [`move_struct!`](crate::move_struct)
+/// macro.
+pub trait MoveStruct: MoveType + Serialize + DeserializeOwned {
+    /// Module ID in the `address::name` format.
+    const MODULE: &'static str;
+    /// Struct name.
+    const NAME: &'static str;
+
+    /// Struct fields in the declaration order.
+    fn fields() -> Fields;
+
+    /// Module ID of the struct.
+    fn module_id() -> Result<ModuleId, MoveStructError> {
+        let (address, name) = Self::MODULE
+            .split_once("::")
+            .ok_or(MoveStructError::InvalidStructTag)?;
+        let address = AccountAddress::from_hex_literal(address)
+            .map_err(|_| MoveStructError::InvalidStructTag)?;
+        let name = Identifier::new(name).map_err(|_| MoveStructError::InvalidStructTag)?;
+
+        Ok(ModuleId::new(address, name))
+    }
+
+    /// Struct tag under which the resources are stored.
+    fn struct_tag() -> Result<StructTag, MoveStructError> {
+        let (address, module) = Self::module_id()?.into();
+        let name = Identifier::new(Self::NAME).map_err(|_| MoveStructError::InvalidStructTag)?;
+
+        Ok(StructTag {
+            address,
+            module,
+            name,
+            type_params: vec![],
+        })
+    }
+
+    /// Checks the struct layout against the published module ABI.
+    fn validate(abi: &ModuleAbi) -> Result<(), MoveStructError> {
+        if abi.id != Self::module_id()? {
+            return Err(MoveStructError::ModuleMismatch);
+        }
+
+        let published = abi
+            .structs
+            .iter()
+            .find(|st| st.name.as_str() == Self::NAME)
+            .ok_or(MoveStructError::StructNotFound)?;
+
+        let fields = Self::fields();
+        let same_layout = published.type_parameters.is_empty()
+            && published.fields.len() == fields.len()
+            && published
+                .fields
+                .iter()
+                .zip(&fields)
+                .all(|(field, (name, tp))| field.name.as_str() == *name && field.tp == *tp);
+
+        if !same_layout {
+            return Err(MoveStructError::LayoutMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Encodes the value in the Move resource format.
+    fn to_move_bytes(&self) -> Result<Vec<u8>, MoveStructError> {
+        bcs::to_bytes(self).map_err(|_| MoveStructError::Serialization)
+    }
+
+    /// Decodes the value from the Move resource format.
+    fn from_move_bytes(bytes: &[u8]) -> Result<Self, MoveStructError> {
+        bcs::from_bytes(bytes).map_err(|_| MoveStructError::Serialization)
+    }
+}
+
+/// Move type of the struct mirror - used by the [`move_struct!`](crate::move_struct) macro.
+#[doc(hidden)]
+pub fn struct_move_type<T: MoveStruct>() -> Type {
+    match T::struct_tag() {
+        Ok(tag) => Type::Struct(StructDef {
+            id: tag.module_id(),
+            name: tag.name,
+            fields: vec![],
+        }),
+        // The invalid struct tag never matches the ABI, so the layout validation fails later.
+        Err(_) => Type::Vector(Box::new(Type::Signer)),
+    }
+}
+
+/// Declares a Rust struct mirroring a Move struct and implements [`MoveStruct`] for it.
+///
+/// See the [`move_struct`](crate::move_struct) module for the example.
+#[macro_export]
+macro_rules! move_struct {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident in $module:literal {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident : $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty),*
+        }
+
+        impl $crate::move_struct::MoveType for $name {
+            fn move_type() -> $crate::abi::Type {
+                $crate::move_struct::struct_move_type::<Self>()
+            }
+        }
+
+        impl $crate::move_struct::MoveStruct for $name {
+            const MODULE: &'static str = $module;
+            const NAME: &'static str = stringify!($name);
+
+            fn fields() -> $crate::move_struct::Fields {
+                $crate::move_struct::Fields::from([
+                    $((
+                        stringify!($field),
+                        <$ty as $crate::move_struct::MoveType>::move_type(),
+                    )),*
+                ])
+            }
+        }
+    };
+}
@@ -0,0 +1,35 @@
+//! Canonical order of the execution receipt entries.
+//!
+//! The receipt roots commit to the order of their leaves, so all nodes list the state writes and
+//! the events of the [`crate::receipt`] in the same order:
+//! - accounts are ordered by the address bytes,
+//! - the modules of an account come before its resources,
+//! - modules are ordered by the name bytes,
+//! - resources are ordered by the BCS-encoded struct tag bytes,
+//! - events keep the emission order, with the fee hook events before the transaction events.
+//!
+//! The struct tag bytes differ from the [`Ord`] of [`StructTag`], since the identifiers are
+//! prefixed with their length, e.g. the resources of the module `Z` come before those of the
+//! module `BasicCoin`.
+//!
+//! The storage itself doesn't depend on the order - the changesets and the accounts keep their
+//! entries in ordered maps, so the applied changes serialize to the same bytes anyway.
+
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use move_core_types::language_storage::StructTag;
+
+/// Key ordering the resource in the canonical order - the BCS-encoded struct tag.
+pub fn resource_order_key(tag: &StructTag) -> Vec<u8> {
+    bcs::to_bytes(tag).expect("struct tags are always serializable")
+}
+
+/// Sorts the resource entries of an account into the canonical order.
+pub fn sort_resources<K, V>(resources: impl IntoIterator<Item = (K, V)>) -> Vec<(K, V)>
+where
+    K: Borrow<StructTag>,
+{
+    let mut resources: Vec<_> = resources.into_iter().collect();
+    resources.sort_by_cached_key(|(tag, _)| resource_order_key(tag.borrow()));
+    resources
+}
@@ -0,0 +1,173 @@
+//! Canonical execution receipts and their hashing scheme.
+//!
+//! Every execution produces an [`ExecutionReceipt`], which light clients and fraud-proof systems
+//! can commit to instead of the full execution results. All hashes are Blake2b-256 with a domain
+//! separation prefix:
+//! - an event leaf is the hash of `move_receipt_event::` followed by the BCS-encoded
+//!   [`MoveEvent`],
+//! - a state write leaf is the hash of `move_receipt_write::` followed by the BCS-encoded
+//!   [`StateWrite`], where the module bytecode is replaced by its
+//!   [`module_hash`](crate::code_hash::module_hash),
+//! - an inner node is the hash of `move_receipt_node::` followed by the left and the right child,
+//! - the receipt hash is the hash of `move_receipt::` followed by the BCS-encoded receipt.
+//!
+//! The roots are binary Merkle trees over the leaves, where the last node of an odd level is
+//! moved to the next level unchanged. The root of an empty list is all zeros. Events and state
+//! writes follow the canonical order of the [`crate::ordering`] module.
+
+use crate::{code_hash::module_hash, event::MoveEvent, ordering};
+use alloc::vec::Vec;
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::{ChangeSet, Op},
+    identifier::Identifier,
+    language_storage::StructTag,
+};
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+
+/// Blake2b-256 hash of the receipt or its parts.
+pub type ReceiptHash = [u8; 32];
+
+/// Root of an empty list of leaves.
+pub const EMPTY_ROOT: ReceiptHash = [0; 32];
+
+const EVENT_LEAF_PREFIX: &[u8] = b"move_receipt_event::";
+const WRITE_LEAF_PREFIX: &[u8] = b"move_receipt_write::";
+const NODE_PREFIX: &[u8] = b"move_receipt_node::";
+const RECEIPT_PREFIX: &[u8] = b"move_receipt::";
+
+/// Commitment to the execution result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Encode, Decode, TypeInfo)]
+pub struct ExecutionReceipt {
+    /// Numeric value of the execution status code.
+    pub status: u64,
+    /// Abort code of the aborted execution.
+    pub abort_code: Option<u64>,
+    /// Gas used.
+    pub gas_used: u64,
+    /// Root of the emitted events - see [`events_root`].
+    pub events_root: ReceiptHash,
+    /// Root of the storage changes - see [`state_diff_root`].
+    pub state_diff_root: ReceiptHash,
+}
+
+impl ExecutionReceipt {
+    /// Calculates the receipt hash.
+    pub fn hash(&self) -> ReceiptHash {
+        let bytes = bcs::to_bytes(self).expect("receipts are always serializable");
+        hash_with_prefix(RECEIPT_PREFIX, &[bytes.as_slice()])
+    }
+}
+
+/// Storage path changed by the execution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatePath {
+    Module(Identifier),
+    Resource(StructTag),
+}
+
+/// Single storage change - the new value or `None` for the deleted ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateWrite {
+    pub address: AccountAddress,
+    pub path: StatePath,
+    pub value: Option<Vec<u8>>,
+}
+
+impl StateWrite {
+    /// Lists the changeset writes in the canonical order.
+    pub fn from_changeset(changeset: &ChangeSet) -> Vec<Self> {
+        let mut writes = Vec::new();
+        for (address, account) in changeset.accounts() {
+            let modules = account
+                .modules()
+                .iter()
+                .map(|(name, op)| (StatePath::Module(name.clone()), op));
+            let resources = ordering::sort_resources(account.resources())
+                .into_iter()
+                .map(|(tag, op)| (StatePath::Resource(tag.clone()), op));
+
+            writes.extend(modules.chain(resources).map(|(path, op)| Self {
+                address: *address,
+                path,
+                value: match op {
+                    Op::New(value) | Op::Modify(value) => Some(value.clone()),
+                    Op::Delete => None,
+                },
+            }));
+        }
+        writes
+    }
+
+    /// Calculates the leaf hash of the write.
+    ///
+    /// The module writes commit to the module hash, so the leaf can be matched against the code
+    /// hash the chain computes for the published bytecode.
+    pub fn leaf_hash(&self) -> ReceiptHash {
+        let bytes = match (&self.path, &self.value) {
+            (StatePath::Module(_), Some(bytecode)) => bcs::to_bytes(&Self {
+                value: Some(module_hash(bytecode).to_vec()),
+                ..self.clone()
+            }),
+            _ => bcs::to_bytes(self),
+        }
+        .expect("state writes are always serializable");
+        hash_with_prefix(WRITE_LEAF_PREFIX, &[bytes.as_slice()])
+    }
+}
+
+/// Calculates the leaf hash of the event.
+pub fn event_leaf_hash(event: &MoveEvent) -> ReceiptHash {
+    let bytes = bcs::to_bytes(event).expect("events are always serializable");
+    hash_with_prefix(EVENT_LEAF_PREFIX, &[bytes.as_slice()])
+}
+
+/// Calculates the Merkle root of the events.
+pub fn events_root(events: &[MoveEvent]) -> ReceiptHash {
+    merkle_root(events.iter().map(event_leaf_hash).collect())
+}
+
+/// Calculates the Merkle root of the changeset writes.
+pub fn state_diff_root(changeset: &ChangeSet) -> ReceiptHash {
+    let leaves = StateWrite::from_changeset(changeset)
+        .iter()
+        .map(StateWrite::leaf_hash)
+        .collect();
+    merkle_root(leaves)
+}
+
+/// Calculates the Merkle root of the leaf hashes.
+pub fn merkle_root(mut level: Vec<ReceiptHash>) -> ReceiptHash {
+    if level.is_empty() {
+        return EMPTY_ROOT;
+    }
+
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => {
+                    hash_with_prefix(NODE_PREFIX, &[left.as_slice(), right.as_slice()])
+                }
+                [single] => *single,
+                _ => unreachable!("chunks have one or two nodes"),
+            })
+            .collect();
+    }
+
+    level[0]
+}
+
+fn hash_with_prefix(prefix: &[u8], parts: &[&[u8]]) -> ReceiptHash {
+    parts
+        .iter()
+        .fold(
+            Blake2b::<U32>::new().chain_update(prefix),
+            |hasher, part| hasher.chain_update(part),
+        )
+        .finalize()
+        .into()
+}
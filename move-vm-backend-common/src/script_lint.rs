@@ -0,0 +1,168 @@
+//! Lints of the compiled scripts.
+//!
+//! The lints point out the common mistakes in the submitted scripts before they are executed:
+//! - type parameters which no signature of the script uses,
+//! - code which can never be reached, e.g. the instructions following an `abort`,
+//! - arithmetic on the script parameters before any condition was checked, i.e. the user input
+//!   isn't validated with an `assert!` and the arithmetic errors abort the transaction instead,
+//! - loops which never exit and call no function, so they only burn the gas.
+//!
+//! The lints are heuristics over the bytecode. A warning doesn't make the script invalid - the
+//! verifier still decides whether the script is accepted.
+
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+use core::fmt;
+use move_binary_format::{
+    access::ScriptAccess,
+    file_format::{Bytecode, CodeOffset, CompiledScript, SignatureToken, TypeParameterIndex},
+};
+use move_core_types::vm_status::StatusCode;
+
+/// Warning reported for a compiled script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintWarning {
+    /// The type parameter isn't used by any signature.
+    UnusedTypeParameter(TypeParameterIndex),
+    /// The instructions starting at the offset can never be executed.
+    UnreachableCode(CodeOffset),
+    /// The arithmetic instruction at the offset operates on a parameter which wasn't checked.
+    UncheckedArithmetic(CodeOffset),
+    /// The loop starting at the offset never exits and calls no function.
+    UnboundedLoop(CodeOffset),
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnusedTypeParameter(idx) => write!(f, "Type parameter {} is never used", idx),
+            Self::UnreachableCode(offset) => write!(f, "Unreachable code at offset {}", offset),
+            Self::UncheckedArithmetic(offset) => write!(
+                f,
+                "Arithmetic on an unchecked parameter at offset {}",
+                offset
+            ),
+            Self::UnboundedLoop(offset) => {
+                write!(f, "Loop at offset {} never exits", offset)
+            }
+        }
+    }
+}
+
+/// Lints the serialized script.
+pub fn lint_script_bytes(script: &[u8]) -> Result<Vec<LintWarning>, StatusCode> {
+    let script = CompiledScript::deserialize(script).map_err(|e| e.major_status())?;
+    Ok(lint_script(&script))
+}
+
+/// Lints the script, the warnings of each lint are ordered by the code offset.
+pub fn lint_script(script: &CompiledScript) -> Vec<LintWarning> {
+    let mut warnings = unused_type_parameters(script);
+    let code = &script.code.code;
+    warnings.extend(unreachable_code(code));
+    warnings.extend(unchecked_arithmetic(script));
+    warnings.extend(unbounded_loops(code));
+    warnings
+}
+
+fn unused_type_parameters(script: &CompiledScript) -> Vec<LintWarning> {
+    let mut used = BTreeSet::new();
+    for signature in &script.signatures {
+        for token in &signature.0 {
+            for token in token.preorder_traversal() {
+                if let SignatureToken::TypeParameter(idx) = token {
+                    used.insert(*idx);
+                }
+            }
+        }
+    }
+
+    (0..script.type_parameters.len() as TypeParameterIndex)
+        .filter(|idx| !used.contains(idx))
+        .map(LintWarning::UnusedTypeParameter)
+        .collect()
+}
+
+/// Reports the first offset of each unreachable run of instructions.
+fn unreachable_code(code: &[Bytecode]) -> Vec<LintWarning> {
+    if code.is_empty() {
+        return vec![];
+    }
+
+    let mut reachable = vec![false; code.len()];
+    let mut pending = vec![0];
+    while let Some(pc) = pending.pop() {
+        if reachable[pc as usize] {
+            continue;
+        }
+        reachable[pc as usize] = true;
+        pending.extend(
+            Bytecode::get_successors(pc, code)
+                .into_iter()
+                .filter(|next| (*next as usize) < code.len()),
+        );
+    }
+
+    (0..code.len())
+        .filter(|&pc| !reachable[pc] && (pc == 0 || reachable[pc - 1]))
+        .map(|pc| LintWarning::UnreachableCode(pc as CodeOffset))
+        .collect()
+}
+
+/// Reports the arithmetic on the parameters which precedes all conditional branches.
+fn unchecked_arithmetic(script: &CompiledScript) -> Vec<LintWarning> {
+    let params = script.signature_at(script.parameters).len();
+    let is_param = |instr: &Bytecode| match instr {
+        Bytecode::CopyLoc(idx) | Bytecode::MoveLoc(idx) => (*idx as usize) < params,
+        _ => false,
+    };
+
+    let code = &script.code.code;
+    let mut warnings = vec![];
+    for (pc, instr) in code.iter().enumerate() {
+        if instr.is_conditional_branch() {
+            break;
+        }
+
+        let arithmetic = matches!(
+            instr,
+            Bytecode::Add
+                | Bytecode::Sub
+                | Bytecode::Mul
+                | Bytecode::Div
+                | Bytecode::Mod
+                | Bytecode::Shl
+        );
+        let operands = &code[pc.saturating_sub(2)..pc];
+        if arithmetic && operands.iter().any(is_param) {
+            warnings.push(LintWarning::UncheckedArithmetic(pc as CodeOffset));
+        }
+    }
+    warnings
+}
+
+/// Reports the loops which can't be left and don't call any function.
+fn unbounded_loops(code: &[Bytecode]) -> Vec<LintWarning> {
+    let mut warnings = vec![];
+    for (pc, instr) in code.iter().enumerate() {
+        let Some(&head) = instr.offset() else {
+            continue;
+        };
+        // Only the back edges close a loop.
+        if head as usize > pc {
+            continue;
+        }
+
+        // A called function can abort, so the loop could still be left through it.
+        let body = head as usize..=pc;
+        let exits = code[body.clone()].iter().any(|instr| match instr {
+            Bytecode::Ret | Bytecode::Abort | Bytecode::Call(_) | Bytecode::CallGeneric(_) => true,
+            _ => instr
+                .offset()
+                .map_or(false, |target| !body.contains(&(*target as usize))),
+        });
+        if !exits {
+            warnings.push(LintWarning::UnboundedLoop(head));
+        }
+    }
+    warnings
+}
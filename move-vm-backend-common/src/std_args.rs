@@ -0,0 +1,162 @@
+//! Arguments of the standard library `String` and `Option<T>` types.
+//!
+//! The `0x1::string::String` and `0x1::option::Option<T>` parameters are passed BCS-encoded like
+//! the other arguments, and their encoding matches the Rust `String` and `Option<T>`:
+//! - `String` is the UTF-8 bytes with the length prefix,
+//! - `Option<T>` is a vector of at most one element, i.e. `0x00` for `None` and `0x01` followed by
+//!   the value for `Some`.
+//!
+//! So the arguments are encoded straight from the Rust values, e.g. with
+//! [`CallBuilder::arg`](crate::call_builder::CallBuilder::arg):
+//! ```ignore
+//! let call = CallBuilder::module("0xCAFE::Profile")
+//!     .function("update")
+//!     .arg(&signer_address)?
+//!     .arg("Alice")?
+//!     .arg(&None::<u64>)?
+//!     .build(&abi)?;
+//! ```
+//!
+//! The MoveVM decodes these arguments by their struct layouts only, so it accepts strings with
+//! invalid UTF-8 and options with several elements, which break the standard library invariants
+//! later on. [`check_arg`] rejects such arguments before the execution.
+
+use crate::call_builder::{check_bool, read_uleb128, take};
+use alloc::{boxed::Box, vec};
+use core::{fmt, str};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag, CORE_CODE_ADDRESS},
+    u256::U256_NUM_BYTES,
+};
+
+/// Error codes for [`check_arg`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StdArgError {
+    /// The `String` value isn't valid UTF-8.
+    InvalidUtf8,
+    /// The `Option` value has more than one element.
+    InvalidOption,
+}
+
+impl fmt::Display for StdArgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidUtf8 => write!(f, "String is not valid UTF-8"),
+            Self::InvalidOption => write!(f, "Option has more than one element"),
+        }
+    }
+}
+
+/// Reason why the value wasn't checked through.
+pub(crate) enum Unchecked {
+    /// The value isn't valid.
+    Invalid(StdArgError),
+    /// The value can't be decoded.
+    Malformed,
+    /// The value holds a struct with an unknown layout.
+    UnknownStruct,
+}
+
+/// Struct tag of `0x1::string::String`.
+pub fn string_tag() -> StructTag {
+    StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: Identifier::new("string").expect("valid identifier"),
+        name: Identifier::new("String").expect("valid identifier"),
+        type_params: vec![],
+    }
+}
+
+/// Struct tag of `0x1::option::Option<inner>`.
+pub fn option_tag(inner: TypeTag) -> StructTag {
+    StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: Identifier::new("option").expect("valid identifier"),
+        name: Identifier::new("Option").expect("valid identifier"),
+        type_params: vec![inner],
+    }
+}
+
+/// Type tag of `0x1::string::String`, e.g. for the type arguments.
+pub fn string_type() -> TypeTag {
+    TypeTag::Struct(Box::new(string_tag()))
+}
+
+/// Type tag of `0x1::option::Option<inner>`, e.g. for the type arguments.
+pub fn option_type(inner: TypeTag) -> TypeTag {
+    TypeTag::Struct(Box::new(option_tag(inner)))
+}
+
+/// Check if the struct tag is `0x1::string::String`.
+pub fn is_string(tag: &StructTag) -> bool {
+    is_std_struct(tag, "string", "String") && tag.type_params.is_empty()
+}
+
+/// Check if the struct tag is `0x1::option::Option<T>`.
+pub fn is_option(tag: &StructTag) -> bool {
+    is_std_struct(tag, "option", "Option") && tag.type_params.len() == 1
+}
+
+fn is_std_struct(tag: &StructTag, module: &str, name: &str) -> bool {
+    tag.address == CORE_CODE_ADDRESS && tag.module.as_str() == module && tag.name.as_str() == name
+}
+
+/// Check the `String` and `Option<T>` values within the BCS-encoded argument of the given type.
+///
+/// Arguments which can't be decoded - the malformed ones and the ones holding other structs - are
+/// left for the MoveVM to reject.
+pub fn check_arg(arg: &[u8], tag: &TypeTag) -> Result<(), StdArgError> {
+    let mut input = arg;
+    match check_value(&mut input, tag) {
+        Err(Unchecked::Invalid(err)) => Err(err),
+        Ok(()) | Err(Unchecked::Malformed) | Err(Unchecked::UnknownStruct) => Ok(()),
+    }
+}
+
+/// Consumes a BCS-encoded value of the given type from the input.
+pub(crate) fn check_value(input: &mut &[u8], tag: &TypeTag) -> Result<(), Unchecked> {
+    let decoded = match tag {
+        TypeTag::Bool => check_bool(input),
+        TypeTag::U8 => take(input, 1),
+        TypeTag::U16 => take(input, 2),
+        TypeTag::U32 => take(input, 4),
+        TypeTag::U64 => take(input, 8),
+        TypeTag::U128 => take(input, 16),
+        TypeTag::U256 => take(input, U256_NUM_BYTES),
+        TypeTag::Address | TypeTag::Signer => take(input, AccountAddress::LENGTH),
+        TypeTag::Vector(inner) => {
+            let len = read_uleb128(input).ok_or(Unchecked::Malformed)?;
+            for _ in 0..len {
+                check_value(input, inner)?;
+            }
+            Some(())
+        }
+        TypeTag::Struct(tag) => return check_struct(input, tag),
+    };
+
+    decoded.ok_or(Unchecked::Malformed)
+}
+
+fn check_struct(input: &mut &[u8], tag: &StructTag) -> Result<(), Unchecked> {
+    if is_string(tag) {
+        let len = read_uleb128(input).ok_or(Unchecked::Malformed)?;
+        let len = usize::try_from(len).map_err(|_| Unchecked::Malformed)?;
+        let bytes = input.get(..len).ok_or(Unchecked::Malformed)?;
+        str::from_utf8(bytes).map_err(|_| Unchecked::Invalid(StdArgError::InvalidUtf8))?;
+        *input = &input[len..];
+        Ok(())
+    } else if is_option(tag) {
+        let len = read_uleb128(input).ok_or(Unchecked::Malformed)?;
+        if len > 1 {
+            return Err(Unchecked::Invalid(StdArgError::InvalidOption));
+        }
+        for _ in 0..len {
+            check_value(input, &tag.type_params[0])?;
+        }
+        Ok(())
+    } else {
+        Err(Unchecked::UnknownStruct)
+    }
+}
@@ -0,0 +1,318 @@
+//! Display forms and parsing of the type tags.
+//!
+//! The type tags are shown in two forms:
+//! - the long form spells out the full-length address, e.g.
+//!   `0x0000000000000000000000000000000000000000000000000000000000000001::string::String`,
+//! - the short form drops the leading zeros of the address, e.g. `0x1::string::String`. The zero
+//!   address is shown as `0x0`.
+//!
+//! Both forms are lowercase hex with the `0x` prefix, and the type parameters are separated with
+//! `, `. The parser accepts both forms, so the displayed tags can always be parsed back.
+//!
+//! Use the short form in the error messages and the long form where the output is compared or
+//! indexed. The event type topics keep hashing [`TypeTag::to_canonical_string`], which is part of
+//! the stable API.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt;
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+};
+
+/// Maximum nesting of the parsed type tags.
+pub const MAX_PARSED_TYPE_DEPTH: usize = 32;
+
+/// Display form of the type tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagForm {
+    /// The address without the leading zeros, e.g. `0x1::string::String`.
+    Short,
+    /// The full-length address.
+    Long,
+}
+
+/// Error codes for [`parse_type_tag`] and [`parse_struct_tag`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeTagError {
+    /// The input ended in the middle of the tag.
+    UnexpectedEnd,
+    /// Unexpected character at the byte offset.
+    UnexpectedToken(usize),
+    /// Invalid address at the byte offset.
+    InvalidAddress(usize),
+    /// Invalid identifier at the byte offset.
+    InvalidIdentifier(usize),
+    /// The tag is nested deeper than the [`MAX_PARSED_TYPE_DEPTH`].
+    TooDeep,
+    /// The parsed tag isn't a struct.
+    NotAStruct,
+}
+
+impl fmt::Display for TypeTagError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "Unexpected end of the type tag"),
+            Self::UnexpectedToken(offset) => {
+                write!(f, "Unexpected character at offset {offset}")
+            }
+            Self::InvalidAddress(offset) => write!(f, "Invalid address at offset {offset}"),
+            Self::InvalidIdentifier(offset) => {
+                write!(f, "Invalid identifier at offset {offset}")
+            }
+            Self::TooDeep => write!(
+                f,
+                "Type tag is nested deeper than {MAX_PARSED_TYPE_DEPTH} levels"
+            ),
+            Self::NotAStruct => write!(f, "Type tag is not a struct"),
+        }
+    }
+}
+
+/// Displays the type tag in the given form.
+pub struct DisplayTypeTag<'a> {
+    tag: &'a TypeTag,
+    form: TagForm,
+}
+
+impl fmt::Display for DisplayTypeTag<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.tag {
+            TypeTag::Bool => write!(f, "bool"),
+            TypeTag::U8 => write!(f, "u8"),
+            TypeTag::U16 => write!(f, "u16"),
+            TypeTag::U32 => write!(f, "u32"),
+            TypeTag::U64 => write!(f, "u64"),
+            TypeTag::U128 => write!(f, "u128"),
+            TypeTag::U256 => write!(f, "u256"),
+            TypeTag::Address => write!(f, "address"),
+            TypeTag::Signer => write!(f, "signer"),
+            TypeTag::Vector(inner) => write!(f, "vector<{}>", display_type_tag(inner, self.form)),
+            TypeTag::Struct(tag) => display_struct_tag(tag, self.form).fmt(f),
+        }
+    }
+}
+
+/// Displays the struct tag in the given form.
+pub struct DisplayStructTag<'a> {
+    tag: &'a StructTag,
+    form: TagForm,
+}
+
+impl fmt::Display for DisplayStructTag<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let tag = self.tag;
+        write!(
+            f,
+            "{}::{}::{}",
+            display_address(&tag.address, self.form),
+            tag.module,
+            tag.name
+        )?;
+
+        if let Some((first, rest)) = tag.type_params.split_first() {
+            write!(f, "<{}", display_type_tag(first, self.form))?;
+            for param in rest {
+                write!(f, ", {}", display_type_tag(param, self.form))?;
+            }
+            write!(f, ">")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Displays the address in the given form.
+pub struct DisplayAddress<'a> {
+    address: &'a AccountAddress,
+    form: TagForm,
+}
+
+impl fmt::Display for DisplayAddress<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x")?;
+
+        let bytes = self.address.as_slice();
+        let skip = match self.form {
+            TagForm::Long => 0,
+            // Keep at least the last byte, so the zero address is shown as `0x0`.
+            TagForm::Short => bytes
+                .iter()
+                .position(|b| *b != 0)
+                .unwrap_or(bytes.len() - 1),
+        };
+
+        let (first, rest) = bytes[skip..].split_first().expect("addresses aren't empty");
+        match self.form {
+            TagForm::Long => write!(f, "{first:02x}")?,
+            TagForm::Short => write!(f, "{first:x}")?,
+        }
+        for b in rest {
+            write!(f, "{b:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Display the type tag in the given form, e.g. `format!("{}", display_type_tag(&tag, form))`.
+pub fn display_type_tag(tag: &TypeTag, form: TagForm) -> DisplayTypeTag {
+    DisplayTypeTag { tag, form }
+}
+
+/// Display the struct tag in the given form.
+pub fn display_struct_tag(tag: &StructTag, form: TagForm) -> DisplayStructTag {
+    DisplayStructTag { tag, form }
+}
+
+/// Display the address in the given form.
+pub fn display_address(address: &AccountAddress, form: TagForm) -> DisplayAddress {
+    DisplayAddress { address, form }
+}
+
+/// Parse the type tag in the short or the long form.
+pub fn parse_type_tag(input: &str) -> Result<TypeTag, TypeTagError> {
+    let mut parser = Parser { input, pos: 0 };
+    let tag = parser.type_tag(0)?;
+    parser.skip_whitespace();
+    if parser.pos != input.len() {
+        return Err(TypeTagError::UnexpectedToken(parser.pos));
+    }
+
+    Ok(tag)
+}
+
+/// Parse the struct tag in the short or the long form.
+pub fn parse_struct_tag(input: &str) -> Result<StructTag, TypeTagError> {
+    match parse_type_tag(input)? {
+        TypeTag::Struct(tag) => Ok(*tag),
+        _ => Err(TypeTagError::NotAStruct),
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn type_tag(&mut self, depth: usize) -> Result<TypeTag, TypeTagError> {
+        if depth > MAX_PARSED_TYPE_DEPTH {
+            return Err(TypeTagError::TooDeep);
+        }
+
+        self.skip_whitespace();
+        if self.rest().starts_with("0x") {
+            return Ok(TypeTag::Struct(Box::new(self.struct_tag(depth)?)));
+        }
+
+        let start = self.pos;
+        let tag = match self.word() {
+            "bool" => TypeTag::Bool,
+            "u8" => TypeTag::U8,
+            "u16" => TypeTag::U16,
+            "u32" => TypeTag::U32,
+            "u64" => TypeTag::U64,
+            "u128" => TypeTag::U128,
+            "u256" => TypeTag::U256,
+            "address" => TypeTag::Address,
+            "signer" => TypeTag::Signer,
+            "vector" => {
+                self.expect('<')?;
+                let inner = self.type_tag(depth + 1)?;
+                self.expect('>')?;
+                TypeTag::Vector(Box::new(inner))
+            }
+            "" if self.pos == self.input.len() => return Err(TypeTagError::UnexpectedEnd),
+            _ => return Err(TypeTagError::UnexpectedToken(start)),
+        };
+        Ok(tag)
+    }
+
+    fn struct_tag(&mut self, depth: usize) -> Result<StructTag, TypeTagError> {
+        let start = self.pos;
+        let address = self.word();
+        let address = AccountAddress::from_hex_literal(address)
+            .map_err(|_| TypeTagError::InvalidAddress(start))?;
+
+        self.expect_str("::")?;
+        let module = self.identifier()?;
+        self.expect_str("::")?;
+        let name = self.identifier()?;
+
+        let mut type_params = Vec::new();
+        self.skip_whitespace();
+        if self.rest().starts_with('<') {
+            self.pos += 1;
+            loop {
+                type_params.push(self.type_tag(depth + 1)?);
+                self.skip_whitespace();
+                if self.rest().starts_with(',') {
+                    self.pos += 1;
+                    continue;
+                }
+                self.expect('>')?;
+                break;
+            }
+        }
+
+        Ok(StructTag {
+            address,
+            module,
+            name,
+            type_params,
+        })
+    }
+
+    fn identifier(&mut self) -> Result<Identifier, TypeTagError> {
+        let start = self.pos;
+        let word = self.word();
+        if word.is_empty() && self.pos == self.input.len() {
+            return Err(TypeTagError::UnexpectedEnd);
+        }
+        Identifier::new(word).map_err(|_| TypeTagError::InvalidIdentifier(start))
+    }
+
+    /// Consume the identifier characters.
+    fn word(&mut self) -> &'a str {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), TypeTagError> {
+        self.skip_whitespace();
+        match self.rest().chars().next() {
+            Some(next) if next == c => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            Some(_) => Err(TypeTagError::UnexpectedToken(self.pos)),
+            None => Err(TypeTagError::UnexpectedEnd),
+        }
+    }
+
+    fn expect_str(&mut self, s: &str) -> Result<(), TypeTagError> {
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            Ok(())
+        } else if self.rest().is_empty() {
+            Err(TypeTagError::UnexpectedEnd)
+        } else {
+            Err(TypeTagError::UnexpectedToken(self.pos))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+}
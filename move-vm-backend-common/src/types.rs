@@ -1,3 +1,4 @@
+use crate::code_hash::{bundle_hash, CodeHash};
 use alloc::vec::Vec;
 use anyhow::{Error, Result};
 use core::convert::TryFrom;
@@ -22,6 +23,11 @@ impl ModuleBundle {
         self.modules
     }
 
+    /// Calculates the bundle hash - see [`crate::code_hash`].
+    pub fn hash(&self) -> CodeHash {
+        bundle_hash(self.modules.iter().map(Vec::as_slice))
+    }
+
     /// Serializes data.
     pub fn encode(self) -> Result<Vec<u8>> {
         bcs::to_bytes(&self).map_err(Error::msg)
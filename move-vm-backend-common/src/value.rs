@@ -0,0 +1,143 @@
+//! Canonical representation of the Move values for the RPC.
+//!
+//! [`CanonicalValue`] is converted from any [`MoveValue`] and can be encoded in two ways:
+//! - SCALE (also in `no_std`), which keeps the exact value kinds,
+//! - any serde format, most notably JSON, with the following schema:
+//!   - `bool` is a JSON boolean,
+//!   - `u8`, `u16` and `u32` are JSON numbers,
+//!   - `u64`, `u128` and `u256` are decimal strings, so no precision is lost in JavaScript,
+//!   - `address` and `signer` are full-length lowercase hex strings with the `0x` prefix,
+//!   - vectors are JSON arrays,
+//!   - structs are JSON objects with the fields in the declaration order.
+//!
+//! Struct field names are only known for the values decoded with the annotated layouts - the
+//! fields of the runtime structs are named by their position (`"0"`, `"1"`, ...).
+
+use crate::type_tag::{display_struct_tag, TagForm};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    u256::U256,
+    value::{MoveStruct, MoveValue},
+};
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+/// Move value in the canonical schema.
+#[derive(Debug, Clone, PartialEq, Eq, TypeInfo, Encode, Decode)]
+pub enum CanonicalValue {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    /// Little-endian bytes of the `u256` value.
+    U256([u8; 32]),
+    Address(AccountAddress),
+    Signer(AccountAddress),
+    Vector(Vec<CanonicalValue>),
+    Struct(CanonicalStruct),
+}
+
+/// Move struct in the canonical schema.
+#[derive(Debug, Clone, PartialEq, Eq, TypeInfo, Encode, Decode)]
+pub struct CanonicalStruct {
+    /// Struct type name in the long form (e.g. `0x00..00cafe::BasicCoin::Coin`), if it's known.
+    pub type_name: Option<String>,
+    /// Field names and values in the declaration order.
+    pub fields: Vec<(String, CanonicalValue)>,
+}
+
+impl From<MoveValue> for CanonicalValue {
+    fn from(value: MoveValue) -> Self {
+        match value {
+            MoveValue::Bool(v) => Self::Bool(v),
+            MoveValue::U8(v) => Self::U8(v),
+            MoveValue::U16(v) => Self::U16(v),
+            MoveValue::U32(v) => Self::U32(v),
+            MoveValue::U64(v) => Self::U64(v),
+            MoveValue::U128(v) => Self::U128(v),
+            MoveValue::U256(v) => Self::U256(v.to_le_bytes()),
+            MoveValue::Address(v) => Self::Address(v),
+            MoveValue::Signer(v) => Self::Signer(v),
+            MoveValue::Vector(values) => Self::Vector(values.into_iter().map(Self::from).collect()),
+            MoveValue::Struct(s) => Self::Struct(s.into()),
+        }
+    }
+}
+
+impl From<MoveStruct> for CanonicalStruct {
+    fn from(value: MoveStruct) -> Self {
+        match value {
+            MoveStruct::Runtime(values) => Self {
+                type_name: None,
+                fields: values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, value)| (i.to_string(), value.into()))
+                    .collect(),
+            },
+            MoveStruct::WithFields(fields) => Self {
+                type_name: None,
+                fields: named_fields(fields),
+            },
+            MoveStruct::WithTypes { type_, fields } => Self {
+                type_name: Some(display_struct_tag(&type_, TagForm::Long).to_string()),
+                fields: named_fields(fields),
+            },
+        }
+    }
+}
+
+impl Serialize for CanonicalValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Bool(v) => serializer.serialize_bool(*v),
+            Self::U8(v) => serializer.serialize_u8(*v),
+            Self::U16(v) => serializer.serialize_u16(*v),
+            Self::U32(v) => serializer.serialize_u32(*v),
+            Self::U64(v) => serializer.collect_str(v),
+            Self::U128(v) => serializer.collect_str(v),
+            Self::U256(v) => serializer.collect_str(&U256::from_le_bytes(v)),
+            Self::Address(v) | Self::Signer(v) => serializer.serialize_str(&address_string(v)),
+            Self::Vector(values) => serializer.collect_seq(values),
+            Self::Struct(s) => s.serialize(serializer),
+        }
+    }
+}
+
+impl Serialize for CanonicalStruct {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for (name, value) in &self.fields {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+impl CanonicalValue {
+    /// Encodes the value in the canonical JSON schema.
+    #[cfg(feature = "std")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("canonical values are always serializable")
+    }
+}
+
+fn named_fields(fields: Vec<(Identifier, MoveValue)>) -> Vec<(String, CanonicalValue)> {
+    fields
+        .into_iter()
+        .map(|(name, value)| (name.into_string(), value.into()))
+        .collect()
+}
+
+fn address_string(address: &AccountAddress) -> String {
+    format!("0x{}", address.to_hex())
+}
@@ -0,0 +1,223 @@
+//! Upper bound of the module verification cost.
+//!
+//! The bytecode verifier runs before any gas is charged for the published code, so a module built
+//! to be expensive to verify could get the verification work underpriced. The bound lets the
+//! pallet charge the worst case as the pre-dispatch weight and refund the difference afterwards.
+//!
+//! The bound is computed from the module tables and the function code only, without running any
+//! verifier pass:
+//! - the bounds, duplication and signature checks are linear in the number of the table entries
+//!   and the signature type nodes,
+//! - the abstract interpretation passes (type, locals and reference safety) visit every
+//!   instruction with a state proportional to the number of locals, and revisit the code for
+//!   every loop until the state reaches the fixpoint - at most once per back edge.
+//!
+//! The costs are expressed in the gas units, so the pallet converts them to the weight the same
+//! way as the execution gas.
+//!
+//! The same tables also report the [`PublishComplexity`] of the code, which the publishing gas
+//! covers on top of the code length - see [`crate::gas_schedule::publishing_cost`].
+
+use crate::types::ModuleBundle;
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{Bytecode, CompiledModule, Signature, StructFieldInformation},
+};
+use move_core_types::vm_status::StatusCode;
+
+/// Gas charged for every table entry and signature type node.
+pub const VERIFICATION_COST_PER_TABLE_ENTRY: u64 = 2;
+
+/// Gas charged for every instruction visit of the abstract interpretation passes.
+pub const VERIFICATION_COST_PER_CODE_UNIT: u64 = 1;
+
+/// Worst-case verification work of a module or a bundle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerificationBound {
+    /// Number of the table entries and the signature type nodes.
+    pub table_entries: u64,
+    /// Maximum number of the instruction visits, weighted by the number of locals.
+    pub code_units: u64,
+}
+
+impl VerificationBound {
+    /// Upper bound of the verification gas.
+    pub fn gas(&self) -> u64 {
+        self.table_entries
+            .saturating_mul(VERIFICATION_COST_PER_TABLE_ENTRY)
+            .saturating_add(
+                self.code_units
+                    .saturating_mul(VERIFICATION_COST_PER_CODE_UNIT),
+            )
+    }
+
+    fn merge(self, other: Self) -> Self {
+        Self {
+            table_entries: self.table_entries.saturating_add(other.table_entries),
+            code_units: self.code_units.saturating_add(other.code_units),
+        }
+    }
+}
+
+/// Complexity of the published code which is priced on top of its length.
+///
+/// Small modules can still be expensive to verify and link, so the length alone underprices them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PublishComplexity {
+    /// Number of the function definitions.
+    pub functions: u64,
+    /// Number of the instructions of all function bodies.
+    pub instructions: u64,
+    /// Number of the type nodes of all signatures.
+    pub signature_nodes: u64,
+}
+
+impl PublishComplexity {
+    fn merge(self, other: Self) -> Self {
+        Self {
+            functions: self.functions.saturating_add(other.functions),
+            instructions: self.instructions.saturating_add(other.instructions),
+            signature_nodes: self.signature_nodes.saturating_add(other.signature_nodes),
+        }
+    }
+}
+
+/// Compute the verification bound of the serialized module.
+///
+/// Modules which fail to deserialize are rejected before the verification, so they are reported
+/// with the deserialization error.
+pub fn module_verification_bound(module: &[u8]) -> Result<VerificationBound, StatusCode> {
+    let module = CompiledModule::deserialize(module).map_err(|e| e.major_status())?;
+    Ok(verification_bound(&module))
+}
+
+/// Compute the verification bound of the serialized [`ModuleBundle`].
+pub fn bundle_verification_bound(bundle: &[u8]) -> Result<VerificationBound, StatusCode> {
+    let bundle =
+        ModuleBundle::try_from(bundle).map_err(|_| StatusCode::CODE_DESERIALIZATION_ERROR)?;
+
+    bundle
+        .into_inner()
+        .iter()
+        .try_fold(VerificationBound::default(), |total, module| {
+            Ok(total.merge(module_verification_bound(module)?))
+        })
+}
+
+/// Compute the verification bound of the module.
+pub fn verification_bound(module: &CompiledModule) -> VerificationBound {
+    let fields = module
+        .struct_defs
+        .iter()
+        .map(|def| match &def.field_information {
+            StructFieldInformation::Declared(fields) => fields.len(),
+            StructFieldInformation::Native => 0,
+        })
+        .sum::<usize>();
+    let type_nodes = module.signatures.iter().map(type_nodes).sum::<u64>();
+
+    let tables = [
+        module.module_handles.len(),
+        module.struct_handles.len(),
+        module.function_handles.len(),
+        module.field_handles.len(),
+        module.friend_decls.len(),
+        module.struct_def_instantiations.len(),
+        module.function_instantiations.len(),
+        module.field_instantiations.len(),
+        module.signatures.len(),
+        module.identifiers.len(),
+        module.address_identifiers.len(),
+        module.constant_pool.len(),
+        module.metadata.len(),
+        module.struct_defs.len(),
+        module.function_defs.len(),
+        fields,
+    ];
+    let table_entries = tables
+        .iter()
+        .map(|len| *len as u64)
+        .fold(type_nodes, u64::saturating_add);
+
+    let code_units = module
+        .function_defs
+        .iter()
+        .filter_map(|def| {
+            let code = def.code.as_ref()?;
+            let handle = module.function_handle_at(def.function);
+            let locals = module.signature_at(handle.parameters).len()
+                + module.signature_at(code.locals).len();
+            Some(function_code_units(&code.code, locals))
+        })
+        .fold(0, u64::saturating_add);
+
+    VerificationBound {
+        table_entries,
+        code_units,
+    }
+}
+
+/// Instruction visits of a function: each pass over the code tracks the state of all locals, and
+/// every back edge can force another pass.
+fn function_code_units(code: &[Bytecode], locals: usize) -> u64 {
+    let back_edges = code
+        .iter()
+        .enumerate()
+        .filter(|(offset, instr)| {
+            matches!(
+                instr,
+                Bytecode::Branch(target) | Bytecode::BrTrue(target) | Bytecode::BrFalse(target)
+                    if (*target as usize) <= *offset
+            )
+        })
+        .count();
+
+    (code.len() as u64)
+        .saturating_mul(locals as u64 + 1)
+        .saturating_mul(back_edges as u64 + 1)
+}
+
+fn type_nodes(signature: &Signature) -> u64 {
+    signature
+        .0
+        .iter()
+        .map(|token| token.preorder_traversal().count() as u64)
+        .sum()
+}
+
+/// Compute the publishing complexity of the serialized module.
+pub fn module_publish_complexity(module: &[u8]) -> Result<PublishComplexity, StatusCode> {
+    let module = CompiledModule::deserialize(module).map_err(|e| e.major_status())?;
+    Ok(publish_complexity(&module))
+}
+
+/// Compute the publishing complexity of the serialized [`ModuleBundle`].
+pub fn bundle_publish_complexity(bundle: &[u8]) -> Result<PublishComplexity, StatusCode> {
+    let bundle =
+        ModuleBundle::try_from(bundle).map_err(|_| StatusCode::CODE_DESERIALIZATION_ERROR)?;
+
+    bundle
+        .into_inner()
+        .iter()
+        .try_fold(PublishComplexity::default(), |total, module| {
+            Ok(total.merge(module_publish_complexity(module)?))
+        })
+}
+
+/// Compute the publishing complexity of the module.
+pub fn publish_complexity(module: &CompiledModule) -> PublishComplexity {
+    PublishComplexity {
+        functions: module.function_defs.len() as u64,
+        instructions: module
+            .function_defs
+            .iter()
+            .filter_map(|def| def.code.as_ref())
+            .map(|code| code.code.len() as u64)
+            .fold(0, u64::saturating_add),
+        signature_nodes: module
+            .signatures
+            .iter()
+            .map(type_nodes)
+            .fold(0, u64::saturating_add),
+    }
+}
@@ -0,0 +1,228 @@
+//! Supported subset of the XCM messages sent from the Move code.
+//!
+//! The `std::xcm` module builds the messages with its constructors and passes them to the host
+//! BCS-encoded. [`XcmMessage::decode`] turns them into the typed form, which the host converts
+//! into the XCM of its runtime and dispatches:
+//! - [`XcmInstruction::TransferAsset`] moves the asset from the sender to the beneficiary on the
+//!   destination chain,
+//! - [`XcmInstruction::Transact`] dispatches the encoded runtime call on the destination chain.
+//!
+//! Messages carry their version, so the subset can be extended without changing the meaning of
+//! the messages built by the already published modules. Only [`XCM_VERSION`] is supported now.
+
+use alloc::vec::Vec;
+use core::fmt;
+use move_core_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+
+/// Version of the supported message format, following the XCM version it's modelled on.
+pub const XCM_VERSION: u8 = 3;
+
+/// Error codes for [`XcmMessage::decode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XcmError {
+    /// The message isn't a BCS-encoded `std::xcm::Message`.
+    Malformed,
+    /// The message version isn't supported.
+    UnsupportedVersion(u8),
+    /// The message doesn't have exactly one instruction.
+    InvalidInstruction,
+    /// The transact origin kind can't be requested from the Move code.
+    InvalidOriginKind(u8),
+}
+
+impl fmt::Display for XcmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "Malformed XCM message"),
+            Self::UnsupportedVersion(version) => write!(f, "Unsupported XCM version {version}"),
+            Self::InvalidInstruction => write!(f, "XCM message must have exactly one instruction"),
+            Self::InvalidOriginKind(kind) => write!(f, "Unsupported XCM origin kind {kind}"),
+        }
+    }
+}
+
+/// Chain or account location relative to the sending chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct XcmLocation {
+    /// Number of the parent hops, e.g. `1` to reach the relay chain from a parachain.
+    pub parents: u8,
+    /// Parachain junction.
+    pub parachain: Option<u32>,
+    /// 32-byte account junction.
+    pub account: Option<AccountAddress>,
+}
+
+/// Asset location relative to the sending chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct XcmAssetId {
+    /// Number of the parent hops.
+    pub parents: u8,
+    /// Parachain junction.
+    pub parachain: Option<u32>,
+    /// Pallet instance junction, e.g. the index of the `pallet-assets`.
+    pub pallet_instance: Option<u8>,
+    /// General index junction, e.g. the asset ID within the `pallet-assets`.
+    pub general_index: Option<u128>,
+}
+
+/// Fungible asset with its amount.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct XcmAsset {
+    pub id: XcmAssetId,
+    pub amount: u128,
+}
+
+/// Origin the transacted call is dispatched with on the destination chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XcmOriginKind {
+    /// Sovereign account of the sender on the destination chain.
+    SovereignAccount,
+    /// The XCM origin, i.e. the sender location itself.
+    Xcm,
+}
+
+impl XcmOriginKind {
+    /// Encoding of the origin kinds, matching the XCM `OriginKind` indices.
+    const SOVEREIGN_ACCOUNT: u8 = 1;
+    const XCM: u8 = 3;
+}
+
+impl TryFrom<u8> for XcmOriginKind {
+    type Error = XcmError;
+
+    fn try_from(kind: u8) -> Result<Self, Self::Error> {
+        match kind {
+            Self::SOVEREIGN_ACCOUNT => Ok(Self::SovereignAccount),
+            Self::XCM => Ok(Self::Xcm),
+            // The native and the superuser origins are never available to the user code.
+            _ => Err(XcmError::InvalidOriginKind(kind)),
+        }
+    }
+}
+
+impl From<XcmOriginKind> for u8 {
+    fn from(kind: XcmOriginKind) -> u8 {
+        match kind {
+            XcmOriginKind::SovereignAccount => XcmOriginKind::SOVEREIGN_ACCOUNT,
+            XcmOriginKind::Xcm => XcmOriginKind::XCM,
+        }
+    }
+}
+
+/// Instruction of the supported subset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XcmInstruction {
+    /// Transfer the asset to the beneficiary on the destination chain.
+    TransferAsset {
+        asset: XcmAsset,
+        beneficiary: XcmLocation,
+    },
+    /// Dispatch the SCALE-encoded runtime call on the destination chain.
+    Transact {
+        origin_kind: XcmOriginKind,
+        /// Maximum reference time weight of the call.
+        ref_time: u64,
+        /// Maximum proof size weight of the call.
+        proof_size: u64,
+        call: Vec<u8>,
+    },
+}
+
+/// Message sent by the Move code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XcmMessage {
+    /// Chain receiving the message.
+    pub destination: XcmLocation,
+    pub instruction: XcmInstruction,
+}
+
+impl XcmMessage {
+    /// Decodes the BCS-encoded `std::xcm::Message`.
+    pub fn decode(bytes: &[u8]) -> Result<Self, XcmError> {
+        let encoded: EncodedMessage = bcs::from_bytes(bytes).map_err(|_| XcmError::Malformed)?;
+        if encoded.version != XCM_VERSION {
+            return Err(XcmError::UnsupportedVersion(encoded.version));
+        }
+
+        let instruction = match (encoded.transfer_asset, encoded.transact) {
+            (Some(transfer), None) => XcmInstruction::TransferAsset {
+                asset: transfer.asset,
+                beneficiary: transfer.beneficiary,
+            },
+            (None, Some(transact)) => XcmInstruction::Transact {
+                origin_kind: transact.origin_kind.try_into()?,
+                ref_time: transact.ref_time,
+                proof_size: transact.proof_size,
+                call: transact.call,
+            },
+            _ => return Err(XcmError::InvalidInstruction),
+        };
+
+        Ok(Self {
+            destination: encoded.destination,
+            instruction,
+        })
+    }
+
+    /// Encodes the message the same way as the `std::xcm` module does.
+    pub fn encode(&self) -> Vec<u8> {
+        let (transfer_asset, transact) = match &self.instruction {
+            XcmInstruction::TransferAsset { asset, beneficiary } => (
+                Some(EncodedTransferAsset {
+                    asset: asset.clone(),
+                    beneficiary: beneficiary.clone(),
+                }),
+                None,
+            ),
+            XcmInstruction::Transact {
+                origin_kind,
+                ref_time,
+                proof_size,
+                call,
+            } => (
+                None,
+                Some(EncodedTransact {
+                    origin_kind: (*origin_kind).into(),
+                    ref_time: *ref_time,
+                    proof_size: *proof_size,
+                    call: call.clone(),
+                }),
+            ),
+        };
+
+        let encoded = EncodedMessage {
+            version: XCM_VERSION,
+            destination: self.destination.clone(),
+            transfer_asset,
+            transact,
+        };
+        bcs::to_bytes(&encoded).expect("messages are always serializable")
+    }
+}
+
+/// Layout of the `std::xcm::Message` struct - Move has no enums, so each instruction is an
+/// optional field.
+#[derive(Serialize, Deserialize)]
+struct EncodedMessage {
+    version: u8,
+    destination: XcmLocation,
+    transfer_asset: Option<EncodedTransferAsset>,
+    transact: Option<EncodedTransact>,
+}
+
+/// Layout of the `std::xcm::TransferAsset` struct.
+#[derive(Serialize, Deserialize)]
+struct EncodedTransferAsset {
+    asset: XcmAsset,
+    beneficiary: XcmLocation,
+}
+
+/// Layout of the `std::xcm::Transact` struct.
+#[derive(Serialize, Deserialize)]
+struct EncodedTransact {
+    origin_kind: u8,
+    ref_time: u64,
+    proof_size: u64,
+    call: Vec<u8>,
+}
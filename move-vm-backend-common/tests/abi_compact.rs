@@ -0,0 +1,167 @@
+//! Tests for the compact ABI encoding.
+
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
+use move_vm_backend_common::{
+    abi::{
+        Field, Friend, Function, FunctionVisibility, ModuleAbi, Struct, StructDef, Type,
+        TypeAbilities, TypeAbility,
+    },
+    abi_compact::{decode, encode, from_json, to_json, CompactAbiError, COMPACT_ABI_MAGIC},
+};
+
+fn ident(name: &str) -> Identifier {
+    Identifier::new(name).unwrap()
+}
+
+fn abilities(abilities: &[TypeAbility]) -> TypeAbilities {
+    TypeAbilities {
+        abilities: abilities.to_vec(),
+    }
+}
+
+fn basic_coin_abi() -> ModuleAbi {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let id = ModuleId::new(cafe, ident("BasicCoin"));
+    let coin = Type::Struct(StructDef {
+        id: id.clone(),
+        name: ident("Coin"),
+        fields: vec![Type::TypeParameter(0)],
+    });
+
+    ModuleAbi {
+        id,
+        friends: vec![Friend {
+            address: cafe,
+            name: ident("Bank"),
+        }],
+        structs: vec![
+            Struct {
+                name: ident("Coin"),
+                type_parameters: vec![abilities(&[])],
+                abilities: abilities(&[TypeAbility::Store]),
+                fields: vec![Field {
+                    name: ident("value"),
+                    tp: Type::U64,
+                }],
+            },
+            Struct {
+                name: ident("Balance"),
+                type_parameters: vec![abilities(&[])],
+                abilities: abilities(&[TypeAbility::Key]),
+                fields: vec![Field {
+                    name: ident("coin"),
+                    tp: coin.clone(),
+                }],
+            },
+        ],
+        funcs: vec![
+            Function {
+                name: ident("balance_of"),
+                visibility: FunctionVisibility::Public,
+                type_parameters: vec![abilities(&[TypeAbility::Copy, TypeAbility::Drop])],
+                parameters: vec![Type::Address],
+                returns: vec![Type::U64],
+            },
+            Function {
+                name: ident("deposit"),
+                visibility: FunctionVisibility::Friend,
+                type_parameters: vec![abilities(&[])],
+                parameters: vec![
+                    Type::MutableReference(Box::new(Type::Signer)),
+                    Type::Vector(Box::new(coin)),
+                    Type::Reference(Box::new(Type::U256)),
+                ],
+                returns: vec![],
+            },
+        ],
+    }
+}
+
+#[test]
+fn compact_abi_round_trip() {
+    let abi = basic_coin_abi();
+    let binary = encode(&abi);
+    assert!(binary.starts_with(COMPACT_ABI_MAGIC));
+    assert_eq!(decode(&binary), Ok(abi));
+}
+
+#[test]
+fn identifiers_are_interned() {
+    let abi = basic_coin_abi();
+    let binary = encode(&abi);
+
+    // The length-prefixed `Coin` identifier, which `BasicCoin` doesn't match.
+    let occurrences = binary.windows(5).filter(|w| *w == b"\x04Coin").count();
+    assert_eq!(occurrences, 1);
+
+    let json = serde_json::to_string(&abi).unwrap();
+    assert!(binary.len() < json.len() / 2);
+}
+
+#[test]
+fn compact_abi_converts_to_and_from_json() {
+    let abi = basic_coin_abi();
+    let json = serde_json::to_string(&abi).unwrap();
+
+    let binary = from_json(&json).unwrap();
+    assert_eq!(binary, encode(&abi));
+    assert_eq!(to_json(&binary).unwrap(), json);
+
+    assert!(matches!(
+        from_json("{}"),
+        Err(CompactAbiError::InvalidJson(_))
+    ));
+}
+
+#[test]
+fn unknown_records_are_skipped() {
+    let abi = basic_coin_abi();
+    let mut binary = encode(&abi);
+    binary.extend([0x7f, 3, 1, 2, 3]);
+
+    assert_eq!(decode(&binary), Ok(abi));
+}
+
+#[test]
+fn malformed_compact_abi_is_rejected() {
+    let binary = encode(&basic_coin_abi());
+
+    let mut bad_magic = binary.clone();
+    bad_magic[0] = b'X';
+    assert_eq!(decode(&bad_magic), Err(CompactAbiError::BadMagic));
+
+    let mut bad_version = binary.clone();
+    bad_version[4] = 2;
+    assert_eq!(
+        decode(&bad_version),
+        Err(CompactAbiError::UnsupportedVersion(2))
+    );
+
+    assert_eq!(
+        decode(&binary[..binary.len() - 1]),
+        Err(CompactAbiError::UnexpectedEnd)
+    );
+
+    // Module ID record only, with the name index past the identifier table.
+    let mut out_of_bounds = COMPACT_ABI_MAGIC.to_vec();
+    out_of_bounds.extend([1, 0, 0x01, 33]);
+    out_of_bounds.extend([0; 32]);
+    out_of_bounds.push(5);
+    assert_eq!(
+        decode(&out_of_bounds),
+        Err(CompactAbiError::IdentifierOutOfBounds(5))
+    );
+
+    // Friend record with an extra byte.
+    let mut trailing = binary;
+    trailing.extend([0x02, 34]);
+    trailing.extend([0; 32]);
+    trailing.extend([0, 0]);
+    assert_eq!(decode(&trailing), Err(CompactAbiError::TrailingBytes));
+
+    let mut no_module = COMPACT_ABI_MAGIC.to_vec();
+    no_module.extend([1, 0]);
+    assert_eq!(decode(&no_module), Err(CompactAbiError::InvalidModuleId));
+}
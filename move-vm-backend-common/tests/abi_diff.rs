@@ -0,0 +1,144 @@
+//! Tests for the module ABI diff.
+
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
+use move_vm_backend_common::{
+    abi::{
+        Field, Friend, Function, FunctionVisibility, ModuleAbi, Struct, Type, TypeAbilities,
+        TypeAbility,
+    },
+    abi_diff::{abi_diff, AbiChange},
+};
+
+fn ident(name: &str) -> Identifier {
+    Identifier::new(name).unwrap()
+}
+
+fn function(name: &str, visibility: FunctionVisibility, parameters: Vec<Type>) -> Function {
+    Function {
+        name: ident(name),
+        visibility,
+        type_parameters: vec![],
+        parameters,
+        returns: vec![],
+    }
+}
+
+fn balance_struct(fields: Vec<Field>) -> Struct {
+    Struct {
+        name: ident("Balance"),
+        type_parameters: vec![],
+        abilities: TypeAbilities {
+            abilities: vec![TypeAbility::Key],
+        },
+        fields,
+    }
+}
+
+fn basic_coin_abi() -> ModuleAbi {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+
+    ModuleAbi {
+        id: ModuleId::new(cafe, ident("BasicCoin")),
+        friends: vec![],
+        structs: vec![balance_struct(vec![Field {
+            name: ident("value"),
+            tp: Type::U64,
+        }])],
+        funcs: vec![
+            function(
+                "transfer",
+                FunctionVisibility::Public,
+                vec![Type::Address, Type::U64],
+            ),
+            function("mint", FunctionVisibility::Friend, vec![Type::U64]),
+        ],
+    }
+}
+
+#[test]
+fn identical_abis_have_no_changes() {
+    let diff = abi_diff(&basic_coin_abi(), &basic_coin_abi());
+
+    assert!(diff.changes.is_empty());
+    assert!(diff.is_compatible());
+}
+
+#[test]
+fn additions_are_compatible() {
+    let old = basic_coin_abi();
+    let mut new = basic_coin_abi();
+    new.funcs
+        .push(function("burn", FunctionVisibility::Public, vec![]));
+    new.friends.push(Friend {
+        address: AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+        name: ident("Exchange"),
+    });
+    // Friend functions can only be linked by the friends.
+    new.funcs[1].parameters.push(Type::Bool);
+
+    let diff = abi_diff(&old, &new);
+    assert_eq!(diff.changes.len(), 3);
+    assert!(diff
+        .changes
+        .contains(&AbiChange::FunctionAdded(ident("burn"))));
+    assert!(diff.is_compatible());
+}
+
+#[test]
+fn public_api_changes_are_breaking() {
+    let old = basic_coin_abi();
+
+    let mut new = basic_coin_abi();
+    new.funcs[0].parameters.pop();
+    let diff = abi_diff(&old, &new);
+    assert_eq!(
+        diff.changes,
+        vec![AbiChange::FunctionSignatureChanged {
+            name: ident("transfer"),
+            visibility: FunctionVisibility::Public,
+        }]
+    );
+    assert!(!diff.is_compatible());
+
+    let mut new = basic_coin_abi();
+    new.funcs.remove(0);
+    let diff = abi_diff(&old, &new);
+    assert_eq!(diff.breaking_changes().count(), 1);
+
+    let mut new = basic_coin_abi();
+    new.funcs[0].visibility = FunctionVisibility::Friend;
+    assert!(!abi_diff(&old, &new).is_compatible());
+
+    // Promoting the friend function to public is fine.
+    let mut new = basic_coin_abi();
+    new.funcs[1].visibility = FunctionVisibility::Public;
+    assert!(abi_diff(&old, &new).is_compatible());
+}
+
+#[test]
+fn struct_layout_changes_are_breaking() {
+    let old = basic_coin_abi();
+
+    let mut new = basic_coin_abi();
+    new.structs[0] = balance_struct(vec![Field {
+        name: ident("value"),
+        tp: Type::U128,
+    }]);
+    let diff = abi_diff(&old, &new);
+    assert_eq!(
+        diff.changes,
+        vec![AbiChange::StructLayoutChanged(ident("Balance"))]
+    );
+    assert!(!diff.is_compatible());
+
+    let mut new = basic_coin_abi();
+    new.structs.clear();
+    let diff = abi_diff(&old, &new);
+    assert_eq!(
+        diff.changes,
+        vec![AbiChange::StructRemoved(ident("Balance"))]
+    );
+    assert!(!diff.is_compatible());
+}
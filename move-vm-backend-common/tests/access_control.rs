@@ -0,0 +1,81 @@
+//! Tests for the `std::access_control` resource queries.
+
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag, CORE_CODE_ADDRESS},
+};
+use move_vm_backend_common::access_control::{access_control_tag, AccessControl, RoleMembers};
+
+fn address(hex: &str) -> AccountAddress {
+    AccountAddress::from_hex_literal(hex).unwrap()
+}
+
+fn access_control() -> AccessControl {
+    AccessControl {
+        owner: address("0xC"),
+        pending_owner: Some(address("0xA")),
+        roles: vec![
+            RoleMembers {
+                role: b"MINTER".to_vec(),
+                members: vec![address("0xA"), address("0xB")],
+            },
+            RoleMembers {
+                role: b"PAUSER".to_vec(),
+                members: vec![address("0xB")],
+            },
+        ],
+    }
+}
+
+#[test]
+fn tag_wraps_the_witness() {
+    let witness = StructTag {
+        address: address("0xCAFE"),
+        module: Identifier::new("Token").unwrap(),
+        name: Identifier::new("Token").unwrap(),
+        type_params: vec![],
+    };
+
+    let tag = access_control_tag(witness.clone());
+    assert_eq!(tag.address, CORE_CODE_ADDRESS);
+    assert_eq!(tag.module.as_str(), "access_control");
+    assert_eq!(tag.name.as_str(), "AccessControl");
+    assert_eq!(tag.type_params, vec![TypeTag::Struct(Box::new(witness))]);
+}
+
+#[test]
+fn resource_roundtrip() {
+    // The resource layout: owner, option (a vector of at most one address) and the roles.
+    let bytes = bcs::to_bytes(&(
+        address("0xC"),
+        vec![address("0xA")],
+        vec![
+            (b"MINTER".to_vec(), vec![address("0xA"), address("0xB")]),
+            (b"PAUSER".to_vec(), vec![address("0xB")]),
+        ],
+    ))
+    .unwrap();
+
+    assert_eq!(AccessControl::decode(&bytes).unwrap(), access_control());
+    assert!(AccessControl::decode(&bytes[1..]).is_err());
+}
+
+#[test]
+fn roles_are_queried() {
+    let control = access_control();
+
+    assert!(control.is_owner(&address("0xC")));
+    assert!(!control.is_owner(&address("0xA")));
+
+    assert!(control.has_role(b"MINTER", &address("0xA")));
+    assert!(!control.has_role(b"PAUSER", &address("0xA")));
+    assert!(!control.has_role(b"BURNER", &address("0xA")));
+
+    assert_eq!(control.members(b"PAUSER"), &[address("0xB")]);
+    assert!(control.members(b"BURNER").is_empty());
+
+    let roles: Vec<&[u8]> = vec![b"MINTER", b"PAUSER"];
+    assert_eq!(control.roles_of(&address("0xB")), roles);
+    assert!(control.roles_of(&address("0xC")).is_empty());
+}
@@ -0,0 +1,55 @@
+//! Tests for the host account conversions.
+
+use move_core_types::account_address::AccountAddress;
+use move_vm_backend_common::address::{Address20Codec, Address32Codec, AddressCodec, AddressError};
+
+#[test]
+fn short_accounts_are_padded() {
+    let account = [0xAB; 20];
+    let address = Address20Codec::to_move(account).unwrap();
+
+    assert_eq!(
+        address,
+        AccountAddress::from_hex_literal(&format!("0x{}", "ab".repeat(20))).unwrap()
+    );
+    assert_eq!(Address20Codec::from_move(&address), Ok(account));
+
+    // Addresses using the padding bytes don't map to any short account.
+    let address = AccountAddress::new([0xAB; AccountAddress::LENGTH]);
+    assert_eq!(
+        Address20Codec::from_move(&address),
+        Err(AddressError::Truncated)
+    );
+}
+
+#[test]
+fn full_length_accounts_map_one_to_one() {
+    let account = [0xCD; 32];
+    let address = Address32Codec::to_move(account).unwrap();
+
+    assert_eq!(address.into_bytes(), account);
+    assert_eq!(Address32Codec::from_move(&address), Ok(account));
+}
+
+#[test]
+fn mismatched_lengths_are_rejected() {
+    assert_eq!(
+        AddressCodec::<33>::to_move([0; 33]),
+        Err(AddressError::TooLong { length: 33 })
+    );
+    assert_eq!(
+        AddressCodec::<33>::from_move(&AccountAddress::ONE),
+        Err(AddressError::TooLong { length: 33 })
+    );
+    assert_eq!(
+        Address20Codec::to_move_from_slice(&[0; 32]),
+        Err(AddressError::LengthMismatch {
+            expected: 20,
+            provided: 32
+        })
+    );
+    assert_eq!(
+        Address20Codec::to_move_from_slice(&[0; 20]),
+        Ok(AccountAddress::ZERO)
+    );
+}
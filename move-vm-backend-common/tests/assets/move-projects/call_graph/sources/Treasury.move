@@ -0,0 +1,20 @@
+module CafeAccount::Treasury {
+    use CafeAccount::Vault;
+
+    struct Coin has store {
+        value: u64,
+    }
+
+    public entry fun open(account: &signer) {
+        Vault::create<Coin>(account);
+        Vault::create<vector<Coin>>(account);
+    }
+
+    public entry fun mint(account: address, value: u64) {
+        Vault::deposit(account, Coin { value });
+    }
+
+    fun countdown(n: u64) {
+        if (n > 0) countdown(n - 1);
+    }
+}
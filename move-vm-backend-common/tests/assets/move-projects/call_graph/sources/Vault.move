@@ -0,0 +1,20 @@
+module CafeAccount::Vault {
+    use std::vector;
+
+    struct Vault<T> has key {
+        items: vector<T>,
+    }
+
+    public fun create<T>(account: &signer) {
+        move_to(account, Vault<T> { items: vector::empty() });
+    }
+
+    public fun deposit<T: store>(account: address, item: T) acquires Vault {
+        let vault = borrow_global_mut<Vault<T>>(account);
+        push(&mut vault.items, item);
+    }
+
+    fun push<T>(items: &mut vector<T>, item: T) {
+        vector::push_back(items, item);
+    }
+}
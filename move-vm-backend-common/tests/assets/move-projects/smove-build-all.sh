@@ -4,6 +4,7 @@
 cd $(dirname $0)
 
 build_dir=(
+    "call_graph"
     "signer_scripts"
 )
 
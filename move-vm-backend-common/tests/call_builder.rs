@@ -0,0 +1,231 @@
+//! Tests for the typed entry function call builder.
+
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
+use move_vm_backend_common::{
+    abi::{Function, FunctionVisibility, ModuleAbi, Type, TypeAbilities},
+    call_builder::{CallArg, CallBuilder, CallBuilderError, Composition},
+};
+
+fn basic_coin_abi() -> ModuleAbi {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+
+    ModuleAbi {
+        id: ModuleId::new(cafe, Identifier::new("BasicCoin").unwrap()),
+        friends: vec![],
+        structs: vec![],
+        funcs: vec![
+            Function {
+                name: Identifier::new("transfer").unwrap(),
+                visibility: FunctionVisibility::Public,
+                type_parameters: vec![],
+                parameters: vec![
+                    Type::Reference(Box::new(Type::Signer)),
+                    Type::Address,
+                    Type::U64,
+                ],
+                returns: vec![],
+            },
+            Function {
+                name: Identifier::new("generic").unwrap(),
+                visibility: FunctionVisibility::Public,
+                type_parameters: vec![TypeAbilities { abilities: vec![] }],
+                parameters: vec![Type::Vector(Box::new(Type::TypeParameter(0)))],
+                returns: vec![],
+            },
+            Function {
+                name: Identifier::new("split").unwrap(),
+                visibility: FunctionVisibility::Public,
+                type_parameters: vec![TypeAbilities { abilities: vec![] }],
+                parameters: vec![Type::U64],
+                returns: vec![Type::U64, Type::Vector(Box::new(Type::TypeParameter(0)))],
+            },
+        ],
+    }
+}
+
+#[test]
+fn valid_call_is_built() {
+    let abi = basic_coin_abi();
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+
+    let call = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("transfer")
+        .arg(&cafe)
+        .unwrap()
+        .arg(&bob)
+        .unwrap()
+        .arg(&100u64)
+        .unwrap()
+        .build(&abi)
+        .unwrap();
+
+    assert_eq!(call.mod_address, cafe);
+    assert_eq!(call.mod_name.as_str(), "BasicCoin");
+    assert_eq!(call.func_name.as_str(), "transfer");
+    assert_eq!(call.args().len(), 3);
+    assert_eq!(call.args()[2], bcs::to_bytes(&100u64).unwrap().as_slice());
+}
+
+#[test]
+fn generic_call_is_validated_against_type_args() {
+    let abi = basic_coin_abi();
+
+    let call = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("generic")
+        .type_arg(TypeTag::U64)
+        .arg(&vec![1u64, 2, 3])
+        .unwrap()
+        .build(&abi);
+    assert!(call.is_ok());
+
+    let call = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("generic")
+        .type_arg(TypeTag::U64)
+        .arg(&vec![true, false])
+        .unwrap()
+        .build(&abi);
+    assert_eq!(call.unwrap_err(), CallBuilderError::InvalidArgument(0));
+
+    let call = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("generic")
+        .arg(&vec![1u64])
+        .unwrap()
+        .build(&abi);
+    assert_eq!(
+        call.unwrap_err(),
+        CallBuilderError::TypeArgumentCountMismatch {
+            expected: 1,
+            provided: 0
+        }
+    );
+}
+
+#[test]
+fn invalid_calls_are_rejected() {
+    let abi = basic_coin_abi();
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+
+    let call = CallBuilder::module("BasicCoin")
+        .function("transfer")
+        .build(&abi);
+    assert!(matches!(
+        call.unwrap_err(),
+        CallBuilderError::InvalidModuleId(_)
+    ));
+
+    let call = CallBuilder::module("0xB0B::BasicCoin")
+        .function("transfer")
+        .build(&abi);
+    assert_eq!(call.unwrap_err(), CallBuilderError::ModuleMismatch);
+
+    let call = CallBuilder::module("0xCAFE::BasicCoin").build(&abi);
+    assert_eq!(call.unwrap_err(), CallBuilderError::MissingFunction);
+
+    let call = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("mint")
+        .build(&abi);
+    assert_eq!(call.unwrap_err(), CallBuilderError::FunctionNotFound);
+
+    let call = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("transfer")
+        .arg(&cafe)
+        .unwrap()
+        .build(&abi);
+    assert_eq!(
+        call.unwrap_err(),
+        CallBuilderError::ArgumentCountMismatch {
+            expected: 3,
+            provided: 1
+        }
+    );
+
+    // The amount is u64, not u128.
+    let call = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("transfer")
+        .arg(&cafe)
+        .unwrap()
+        .arg(&cafe)
+        .unwrap()
+        .arg(&100u128)
+        .unwrap()
+        .build(&abi);
+    assert_eq!(call.unwrap_err(), CallBuilderError::InvalidArgument(2));
+}
+
+#[test]
+fn composed_calls_refer_to_earlier_return_values() {
+    let abi = basic_coin_abi();
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let mut composition = Composition::new();
+
+    let split = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("split")
+        .type_arg(TypeTag::U8)
+        .arg(&10u64)
+        .unwrap();
+    assert_eq!(composition.add(split, &abi), Ok(0));
+
+    let transfer = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("transfer")
+        .arg(&cafe)
+        .unwrap()
+        .arg(&cafe)
+        .unwrap()
+        .result_arg(0, 0);
+    assert_eq!(composition.add(transfer, &abi), Ok(1));
+
+    // The type parameters are substituted on both sides.
+    let generic = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("generic")
+        .type_arg(TypeTag::U8)
+        .result_arg(0, 1);
+    assert_eq!(composition.add(generic, &abi), Ok(2));
+
+    let generic = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("generic")
+        .type_arg(TypeTag::U64)
+        .result_arg(0, 1);
+    assert_eq!(
+        composition.add(generic, &abi),
+        Err(CallBuilderError::ResultTypeMismatch(0))
+    );
+
+    // The transfer call has no return values.
+    let split = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("split")
+        .type_arg(TypeTag::U8)
+        .result_arg(1, 0);
+    assert_eq!(
+        composition.add(split, &abi),
+        Err(CallBuilderError::InvalidResultReference(0))
+    );
+
+    let calls = composition.into_calls();
+    assert_eq!(calls.len(), 3);
+    assert_eq!(calls[1].args[2], CallArg::Result { call: 0, index: 0 });
+
+    let results = vec![vec![bcs::to_bytes(&10u64).unwrap(), vec![0]]];
+    let args = calls[1].resolve_args(&results).unwrap();
+    assert_eq!(args[2], bcs::to_bytes(&10u64).unwrap());
+    assert_eq!(calls[1].resolve_args(&[]), None);
+}
+
+#[test]
+fn single_call_cannot_refer_to_return_values() {
+    let abi = basic_coin_abi();
+
+    let call = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("split")
+        .type_arg(TypeTag::U8)
+        .result_arg(0, 0)
+        .build(&abi);
+    assert_eq!(
+        call.unwrap_err(),
+        CallBuilderError::InvalidResultReference(0)
+    );
+}
@@ -0,0 +1,135 @@
+//! Integration tests for the call graph.
+//!
+//! Note:
+//! These tests depend on the `call_graph` Move project within tests/assets/move-projects.
+
+use move_binary_format::CompiledModule;
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
+use move_vm_backend_common::call_graph::{CallEdge, CallGraph, FunctionId};
+use std::collections::BTreeSet;
+
+/// Reads a precompiled Move module from our assets directory.
+fn read_module_from_project(project: &str, module_name: &str) -> CompiledModule {
+    const MOVE_PROJECTS: &str = "tests/assets/move-projects";
+
+    let path =
+        format!("{MOVE_PROJECTS}/{project}/build/{project}/bytecode_modules/{module_name}.mv");
+    let bytes = std::fs::read(&path)
+        .unwrap_or_else(|e| panic!("Can't read {path}: {e} - make sure you run move-vm-backend-common/tests/assets/move-projects/smove-build-all.sh"));
+
+    CompiledModule::deserialize(&bytes).unwrap()
+}
+
+fn function(address: AccountAddress, module: &str, name: &str) -> FunctionId {
+    FunctionId {
+        module: ModuleId::new(address, Identifier::new(module).unwrap()),
+        name: Identifier::new(name).unwrap(),
+    }
+}
+
+fn cafe(module: &str, name: &str) -> FunctionId {
+    function(
+        AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+        module,
+        name,
+    )
+}
+
+fn call(callee: FunctionId, type_args: &[&str]) -> CallEdge {
+    CallEdge {
+        callee,
+        type_args: type_args.iter().map(|t| t.to_string()).collect(),
+    }
+}
+
+fn call_graph() -> CallGraph {
+    let vault = read_module_from_project("call_graph", "Vault");
+    let treasury = read_module_from_project("call_graph", "Treasury");
+
+    CallGraph::from_modules([&vault, &treasury])
+}
+
+#[test]
+fn all_defined_functions_are_nodes() {
+    let graph = call_graph();
+
+    let nodes: BTreeSet<_> = graph.edges.keys().cloned().collect();
+    let expected = BTreeSet::from([
+        cafe("Vault", "create"),
+        cafe("Vault", "deposit"),
+        cafe("Vault", "push"),
+        cafe("Treasury", "open"),
+        cafe("Treasury", "mint"),
+        cafe("Treasury", "countdown"),
+    ]);
+    assert_eq!(nodes, expected);
+
+    // Functions outside the analyzed modules don't have any known callees.
+    let push_back = function(AccountAddress::ONE, "vector", "push_back");
+    assert_eq!(graph.callees(&push_back).count(), 0);
+}
+
+#[test]
+fn generic_instantiations_are_recorded() {
+    let graph = call_graph();
+    let coin = "0xcafe::Treasury::Coin";
+
+    let open: BTreeSet<_> = graph.callees(&cafe("Treasury", "open")).cloned().collect();
+    let expected = BTreeSet::from([
+        call(cafe("Vault", "create"), &[coin]),
+        call(cafe("Vault", "create"), &[&format!("vector<{coin}>")]),
+    ]);
+    assert_eq!(open, expected);
+
+    // Type parameters are kept in the caller's terms.
+    let push: Vec<_> = graph.callees(&cafe("Vault", "push")).collect();
+    let push_back = function(AccountAddress::ONE, "vector", "push_back");
+    assert_eq!(push, vec![&call(push_back, &["T0"])]);
+
+    let deposit: Vec<_> = graph.callees(&cafe("Vault", "deposit")).collect();
+    assert_eq!(deposit, vec![&call(cafe("Vault", "push"), &["T0"])]);
+}
+
+#[test]
+fn callers_and_reachability_are_resolved() {
+    let graph = call_graph();
+
+    let callers: Vec<_> = graph.callers(&cafe("Vault", "push")).collect();
+    assert_eq!(callers, vec![&cafe("Vault", "deposit")]);
+
+    // Recursive functions reach themselves.
+    let countdown = cafe("Treasury", "countdown");
+    assert_eq!(
+        graph.reachable(&countdown),
+        BTreeSet::from([countdown.clone()])
+    );
+
+    let reachable = graph.reachable(&cafe("Treasury", "mint"));
+    let expected = BTreeSet::from([
+        cafe("Vault", "deposit"),
+        cafe("Vault", "push"),
+        function(AccountAddress::ONE, "vector", "push_back"),
+    ]);
+    assert_eq!(reachable, expected);
+}
+
+#[test]
+fn module_dependencies_exclude_the_module_itself() {
+    let graph = call_graph();
+    let treasury = cafe("Treasury", "open").module;
+    let vault = cafe("Vault", "create").module;
+
+    assert_eq!(
+        graph.module_dependencies(&treasury),
+        BTreeSet::from([vault.clone()])
+    );
+    assert_eq!(
+        graph.module_dependencies(&vault),
+        BTreeSet::from([ModuleId::new(
+            AccountAddress::ONE,
+            Identifier::new("vector").unwrap()
+        )])
+    );
+}
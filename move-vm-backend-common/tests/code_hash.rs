@@ -0,0 +1,34 @@
+//! Tests for the code hashes.
+
+use move_vm_backend_common::{
+    code_hash::{bundle_hash, module_hash, script_hash},
+    types::ModuleBundle,
+};
+
+#[test]
+fn code_kinds_are_domain_separated() {
+    let bytecode = [0xa1, 0x1c, 0xeb, 0x0b, 6, 0, 0, 0];
+
+    assert_ne!(module_hash(&bytecode), script_hash(&bytecode));
+    assert_ne!(module_hash(&bytecode), bundle_hash([bytecode.as_slice()]));
+    assert_ne!(script_hash(&bytecode), bundle_hash([bytecode.as_slice()]));
+}
+
+#[test]
+fn bundle_hash_commits_to_the_module_order() {
+    let (first, second) = (vec![1, 2, 3], vec![4, 5]);
+
+    let bundle = ModuleBundle::new(vec![first.clone(), second.clone()]);
+    assert_eq!(
+        bundle.hash(),
+        bundle_hash([first.as_slice(), second.as_slice()])
+    );
+    assert_ne!(
+        bundle.hash(),
+        bundle_hash([second.as_slice(), first.as_slice()])
+    );
+
+    // The module boundaries are part of the hash.
+    assert_ne!(bundle.hash(), bundle_hash([[1, 2, 3, 4, 5].as_slice()]));
+    assert_ne!(bundle_hash([]), bundle_hash([[].as_slice()]));
+}
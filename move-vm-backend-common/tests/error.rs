@@ -0,0 +1,28 @@
+//! Tests for the canonical abort code decoding.
+
+use move_vm_backend_common::error::{CanonicalError, ErrorCategory};
+
+#[test]
+fn canonical_codes_are_decoded() {
+    let error = CanonicalError::decode(0x10003).unwrap();
+    assert_eq!(error.category, ErrorCategory::InvalidArgument);
+    assert_eq!(error.reason, 3);
+    assert_eq!(error.abort_code(), 0x10003);
+
+    // The upper bytes are free for the module's own use.
+    let error = CanonicalError::decode(0xFF_000D_0001).unwrap();
+    assert_eq!(error.category, ErrorCategory::Unavailable);
+    assert_eq!(error.reason, 1);
+
+    for code in 0x1..=0xD {
+        let category = ErrorCategory::from_code(code).unwrap();
+        assert_eq!(category.code(), code);
+    }
+}
+
+#[test]
+fn module_local_codes_are_not_decoded() {
+    assert_eq!(CanonicalError::decode(0), None);
+    assert_eq!(CanonicalError::decode(2), None);
+    assert_eq!(CanonicalError::decode(0xE0000), None);
+}
@@ -0,0 +1,85 @@
+//! Tests for the canonical event schema.
+
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+};
+use move_vm_backend_common::event::{
+    event_field_topic, event_type_topic, EventError, MoveEvent, MAX_EVENT_TOPICS,
+};
+
+fn minted_type() -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Minted").unwrap(),
+        type_params: vec![],
+    }))
+}
+
+#[test]
+fn topics_follow_the_hashing_rules() {
+    let owner = bcs::to_bytes(&AccountAddress::from_hex_literal("0xB0B").unwrap()).unwrap();
+    let amount = bcs::to_bytes(&100u64).unwrap();
+
+    let event = MoveEvent::new(minted_type(), &[owner.clone()], amount.clone()).unwrap();
+    assert_eq!(
+        event.topics,
+        vec![event_type_topic(&minted_type()), event_field_topic(&owner)]
+    );
+    assert_eq!(event.data, amount);
+
+    // Type and field topics are domain separated.
+    let type_name = minted_type().to_canonical_string();
+    assert_ne!(
+        event_type_topic(&minted_type()),
+        event_field_topic(type_name.as_bytes())
+    );
+    assert_ne!(
+        event_type_topic(&TypeTag::U64),
+        event_type_topic(&TypeTag::U8)
+    );
+
+    let too_many = vec![owner; MAX_EVENT_TOPICS];
+    assert_eq!(
+        MoveEvent::new(minted_type(), &too_many, vec![]),
+        Err(EventError::TooManyTopics)
+    );
+}
+
+#[test]
+fn vm_events_are_converted() {
+    let owner = bcs::to_bytes(&AccountAddress::from_hex_literal("0xB0B").unwrap()).unwrap();
+    let guid = bcs::to_bytes(&vec![owner.clone()]).unwrap();
+    let data = bcs::to_bytes(&100u64).unwrap();
+
+    let event = MoveEvent::try_from((guid, 0, minted_type(), data.clone())).unwrap();
+    assert_eq!(
+        event,
+        MoveEvent::new(minted_type(), &[owner], data.clone()).unwrap()
+    );
+
+    // Events without the indexed fields in the GUID are rejected.
+    assert_eq!(
+        MoveEvent::try_from((vec![0xFF], 0, minted_type(), data)),
+        Err(EventError::InvalidIndexedFields)
+    );
+}
+
+#[test]
+fn events_are_filtered_by_topics() {
+    let owner = bcs::to_bytes(&AccountAddress::from_hex_literal("0xB0B").unwrap()).unwrap();
+    let event = MoveEvent::new(minted_type(), &[owner.clone()], vec![]).unwrap();
+    let type_topic = event_type_topic(&minted_type());
+    let owner_topic = event_field_topic(&owner);
+
+    assert!(event.matches(&[]));
+    assert!(event.matches(&[Some(type_topic)]));
+    assert!(event.matches(&[None, Some(owner_topic)]));
+    assert!(event.matches(&[Some(type_topic), Some(owner_topic)]));
+
+    assert!(!event.matches(&[Some(owner_topic)]));
+    assert!(!event.matches(&[None, Some(type_topic)]));
+    assert!(!event.matches(&[None, None, None]));
+}
@@ -0,0 +1,117 @@
+//! Tests for the re-encoding of the legacy 16-byte address binaries.
+
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format_common::{BinaryConstants, SerializedType, TableType, VERSION_6},
+    CompiledModule,
+};
+use move_core_types::{account_address::AccountAddress, value::MoveValue};
+use move_vm_backend_common::legacy::{
+    upgrade_legacy_binary, LegacyAddressCodec, LegacyBinaryError, LEGACY_ADDRESS_LENGTH,
+};
+
+fn legacy_cafe() -> [u8; LEGACY_ADDRESS_LENGTH] {
+    let mut address = [0u8; LEGACY_ADDRESS_LENGTH];
+    address[LEGACY_ADDRESS_LENGTH - 2..].copy_from_slice(&[0xCA, 0xFE]);
+    address
+}
+
+/// Builds the binary of the empty module `0xCAFE::M` compiled with the 16-byte addresses, with
+/// the `address` and the `vector<address>` constants.
+fn legacy_module() -> Vec<u8> {
+    let cafe = legacy_cafe();
+
+    let module_handles = vec![0, 0];
+    let identifiers = vec![1, b'M'];
+    let addresses = cafe.to_vec();
+    let mut constants = vec![SerializedType::ADDRESS as u8, LEGACY_ADDRESS_LENGTH as u8];
+    constants.extend(cafe);
+    constants.extend([
+        SerializedType::VECTOR as u8,
+        SerializedType::ADDRESS as u8,
+        2 * LEGACY_ADDRESS_LENGTH as u8 + 1,
+        2,
+    ]);
+    constants.extend(cafe);
+    constants.extend([0xBE; LEGACY_ADDRESS_LENGTH]);
+
+    let tables = [
+        (TableType::MODULE_HANDLES, module_handles),
+        (TableType::IDENTIFIERS, identifiers),
+        (TableType::ADDRESS_IDENTIFIERS, addresses),
+        (TableType::CONSTANT_POOL, constants),
+    ];
+
+    let mut binary = BinaryConstants::MOVE_MAGIC.to_vec();
+    binary.extend(VERSION_6.to_le_bytes());
+    binary.push(tables.len() as u8);
+    let mut offset = 0;
+    for (kind, data) in &tables {
+        binary.extend([*kind as u8, offset as u8, data.len() as u8]);
+        offset += data.len();
+    }
+    for (_, data) in tables {
+        binary.extend(data);
+    }
+    // Self module handle index.
+    binary.push(0);
+    binary
+}
+
+#[test]
+fn legacy_modules_are_upgraded() {
+    let legacy = legacy_module();
+    assert!(CompiledModule::deserialize(&legacy).is_err());
+
+    let upgraded = upgrade_legacy_binary(&legacy).unwrap();
+    let module = CompiledModule::deserialize(&upgraded).unwrap();
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    assert_eq!(module.self_id().address(), &cafe);
+    assert_eq!(module.self_id().name().as_str(), "M");
+
+    let constants: Vec<_> = module
+        .constant_pool()
+        .iter()
+        .map(|constant| constant.deserialize_constant().unwrap())
+        .collect();
+    let other = LegacyAddressCodec::to_move([0xBE; LEGACY_ADDRESS_LENGTH]).unwrap();
+    assert_eq!(
+        constants,
+        vec![
+            MoveValue::Address(cafe),
+            MoveValue::Vector(vec![MoveValue::Address(cafe), MoveValue::Address(other)]),
+        ]
+    );
+
+    // The legacy addresses map back to the original ones.
+    assert_eq!(LegacyAddressCodec::from_move(&cafe), Ok(legacy_cafe()));
+}
+
+#[test]
+fn malformed_legacy_binaries_are_rejected() {
+    assert_eq!(
+        upgrade_legacy_binary(&[0xBA, 0xD, 0xCA, 0xFE]),
+        Err(LegacyBinaryError::BadHeader)
+    );
+
+    // Truncated table contents.
+    let legacy = legacy_module();
+    assert_eq!(
+        upgrade_legacy_binary(&legacy[..legacy.len() - 20]),
+        Err(LegacyBinaryError::BadTables)
+    );
+
+    // The address pool isn't made of the 16-byte addresses.
+    let mut legacy = legacy_module();
+    let address_table = legacy
+        .windows(2)
+        .position(|header| header == [TableType::ADDRESS_IDENTIFIERS as u8, 4])
+        .unwrap();
+    legacy[address_table + 2] -= 1;
+    legacy[address_table + 4] -= 1;
+    assert_eq!(
+        upgrade_legacy_binary(&legacy),
+        Err(LegacyBinaryError::BadAddressPool)
+    );
+}
@@ -0,0 +1,93 @@
+//! Tests of the bundle dependency checks.
+
+use move_binary_format::file_format::{
+    empty_module, AddressIdentifierIndex, CompiledModule, IdentifierIndex, ModuleHandle,
+};
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
+use move_vm_backend_common::module_deps::{check_bundle_dependencies, DependencyError};
+
+fn id(name: &str) -> ModuleId {
+    ModuleId::new(AccountAddress::ZERO, Identifier::new(name).unwrap())
+}
+
+/// Module with the given name importing the given modules, all at the zero address.
+fn module(name: &str, dependencies: &[&str]) -> Vec<u8> {
+    let mut module: CompiledModule = empty_module();
+    module.identifiers[0] = Identifier::new(name).unwrap();
+    for dependency in dependencies {
+        module.module_handles.push(ModuleHandle {
+            address: AddressIdentifierIndex(0),
+            name: IdentifierIndex(module.identifiers.len() as u16),
+        });
+        module
+            .identifiers
+            .push(Identifier::new(*dependency).unwrap());
+    }
+
+    let mut bytes = vec![];
+    module.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+fn check(bundle: &[Vec<u8>]) -> Result<(), DependencyError> {
+    check_bundle_dependencies(bundle.iter().map(Vec::as_slice))
+}
+
+#[test]
+fn acyclic_bundles_pass() {
+    let bundle = [
+        module("A", &[]),
+        module("B", &["A"]),
+        module("C", &["A", "B", "Published"]),
+    ];
+    assert_eq!(check(&bundle), Ok(()));
+
+    // The order within the bundle doesn't matter here.
+    let bundle = [module("B", &["A"]), module("A", &[])];
+    assert_eq!(check(&bundle), Ok(()));
+}
+
+#[test]
+fn self_dependency_is_named() {
+    let bundle = [module("A", &[]), module("B", &["A", "B"])];
+
+    let err = check(&bundle).unwrap_err();
+    assert_eq!(err, DependencyError::SelfDependency(id("B")));
+    assert_eq!(err.to_string(), "Module 0x0::B depends on itself");
+}
+
+#[test]
+fn cycles_are_named_in_the_dependency_order() {
+    let bundle = [
+        module("A", &["B"]),
+        module("B", &["C"]),
+        module("C", &["A"]),
+        module("D", &["A"]),
+    ];
+
+    let err = check(&bundle).unwrap_err();
+    assert_eq!(err, DependencyError::Cycle(vec![id("A"), id("B"), id("C")]));
+    assert_eq!(
+        err.to_string(),
+        "Cyclic dependency between the bundle modules: 0x0::A -> 0x0::B -> 0x0::C -> 0x0::A"
+    );
+
+    // Only the modules within the cycle are named.
+    let bundle = [
+        module("D", &["A"]),
+        module("A", &["B"]),
+        module("B", &["A"]),
+    ];
+    assert_eq!(
+        check(&bundle),
+        Err(DependencyError::Cycle(vec![id("A"), id("B")]))
+    );
+}
+
+#[test]
+fn malformed_modules_are_left_for_the_vm() {
+    let bundle = [vec![0xde, 0xad], module("A", &[])];
+    assert_eq!(check(&bundle), Ok(()));
+}
@@ -0,0 +1,123 @@
+//! Tests for the Rust mirror types of Move structs.
+
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
+use move_vm_backend_common::{
+    abi::{Field, ModuleAbi, Struct, StructDef, Type, TypeAbilities, TypeAbility},
+    move_struct,
+    move_struct::{MoveStruct, MoveStructError},
+};
+use serde::{Deserialize, Serialize};
+
+move_struct! {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    pub struct Coin in "0xCAFE::BasicCoin" {
+        pub value: u64,
+    }
+}
+
+move_struct! {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    pub struct Balance in "0xCAFE::BasicCoin" {
+        pub coin: Coin,
+    }
+}
+
+move_struct! {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    pub struct Wrong in "0xCAFE::BasicCoin" {
+        pub value: u128,
+    }
+}
+
+fn ident(name: &str) -> Identifier {
+    Identifier::new(name).unwrap()
+}
+
+fn basic_coin_struct(name: &str, fields: Vec<Field>) -> Struct {
+    Struct {
+        name: ident(name),
+        type_parameters: vec![],
+        abilities: TypeAbilities {
+            abilities: vec![TypeAbility::Store],
+        },
+        fields,
+    }
+}
+
+fn basic_coin_abi() -> ModuleAbi {
+    let id = ModuleId::new(
+        AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+        ident("BasicCoin"),
+    );
+
+    ModuleAbi {
+        id: id.clone(),
+        friends: vec![],
+        structs: vec![
+            basic_coin_struct(
+                "Coin",
+                vec![Field {
+                    name: ident("value"),
+                    tp: Type::U64,
+                }],
+            ),
+            basic_coin_struct(
+                "Balance",
+                vec![Field {
+                    name: ident("coin"),
+                    tp: Type::Struct(StructDef {
+                        id,
+                        name: ident("Coin"),
+                        fields: vec![],
+                    }),
+                }],
+            ),
+            basic_coin_struct(
+                "Wrong",
+                vec![Field {
+                    name: ident("value"),
+                    tp: Type::U64,
+                }],
+            ),
+        ],
+        funcs: vec![],
+    }
+}
+
+#[test]
+fn mirror_types_match_the_abi() {
+    let abi = basic_coin_abi();
+
+    assert_eq!(Coin::validate(&abi), Ok(()));
+    assert_eq!(Balance::validate(&abi), Ok(()));
+    assert_eq!(Wrong::validate(&abi), Err(MoveStructError::LayoutMismatch));
+
+    let mut other = basic_coin_abi();
+    other.id = ModuleId::new(AccountAddress::ONE, ident("BasicCoin"));
+    assert_eq!(Coin::validate(&other), Err(MoveStructError::ModuleMismatch));
+
+    let mut other = basic_coin_abi();
+    other.structs.remove(0);
+    assert_eq!(Coin::validate(&other), Err(MoveStructError::StructNotFound));
+}
+
+#[test]
+fn mirror_types_round_trip() {
+    let balance = Balance {
+        coin: Coin { value: 42 },
+    };
+
+    let bytes = balance.to_move_bytes().unwrap();
+    assert_eq!(bytes, 42u64.to_le_bytes());
+    assert_eq!(Balance::from_move_bytes(&bytes), Ok(balance));
+    assert_eq!(
+        Balance::from_move_bytes(&[1, 2]),
+        Err(MoveStructError::Serialization)
+    );
+
+    let tag = Balance::struct_tag().unwrap();
+    assert_eq!(tag.module_id(), basic_coin_abi().id);
+    assert_eq!(tag.name, ident("Balance"));
+}
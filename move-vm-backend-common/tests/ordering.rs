@@ -0,0 +1,67 @@
+//! Tests for the canonical order of the execution outputs.
+
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::{ChangeSet, Op},
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+};
+use move_vm_backend_common::{
+    ordering::{resource_order_key, sort_resources},
+    receipt::{StatePath, StateWrite},
+};
+
+fn tag(module: &str, name: &str, type_params: Vec<TypeTag>) -> StructTag {
+    StructTag {
+        address: AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+        module: Identifier::new(module).unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params,
+    }
+}
+
+#[test]
+fn resources_are_ordered_by_tag_bytes() {
+    let coin = tag("BasicCoin", "Balance", vec![]);
+    let short_module = tag("Z", "Balance", vec![]);
+    let generic = tag("BasicCoin", "Balance", vec![TypeTag::U8]);
+    let short_name = tag("BasicCoin", "Id", vec![]);
+
+    // The tag order puts `BasicCoin` first, the canonical one puts the shorter `Z` first.
+    assert!(coin < short_module);
+    assert!(resource_order_key(&short_module) < resource_order_key(&coin));
+
+    let sorted = sort_resources([
+        (coin.clone(), 1),
+        (generic.clone(), 2),
+        (short_name.clone(), 3),
+        (short_module.clone(), 4),
+    ]);
+    assert_eq!(
+        sorted,
+        vec![(short_module, 4), (short_name, 3), (coin, 1), (generic, 2)]
+    );
+}
+
+#[test]
+fn state_writes_follow_the_canonical_order() {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let mut changeset = ChangeSet::new();
+    for name in ["Balance", "Id"] {
+        changeset
+            .add_resource_op(cafe, tag("BasicCoin", name, vec![]), Op::Delete)
+            .unwrap();
+    }
+
+    let paths: Vec<_> = StateWrite::from_changeset(&changeset)
+        .into_iter()
+        .map(|write| write.path)
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            StatePath::Resource(tag("BasicCoin", "Id", vec![])),
+            StatePath::Resource(tag("BasicCoin", "Balance", vec![])),
+        ]
+    );
+}
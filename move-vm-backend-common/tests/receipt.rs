@@ -0,0 +1,150 @@
+//! Tests for the execution receipt hashing scheme.
+
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::{ChangeSet, Op},
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
+use move_vm_backend_common::{
+    code_hash::module_hash,
+    event::MoveEvent,
+    receipt::{
+        event_leaf_hash, events_root, merkle_root, state_diff_root, ExecutionReceipt, StatePath,
+        StateWrite, EMPTY_ROOT,
+    },
+};
+
+fn tag(name: &str) -> StructTag {
+    StructTag {
+        address: AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params: vec![],
+    }
+}
+
+#[test]
+fn merkle_root_promotes_the_odd_nodes() {
+    let leaves: Vec<_> = (0..3u8).map(|i| [i; 32]).collect();
+
+    assert_eq!(merkle_root(vec![]), EMPTY_ROOT);
+    assert_eq!(merkle_root(leaves[..1].to_vec()), leaves[0]);
+
+    let pair = merkle_root(leaves[..2].to_vec());
+    assert_ne!(pair, merkle_root(vec![leaves[1], leaves[0]]));
+    assert_eq!(
+        merkle_root(leaves.clone()),
+        merkle_root(vec![pair, leaves[2]])
+    );
+}
+
+#[test]
+fn events_are_committed_in_the_emission_order() {
+    let event = |value: u8| {
+        MoveEvent::new(TypeTag::Struct(Box::new(tag("Minted"))), &[], vec![value]).unwrap()
+    };
+    let events = [event(1), event(2)];
+
+    assert_eq!(events_root(&[]), EMPTY_ROOT);
+    assert_eq!(events_root(&events[..1]), event_leaf_hash(&events[0]));
+    assert_ne!(events_root(&events), events_root(&[event(2), event(1)]));
+}
+
+#[test]
+fn state_writes_are_ordered_canonically() {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+
+    let mut changeset = ChangeSet::new();
+    changeset
+        .add_resource_op(cafe, tag("Balance"), Op::Modify(vec![2]))
+        .unwrap();
+    changeset
+        .add_module_op(
+            ModuleId::new(cafe, Identifier::new("BasicCoin").unwrap()),
+            Op::New(vec![1]),
+        )
+        .unwrap();
+    changeset
+        .add_resource_op(bob, tag("Balance"), Op::Delete)
+        .unwrap();
+
+    let writes = StateWrite::from_changeset(&changeset);
+    assert_eq!(
+        writes,
+        vec![
+            StateWrite {
+                address: bob,
+                path: StatePath::Resource(tag("Balance")),
+                value: None,
+            },
+            StateWrite {
+                address: cafe,
+                path: StatePath::Module(Identifier::new("BasicCoin").unwrap()),
+                value: Some(vec![1]),
+            },
+            StateWrite {
+                address: cafe,
+                path: StatePath::Resource(tag("Balance")),
+                value: Some(vec![2]),
+            },
+        ]
+    );
+
+    let leaves = writes.iter().map(StateWrite::leaf_hash).collect();
+    assert_eq!(state_diff_root(&changeset), merkle_root(leaves));
+    assert_eq!(state_diff_root(&ChangeSet::new()), EMPTY_ROOT);
+}
+
+#[test]
+fn module_writes_commit_to_the_module_hash() {
+    let write = |value: Vec<u8>| StateWrite {
+        address: AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+        path: StatePath::Module(Identifier::new("BasicCoin").unwrap()),
+        value: Some(value),
+    };
+
+    let bytecode = vec![0xa1, 0x1c, 0xeb, 0x0b];
+    assert_eq!(
+        write(bytecode.clone()).leaf_hash(),
+        write(module_hash(&bytecode).to_vec()).leaf_hash()
+    );
+    assert_ne!(
+        write(bytecode).leaf_hash(),
+        write(vec![0xa1, 0x1c]).leaf_hash()
+    );
+}
+
+#[test]
+fn receipt_hash_commits_to_all_fields() {
+    let receipt = ExecutionReceipt {
+        status: 4001,
+        abort_code: None,
+        gas_used: 100,
+        events_root: EMPTY_ROOT,
+        state_diff_root: EMPTY_ROOT,
+    };
+
+    let changed = [
+        ExecutionReceipt {
+            abort_code: Some(1),
+            ..receipt
+        },
+        ExecutionReceipt {
+            gas_used: 101,
+            ..receipt
+        },
+        ExecutionReceipt {
+            events_root: [1; 32],
+            ..receipt
+        },
+        ExecutionReceipt {
+            state_diff_root: [1; 32],
+            ..receipt
+        },
+    ];
+    for other in changed {
+        assert_ne!(receipt.hash(), other.hash());
+    }
+}
@@ -0,0 +1,115 @@
+//! Tests of the compiled script lints.
+
+use move_binary_format::file_format::{
+    empty_script, AbilitySet, Bytecode, CompiledScript, Signature, SignatureIndex, SignatureToken,
+};
+use move_vm_backend_common::script_lint::{lint_script, lint_script_bytes, LintWarning};
+
+/// Script with two `u64` parameters and the given code.
+fn script_with_code(code: Vec<Bytecode>) -> CompiledScript {
+    let mut script = empty_script();
+    script
+        .signatures
+        .push(Signature(vec![SignatureToken::U64, SignatureToken::U64]));
+    script.parameters = SignatureIndex(1);
+    script.code.code = code;
+    script
+}
+
+#[test]
+fn clean_script_has_no_warnings() {
+    let script = script_with_code(vec![
+        Bytecode::CopyLoc(0),
+        Bytecode::LdU64(10),
+        Bytecode::Lt,
+        Bytecode::BrFalse(8),
+        Bytecode::MoveLoc(0),
+        Bytecode::MoveLoc(1),
+        Bytecode::Add,
+        Bytecode::Pop,
+        Bytecode::Ret,
+    ]);
+
+    assert_eq!(lint_script(&script), vec![]);
+}
+
+#[test]
+fn unused_type_parameters_are_reported() {
+    let mut script = script_with_code(vec![Bytecode::Ret]);
+    script.type_parameters = vec![AbilitySet::EMPTY; 3];
+    script
+        .signatures
+        .push(Signature(vec![SignatureToken::Vector(Box::new(
+            SignatureToken::TypeParameter(1),
+        ))]));
+
+    assert_eq!(
+        lint_script(&script),
+        vec![
+            LintWarning::UnusedTypeParameter(0),
+            LintWarning::UnusedTypeParameter(2),
+        ]
+    );
+}
+
+#[test]
+fn code_after_abort_is_unreachable() {
+    let script = script_with_code(vec![
+        Bytecode::LdU64(1),
+        Bytecode::Abort,
+        Bytecode::LdU64(2),
+        Bytecode::Pop,
+        Bytecode::Ret,
+    ]);
+
+    assert_eq!(lint_script(&script), vec![LintWarning::UnreachableCode(2)]);
+}
+
+#[test]
+fn arithmetic_on_unchecked_parameters_is_reported() {
+    let script = script_with_code(vec![
+        Bytecode::MoveLoc(0),
+        Bytecode::LdU64(2),
+        Bytecode::Mul,
+        Bytecode::MoveLoc(1),
+        Bytecode::Sub,
+        Bytecode::Pop,
+        Bytecode::Ret,
+    ]);
+
+    assert_eq!(
+        lint_script(&script),
+        vec![
+            LintWarning::UncheckedArithmetic(2),
+            LintWarning::UncheckedArithmetic(4),
+        ]
+    );
+}
+
+#[test]
+fn loops_without_exit_are_reported() {
+    let script = script_with_code(vec![Bytecode::LdU64(0), Bytecode::Pop, Bytecode::Branch(0)]);
+    assert_eq!(lint_script(&script), vec![LintWarning::UnboundedLoop(0)]);
+
+    // The loop is left once the counter reaches zero.
+    let script = script_with_code(vec![
+        Bytecode::CopyLoc(0),
+        Bytecode::LdU64(0),
+        Bytecode::Eq,
+        Bytecode::BrTrue(5),
+        Bytecode::Branch(0),
+        Bytecode::Ret,
+    ]);
+    assert_eq!(lint_script(&script), vec![]);
+}
+
+#[test]
+fn invalid_script_bytes_are_rejected() {
+    assert!(lint_script_bytes(&[0xde, 0xad]).is_err());
+
+    let mut bytes = vec![];
+    script_with_code(vec![Bytecode::Ret])
+        .serialize(&mut bytes)
+        .unwrap();
+    assert_eq!(lint_script_bytes(&bytes), Ok(vec![]));
+}
@@ -0,0 +1,158 @@
+//! Tests for the standard library `String` and `Option<T>` arguments.
+
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag, CORE_CODE_ADDRESS},
+};
+use move_vm_backend_common::{
+    abi::{Function, FunctionVisibility, ModuleAbi, StructDef, Type, TypeAbilities},
+    call_builder::{CallBuilder, CallBuilderError},
+    std_args::{check_arg, option_type, string_type, StdArgError},
+};
+
+fn string() -> Type {
+    Type::Struct(StructDef {
+        id: ModuleId::new(CORE_CODE_ADDRESS, Identifier::new("string").unwrap()),
+        name: Identifier::new("String").unwrap(),
+        fields: vec![],
+    })
+}
+
+fn option(inner: Type) -> Type {
+    Type::Struct(StructDef {
+        id: ModuleId::new(CORE_CODE_ADDRESS, Identifier::new("option").unwrap()),
+        name: Identifier::new("Option").unwrap(),
+        fields: vec![inner],
+    })
+}
+
+fn profile_abi() -> ModuleAbi {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+
+    ModuleAbi {
+        id: ModuleId::new(cafe, Identifier::new("Profile").unwrap()),
+        friends: vec![],
+        structs: vec![],
+        funcs: vec![
+            Function {
+                name: Identifier::new("create").unwrap(),
+                visibility: FunctionVisibility::Public,
+                type_parameters: vec![],
+                parameters: vec![string(), option(Type::U64)],
+                returns: vec![],
+            },
+            Function {
+                name: Identifier::new("generic").unwrap(),
+                visibility: FunctionVisibility::Public,
+                type_parameters: vec![TypeAbilities { abilities: vec![] }],
+                parameters: vec![option(Type::TypeParameter(0))],
+                returns: vec![],
+            },
+        ],
+    }
+}
+
+#[test]
+fn rust_values_are_valid_args() {
+    let args = [
+        (bcs::to_bytes("Alice").unwrap(), string_type()),
+        (bcs::to_bytes("").unwrap(), string_type()),
+        (
+            bcs::to_bytes(&None::<u64>).unwrap(),
+            option_type(TypeTag::U64),
+        ),
+        (
+            bcs::to_bytes(&Some(7u64)).unwrap(),
+            option_type(TypeTag::U64),
+        ),
+        (
+            bcs::to_bytes(&Some("nested")).unwrap(),
+            option_type(string_type()),
+        ),
+        (
+            bcs::to_bytes(&vec!["a", "b"]).unwrap(),
+            TypeTag::Vector(Box::new(string_type())),
+        ),
+    ];
+
+    for (arg, tag) in args {
+        assert_eq!(check_arg(&arg, &tag), Ok(()), "{tag} is not valid");
+    }
+}
+
+#[test]
+fn invalid_values_are_rejected() {
+    let invalid_utf8 = bcs::to_bytes(&vec![0xffu8, 0xfe]).unwrap();
+    assert_eq!(
+        check_arg(&invalid_utf8, &string_type()),
+        Err(StdArgError::InvalidUtf8)
+    );
+
+    let nested = TypeTag::Vector(Box::new(option_type(string_type())));
+    let mut arg = bcs::to_bytes(&vec![Some("ok")]).unwrap();
+    arg[0] = 2;
+    arg.extend(bcs::to_bytes(&Some(vec![0xffu8])).unwrap());
+    assert_eq!(check_arg(&arg, &nested), Err(StdArgError::InvalidUtf8));
+
+    let two_elements = bcs::to_bytes(&vec![1u64, 2]).unwrap();
+    assert_eq!(
+        check_arg(&two_elements, &option_type(TypeTag::U64)),
+        Err(StdArgError::InvalidOption)
+    );
+}
+
+#[test]
+fn undecodable_values_are_left_for_the_vm() {
+    // Truncated string.
+    assert_eq!(check_arg(&[5, b'a'], &string_type()), Ok(()));
+
+    // Structs with an unknown layout.
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let coin = TypeTag::Struct(Box::new(StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Coin").unwrap(),
+        type_params: vec![],
+    }));
+    assert_eq!(check_arg(&[1, 2, 3], &coin), Ok(()));
+}
+
+#[test]
+fn call_builder_accepts_string_and_option_args() {
+    let abi = profile_abi();
+
+    let call = CallBuilder::module("0xCAFE::Profile")
+        .function("create")
+        .arg("Alice")
+        .unwrap()
+        .arg(&None::<u64>)
+        .unwrap()
+        .build(&abi);
+    assert!(call.is_ok());
+
+    let call = CallBuilder::module("0xCAFE::Profile")
+        .function("generic")
+        .type_arg(string_type())
+        .arg(&Some("Bob"))
+        .unwrap()
+        .build(&abi);
+    assert!(call.is_ok());
+
+    let call = CallBuilder::module("0xCAFE::Profile")
+        .function("create")
+        .raw_arg(bcs::to_bytes(&vec![0xffu8]).unwrap())
+        .arg(&Some(1u64))
+        .unwrap()
+        .build(&abi);
+    assert_eq!(call.unwrap_err(), CallBuilderError::InvalidArgument(0));
+
+    let call = CallBuilder::module("0xCAFE::Profile")
+        .function("create")
+        .arg("Alice")
+        .unwrap()
+        .arg(&vec![1u64, 2])
+        .unwrap()
+        .build(&abi);
+    assert_eq!(call.unwrap_err(), CallBuilderError::InvalidArgument(1));
+}
@@ -0,0 +1,98 @@
+//! Tests of the type tag display forms and parsing.
+
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use move_vm_backend_common::type_tag::{
+    display_address, display_type_tag, parse_struct_tag, parse_type_tag, TagForm, TypeTagError,
+};
+
+fn struct_tag(address: &str, module: &str, name: &str, type_params: Vec<TypeTag>) -> StructTag {
+    StructTag {
+        address: AccountAddress::from_hex_literal(address).unwrap(),
+        module: Identifier::new(module).unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params,
+    }
+}
+
+/// `0x1::table::Table<address, vector<0xcafe::BasicCoin::Coin>>`
+fn table_tag() -> TypeTag {
+    let coin = struct_tag("0xCAFE", "BasicCoin", "Coin", vec![]);
+    let coins = TypeTag::Vector(Box::new(TypeTag::Struct(Box::new(coin))));
+    TypeTag::Struct(Box::new(struct_tag(
+        "0x1",
+        "table",
+        "Table",
+        vec![TypeTag::Address, coins],
+    )))
+}
+
+#[test]
+fn short_form_drops_leading_zeros() {
+    assert_eq!(
+        display_type_tag(&table_tag(), TagForm::Short).to_string(),
+        "0x1::table::Table<address, vector<0xcafe::BasicCoin::Coin>>"
+    );
+    assert_eq!(
+        display_address(&AccountAddress::ZERO, TagForm::Short).to_string(),
+        "0x0"
+    );
+    let address = AccountAddress::from_hex_literal("0xA").unwrap();
+    assert_eq!(display_address(&address, TagForm::Short).to_string(), "0xa");
+}
+
+#[test]
+fn long_form_spells_out_the_address() {
+    let one = AccountAddress::ONE.to_canonical_string();
+    let cafe = AccountAddress::from_hex_literal("0xCAFE")
+        .unwrap()
+        .to_canonical_string();
+
+    assert_eq!(
+        display_type_tag(&table_tag(), TagForm::Long).to_string(),
+        format!("0x{one}::table::Table<address, vector<0x{cafe}::BasicCoin::Coin>>")
+    );
+}
+
+#[test]
+fn both_forms_parse_back() {
+    let tag = table_tag();
+    for form in [TagForm::Short, TagForm::Long] {
+        let displayed = display_type_tag(&tag, form).to_string();
+        assert_eq!(parse_type_tag(&displayed), Ok(tag.clone()));
+    }
+
+    // The whitespace around the type parameters is optional.
+    assert_eq!(
+        parse_type_tag("0x1::table::Table< address ,vector<0xCAFE::BasicCoin::Coin> >"),
+        Ok(tag)
+    );
+    assert_eq!(
+        parse_struct_tag("0x1::string::String"),
+        Ok(struct_tag("0x1", "string", "String", vec![]))
+    );
+}
+
+#[test]
+fn malformed_tags_are_rejected() {
+    assert_eq!(parse_type_tag(""), Err(TypeTagError::UnexpectedEnd));
+    assert_eq!(
+        parse_type_tag("vector<u8"),
+        Err(TypeTagError::UnexpectedEnd)
+    );
+    assert_eq!(parse_type_tag("u8>"), Err(TypeTagError::UnexpectedToken(2)));
+    assert_eq!(
+        parse_type_tag("uint"),
+        Err(TypeTagError::UnexpectedToken(0))
+    );
+    assert_eq!(
+        parse_type_tag("0xZZ::m::S"),
+        Err(TypeTagError::InvalidAddress(0))
+    );
+    assert_eq!(parse_type_tag("0x1::m::"), Err(TypeTagError::UnexpectedEnd));
+    assert_eq!(parse_struct_tag("u64"), Err(TypeTagError::NotAStruct));
+
+    let deep = format!("{}u8{}", "vector<".repeat(40), ">".repeat(40));
+    assert_eq!(parse_type_tag(&deep), Err(TypeTagError::TooDeep));
+}
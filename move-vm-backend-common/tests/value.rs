@@ -0,0 +1,88 @@
+//! Tests for the canonical value schema.
+
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::StructTag,
+    u256::U256,
+    value::{MoveStruct, MoveValue},
+};
+use move_vm_backend_common::value::CanonicalValue;
+use parity_scale_codec::{Decode, Encode};
+
+fn coin(value: u64) -> MoveValue {
+    MoveValue::Struct(MoveStruct::WithTypes {
+        type_: StructTag {
+            address: AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+            module: Identifier::new("BasicCoin").unwrap(),
+            name: Identifier::new("Coin").unwrap(),
+            type_params: vec![],
+        },
+        fields: vec![(Identifier::new("value").unwrap(), MoveValue::U64(value))],
+    })
+}
+
+#[test]
+fn json_follows_the_canonical_schema() {
+    let small = CanonicalValue::from(MoveValue::Vector(vec![
+        MoveValue::Bool(true),
+        MoveValue::U8(1),
+        MoveValue::U16(2),
+        MoveValue::U32(3),
+    ]));
+    assert_eq!(small.to_json(), r#"[true,1,2,3]"#);
+
+    // Big integers are strings, so they survive the JavaScript numbers.
+    let big = CanonicalValue::from(MoveValue::Vector(vec![
+        MoveValue::U64(u64::MAX),
+        MoveValue::U128(u128::MAX),
+        MoveValue::U256(U256::from(7u8)),
+    ]));
+    assert_eq!(
+        big.to_json(),
+        format!(r#"["{}","{}","7"]"#, u64::MAX, u128::MAX)
+    );
+
+    let address = CanonicalValue::from(MoveValue::Address(
+        AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+    ));
+    assert_eq!(
+        address.to_json(),
+        format!(r#""0x{}cafe""#, "0".repeat(AccountAddress::LENGTH * 2 - 4))
+    );
+
+    // Fields keep the declaration order, runtime structs use the positional names.
+    let runtime = CanonicalValue::from(MoveValue::Struct(MoveStruct::Runtime(vec![
+        MoveValue::U8(1),
+        coin(10),
+    ])));
+    assert_eq!(runtime.to_json(), r#"{"0":1,"1":{"value":"10"}}"#);
+}
+
+#[test]
+fn scale_keeps_the_value_kinds() {
+    let value = CanonicalValue::from(MoveValue::Vector(vec![
+        coin(10),
+        MoveValue::Struct(MoveStruct::WithFields(vec![(
+            Identifier::new("big").unwrap(),
+            MoveValue::U256(U256::max_value()),
+        )])),
+        MoveValue::Signer(AccountAddress::ONE),
+    ]));
+
+    let encoded = value.encode();
+    assert_eq!(CanonicalValue::decode(&mut &encoded[..]).unwrap(), value);
+
+    let CanonicalValue::Vector(values) = value else {
+        panic!("not a vector");
+    };
+    let CanonicalValue::Struct(coin) = &values[0] else {
+        panic!("not a struct");
+    };
+    // The type name is in the long form, like the addresses.
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    assert_eq!(
+        coin.type_name.as_deref(),
+        Some(format!("0x{}::BasicCoin::Coin", cafe.to_canonical_string()).as_str())
+    );
+}
@@ -0,0 +1,132 @@
+//! Tests of the module verification bound.
+
+use move_binary_format::file_format::{
+    basic_test_module, Bytecode, CompiledModule, Signature, SignatureIndex, SignatureToken,
+};
+use move_core_types::gas_algebra::NumBytes;
+use move_core_types::vm_status::StatusCode;
+use move_vm_backend_common::gas_schedule::{
+    publishing_cost, NumPublishedFunctions, NumPublishedInstructions, NumSignatureNodes,
+    GAS_COST_PER_PUBLISHED_BYTE, GAS_COST_PER_PUBLISHED_FUNCTION,
+    GAS_COST_PER_PUBLISHED_INSTRUCTION, GAS_COST_PER_SIGNATURE_NODE,
+};
+use move_vm_backend_common::types::ModuleBundle;
+use move_vm_backend_common::verification_bound::{
+    bundle_publish_complexity, bundle_verification_bound, module_publish_complexity,
+    module_verification_bound, publish_complexity, verification_bound, PublishComplexity,
+    VerificationBound, VERIFICATION_COST_PER_CODE_UNIT, VERIFICATION_COST_PER_TABLE_ENTRY,
+};
+
+fn serialize(module: &CompiledModule) -> Vec<u8> {
+    let mut bytes = vec![];
+    module.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn bound_counts_tables_and_code() {
+    // Handles, identifiers, the address, the signature, the definitions and the field.
+    let bound = verification_bound(&basic_test_module());
+    assert_eq!(
+        bound,
+        VerificationBound {
+            table_entries: 12,
+            code_units: 1,
+        }
+    );
+    assert_eq!(
+        bound.gas(),
+        12 * VERIFICATION_COST_PER_TABLE_ENTRY + VERIFICATION_COST_PER_CODE_UNIT
+    );
+}
+
+#[test]
+fn loops_and_locals_raise_the_bound() {
+    let mut module = basic_test_module();
+    module
+        .signatures
+        .push(Signature(vec![SignatureToken::Vector(Box::new(
+            SignatureToken::U64,
+        ))]));
+    let code = module.function_defs[0].code.as_mut().unwrap();
+    code.locals = SignatureIndex(1);
+    code.code = vec![Bytecode::LdTrue, Bytecode::BrTrue(0), Bytecode::Ret];
+
+    // The new signature has two type nodes.
+    let bound = verification_bound(&module);
+    assert_eq!(bound.table_entries, 15);
+    // Three instructions with one local, visited once more for the back edge.
+    assert_eq!(bound.code_units, 3 * 2 * 2);
+}
+
+#[test]
+fn bundle_bound_sums_the_modules() {
+    let module = serialize(&basic_test_module());
+    let single = module_verification_bound(&module).unwrap();
+
+    let bundle = ModuleBundle::new(vec![module.clone(), module])
+        .encode()
+        .unwrap();
+    let total = bundle_verification_bound(&bundle).unwrap();
+    assert_eq!(total.table_entries, 2 * single.table_entries);
+    assert_eq!(total.code_units, 2 * single.code_units);
+}
+
+#[test]
+fn malformed_modules_are_rejected() {
+    assert_eq!(
+        module_verification_bound(&[0xA1, 0x1C]),
+        Err(StatusCode::BAD_MAGIC)
+    );
+    assert_eq!(
+        bundle_verification_bound(&[0xff]),
+        Err(StatusCode::CODE_DESERIALIZATION_ERROR)
+    );
+}
+
+#[test]
+fn publish_complexity_counts_functions_instructions_and_signatures() {
+    let mut module = basic_test_module();
+    module
+        .signatures
+        .push(Signature(vec![SignatureToken::Vector(Box::new(
+            SignatureToken::U64,
+        ))]));
+    module.function_defs[0].code.as_mut().unwrap().code =
+        vec![Bytecode::LdTrue, Bytecode::Pop, Bytecode::Ret];
+
+    let complexity = publish_complexity(&module);
+    assert_eq!(
+        complexity,
+        PublishComplexity {
+            functions: 1,
+            instructions: 3,
+            signature_nodes: 2,
+        }
+    );
+
+    // The same length costs more with the complexity terms.
+    let cost = publishing_cost(100, &complexity);
+    let expected = NumBytes::new(100) * GAS_COST_PER_PUBLISHED_BYTE
+        + NumPublishedFunctions::new(1) * GAS_COST_PER_PUBLISHED_FUNCTION
+        + NumPublishedInstructions::new(3) * GAS_COST_PER_PUBLISHED_INSTRUCTION
+        + NumSignatureNodes::new(2) * GAS_COST_PER_SIGNATURE_NODE;
+    assert_eq!(cost, expected);
+}
+
+#[test]
+fn bundle_publish_complexity_sums_the_modules() {
+    let module = serialize(&basic_test_module());
+    let single = module_publish_complexity(&module).unwrap();
+
+    let bundle = ModuleBundle::new(vec![module.clone(), module])
+        .encode()
+        .unwrap();
+    let total = bundle_publish_complexity(&bundle).unwrap();
+    assert_eq!(total.functions, 2 * single.functions);
+    assert_eq!(total.instructions, 2 * single.instructions);
+    assert_eq!(
+        bundle_publish_complexity(&[0xff]),
+        Err(StatusCode::CODE_DESERIALIZATION_ERROR)
+    );
+}
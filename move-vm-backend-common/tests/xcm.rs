@@ -0,0 +1,137 @@
+//! Tests for the XCM message encoding shared with the `std::xcm` module.
+
+use move_core_types::account_address::AccountAddress;
+use move_vm_backend_common::xcm::{
+    XcmAsset, XcmAssetId, XcmError, XcmInstruction, XcmLocation, XcmMessage, XcmOriginKind,
+    XCM_VERSION,
+};
+use serde::Serialize;
+
+fn relay_chain() -> XcmLocation {
+    XcmLocation {
+        parents: 1,
+        parachain: None,
+        account: None,
+    }
+}
+
+fn transfer() -> XcmMessage {
+    XcmMessage {
+        destination: XcmLocation {
+            parents: 1,
+            parachain: Some(1000),
+            account: None,
+        },
+        instruction: XcmInstruction::TransferAsset {
+            asset: XcmAsset {
+                id: XcmAssetId {
+                    parents: 1,
+                    parachain: Some(1000),
+                    pallet_instance: Some(50),
+                    general_index: Some(1984),
+                },
+                amount: 1_000_000,
+            },
+            beneficiary: XcmLocation {
+                parents: 0,
+                parachain: None,
+                account: Some(AccountAddress::from_hex_literal("0xCAFE").unwrap()),
+            },
+        },
+    }
+}
+
+fn transact(origin_kind: XcmOriginKind) -> XcmMessage {
+    XcmMessage {
+        destination: relay_chain(),
+        instruction: XcmInstruction::Transact {
+            origin_kind,
+            ref_time: 1_000_000,
+            proof_size: 10_000,
+            call: vec![0, 7, 42],
+        },
+    }
+}
+
+/// Same layout as the `std::xcm::Message`, with raw fields to build the invalid messages.
+#[derive(Serialize)]
+struct RawMessage {
+    version: u8,
+    destination: XcmLocation,
+    transfer_asset: Option<(XcmAsset, XcmLocation)>,
+    transact: Option<(u8, u64, u64, Vec<u8>)>,
+}
+
+fn raw_transact(origin_kind: u8) -> RawMessage {
+    RawMessage {
+        version: XCM_VERSION,
+        destination: relay_chain(),
+        transfer_asset: None,
+        transact: Some((origin_kind, 0, 0, vec![])),
+    }
+}
+
+#[test]
+fn messages_roundtrip() {
+    for message in [
+        transfer(),
+        transact(XcmOriginKind::SovereignAccount),
+        transact(XcmOriginKind::Xcm),
+    ] {
+        assert_eq!(XcmMessage::decode(&message.encode()), Ok(message));
+    }
+}
+
+#[test]
+fn malformed_message_is_rejected() {
+    let mut bytes = transfer().encode();
+    bytes.pop();
+    assert_eq!(XcmMessage::decode(&bytes), Err(XcmError::Malformed));
+    assert_eq!(XcmMessage::decode(&[]), Err(XcmError::Malformed));
+}
+
+#[test]
+fn unsupported_version_is_rejected() {
+    let mut message = raw_transact(1);
+    message.version = XCM_VERSION + 1;
+    let bytes = bcs::to_bytes(&message).unwrap();
+
+    assert_eq!(
+        XcmMessage::decode(&bytes),
+        Err(XcmError::UnsupportedVersion(XCM_VERSION + 1))
+    );
+}
+
+#[test]
+fn message_must_have_exactly_one_instruction() {
+    let mut message = raw_transact(1);
+    message.transact = None;
+    let bytes = bcs::to_bytes(&message).unwrap();
+    assert_eq!(
+        XcmMessage::decode(&bytes),
+        Err(XcmError::InvalidInstruction)
+    );
+
+    let mut message = raw_transact(1);
+    let XcmInstruction::TransferAsset { asset, beneficiary } = transfer().instruction else {
+        unreachable!()
+    };
+    message.transfer_asset = Some((asset, beneficiary));
+    let bytes = bcs::to_bytes(&message).unwrap();
+    assert_eq!(
+        XcmMessage::decode(&bytes),
+        Err(XcmError::InvalidInstruction)
+    );
+}
+
+#[test]
+fn privileged_origin_kinds_are_rejected() {
+    // The native (0) and the superuser (2) origins.
+    for origin_kind in [0, 2, 4] {
+        let bytes = bcs::to_bytes(&raw_transact(origin_kind)).unwrap();
+        assert_eq!(
+            XcmMessage::decode(&bytes),
+            Err(XcmError::InvalidOriginKind(origin_kind))
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! Deterministic test accounts.
+
+use move_core_types::account_address::AccountAddress;
+
+/// Generate `count` distinct account addresses from the `seed`.
+///
+/// The same seed always yields the same addresses, so the failing tests can be reproduced.
+pub fn seeded_accounts(seed: u64, count: usize) -> Vec<AccountAddress> {
+    let mut rng = SplitMix64(seed);
+
+    (0..count)
+        .map(|_| {
+            let mut bytes = [0u8; AccountAddress::LENGTH];
+            for chunk in bytes.chunks_mut(8) {
+                chunk.copy_from_slice(&rng.next().to_le_bytes());
+            }
+            AccountAddress::new(bytes)
+        })
+        .collect()
+}
+
+/// Minimal seeded generator, so the crate doesn't depend on the `rand` version of the pallet.
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
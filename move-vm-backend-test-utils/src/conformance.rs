@@ -0,0 +1,173 @@
+//! Conformance checks of the host balance handling.
+//!
+//! The MoveVM relies on the balance contract documented on
+//! [`HostBindings::transfer`](move_vm_backend::host::HostBindings::transfer). Adapters should run
+//! [`check_balance_contract`] against their implementation, e.g. in a mock runtime:
+//! ```ignore
+//! new_test_ext().execute_with(|| {
+//!     let adapter = Adapter::new();
+//!     adapter.write_cheque(address(ALICE), 1_000);
+//!     check_balance_contract(&adapter, &ConformanceAccounts {
+//!         funded: address(ALICE),
+//!         existing: address(BOB),
+//!         missing: address(CHARLIE),
+//!     });
+//! });
+//! ```
+//!
+//! The checks panic with the violated rule. Only the cases reaching the host are checked - the
+//! MoveVM handles the transfers to self, the overflows and the dust transfers to the missing
+//! accounts before calling the host.
+
+use move_core_types::account_address::AccountAddress;
+use move_vm_backend::host::HostBindings;
+use std::fmt::Debug;
+
+/// Accounts prepared by the adapter for the conformance checks.
+#[derive(Clone, Copy, Debug)]
+pub struct ConformanceAccounts {
+    /// Existing account with a cheque of at least the minimum balance plus two units.
+    pub funded: AccountAddress,
+    /// Existing account other than the funded one.
+    pub existing: AccountAddress,
+    /// Account which doesn't exist.
+    pub missing: AccountAddress,
+}
+
+/// Balances of the account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Balances {
+    exists: bool,
+    cheque: u128,
+    total: u128,
+}
+
+fn balances<H: HostBindings>(host: &H, account: AccountAddress) -> Balances
+where
+    H::Error: Debug,
+{
+    Balances {
+        exists: host.account_exists(account).expect("account_exists failed"),
+        cheque: host.cheque_amount(account).expect("cheque_amount failed"),
+        total: host.total_amount(account).expect("total_amount failed"),
+    }
+}
+
+/// Check the balance handling of the host against the contract.
+///
+/// The checks move at most the minimum balance plus one unit from the funded account.
+pub fn check_balance_contract<H: HostBindings>(host: &H, accounts: &ConformanceAccounts)
+where
+    H::Error: Debug,
+{
+    let ConformanceAccounts {
+        funded,
+        existing,
+        missing,
+    } = *accounts;
+    let transfer = |src, dst, amount| {
+        host.transfer(src, dst, amount)
+            .expect("transfer failed - the error is reserved for the host failures")
+    };
+    let snapshot = || {
+        (
+            balances(host, funded),
+            balances(host, existing),
+            balances(host, missing),
+            host.total_issuance().expect("total_issuance failed"),
+        )
+    };
+
+    let minimum_balance = host.minimum_balance().expect("minimum_balance failed");
+    let before = snapshot();
+    assert!(
+        before.0.exists && before.1.exists,
+        "prepared accounts must exist"
+    );
+    assert!(!before.2.exists, "the missing account must not exist");
+    assert!(
+        before.0.cheque >= minimum_balance.saturating_add(2),
+        "the funded account needs a cheque of at least the minimum balance plus two units"
+    );
+
+    // Zero transfers.
+    assert!(transfer(funded, existing, 0), "zero transfers must succeed");
+    assert!(transfer(funded, missing, 0), "zero transfers must succeed");
+    assert_eq!(
+        snapshot(),
+        before,
+        "zero transfers must not change anything"
+    );
+
+    // Transfers beyond the cheque.
+    if let Some(amount) = before.0.cheque.checked_add(1) {
+        assert!(
+            !transfer(funded, existing, amount),
+            "transfers beyond the cheque must return false"
+        );
+    }
+    if before.0.cheque < u128::MAX {
+        assert!(
+            !transfer(funded, existing, u128::MAX),
+            "transfers beyond the balance type must return false"
+        );
+    }
+    assert_eq!(
+        snapshot(),
+        before,
+        "refused transfers must not change anything"
+    );
+
+    // Dust transfers to the missing accounts.
+    if minimum_balance > 1 {
+        assert!(
+            !transfer(funded, missing, minimum_balance - 1),
+            "transfers below the minimum balance to a missing account must return false"
+        );
+        assert_eq!(
+            snapshot(),
+            before,
+            "refused transfers must not change anything"
+        );
+    }
+
+    // Regular transfers.
+    assert!(
+        transfer(funded, existing, 1),
+        "affordable transfers must succeed"
+    );
+    let after = snapshot();
+    assert_eq!(
+        after.0.cheque,
+        before.0.cheque - 1,
+        "the cheque must be spent"
+    );
+    assert_eq!(
+        after.0.total,
+        before.0.total - 1,
+        "the source must be debited"
+    );
+    assert_eq!(
+        after.1.total,
+        before.1.total + 1,
+        "the destination must be credited"
+    );
+    assert_eq!(
+        after.3, before.3,
+        "transfers must not change the total issuance"
+    );
+
+    let amount = minimum_balance.max(1);
+    assert!(
+        transfer(funded, missing, amount),
+        "transfers of the minimum balance to a missing account must succeed"
+    );
+    let created = balances(host, missing);
+    assert!(created.exists, "the destination account must be created");
+    assert_eq!(created.total, amount, "the destination must be credited");
+    assert_eq!(
+        host.total_issuance().expect("total_issuance failed"),
+        before.3,
+        "transfers must not change the total issuance"
+    );
+}
@@ -0,0 +1,72 @@
+//! Helpers for fuzzing the gas limits.
+//!
+//! Operations must fail cleanly with the out-of-gas error at any limit below the required gas,
+//! without partially applying the changes. [`fuzz_gas_limits`] runs an operation with the seeded
+//! random limits, and [`min_gas_limit`] finds the exact limit an operation needs.
+
+use crate::accounts::SplitMix64;
+use move_vm_backend::types::{GasAmount, GasStrategy, VmResult, MAX_GAS_AMOUNT};
+
+/// Generate `count` metered gas strategies with the limits in the `0..=max` range.
+pub fn gas_limits(seed: u64, max: u64, count: usize) -> Vec<GasStrategy> {
+    let mut rng = SplitMix64(seed);
+
+    (0..count)
+        .map(|_| {
+            let limit = match max.checked_add(1) {
+                Some(bound) => rng.next() % bound,
+                None => rng.next(),
+            };
+            metered(limit)
+        })
+        .collect()
+}
+
+/// Run the operation with the seeded random gas limits in the `0..=max` range.
+///
+/// Returns the limits with the results of the operation.
+pub fn fuzz_gas_limits<F>(seed: u64, max: u64, count: usize, mut op: F) -> Vec<(u64, VmResult)>
+where
+    F: FnMut(GasStrategy) -> VmResult,
+{
+    gas_limits(seed, max, count)
+        .into_iter()
+        .map(|gas| {
+            let GasStrategy::Metered(limit) = gas else {
+                unreachable!("only the metered strategies are generated");
+            };
+            (limit.inner(), op(gas))
+        })
+        .collect()
+}
+
+/// Find the lowest gas limit up to the `max` the operation succeeds with.
+///
+/// The operation must be repeatable, e.g. by restoring a
+/// [`StorageSnapshot`](crate::StorageSnapshot) before each run.
+pub fn min_gas_limit<F>(max: u64, mut op: F) -> Option<u64>
+where
+    F: FnMut(GasStrategy) -> VmResult,
+{
+    if !op(metered(max)).is_ok() {
+        return None;
+    }
+
+    let (mut low, mut high) = (0, max);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if op(metered(mid)).is_ok() {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    Some(high)
+}
+
+fn metered(limit: u64) -> GasStrategy {
+    let amount =
+        GasAmount::new(limit.min(MAX_GAS_AMOUNT)).expect("the limit is capped to the maximum");
+    GasStrategy::Metered(amount)
+}
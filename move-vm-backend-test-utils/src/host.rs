@@ -0,0 +1,286 @@
+//! In-memory host bindings mock.
+
+use move_core_types::account_address::AccountAddress;
+use move_core_types::vm_status::StatusCode;
+use move_vm_backend::host::{ForeignCallResponse, HostBindings, XcmMessage};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Foreign call target which echoes the payload.
+pub const ECHO_TARGET: u64 = 1;
+
+/// Foreign call target which always fails with the [`FAILING_TARGET_ABORT_CODE`].
+pub const FAILING_TARGET: u64 = 2;
+
+pub const FAILING_TARGET_ABORT_CODE: u64 = 42;
+
+/// Host failure injected into the [`HostMock`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HostFailure {
+    /// [`HostBindings::transfer`] fails.
+    Transfer,
+    /// The balance queries fail.
+    Balance,
+    /// [`HostBindings::create_account`] fails.
+    CreateAccount,
+    /// [`HostBindings::refund_fee`] fails.
+    RefundFee,
+    /// [`HostBindings::foreign_call`] fails.
+    ForeignCall,
+    /// [`HostBindings::send_xcm`] fails.
+    SendXcm,
+    /// [`HostBindings::random_seed`] fails.
+    Randomness,
+    /// [`HostBindings::now_milliseconds`] fails.
+    Time,
+}
+
+/// In-memory host bindings for testing.
+///
+/// The balances are the cheque amounts. The random seeds are the SHA3-256 hashes of the
+/// randomness set with [`HostMock::set_randomness`] and the subject. The module verification
+/// markers are kept in memory, the same way as in the node-local storage. Clones share the state.
+#[derive(Clone, Debug, Default)]
+pub struct HostMock {
+    cheques: Rc<RefCell<HashMap<AccountAddress, u128>>>,
+    sent_xcm: Rc<RefCell<Vec<(AccountAddress, XcmMessage)>>>,
+    failures: Rc<RefCell<HashMap<HostFailure, StatusCode>>>,
+    transfer_weight: Rc<Cell<u64>>,
+    consumed_weight: Rc<Cell<u64>>,
+    minimum_balance: Rc<Cell<u128>>,
+    randomness: Rc<Cell<[u8; 32]>>,
+    now_milliseconds: Rc<Cell<u64>>,
+    verified_modules: Rc<RefCell<HashMap<[u8; 32], [u8; 32]>>>,
+}
+
+impl HostMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mock with each of the `accounts` holding the `amount`.
+    ///
+    /// Use with [`seeded_accounts`](crate::seeded_accounts) for deterministic test accounts.
+    pub fn with_accounts(accounts: &[AccountAddress], amount: u128) -> Self {
+        let mut mock = Self::new();
+        for account in accounts {
+            mock.write_cheque(*account, amount);
+        }
+        mock
+    }
+
+    /// XCM messages sent so far, with their origins.
+    pub fn sent_xcm(&self) -> Vec<(AccountAddress, XcmMessage)> {
+        self.sent_xcm.borrow().clone()
+    }
+
+    pub fn write_cheque(&mut self, account: AccountAddress, amount: u128) {
+        let mut cheques = self.cheques.borrow_mut();
+
+        if let Some(current_amount) = cheques.get_mut(&account) {
+            *current_amount += amount;
+        } else {
+            cheques.insert(account, amount);
+        }
+    }
+
+    /// Copy the current balances.
+    pub fn snapshot(&self) -> HashMap<AccountAddress, u128> {
+        self.cheques.borrow().clone()
+    }
+
+    /// Replace the balances with the snapshot.
+    pub fn restore(&self, snapshot: &HashMap<AccountAddress, u128>) {
+        *self.cheques.borrow_mut() = snapshot.clone();
+    }
+
+    /// Make the host service fail with the `error` until the failures are cleared.
+    pub fn inject_failure(&self, failure: HostFailure, error: StatusCode) {
+        self.failures.borrow_mut().insert(failure, error);
+    }
+
+    /// Remove all injected failures.
+    pub fn clear_failures(&self) {
+        self.failures.borrow_mut().clear();
+    }
+
+    /// Report the `weight` as consumed by every transfer.
+    pub fn set_transfer_weight(&self, weight: u64) {
+        self.transfer_weight.set(weight);
+    }
+
+    /// Set the minimum balance of the accounts, zero by default.
+    pub fn set_minimum_balance(&self, amount: u128) {
+        self.minimum_balance.set(amount);
+    }
+
+    /// Set the randomness the seeds are derived from, zeros by default.
+    pub fn set_randomness(&self, randomness: [u8; 32]) {
+        self.randomness.set(randomness);
+    }
+
+    /// Set the timestamp of the current block, zero by default.
+    pub fn set_now_milliseconds(&self, now: u64) {
+        self.now_milliseconds.set(now);
+    }
+
+    /// Module verification markers recorded so far, keyed by the module hash.
+    pub fn verified_modules(&self) -> HashMap<[u8; 32], [u8; 32]> {
+        self.verified_modules.borrow().clone()
+    }
+
+    fn check(&self, failure: HostFailure) -> Result<(), StatusCode> {
+        match self.failures.borrow().get(&failure) {
+            Some(error) => Err(*error),
+            None => Ok(()),
+        }
+    }
+}
+
+impl HostBindings for HostMock {
+    type Error = StatusCode;
+
+    fn transfer(
+        &self,
+        src: AccountAddress,
+        dst: AccountAddress,
+        cheque_amount: u128,
+    ) -> Result<bool, Self::Error> {
+        self.check(HostFailure::Transfer)?;
+        self.consumed_weight
+            .set(self.consumed_weight.get() + self.transfer_weight.get());
+        let mut cheques = self.cheques.borrow_mut();
+        if cheque_amount == 0 {
+            return Ok(true);
+        }
+
+        let src_balance = cheques.get(&src).copied().unwrap_or(0);
+        let dst_balance = cheques.get(&dst).copied();
+        if src_balance < cheque_amount {
+            return Ok(false);
+        }
+        if src == dst {
+            return Ok(true);
+        }
+        let dst_balance = match dst_balance {
+            Some(balance) => balance.checked_add(cheque_amount),
+            None if cheque_amount < self.minimum_balance.get() => None,
+            None => Some(cheque_amount),
+        };
+        let Some(dst_balance) = dst_balance else {
+            return Ok(false);
+        };
+
+        cheques.insert(src, src_balance - cheque_amount);
+        cheques.insert(dst, dst_balance);
+        Ok(true)
+    }
+
+    fn cheque_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
+        self.check(HostFailure::Balance)?;
+        Ok(*self.cheques.borrow().get(&account).unwrap_or(&0))
+    }
+
+    fn total_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
+        // We won't need it here.
+        self.cheque_amount(account)
+    }
+
+    fn total_issuance(&self) -> Result<u128, Self::Error> {
+        self.check(HostFailure::Balance)?;
+        Ok(self.cheques.borrow().values().sum())
+    }
+
+    fn account_exists(&self, account: AccountAddress) -> Result<bool, Self::Error> {
+        self.check(HostFailure::Balance)?;
+        Ok(self.cheques.borrow().contains_key(&account))
+    }
+
+    fn minimum_balance(&self) -> Result<u128, Self::Error> {
+        self.check(HostFailure::Balance)?;
+        Ok(self.minimum_balance.get())
+    }
+
+    fn create_account(
+        &self,
+        _payer: AccountAddress,
+        account: AccountAddress,
+    ) -> Result<bool, Self::Error> {
+        self.check(HostFailure::CreateAccount)?;
+        // The creation is free, whatever the minimum balance.
+        self.cheques.borrow_mut().entry(account).or_insert(0);
+        Ok(true)
+    }
+
+    fn withdraw_fee(&self, payer: AccountAddress, fee: u128) -> Result<bool, Self::Error> {
+        self.check(HostFailure::Balance)?;
+        let mut cheques = self.cheques.borrow_mut();
+
+        match cheques.get_mut(&payer) {
+            Some(balance) if *balance >= fee => {
+                *balance -= fee;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn refund_fee(&self, payer: AccountAddress, refund: u128) -> Result<(), Self::Error> {
+        self.check(HostFailure::RefundFee)?;
+        *self.cheques.borrow_mut().entry(payer).or_insert(0) += refund;
+        Ok(())
+    }
+
+    fn foreign_call(
+        &self,
+        target: u64,
+        payload: &[u8],
+        gas_limit: u64,
+    ) -> Result<ForeignCallResponse, Self::Error> {
+        self.check(HostFailure::ForeignCall)?;
+        match target {
+            // Each echoed byte costs a gas unit.
+            ECHO_TARGET if payload.len() as u64 <= gas_limit => Ok(ForeignCallResponse {
+                gas_used: payload.len() as u64,
+                result: Ok(payload.to_vec()),
+            }),
+            ECHO_TARGET => Err(StatusCode::OUT_OF_GAS),
+            FAILING_TARGET => Ok(ForeignCallResponse {
+                gas_used: 0,
+                result: Err(FAILING_TARGET_ABORT_CODE),
+            }),
+            _ => Err(StatusCode::VM_EXTENSION_ERROR),
+        }
+    }
+
+    fn send_xcm(&self, origin: AccountAddress, message: XcmMessage) -> Result<bool, Self::Error> {
+        self.check(HostFailure::SendXcm)?;
+        self.sent_xcm.borrow_mut().push((origin, message));
+        Ok(true)
+    }
+
+    fn random_seed(&self, subject: &[u8]) -> Result<[u8; 32], Self::Error> {
+        self.check(HostFailure::Randomness)?;
+        Ok(self.sha3_256(&[&self.randomness.get()[..], subject].concat()))
+    }
+
+    fn now_milliseconds(&self) -> Result<u64, Self::Error> {
+        self.check(HostFailure::Time)?;
+        Ok(self.now_milliseconds.get())
+    }
+
+    fn take_consumed_weight(&self) -> u64 {
+        self.consumed_weight.take()
+    }
+
+    fn verified_module(&self, module_hash: &[u8; 32]) -> Option<[u8; 32]> {
+        self.verified_modules.borrow().get(module_hash).copied()
+    }
+
+    fn set_verified_module(&self, module_hash: &[u8; 32], config_hash: &[u8; 32]) {
+        self.verified_modules
+            .borrow_mut()
+            .insert(*module_hash, *config_hash);
+    }
+}
@@ -0,0 +1,26 @@
+//! Mocks and helpers for testing the MoveVM backend integrations.
+//!
+//! The pallets can use the mocks to test their MoveVM code without the full runtime:
+//! - [`StorageMock`] - in-memory [`Storage`](move_vm_backend::storage::Storage) with the snapshots,
+//!   the key-value dumps and the failure injection.
+//! - [`HostMock`] - in-memory [`HostBindings`](move_vm_backend::host::HostBindings) with the
+//!   cheque balances, foreign call targets, randomness, time and the failure injection.
+//! - [`seeded_accounts`] - deterministic account addresses.
+//! - [`check_balance_contract`] - conformance checks of the host balance handling.
+//! - [`gas`] - helpers for fuzzing the gas limits.
+//! - `package` - in-process builds of the Move packages, with the `package-build` feature.
+
+pub mod accounts;
+pub mod conformance;
+pub mod gas;
+pub mod host;
+#[cfg(feature = "package-build")]
+pub mod package;
+pub mod storage;
+
+pub use accounts::seeded_accounts;
+pub use conformance::{check_balance_contract, ConformanceAccounts};
+pub use host::{HostFailure, HostMock, ECHO_TARGET, FAILING_TARGET, FAILING_TARGET_ABORT_CODE};
+#[cfg(feature = "package-build")]
+pub use package::{BuiltPackage, PackageBuilder};
+pub use storage::{StorageFailure, StorageMock, StorageSnapshot};
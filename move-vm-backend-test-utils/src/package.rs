@@ -0,0 +1,193 @@
+//! Programmatic builds of the Move packages.
+//!
+//! [`PackageBuilder`] runs the Move compiler in-process, so the test suites and the tooling don't
+//! need the external `smove` binary to get the bytecode the backend accepts:
+//! ```ignore
+//! let package = PackageBuilder::new("tests/assets/move-projects/basic_coin")
+//!     .named_address("CafeAccount", cafe)
+//!     .build()?;
+//! let result = vm.publish_module_bundle(&package.bundle()?, cafe, gas);
+//! ```
+//!
+//! The named addresses set on the builder take precedence over the package manifest, so the same
+//! package can be built for different accounts. The bytecode is serialized with
+//! [`VERSION_MAX`], the newest version the backend accepts, unless pinned to another one with
+//! [`PackageBuilder::bytecode_version`].
+//!
+//! Only the modules of the package itself are included - its dependencies, e.g. the standard
+//! library, are expected to be published already.
+
+use anyhow::{bail, Result};
+use move_binary_format::{access::ModuleAccess, file_format_common::VERSION_MAX, CompiledModule};
+use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule, NamedCompiledScript};
+use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
+use move_package::BuildConfig;
+use move_vm_backend_common::types::ModuleBundle;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Builder of the Move package in the given directory.
+#[derive(Debug, Clone)]
+pub struct PackageBuilder {
+    path: PathBuf,
+    named_addresses: BTreeMap<String, AccountAddress>,
+    bytecode_version: u32,
+    install_dir: Option<PathBuf>,
+    dev_mode: bool,
+}
+
+impl PackageBuilder {
+    /// Build the package in the given directory, containing the `Move.toml` manifest.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            named_addresses: BTreeMap::new(),
+            bytecode_version: VERSION_MAX,
+            install_dir: None,
+            dev_mode: false,
+        }
+    }
+
+    /// Set the value of the named address, overriding the manifest.
+    pub fn named_address(mut self, name: &str, address: AccountAddress) -> Self {
+        self.named_addresses.insert(name.to_string(), address);
+        self
+    }
+
+    /// Serialize the bytecode with the given version.
+    pub fn bytecode_version(mut self, version: u32) -> Self {
+        self.bytecode_version = version;
+        self
+    }
+
+    /// Write the build artifacts to the given directory instead of the package directory.
+    pub fn install_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.install_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Use the `dev-addresses` and `dev-dependencies` of the manifest.
+    pub fn dev_mode(mut self, enabled: bool) -> Self {
+        self.dev_mode = enabled;
+        self
+    }
+
+    /// Compile the package.
+    ///
+    /// The compiler diagnostics are returned as the error.
+    pub fn build(self) -> Result<BuiltPackage> {
+        let config = BuildConfig {
+            dev_mode: self.dev_mode,
+            install_dir: self.install_dir,
+            additional_named_addresses: self.named_addresses,
+            bytecode_version: Some(self.bytecode_version),
+            ..Default::default()
+        };
+
+        let mut output = Vec::new();
+        let package = match config.compile_package_no_exit(&self.path, &mut output) {
+            Ok(package) => package,
+            Err(e) => bail!("{e}\n{}", String::from_utf8_lossy(&output)),
+        };
+
+        let mut modules = Vec::new();
+        let mut scripts = BTreeMap::new();
+        for unit in &package.root_compiled_units {
+            match &unit.unit {
+                CompiledUnit::Module(NamedCompiledModule { module, .. }) => {
+                    modules.push(module.clone())
+                }
+                CompiledUnit::Script(NamedCompiledScript { name, .. }) => {
+                    scripts.insert(
+                        name.to_string(),
+                        unit.unit.serialize(Some(self.bytecode_version)),
+                    );
+                }
+            }
+        }
+
+        let modules = dependency_order(modules)?
+            .into_iter()
+            .map(|module| {
+                let mut bytes = Vec::new();
+                module.serialize_for_version(Some(self.bytecode_version), &mut bytes)?;
+                Ok((module.self_id(), bytes))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(BuiltPackage { modules, scripts })
+    }
+}
+
+/// Bytecode of the built package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuiltPackage {
+    /// Serialized modules of the package, each after the package modules it depends on.
+    pub modules: Vec<(ModuleId, Vec<u8>)>,
+    /// Serialized scripts of the package by their names.
+    pub scripts: BTreeMap<String, Vec<u8>>,
+}
+
+impl BuiltPackage {
+    /// Serialized module with the given name.
+    pub fn module(&self, name: &str) -> Option<&[u8]> {
+        self.modules
+            .iter()
+            .find(|(id, _)| id.name().as_str() == name)
+            .map(|(_, bytes)| bytes.as_slice())
+    }
+
+    /// Serialized script with the given name.
+    pub fn script(&self, name: &str) -> Option<&[u8]> {
+        self.scripts.get(name).map(Vec::as_slice)
+    }
+
+    /// Encode all modules as the bundle for [`move_vm_backend::Mvm::publish_module_bundle`].
+    pub fn bundle(&self) -> Result<Vec<u8>> {
+        let modules = self
+            .modules
+            .iter()
+            .map(|(_, bytes)| bytes.clone())
+            .collect();
+        ModuleBundle::new(modules).encode()
+    }
+}
+
+/// Sort the modules so each one follows the modules it depends on.
+fn dependency_order(modules: Vec<CompiledModule>) -> Result<Vec<CompiledModule>> {
+    let mut pending: BTreeMap<_, _> = modules
+        .into_iter()
+        .map(|module| (module.self_id(), module))
+        .collect();
+    let mut ordered = Vec::with_capacity(pending.len());
+
+    while let Some(id) = pending.keys().next().cloned() {
+        visit(id, &mut pending, &mut BTreeSet::new(), &mut ordered)?;
+    }
+
+    Ok(ordered)
+}
+
+fn visit(
+    id: ModuleId,
+    pending: &mut BTreeMap<ModuleId, CompiledModule>,
+    path: &mut BTreeSet<ModuleId>,
+    ordered: &mut Vec<CompiledModule>,
+) -> Result<()> {
+    if !path.insert(id.clone()) {
+        bail!("Cyclic dependency of module {id}");
+    }
+    // Visited already or the module of another package.
+    let Some(module) = pending.remove(&id) else {
+        path.remove(&id);
+        return Ok(());
+    };
+
+    for dependency in module.immediate_dependencies() {
+        visit(dependency, pending, path, ordered)?;
+    }
+
+    path.remove(&id);
+    ordered.push(module);
+    Ok(())
+}
@@ -0,0 +1,132 @@
+//! In-memory storage mock.
+
+use move_vm_backend::storage::Storage;
+use move_vm_backend::storage_dump::StorageDump;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Storage failure injected into the [`StorageMock`].
+///
+/// The [`Storage`] trait is infallible, so the failures simulate a corrupted or an inconsistent
+/// storage the MoveVM must survive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageFailure {
+    /// The reads return nothing, as if the entry was missing.
+    MissingRead,
+    /// The reads return a value which can't be decoded.
+    CorruptedRead,
+    /// The writes and removals are silently dropped.
+    DroppedWrite,
+}
+
+/// Copy of the [`StorageMock`] data, see [`StorageMock::snapshot`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageSnapshot(HashMap<Vec<u8>, Vec<u8>>);
+
+/// In-memory storage for testing.
+///
+/// Clones share the data, so the test can inspect the storage after handing it to the MoveVM.
+#[derive(Clone, Debug, Default)]
+pub struct StorageMock {
+    pub data: Rc<RefCell<HashMap<Vec<u8>, Vec<u8>>>>,
+    /// Keys and values of all writes, in the order they were made.
+    pub writes: Rc<RefCell<Vec<(Vec<u8>, Vec<u8>)>>>,
+    /// Injected failures with the key prefixes they apply to.
+    failures: Rc<RefCell<Vec<(StorageFailure, Vec<u8>)>>>,
+}
+
+impl StorageMock {
+    pub fn new() -> StorageMock {
+        StorageMock::default()
+    }
+
+    /// Copy the current data.
+    pub fn snapshot(&self) -> StorageSnapshot {
+        StorageSnapshot(self.data.borrow().clone())
+    }
+
+    /// Replace the data with the snapshot, e.g. to repeat an operation from the same state.
+    ///
+    /// The recorded writes are kept.
+    pub fn restore(&self, snapshot: &StorageSnapshot) {
+        *self.data.borrow_mut() = snapshot.0.clone();
+    }
+
+    /// Storage holding the entries of the dump, e.g. the state captured from a node.
+    pub fn from_dump(dump: &StorageDump) -> StorageMock {
+        let storage = StorageMock::new();
+        storage
+            .data
+            .borrow_mut()
+            .extend(dump.entries().iter().cloned());
+        storage
+    }
+
+    /// Export the current data, e.g. to replay a test scenario later.
+    pub fn dump(&self) -> StorageDump {
+        StorageDump::new(
+            self.data
+                .borrow()
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        )
+    }
+
+    /// Inject the failure for all keys starting with the `prefix`.
+    ///
+    /// An empty prefix applies the failure to the whole storage.
+    pub fn inject_failure(&self, failure: StorageFailure, prefix: &[u8]) {
+        self.failures
+            .borrow_mut()
+            .push((failure, prefix.to_owned()));
+    }
+
+    /// Remove all injected failures.
+    pub fn clear_failures(&self) {
+        self.failures.borrow_mut().clear();
+    }
+
+    fn fails(&self, failure: StorageFailure, key: &[u8]) -> bool {
+        self.failures
+            .borrow()
+            .iter()
+            .any(|(f, prefix)| *f == failure && key.starts_with(prefix))
+    }
+}
+
+impl Storage for StorageMock {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        if self.fails(StorageFailure::MissingRead, key) {
+            return None;
+        }
+        if self.fails(StorageFailure::CorruptedRead, key) {
+            // Not a valid BCS value - the length prefix claims more bytes than there are.
+            return Some(vec![0xff; 4]);
+        }
+
+        let data = self.data.borrow();
+        data.get(key).map(|blob| blob.to_owned())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) {
+        if self.fails(StorageFailure::DroppedWrite, key) {
+            return;
+        }
+
+        let mut data = self.data.borrow_mut();
+        data.insert(key.to_owned(), value.to_owned());
+        self.writes
+            .borrow_mut()
+            .push((key.to_owned(), value.to_owned()));
+    }
+
+    fn remove(&self, key: &[u8]) {
+        if self.fails(StorageFailure::DroppedWrite, key) {
+            return;
+        }
+
+        let mut data = self.data.borrow_mut();
+        data.remove(key);
+    }
+}
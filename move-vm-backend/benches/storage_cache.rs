@@ -0,0 +1,113 @@
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use move_vm_backend::storage::Storage;
+use move_vm_backend::storage_cache::{CachedStorage, LruReadCache, WriteBackCache};
+use move_vm_backend_test_utils::StorageMock;
+
+//
+// Storage cache benchmarks
+//
+// The host storage is simulated by the in-memory storage hashing every accessed key, which
+// stands for the cost of the runtime interface call. Each iteration replays the access pattern
+// of a block: mostly the reads of a small hot set of accounts, with a write every few reads, and
+// the flush at the end of every transaction.
+//
+
+/// Number of the stored entries.
+const ENTRIES: usize = 1_000;
+/// Number of the entries most transactions touch.
+const HOT_ENTRIES: usize = 50;
+/// Size of each stored value.
+const VALUE_SIZE: usize = 512;
+/// Storage accesses of a single transaction.
+const ACCESSES_PER_TRANSACTION: usize = 20;
+/// Transactions of a block.
+const TRANSACTIONS: usize = 100;
+
+/// In-memory storage charging a hash of the key for every access.
+struct HostStorage(StorageMock);
+
+impl HostStorage {
+    fn host_call(key: &[u8]) {
+        criterion::black_box(Blake2b::<U32>::digest(key));
+    }
+}
+
+impl Storage for HostStorage {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        Self::host_call(key);
+        self.0.get(key)
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) {
+        Self::host_call(key);
+        self.0.set(key, value)
+    }
+
+    fn remove(&self, key: &[u8]) {
+        Self::host_call(key);
+        self.0.remove(key)
+    }
+}
+
+fn host_storage() -> HostStorage {
+    let storage = StorageMock::new();
+    for index in 0..ENTRIES {
+        storage.set(&key(index), &[index as u8; VALUE_SIZE]);
+    }
+    HostStorage(storage)
+}
+
+fn key(index: usize) -> Vec<u8> {
+    format!("account-{index:08}").into_bytes()
+}
+
+/// Replay the block on the storage.
+fn block(storage: &impl Storage) {
+    for transaction in 0..TRANSACTIONS {
+        for access in 0..ACCESSES_PER_TRANSACTION {
+            // Every tenth access touches a cold entry.
+            let index = if access % 10 == 9 {
+                (transaction * ACCESSES_PER_TRANSACTION + access) % ENTRIES
+            } else {
+                (transaction + access) % HOT_ENTRIES
+            };
+
+            let value = storage.get(&key(index));
+            if access % 4 == 3 {
+                storage.set(&key(index), &value.unwrap_or_default());
+            }
+        }
+        storage.flush();
+    }
+}
+
+fn storage_cache(c: &mut Criterion) {
+    let mut group = c.benchmark_group("storage_cache");
+
+    group.bench_function("uncached", |b| {
+        let storage = host_storage();
+        b.iter(|| block(&storage))
+    });
+
+    for budget in [HOT_ENTRIES * VALUE_SIZE / 4, 2 * HOT_ENTRIES * VALUE_SIZE] {
+        group.bench_with_input(
+            BenchmarkId::new("lru_read", budget),
+            &budget,
+            |b, &budget| {
+                let storage = CachedStorage::new(host_storage(), LruReadCache::new(budget));
+                b.iter(|| block(&storage))
+            },
+        );
+    }
+
+    group.bench_function("write_back", |b| {
+        let storage = CachedStorage::new(host_storage(), WriteBackCache::new());
+        b.iter(|| block(&storage))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, storage_cache);
+criterion_main!(benches);
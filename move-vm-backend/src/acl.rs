@@ -0,0 +1,67 @@
+//! Owner-only resource access control enforced by the VM.
+//!
+//! A module can opt in by listing some of its structs in the [`OWNER_ONLY_METADATA_KEY`]
+//! metadata entry. Resources of the listed structs can only be created, modified or deleted in
+//! transactions signed by the account holding the resource - regardless of the Move-level logic
+//! of the module.
+
+use alloc::{collections::BTreeSet, vec::Vec};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{CompiledModule, SignatureToken},
+};
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, metadata::Metadata,
+};
+
+/// Metadata key of the BCS-encoded list of the owner-only struct names.
+pub const OWNER_ONLY_METADATA_KEY: &[u8] = b"owner_only_resources";
+
+/// Creates the module metadata entry which marks the given structs as owner-only.
+pub fn owner_only_metadata(struct_names: &[Identifier]) -> Metadata {
+    Metadata {
+        key: OWNER_ONLY_METADATA_KEY.to_vec(),
+        value: bcs::to_bytes(struct_names).expect("identifier serialization can't fail"),
+    }
+}
+
+/// Reads the owner-only struct names from the module metadata.
+///
+/// The invalid metadata entry marks no structs.
+pub(crate) fn owner_only_structs(module: &CompiledModule) -> BTreeSet<Identifier> {
+    module
+        .metadata
+        .iter()
+        .filter(|metadata| metadata.key == OWNER_ONLY_METADATA_KEY)
+        .filter_map(|metadata| bcs::from_bytes::<Vec<Identifier>>(&metadata.value).ok())
+        .flatten()
+        .collect()
+}
+
+/// Reads the signer addresses from the arguments which match the leading signer parameters.
+pub(crate) fn leading_signers(
+    params: &[SignatureToken],
+    args: &[Vec<u8>],
+) -> BTreeSet<AccountAddress> {
+    params
+        .iter()
+        .zip(args)
+        .take_while(|(param, _)| match param {
+            SignatureToken::Signer => true,
+            SignatureToken::Reference(inner) => inner.is_signer(),
+            _ => false,
+        })
+        .filter_map(|(_, arg)| bcs::from_bytes(arg).ok())
+        .collect()
+}
+
+/// Reads the parameters of the module function.
+pub(crate) fn function_params(module: &CompiledModule, name: &Identifier) -> Vec<SignatureToken> {
+    module
+        .function_defs()
+        .iter()
+        .map(|def| module.function_handle_at(def.function))
+        .find(|handle| module.identifier_at(handle.name) == name.as_ident_str())
+        .map(|handle| module.signature_at(handle.parameters).0.clone())
+        .unwrap_or_default()
+}
@@ -0,0 +1,63 @@
+//! Optional allowlist policy for the script execution.
+//!
+//! Once the policy is enabled, only the scripts whose bytecode hash is in the allowlist can be
+//! executed. Module function calls are never restricted.
+
+use crate::storage::Storage;
+use crate::storage_key::{ALLOWED_SCRIPT_KEY_PREFIX, ALLOWLIST_ENABLED_KEY};
+use alloc::vec::Vec;
+use move_vm_backend_common::code_hash::{script_hash, CodeHash};
+
+/// Script hash - see [`move_vm_backend_common::code_hash`].
+pub type AllowedScriptHash = CodeHash;
+
+/// Calculates the [`AllowedScriptHash`] for the script bytecode.
+pub fn allowed_script_hash(bytecode: &[u8]) -> AllowedScriptHash {
+    script_hash(bytecode)
+}
+
+/// Keeps the script allowlist in the storage.
+pub(crate) struct ScriptAllowlist<'a, S: Storage> {
+    storage: &'a S,
+}
+
+impl<'a, S: Storage> ScriptAllowlist<'a, S> {
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self { storage }
+    }
+
+    /// Check if the allowlist policy is enabled.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.storage.get(ALLOWLIST_ENABLED_KEY).is_some()
+    }
+
+    /// Enables or disables the allowlist policy - the allowed hashes are kept either way.
+    pub(crate) fn set_enabled(&self, enabled: bool) {
+        if enabled {
+            self.storage.set(ALLOWLIST_ENABLED_KEY, &[1]);
+        } else {
+            self.storage.remove(ALLOWLIST_ENABLED_KEY);
+        }
+    }
+
+    /// Check if the script can be executed under the current policy.
+    pub(crate) fn is_allowed(&self, bytecode: &[u8]) -> bool {
+        !self.is_enabled() || self.contains(&allowed_script_hash(bytecode))
+    }
+
+    pub(crate) fn contains(&self, hash: &AllowedScriptHash) -> bool {
+        self.storage.get(&Self::key(hash)).is_some()
+    }
+
+    pub(crate) fn add(&self, hash: &AllowedScriptHash) {
+        self.storage.set(&Self::key(hash), &[1]);
+    }
+
+    pub(crate) fn remove(&self, hash: &AllowedScriptHash) {
+        self.storage.remove(&Self::key(hash));
+    }
+
+    fn key(hash: &AllowedScriptHash) -> Vec<u8> {
+        [ALLOWED_SCRIPT_KEY_PREFIX, hash.as_slice()].concat()
+    }
+}
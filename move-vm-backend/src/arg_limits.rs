@@ -0,0 +1,178 @@
+//! Size limits of the vector arguments.
+//!
+//! The bytecode verifier can't bound the arguments supplied by the caller, so an oversized vector
+//! is otherwise only noticed once the execution runs out of memory or gas. The limits are checked
+//! against the encoded arguments before the execution starts, so such transactions are rejected
+//! without being charged.
+//!
+//! Only the top-level vector parameters are checked - the element count is the length prefix of
+//! the encoded argument, and the byte size is the length of the whole encoded argument, including
+//! the nested vectors. The limit is selected by the element type, e.g. `u8` for `vector<u8>`,
+//! with the fallback to the default limit.
+
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use move_binary_format::{
+    binary_views::BinaryIndexedView,
+    file_format::{SignatureToken, StructHandleIndex},
+};
+use move_core_types::language_storage::{StructTag, TypeTag};
+
+/// Size limit of a single vector argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VectorLimit {
+    /// Maximum number of the vector elements.
+    pub max_elements: u64,
+    /// Maximum size of the encoded argument in bytes.
+    pub max_bytes: usize,
+}
+
+/// Size limits of the vector arguments by their element type.
+///
+/// No vector arguments are limited by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VectorArgLimits {
+    /// Limit of the vectors whose element type has no limit of its own.
+    pub default: Option<VectorLimit>,
+    /// Limits by the vector element type.
+    pub per_type: BTreeMap<TypeTag, VectorLimit>,
+}
+
+impl VectorArgLimits {
+    /// Create the limits with the same limit for all element types.
+    pub fn new(default: VectorLimit) -> Self {
+        Self {
+            default: Some(default),
+            per_type: BTreeMap::new(),
+        }
+    }
+
+    /// Set the limit for the vectors of the given element type.
+    pub fn with_type_limit(mut self, element: TypeTag, limit: VectorLimit) -> Self {
+        self.per_type.insert(element, limit);
+        self
+    }
+
+    /// Limit applied to the vectors of the given element type.
+    pub fn limit_for(&self, element: &TypeTag) -> Option<VectorLimit> {
+        self.per_type.get(element).copied().or(self.default)
+    }
+
+    /// Check if no vector arguments are limited.
+    pub fn is_unlimited(&self) -> bool {
+        self.default.is_none() && self.per_type.is_empty()
+    }
+
+    /// Check the encoded arguments of the function with the given parameters.
+    ///
+    /// Returns the description of the first argument exceeding its limit. Arguments which can't be
+    /// decoded are left for the MoveVM to reject.
+    pub(crate) fn check(
+        &self,
+        view: &BinaryIndexedView,
+        params: &[SignatureToken],
+        type_args: &[TypeTag],
+        args: &[Vec<u8>],
+    ) -> Result<(), String> {
+        for (position, (param, arg)) in params.iter().zip(args).enumerate() {
+            let SignatureToken::Vector(element) = param else {
+                continue;
+            };
+
+            let element = type_tag(view, element, type_args);
+            let limit = match &element {
+                Some(element) => self.limit_for(element),
+                None => self.default,
+            };
+            let Some(limit) = limit else {
+                continue;
+            };
+            let element = element.map_or_else(|| "_".to_string(), |tag| tag.to_string());
+
+            if arg.len() > limit.max_bytes {
+                return Err(format!(
+                    "Argument {position} of type vector<{element}> has {} bytes, the limit is {}",
+                    arg.len(),
+                    limit.max_bytes
+                ));
+            }
+
+            let Some(elements) = uleb128_prefix(arg) else {
+                continue;
+            };
+            if elements > limit.max_elements {
+                return Err(format!(
+                    "Argument {position} of type vector<{element}> has {elements} elements, the limit is {}",
+                    limit.max_elements
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Decodes the ULEB128 length prefix of the BCS-encoded vector.
+fn uleb128_prefix(bytes: &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().take(10).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Substitutes the type parameters in the signature token - `None` for the unresolvable ones.
+pub(crate) fn type_tag(
+    view: &BinaryIndexedView,
+    token: &SignatureToken,
+    type_args: &[TypeTag],
+) -> Option<TypeTag> {
+    use SignatureToken::*;
+
+    Some(match token {
+        Bool => TypeTag::Bool,
+        U8 => TypeTag::U8,
+        U16 => TypeTag::U16,
+        U32 => TypeTag::U32,
+        U64 => TypeTag::U64,
+        U128 => TypeTag::U128,
+        U256 => TypeTag::U256,
+        Address => TypeTag::Address,
+        Signer => TypeTag::Signer,
+        Vector(inner) => TypeTag::Vector(Box::new(type_tag(view, inner, type_args)?)),
+        Struct(idx) => TypeTag::Struct(Box::new(struct_tag(view, *idx, Vec::new()))),
+        StructInstantiation(idx, tokens) => {
+            let type_params = tokens
+                .iter()
+                .map(|token| type_tag(view, token, type_args))
+                .collect::<Option<_>>()?;
+            TypeTag::Struct(Box::new(struct_tag(view, *idx, type_params)))
+        }
+        TypeParameter(idx) => type_args.get(*idx as usize)?.clone(),
+        Reference(_) | MutableReference(_) => return None,
+    })
+}
+
+fn struct_tag(
+    view: &BinaryIndexedView,
+    idx: StructHandleIndex,
+    type_params: Vec<TypeTag>,
+) -> StructTag {
+    let handle = view.struct_handle_at(idx);
+    let module = view.module_handle_at(handle.module);
+
+    StructTag {
+        address: *view.address_identifier_at(module.address),
+        module: view.identifier_at(module.name).to_owned(),
+        name: view.identifier_at(handle.name).to_owned(),
+        type_params,
+    }
+}
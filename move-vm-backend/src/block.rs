@@ -0,0 +1,96 @@
+//! Block-scoped lifecycle of the caches.
+//!
+//! The MoveVM keeps the loaded modules, the scripts, the struct layouts and the pre-verified code
+//! in the memory between the transactions. Sharing them within a block saves the repeated loading,
+//! but nothing cached while executing one block may affect the next one - e.g. a module loaded by
+//! a transaction which the pallet later discarded.
+//!
+//! The pallet brackets every block with [`crate::Mvm::begin_block`] and [`crate::Mvm::end_block`].
+//! Both drop the caches, so every block starts from the storage alone. Only the code which doesn't
+//! depend on the executed transactions is kept - the modules warmed up with
+//! [`crate::Mvm::warm_up`] are reloaded from the storage, and the code handed over with
+//! [`crate::Mvm::preload_verified`] before the block starts is kept until the block ends.
+//!
+//! The block can also get a gas pool shared by its transactions - once the pool is used up, the
+//! remaining transactions fail with the `EXECUTION_LIMIT_REACHED` status code without any changes
+//! applied.
+
+use crate::fee_policy::FeeMultiplier;
+use core::fmt;
+
+/// Error of the block lifecycle calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockError {
+    /// The previous block wasn't ended.
+    AlreadyStarted,
+    /// No block was started.
+    NotStarted,
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AlreadyStarted => write!(f, "Block already started"),
+            Self::NotStarted => write!(f, "No block started"),
+        }
+    }
+}
+
+/// Summary of the ended block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockSummary {
+    /// Gas used by the successful transactions of the block.
+    pub gas_used: u64,
+    /// Number of the transactions whose changes were applied.
+    pub transactions: u64,
+    /// Number of the transactions rejected by the exhausted gas pool.
+    pub rejected: u64,
+}
+
+/// State of the block in progress.
+pub(crate) struct BlockState {
+    /// Gas the transactions can still use, unlimited if `None`.
+    gas_pool: Option<u64>,
+    /// Congestion multiplier of the fees.
+    fee_multiplier: FeeMultiplier,
+    summary: BlockSummary,
+}
+
+impl BlockState {
+    pub(crate) fn new(gas_pool: Option<u64>) -> Self {
+        Self {
+            gas_pool,
+            fee_multiplier: FeeMultiplier::ONE,
+            summary: BlockSummary::default(),
+        }
+    }
+
+    /// Take the gas of a transaction from the pool.
+    ///
+    /// Returns `false` if the pool can't cover it - the transaction must not be applied then.
+    pub(crate) fn take_gas(&mut self, gas: u64) -> bool {
+        if let Some(pool) = self.gas_pool.as_mut() {
+            let Some(left) = pool.checked_sub(gas) else {
+                self.summary.rejected += 1;
+                return false;
+            };
+            *pool = left;
+        }
+
+        self.summary.gas_used = self.summary.gas_used.saturating_add(gas);
+        self.summary.transactions += 1;
+        true
+    }
+
+    pub(crate) fn fee_multiplier(&self) -> FeeMultiplier {
+        self.fee_multiplier
+    }
+
+    pub(crate) fn set_fee_multiplier(&mut self, multiplier: FeeMultiplier) {
+        self.fee_multiplier = multiplier;
+    }
+
+    pub(crate) fn summary(&self) -> BlockSummary {
+        self.summary
+    }
+}
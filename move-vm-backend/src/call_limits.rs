@@ -0,0 +1,364 @@
+//! Gas ceilings of the calls into the selected modules.
+//!
+//! Some modules are called automatically rather than by the transaction sender, e.g. the fee hook
+//! or the init function of a published bundle. With a ceiling set by
+//! [`crate::Mvm::set_call_gas_limit`], a call into such a module can only use the given part of the
+//! transaction gas, so a misbehaving hook can't exhaust the whole budget.
+//!
+//! The [`CallLimitedGasMeter`] wraps the gas meter used by the MoveVM and follows the call stack of
+//! the interpreter the same way as the gas profiler. Each call into a limited module records the
+//! balance below which the call runs out of its gas. The gas of the callees counts towards the
+//! ceiling, and the calls within the module share the ceiling of the outermost call.
+
+use alloc::{collections::BTreeMap, format, vec::Vec};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    gas_algebra::{InternalGas, NumArgs, NumBytes},
+    language_storage::ModuleId,
+    vm_status::StatusCode,
+};
+use move_vm_types::{
+    gas::{GasMeter, SimpleInstruction},
+    views::{TypeView, ValueView},
+};
+
+/// Gas ceilings of the calls into the modules in internal gas units.
+pub(crate) type CallGasLimits = BTreeMap<ModuleId, u64>;
+
+/// Gas meter which enforces the call gas ceilings while delegating all charges to the inner meter.
+pub(crate) struct CallLimitedGasMeter<'a, G: GasMeter> {
+    meter: &'a mut G,
+    limits: &'a CallGasLimits,
+    /// Functions currently on the interpreter call stack, with the lowest balance they can reach
+    /// and the module which set it.
+    stack: Vec<(u64, Option<&'a ModuleId>)>,
+}
+
+impl<'a, G: GasMeter> CallLimitedGasMeter<'a, G> {
+    /// Starts following the execution of the entry function of the given module - scripts have
+    /// none.
+    pub(crate) fn new(
+        meter: &'a mut G,
+        limits: &'a CallGasLimits,
+        entry: Option<&ModuleId>,
+    ) -> Self {
+        let mut meter = Self {
+            meter,
+            limits,
+            stack: Vec::new(),
+        };
+        meter.enter(entry);
+        meter
+    }
+
+    fn enter(&mut self, module_id: Option<&ModuleId>) {
+        let balance: u64 = self.meter.balance_internal().into();
+        let outer = self.stack.last().copied().unwrap_or((0, None));
+
+        let limits = self.limits;
+        let limited = module_id
+            .and_then(|id| limits.get_key_value(id))
+            .map(|(id, limit)| (balance.saturating_sub(*limit), Some(id)));
+        let frame = match limited {
+            Some(frame) if frame.0 > outer.0 => frame,
+            _ => outer,
+        };
+        self.stack.push(frame);
+    }
+
+    fn exit(&mut self) {
+        self.stack.pop();
+    }
+
+    /// Fails once a call uses more than its ceiling.
+    fn check(&self) -> PartialVMResult<()> {
+        let Some((floor, Some(module_id))) = self.stack.last() else {
+            return Ok(());
+        };
+
+        let balance: u64 = self.meter.balance_internal().into();
+        if balance < *floor {
+            return Err(
+                PartialVMError::new(StatusCode::OUT_OF_GAS).with_message(format!(
+                    "Call into {} exceeded its gas limit",
+                    module_id.short_str_lossless()
+                )),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl<G: GasMeter> GasMeter for CallLimitedGasMeter<'_, G> {
+    fn balance_internal(&self) -> InternalGas {
+        self.meter.balance_internal()
+    }
+
+    fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
+        self.meter.charge_simple_instr(instr)?;
+        self.check()?;
+
+        if instr == SimpleInstruction::Ret {
+            self.exit();
+        }
+
+        Ok(())
+    }
+
+    fn charge_back_edge(&mut self) -> PartialVMResult<()> {
+        self.meter.charge_back_edge()?;
+        self.check()
+    }
+
+    fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_pop(popped_val)?;
+        self.check()
+    }
+
+    fn charge_call(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_call(module_id, func_name, args, num_locals)?;
+        // The call instruction itself is paid by the caller.
+        self.check()?;
+        self.enter(Some(module_id));
+        Ok(())
+    }
+
+    fn charge_call_generic(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_call_generic(module_id, func_name, ty_args, args, num_locals)?;
+        self.check()?;
+        self.enter(Some(module_id));
+        Ok(())
+    }
+
+    fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
+        self.meter.charge_ld_const(size)?;
+        self.check()
+    }
+
+    fn charge_ld_const_after_deserialization(
+        &mut self,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_ld_const_after_deserialization(val)?;
+        self.check()
+    }
+
+    fn charge_copy_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_copy_loc(val)?;
+        self.check()
+    }
+
+    fn charge_move_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_move_loc(val)?;
+        self.check()
+    }
+
+    fn charge_store_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_store_loc(val)?;
+        self.check()
+    }
+
+    fn charge_pack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_pack(is_generic, args)?;
+        self.check()
+    }
+
+    fn charge_unpack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_unpack(is_generic, args)?;
+        self.check()
+    }
+
+    fn charge_read_ref(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_read_ref(val)?;
+        self.check()
+    }
+
+    fn charge_write_ref(
+        &mut self,
+        new_val: impl ValueView,
+        old_val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_write_ref(new_val, old_val)?;
+        self.check()
+    }
+
+    fn charge_eq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_eq(lhs, rhs)?;
+        self.check()
+    }
+
+    fn charge_neq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_neq(lhs, rhs)?;
+        self.check()
+    }
+
+    fn charge_borrow_global(
+        &mut self,
+        is_mut: bool,
+        is_generic: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_borrow_global(is_mut, is_generic, ty, is_success)?;
+        self.check()
+    }
+
+    fn charge_exists(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        exists: bool,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_exists(is_generic, ty, exists)?;
+        self.check()
+    }
+
+    fn charge_move_from(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_move_from(is_generic, ty, val)?;
+        self.check()
+    }
+
+    fn charge_move_to(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: impl ValueView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_move_to(is_generic, ty, val, is_success)?;
+        self.check()
+    }
+
+    fn charge_vec_pack<'a>(
+        &mut self,
+        ty: impl TypeView + 'a,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_vec_pack(ty, args)?;
+        self.check()
+    }
+
+    fn charge_vec_len(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.meter.charge_vec_len(ty)?;
+        self.check()
+    }
+
+    fn charge_vec_borrow(
+        &mut self,
+        is_mut: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_vec_borrow(is_mut, ty, is_success)?;
+        self.check()
+    }
+
+    fn charge_vec_push_back(
+        &mut self,
+        ty: impl TypeView,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_vec_push_back(ty, val)?;
+        self.check()
+    }
+
+    fn charge_vec_pop_back(
+        &mut self,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_vec_pop_back(ty, val)?;
+        self.check()
+    }
+
+    fn charge_vec_unpack(
+        &mut self,
+        ty: impl TypeView,
+        expect_num_elements: NumArgs,
+        elems: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_vec_unpack(ty, expect_num_elements, elems)?;
+        self.check()
+    }
+
+    fn charge_vec_swap(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.meter.charge_vec_swap(ty)?;
+        self.check()
+    }
+
+    fn charge_load_resource(
+        &mut self,
+        loaded: Option<(NumBytes, impl ValueView)>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_load_resource(loaded)?;
+        self.check()
+    }
+
+    fn charge_native_function(
+        &mut self,
+        amount: InternalGas,
+        ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView>>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_native_function(amount, ret_vals)?;
+        self.check()?;
+
+        // Native functions return right away.
+        self.exit();
+
+        Ok(())
+    }
+
+    fn charge_native_function_before_execution(
+        &mut self,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_native_function_before_execution(ty_args, args)?;
+        self.check()
+    }
+
+    fn charge_drop_frame(
+        &mut self,
+        locals: impl Iterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_drop_frame(locals)?;
+        self.check()
+    }
+
+    fn charge_dependency(&mut self, module_id: &ModuleId, size: NumBytes) -> PartialVMResult<()> {
+        self.meter.charge_dependency(module_id, size)?;
+        self.check()
+    }
+}
@@ -0,0 +1,100 @@
+//! Transparent compression of the stored module bytes.
+//!
+//! Compressed modules are stored with a format tag followed by the uncompressed module length and
+//! the DEFLATE stream. Valid modules always start with the Move magic, so the untagged module
+//! bytes written before the compression was enabled can still be read as they are.
+//!
+//! The same codec is used in `std` and `no_std` builds, since the native and the runtime
+//! execution must always produce the identical storage state.
+
+use alloc::vec::Vec;
+use anyhow::{bail, Result};
+use miniz_oxide::{deflate::compress_to_vec, inflate::decompress_to_vec_with_limit};
+
+/// Format tag of the compressed module - the last byte is the codec version.
+const COMPRESSED_MODULE_TAG: &[u8] = b"MVZ\x01";
+
+/// Size of the encoded uncompressed module length.
+const MODULE_LEN_SIZE: usize = core::mem::size_of::<u32>();
+
+/// DEFLATE compression level - a balance between the compression ratio and speed.
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// Compresses the module bytes.
+///
+/// Modules which don't get any smaller are kept uncompressed.
+#[cfg_attr(not(feature = "module-compression"), allow(dead_code))]
+pub(crate) fn compress_module(module: Vec<u8>) -> Vec<u8> {
+    let Ok(module_len) = u32::try_from(module.len()) else {
+        return module;
+    };
+
+    let compressed = compress_to_vec(&module, COMPRESSION_LEVEL);
+    if COMPRESSED_MODULE_TAG.len() + MODULE_LEN_SIZE + compressed.len() >= module.len() {
+        return module;
+    }
+
+    [
+        COMPRESSED_MODULE_TAG,
+        &module_len.to_le_bytes(),
+        compressed.as_slice(),
+    ]
+    .concat()
+}
+
+/// Decompresses the stored module bytes - untagged bytes are returned as they are.
+pub(crate) fn decompress_module(stored: Vec<u8>) -> Result<Vec<u8>> {
+    let Some(data) = stored.strip_prefix(COMPRESSED_MODULE_TAG) else {
+        return Ok(stored);
+    };
+
+    if data.len() < MODULE_LEN_SIZE {
+        bail!("Compressed module is missing its length");
+    }
+    let (module_len, compressed) = data.split_at(MODULE_LEN_SIZE);
+    let mut len_bytes = [0u8; MODULE_LEN_SIZE];
+    len_bytes.copy_from_slice(module_len);
+    let module_len = u32::from_le_bytes(len_bytes) as usize;
+
+    // The limit prevents the corrupted stream from inflating beyond the declared size.
+    let module = decompress_to_vec_with_limit(compressed, module_len)
+        .map_err(|_| anyhow::anyhow!("Failed to decompress the module"))?;
+    if module.len() != module_len {
+        bail!("Decompressed module length mismatch");
+    }
+
+    Ok(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compression_round_trip() {
+        let module: Vec<u8> = (0..4096u32).map(|i| (i % 16) as u8).collect();
+
+        let compressed = compress_module(module.clone());
+        assert!(compressed.starts_with(COMPRESSED_MODULE_TAG));
+        assert!(compressed.len() < module.len());
+        assert_eq!(decompress_module(compressed).unwrap(), module);
+    }
+
+    #[test]
+    fn incompressible_module_is_kept_raw() {
+        let module = vec![0xA1, 0x1C, 0xEB, 0x0B, 0x06];
+
+        let stored = compress_module(module.clone());
+        assert_eq!(stored, module);
+        assert_eq!(decompress_module(stored).unwrap(), module);
+    }
+
+    #[test]
+    fn corrupted_module_is_rejected() {
+        let module: Vec<u8> = (0..4096u32).map(|i| (i % 16) as u8).collect();
+
+        let mut compressed = compress_module(module);
+        compressed.truncate(compressed.len() / 2);
+        assert!(decompress_module(compressed).is_err());
+    }
+}
@@ -0,0 +1,50 @@
+//! Sequence numbers of the emitted events.
+//!
+//! The events of the successful calls are numbered in the emission order, continuing from the
+//! last event of the previous call, so the numbers keep increasing across the blocks. Dry runs
+//! number their events the same way, but don't advance the sequence.
+//!
+//! The next sequence number is kept in the storage under the events namespace.
+
+use crate::storage::Storage;
+use crate::storage_key::EVENT_SEQUENCE_KEY;
+use move_vm_backend_common::event::MoveEvent;
+
+/// Keeps the event sequence in the storage.
+pub(crate) struct EventSequence<'a, S: Storage> {
+    storage: &'a S,
+}
+
+impl<'a, S: Storage> EventSequence<'a, S> {
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self { storage }
+    }
+
+    /// Sequence number of the next emitted event.
+    pub(crate) fn next(&self) -> u64 {
+        self.storage
+            .get(EVENT_SEQUENCE_KEY)
+            .and_then(|bytes| bcs::from_bytes(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Numbers the events of a call, following the already emitted events.
+    pub(crate) fn assign(&self, events: &mut [MoveEvent]) {
+        let next = self.next();
+        for (index, event) in events.iter_mut().enumerate() {
+            event.index = index as u32;
+            event.sequence_number = next + index as u64;
+        }
+    }
+
+    /// Advances the sequence past the committed events.
+    pub(crate) fn advance(&self, events: &[MoveEvent]) {
+        if events.is_empty() {
+            return;
+        }
+
+        let next = self.next() + events.len() as u64;
+        let bytes = bcs::to_bytes(&next).expect("sequence number is always serializable");
+        self.storage.set(EVENT_SEQUENCE_KEY, &bytes);
+    }
+}
@@ -0,0 +1,279 @@
+//! Expiry of the stored resources.
+//!
+//! Transactions set the expiry block of their signers' resources with the `std::expiry` natives.
+//! The expiries are kept outside of the accounts, so the stored resources don't change, and
+//! [`crate::Mvm::sweep_expired`] removes the resources whose expiry block is reached. The sweep is
+//! meant to be called when the block has spare weight, e.g. from the `on_idle` hook, so it's
+//! limited to a given number of index entries per call.
+//!
+//! A resource deleted by a transaction loses its expiry, so a resource published later under the
+//! same type doesn't inherit it.
+//!
+//! Each expiry is kept under its own key, next to an index of the resources ordered by the expiry
+//! block. The index is an append-only list of the resources per block, which the sweep walks from
+//! its cursor - so setting, changing or removing an expiry, as well as a sweep, only touches a
+//! bounded number of entries regardless of how many expiries exist. The index entries of the
+//! changed or removed expiries are left behind and skipped once the sweep reaches them. The sweep
+//! visits the blocks one by one, but skips straight to the current block once no entries are left.
+
+use crate::storage::Storage;
+use crate::storage_key::{EXPIRY_INDEX_KEY_PREFIX, EXPIRY_KEY_PREFIX, EXPIRY_SWEEP_KEY};
+use alloc::vec::Vec;
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::{ChangeSet, Op},
+    language_storage::StructTag,
+};
+use move_stdlib::natives::expiry::ExpiryChanges;
+use serde::{Deserialize, Serialize};
+
+/// Resource stored under the account.
+pub type ResourceKey = (AccountAddress, StructTag);
+
+/// Outcome of [`crate::Mvm::sweep_expired`].
+#[derive(Debug, Default)]
+pub struct SweepReport {
+    /// Removed resources - expired resources which no longer existed are left out.
+    pub removed: Vec<ResourceKey>,
+    /// Number of the storage bytes freed by the removed resources.
+    pub freed_bytes: u64,
+    /// Expired resources which are frozen - they keep their expiries for the following sweeps.
+    pub frozen: Vec<ResourceKey>,
+    /// All expiries due at the block were visited - otherwise the rest is left for the next sweep.
+    pub complete: bool,
+}
+
+/// Adds the removal of the expiries of the resources deleted by the changeset to the changes.
+pub(crate) fn with_deleted(changeset: &ChangeSet, mut changes: ExpiryChanges) -> ExpiryChanges {
+    let deleted = changeset
+        .resources()
+        .filter(|(_, _, op)| matches!(op, Op::Delete))
+        .map(|(address, tag, _)| ((address, tag.clone()), None));
+    changes.extend(deleted);
+    changes
+}
+
+/// Position of the sweep within the index.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+struct SweepCursor {
+    /// Block whose index entries are visited next.
+    block: u64,
+    /// Next index entry of the block.
+    position: u32,
+    /// Number of the index entries which weren't visited yet.
+    pending: u64,
+}
+
+/// Index entries visited by the sweep, which are removed once the sweep is applied.
+pub(crate) struct Sweep {
+    /// Resources whose expiry is due.
+    pub(crate) expired: Vec<ResourceKey>,
+    /// All due expiries were visited.
+    pub(crate) complete: bool,
+    /// First visited index entry of the first visited block.
+    start: u32,
+    /// Visited blocks with the end of their visited index entries and whether all their entries
+    /// were visited.
+    visited: Vec<(u64, u32, bool)>,
+    /// Cursor after the sweep.
+    cursor: SweepCursor,
+}
+
+impl Sweep {
+    /// Records the visited index entries of the block up to the `end`.
+    fn visit(&mut self, block: u64, end: u32, completed: bool) {
+        match self.visited.last_mut() {
+            Some(last) if last.0 == block => *last = (block, end, completed),
+            _ => self.visited.push((block, end, completed)),
+        }
+    }
+}
+
+/// Keeps the resource expiries in the storage.
+pub(crate) struct ExpiryRegistry<'a, S: Storage> {
+    storage: &'a S,
+}
+
+impl<'a, S: Storage> ExpiryRegistry<'a, S> {
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self { storage }
+    }
+
+    /// Expiry block of the resource.
+    pub(crate) fn get(&self, key: &ResourceKey) -> Option<u64> {
+        self.storage
+            .get(&Self::expiry_key(key))
+            .and_then(|bytes| bcs::from_bytes(&bytes).ok())
+    }
+
+    /// Applies the expiry changes: `Some(block)` sets the expiry, `None` removes it.
+    pub(crate) fn apply(&self, changes: ExpiryChanges) {
+        // The removals don't touch the index, so the cursor is only read for the new expiries.
+        let mut cursor = None;
+        for (key, change) in changes {
+            match change {
+                Some(block) => {
+                    let bytes = bcs::to_bytes(&block).expect("blocks are always serializable");
+                    self.storage.set(&Self::expiry_key(&key), &bytes);
+                    let cursor = cursor.get_or_insert_with(|| self.cursor());
+                    self.push(cursor, block, &key);
+                }
+                None => self.storage.remove(&Self::expiry_key(&key)),
+            }
+        }
+
+        if let Some(cursor) = cursor {
+            self.set_cursor(&cursor);
+        }
+    }
+
+    /// Visits at most `limit` blocks and index entries due at the block, the earliest expiries
+    /// first.
+    ///
+    /// Nothing is changed until the sweep is applied with [`ExpiryRegistry::finish`].
+    pub(crate) fn sweep(&self, block: u64, limit: usize) -> Sweep {
+        let cursor = self.cursor();
+        let mut sweep = Sweep {
+            expired: Vec::new(),
+            complete: false,
+            start: cursor.position,
+            visited: Vec::new(),
+            cursor,
+        };
+
+        let mut steps = 0;
+        let mut block_count = None;
+        while sweep.cursor.pending > 0 && sweep.cursor.block <= block && steps < limit {
+            steps += 1;
+            let SweepCursor {
+                block: current,
+                position,
+                ..
+            } = sweep.cursor;
+            let count = *block_count.get_or_insert_with(|| self.count(current));
+            if position >= count {
+                // The empty blocks don't have any index entries to remove.
+                if count > 0 {
+                    sweep.visit(current, position, true);
+                }
+                sweep.cursor.block += 1;
+                sweep.cursor.position = 0;
+                block_count = None;
+                continue;
+            }
+
+            // The entries of the changed or removed expiries are skipped, as well as the
+            // duplicates of the expiries set again.
+            if let Some(key) = self.entry(current, position) {
+                let due = self.get(&key).is_some_and(|expires_at| expires_at <= block);
+                if due && !sweep.expired.contains(&key) {
+                    sweep.expired.push(key);
+                }
+            }
+            sweep.visit(current, position + 1, false);
+            sweep.cursor.position += 1;
+            sweep.cursor.pending -= 1;
+        }
+
+        // Without any pending entries, there is nothing left to visit up to the block.
+        if sweep.cursor.pending == 0 && sweep.cursor.block <= block {
+            let SweepCursor {
+                block: current,
+                position,
+                ..
+            } = sweep.cursor;
+            if position > 0 {
+                sweep.visit(current, position, true);
+            }
+            sweep.cursor.block = block.saturating_add(1);
+            sweep.cursor.position = 0;
+        }
+        sweep.complete = sweep.cursor.block > block;
+
+        sweep
+    }
+
+    /// Applies the sweep: the visited index entries and the expiries of the expired resources are
+    /// removed, except for the `kept` resources, which are indexed again for the following sweeps.
+    pub(crate) fn finish(&self, sweep: Sweep, kept: &[ResourceKey]) {
+        let mut start = sweep.start;
+        for (block, end, completed) in sweep.visited {
+            for position in start..end {
+                self.storage.remove(&Self::entry_key(block, position));
+            }
+            if completed {
+                self.storage.remove(&Self::count_key(block));
+            }
+            start = 0;
+        }
+
+        for key in sweep.expired.iter().filter(|key| !kept.contains(key)) {
+            self.storage.remove(&Self::expiry_key(key));
+        }
+
+        let mut cursor = sweep.cursor;
+        let next_block = cursor.block;
+        for key in kept {
+            self.push(&mut cursor, next_block, key);
+        }
+        self.set_cursor(&cursor);
+    }
+
+    /// Appends the resource to the index entries of the block.
+    ///
+    /// The blocks already passed by the sweep are never visited again, so their expiries go to the
+    /// block of the cursor.
+    fn push(&self, cursor: &mut SweepCursor, block: u64, key: &ResourceKey) {
+        let block = block.max(cursor.block);
+        let count = self.count(block);
+        let bytes = bcs::to_bytes(key).expect("resource keys are always serializable");
+        self.storage.set(&Self::entry_key(block, count), &bytes);
+        let bytes = bcs::to_bytes(&(count + 1)).expect("counts are always serializable");
+        self.storage.set(&Self::count_key(block), &bytes);
+        cursor.pending += 1;
+    }
+
+    /// Number of the index entries of the block.
+    fn count(&self, block: u64) -> u32 {
+        self.storage
+            .get(&Self::count_key(block))
+            .and_then(|bytes| bcs::from_bytes(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    fn entry(&self, block: u64, position: u32) -> Option<ResourceKey> {
+        self.storage
+            .get(&Self::entry_key(block, position))
+            .and_then(|bytes| bcs::from_bytes(&bytes).ok())
+    }
+
+    fn cursor(&self) -> SweepCursor {
+        self.storage
+            .get(EXPIRY_SWEEP_KEY)
+            .and_then(|bytes| bcs::from_bytes(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    fn set_cursor(&self, cursor: &SweepCursor) {
+        let bytes = bcs::to_bytes(cursor).expect("the cursor is always serializable");
+        self.storage.set(EXPIRY_SWEEP_KEY, &bytes);
+    }
+
+    fn expiry_key(key: &ResourceKey) -> Vec<u8> {
+        let key = bcs::to_bytes(key).expect("resource keys are always serializable");
+        [EXPIRY_KEY_PREFIX, key.as_slice()].concat()
+    }
+
+    fn count_key(block: u64) -> Vec<u8> {
+        [EXPIRY_INDEX_KEY_PREFIX, &block.to_be_bytes()].concat()
+    }
+
+    fn entry_key(block: u64, position: u32) -> Vec<u8> {
+        [
+            EXPIRY_INDEX_KEY_PREFIX,
+            &block.to_be_bytes(),
+            &position.to_be_bytes(),
+        ]
+        .concat()
+    }
+}
@@ -0,0 +1,60 @@
+//! Fee payment by a designated Move function.
+//!
+//! With a [`FeeHook`] configured, every transaction is preceded by the validation phase, which
+//! calls the hook entry function with the signer of the hook module's address (the sponsor), the
+//! transaction signers and the gas limit of the transaction:
+//! ```move
+//! entry public fun pay_fee(sponsor: &signer, signers: vector<address>, gas_limit: u64)
+//! ```
+//! The hook rejects the transaction by aborting, which skips the execution altogether. Otherwise,
+//! the hook changes (e.g. the fee payment from the sponsor account) are applied before the
+//! transaction is executed and they are kept even if the transaction fails. Such results are
+//! marked as [`crate::types::VmResult::sponsored`], so the caller doesn't charge the fees again.
+//!
+//! The validation phase is metered separately from the transaction, with the hook's own limit.
+
+use crate::types::{Call, GasAmount, GasStrategy, Transaction, MAX_GAS_AMOUNT};
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
+
+/// Move entry function which validates and pays the transaction fees.
+#[derive(Debug, Clone)]
+pub struct FeeHook {
+    /// Module of the hook - its address is the sponsor account.
+    pub module: ModuleId,
+    /// Name of the hook entry function.
+    pub function: Identifier,
+    /// Gas limit of the validation phase.
+    pub validation_gas: GasAmount,
+}
+
+impl FeeHook {
+    /// The hook call for the transaction with the given signers and gas.
+    pub(crate) fn transaction(
+        &self,
+        signers: &BTreeSet<AccountAddress>,
+        gas: GasStrategy,
+    ) -> Transaction {
+        let gas_limit = match gas {
+            GasStrategy::Metered(amount) => amount.inner(),
+            _ => MAX_GAS_AMOUNT,
+        };
+        let signers: Vec<_> = signers.iter().collect();
+
+        Transaction {
+            call: Call::ScriptFunction {
+                mod_address: *self.module.address(),
+                mod_name: self.module.name().to_owned(),
+                func_name: self.function.clone(),
+            },
+            type_args: vec![],
+            args: vec![
+                bcs::to_bytes(self.module.address()).expect("addresses are serializable"),
+                bcs::to_bytes(&signers).expect("addresses are serializable"),
+                bcs::to_bytes(&gas_limit).expect("integers are serializable"),
+            ],
+        }
+    }
+}
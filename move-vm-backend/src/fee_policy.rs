@@ -0,0 +1,70 @@
+//! Conversion of the used gas into the balance fee.
+//!
+//! With a [`FeePolicy`] configured, every result carries the fee for its used gas in
+//! [`crate::types::VmResult::fee`], so the pallet and the tooling show the costs in tokens the same
+//! way. The policy gets the congestion multiplier of the block in progress, set with
+//! [`crate::Mvm::set_block_fee_multiplier`] - outside of a block, the multiplier is
+//! [`FeeMultiplier::ONE`].
+
+/// Congestion multiplier of the fees in millionths, e.g. `1_500_000` for 1.5x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FeeMultiplier(u64);
+
+impl FeeMultiplier {
+    /// Number of millionths in one.
+    const SCALE: u128 = 1_000_000;
+
+    /// The multiplier leaving the fees unchanged.
+    pub const ONE: Self = Self(Self::SCALE as u64);
+
+    /// Create the multiplier from its value in millionths.
+    pub const fn from_millionths(millionths: u64) -> Self {
+        Self(millionths)
+    }
+
+    /// Value of the multiplier in millionths.
+    pub const fn millionths(self) -> u64 {
+        self.0
+    }
+
+    /// Multiply the amount, rounded up.
+    pub fn apply(self, amount: u128) -> u128 {
+        let whole = (amount / Self::SCALE).saturating_mul(u128::from(self.0));
+        let part = (amount % Self::SCALE * u128::from(self.0)).div_ceil(Self::SCALE);
+        whole.saturating_add(part)
+    }
+}
+
+impl Default for FeeMultiplier {
+    fn default() -> Self {
+        Self::ONE
+    }
+}
+
+/// Conversion of the used gas into the balance fee.
+pub trait FeePolicy {
+    /// Fee for the used gas with the congestion multiplier of the block.
+    fn fee(&self, gas_used: u64, multiplier: FeeMultiplier) -> u128;
+}
+
+impl<F> FeePolicy for F
+where
+    F: Fn(u64, FeeMultiplier) -> u128,
+{
+    fn fee(&self, gas_used: u64, multiplier: FeeMultiplier) -> u128 {
+        self(gas_used, multiplier)
+    }
+}
+
+/// Policy with a fixed price per gas unit, scaled by the multiplier.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinearFee {
+    /// Price of one gas unit in the smallest balance units.
+    pub price_per_gas: u128,
+}
+
+impl FeePolicy for LinearFee {
+    fn fee(&self, gas_used: u64, multiplier: FeeMultiplier) -> u128 {
+        multiplier.apply(u128::from(gas_used).saturating_mul(self.price_per_gas))
+    }
+}
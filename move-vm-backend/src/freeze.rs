@@ -0,0 +1,107 @@
+//! Freezing of the accounts and resources by the governance.
+//!
+//! Freezing is the emergency brake for an exploited contract: once an account or a resource is
+//! frozen with [`crate::Mvm::freeze_account`] or [`crate::Mvm::freeze_resource`], the transactions
+//! can still read it, but any transaction whose changeset writes, moves or deletes it is rejected
+//! when its session finishes. A frozen account rejects the changes of all its resources and
+//! modules, and fails with [`StatusCode::ACCOUNT_FROZEN`]; a frozen resource fails with
+//! [`StatusCode::RESOURCE_FROZEN`].
+//!
+//! The freezing methods require a [`FreezeCapability`], which is an embedder-supplied token type
+//! like [`crate::privileged::PublishCapability`].
+//!
+//! Each frozen account and resource is kept under its own key in the governance namespace, so the
+//! check of a changeset only looks up the accounts and the resources it touches.
+
+use crate::{
+    expiry::ResourceKey,
+    storage::Storage,
+    storage_key::{FROZEN_ACCOUNT_KEY_PREFIX, FROZEN_RESOURCE_KEY_PREFIX},
+};
+use alloc::{format, string::String, vec::Vec};
+use move_core_types::{account_address::AccountAddress, effects::ChangeSet, vm_status::StatusCode};
+use move_vm_backend_common::type_tag::{display_address, display_struct_tag, TagForm};
+
+/// Proof that the caller may freeze and unfreeze the accounts and resources.
+pub trait FreezeCapability {}
+
+/// Value stored under the key of the frozen account or resource.
+const FROZEN: &[u8] = &[1];
+
+/// Keeps the frozen accounts and resources in the storage.
+pub(crate) struct FreezeRegistry<'a, S: Storage> {
+    storage: &'a S,
+}
+
+impl<'a, S: Storage> FreezeRegistry<'a, S> {
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self { storage }
+    }
+
+    pub(crate) fn is_account_frozen(&self, address: &AccountAddress) -> bool {
+        self.storage.get(&Self::account_key(address)).is_some()
+    }
+
+    pub(crate) fn is_resource_frozen(&self, key: &ResourceKey) -> bool {
+        self.storage.get(&Self::resource_key(key)).is_some()
+    }
+
+    /// Freezes or unfreezes the account.
+    pub(crate) fn set_account(&self, address: AccountAddress, frozen: bool) {
+        self.set(&Self::account_key(&address), frozen);
+    }
+
+    /// Freezes or unfreezes the resource.
+    pub(crate) fn set_resource(&self, key: ResourceKey, frozen: bool) {
+        self.set(&Self::resource_key(&key), frozen);
+    }
+
+    /// Returns the status code and the message if the changeset touches anything frozen.
+    pub(crate) fn check(&self, changeset: &ChangeSet) -> Result<(), (StatusCode, String)> {
+        if let Some(address) = changeset
+            .accounts()
+            .keys()
+            .find(|address| self.is_account_frozen(address))
+        {
+            return Err((
+                StatusCode::ACCOUNT_FROZEN,
+                format!(
+                    "Account {} is frozen",
+                    display_address(address, TagForm::Short)
+                ),
+            ));
+        }
+
+        for (address, tag, _) in changeset.resources() {
+            if self.is_resource_frozen(&(address, tag.clone())) {
+                return Err((
+                    StatusCode::RESOURCE_FROZEN,
+                    format!(
+                        "Resource {} of {} is frozen",
+                        display_struct_tag(tag, TagForm::Short),
+                        display_address(&address, TagForm::Short)
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], frozen: bool) {
+        if frozen {
+            self.storage.set(key, FROZEN);
+        } else {
+            self.storage.remove(key);
+        }
+    }
+
+    fn account_key(address: &AccountAddress) -> Vec<u8> {
+        [FROZEN_ACCOUNT_KEY_PREFIX, address.as_slice()].concat()
+    }
+
+    fn resource_key(key: &ResourceKey) -> Vec<u8> {
+        let key = bcs::to_bytes(key).expect("resource keys are always serializable");
+        [FROZEN_RESOURCE_KEY_PREFIX, key.as_slice()].concat()
+    }
+}
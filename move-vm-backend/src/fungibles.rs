@@ -0,0 +1,242 @@
+//! [`HostBindings`] over the `fungibles` traits of the FRAME, e.g. the `pallet-assets`.
+//!
+//! The [`FungiblesAdapter`] exposes a single asset, selected by the `AssetId` getter, as the
+//! balance handled by the MoveVM. Move addresses are mapped to the runtime accounts with the
+//! `AddressConverter`.
+//!
+//! The cheques limit how much each account can spend within the current execution - the caller
+//! writes them with [`FungiblesAdapter::write_cheque`] before the execution, e.g. from the limit
+//! signed by the transaction sender.
+//!
+//! Transfers never reap the source account and only succeed if the destination account can
+//! receive the amount, so a transfer below the existential deposit to a new account fails instead
+//! of burning the funds. Failed transfers return `false` and leave the cheques and the balances
+//! untouched.
+//!
+//! Accounts are created by transferring the existential deposit from the payer, which is limited
+//! by the payer's cheque as any other transfer.
+//!
+//! Transaction fees are burned from the fee payer, which can't get reaped by the withdrawal, and
+//! the unused part of the fee is minted back on the refund. The cheques don't limit the fees.
+//!
+//! The adapter doesn't provide any foreign call targets, randomness or time and doesn't deliver any
+//! XCM messages.
+
+use crate::host::{ForeignCallResponse, HostBindings, XcmMessage};
+use alloc::collections::BTreeMap;
+use core::{cell::RefCell, marker::PhantomData};
+use frame_support::{
+    sp_runtime::traits::{Convert, Zero},
+    traits::{
+        fungibles::{Inspect, Mutate},
+        tokens::{DepositConsequence, Fortitude, Precision, Preservation, Provenance},
+        Get,
+    },
+};
+use move_core_types::{account_address::AccountAddress, vm_status::StatusCode};
+
+/// [`HostBindings`] implementation for a single asset of the `fungibles` implementation.
+pub struct FungiblesAdapter<AccountId, Assets, AssetId, AddressConverter> {
+    /// Amounts each account can still spend within the current execution.
+    cheques: RefCell<BTreeMap<AccountAddress, u128>>,
+    _phantom: PhantomData<(AccountId, Assets, AssetId, AddressConverter)>,
+}
+
+impl<AccountId, Assets, AssetId, AddressConverter> Default
+    for FungiblesAdapter<AccountId, Assets, AssetId, AddressConverter>
+{
+    fn default() -> Self {
+        Self {
+            cheques: RefCell::new(BTreeMap::new()),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<AccountId, Assets, AssetId, AddressConverter>
+    FungiblesAdapter<AccountId, Assets, AssetId, AddressConverter>
+where
+    Assets: Inspect<AccountId> + Mutate<AccountId>,
+    Assets::Balance: TryFrom<u128> + Into<u128>,
+    AssetId: Get<Assets::AssetId>,
+    AddressConverter: Convert<AccountAddress, AccountId>,
+{
+    /// Create the adapter without any cheques.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow the account to spend the additional amount within the current execution.
+    pub fn write_cheque(&self, account: AccountAddress, amount: u128) {
+        let mut cheques = self.cheques.borrow_mut();
+        let cheque = cheques.entry(account).or_default();
+        *cheque = cheque.saturating_add(amount);
+    }
+
+    /// Remove all cheques, e.g. once the execution is finished.
+    pub fn clear_cheques(&self) {
+        self.cheques.borrow_mut().clear();
+    }
+
+    /// Amount the account can spend without getting reaped.
+    fn reducible_balance(account: &AccountId) -> u128 {
+        Assets::reducible_balance(
+            AssetId::get(),
+            account,
+            Preservation::Preserve,
+            Fortitude::Polite,
+        )
+        .into()
+    }
+}
+
+impl<AccountId, Assets, AssetId, AddressConverter> HostBindings
+    for FungiblesAdapter<AccountId, Assets, AssetId, AddressConverter>
+where
+    Assets: Inspect<AccountId> + Mutate<AccountId>,
+    Assets::Balance: TryFrom<u128> + Into<u128>,
+    AssetId: Get<Assets::AssetId>,
+    AddressConverter: Convert<AccountAddress, AccountId>,
+{
+    type Error = StatusCode;
+
+    fn transfer(
+        &self,
+        src: AccountAddress,
+        dst: AccountAddress,
+        cheque_amount: u128,
+    ) -> Result<bool, Self::Error> {
+        // Zero transfers must not create the destination, which the assets pallet refuses anyway.
+        if cheque_amount == 0 {
+            return Ok(true);
+        }
+        if self.cheque_amount(src)? < cheque_amount {
+            return Ok(false);
+        }
+        let Ok(amount) = Assets::Balance::try_from(cheque_amount) else {
+            return Ok(false);
+        };
+
+        let asset = AssetId::get();
+        let dst_account = AddressConverter::convert(dst);
+        if Assets::can_deposit(asset.clone(), &dst_account, amount, Provenance::Extant)
+            != DepositConsequence::Success
+        {
+            return Ok(false);
+        }
+
+        let src_account = AddressConverter::convert(src);
+        let transferred = Assets::transfer(
+            asset,
+            &src_account,
+            &dst_account,
+            amount,
+            Preservation::Preserve,
+        );
+        if transferred.is_err() {
+            return Ok(false);
+        }
+
+        if let Some(cheque) = self.cheques.borrow_mut().get_mut(&src) {
+            *cheque -= cheque_amount;
+        }
+
+        Ok(true)
+    }
+
+    fn cheque_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
+        let cheque = self.cheques.borrow().get(&account).copied().unwrap_or(0);
+        let spendable = Self::reducible_balance(&AddressConverter::convert(account));
+
+        Ok(cheque.min(spendable))
+    }
+
+    fn total_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
+        let account = AddressConverter::convert(account);
+        Ok(Assets::total_balance(AssetId::get(), &account).into())
+    }
+
+    fn total_issuance(&self) -> Result<u128, Self::Error> {
+        Ok(Assets::total_issuance(AssetId::get()).into())
+    }
+
+    fn account_exists(&self, account: AccountAddress) -> Result<bool, Self::Error> {
+        let account = AddressConverter::convert(account);
+        Ok(!Assets::total_balance(AssetId::get(), &account).is_zero())
+    }
+
+    fn minimum_balance(&self) -> Result<u128, Self::Error> {
+        Ok(Assets::minimum_balance(AssetId::get()).into())
+    }
+
+    fn create_account(
+        &self,
+        payer: AccountAddress,
+        account: AccountAddress,
+    ) -> Result<bool, Self::Error> {
+        if self.account_exists(account)? {
+            return Ok(true);
+        }
+
+        // The account is created by the transfer of the existential deposit.
+        self.transfer(payer, account, self.minimum_balance()?)
+    }
+
+    fn withdraw_fee(&self, payer: AccountAddress, fee: u128) -> Result<bool, Self::Error> {
+        if fee == 0 {
+            return Ok(true);
+        }
+
+        let account = AddressConverter::convert(payer);
+        if Self::reducible_balance(&account) < fee {
+            return Ok(false);
+        }
+        let Ok(amount) = Assets::Balance::try_from(fee) else {
+            return Ok(false);
+        };
+
+        let burned = Assets::burn_from(
+            AssetId::get(),
+            &account,
+            amount,
+            Precision::Exact,
+            Fortitude::Polite,
+        );
+        Ok(burned.is_ok())
+    }
+
+    fn refund_fee(&self, payer: AccountAddress, refund: u128) -> Result<(), Self::Error> {
+        if refund == 0 {
+            return Ok(());
+        }
+
+        let amount =
+            Assets::Balance::try_from(refund).map_err(|_| StatusCode::VM_EXTENSION_ERROR)?;
+        let account = AddressConverter::convert(payer);
+        Assets::mint_into(AssetId::get(), &account, amount)
+            .map(|_| ())
+            .map_err(|_| StatusCode::VM_EXTENSION_ERROR)
+    }
+
+    fn foreign_call(
+        &self,
+        _target: u64,
+        _payload: &[u8],
+        _gas_limit: u64,
+    ) -> Result<ForeignCallResponse, Self::Error> {
+        // The adapter only provides the balances.
+        Err(StatusCode::VM_EXTENSION_ERROR)
+    }
+
+    fn send_xcm(&self, _origin: AccountAddress, _message: XcmMessage) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn random_seed(&self, _subject: &[u8]) -> Result<[u8; 32], Self::Error> {
+        Err(StatusCode::VM_EXTENSION_ERROR)
+    }
+
+    fn now_milliseconds(&self) -> Result<u64, Self::Error> {
+        Err(StatusCode::VM_EXTENSION_ERROR)
+    }
+}
@@ -1,17 +1,28 @@
 //! Provides a configuration to prepare the initial MoveVM storage state.
+//!
+//! Besides the standard libraries, the genesis writes the chain constants read by the contracts
+//! through the `std::chain_config` module, if the host provides them with a
+//! [`ChainConfigProvider`].
 
-use crate::balance::DummyBalanceHandler;
+use crate::host::DummyHostBindings;
+#[cfg(feature = "scripts")]
+use crate::templates::{ScriptTemplate, TemplateError};
 use crate::Mvm;
 use crate::VmResult;
 use crate::{storage::Storage, types::GasStrategy};
 use alloc::borrow::Cow;
 use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
 use core::cell::RefCell;
 use core::fmt;
-use hashbrown::HashMap;
+use move_core_types::account_address::AccountAddress;
+#[cfg(feature = "scripts")]
+use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::CORE_CODE_ADDRESS;
 use move_stdlib::{move_stdlib_bundle, substrate_stdlib_bundle};
+use move_vm_backend_common::chain_config::ChainConfig;
+use serde::{Deserialize, Serialize};
 
 /// Error codes for [`GenesisConfig`].
 #[derive(Debug)]
@@ -20,6 +31,11 @@ pub enum GenesisConfigError {
     MoveVmInitFailure,
     /// Publish bundle failure.
     PublishBundle(VmResult),
+    /// Script template registration failure.
+    #[cfg(feature = "scripts")]
+    ScriptTemplate(TemplateError),
+    /// Chain configuration write failure.
+    ChainConfig,
 }
 
 impl fmt::Display for GenesisConfigError {
@@ -27,10 +43,41 @@ impl fmt::Display for GenesisConfigError {
         match self {
             Self::MoveVmInitFailure => write!(f, "MoveVM failed to initalize"),
             Self::PublishBundle(vm_result) => write!(f, "Publish bundle failed: {:?}", vm_result),
+            #[cfg(feature = "scripts")]
+            Self::ScriptTemplate(e) => write!(f, "Script template registration failed: {}", e),
+            Self::ChainConfig => write!(f, "Chain configuration write failed"),
         }
     }
 }
 
+/// Source of the chain constants written at genesis.
+pub trait ChainConfigProvider {
+    /// Identifier of the chain.
+    fn chain_id(&self) -> u64;
+
+    /// Number of the decimals of the native token.
+    fn token_decimals(&self) -> u8;
+
+    /// Length of the account addresses in bytes.
+    fn address_length(&self) -> u8 {
+        AccountAddress::LENGTH as u8
+    }
+}
+
+impl ChainConfigProvider for ChainConfig {
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn token_decimals(&self) -> u8 {
+        self.token_decimals
+    }
+
+    fn address_length(&self) -> u8 {
+        self.address_length
+    }
+}
+
 /// Configuration to prepare the initial MoveVM storage state.
 ///
 /// By default - a precompiled standard library is used from the `substrate-move` repository.
@@ -39,6 +86,11 @@ pub struct VmGenesisConfig {
     stdlib_bundle: Vec<u8>,
     /// An extended standard library for Substrate framework.
     substrate_stdlib_bundle: Vec<u8>,
+    /// Script templates registered after the standard libraries are published.
+    #[cfg(feature = "scripts")]
+    script_templates: Vec<(Identifier, ScriptTemplate)>,
+    /// Chain constants for the `std::chain_config` module.
+    chain_config: Option<ChainConfig>,
     // - additional_bundles: Vec<Vec<u8>>,
     // - initial_script
 }
@@ -48,6 +100,9 @@ impl Default for VmGenesisConfig {
         Self {
             stdlib_bundle: move_stdlib_bundle().to_vec(),
             substrate_stdlib_bundle: substrate_stdlib_bundle().to_vec(),
+            #[cfg(feature = "scripts")]
+            script_templates: Vec::new(),
+            chain_config: None,
         }
     }
 }
@@ -63,10 +118,47 @@ impl VmGenesisConfig {
         self.substrate_stdlib_bundle = bundle;
     }
 
+    /// Register the script template under the name - see [`crate::templates`].
+    #[cfg(feature = "scripts")]
+    pub fn add_script_template(&mut self, name: Identifier, template: ScriptTemplate) {
+        self.script_templates.push((name, template));
+    }
+
+    /// Configure the chain constants readable through the `std::chain_config` module.
+    pub fn configure_chain(&mut self, provider: &impl ChainConfigProvider) {
+        self.chain_config = Some(ChainConfig {
+            chain_id: provider.chain_id(),
+            token_decimals: provider.token_decimals(),
+            address_length: provider.address_length(),
+        });
+    }
+
     /// Apply the configuration to the storage.
     pub fn apply<S: Storage>(self, storage: S) -> Result<(), GenesisConfigError> {
         let storage_safe = StorageSafe::new(storage);
-        let vm = Mvm::new(&storage_safe, DummyBalanceHandler {})
+        self.publish(&storage_safe)?;
+
+        // In case of the successful initialization, apply changes to the storage.
+        storage_safe.apply_changes();
+
+        Ok(())
+    }
+
+    /// Build the storage writes [`VmGenesisConfig::apply`] makes to an empty storage, without
+    /// needing a storage backend.
+    ///
+    /// The snapshot can be embedded in the chain specification and written with
+    /// [`GenesisSnapshot::apply`].
+    pub fn build_snapshot(self) -> Result<GenesisSnapshot, GenesisConfigError> {
+        let storage_safe = StorageSafe::new(EmptyStorage);
+        self.publish(&storage_safe)?;
+
+        Ok(storage_safe.into_snapshot())
+    }
+
+    /// Publish the configured bundles and register the script templates in the storage safe.
+    fn publish<S: Storage>(self, storage_safe: &StorageSafe<S>) -> Result<(), GenesisConfigError> {
+        let vm = Mvm::new(storage_safe, DummyHostBindings {})
             .map_err(|_| GenesisConfigError::MoveVmInitFailure)?;
 
         let publish_under_stdaddr = |bundle: &[u8]| {
@@ -82,19 +174,58 @@ impl VmGenesisConfig {
         publish_under_stdaddr(&self.stdlib_bundle)?;
         publish_under_stdaddr(&self.substrate_stdlib_bundle)?;
 
-        // In case of the successful initialization, apply changes to the storage.
-        storage_safe.apply_changes();
+        if let Some(config) = &self.chain_config {
+            vm.write_chain_config(config)
+                .map_err(|_| GenesisConfigError::ChainConfig)?;
+        }
+
+        #[cfg(feature = "scripts")]
+        for (name, template) in &self.script_templates {
+            vm.register_script_template(name, template)
+                .map_err(GenesisConfigError::ScriptTemplate)?;
+        }
 
         Ok(())
     }
 }
 
+/// Storage writes of the genesis configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisSnapshot {
+    /// Written keys and their values, ordered by the key.
+    pub entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl GenesisSnapshot {
+    /// Write the snapshot to the storage.
+    pub fn apply<S: Storage>(&self, storage: S) {
+        for (key, value) in &self.entries {
+            storage.set(key, value);
+        }
+    }
+}
+
+/// Storage without any data, which ignores all writes.
+struct EmptyStorage;
+
+impl Storage for EmptyStorage {
+    fn get(&self, _key: &[u8]) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn set(&self, _key: &[u8], _value: &[u8]) {}
+
+    fn remove(&self, _key: &[u8]) {}
+}
+
 /// Storage safe keeps internal storage immutable until the changes are specificially applied.
 struct StorageSafe<S: Storage> {
     /// A safe place for our storage.
     inner: S,
     /// Separate list of storage changesets.
-    diff: RefCell<HashMap<Cow<'static, [u8]>, Option<Vec<u8>>>>,
+    ///
+    /// Ordered, so the changes are always applied to the storage in the same order.
+    diff: RefCell<BTreeMap<Cow<'static, [u8]>, Option<Vec<u8>>>>,
 }
 
 impl<S: Storage> StorageSafe<S> {
@@ -106,6 +237,18 @@ impl<S: Storage> StorageSafe<S> {
         }
     }
 
+    /// Collects the written values - removals are no-ops on the empty storage.
+    fn into_snapshot(self) -> GenesisSnapshot {
+        let entries = self
+            .diff
+            .take()
+            .into_iter()
+            .filter_map(|(key, val)| Some((key.into_owned(), val?)))
+            .collect();
+
+        GenesisSnapshot { entries }
+    }
+
     /// Finally applies internal changesets to the internal storage.
     fn apply_changes(self) {
         for (key, val) in self.diff.take() {
@@ -0,0 +1,253 @@
+//! Host services available to the MoveVM.
+//!
+//! All functionality the MoveVM needs from the host (the pallet) is provided through the single
+//! [`HostBindings`] trait, so the pallet can implement it in one place via the runtime interfaces.
+//! New host services are added as new trait methods.
+
+use move_core_types::{account_address::AccountAddress, vm_status::StatusCode};
+
+pub use move_stdlib::natives::foreign::ForeignCallResponse;
+pub use move_vm_backend_common::xcm::XcmMessage;
+
+/// Trait for the host services.
+///
+/// This is used to provide an access to the external functionality from within the MoveVM.
+pub trait HostBindings {
+    /// Error type of the host services.
+    type Error: Into<StatusCode>;
+
+    // Balance handling.
+
+    /// Transfer the `cheque_amount` from the `src` to the `dst` account.
+    ///
+    /// Returns `false` if the transfer can't be made, e.g. the `src` can't afford it or the amount
+    /// doesn't fit the host balance type - the Move code decides how to handle it. The error is
+    /// reserved for the host failures and fails the whole transaction.
+    ///
+    /// The MoveVM handles the edge cases before calling the host, so the host never sees:
+    /// - the transfers to self, which succeed without any changes if the `src` can afford them,
+    /// - the transfers overflowing the total balance of the `dst`, which return `false`,
+    /// - the transfers below the [`Self::minimum_balance`] to an account which doesn't exist,
+    ///   which return `false`, as the `dst` would be reaped right away.
+    ///
+    /// Zero transfers reach the host, so it can account for the call, and must succeed without
+    /// moving any funds or creating the `dst` account. Whether the `src` may be left below the
+    /// minimum balance is up to the host - it may refuse the transfer or reap the account.
+    ///
+    /// Adapters can check their implementation with the conformance suite of the
+    /// `move-vm-backend-test-utils` crate.
+    fn transfer(
+        &self,
+        src: AccountAddress,
+        dst: AccountAddress,
+        cheque_amount: u128,
+    ) -> Result<bool, Self::Error>;
+
+    /// Amount the account is allowed to transfer within the current execution context.
+    fn cheque_amount(&self, account: AccountAddress) -> Result<u128, Self::Error>;
+
+    /// Total balance of the account.
+    fn total_amount(&self, account: AccountAddress) -> Result<u128, Self::Error>;
+
+    /// Total issuance of the currency.
+    fn total_issuance(&self) -> Result<u128, Self::Error>;
+
+    /// Check if the account exists - accounts below the minimum balance get reaped.
+    fn account_exists(&self, account: AccountAddress) -> Result<bool, Self::Error>;
+
+    /// Minimum balance an account must keep to exist (the existential deposit).
+    fn minimum_balance(&self) -> Result<u128, Self::Error>;
+
+    // Account lifecycle.
+
+    /// Create the `account`, with the `payer` paying for it, e.g. with the existential deposit.
+    ///
+    /// Returns `false` if the account can't be created, e.g. the payer can't afford it - the Move
+    /// code decides how to handle it. Creating an existing account must succeed without any
+    /// costs.
+    fn create_account(
+        &self,
+        payer: AccountAddress,
+        account: AccountAddress,
+    ) -> Result<bool, Self::Error>;
+
+    // Transaction fees.
+
+    /// Withdraw the `fee` from the `payer` account, which pays for the transaction signed by the
+    /// other accounts - see [`crate::Mvm::execute_with_fee_payer`] and
+    /// [`crate::Mvm::set_fee_payer`].
+    ///
+    /// Returns `false` if the payer can't afford the fee, which rejects the transaction.
+    fn withdraw_fee(&self, payer: AccountAddress, fee: u128) -> Result<bool, Self::Error>;
+
+    /// Return the unused part of the withdrawn fee to the `payer` account.
+    ///
+    /// A failed refund doesn't revert the executed transaction - it's reported in
+    /// [`crate::types::VmResult::refund_error`].
+    fn refund_fee(&self, payer: AccountAddress, refund: u128) -> Result<(), Self::Error>;
+
+    // Foreign calls.
+
+    /// Invoke the host function registered under the `target` with the BCS-encoded `payload`, e.g.
+    /// a call into the `pallet-contracts` or an XCM sender.
+    ///
+    /// The function may use at most `gas_limit` internal gas units and must report the gas it
+    /// used. Failures of the function itself should be returned as the abort code in the response,
+    /// the error is reserved for the host failures and fails the whole transaction.
+    ///
+    /// The function must not execute any MoveVM code - the MoveVM instance making the call rejects
+    /// all executions and publishing until the foreign call returns.
+    fn foreign_call(
+        &self,
+        target: u64,
+        payload: &[u8],
+        gas_limit: u64,
+    ) -> Result<ForeignCallResponse, Self::Error>;
+
+    // Cross-chain messages.
+
+    /// Send the XCM `message` built by the `std::xcm` module with the `origin` account as the
+    /// message origin.
+    ///
+    /// Returns `false` if the message can't be delivered, e.g. the destination is unreachable or
+    /// the origin can't pay the delivery fees - the Move code decides how to handle it.
+    fn send_xcm(&self, origin: AccountAddress, message: XcmMessage) -> Result<bool, Self::Error>;
+
+    // Hashing.
+
+    /// SHA2-256 digest of the `data`, used by the `std::hash` module.
+    ///
+    /// Hosts can compute it with the runtime interface, e.g. `sp_io::hashing::sha2_256`, so it runs
+    /// natively instead of in the runtime. The default computes it in place.
+    fn sha2_256(&self, data: &[u8]) -> [u8; 32] {
+        move_stdlib::natives::hash::sha2_256(data)
+    }
+
+    /// SHA3-256 digest of the `data`, used by the `std::hash` module - see [`Self::sha2_256`].
+    fn sha3_256(&self, data: &[u8]) -> [u8; 32] {
+        move_stdlib::natives::hash::sha3_256(data)
+    }
+
+    // Randomness.
+
+    /// Random seed for the `subject`, used by the `std::random` module.
+    ///
+    /// The seed must be the same on all nodes executing the transaction, e.g. derived from the
+    /// randomness beacon of the block and the subject - local entropy would break the consensus.
+    fn random_seed(&self, subject: &[u8]) -> Result<[u8; 32], Self::Error>;
+
+    // Time.
+
+    /// Timestamp of the current block in milliseconds since the Unix epoch, used by the
+    /// `std::timestamp` module.
+    fn now_milliseconds(&self) -> Result<u64, Self::Error>;
+
+    // Gas metering.
+
+    /// Weight consumed by the host calls since the last report, e.g. the storage accesses of a
+    /// transfer.
+    ///
+    /// The natives query it after every host call and charge it to the transaction, converted to
+    /// gas with the ratio set by [`crate::Mvm::set_host_weight_ratio`]. The host must reset the
+    /// counter on every report, so the weight isn't charged twice. Hosts which price their calls
+    /// into the native costs can keep the default, which reports nothing.
+    fn take_consumed_weight(&self) -> u64 {
+        0
+    }
+
+    // Module verification.
+
+    /// Verification marker of the module bytecode with the `module_hash`.
+    ///
+    /// The marker holds the [`VMConfig::verification_hash`] of the verifier which accepted the
+    /// bytecode. Loading the module skips the bytecode verification while the hashes match, so
+    /// changing the verifier config or the maximum binary format version invalidates all markers at
+    /// once.
+    ///
+    /// Whether the marker gets written depends on the loader cache of the node, so the markers must
+    /// be kept outside of the consensus state, e.g. in the node-local offchain storage. Hosts
+    /// without such storage can keep the default, which keeps no markers - the modules are then
+    /// verified on every load.
+    ///
+    /// [`VMConfig::verification_hash`]: move_vm_runtime::config::VMConfig::verification_hash
+    fn verified_module(&self, _module_hash: &[u8; 32]) -> Option<[u8; 32]> {
+        None
+    }
+
+    /// Record the verification marker of the module bytecode with the `module_hash` - see
+    /// [`Self::verified_module`].
+    fn set_verified_module(&self, _module_hash: &[u8; 32], _config_hash: &[u8; 32]) {}
+}
+
+/// An unused [`HostBindings`] implementation that is needed for special cases (genesis configuration).
+pub(crate) struct DummyHostBindings;
+
+impl HostBindings for DummyHostBindings {
+    type Error = StatusCode;
+
+    fn transfer(
+        &self,
+        _src: AccountAddress,
+        _dst: AccountAddress,
+        _cheque_amount: u128,
+    ) -> Result<bool, Self::Error> {
+        unreachable!()
+    }
+
+    fn cheque_amount(&self, _account: AccountAddress) -> Result<u128, Self::Error> {
+        unreachable!()
+    }
+
+    fn total_amount(&self, _account: AccountAddress) -> Result<u128, Self::Error> {
+        unreachable!()
+    }
+
+    fn total_issuance(&self) -> Result<u128, Self::Error> {
+        unreachable!()
+    }
+
+    fn account_exists(&self, _account: AccountAddress) -> Result<bool, Self::Error> {
+        unreachable!()
+    }
+
+    fn minimum_balance(&self) -> Result<u128, Self::Error> {
+        unreachable!()
+    }
+
+    fn create_account(
+        &self,
+        _payer: AccountAddress,
+        _account: AccountAddress,
+    ) -> Result<bool, Self::Error> {
+        unreachable!()
+    }
+
+    fn withdraw_fee(&self, _payer: AccountAddress, _fee: u128) -> Result<bool, Self::Error> {
+        unreachable!()
+    }
+
+    fn refund_fee(&self, _payer: AccountAddress, _refund: u128) -> Result<(), Self::Error> {
+        unreachable!()
+    }
+
+    fn foreign_call(
+        &self,
+        _target: u64,
+        _payload: &[u8],
+        _gas_limit: u64,
+    ) -> Result<ForeignCallResponse, Self::Error> {
+        unreachable!()
+    }
+
+    fn send_xcm(&self, _origin: AccountAddress, _message: XcmMessage) -> Result<bool, Self::Error> {
+        unreachable!()
+    }
+
+    fn random_seed(&self, _subject: &[u8]) -> Result<[u8; 32], Self::Error> {
+        unreachable!()
+    }
+
+    fn now_milliseconds(&self) -> Result<u64, Self::Error> {
+        unreachable!()
+    }
+}
@@ -0,0 +1,147 @@
+//! Optional naming policy for the published modules.
+//!
+//! The binary format already restricts the identifiers to ASCII alphanumerics and `_` (so there
+//! are no confusable unicode characters) and caps their length at 255 bytes. A chain can narrow
+//! these rules further with an [`IdentifierPolicy`], which is checked for the module name and all
+//! struct, field and function names declared in every published module - both for single modules
+//! and for module bundles.
+//!
+//! All publishing paths validate the modules with [`check_module_bytes`], which the embedders can
+//! also call to reject the modules early, e.g. in the transaction pool.
+
+use crate::MAX_BINARY_FORMAT_VERSION;
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{CompiledModule, StructFieldInformation},
+};
+use move_core_types::identifier::IdentStr;
+
+/// Kind of the checked identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentifierKind {
+    Module,
+    Struct,
+    Field,
+    Function,
+}
+
+impl fmt::Display for IdentifierKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Module => write!(f, "Module"),
+            Self::Struct => write!(f, "Struct"),
+            Self::Field => write!(f, "Field"),
+            Self::Function => write!(f, "Function"),
+        }
+    }
+}
+
+/// Publish-time check of the identifiers declared by the modules.
+pub trait IdentifierPolicy {
+    /// Returns the reason why the identifier is rejected, if it is.
+    fn check(&self, kind: IdentifierKind, ident: &IdentStr) -> Result<(), &'static str>;
+}
+
+impl<F> IdentifierPolicy for F
+where
+    F: Fn(IdentifierKind, &IdentStr) -> Result<(), &'static str>,
+{
+    fn check(&self, kind: IdentifierKind, ident: &IdentStr) -> Result<(), &'static str> {
+        self(kind, ident)
+    }
+}
+
+/// Policy with the common naming rules, applied to all identifier kinds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamingPolicy {
+    /// Maximum identifier length in bytes.
+    pub max_length: Option<usize>,
+    /// Prefixes reserved for the chain, e.g. `pallet_`.
+    pub reserved_prefixes: Vec<String>,
+    /// Reject the identifiers starting with `_`.
+    pub forbid_leading_underscore: bool,
+    /// Require the module names to be lowercase, e.g. `basic_coin`.
+    pub lowercase_module_names: bool,
+}
+
+impl IdentifierPolicy for NamingPolicy {
+    fn check(&self, kind: IdentifierKind, ident: &IdentStr) -> Result<(), &'static str> {
+        if matches!(self.max_length, Some(max) if ident.len() > max) {
+            return Err("identifier is too long");
+        }
+
+        if self.forbid_leading_underscore && ident.as_str().starts_with('_') {
+            return Err("identifier starts with an underscore");
+        }
+
+        if self.lowercase_module_names
+            && kind == IdentifierKind::Module
+            && ident.as_str().chars().any(|c| c.is_ascii_uppercase())
+        {
+            return Err("module name is not lowercase");
+        }
+
+        if self
+            .reserved_prefixes
+            .iter()
+            .any(|prefix| ident.as_str().starts_with(prefix.as_str()))
+        {
+            return Err("identifier uses a reserved prefix");
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks the serialized module against the policy.
+///
+/// Modules which fail to deserialize pass, since the MoveVM rejects them when publishing anyway.
+pub fn check_module_bytes(policy: &dyn IdentifierPolicy, module: &[u8]) -> Result<(), String> {
+    match CompiledModule::deserialize_with_max_version(module, MAX_BINARY_FORMAT_VERSION) {
+        Ok(module) => check_module(policy, &module),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Checks the module name and the names of all structs, fields and functions defined in the
+/// module.
+pub(crate) fn check_module(
+    policy: &dyn IdentifierPolicy,
+    module: &CompiledModule,
+) -> Result<(), String> {
+    let module_name = core::iter::once((
+        IdentifierKind::Module,
+        module.identifier_at(module.self_handle().name),
+    ));
+    let struct_names = module.struct_defs().iter().map(|def| {
+        let handle = module.struct_handle_at(def.struct_handle);
+        (IdentifierKind::Struct, module.identifier_at(handle.name))
+    });
+    let field_names = module
+        .struct_defs()
+        .iter()
+        .filter_map(|def| match &def.field_information {
+            StructFieldInformation::Declared(fields) => Some(fields),
+            StructFieldInformation::Native => None,
+        })
+        .flatten()
+        .map(|field| (IdentifierKind::Field, module.identifier_at(field.name)));
+    let function_names = module.function_defs().iter().map(|def| {
+        let handle = module.function_handle_at(def.function);
+        (IdentifierKind::Function, module.identifier_at(handle.name))
+    });
+
+    let names = module_name
+        .chain(struct_names)
+        .chain(field_names)
+        .chain(function_names);
+    for (kind, ident) in names {
+        policy
+            .check(kind, ident)
+            .map_err(|reason| alloc::format!("{kind} name `{ident}` is not allowed: {reason}"))?;
+    }
+
+    Ok(())
+}
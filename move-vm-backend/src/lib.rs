@@ -2,74 +2,579 @@
 
 extern crate alloc;
 
-pub mod balance;
+pub mod acl;
+#[cfg(feature = "scripts")]
+pub mod allowlist;
+pub mod arg_limits;
+pub mod block;
+mod call_limits;
+mod compression;
+mod event_sequence;
+pub mod expiry;
+pub mod fee_hook;
+pub mod fee_policy;
+pub mod freeze;
+#[cfg(feature = "substrate")]
+pub mod fungibles;
 pub mod genesis;
+pub mod host;
+pub mod identifier_policy;
+mod memory;
+pub mod metrics;
+#[cfg(feature = "scripts")]
+pub mod migration;
+#[cfg(feature = "scripts")]
+pub mod multisig;
+pub mod native_gating;
+#[cfg(feature = "scripts")]
+mod parallel;
+#[cfg(feature = "std")]
+pub mod preverify;
+pub mod privileged;
+mod profiler;
+pub mod reentrancy;
+pub mod retirement;
+pub mod stats;
 pub mod storage;
+pub mod storage_cache;
+pub mod storage_dump;
+pub mod storage_key;
+#[cfg(feature = "std")]
+pub mod subscription;
+pub mod system_calls;
+#[cfg(feature = "scripts")]
+pub mod templates;
 pub mod types;
+pub mod upgrade;
 mod warehouse;
+pub mod warm_up;
 
+#[cfg(feature = "scripts")]
+use crate::allowlist::{AllowedScriptHash, ScriptAllowlist};
+use crate::arg_limits::VectorArgLimits;
+use crate::block::{BlockError, BlockState, BlockSummary};
+use crate::call_limits::{CallGasLimits, CallLimitedGasMeter};
+use crate::event_sequence::EventSequence;
+use crate::expiry::{ExpiryRegistry, SweepReport};
+use crate::fee_hook::FeeHook;
+use crate::fee_policy::{FeeMultiplier, FeePolicy};
+use crate::freeze::{FreezeCapability, FreezeRegistry};
+use crate::identifier_policy::IdentifierPolicy;
+use crate::memory::MemoryTrackedGasMeter;
+use crate::metrics::{LayoutCacheStats, Metrics};
+#[cfg(feature = "scripts")]
+use crate::migration::{LayoutHash, Migration, MigrationRegistry, MigrationReport, MigrationView};
+#[cfg(feature = "scripts")]
+use crate::multisig::{
+    script_hash, MultisigAccount, MultisigAccounts, MultisigError, MultisigStatus, PendingScript,
+    PendingScripts, ScriptHash,
+};
+use crate::native_gating::GatedNative;
+#[cfg(feature = "scripts")]
+use crate::parallel::Speculation;
+#[cfg(feature = "std")]
+use crate::preverify::{CodeHash, PreverificationQueue, VerifiedCode};
+use crate::privileged::PublishCapability;
+use crate::profiler::{GasProfiler, ProfilingGasMeter};
+use crate::reentrancy::ReentrancyGuard;
+use crate::retirement::{ModuleIndex, RetireError};
+use crate::stats::{ModuleStats, ModuleStatsRegistry};
 use crate::storage::Storage;
-use crate::types::{Call, Transaction, VmResult};
+#[cfg(feature = "std")]
+use crate::subscription::{Subscriber, Subscribers, SubscriptionId};
+use crate::system_calls::{SystemCallCapability, SystemFunction};
+#[cfg(feature = "scripts")]
+use crate::templates::{ScriptTemplate, TemplateArg, TemplateError, TemplateRegistry};
+use crate::types::{Call, ErrorContext, Transaction, VmResult};
+use crate::upgrade::{InvalidationReport, UpgradeHook};
 use crate::warehouse::Warehouse;
-use alloc::{format, string::ToString, vec::Vec};
+use crate::warm_up::{WarmUpOutcome, WarmUpProgress, WarmUpReport};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use anyhow::{anyhow, Error};
-use balance::BalanceHandler;
-use move_binary_format::{errors::VMResult, file_format::CompiledModule};
+use core::cell::RefCell;
+use host::HostBindings;
+use move_binary_format::{
+    access::ModuleAccess,
+    binary_views::BinaryIndexedView,
+    compatibility::Compatibility,
+    errors::{Location, PartialVMError, VMError, VMResult},
+    file_format::{CompiledModule, SignatureToken},
+    file_format_common::{VERSION_7, VERSION_MAX},
+};
+#[cfg(feature = "scripts")]
+use move_binary_format::{access::ScriptAccess, file_format::CompiledScript};
+#[cfg(feature = "scripts")]
+use move_core_types::value::MoveTypeLayout;
 use move_core_types::{
     account_address::AccountAddress,
-    effects::{ChangeSet, Event},
-    identifier::Identifier,
-    language_storage::{ModuleId, TypeTag, CORE_CODE_ADDRESS},
-    resolver::{ModuleResolver, ResourceResolver},
+    effects::{ChangeSet, Event, Op},
+    gas_algebra::InternalGas,
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, StructTag, TypeTag, CORE_CODE_ADDRESS},
+    resolver::{ModuleResolver, MoveResolver, ResourceResolver},
+    value::MoveValue,
     vm_status::StatusCode,
 };
-use move_stdlib::natives::all_natives;
+use move_stdlib::natives::{
+    account::{AccountHandler, NativeAccountContext},
+    all_natives,
+    expiry::{ExpiryChanges, NativeExpiryContext},
+    foreign::{ForeignCallHandler, NativeForeignCallContext},
+    hash::{HashHandler, NativeHashContext},
+    host_gas::{HostWeightReporter, NativeHostGasContext},
+    random::{NativeRandomnessContext, RandomnessHandler},
+    timestamp::{NativeTimeContext, TimeHandler},
+    xcm::{NativeXcmContext, XcmSender},
+};
 use move_vm_backend_common::{
-    abi::ModuleAbi, gas_schedule::NATIVE_COST_PARAMS, types::ModuleBundle,
+    abi::ModuleAbi,
+    access_control::{access_control_tag, AccessControl},
+    call_builder::{CallArg, CallBuilder, ComposedCall, Composition, EntryCall},
+    chain_config::{chain_config_tag, ChainConfig},
+    event::MoveEvent,
+    gas_schedule::{
+        DEFAULT_HOST_WEIGHT_PER_GAS, DEFAULT_MAX_TYPE_TAG_DEPTH, DEFAULT_MEMORY_LIMIT,
+        NATIVE_COST_PARAMS,
+    },
+    legacy::{upgrade_legacy_binary, LegacyBinaryError},
+    module_deps, receipt, std_args,
+    types::ModuleBundle,
+    value::CanonicalValue,
+    verification_bound::{bundle_publish_complexity, module_publish_complexity},
 };
-use move_vm_runtime::move_vm::MoveVM;
-use types::{GasHandler, GasStrategy};
+#[cfg(feature = "scripts")]
+use move_vm_backend_common::{
+    footprint::{analyze_script_footprint, StorageFootprint},
+    script_lint::{self, LintWarning},
+    types::ScriptTransaction,
+};
+use move_vm_runtime::{
+    config::{InterruptHandle, VMConfig, DEFAULT_MAX_CALL_DEPTH},
+    move_vm::MoveVM,
+    native_extensions::NativeContextExtensions,
+    session::Session,
+};
+use move_vm_types::gas::GasMeter;
+#[cfg(feature = "scripts")]
+use types::DryRunReport;
+use types::{ExecutionConfig, GasAmount, GasHandler, GasStrategy};
+
+/// Changeset, events and resource expiry changes of the executed transaction.
+pub(crate) type TransactionOutput = (ChangeSet, Vec<Event>, ExpiryChanges);
 
 /// Main MoveVM structure, which is used to represent the virutal machine itself.
-pub struct Mvm<S, B>
+pub struct Mvm<S, H>
 where
     S: Storage,
-    B: BalanceHandler,
+    H: HostBindings,
 {
     // MoveVM instance - from move_vm_runtime crate
     vm: MoveVM,
     // Storage instance
-    warehouse: Warehouse<S, B>,
+    warehouse: Warehouse<S, H>,
+    // Settings for the executed transactions.
+    config: ExecutionConfig,
+    // Naming policy for the published modules.
+    identifier_policy: Option<Box<dyn IdentifierPolicy>>,
+    // Move function paying the transaction fees.
+    fee_hook: Option<FeeHook>,
+    // Account paying the transaction fees instead of the signers.
+    fee_payer: Option<AccountAddress>,
+    // Conversion of the used gas into the fees.
+    fee_policy: Option<Box<dyn FeePolicy>>,
+    // Gas ceilings of the calls into the modules.
+    call_gas_limits: BTreeMap<ModuleId, GasAmount>,
+    // Addresses where only the privileged path can publish modules.
+    reserved_addresses: BTreeSet<AccountAddress>,
+    // Size limits of the vector arguments.
+    vector_arg_limits: VectorArgLimits,
+    // Framework functions executable without gas by the root context.
+    system_calls: BTreeSet<SystemFunction>,
+    // Natives failing when called.
+    gated_natives: BTreeSet<GatedNative>,
+    // State of the block in progress.
+    block: RefCell<Option<BlockState>>,
+    // Modules reloaded whenever the caches are flushed.
+    warmed_modules: RefCell<BTreeSet<ModuleId>>,
+    // Flag aborting the running executions.
+    interrupt: InterruptHandle,
+    // Embedders notified of the finalized changes.
+    #[cfg(feature = "std")]
+    subscribers: Subscribers,
+    // Embedder notified of the module upgrades.
+    upgrade_hook: Option<Box<dyn UpgradeHook>>,
 }
 
-impl<S, B> Mvm<S, B>
+impl<S, H> Mvm<S, H>
 where
     S: Storage,
-    B: BalanceHandler,
+    H: HostBindings,
 {
     /// Create a new Move VM with the given storage.
-    pub fn new(storage: S, balance_handler: B) -> Result<Mvm<S, B>, Error> {
-        Self::new_with_config(storage, balance_handler)
+    pub fn new(storage: S, host: H) -> Result<Mvm<S, H>, Error> {
+        Self::new_with_config(storage, host)
     }
 
     /// Create a new Move VM with the given storage and configuration.
     pub(crate) fn new_with_config(
         storage: S,
-        balance_handler: B,
+        host: H,
         // config: VMConfig,
-    ) -> Result<Mvm<S, B>, Error> {
+    ) -> Result<Mvm<S, H>, Error> {
+        let interrupt = InterruptHandle::new();
         Ok(Mvm {
-            // TODO(rqnsom): see if we can avoid GAS_PARAMS cloning
-            vm: MoveVM::new(all_natives(CORE_CODE_ADDRESS, NATIVE_COST_PARAMS.clone())).map_err(
-                |err| {
-                    let (code, _, msg, _, _, _, _) = err.all_data();
-                    anyhow!("Error code:{:?}: msg: '{}'", code, msg.unwrap_or_default())
-                },
+            vm: new_move_vm(
+                &BTreeSet::new(),
+                ExecutionConfig::default().max_call_depth,
+                &interrupt,
             )?,
-            warehouse: Warehouse::new(storage, balance_handler),
+            warehouse: Warehouse::new(storage, host),
+            config: ExecutionConfig::default(),
+            identifier_policy: None,
+            fee_hook: None,
+            fee_payer: None,
+            fee_policy: None,
+            call_gas_limits: BTreeMap::new(),
+            reserved_addresses: BTreeSet::new(),
+            vector_arg_limits: VectorArgLimits::default(),
+            system_calls: BTreeSet::new(),
+            gated_natives: BTreeSet::new(),
+            block: RefCell::new(None),
+            warmed_modules: RefCell::new(BTreeSet::new()),
+            interrupt,
+            #[cfg(feature = "std")]
+            subscribers: Subscribers::default(),
+            upgrade_hook: None,
         })
     }
 
+    /// Enable or disable the per-function gas profiling.
+    ///
+    /// Once enabled, the results of the executed transactions contain the gas consumed by each
+    /// called function.
+    pub fn set_gas_profiling(&mut self, enabled: bool) {
+        self.config.gas_profiling = enabled;
+    }
+
+    /// Set the maximum amount of memory in bytes a single transaction can allocate for the VM
+    /// values.
+    ///
+    /// The execution exceeding the limit fails with the `MEMORY_LIMIT_EXCEEDED` status code. The
+    /// default limit is [`DEFAULT_MEMORY_LIMIT`].
+    pub fn set_memory_limit(&mut self, limit: u64) {
+        self.config.memory_limit = limit;
+    }
+
+    /// Set how many host weight units are charged as one internal gas unit.
+    ///
+    /// The weight reported by [`HostBindings::take_consumed_weight`] after the host calls is
+    /// converted with the ratio, rounded up, and charged to the transaction. The ratio of zero is
+    /// treated as one. The default ratio is [`DEFAULT_HOST_WEIGHT_PER_GAS`].
+    pub fn set_host_weight_ratio(&mut self, weight_per_gas: u64) {
+        self.config.host_weight_per_gas = weight_per_gas;
+    }
+
+    /// Enable or disable charging the deserialization of the transaction arguments.
+    ///
+    /// Once enabled, the BCS-encoded arguments are charged before the execution begins according
+    /// to their byte length and the nesting depth of their parameter types - see
+    /// [`argument_cost`](move_vm_backend_common::gas_schedule::argument_cost). The arguments are
+    /// not charged by default.
+    pub fn set_arg_metering(&mut self, enabled: bool) {
+        self.config.arg_metering = enabled;
+    }
+
+    /// Set which balance calls are guarded against the reentrancy - see [`reentrancy`].
+    ///
+    /// The balance-affecting calls are guarded by default.
+    pub fn set_reentrancy_guard(&mut self, guard: ReentrancyGuard) {
+        self.warehouse.set_reentrancy_guard(guard);
+    }
+
+    /// Enable or disable recording the per-module execution statistics - see [`stats`].
+    ///
+    /// The recorded statistics are kept when disabled.
+    pub fn set_module_stats(&mut self, enabled: bool) {
+        self.config.module_stats = enabled;
+    }
+
+    /// Set the size limits of the vector arguments - see [`arg_limits`].
+    ///
+    /// Transactions exceeding the limits are rejected before the execution with the
+    /// `FAILED_TO_DESERIALIZE_ARGUMENT` status code. No vector arguments are limited by default.
+    pub fn set_vector_arg_limits(&mut self, limits: VectorArgLimits) {
+        self.vector_arg_limits = limits;
+    }
+
+    /// Set the maximum size in bytes of the BCS-encoded data of a single event.
+    ///
+    /// Transactions emitting a bigger event fail with the `EVENT_DATA_TOO_LARGE` status code and
+    /// make no changes. The limit applies to each event on its own, so a single oversized event
+    /// can't blow up the block even if the transaction emits few events. Events aren't limited by
+    /// default.
+    pub fn set_max_event_size(&mut self, size: Option<usize>) {
+        self.config.max_event_size = size;
+    }
+
+    /// Set the maximum nesting depth of the caller-supplied type arguments.
+    ///
+    /// Deeper type arguments are rejected before the execution with the
+    /// `VM_MAX_TYPE_DEPTH_REACHED` status code, even for the unmetered executions. The default
+    /// depth is [`DEFAULT_MAX_TYPE_TAG_DEPTH`].
+    pub fn set_max_type_tag_depth(&mut self, depth: usize) {
+        self.config.max_type_tag_depth = depth;
+    }
+
+    /// Set the naming policy checked for all identifiers declared by the published modules.
+    ///
+    /// Modules violating the policy are rejected with the `CONSTRAINT_NOT_SATISFIED` status code.
+    pub fn set_identifier_policy(&mut self, policy: impl IdentifierPolicy + 'static) {
+        self.identifier_policy = Some(Box::new(policy));
+    }
+
+    /// Remove the naming policy for the published modules.
+    pub fn clear_identifier_policy(&mut self) {
+        self.identifier_policy = None;
+    }
+
+    /// Let the Move function validate and pay the fees of the executed transactions.
+    ///
+    /// The hook applies to the single transactions and the blocks, which are then executed
+    /// sequentially.
+    pub fn set_fee_hook(&mut self, hook: FeeHook) {
+        self.fee_hook = Some(hook);
+    }
+
+    /// Remove the fee payment hook.
+    pub fn clear_fee_hook(&mut self) {
+        self.fee_hook = None;
+    }
+
+    /// Let the `fee_payer` account pay the fees of the executed transactions instead of their
+    /// signers - see [`Mvm::execute_with_fee_payer`].
+    ///
+    /// The fee payer applies to the single transactions, the call sequences and the blocks, which
+    /// are then executed sequentially. The fee hook isn't invoked while the fee payer is set.
+    /// System calls are never paid by the fee payer.
+    pub fn set_fee_payer(&mut self, fee_payer: AccountAddress) {
+        self.fee_payer = Some(fee_payer);
+    }
+
+    /// Let the signers pay the fees again.
+    pub fn clear_fee_payer(&mut self) {
+        self.fee_payer = None;
+    }
+
+    /// Set the conversion of the used gas into the fees reported in the results - see
+    /// [`fee_policy`].
+    pub fn set_fee_policy(&mut self, policy: impl FeePolicy + 'static) {
+        self.fee_policy = Some(Box::new(policy));
+    }
+
+    /// Remove the fee policy - the results then report no fees.
+    pub fn clear_fee_policy(&mut self) {
+        self.fee_policy = None;
+    }
+
+    /// Limit the gas every call into the module can use, on top of the transaction gas - see
+    /// [`call_limits`].
+    ///
+    /// The limit applies to the calls from other modules and to the module's functions executed
+    /// as the transaction entry, e.g. the fee hook or the init function. Calls exceeding the limit
+    /// fail the transaction with the `OUT_OF_GAS` status code. With the instruction counting, the
+    /// limit counts the instructions, and the unmetered executions aren't limited. No calls are
+    /// limited by default.
+    pub fn set_call_gas_limit(&mut self, module: ModuleId, limit: GasAmount) {
+        self.call_gas_limits.insert(module, limit);
+    }
+
+    /// Remove the gas limit of the calls into the module.
+    pub fn clear_call_gas_limit(&mut self, module: &ModuleId) {
+        self.call_gas_limits.remove(module);
+    }
+
+    /// Fee for the given gas with the multiplier of the block in progress.
+    ///
+    /// Returns zero if no fee policy is set.
+    pub fn fee_for(&self, gas_used: u64) -> u128 {
+        let Some(policy) = &self.fee_policy else {
+            return 0;
+        };
+        let multiplier = self
+            .block
+            .borrow()
+            .as_ref()
+            .map_or(FeeMultiplier::ONE, BlockState::fee_multiplier);
+        policy.fee(gas_used, multiplier)
+    }
+
+    /// Reserve the addresses for the privileged module publishing - see [`privileged`].
+    ///
+    /// Replaces the previously reserved addresses. No address is reserved by default.
+    pub fn set_reserved_addresses(&mut self, addresses: impl IntoIterator<Item = AccountAddress>) {
+        self.reserved_addresses = addresses.into_iter().collect();
+    }
+
+    /// Mark the functions as gas-exempt system calls - see [`system_calls`].
+    ///
+    /// Replaces the previously marked functions. No function is marked by default.
+    pub fn set_system_calls(&mut self, functions: impl IntoIterator<Item = SystemFunction>) {
+        self.system_calls = functions.into_iter().collect();
+    }
+
+    /// Switch off the native functions - see [`native_gating`].
+    ///
+    /// Replaces the previously gated natives. No native is gated by default. The MoveVM is rebuilt
+    /// with the new natives, so the loaded code is dropped from the cache.
+    pub fn set_gated_natives(
+        &mut self,
+        natives: impl IntoIterator<Item = GatedNative>,
+    ) -> Result<(), Error> {
+        let gated_natives = natives.into_iter().collect();
+        self.vm = new_move_vm(&gated_natives, self.config.max_call_depth, &self.interrupt)?;
+        self.gated_natives = gated_natives;
+        Ok(())
+    }
+
+    /// Set the maximum number of frames on the call stack, i.e. the callers of the running
+    /// function.
+    ///
+    /// Deeper calls fail the transaction with the `CALL_STACK_OVERFLOW` status code, the same way
+    /// in the native and the WASM builds. The default depth is [`DEFAULT_MAX_CALL_DEPTH`]. The
+    /// MoveVM is rebuilt with the new limit, so the loaded code is dropped from the cache.
+    pub fn set_max_call_depth(&mut self, depth: usize) -> Result<(), Error> {
+        self.vm = new_move_vm(&self.gated_natives, depth, &self.interrupt)?;
+        self.config.max_call_depth = depth;
+        Ok(())
+    }
+
+    /// Handle aborting the running executions from another thread, e.g. once the block authorship
+    /// deadline is reached.
+    ///
+    /// The interrupted transaction fails with the `EXECUTION_INTERRUPTED` status code and none of
+    /// its changes are applied, while the fee already paid by the fee hook is kept as for the other
+    /// failures. The interrupted transactions consume no gas of the block in progress. The flag
+    /// stays raised until reset with [`InterruptHandle::reset`], failing the following
+    /// transactions as well.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.interrupt.clone()
+    }
+
+    /// Check if the native function is gated.
+    pub fn is_native_gated(&self, module: &ModuleId, function: &IdentStr) -> bool {
+        self.gated_natives
+            .contains(&(module.clone(), function.to_owned()))
+    }
+
+    /// List the registered natives with their declared base gas costs.
+    ///
+    /// Every native is listed as `(address, module, function, base cost)`, sorted by the module
+    /// and the function. Gated natives are listed as well - see [`Self::is_native_gated`].
+    pub fn list_natives(&self) -> Vec<(AccountAddress, Identifier, Identifier, InternalGas)> {
+        let mut natives: Vec<_> = all_natives(CORE_CODE_ADDRESS, NATIVE_COST_PARAMS.clone())
+            .into_iter()
+            .map(|(address, module, function, _)| {
+                let base_cost = NATIVE_COST_PARAMS
+                    .base_cost(module.as_str(), function.as_str())
+                    .unwrap_or_else(InternalGas::zero);
+                (address, module, function, base_cost)
+            })
+            .collect();
+        natives.sort();
+        natives
+    }
+
+    /// Check if the function is marked as a system call.
+    pub fn is_system_call(&self, module: &ModuleId, function: &IdentStr) -> bool {
+        self.system_calls
+            .contains(&(module.clone(), function.to_owned()))
+    }
+
+    /// Notify the subscriber about the changes applied to the storage - see [`subscription`].
+    #[cfg(feature = "std")]
+    pub fn subscribe(&mut self, subscriber: impl Subscriber + 'static) -> SubscriptionId {
+        self.subscribers.add(Box::new(subscriber))
+    }
+
+    /// Stop notifying the subscriber.
+    ///
+    /// Returns `false` if there is no such subscription.
+    #[cfg(feature = "std")]
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.subscribers.remove(id)
+    }
+
+    /// Report the module upgrades and their invalidated dependents to the hook - see
+    /// [`upgrade`].
+    pub fn set_upgrade_hook(&mut self, hook: impl UpgradeHook + 'static) {
+        self.upgrade_hook = Some(Box::new(hook));
+    }
+
+    /// Remove the module upgrade hook.
+    pub fn clear_upgrade_hook(&mut self) {
+        self.upgrade_hook = None;
+    }
+
+    /// Start a block - see [`block`].
+    ///
+    /// Drops the caches, so nothing cached before the block affects it. The modules warmed up with
+    /// [`Self::warm_up`] are reloaded from the storage and the code handed over with
+    /// [`Self::preload_verified`] is kept, since neither depends on the executed transactions. The
+    /// transactions of the block can use at most the `gas_pool` in total, unlimited if `None`.
+    pub fn begin_block(&self, gas_pool: Option<GasAmount>) -> Result<(), BlockError> {
+        let mut block = self.block.borrow_mut();
+        if block.is_some() {
+            return Err(BlockError::AlreadyStarted);
+        }
+
+        self.flush_caches();
+        *block = Some(BlockState::new(gas_pool.map(|gas| gas.inner())));
+        Ok(())
+    }
+
+    /// Set the congestion multiplier of the fees for the rest of the block in progress - see
+    /// [`fee_policy`].
+    pub fn set_block_fee_multiplier(&self, multiplier: FeeMultiplier) -> Result<(), BlockError> {
+        self.block
+            .borrow_mut()
+            .as_mut()
+            .ok_or(BlockError::NotStarted)?
+            .set_fee_multiplier(multiplier);
+        Ok(())
+    }
+
+    /// End the block started with [`Self::begin_block`] - see [`block`].
+    ///
+    /// Drops the caches and the pre-verified code, so nothing cached during the block leaks into the
+    /// next one. The warmed-up modules are reloaded from the storage.
+    pub fn end_block(&self) -> Result<BlockSummary, BlockError> {
+        let state = self
+            .block
+            .borrow_mut()
+            .take()
+            .ok_or(BlockError::NotStarted)?;
+        self.flush_caches();
+        self.vm.clear_verified_code();
+        Ok(state.summary())
+    }
+
+    /// Drop the loaded code and the struct layouts, reloading the warmed-up modules.
+    fn flush_caches(&self) {
+        self.vm.mark_loader_cache_as_invalid();
+        self.vm.flush_loader_cache_if_invalidated();
+
+        // Modules which fail to load, e.g. the retired ones, are loaded on demand again.
+        for module in self.warmed_modules.borrow().iter() {
+            let _ = self.vm.load_module(module, &self.warehouse);
+        }
+    }
+
     /// Get module binary using the address and the name.
     pub fn get_module(
         &self,
@@ -89,13 +594,27 @@ where
     ) -> Result<Option<ModuleAbi>, Error> {
         if let Some(bytecode) = self.get_module(address, name)? {
             Ok(Some(ModuleAbi::from(
-                CompiledModule::deserialize(&bytecode).map_err(Error::msg)?,
+                CompiledModule::deserialize_with_max_version(&bytecode, MAX_BINARY_FORMAT_VERSION)
+                    .map_err(Error::msg)?,
             )))
         } else {
             Ok(None)
         }
     }
 
+    /// Get the ABIs of all modules published under the address, ordered by the module name.
+    pub fn get_address_abi(&self, address: AccountAddress) -> Result<Vec<ModuleAbi>, Error> {
+        self.warehouse
+            .get_modules(&address)?
+            .iter()
+            .map(|bytecode| {
+                CompiledModule::deserialize_with_max_version(bytecode, MAX_BINARY_FORMAT_VERSION)
+                    .map(ModuleAbi::from)
+                    .map_err(Error::msg)
+            })
+            .collect()
+    }
+
     /// Get resource using an address and a tag.
     // TODO: could we use Identifier and AccountAddress here instead as arguments?
     pub fn get_resource(
@@ -107,6 +626,294 @@ where
         self.warehouse.get_resource(address, &tag)
     }
 
+    /// Get all resources under the address whose struct tag belongs to the module, with the tags
+    /// ordered.
+    ///
+    /// All instantiations of the generic resources are included, so the explorers can list e.g.
+    /// every `BasicCoin` resource of an account without knowing the type arguments upfront.
+    pub fn find_resources(
+        &self,
+        address: &AccountAddress,
+        module_id: &ModuleId,
+    ) -> Result<Vec<(StructTag, Vec<u8>)>, Error> {
+        self.warehouse.get_module_resources(address, module_id)
+    }
+
+    /// Total bytes of the modules and resources stored under the address.
+    ///
+    /// The usage is tracked as the changesets are applied, so it's read without scanning the
+    /// account data. The keys (module names and struct tags) aren't counted.
+    pub fn storage_usage(&self, address: &AccountAddress) -> Result<u64, Error> {
+        self.warehouse.storage_usage(address)
+    }
+
+    /// Get the resource decoded into the canonical value schema, with the field names.
+    pub fn get_resource_value(
+        &self,
+        address: &AccountAddress,
+        tag: &StructTag,
+    ) -> Result<Option<CanonicalValue>, Error> {
+        let Some(blob) = self.warehouse.get_resource(address, tag)? else {
+            return Ok(None);
+        };
+
+        let sess = self.vm.new_session(&self.warehouse);
+        let layout = sess
+            .get_fully_annotated_type_layout(&TypeTag::Struct(Box::new(tag.clone())))
+            .map_err(|err| {
+                anyhow!(
+                    "Failed to load the layout of {}: {:?}",
+                    tag,
+                    err.major_status()
+                )
+            })?;
+        let value = MoveValue::simple_deserialize(&blob, &layout)?;
+
+        Ok(Some(value.into()))
+    }
+
+    /// Get the `std::access_control` resource of the contract module defining the `witness` type.
+    pub fn get_access_control(
+        &self,
+        holder: &AccountAddress,
+        witness: StructTag,
+    ) -> Result<Option<AccessControl>, Error> {
+        let tag = access_control_tag(witness);
+        self.warehouse
+            .get_resource(holder, &tag)?
+            .map(|blob| AccessControl::decode(&blob).map_err(Error::msg))
+            .transpose()
+    }
+
+    /// Get the chain constants written at genesis - see [`genesis`].
+    pub fn get_chain_config(&self) -> Result<Option<ChainConfig>, Error> {
+        self.warehouse
+            .get_resource(&CORE_CODE_ADDRESS, &chain_config_tag())?
+            .map(|blob| ChainConfig::decode(&blob).map_err(Error::msg))
+            .transpose()
+    }
+
+    /// Write the chain constants read by the `std::chain_config` module.
+    pub(crate) fn write_chain_config(&self, config: &ChainConfig) -> Result<(), Error> {
+        let mut changeset = ChangeSet::new();
+        changeset.add_resource_op(
+            CORE_CODE_ADDRESS,
+            chain_config_tag(),
+            Op::New(config.encode()),
+        )?;
+        self.warehouse.apply_changes(changeset)
+    }
+
+    /// Get the block at which the resource expires - see [`expiry`].
+    pub fn get_resource_expiry(&self, address: &AccountAddress, tag: &StructTag) -> Option<u64> {
+        ExpiryRegistry::new(&*self.warehouse).get(&(*address, tag.clone()))
+    }
+
+    /// Remove the resources which expired at the `current_block`.
+    ///
+    /// The earliest expiries are removed first. The sweep visits at most `limit` entries of the
+    /// expiry index, which bounds its cost - the rest is left for the following sweeps. The frozen
+    /// resources and the resources of the frozen accounts - see [`freeze`] - aren't removed and
+    /// keep their expiries for the following sweeps.
+    pub fn sweep_expired(&self, current_block: u64, limit: usize) -> Result<SweepReport, Error> {
+        let registry = ExpiryRegistry::new(&*self.warehouse);
+        let sweep = registry.sweep(current_block, limit);
+        let freeze_registry = FreezeRegistry::new(&*self.warehouse);
+
+        let mut changeset = ChangeSet::new();
+        let mut removed = Vec::new();
+        let mut frozen = Vec::new();
+        for (address, tag) in &sweep.expired {
+            if self.warehouse.get_resource(address, tag)?.is_none() {
+                continue;
+            }
+
+            // The removal is subject to the same freeze check as the transactions.
+            let mut removal = ChangeSet::new();
+            removal.add_resource_op(*address, tag.clone(), Op::Delete)?;
+            if freeze_registry.check(&removal).is_err() {
+                frozen.push((*address, tag.clone()));
+                continue;
+            }
+
+            changeset.add_resource_op(*address, tag.clone(), Op::Delete)?;
+            removed.push((*address, tag.clone()));
+        }
+
+        let freed = self.warehouse.freed_storage(&changeset)?;
+        #[cfg(feature = "std")]
+        let writes = self.subscribers.writes(&changeset);
+        self.warehouse.apply_changes(changeset)?;
+        let complete = sweep.complete;
+        registry.finish(sweep, &frozen);
+        #[cfg(feature = "std")]
+        self.subscribers.notify(&[], writes);
+
+        Ok(SweepReport {
+            removed,
+            freed_bytes: freed.freed_bytes.into(),
+            frozen,
+            complete,
+        })
+    }
+
+    /// Freeze the account, so no transaction can change its resources or modules - see [`freeze`].
+    pub fn freeze_account(&self, _capability: &impl FreezeCapability, address: AccountAddress) {
+        FreezeRegistry::new(&*self.warehouse).set_account(address, true)
+    }
+
+    /// Unfreeze the account frozen with [`Mvm::freeze_account`].
+    pub fn unfreeze_account(&self, _capability: &impl FreezeCapability, address: AccountAddress) {
+        FreezeRegistry::new(&*self.warehouse).set_account(address, false)
+    }
+
+    /// Remove the module from the storage - see [`retirement`].
+    ///
+    /// Fails if any published module depends on the module, any resource of its types exists, its
+    /// account is frozen, or it was published before the module index existed.
+    pub fn retire_module(
+        &self,
+        address: AccountAddress,
+        name: &IdentStr,
+    ) -> Result<(), RetireError> {
+        if FreezeRegistry::new(&*self.warehouse).is_account_frozen(&address) {
+            return Err(RetireError::AccountFrozen);
+        }
+
+        let module = ModuleId::new(address, name.to_owned());
+        if !self.is_published(&module)? {
+            return Err(RetireError::NotFound);
+        }
+
+        let index = ModuleIndex::new(&*self.warehouse);
+        let Some(count) = index.resource_count(&module) else {
+            return Err(RetireError::NotIndexed);
+        };
+        if count > 0 {
+            return Err(RetireError::HasResources(count));
+        }
+
+        let mut dependents = Vec::new();
+        for dependent in index.dependents(&module) {
+            if self.depends_on(&dependent, &module)? {
+                dependents.push(dependent);
+            }
+        }
+        if !dependents.is_empty() {
+            return Err(RetireError::HasDependents(dependents));
+        }
+
+        self.remove_module(&module)?;
+        index.remove(&module);
+        Ok(())
+    }
+
+    /// Remove the module from the storage without any safety checks.
+    ///
+    /// The dependent modules fail to load and the resources of the module types can't be used
+    /// until the module is published again. The module index is kept, so it still counts them.
+    pub fn force_retire_module(
+        &self,
+        _capability: &impl PublishCapability,
+        address: AccountAddress,
+        name: &IdentStr,
+    ) -> Result<(), RetireError> {
+        self.remove_module(&ModuleId::new(address, name.to_owned()))
+    }
+
+    /// Check if the published `dependent` module still depends on the `module`.
+    fn depends_on(&self, dependent: &ModuleId, module: &ModuleId) -> Result<bool, RetireError> {
+        let bytes = self
+            .warehouse
+            .get_module(dependent)
+            .map_err(|_| RetireError::StorageError)?;
+        let Some(bytes) = bytes else {
+            return Ok(false);
+        };
+
+        let dependent =
+            CompiledModule::deserialize_with_max_version(&bytes, MAX_BINARY_FORMAT_VERSION)
+                .map_err(|_| RetireError::StorageError)?;
+        Ok(dependent.immediate_dependencies().contains(module))
+    }
+
+    fn is_published(&self, module: &ModuleId) -> Result<bool, RetireError> {
+        match self.warehouse.get_module(module) {
+            Ok(bytes) => Ok(bytes.is_some()),
+            Err(_) => Err(RetireError::StorageError),
+        }
+    }
+
+    fn remove_module(&self, module: &ModuleId) -> Result<(), RetireError> {
+        if !self.is_published(module)? {
+            return Err(RetireError::NotFound);
+        }
+
+        let mut changeset = ChangeSet::new();
+        changeset
+            .add_module_op(module.clone(), Op::Delete)
+            .map_err(|_| RetireError::StorageError)?;
+        #[cfg(feature = "std")]
+        let writes = self.subscribers.writes(&changeset);
+        self.warehouse
+            .apply_changes(changeset)
+            .map_err(|_| RetireError::StorageError)?;
+        #[cfg(feature = "std")]
+        self.subscribers.notify(&[], writes);
+
+        // The loader must not serve the retired module from the cache.
+        self.vm.mark_loader_cache_as_invalid();
+        self.vm.flush_loader_cache_if_invalidated();
+        Ok(())
+    }
+
+    /// Check whether the account is frozen.
+    pub fn is_account_frozen(&self, address: &AccountAddress) -> bool {
+        FreezeRegistry::new(&*self.warehouse).is_account_frozen(address)
+    }
+
+    /// Freeze the resource, so no transaction can write, move or delete it - see [`freeze`].
+    pub fn freeze_resource(
+        &self,
+        _capability: &impl FreezeCapability,
+        address: AccountAddress,
+        tag: StructTag,
+    ) {
+        FreezeRegistry::new(&*self.warehouse).set_resource((address, tag), true)
+    }
+
+    /// Unfreeze the resource frozen with [`Mvm::freeze_resource`].
+    pub fn unfreeze_resource(
+        &self,
+        _capability: &impl FreezeCapability,
+        address: AccountAddress,
+        tag: StructTag,
+    ) {
+        FreezeRegistry::new(&*self.warehouse).set_resource((address, tag), false)
+    }
+
+    /// Check whether the resource is frozen. Resources of the frozen accounts aren't reported.
+    pub fn is_resource_frozen(&self, address: &AccountAddress, tag: &StructTag) -> bool {
+        FreezeRegistry::new(&*self.warehouse).is_resource_frozen(&(*address, tag.clone()))
+    }
+
+    /// Sequence number of the next emitted event - see [`MoveEvent::sequence_number`].
+    ///
+    /// The consumers which have seen all events up to this number are up to date.
+    pub fn next_event_sequence(&self) -> u64 {
+        EventSequence::new(&*self.warehouse).next()
+    }
+
+    /// Get the execution statistics of the module.
+    pub fn get_module_stats(&self, module: &ModuleId) -> Option<ModuleStats> {
+        ModuleStatsRegistry::new(&*self.warehouse).get(module)
+    }
+
+    /// Reset the execution statistics of the module.
+    pub fn reset_module_stats(&self, module: &ModuleId) {
+        ModuleStatsRegistry::new(&*self.warehouse).reset(module)
+    }
+
     /// Publish module into the storage. Module is published under the given address.
     pub fn publish_module(
         &self,
@@ -114,144 +921,1981 @@ where
         address: AccountAddress,
         gas: GasStrategy,
     ) -> VmResult {
-        let mut gas_handler = GasHandler::new(gas);
-
-        // MoveVM by default doesn't charge gas for publishing, so we need to do it manually here.
-        if let Err(result) = gas_handler.charge_publishing_to_storage(module.len()) {
+        if let Err(result) = self.check_publisher(address) {
             return result;
         }
 
-        let mut sess = self.vm.new_session(&self.warehouse);
-        let result = sess.publish_module(module.to_vec(), address, &mut gas_handler.status);
+        self.publish_module_with_compat(module, address, gas, Compatibility::full_check())
+    }
 
-        self.handle_result(result.and_then(|_| sess.finish()), gas_handler)
+    /// Publish module under any address, including the reserved ones.
+    pub fn publish_module_as(
+        &self,
+        _capability: &impl PublishCapability,
+        module: &[u8],
+        address: AccountAddress,
+        gas: GasStrategy,
+    ) -> VmResult {
+        self.publish_module_with_compat(module, address, gas, Compatibility::full_check())
     }
 
-    /// Publish a bundle of modules into the storage under the given address.
-    pub fn publish_module_bundle(
+    #[cfg(feature = "scripts")]
+    /// Publish a module upgrade which can change the layout of the existing structs.
+    ///
+    /// The resources stored with the old layouts can't be loaded until they are migrated with
+    /// [`Mvm::migrate_resources`]. All other compatibility checks still apply.
+    pub fn publish_module_with_layout_changes(
         &self,
-        bundle: &[u8],
+        module: &[u8],
         address: AccountAddress,
         gas: GasStrategy,
     ) -> VmResult {
-        let mut gas_handler = GasHandler::new(gas);
+        if let Err(result) = self.check_publisher(address) {
+            return result;
+        }
 
-        let modules = ModuleBundle::try_from(bundle)
-            .map_err(|e| VmResult::new(StatusCode::UNKNOWN_MODULE, Some(e.to_string()), 0));
+        let compat = Compatibility::new(true, false, true);
+        self.publish_module_with_compat(module, address, gas, compat)
+    }
 
-        let modules = match modules {
-            Ok(modules) => modules.into_inner(),
-            Err(e) => return e,
-        };
+    fn publish_module_with_compat(
+        &self,
+        module: &[u8],
+        address: AccountAddress,
+        gas: GasStrategy,
+        compat: Compatibility,
+    ) -> VmResult {
+        if let Err(result) = self.check_reentrancy() {
+            return result;
+        }
+        if let Err(result) = self.check_identifier_policy(core::iter::once(module)) {
+            return result;
+        }
+        if let Err(result) = check_dependencies(core::iter::once(module)) {
+            return result;
+        }
+
+        let mut gas_handler = GasHandler::new(gas);
 
         // MoveVM by default doesn't charge gas for publishing, so we need to do it manually here.
-        if let Err(result) = gas_handler.charge_publishing_to_storage(bundle.len()) {
+        // The malformed modules are rejected by the MoveVM right after, only their length counts.
+        let complexity = module_publish_complexity(module).unwrap_or_default();
+        if let Err(mut result) = gas_handler.charge_publishing_to_storage(module.len(), &complexity)
+        {
+            result.fee = self.fee_for(result.gas_used);
             return result;
         }
 
         let mut sess = self.vm.new_session(&self.warehouse);
-        let result = sess.publish_module_bundle(modules, address, &mut gas_handler.status);
+        let result = sess.publish_module_bundle_with_compat_config(
+            alloc::vec![module.to_vec()],
+            address,
+            &mut gas_handler.status,
+            compat,
+        );
+        let result = result
+            .and_then(|_| sess.finish())
+            .map(|(changeset, events)| (changeset, events, ExpiryChanges::new()));
+        let result = self.handle_result(result, gas_handler);
+
+        // Module upgrades invalidate the loader cache, which can only be flushed once the session
+        // is gone.
+        self.vm.flush_loader_cache_if_invalidated();
 
-        self.handle_result(result.and_then(|_| sess.finish()), gas_handler)
+        result
     }
 
-    /// Execute script using the given arguments (args).
-    pub fn execute_script(
+    /// Publish a module compiled with the 16-byte addresses - see [`legacy`].
+    ///
+    /// The module is re-encoded for the configured address length before publishing, so the
+    /// address should be the upgraded legacy address, and the re-encoded module size is charged.
+    ///
+    /// [`legacy`]: move_vm_backend_common::legacy
+    pub fn publish_legacy_module(
         &self,
-        script: &[u8],
-        type_args: Vec<TypeTag>,
-        args: Vec<&[u8]>,
+        module: &[u8],
+        address: AccountAddress,
         gas: GasStrategy,
     ) -> VmResult {
-        self.execute_script_worker(
-            Transaction {
-                call: Call::Script {
-                    code: script.to_vec(),
-                },
-                type_args,
-                args: args.iter().map(|x| x.to_vec()).collect(),
-            },
-            gas,
-        )
+        match upgrade_legacy_binary(module) {
+            Ok(module) => self.publish_module(&module, address, gas),
+            Err(e) => legacy_binary_error(e),
+        }
     }
 
-    /// Execute function from module using the given arguments (args).
-    pub fn execute_function(
+    /// Publish a bundle of modules compiled with the 16-byte addresses - see [`legacy`].
+    ///
+    /// [`legacy`]: move_vm_backend_common::legacy
+    pub fn publish_legacy_module_bundle(
         &self,
-        mod_address: AccountAddress,
-        mod_name: Identifier,
-        func_name: Identifier,
-        type_args: Vec<TypeTag>,
-        args: Vec<&[u8]>,
+        bundle: &[u8],
+        address: AccountAddress,
         gas: GasStrategy,
     ) -> VmResult {
-        self.execute_script_worker(
-            Transaction {
-                call: Call::ScriptFunction {
-                    mod_address,
-                    mod_name,
-                    func_name,
-                },
-                type_args,
-                args: args.iter().map(|x| x.to_vec()).collect(),
-            },
-            gas,
-        )
-    }
+        let modules = match ModuleBundle::try_from(bundle) {
+            Ok(modules) => modules.into_inner(),
+            Err(e) => return VmResult::new(StatusCode::UNKNOWN_MODULE, Some(e.to_string()), 0),
+        };
 
-    /// Execute script using the given arguments (args).
-    fn execute_script_worker(&self, transaction: Transaction, gas: GasStrategy) -> VmResult {
-        let mut gas_handler = GasHandler::new(gas);
-        let mut sess = self.vm.new_session(&self.warehouse);
+        let modules = match modules
+            .iter()
+            .map(|module| upgrade_legacy_binary(module))
+            .collect::<Result<_, _>>()
+        {
+            Ok(modules) => modules,
+            Err(e) => return legacy_binary_error(e),
+        };
+
+        match ModuleBundle::new(modules).encode() {
+            Ok(bundle) => self.publish_module_bundle(&bundle, address, gas),
+            Err(e) => VmResult::new(StatusCode::UNKNOWN_MODULE, Some(e.to_string()), 0),
+        }
+    }
+
+    /// Publish a bundle of modules into the storage under the given address.
+    pub fn publish_module_bundle(
+        &self,
+        bundle: &[u8],
+        address: AccountAddress,
+        gas: GasStrategy,
+    ) -> VmResult {
+        if let Err(result) = self.check_publisher(address) {
+            return result;
+        }
+
+        self.publish_module_bundle_unchecked(bundle, &[address], None, gas)
+    }
+
+    /// Publish a bundle whose modules are published under several addresses, all or nothing.
+    ///
+    /// The embedder authorizes the addresses, e.g. by checking a signature of each address owner.
+    /// Each module must be published under one of the authorized addresses, otherwise the bundle
+    /// is rejected with the `MODULE_ADDRESS_DOES_NOT_MATCH_SENDER` status code.
+    pub fn publish_multi_address_bundle(
+        &self,
+        bundle: &[u8],
+        authorized: &[AccountAddress],
+        gas: GasStrategy,
+    ) -> VmResult {
+        for address in authorized {
+            if let Err(result) = self.check_publisher(*address) {
+                return result;
+            }
+        }
+
+        self.publish_module_bundle_unchecked(bundle, authorized, None, gas)
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Publish a bundle of modules and run the init script right after in the same session.
+    ///
+    /// The script can e.g. initialize the configuration resources of the published modules. The
+    /// publishing is rolled back if the script fails, so the modules never end up on-chain without
+    /// their initial state. The gas is shared by the publishing and the script.
+    pub fn publish_module_bundle_with_init(
+        &self,
+        bundle: &[u8],
+        address: AccountAddress,
+        script: &[u8],
+        type_args: Vec<TypeTag>,
+        args: Vec<&[u8]>,
+        gas: GasStrategy,
+    ) -> VmResult {
+        if let Err(result) = self.check_publisher(address) {
+            return result;
+        }
+
+        let init = Transaction {
+            call: Call::Script {
+                code: script.to_vec(),
+            },
+            type_args,
+            args: args.iter().map(|x| x.to_vec()).collect(),
+        };
+        self.publish_module_bundle_unchecked(bundle, &[address], Some(init), gas)
+    }
+
+    /// Publish a bundle of modules under any address, including the reserved ones.
+    pub fn publish_module_bundle_as(
+        &self,
+        _capability: &impl PublishCapability,
+        bundle: &[u8],
+        address: AccountAddress,
+        gas: GasStrategy,
+    ) -> VmResult {
+        self.publish_module_bundle_unchecked(bundle, &[address], None, gas)
+    }
+
+    /// Publish the bundle, whose modules may be published under any of the senders' addresses.
+    fn publish_module_bundle_unchecked(
+        &self,
+        bundle: &[u8],
+        senders: &[AccountAddress],
+        init: Option<Transaction>,
+        gas: GasStrategy,
+    ) -> VmResult {
+        if let Err(result) = self.check_reentrancy() {
+            return result;
+        }
+        if let Some(init) = &init {
+            #[cfg(feature = "scripts")]
+            if let Err(result) = self.check_script_allowlist(init) {
+                return result;
+            }
+            if let Err(result) = self.check_args(init) {
+                return result;
+            }
+        }
+
+        let mut gas_handler = match init {
+            Some(_) => GasHandler::for_execution(gas, self.config, &self.call_gas_limits),
+            None => GasHandler::new(gas),
+        };
+
+        let modules = ModuleBundle::try_from(bundle)
+            .map_err(|e| VmResult::new(StatusCode::UNKNOWN_MODULE, Some(e.to_string()), 0));
+
+        let modules = match modules {
+            Ok(modules) => modules.into_inner(),
+            Err(e) => return e,
+        };
+
+        if let Err(result) = self.check_identifier_policy(modules.iter().map(Vec::as_slice)) {
+            return result;
+        }
+        if let Err(result) = check_dependencies(modules.iter().map(Vec::as_slice)) {
+            return result;
+        }
+
+        // MoveVM by default doesn't charge gas for publishing, so we need to do it manually here.
+        let complexity = bundle_publish_complexity(bundle).unwrap_or_default();
+        if let Err(mut result) = gas_handler.charge_publishing_to_storage(bundle.len(), &complexity)
+        {
+            result.fee = self.fee_for(result.gas_used);
+            return result;
+        }
+
+        let Some(init) = init else {
+            let mut sess = self.vm.new_session(&self.warehouse);
+            let result = sess
+                .publish_module_bundle_for_senders(modules, senders, &mut gas_handler.status)
+                .and_then(|_| sess.finish())
+                .map(|(changeset, events)| (changeset, events, ExpiryChanges::new()));
+
+            return self.handle_result(result, gas_handler);
+        };
+
+        let signers = self.transaction_signers(&init);
+        let result = publish_with_init(
+            &self.vm,
+            &self.warehouse,
+            modules,
+            senders,
+            init,
+            &mut gas_handler,
+        );
+        let result = self.check_resource_acl(result, &signers);
+        let result = self.handle_result(result, gas_handler);
+
+        // The loader could have cached the modules for the init script, which must be dropped
+        // when the publishing is rolled back.
+        if result.is_err() {
+            self.vm.mark_loader_cache_as_invalid();
+            self.vm.flush_loader_cache_if_invalidated();
+        }
+
+        result
+    }
+
+    /// Returns the error result if the address is reserved for the privileged publishing.
+    fn check_publisher(&self, address: AccountAddress) -> Result<(), VmResult> {
+        if self.reserved_addresses.contains(&address) {
+            return Err(VmResult::new(
+                StatusCode::INVALID_MODULE_PUBLISHER,
+                Some(format!("Address {address} is reserved")),
+                0,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the error result if any of the modules violates the identifier policy.
+    ///
+    /// Modules which fail to deserialize are left for the MoveVM to reject.
+    fn check_identifier_policy<'m>(
+        &self,
+        modules: impl Iterator<Item = &'m [u8]>,
+    ) -> Result<(), VmResult> {
+        let Some(policy) = &self.identifier_policy else {
+            return Ok(());
+        };
+
+        for module in modules {
+            identifier_policy::check_module_bytes(policy.as_ref(), module)
+                .map_err(|msg| VmResult::new(StatusCode::CONSTRAINT_NOT_SATISFIED, Some(msg), 0))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Execute script using the given arguments (args).
+    pub fn execute_script(
+        &self,
+        script: &[u8],
+        type_args: Vec<TypeTag>,
+        args: Vec<&[u8]>,
+        gas: GasStrategy,
+    ) -> VmResult {
+        self.execute_script_worker(
+            Transaction {
+                call: Call::Script {
+                    code: script.to_vec(),
+                },
+                type_args,
+                args: args.iter().map(|x| x.to_vec()).collect(),
+            },
+            gas,
+        )
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Execute script compiled with the 16-byte addresses - see [`legacy`].
+    ///
+    /// The address arguments must be converted with the [`LegacyAddressCodec`] by the caller.
+    ///
+    /// [`legacy`]: move_vm_backend_common::legacy
+    /// [`LegacyAddressCodec`]: move_vm_backend_common::legacy::LegacyAddressCodec
+    pub fn execute_legacy_script(
+        &self,
+        script: &[u8],
+        type_args: Vec<TypeTag>,
+        args: Vec<&[u8]>,
+        gas: GasStrategy,
+    ) -> VmResult {
+        match upgrade_legacy_binary(script) {
+            Ok(script) => self.execute_script(&script, type_args, args, gas),
+            Err(e) => legacy_binary_error(e),
+        }
+    }
+
+    /// Execute function from module using the given arguments (args).
+    pub fn execute_function(
+        &self,
+        mod_address: AccountAddress,
+        mod_name: Identifier,
+        func_name: Identifier,
+        type_args: Vec<TypeTag>,
+        args: Vec<&[u8]>,
+        gas: GasStrategy,
+    ) -> VmResult {
+        self.execute_script_worker(
+            Transaction {
+                call: Call::ScriptFunction {
+                    mod_address,
+                    mod_name,
+                    func_name,
+                },
+                type_args,
+                args: args.iter().map(|x| x.to_vec()).collect(),
+            },
+            gas,
+        )
+    }
+
+    /// Execute the system call function without any gas limit.
+    ///
+    /// Only the functions marked with [`Mvm::set_system_calls`] can be executed. No fees are paid
+    /// for the system calls, so the fee hook isn't invoked.
+    pub fn execute_system_call(
+        &self,
+        _capability: &impl SystemCallCapability,
+        module: ModuleId,
+        func_name: Identifier,
+        type_args: Vec<TypeTag>,
+        args: Vec<&[u8]>,
+    ) -> VmResult {
+        if !self.is_system_call(&module, &func_name) {
+            return VmResult::new(
+                StatusCode::NO_ACCOUNT_ROLE,
+                Some(format!("{module}::{func_name} is not a system call")),
+                0,
+            );
+        }
+
+        let transaction = Transaction {
+            call: Call::ScriptFunction {
+                mod_address: *module.address(),
+                mod_name: module.name().to_owned(),
+                func_name,
+            },
+            type_args,
+            args: args.iter().map(|x| x.to_vec()).collect(),
+        };
+        if let Err(result) = self
+            .check_reentrancy()
+            .and_then(|_| self.check_args(&transaction))
+        {
+            return result;
+        }
+
+        let result = self.execute_unsponsored(transaction, GasStrategy::Unmetered, None);
+        self.record_module_stats(Some(&module), &result, false);
+        result
+    }
+
+    /// Execute the transaction with the fees paid by the `fee_payer` account instead of the signers.
+    ///
+    /// The signers still authorize the transaction, while the fee for the whole gas limit is
+    /// withdrawn from the fee payer with [`HostBindings::withdraw_fee`] before the execution and
+    /// the fee for the unused gas is refunded afterwards with [`HostBindings::refund_fee`]. The
+    /// transaction is rejected with the `INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE` status code if
+    /// the fee payer can't afford the fee. A failed refund doesn't change the outcome of the
+    /// executed transaction - it's reported in [`VmResult::refund_error`] instead.
+    ///
+    /// Overrides the fee payer set with [`Mvm::set_fee_payer`]. The fee hook isn't invoked, since
+    /// the fee payer pays the fees already. Nothing is withdrawn for the dry runs and the unmetered
+    /// executions.
+    pub fn execute_with_fee_payer(
+        &self,
+        transaction: Transaction,
+        fee_payer: AccountAddress,
+        gas: GasStrategy,
+    ) -> VmResult {
+        self.execute_paid_transaction(transaction, gas, Some(fee_payer))
+    }
+
+    /// Execute the block of script transactions with the optimistic conflict detection.
+    ///
+    /// The results and the final state are identical to executing the transactions one by one.
+    #[cfg(all(feature = "scripts", not(feature = "parallel")))]
+    pub fn execute_block(
+        &self,
+        transactions: Vec<ScriptTransaction>,
+        gas: GasStrategy,
+    ) -> Vec<VmResult> {
+        let transactions: Vec<_> = transactions.into_iter().map(Transaction::from).collect();
+        if self.fee_hook.is_some() || self.fee_payer.is_some() {
+            return self.execute_sequentially(transactions, gas);
+        }
+
+        let speculations = transactions
+            .iter()
+            .map(|tx| {
+                parallel::speculate(
+                    &self.vm,
+                    &self.warehouse,
+                    tx.clone(),
+                    gas,
+                    self.config,
+                    &self.call_gas_limits,
+                )
+            })
+            .collect();
+
+        self.commit_block(transactions, speculations, gas)
+    }
+
+    /// Execute the block of script transactions with the optimistic conflict detection.
+    ///
+    /// The transactions are speculatively executed on multiple threads. The results and the
+    /// final state are identical to executing the transactions one by one.
+    #[cfg(all(feature = "scripts", feature = "parallel"))]
+    pub fn execute_block(
+        &self,
+        transactions: Vec<ScriptTransaction>,
+        gas: GasStrategy,
+    ) -> Vec<VmResult>
+    where
+        S: Sync,
+        H: Sync,
+    {
+        let transactions: Vec<_> = transactions.into_iter().map(Transaction::from).collect();
+        if self.fee_hook.is_some() || self.fee_payer.is_some() {
+            return self.execute_sequentially(transactions, gas);
+        }
+
+        let speculations = parallel::speculate_in_parallel(
+            &self.warehouse,
+            &transactions,
+            gas,
+            self.config,
+            &self.call_gas_limits,
+            &self.gated_natives,
+            &self.interrupt,
+        );
+
+        self.commit_block(transactions, speculations, gas)
+    }
+
+    /// Hand the pre-verified code of the upcoming transactions to the MoveVM.
+    ///
+    /// The scripts and modules with the given hashes skip the deserialization and the bytecode
+    /// verification when executed or published by this instance - only their dependencies are
+    /// loaded and linked. Failed and unknown hashes are ignored.
+    #[cfg(feature = "std")]
+    pub fn preload_verified<'a>(
+        &self,
+        queue: &PreverificationQueue,
+        hashes: impl IntoIterator<Item = &'a CodeHash>,
+    ) {
+        for (loader_key, code) in queue.verified(hashes) {
+            match code {
+                VerifiedCode::Script(script) => {
+                    self.vm.add_verified_script(loader_key, (*script).clone())
+                }
+                VerifiedCode::Module(module) => self.vm.add_verified_module((*module).clone()),
+            }
+        }
+    }
+
+    /// Load, verify and cache the modules ahead of the first transactions using them - see
+    /// [`warm_up`].
+    ///
+    /// The modules stay cached across the blocks - [`Self::begin_block`] and
+    /// [`Self::end_block`] reload them after dropping the caches.
+    pub fn warm_up(&self, modules: &[ModuleId]) -> WarmUpReport {
+        self.warm_up_with_progress(modules, |_| ())
+    }
+
+    /// Same as [`Mvm::warm_up`], reporting the progress after each listed module.
+    pub fn warm_up_with_progress(
+        &self,
+        modules: &[ModuleId],
+        mut progress: impl FnMut(WarmUpProgress),
+    ) -> WarmUpReport {
+        let mut report = WarmUpReport::default();
+        let cached_before = self.vm.cached_module_count();
+
+        for (index, module) in modules.iter().enumerate() {
+            let outcome = if self.vm.is_module_cached(module) {
+                WarmUpOutcome::Cached
+            } else {
+                match self.vm.load_module(module, &self.warehouse) {
+                    Ok(_) => WarmUpOutcome::Loaded,
+                    Err(err) => WarmUpOutcome::Failed(err.major_status()),
+                }
+            };
+
+            if !matches!(outcome, WarmUpOutcome::Failed(_)) {
+                self.warmed_modules.borrow_mut().insert(module.clone());
+            }
+            progress(WarmUpProgress {
+                module,
+                outcome,
+                done: index + 1,
+                total: modules.len(),
+            });
+            report.record(module.clone(), outcome);
+        }
+
+        report.cached_modules = self.vm.cached_module_count();
+        report.dependencies = report
+            .cached_modules
+            .saturating_sub(cached_before + report.loaded.len());
+        report
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Analyze which resources the script may read or write when executed with the type arguments.
+    ///
+    /// The footprint can be used to pre-declare the storage access of the transaction.
+    pub fn script_storage_footprint(
+        &self,
+        script: &[u8],
+        type_args: &[TypeTag],
+    ) -> Result<StorageFootprint, Error> {
+        analyze_script_footprint(script, type_args, |module_id| {
+            self.load_compiled_module(module_id)
+        })
+        .map_err(Error::msg)
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Lint the script for the common mistakes - see [`script_lint`].
+    ///
+    /// The warnings don't affect the execution, the script is still validated by the verifier.
+    pub fn lint_script(&self, script: &[u8]) -> Result<Vec<LintWarning>, Error> {
+        script_lint::lint_script_bytes(script)
+            .map_err(|status| anyhow!("Failed to deserialize the script: {:?}", status))
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Dry-run the script and report the lint warnings next to the execution result.
+    ///
+    /// The storage is left untouched, as with [`GasStrategy::DryRun`].
+    pub fn dry_run_script(
+        &self,
+        script: &[u8],
+        type_args: Vec<TypeTag>,
+        args: Vec<&[u8]>,
+    ) -> DryRunReport {
+        let result = self.execute_script(script, type_args, args, GasStrategy::DryRun);
+        let warnings = script_lint::lint_script_bytes(script).unwrap_or_default();
+        DryRunReport { result, warnings }
+    }
+
+    /// Validate the call against the on-chain module ABI and produce the entry function inputs.
+    pub fn build_call(&self, builder: CallBuilder) -> Result<EntryCall, Error> {
+        let module_id = builder.module_id().map_err(Error::msg)?;
+        let abi = self
+            .get_module_abi(*module_id.address(), module_id.name().as_str())?
+            .ok_or_else(|| anyhow!("Module {} not found", module_id))?;
+
+        builder.build(&abi).map_err(Error::msg)
+    }
+
+    /// Execute the entry function call produced by the [`CallBuilder`].
+    pub fn execute_call(&self, call: EntryCall, gas: GasStrategy) -> VmResult {
+        self.execute_script_worker(call.into(), gas)
+    }
+
+    /// Execute the entry function calls as a single atomic transaction.
+    ///
+    /// The calls run in order in one session with one gas budget, and each call sees the effects
+    /// of the previous ones. If any call fails, the effects of all calls are dropped and the
+    /// result names the failed call in [`VmResult::failed_call`]. The fee hook is invoked once for
+    /// the whole sequence, with the signers of all calls.
+    pub fn execute_calls(&self, calls: Vec<EntryCall>, gas: GasStrategy) -> VmResult {
+        let calls = calls.into_iter().map(ComposedCall::from).collect();
+        self.execute_call_sequence_worker(calls, gas)
+    }
+
+    /// Validate the call against the on-chain module ABI and append it to the composition.
+    ///
+    /// Returns the index of the call in the composition.
+    pub fn compose_call(
+        &self,
+        composition: &mut Composition,
+        builder: CallBuilder,
+    ) -> Result<usize, Error> {
+        let module_id = builder.module_id().map_err(Error::msg)?;
+        let abi = self
+            .get_module_abi(*module_id.address(), module_id.name().as_str())?
+            .ok_or_else(|| anyhow!("Module {} not found", module_id))?;
+
+        composition.add(builder, &abi).map_err(Error::msg)
+    }
+
+    /// Execute the composed calls as a single atomic transaction.
+    ///
+    /// Same as [`Mvm::execute_calls`], but the return values of the calls are passed as the
+    /// arguments of the later calls where the composition refers to them.
+    pub fn execute_composition(&self, composition: Composition, gas: GasStrategy) -> VmResult {
+        self.execute_call_sequence_worker(composition.into_calls(), gas)
+    }
+
+    /// Execute the calls as a single atomic transaction.
+    fn execute_call_sequence_worker(&self, calls: Vec<ComposedCall>, gas: GasStrategy) -> VmResult {
+        // The return values are produced on-chain, so only the values provided by the caller
+        // are checked before the execution.
+        let transactions: Vec<Transaction> = calls
+            .iter()
+            .map(|call| {
+                let args = call
+                    .args
+                    .iter()
+                    .map(|arg| match arg {
+                        CallArg::Value(value) => value.clone(),
+                        CallArg::Result { .. } => Vec::new(),
+                    })
+                    .collect();
+                Transaction::composed(call.clone(), args)
+            })
+            .collect();
+
+        if let Err(result) = self.check_reentrancy() {
+            return result;
+        }
+        if let Err(result) = transactions
+            .iter()
+            .try_for_each(|transaction| self.check_args(transaction))
+        {
+            return result;
+        }
+
+        let signers: BTreeSet<_> = transactions
+            .iter()
+            .flat_map(|transaction| self.transaction_signers(transaction))
+            .collect();
+        let withdrawn_fee = match self.fee_payer {
+            Some(fee_payer) => match self.withdraw_payer_fee(fee_payer, gas) {
+                Ok(fee) => Some(fee),
+                Err(rejection) => return rejection,
+            },
+            None => None,
+        };
+        let fee_result = match (&self.fee_hook, self.fee_payer) {
+            (Some(hook), None) => match self.pay_fees(hook, &signers, gas) {
+                Ok(fee_result) => Some(fee_result),
+                Err(rejection) => return rejection,
+            },
+            _ => None,
+        };
+
+        let mut gas_handler = GasHandler::for_execution(gas, self.config, &self.call_gas_limits);
+        gas_handler.fee_payer = self.fee_payer;
+        let mut failed_call = None;
+        let result = execute_call_sequence(&self.vm, &self.warehouse, calls, &mut gas_handler)
+            .map_err(|(index, err)| {
+                failed_call = index;
+                err
+            });
+        let result = self.check_resource_acl(result, &signers);
+
+        let mut result = self.handle_result(result, gas_handler);
+        result.failed_call = failed_call;
+        if let (Some(fee_payer), Some(withdrawn)) = (self.fee_payer, withdrawn_fee) {
+            self.refund_payer_fee(fee_payer, withdrawn, &mut result);
+        }
+        if let Some(fee_result) = fee_result {
+            result.sponsored = true;
+            result.gas_used = result.gas_used.saturating_add(fee_result.gas_used);
+            result.fee = self.fee_for(result.gas_used);
+            result.events.splice(0..0, fee_result.events);
+        }
+        result
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Register the multisig account which is approved by `threshold` of its `members`.
+    ///
+    /// Returns the address of the account, which is derived from the members and the threshold.
+    /// Registering the same account again has no effect.
+    pub fn register_multisig_account(
+        &self,
+        members: BTreeSet<AccountAddress>,
+        threshold: usize,
+    ) -> Result<AccountAddress, MultisigError> {
+        let account = MultisigAccount::new(members, threshold)?;
+        MultisigAccounts::new(&*self.warehouse).set(&account);
+
+        Ok(account.address())
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Get the registered multisig account for the given address.
+    pub fn get_multisig_account(&self, address: &AccountAddress) -> Option<MultisigAccount> {
+        MultisigAccounts::new(&*self.warehouse).get(address)
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Submit a script transaction which is executed once all its signers approve it.
+    ///
+    /// The transaction is an encoded [`ScriptTransaction`] and the signers are read from the
+    /// beginning of its argument list. A signer which is a registered multisig account is approved
+    /// by the threshold of its members - see [`Mvm::register_multisig_account`]. Returns the hash
+    /// under which the script is pending.
+    pub fn submit_multisig_script(&self, transaction: &[u8]) -> Result<ScriptHash, MultisigError> {
+        let multisig_accounts = MultisigAccounts::new(&*self.warehouse);
+        let pending = PendingScript::new(transaction, |signer| multisig_accounts.get(signer))?;
+        let hash = script_hash(transaction);
+
+        let pending_scripts = PendingScripts::new(&*self.warehouse);
+        if pending_scripts.get(&hash).is_some() {
+            return Err(MultisigError::AlreadyPending);
+        }
+        pending_scripts.set(&hash, &pending);
+
+        Ok(hash)
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Approve the pending script on behalf of one of its signers or of a multisig signer member.
+    ///
+    /// The final approval executes the script with the given gas strategy.
+    pub fn approve_multisig_script(
+        &self,
+        hash: &ScriptHash,
+        approver: AccountAddress,
+        gas: GasStrategy,
+    ) -> Result<MultisigStatus, MultisigError> {
+        let pending_scripts = PendingScripts::new(&*self.warehouse);
+        let mut pending = pending_scripts.get(hash).ok_or(MultisigError::NotFound)?;
+
+        pending.approve(approver)?;
+
+        if !pending.is_approved() {
+            pending_scripts.set(hash, &pending);
+            return Ok(MultisigStatus::Pending {
+                remaining_approvals: pending.remaining_approvals(),
+            });
+        }
+
+        pending_scripts.remove(hash);
+
+        // The transaction was already decoded once the script was submitted.
+        let script = ScriptTransaction::try_from(pending.transaction.as_slice())
+            .map_err(|_| MultisigError::InvalidTransaction)?;
+
+        let result = self.execute_script_worker(script.into(), gas);
+
+        Ok(MultisigStatus::Executed(result))
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Get the pending script for the given hash.
+    pub fn get_multisig_script(&self, hash: &ScriptHash) -> Option<PendingScript> {
+        PendingScripts::new(&*self.warehouse).get(hash)
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Discard the pending script for the given hash without executing it.
+    pub fn discard_multisig_script(&self, hash: &ScriptHash) -> Result<(), MultisigError> {
+        let pending_scripts = PendingScripts::new(&*self.warehouse);
+        pending_scripts.get(hash).ok_or(MultisigError::NotFound)?;
+        pending_scripts.remove(hash);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Enable or disable the script allowlist policy.
+    ///
+    /// Once enabled, only the scripts in the allowlist can be executed.
+    pub fn set_script_allowlist_enabled(&self, enabled: bool) {
+        ScriptAllowlist::new(&*self.warehouse).set_enabled(enabled);
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Check if the script allowlist policy is enabled.
+    pub fn is_script_allowlist_enabled(&self) -> bool {
+        ScriptAllowlist::new(&*self.warehouse).is_enabled()
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Add the script hash to the allowlist.
+    pub fn allow_script(&self, hash: &AllowedScriptHash) {
+        ScriptAllowlist::new(&*self.warehouse).add(hash);
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Remove the script hash from the allowlist.
+    pub fn disallow_script(&self, hash: &AllowedScriptHash) {
+        ScriptAllowlist::new(&*self.warehouse).remove(hash);
+    }
 
-        let result = match transaction.call {
-            Call::Script { code } => sess.execute_script(
-                code,
-                transaction.type_args,
-                transaction.args,
-                &mut gas_handler.status,
-            ),
+    #[cfg(feature = "scripts")]
+    /// Check if the script can be executed under the current allowlist policy.
+    pub fn is_script_allowed(&self, bytecode: &[u8]) -> bool {
+        ScriptAllowlist::new(&*self.warehouse).is_allowed(bytecode)
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Register the script template under the name, replacing the previous one - see [`templates`].
+    ///
+    /// The template is rejected if its parameters don't match the script signature.
+    pub fn register_script_template(
+        &self,
+        name: &IdentStr,
+        template: &ScriptTemplate,
+    ) -> Result<(), TemplateError> {
+        template.validate()?;
+        TemplateRegistry::new(&*self.warehouse).set(name, template);
+        Ok(())
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Remove the script template registered under the name.
+    pub fn remove_script_template(&self, name: &IdentStr) {
+        TemplateRegistry::new(&*self.warehouse).remove(name);
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Get the script template registered under the name.
+    pub fn get_script_template(&self, name: &IdentStr) -> Option<ScriptTemplate> {
+        TemplateRegistry::new(&*self.warehouse).get(name)
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Execute the script template with the arguments bound to its parameters.
+    ///
+    /// The arguments are checked against the template parameters before the execution, so the
+    /// binding errors are returned instead of the execution result.
+    pub fn execute_template(
+        &self,
+        name: &IdentStr,
+        args: &[TemplateArg],
+        gas: GasStrategy,
+    ) -> Result<VmResult, TemplateError> {
+        let template = self
+            .get_script_template(name)
+            .ok_or(TemplateError::NotFound)?;
+        let args = template.bind(args)?;
+
+        Ok(self.execute_script(
+            &template.bytecode,
+            Vec::new(),
+            args.iter().map(Vec::as_slice).collect(),
+            gas,
+        ))
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Get the layout of the resource struct as currently published.
+    ///
+    /// The [`migration::layout_hash`] of the layout identifies the migrations towards it.
+    pub fn resource_layout(&self, tag: &StructTag) -> Result<MoveTypeLayout, Error> {
+        let sess = self.vm.new_session(&self.warehouse);
+        sess.get_type_layout(&TypeTag::Struct(Box::new(tag.clone())))
+            .map_err(|err| {
+                anyhow!(
+                    "Failed to load the layout of {}: {:?}",
+                    tag,
+                    err.major_status()
+                )
+            })
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Register the migration of the resources into the layout with the given hash.
+    ///
+    /// The migration replaces the one registered for the same pair of layouts.
+    pub fn register_migration(&self, new_layout: &LayoutHash, migration: Migration) {
+        MigrationRegistry::new(&*self.warehouse).add(new_layout, migration);
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Remove the migration between the two layouts.
+    pub fn unregister_migration(&self, old_layout: &LayoutHash, new_layout: &LayoutHash) {
+        MigrationRegistry::new(&*self.warehouse).remove(old_layout, new_layout);
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Migrate the resources stored under the accounts to the current layout of the struct.
+    ///
+    /// The migration scripts run with special privileges: they receive the signer of the resource
+    /// owner without the owner's signature, and neither the script allowlist nor the owner-only
+    /// resource checks apply. Missing resources and resources already in the current layout are
+    /// skipped. The gas strategy applies to each migrated resource separately.
+    pub fn migrate_resources(
+        &self,
+        addresses: impl IntoIterator<Item = AccountAddress>,
+        tag: &StructTag,
+        gas: GasStrategy,
+    ) -> Result<MigrationReport, Error> {
+        let layout = self.resource_layout(tag)?;
+        let migrations =
+            MigrationRegistry::new(&*self.warehouse).get(&migration::layout_hash(&layout));
+
+        let mut report = MigrationReport::default();
+        for address in addresses {
+            match self.migrate_resource(address, tag, &layout, &migrations, gas) {
+                Ok(true) => report.migrated.push(address),
+                Ok(false) => (),
+                Err(result) => report.failed.push((address, result)),
+            }
+        }
+
+        Ok(report)
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Migrate a single resource - returns `false` if there was nothing to migrate.
+    fn migrate_resource(
+        &self,
+        address: AccountAddress,
+        tag: &StructTag,
+        layout: &MoveTypeLayout,
+        migrations: &[Migration],
+        gas: GasStrategy,
+    ) -> Result<bool, VmResult> {
+        let blob = match self.warehouse.get_resource(&address, tag) {
+            Ok(Some(blob)) => blob,
+            Ok(None) => return Ok(false),
+            Err(e) => {
+                let msg = format!("Storage error: {}", e);
+                return Err(VmResult::new(StatusCode::STORAGE_ERROR, Some(msg), 0));
+            }
+        };
+
+        if MoveValue::simple_deserialize(&blob, layout).is_ok() {
+            return Ok(false);
+        }
+
+        let (migration, fields) = migrations
+            .iter()
+            .find_map(|migration| {
+                match MoveValue::simple_deserialize(&blob, &migration.old_layout) {
+                    Ok(MoveValue::Struct(fields)) => Some((migration, fields.into_fields())),
+                    _ => None,
+                }
+            })
+            .ok_or_else(|| {
+                VmResult::new(
+                    StatusCode::FAILED_TO_DESERIALIZE_RESOURCE,
+                    Some("No migration for the stored resource layout".to_string()),
+                    0,
+                )
+            })?;
+
+        // The owner's signer goes first, followed by the old field values.
+        let args = core::iter::once(Some(address.to_vec()))
+            .chain(fields.iter().map(MoveValue::simple_serialize))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                VmResult::new(
+                    StatusCode::VALUE_SERIALIZATION_ERROR,
+                    Some("Failed to serialize the resource fields".to_string()),
+                    0,
+                )
+            })?;
+        let transaction = Transaction {
+            call: Call::Script {
+                code: migration.script.clone(),
+            },
+            type_args: Vec::new(),
+            args,
+        };
+
+        let view = MigrationView::new(&self.warehouse, address, tag);
+        let mut gas_handler = GasHandler::for_execution(gas, self.config, &self.call_gas_limits);
+        let result = execute_transaction(&self.vm, &view, transaction, &mut gas_handler).and_then(
+            |(changeset, events, expiries)| {
+                Ok((view.into_replacement(changeset)?, events, expiries))
+            },
+        );
+
+        let result = self.handle_result(result, gas_handler);
+        if result.is_ok() {
+            Ok(true)
+        } else {
+            Err(result)
+        }
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Returns the error result if the transaction is a script which is not allowed.
+    fn check_script_allowlist(&self, transaction: &Transaction) -> Result<(), VmResult> {
+        match &transaction.call {
+            Call::Script { code } if !self.is_script_allowed(code) => Err(VmResult::new(
+                StatusCode::UNKNOWN_SCRIPT,
+                Some("Script is not in the allowlist".to_string()),
+                0,
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reject the execution while a foreign call or a guarded balance call is in progress, i.e.
+    /// the host tries to re-enter the MoveVM from the called host function.
+    fn check_reentrancy(&self) -> Result<(), VmResult> {
+        if self.warehouse.foreign_call_active() {
+            return Err(VmResult::new(
+                StatusCode::VM_EXTENSION_ERROR,
+                Some("MoveVM can't be re-entered from a foreign call".to_string()),
+                0,
+            ));
+        }
+        if self.warehouse.balance_call_active() {
+            return Err(VmResult::new(
+                StatusCode::REENTRANT_BALANCE_CALL,
+                Some("MoveVM can't be re-entered from a balance call".to_string()),
+                0,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Execute script using the given arguments (args).
+    fn execute_script_worker(&self, transaction: Transaction, gas: GasStrategy) -> VmResult {
+        self.execute_paid_transaction(transaction, gas, self.fee_payer)
+    }
+
+    /// Execute the transaction with the fees paid by the fee payer, the fee hook or the signers.
+    fn execute_paid_transaction(
+        &self,
+        transaction: Transaction,
+        gas: GasStrategy,
+        fee_payer: Option<AccountAddress>,
+    ) -> VmResult {
+        if let Err(result) = self.check_transaction(&transaction) {
+            return result;
+        }
+
+        let module = entry_module(&transaction.call);
+        let dry_run = matches!(gas, GasStrategy::DryRun);
+
+        if let Some(fee_payer) = fee_payer {
+            let withdrawn = match self.withdraw_payer_fee(fee_payer, gas) {
+                Ok(withdrawn) => withdrawn,
+                Err(rejection) => return rejection,
+            };
+            let mut result = self.execute_unsponsored(transaction, gas, Some(fee_payer));
+            self.refund_payer_fee(fee_payer, withdrawn, &mut result);
+            self.record_module_stats(module.as_ref(), &result, dry_run);
+            return result;
+        }
+
+        let Some(hook) = &self.fee_hook else {
+            let result = self.execute_unsponsored(transaction, gas, None);
+            self.record_module_stats(module.as_ref(), &result, dry_run);
+            return result;
+        };
+
+        let signers = self.transaction_signers(&transaction);
+        let fee_result = match self.pay_fees(hook, &signers, gas) {
+            Ok(fee_result) => fee_result,
+            Err(rejection) => return rejection,
+        };
+
+        let mut result = self.execute_unsponsored(transaction, gas, None);
+        result.sponsored = true;
+        result.gas_used = result.gas_used.saturating_add(fee_result.gas_used);
+        result.fee = self.fee_for(result.gas_used);
+        result.events.splice(0..0, fee_result.events);
+        self.record_module_stats(module.as_ref(), &result, dry_run);
+        result
+    }
+
+    /// Returns the error result if the transaction can't be executed at all.
+    fn check_transaction(&self, transaction: &Transaction) -> Result<(), VmResult> {
+        self.check_reentrancy()?;
+        #[cfg(feature = "scripts")]
+        self.check_script_allowlist(transaction)?;
+        self.check_args(transaction)
+    }
+
+    /// Update the statistics of the module whose entry function was called.
+    fn record_module_stats(&self, module: Option<&ModuleId>, result: &VmResult, dry_run: bool) {
+        let Some(module) = module else {
+            return;
+        };
+        if !self.config.module_stats || dry_run {
+            return;
+        }
+
+        ModuleStatsRegistry::new(&*self.warehouse).record(module, result.gas_used, !result.is_ok());
+    }
+
+    /// Execute the transaction without the fee hook, with the fees accounted to the fee payer.
+    fn execute_unsponsored(
+        &self,
+        transaction: Transaction,
+        gas: GasStrategy,
+        fee_payer: Option<AccountAddress>,
+    ) -> VmResult {
+        let signers = self.transaction_signers(&transaction);
+        let mut gas_handler = GasHandler::for_execution(gas, self.config, &self.call_gas_limits);
+        gas_handler.fee_payer = fee_payer;
+        let result = execute_transaction(&self.vm, &self.warehouse, transaction, &mut gas_handler);
+        let result = self.check_resource_acl(result, &signers);
+
+        self.handle_result(result, gas_handler)
+    }
+
+    /// Withdraw the fee for the whole gas limit from the fee payer.
+    ///
+    /// Returns the withdrawn fee, or the error result if the fee payer can't afford it. Nothing is
+    /// withdrawn for the dry runs and the unmetered executions.
+    fn withdraw_payer_fee(
+        &self,
+        fee_payer: AccountAddress,
+        gas: GasStrategy,
+    ) -> Result<u128, VmResult> {
+        let max_fee = match gas {
+            GasStrategy::Metered(amount) => self.fee_for(amount.inner()),
+            GasStrategy::InstructionCount(limit) => self.fee_for(limit),
+            GasStrategy::DryRun | GasStrategy::Unmetered => 0,
+        };
+        if max_fee == 0 {
+            return Ok(0);
+        }
+
+        match self.warehouse.withdraw_fee(fee_payer, max_fee) {
+            Ok(true) => Ok(max_fee),
+            Ok(false) => Err(VmResult::new(
+                StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE,
+                Some(format!(
+                    "Fee payer {fee_payer} can't afford the fee {max_fee}"
+                )),
+                0,
+            )),
+            Err(status_code) => Err(VmResult::new(status_code, None, 0)),
+        }
+    }
+
+    /// Refund the fee for the unused gas to the fee payer.
+    ///
+    /// The transaction outcome stands even if the refund fails - the failure is reported in
+    /// [`VmResult::refund_error`].
+    fn refund_payer_fee(&self, fee_payer: AccountAddress, withdrawn: u128, result: &mut VmResult) {
+        let refund = withdrawn.saturating_sub(result.fee);
+        if refund > 0 {
+            result.refund_error = self.warehouse.refund_fee(fee_payer, refund).err();
+        }
+        result.sponsored = true;
+    }
+
+    /// Run the validation phase of the fee hook, which applies the fee payment.
+    ///
+    /// Returns the error result if the hook rejects the transaction.
+    fn pay_fees(
+        &self,
+        hook: &FeeHook,
+        signers: &BTreeSet<AccountAddress>,
+        gas: GasStrategy,
+    ) -> Result<VmResult, VmResult> {
+        let transaction = hook.transaction(signers, gas);
+        let hook_signers = self.transaction_signers(&transaction);
+
+        let mut gas_handler = GasHandler::for_execution(
+            GasStrategy::Metered(hook.validation_gas),
+            self.config,
+            &self.call_gas_limits,
+        );
+        // The fee payment isn't applied for the dry runs either.
+        gas_handler.dry_run = matches!(gas, GasStrategy::DryRun);
+
+        let result = execute_transaction(&self.vm, &self.warehouse, transaction, &mut gas_handler);
+        let result = self.check_resource_acl(result, &hook_signers);
+        let mut result = self.handle_result(result, gas_handler);
+
+        if result.is_err() {
+            let reason = result.error_message.take().unwrap_or_default();
+            result.error_message = Some(format!("Fee hook rejected the transaction: {reason}"));
+            return Err(result);
+        }
+
+        Ok(result)
+    }
+
+    /// Execute the transactions one by one.
+    #[cfg(feature = "scripts")]
+    fn execute_sequentially(
+        &self,
+        transactions: Vec<Transaction>,
+        gas: GasStrategy,
+    ) -> Vec<VmResult> {
+        transactions
+            .into_iter()
+            .map(|transaction| self.execute_script_worker(transaction, gas))
+            .collect()
+    }
+
+    /// Returns the error result if any vector argument exceeds its size limit, or any `String` or
+    /// `Option<T>` argument is invalid - see [`std_args`].
+    fn check_args(&self, transaction: &Transaction) -> Result<(), VmResult> {
+        let checked = match &transaction.call {
+            #[cfg(feature = "scripts")]
+            Call::Script { code } => {
+                // Invalid scripts are left for the MoveVM to reject.
+                let Ok(script) =
+                    CompiledScript::deserialize_with_max_version(code, MAX_BINARY_FORMAT_VERSION)
+                else {
+                    return Ok(());
+                };
+                self.check_params(
+                    &BinaryIndexedView::Script(&script),
+                    &script.signature_at(script.parameters).0,
+                    transaction,
+                )
+            }
+            Call::ScriptFunction {
+                mod_address,
+                mod_name,
+                func_name,
+            } => {
+                let module_id = ModuleId::new(*mod_address, mod_name.clone());
+                let Some(module) = self.load_compiled_module(&module_id) else {
+                    return Ok(());
+                };
+                self.check_params(
+                    &BinaryIndexedView::Module(&module),
+                    &acl::function_params(&module, func_name),
+                    transaction,
+                )
+            }
+        };
+
+        checked
+            .map_err(|msg| VmResult::new(StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT, Some(msg), 0))
+    }
+
+    /// Check the encoded arguments of the transaction against the parameters of the called code.
+    fn check_params(
+        &self,
+        view: &BinaryIndexedView,
+        params: &[SignatureToken],
+        transaction: &Transaction,
+    ) -> Result<(), String> {
+        if !self.vector_arg_limits.is_unlimited() {
+            self.vector_arg_limits.check(
+                view,
+                params,
+                &transaction.type_args,
+                &transaction.args,
+            )?;
+        }
+
+        for (position, (param, arg)) in params.iter().zip(&transaction.args).enumerate() {
+            let Some(tag) = arg_limits::type_tag(view, param, &transaction.type_args) else {
+                continue;
+            };
+            std_args::check_arg(arg, &tag)
+                .map_err(|err| format!("Argument {position} of type {tag}: {err}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Collect the addresses which signed the transaction.
+    fn transaction_signers(&self, transaction: &Transaction) -> BTreeSet<AccountAddress> {
+        let params = match &transaction.call {
+            #[cfg(feature = "scripts")]
+            Call::Script { code } => {
+                CompiledScript::deserialize_with_max_version(code, MAX_BINARY_FORMAT_VERSION)
+                    .map(|script| script.signature_at(script.parameters).0.clone())
+                    .unwrap_or_default()
+            }
             Call::ScriptFunction {
                 mod_address,
                 mod_name,
                 func_name,
-            } => sess.execute_entry_function(
-                &ModuleId::new(mod_address, mod_name),
-                &func_name,
-                transaction.type_args,
-                transaction.args,
-                &mut gas_handler.status,
-            ),
+            } => self
+                .load_compiled_module(&ModuleId::new(*mod_address, mod_name.clone()))
+                .map(|module| acl::function_params(&module, func_name))
+                .unwrap_or_default(),
+        };
+
+        acl::leading_signers(&params, &transaction.args)
+    }
+
+    /// Reject the changeset which writes owner-only resources of the accounts which didn't sign
+    /// the transaction.
+    fn check_resource_acl(
+        &self,
+        result: VMResult<TransactionOutput>,
+        signers: &BTreeSet<AccountAddress>,
+    ) -> VMResult<TransactionOutput> {
+        let (changeset, events, expiries) = result?;
+        let mut owner_only_structs: BTreeMap<ModuleId, BTreeSet<Identifier>> = BTreeMap::new();
+
+        for (address, tag, _) in changeset.resources() {
+            if signers.contains(&address) {
+                continue;
+            }
+
+            let structs = owner_only_structs
+                .entry(tag.module_id())
+                .or_insert_with_key(|module_id| {
+                    self.load_compiled_module(module_id)
+                        .map(|module| acl::owner_only_structs(&module))
+                        .unwrap_or_default()
+                });
+
+            if structs.contains(&tag.name) {
+                return Err(PartialVMError::new(StatusCode::REJECTED_WRITE_SET)
+                    .with_message(format!(
+                        "Owner-only resource {} written without the owner signature",
+                        tag
+                    ))
+                    .finish(Location::Undefined));
+            }
+        }
+
+        Ok((changeset, events, expiries))
+    }
+
+    /// Load the published module.
+    fn load_compiled_module(&self, module_id: &ModuleId) -> Option<CompiledModule> {
+        let bytecode = self.warehouse.get_module(module_id).ok()??;
+        CompiledModule::deserialize_with_max_version(&bytecode, MAX_BINARY_FORMAT_VERSION).ok()
+    }
+
+    #[cfg(feature = "scripts")]
+    /// Commit the speculatively executed block transactions in order.
+    ///
+    /// Transactions which read resources written by the preceding transactions are re-executed.
+    fn commit_block(
+        &self,
+        transactions: Vec<Transaction>,
+        speculations: Vec<Speculation>,
+        gas: GasStrategy,
+    ) -> Vec<VmResult> {
+        let mut written = BTreeSet::new();
+        let mut results = Vec::with_capacity(transactions.len());
+
+        for (transaction, speculation) in transactions.into_iter().zip(speculations) {
+            if let Err(result) = self
+                .check_reentrancy()
+                .and_then(|_| self.check_script_allowlist(&transaction))
+                .and_then(|_| self.check_args(&transaction))
+            {
+                results.push(result);
+                continue;
+            }
+
+            let signers = self.transaction_signers(&transaction);
+            let module = entry_module(&transaction.call);
+            let (result, gas_handler) = match speculation.validate(&written) {
+                Some(outcome) => outcome,
+                None => {
+                    let mut gas_handler =
+                        GasHandler::for_execution(gas, self.config, &self.call_gas_limits);
+                    let result = execute_transaction(
+                        &self.vm,
+                        &self.warehouse,
+                        transaction,
+                        &mut gas_handler,
+                    );
+                    (result, gas_handler)
+                }
+            };
+            let result = self.check_resource_acl(result, &signers);
+
+            // Dry runs don't update the storage.
+            if let (Ok((changeset, _, _)), false) = (&result, gas_handler.dry_run) {
+                written.extend(parallel::written_resources(changeset));
+            }
+
+            let dry_run = gas_handler.dry_run;
+            let result = self.handle_result(result, gas_handler);
+            self.record_module_stats(module.as_ref(), &result, dry_run);
+            results.push(result);
+        }
+
+        results
+    }
+
+    /// Where the MoveVM failed, with the function name resolved from the published module.
+    fn error_context(&self, err: &VMError) -> ErrorContext {
+        let module = match err.location() {
+            Location::Module(id) => Some(id.clone()),
+            Location::Script | Location::Undefined => None,
         };
+        let frame = err.offsets().last().copied();
+        let function = module.as_ref().zip(frame).and_then(|(id, (index, _))| {
+            let bytes = self.warehouse.get_module(id).ok()??;
+            let module =
+                CompiledModule::deserialize_with_max_version(&bytes, MAX_BINARY_FORMAT_VERSION)
+                    .ok()?;
+            let def = module.function_defs.get(index.0 as usize)?;
+            let handle = module.function_handles.get(def.function.0 as usize)?;
+            Some(module.identifier_at(handle.name).to_owned())
+        });
 
-        self.handle_result(result.and_then(|_| sess.finish()), gas_handler)
+        ErrorContext {
+            stage: err.status_type().into(),
+            module,
+            function,
+            frame,
+        }
+    }
+
+    /// Flush the code loaded before the upgrade and report the invalidated modules - see
+    /// [`upgrade`].
+    ///
+    /// Must be called once the changes are applied, when no session is alive.
+    fn invalidate_upgraded(&self, upgraded: Vec<ModuleId>) {
+        self.vm.mark_loader_cache_as_invalid();
+        self.vm.flush_loader_cache_if_invalidated();
+
+        let Some(hook) = &self.upgrade_hook else {
+            return;
+        };
+
+        let index = ModuleIndex::new(&*self.warehouse);
+        let mut dependents = BTreeSet::new();
+        let mut pending = upgraded.clone();
+        while let Some(module) = pending.pop() {
+            for dependent in index.dependents(&module) {
+                if upgraded.contains(&dependent) || dependents.contains(&dependent) {
+                    continue;
+                }
+                // The index keeps the dependents which dropped the dependency since - the ones
+                // which can't be read are reported to be on the safe side.
+                if !self.depends_on(&dependent, &module).unwrap_or(true) {
+                    continue;
+                }
+                dependents.insert(dependent.clone());
+                pending.push(dependent);
+            }
+        }
+
+        hook.on_upgrade(&InvalidationReport {
+            upgraded,
+            dependents: dependents.into_iter().collect(),
+        });
     }
 
+    /// Write the outcome of the transaction and persist the writes deferred by the storage cache.
     fn handle_result(
         &self,
-        result: VMResult<(ChangeSet, Vec<Event>)>,
+        result: VMResult<TransactionOutput>,
+        gas_handler: GasHandler,
+    ) -> VmResult {
+        let result = self.write_result(result, gas_handler);
+        self.warehouse.flush();
+        result
+    }
+
+    fn write_result(
+        &self,
+        result: VMResult<TransactionOutput>,
         gas_handler: GasHandler,
     ) -> VmResult {
         match result {
-            Ok((changeset, _)) => {
+            Ok((changeset, events, expiries)) => {
                 let mut result = VmResult::new(StatusCode::EXECUTED, None, gas_handler.gas_used());
+                result.gas_profile = gas_handler.gas_profile();
+                result.fee = self.fee_for(result.gas_used);
+                result.fee_payer = gas_handler.fee_payer;
+
+                if let Err((status_code, msg)) =
+                    FreezeRegistry::new(&*self.warehouse).check(&changeset)
+                {
+                    result.status_code = status_code;
+                    result.error_message = Some(msg);
+                    return result;
+                }
+
+                // Only the indexed event natives are registered, so all events carry the topics.
+                result.events = match events.into_iter().map(MoveEvent::try_from).collect() {
+                    Ok(events) => events,
+                    Err(e) => {
+                        result.status_code = StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR;
+                        result.error_message = Some(format!("Event error: {}", e));
+                        return result;
+                    }
+                };
+
+                if let Some(max_size) = self.config.max_event_size {
+                    if let Some(event) = result.events.iter().find(|e| e.data.len() > max_size) {
+                        let msg = format!(
+                            "Event {} data takes {} bytes, the limit is {}",
+                            event.index,
+                            event.data.len(),
+                            max_size
+                        );
+                        result.events.clear();
+                        result.status_code = StatusCode::EVENT_DATA_TOO_LARGE;
+                        result.error_message = Some(msg);
+                        return result;
+                    }
+                }
+
+                let sequence = EventSequence::new(&*self.warehouse);
+                sequence.assign(&mut result.events);
+
+                result.state_diff_root = receipt::state_diff_root(&changeset);
+
+                match self.warehouse.freed_storage(&changeset) {
+                    Ok(freed) => result.gas_refund = gas_handler.gas_refund(freed),
+                    Err(e) => {
+                        result.status_code = StatusCode::STORAGE_ERROR;
+                        result.error_message = Some(format!("Storage error: {}", e));
+                        return result;
+                    }
+                }
 
                 // No storage update!
                 if gas_handler.dry_run {
                     return result;
                 }
 
+                if let Some(block) = self.block.borrow_mut().as_mut() {
+                    if !block.take_gas(result.gas_used) {
+                        result.status_code = StatusCode::EXECUTION_LIMIT_REACHED;
+                        result.error_message = Some("Block gas pool exhausted".to_string());
+                        return result;
+                    }
+                }
+
+                // Deleted resources lose their expiries.
+                let expiries = expiry::with_deleted(&changeset, expiries);
+                let upgraded = upgrade::upgraded_modules(&changeset);
+                #[cfg(feature = "std")]
+                let writes = self.subscribers.writes(&changeset);
                 if let Err(e) = self.warehouse.apply_changes(changeset) {
                     result.status_code = StatusCode::STORAGE_ERROR;
                     result.error_message = Some(format!("Storage error: {}", e));
+                    return result;
+                }
+                ExpiryRegistry::new(&*self.warehouse).apply(expiries);
+                sequence.advance(&result.events);
+                #[cfg(feature = "std")]
+                self.subscribers.notify(&result.events, writes);
+                if !upgraded.is_empty() {
+                    self.invalidate_upgraded(upgraded);
                 }
 
                 result
             }
             Err(err) => {
-                let (status_code, _, msg, _, _, _, _) = err.all_data();
-                VmResult::new(status_code, msg.clone(), 0)
+                let error_context = self.error_context(&err);
+                let (status_code, sub_status, msg, _, _, _, _) = err.all_data();
+                // The failed execution still pays for the gas it consumed.
+                let mut result = VmResult::new(status_code, msg.clone(), gas_handler.gas_used());
+                result.error_context = Some(error_context);
+                result.fee = self.fee_for(result.gas_used);
+                result.fee_payer = gas_handler.fee_payer;
+                if status_code == StatusCode::ABORTED {
+                    result.abort_code = sub_status;
+                }
+                result.gas_profile = gas_handler.gas_profile();
+                result
             }
         }
     }
 }
+
+impl<S, H> Metrics for Mvm<S, H>
+where
+    S: Storage,
+    H: HostBindings,
+{
+    fn layout_cache_stats(&self) -> LayoutCacheStats {
+        self.vm.layout_cache_stats()
+    }
+
+    fn cached_modules(&self) -> usize {
+        self.vm.cached_module_count()
+    }
+}
+
+/// Returns the error result if any of the modules depends on itself or the modules depend on each
+/// other in a cycle - see [`module_deps`].
+fn check_dependencies<'m>(modules: impl Iterator<Item = &'m [u8]>) -> Result<(), VmResult> {
+    module_deps::check_bundle_dependencies(modules)
+        .map_err(|e| VmResult::new(StatusCode::CYCLIC_MODULE_DEPENDENCY, Some(e.to_string()), 0))
+}
+
+/// Module of the called entry function - scripts have none.
+fn entry_module(call: &Call) -> Option<ModuleId> {
+    match call {
+        #[cfg(feature = "scripts")]
+        Call::Script { .. } => None,
+        Call::ScriptFunction {
+            mod_address,
+            mod_name,
+            ..
+        } => Some(ModuleId::new(*mod_address, mod_name.clone())),
+    }
+}
+
+/// Result of the legacy binary which can't be re-encoded.
+fn legacy_binary_error(error: LegacyBinaryError) -> VmResult {
+    VmResult::new(
+        StatusCode::CODE_DESERIALIZATION_ERROR,
+        Some(format!("Legacy binary error: {error}")),
+        0,
+    )
+}
+
+/// Latest binary format version of the loaded modules and scripts.
+pub(crate) const MAX_BINARY_FORMAT_VERSION: u32 = if cfg!(feature = "experimental-format") {
+    VERSION_7
+} else {
+    VERSION_MAX
+};
+
+/// Create a new MoveVM instance with all natives, the gated ones failing when called.
+fn new_move_vm(
+    gated_natives: &BTreeSet<GatedNative>,
+    max_call_depth: usize,
+    interrupt: &InterruptHandle,
+) -> Result<MoveVM, Error> {
+    // TODO(rqnsom): see if we can avoid GAS_PARAMS cloning
+    let natives = all_natives(CORE_CODE_ADDRESS, NATIVE_COST_PARAMS.clone());
+    let config = VMConfig {
+        max_call_depth,
+        max_binary_format_version: MAX_BINARY_FORMAT_VERSION,
+        interrupt: Some(interrupt.clone()),
+        ..Default::default()
+    };
+    MoveVM::new_with_config(native_gating::gate_natives(natives, gated_natives), config).map_err(
+        |err| {
+            let (code, _, msg, _, _, _, _) = err.all_data();
+            anyhow!("Error code:{:?}: msg: '{}'", code, msg.unwrap_or_default())
+        },
+    )
+}
+
+/// Execute the transaction in a new session on top of the given resolver.
+fn execute_transaction<R: MoveResolver + NativeHost>(
+    vm: &MoveVM,
+    resolver: &R,
+    transaction: Transaction,
+    gas_handler: &mut GasHandler,
+) -> VMResult<TransactionOutput> {
+    // Type arguments are resolved before the metered execution starts.
+    gas_handler
+        .charge_type_args(&transaction.type_args)
+        .map_err(|e| e.finish(Location::Undefined))?;
+
+    let mut sess = vm.new_session_with_extensions(
+        resolver,
+        native_extensions(resolver, gas_handler.host_weight_per_gas),
+    );
+    charge_call_args(
+        &sess,
+        gas_handler,
+        &transaction.call,
+        &transaction.type_args,
+        transaction.args.iter().map(Vec::as_slice).enumerate(),
+    )?;
+    let mut meter = MemoryTrackedGasMeter::new(&mut gas_handler.status, gas_handler.memory_limit);
+
+    if !gas_handler.profiling {
+        let result = execute_call(
+            &mut sess,
+            transaction,
+            &mut meter,
+            &gas_handler.call_gas_limits,
+        );
+        return result.and_then(|_| finish_session(sess));
+    }
+
+    let entry = match &transaction.call {
+        #[cfg(feature = "scripts")]
+        Call::Script { .. } => "script".to_string(),
+        Call::ScriptFunction {
+            mod_address,
+            mod_name,
+            func_name,
+        } => profiler::function_name(
+            &ModuleId::new(*mod_address, mod_name.clone()),
+            func_name.as_str(),
+        ),
+    };
+    let mut profiler = GasProfiler::new(entry, meter.balance_internal());
+    let mut profiling_meter = ProfilingGasMeter {
+        meter: &mut meter,
+        profiler: &mut profiler,
+    };
+
+    let result = execute_call(
+        &mut sess,
+        transaction,
+        &mut profiling_meter,
+        &gas_handler.call_gas_limits,
+    );
+    gas_handler.profile = Some(profiler.finish(meter.balance_internal()));
+
+    result.and_then(|_| finish_session(sess))
+}
+
+/// Publish the modules and execute the init transaction in a single session on top of the given
+/// resolver.
+fn publish_with_init<R: MoveResolver + NativeHost>(
+    vm: &MoveVM,
+    resolver: &R,
+    modules: Vec<Vec<u8>>,
+    senders: &[AccountAddress],
+    init: Transaction,
+    gas_handler: &mut GasHandler,
+) -> VMResult<TransactionOutput> {
+    gas_handler
+        .charge_type_args(&init.type_args)
+        .map_err(|e| e.finish(Location::Undefined))?;
+
+    let mut sess = vm.new_session_with_extensions(
+        resolver,
+        native_extensions(resolver, gas_handler.host_weight_per_gas),
+    );
+    let mut meter = MemoryTrackedGasMeter::new(&mut gas_handler.status, gas_handler.memory_limit);
+    sess.publish_module_bundle_for_senders(modules, senders, &mut meter)?;
+
+    // The init function may come with the published modules.
+    charge_call_args(
+        &sess,
+        gas_handler,
+        &init.call,
+        &init.type_args,
+        init.args.iter().map(Vec::as_slice).enumerate(),
+    )?;
+    let mut meter = MemoryTrackedGasMeter::new(&mut gas_handler.status, gas_handler.memory_limit);
+
+    execute_call(&mut sess, init, &mut meter, &gas_handler.call_gas_limits)?;
+    finish_session(sess)
+}
+
+/// Host services the natives reach through the resolver of the transaction session.
+trait NativeHost:
+    AccountHandler
+    + ForeignCallHandler
+    + XcmSender
+    + HostWeightReporter
+    + HashHandler
+    + RandomnessHandler
+    + TimeHandler
+{
+}
+
+impl<R> NativeHost for R where
+    R: AccountHandler
+        + ForeignCallHandler
+        + XcmSender
+        + HostWeightReporter
+        + HashHandler
+        + RandomnessHandler
+        + TimeHandler
+{
+}
+
+/// Native context extensions of the transaction session.
+fn native_extensions<R: NativeHost>(
+    resolver: &R,
+    host_weight_per_gas: u64,
+) -> NativeContextExtensions<'_> {
+    let mut extensions = NativeContextExtensions::default();
+    extensions.add(NativeExpiryContext::default());
+    extensions.add(NativeForeignCallContext::new(resolver));
+    extensions.add(NativeXcmContext::new(resolver));
+    extensions.add(NativeAccountContext::new(resolver));
+    extensions.add(NativeHostGasContext::new(resolver, host_weight_per_gas));
+    extensions.add(NativeHashContext::new(resolver));
+    extensions.add(NativeRandomnessContext::new(resolver));
+    extensions.add(NativeTimeContext::new(resolver));
+    extensions
+}
+
+/// Finish the transaction session and collect the resource expiry changes.
+fn finish_session<R: MoveResolver>(sess: Session<'_, '_, R>) -> VMResult<TransactionOutput> {
+    let (changeset, events, mut extensions) = sess.finish_with_extensions()?;
+    let expiries = extensions.remove::<NativeExpiryContext>().into_changes();
+
+    Ok((changeset, events, expiries))
+}
+
+/// Execute the calls in order in a single session on top of the given resolver.
+///
+/// The return values of each call are kept for the arguments of the later calls. Failures of the
+/// calls come with the index of the failed call.
+fn execute_call_sequence<R>(
+    vm: &MoveVM,
+    resolver: &R,
+    calls: Vec<ComposedCall>,
+    gas_handler: &mut GasHandler,
+) -> Result<TransactionOutput, (Option<usize>, VMError)>
+where
+    R: MoveResolver + NativeHost,
+{
+    for call in &calls {
+        gas_handler
+            .charge_type_args(&call.type_args)
+            .map_err(|e| (None, e.finish(Location::Undefined)))?;
+    }
+
+    let mut sess = vm.new_session_with_extensions(
+        resolver,
+        native_extensions(resolver, gas_handler.host_weight_per_gas),
+    );
+    // The return values of the earlier calls are never deserialized from the input.
+    for (index, call) in calls.iter().enumerate() {
+        let entry = Call::ScriptFunction {
+            mod_address: call.mod_address,
+            mod_name: call.mod_name.clone(),
+            func_name: call.func_name.clone(),
+        };
+        let args = call
+            .args
+            .iter()
+            .enumerate()
+            .filter_map(|(param, arg)| match arg {
+                CallArg::Value(bytes) => Some((param, bytes.as_slice())),
+                CallArg::Result { .. } => None,
+            });
+        charge_call_args(&sess, gas_handler, &entry, &call.type_args, args)
+            .map_err(|e| (Some(index), e))?;
+    }
+    let mut meter = MemoryTrackedGasMeter::new(&mut gas_handler.status, gas_handler.memory_limit);
+
+    let mut results = Vec::with_capacity(calls.len());
+    for (index, call) in calls.into_iter().enumerate() {
+        let args = call.resolve_args(&results).ok_or_else(|| {
+            let err = PartialVMError::new(StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT)
+                .with_message("Argument refers to an unknown return value".to_string());
+            (Some(index), err.finish(Location::Undefined))
+        })?;
+        let transaction = Transaction::composed(call, args);
+        let returns = execute_call(
+            &mut sess,
+            transaction,
+            &mut meter,
+            &gas_handler.call_gas_limits,
+        )
+        .map_err(|e| (Some(index), e))?;
+        results.push(returns);
+    }
+    finish_session(sess).map_err(|e| (None, e))
+}
+
+/// Charge the BCS-encoded arguments of the call, given with the indices of their parameters, if
+/// the argument metering is enabled.
+///
+/// The arguments without a matching parameter are left for the execution to reject.
+fn charge_call_args<'a, R: MoveResolver>(
+    sess: &Session<'_, '_, R>,
+    gas_handler: &mut GasHandler,
+    call: &Call,
+    type_args: &[TypeTag],
+    args: impl IntoIterator<Item = (usize, &'a [u8])>,
+) -> VMResult<()> {
+    if !gas_handler.arg_metering {
+        return Ok(());
+    }
+
+    let function = match call {
+        #[cfg(feature = "scripts")]
+        Call::Script { code } => sess.load_script(code.as_slice(), type_args.to_vec())?,
+        Call::ScriptFunction {
+            mod_address,
+            mod_name,
+            func_name,
+        } => sess.load_function(
+            &ModuleId::new(*mod_address, mod_name.clone()),
+            func_name,
+            type_args,
+        )?,
+    };
+
+    let args = args
+        .into_iter()
+        .filter_map(|(index, arg)| Some((function.parameters.get(index)?, arg)));
+    gas_handler
+        .charge_args(args)
+        .map_err(|e| e.finish(Location::Undefined))
+}
+
+/// Execute the transaction call in the session with the given gas meter.
+///
+/// The calls into the modules with the gas limits are metered against their limits as well.
+/// Returns the BCS-encoded return values of the call.
+fn execute_call<R: MoveResolver, G: GasMeter>(
+    sess: &mut Session<'_, '_, R>,
+    transaction: Transaction,
+    gas_meter: &mut G,
+    call_gas_limits: &CallGasLimits,
+) -> VMResult<Vec<Vec<u8>>> {
+    let entry = entry_module(&transaction.call);
+    let gas_meter = &mut CallLimitedGasMeter::new(gas_meter, call_gas_limits, entry.as_ref());
+
+    let result = match transaction.call {
+        #[cfg(feature = "scripts")]
+        Call::Script { code } => {
+            sess.execute_script(code, transaction.type_args, transaction.args, gas_meter)
+        }
+        Call::ScriptFunction {
+            mod_address,
+            mod_name,
+            func_name,
+        } => sess.execute_entry_function(
+            &ModuleId::new(mod_address, mod_name),
+            &func_name,
+            transaction.type_args,
+            transaction.args,
+            gas_meter,
+        ),
+    };
+
+    result.map(|values| {
+        values
+            .return_values
+            .into_iter()
+            .map(|(bytes, _)| bytes)
+            .collect()
+    })
+}
@@ -0,0 +1,302 @@
+//! Memory metering of the VM values.
+//!
+//! The [`MemoryTrackedGasMeter`] wraps the gas meter used by the MoveVM and counts the abstract
+//! memory size of the values allocated during the execution: loaded constants, copied locals and
+//! references, values packed into structs and vectors or pushed to vectors, values returned from
+//! native functions and resources loaded from the storage.
+//!
+//! The allocations are only ever added up, so the limit bounds the total memory allocated by the
+//! transaction rather than the memory in use at any given point. Values moved into containers are
+//! counted again, which makes the nested containers count once per nesting level.
+
+use alloc::vec::Vec;
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    gas_algebra::{InternalGas, NumArgs, NumBytes},
+    language_storage::ModuleId,
+    vm_status::StatusCode,
+};
+use move_vm_types::{
+    gas::{GasMeter, SimpleInstruction},
+    views::{TypeView, ValueView},
+};
+
+/// Gas meter which enforces the memory limit while delegating all charges to the inner meter.
+pub(crate) struct MemoryTrackedGasMeter<'a, G: GasMeter> {
+    meter: &'a mut G,
+    /// Maximum amount of memory in bytes.
+    limit: u64,
+    /// Memory allocated so far.
+    pub(crate) used: u64,
+}
+
+impl<'a, G: GasMeter> MemoryTrackedGasMeter<'a, G> {
+    pub(crate) fn new(meter: &'a mut G, limit: u64) -> Self {
+        Self {
+            meter,
+            limit,
+            used: 0,
+        }
+    }
+
+    fn allocate(&mut self, bytes: u64) -> PartialVMResult<()> {
+        self.used = self.used.saturating_add(bytes);
+
+        if self.used > self.limit {
+            return Err(PartialVMError::new(StatusCode::MEMORY_LIMIT_EXCEEDED));
+        }
+
+        Ok(())
+    }
+
+    fn allocate_value(&mut self, val: &impl ValueView) -> PartialVMResult<()> {
+        self.allocate(val.legacy_abstract_memory_size().into())
+    }
+
+    fn allocate_values<V: ValueView>(&mut self, vals: &[V]) -> PartialVMResult<()> {
+        let bytes = vals.iter().fold(0u64, |bytes, val| {
+            bytes.saturating_add(val.legacy_abstract_memory_size().into())
+        });
+        self.allocate(bytes)
+    }
+}
+
+impl<G: GasMeter> GasMeter for MemoryTrackedGasMeter<'_, G> {
+    fn balance_internal(&self) -> InternalGas {
+        self.meter.balance_internal()
+    }
+
+    fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
+        self.meter.charge_simple_instr(instr)
+    }
+
+    fn charge_back_edge(&mut self) -> PartialVMResult<()> {
+        self.meter.charge_back_edge()
+    }
+
+    fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_pop(popped_val)
+    }
+
+    fn charge_call(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_call(module_id, func_name, args, num_locals)
+    }
+
+    fn charge_call_generic(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_call_generic(module_id, func_name, ty_args, args, num_locals)
+    }
+
+    fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
+        self.meter.charge_ld_const(size)
+    }
+
+    fn charge_ld_const_after_deserialization(
+        &mut self,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.allocate_value(&val)?;
+        self.meter.charge_ld_const_after_deserialization(val)
+    }
+
+    fn charge_copy_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.allocate_value(&val)?;
+        self.meter.charge_copy_loc(val)
+    }
+
+    fn charge_move_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_move_loc(val)
+    }
+
+    fn charge_store_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_store_loc(val)
+    }
+
+    fn charge_pack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let args: Vec<_> = args.collect();
+        self.allocate_values(&args)?;
+        self.meter.charge_pack(is_generic, args.into_iter())
+    }
+
+    fn charge_unpack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_unpack(is_generic, args)
+    }
+
+    fn charge_read_ref(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.allocate_value(&val)?;
+        self.meter.charge_read_ref(val)
+    }
+
+    fn charge_write_ref(
+        &mut self,
+        new_val: impl ValueView,
+        old_val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_write_ref(new_val, old_val)
+    }
+
+    fn charge_eq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_eq(lhs, rhs)
+    }
+
+    fn charge_neq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_neq(lhs, rhs)
+    }
+
+    fn charge_borrow_global(
+        &mut self,
+        is_mut: bool,
+        is_generic: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_borrow_global(is_mut, is_generic, ty, is_success)
+    }
+
+    fn charge_exists(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        exists: bool,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_exists(is_generic, ty, exists)
+    }
+
+    fn charge_move_from(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_move_from(is_generic, ty, val)
+    }
+
+    fn charge_move_to(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: impl ValueView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_move_to(is_generic, ty, val, is_success)
+    }
+
+    fn charge_vec_pack<'a>(
+        &mut self,
+        ty: impl TypeView + 'a,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let args: Vec<_> = args.collect();
+        self.allocate_values(&args)?;
+        self.meter.charge_vec_pack(ty, args.into_iter())
+    }
+
+    fn charge_vec_len(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.meter.charge_vec_len(ty)
+    }
+
+    fn charge_vec_borrow(
+        &mut self,
+        is_mut: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_vec_borrow(is_mut, ty, is_success)
+    }
+
+    fn charge_vec_push_back(
+        &mut self,
+        ty: impl TypeView,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.allocate_value(&val)?;
+        self.meter.charge_vec_push_back(ty, val)
+    }
+
+    fn charge_vec_pop_back(
+        &mut self,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_vec_pop_back(ty, val)
+    }
+
+    fn charge_vec_unpack(
+        &mut self,
+        ty: impl TypeView,
+        expect_num_elements: NumArgs,
+        elems: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_vec_unpack(ty, expect_num_elements, elems)
+    }
+
+    fn charge_vec_swap(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.meter.charge_vec_swap(ty)
+    }
+
+    fn charge_load_resource(
+        &mut self,
+        loaded: Option<(NumBytes, impl ValueView)>,
+    ) -> PartialVMResult<()> {
+        if let Some((_, val)) = &loaded {
+            self.allocate_value(val)?;
+        }
+        self.meter.charge_load_resource(loaded)
+    }
+
+    fn charge_native_function(
+        &mut self,
+        amount: InternalGas,
+        ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView>>,
+    ) -> PartialVMResult<()> {
+        let ret_vals = ret_vals.map(|vals| vals.collect::<Vec<_>>());
+        if let Some(vals) = &ret_vals {
+            self.allocate_values(vals)?;
+        }
+        self.meter
+            .charge_native_function(amount, ret_vals.map(Vec::into_iter))
+    }
+
+    fn charge_native_function_before_execution(
+        &mut self,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_native_function_before_execution(ty_args, args)
+    }
+
+    fn charge_drop_frame(
+        &mut self,
+        locals: impl Iterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_drop_frame(locals)
+    }
+
+    fn charge_dependency(&mut self, module_id: &ModuleId, size: NumBytes) -> PartialVMResult<()> {
+        self.meter.charge_dependency(module_id, size)
+    }
+}
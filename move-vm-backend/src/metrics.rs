@@ -0,0 +1,16 @@
+//! Runtime metrics of the VM instance.
+//!
+//! Unlike the [`crate::stats`] kept in the storage, these metrics live in memory only and describe
+//! the node-local VM instance, e.g. how well its caches perform.
+
+pub use move_vm_runtime::move_vm::LayoutCacheStats;
+
+/// Source of the VM instance metrics.
+pub trait Metrics {
+    /// Statistics of the loader cache of the struct layouts, used when decoding the resources and
+    /// validating the transaction arguments.
+    fn layout_cache_stats(&self) -> LayoutCacheStats;
+
+    /// Number of modules in the loader cache, e.g. after the [`crate::warm_up`].
+    fn cached_modules(&self) -> usize;
+}
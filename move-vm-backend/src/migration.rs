@@ -0,0 +1,287 @@
+//! Migration of the stored resources after the struct layout changes.
+//!
+//! Modules upgraded with [`crate::Mvm::publish_module_with_layout_changes`] can change the layout
+//! of their structs, which makes the already stored resources undecodable. Each registered
+//! [`Migration`] describes how to convert the resources stored with the old layout into the new
+//! one: the migration script receives the resource owner's `signer` followed by the old field
+//! values as arguments and is expected to publish the resource in the new layout.
+//!
+//! Migrations are keyed by the [`LayoutHash`] of the new layout, so all migrations towards the
+//! current layout of a struct can be found without knowing how the stored resources look.
+
+use crate::{storage::Storage, storage_key::MIGRATION_KEY_PREFIX, types::VmResult};
+use alloc::vec::Vec;
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use move_binary_format::errors::{Location, PartialVMError, PartialVMResult, VMResult};
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::{AccountChangeSet, ChangeSet, Op},
+    language_storage::{ModuleId, StructTag},
+    resolver::{BalanceResolver, ModuleResolver, ResourceResolver},
+    value::MoveTypeLayout,
+    vm_status::StatusCode,
+};
+use move_stdlib::natives::{
+    account::AccountHandler,
+    foreign::{ForeignCallHandler, ForeignCallResponse},
+    hash::HashHandler,
+    host_gas::HostWeightReporter,
+    random::RandomnessHandler,
+    timestamp::TimeHandler,
+    xcm::XcmSender,
+};
+use serde::{Deserialize, Serialize};
+
+/// Blake2b-256 hash of the BCS-encoded type layout.
+pub type LayoutHash = [u8; 32];
+
+/// Calculates the [`LayoutHash`] of the type layout.
+///
+/// Only the encoding matters - renaming the fields doesn't change the hash.
+pub fn layout_hash(layout: &MoveTypeLayout) -> LayoutHash {
+    let bytes = bcs::to_bytes(layout).expect("type layouts are always serializable");
+    Blake2b::<U32>::digest(bytes).into()
+}
+
+/// Conversion of the resources stored with the old layout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Migration {
+    /// Layout of the stored resources.
+    pub old_layout: MoveTypeLayout,
+    /// Script executed for each migrated resource.
+    pub script: Vec<u8>,
+}
+
+/// Outcome of [`crate::Mvm::migrate_resources`].
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    /// Accounts whose resources were migrated.
+    pub migrated: Vec<AccountAddress>,
+    /// Accounts whose resources couldn't be migrated, with the reason.
+    pub failed: Vec<(AccountAddress, VmResult)>,
+}
+
+/// Keeps the registered migrations in the storage.
+pub(crate) struct MigrationRegistry<'a, S: Storage> {
+    storage: &'a S,
+}
+
+impl<'a, S: Storage> MigrationRegistry<'a, S> {
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self { storage }
+    }
+
+    /// All migrations towards the new layout.
+    pub(crate) fn get(&self, new_layout: &LayoutHash) -> Vec<Migration> {
+        self.storage
+            .get(&Self::key(new_layout))
+            .and_then(|bytes| bcs::from_bytes(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Registers the migration, replacing the one with the same old layout.
+    pub(crate) fn add(&self, new_layout: &LayoutHash, migration: Migration) {
+        let old_layout = layout_hash(&migration.old_layout);
+        let mut migrations = self.get(new_layout);
+        migrations.retain(|m| layout_hash(&m.old_layout) != old_layout);
+        migrations.push(migration);
+        self.set(new_layout, &migrations);
+    }
+
+    pub(crate) fn remove(&self, old_layout: &LayoutHash, new_layout: &LayoutHash) {
+        let mut migrations = self.get(new_layout);
+        migrations.retain(|m| layout_hash(&m.old_layout) != *old_layout);
+        self.set(new_layout, &migrations);
+    }
+
+    fn set(&self, new_layout: &LayoutHash, migrations: &[Migration]) {
+        let key = Self::key(new_layout);
+        if migrations.is_empty() {
+            self.storage.remove(&key);
+        } else {
+            let bytes = bcs::to_bytes(migrations).expect("migrations are always serializable");
+            self.storage.set(&key, &bytes);
+        }
+    }
+
+    fn key(new_layout: &LayoutHash) -> Vec<u8> {
+        [MIGRATION_KEY_PREFIX, new_layout.as_slice()].concat()
+    }
+}
+
+/// Storage view which hides the migrated resource, so the migration script can publish the
+/// resource in the new layout.
+pub(crate) struct MigrationView<'a, R> {
+    resolver: &'a R,
+    address: AccountAddress,
+    tag: &'a StructTag,
+}
+
+impl<'a, R> MigrationView<'a, R> {
+    pub(crate) fn new(resolver: &'a R, address: AccountAddress, tag: &'a StructTag) -> Self {
+        Self {
+            resolver,
+            address,
+            tag,
+        }
+    }
+
+    /// Turns the changeset of the migration script into the replacement of the stored resource.
+    ///
+    /// The resource published by the script overwrites the old one. If the script didn't publish
+    /// it, the old resource is deleted.
+    pub(crate) fn into_replacement(self, changeset: ChangeSet) -> VMResult<ChangeSet> {
+        let mut accounts = changeset.into_inner();
+        let (modules, mut resources) = accounts
+            .remove(&self.address)
+            .map(AccountChangeSet::into_inner)
+            .unwrap_or_default();
+
+        let op = match resources.remove(self.tag) {
+            Some(Op::New(blob)) => Op::Modify(blob),
+            Some(_) => {
+                return Err(
+                    PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                        .with_message("Migration script accessed the migrated resource".into())
+                        .finish(Location::Undefined),
+                )
+            }
+            None => Op::Delete,
+        };
+        resources.insert(self.tag.clone(), op);
+
+        let mut changeset = ChangeSet::new();
+        let account = AccountChangeSet::from_modules_resources(modules, resources);
+        for (address, account) in accounts.into_iter().chain([(self.address, account)]) {
+            changeset
+                .add_account_changeset(address, account)
+                .expect("addresses are unique");
+        }
+
+        Ok(changeset)
+    }
+}
+
+impl<R: ModuleResolver> ModuleResolver for MigrationView<'_, R> {
+    type Error = R::Error;
+
+    fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.resolver.get_module(module_id)
+    }
+
+    fn is_module_verified(&self, bytes: &[u8], config_hash: &[u8; 32]) -> bool {
+        self.resolver.is_module_verified(bytes, config_hash)
+    }
+
+    fn set_module_verified(&self, bytes: &[u8], config_hash: &[u8; 32]) {
+        self.resolver.set_module_verified(bytes, config_hash)
+    }
+}
+
+impl<R: ResourceResolver> ResourceResolver for MigrationView<'_, R> {
+    type Error = R::Error;
+
+    fn get_resource(
+        &self,
+        address: &AccountAddress,
+        tag: &StructTag,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        if *address == self.address && tag == self.tag {
+            return Ok(None);
+        }
+
+        self.resolver.get_resource(address, tag)
+    }
+}
+
+impl<R: BalanceResolver> BalanceResolver for MigrationView<'_, R> {
+    type Error = R::Error;
+
+    fn transfer(
+        &self,
+        src: AccountAddress,
+        dst: AccountAddress,
+        cheque_amount: u128,
+    ) -> Result<bool, Self::Error> {
+        self.resolver.transfer(src, dst, cheque_amount)
+    }
+
+    fn cheque_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
+        self.resolver.cheque_amount(account)
+    }
+
+    fn total_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
+        self.resolver.total_amount(account)
+    }
+
+    fn total_issuance(&self) -> Result<u128, Self::Error> {
+        self.resolver.total_issuance()
+    }
+
+    fn account_exists(&self, account: AccountAddress) -> Result<bool, Self::Error> {
+        self.resolver.account_exists(account)
+    }
+
+    fn minimum_balance(&self) -> Result<u128, Self::Error> {
+        self.resolver.minimum_balance()
+    }
+}
+
+impl<R: AccountHandler> AccountHandler for MigrationView<'_, R> {
+    fn account_exists(&self, account: AccountAddress) -> PartialVMResult<bool> {
+        self.resolver.account_exists(account)
+    }
+
+    fn create_account(
+        &self,
+        payer: AccountAddress,
+        account: AccountAddress,
+    ) -> PartialVMResult<bool> {
+        self.resolver.create_account(payer, account)
+    }
+}
+
+impl<R: ForeignCallHandler> ForeignCallHandler for MigrationView<'_, R> {
+    fn foreign_call(
+        &self,
+        target: u64,
+        payload: &[u8],
+        gas_limit: u64,
+    ) -> PartialVMResult<ForeignCallResponse> {
+        self.resolver.foreign_call(target, payload, gas_limit)
+    }
+}
+
+impl<R: XcmSender> XcmSender for MigrationView<'_, R> {
+    fn send_xcm(&self, origin: AccountAddress, message: &[u8]) -> PartialVMResult<bool> {
+        self.resolver.send_xcm(origin, message)
+    }
+}
+
+impl<R: HostWeightReporter> HostWeightReporter for MigrationView<'_, R> {
+    fn take_consumed_weight(&self) -> u64 {
+        self.resolver.take_consumed_weight()
+    }
+}
+
+impl<R: HashHandler> HashHandler for MigrationView<'_, R> {
+    fn sha2_256(&self, data: &[u8]) -> [u8; 32] {
+        self.resolver.sha2_256(data)
+    }
+
+    fn sha3_256(&self, data: &[u8]) -> [u8; 32] {
+        self.resolver.sha3_256(data)
+    }
+}
+
+impl<R: RandomnessHandler> RandomnessHandler for MigrationView<'_, R> {
+    fn random_seed(&self, subject: &[u8]) -> PartialVMResult<[u8; 32]> {
+        self.resolver.random_seed(subject)
+    }
+}
+
+impl<R: TimeHandler> TimeHandler for MigrationView<'_, R> {
+    fn now_milliseconds(&self) -> PartialVMResult<u64> {
+        self.resolver.now_milliseconds()
+    }
+}
@@ -0,0 +1,274 @@
+//! Deferred script execution which waits for the approval of the script signers.
+//!
+//! A script transaction with one or more signers can be submitted without being executed right
+//! away. The pending script is kept in the storage under its hash until every signer approves it -
+//! the final approval executes the script. No signer can therefore act on behalf of the others.
+//!
+//! M-of-N approvals are provided by the registered multisig accounts. The address of such an
+//! account is derived from its members and its threshold, so nobody holds its key. Once the
+//! account is one of the script signers, it's approved by `threshold` of its members instead -
+//! only the signer of the multisig account is passed to the script, never the signers of the
+//! members.
+
+use crate::storage::Storage;
+use crate::storage_key::{MULTISIG_ACCOUNT_KEY_PREFIX, PENDING_SCRIPT_KEY_PREFIX};
+use crate::types::VmResult;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use core::fmt;
+use move_core_types::{account_address::AccountAddress, vm_status::StatusCode};
+use move_vm_backend_common::{
+    bytecode::verify_script_integrity_and_check_signers, types::ScriptTransaction,
+};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+
+/// Hash of the encoded [`ScriptTransaction`] used to identify the pending script.
+pub type ScriptHash = [u8; 32];
+
+/// Error codes for the deferred script execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultisigError {
+    /// The transaction cannot be decoded.
+    InvalidTransaction,
+    /// The script bytecode is invalid.
+    InvalidScript(StatusCode),
+    /// The script doesn't have any signers, so it can be executed directly.
+    NoSigners,
+    /// The approval threshold is zero or exceeds the number of the multisig account members.
+    InvalidThreshold,
+    /// The same script transaction is already pending.
+    AlreadyPending,
+    /// There is no pending script under the given hash.
+    NotFound,
+    /// The approver is neither one of the script signers nor a member of a multisig signer.
+    NotASigner,
+    /// The approver has already approved the script.
+    AlreadyApproved,
+}
+
+impl fmt::Display for MultisigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidTransaction => write!(f, "Invalid script transaction"),
+            Self::InvalidScript(code) => write!(f, "Invalid script: {:?}", code),
+            Self::NoSigners => write!(f, "Script has no signers"),
+            Self::InvalidThreshold => write!(f, "Invalid approval threshold"),
+            Self::AlreadyPending => write!(f, "Script is already pending"),
+            Self::NotFound => write!(f, "Pending script not found"),
+            Self::NotASigner => write!(f, "Approver is not a script signer"),
+            Self::AlreadyApproved => write!(f, "Script already approved by the approver"),
+        }
+    }
+}
+
+/// Status of the pending script after an approval.
+#[derive(Debug)]
+pub enum MultisigStatus {
+    /// The script is still waiting for the approvals.
+    Pending {
+        /// Number of approvals still needed.
+        remaining_approvals: usize,
+    },
+    /// The script got all approvals and was executed.
+    ///
+    /// The pending script is removed regardless of the execution result.
+    Executed(VmResult),
+}
+
+/// Account approved by `threshold` of its members.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigAccount {
+    /// Accounts which can approve the scripts signed by the multisig account.
+    pub members: BTreeSet<AccountAddress>,
+    /// Number of the members which must approve the script.
+    pub threshold: usize,
+}
+
+impl MultisigAccount {
+    /// Creates a new [`MultisigAccount`] which needs the approval of `threshold` distinct members.
+    pub fn new(members: BTreeSet<AccountAddress>, threshold: usize) -> Result<Self, MultisigError> {
+        if threshold == 0 || threshold > members.len() {
+            return Err(MultisigError::InvalidThreshold);
+        }
+
+        Ok(Self { members, threshold })
+    }
+
+    /// Address of the multisig account derived from its members and its threshold.
+    pub fn address(&self) -> AccountAddress {
+        let blob = bcs::to_bytes(self).expect("multisig account serialization can't fail");
+        let hash = Sha3_256::new()
+            .chain_update(MULTISIG_ACCOUNT_KEY_PREFIX)
+            .chain_update(blob)
+            .finalize();
+
+        AccountAddress::from_bytes(&hash[..AccountAddress::LENGTH])
+            .expect("the hash is longer than the address")
+    }
+
+    /// Number of the member approvals still needed.
+    fn remaining_approvals(&self, approvals: &BTreeSet<AccountAddress>) -> usize {
+        let approved = self.members.intersection(approvals).count();
+        self.threshold.saturating_sub(approved)
+    }
+}
+
+/// A script transaction waiting for the signer approvals.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingScript {
+    /// Encoded [`ScriptTransaction`].
+    pub transaction: Vec<u8>,
+    /// Signers required to approve the script - read from the script argument list.
+    pub signers: BTreeSet<AccountAddress>,
+    /// Multisig accounts among the signers, which are approved by their members instead.
+    pub multisig_signers: BTreeMap<AccountAddress, MultisigAccount>,
+    /// Signers and multisig members which have already approved the script.
+    pub approvals: BTreeSet<AccountAddress>,
+}
+
+impl PendingScript {
+    /// Creates a new [`PendingScript`] from the encoded [`ScriptTransaction`] which needs the
+    /// approval of all its signers.
+    ///
+    /// The `multisig_account` lookup returns the registered multisig account for the signer.
+    pub(crate) fn new(
+        transaction: &[u8],
+        multisig_account: impl Fn(&AccountAddress) -> Option<MultisigAccount>,
+    ) -> Result<Self, MultisigError> {
+        let script = ScriptTransaction::try_from(transaction)
+            .map_err(|_| MultisigError::InvalidTransaction)?;
+
+        let signer_cnt = verify_script_integrity_and_check_signers(&script.bytecode)
+            .map_err(MultisigError::InvalidScript)?;
+        if signer_cnt == 0 {
+            return Err(MultisigError::NoSigners);
+        }
+
+        let signers = script
+            .args
+            .get(..signer_cnt)
+            .ok_or(MultisigError::InvalidTransaction)?
+            .iter()
+            .map(|arg| bcs::from_bytes(arg).map_err(|_| MultisigError::InvalidTransaction))
+            .collect::<Result<BTreeSet<_>, _>>()?;
+
+        let multisig_signers = signers
+            .iter()
+            .filter_map(|signer| Some((*signer, multisig_account(signer)?)))
+            .collect();
+
+        Ok(Self {
+            transaction: transaction.to_vec(),
+            signers,
+            multisig_signers,
+            approvals: BTreeSet::new(),
+        })
+    }
+
+    /// Number of approvals still needed to execute the script.
+    ///
+    /// A member shared by multiple multisig signers counts for each of them, so a single approval
+    /// can lower the number by more than one.
+    pub fn remaining_approvals(&self) -> usize {
+        self.signers
+            .iter()
+            .map(|signer| match self.multisig_signers.get(signer) {
+                Some(account) => account.remaining_approvals(&self.approvals),
+                None => usize::from(!self.approvals.contains(signer)),
+            })
+            .sum()
+    }
+
+    /// Check if every signer approved the script.
+    #[inline]
+    pub fn is_approved(&self) -> bool {
+        self.remaining_approvals() == 0
+    }
+
+    /// Adds an approval for the given signer or multisig member.
+    ///
+    /// The multisig accounts can't approve the scripts themselves.
+    pub(crate) fn approve(&mut self, approver: AccountAddress) -> Result<(), MultisigError> {
+        let is_signer =
+            self.signers.contains(&approver) && !self.multisig_signers.contains_key(&approver);
+        let is_member = self
+            .multisig_signers
+            .values()
+            .any(|account| account.members.contains(&approver));
+        if !is_signer && !is_member {
+            return Err(MultisigError::NotASigner);
+        }
+
+        if !self.approvals.insert(approver) {
+            return Err(MultisigError::AlreadyApproved);
+        }
+
+        Ok(())
+    }
+}
+
+/// Calculates the [`ScriptHash`] for the encoded [`ScriptTransaction`].
+pub fn script_hash(transaction: &[u8]) -> ScriptHash {
+    Sha3_256::digest(transaction).into()
+}
+
+/// Keeps the pending scripts in the storage.
+pub(crate) struct PendingScripts<'a, S: Storage> {
+    storage: &'a S,
+}
+
+impl<'a, S: Storage> PendingScripts<'a, S> {
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self { storage }
+    }
+
+    pub(crate) fn get(&self, hash: &ScriptHash) -> Option<PendingScript> {
+        let blob = self.storage.get(&Self::key(hash))?;
+
+        // Only this module writes under the prefixed keys, so the blob must be valid.
+        bcs::from_bytes(&blob).ok()
+    }
+
+    pub(crate) fn set(&self, hash: &ScriptHash, pending: &PendingScript) {
+        let blob = bcs::to_bytes(pending).expect("pending script serialization can't fail");
+        self.storage.set(&Self::key(hash), &blob);
+    }
+
+    pub(crate) fn remove(&self, hash: &ScriptHash) {
+        self.storage.remove(&Self::key(hash));
+    }
+
+    fn key(hash: &ScriptHash) -> Vec<u8> {
+        [PENDING_SCRIPT_KEY_PREFIX, hash.as_slice()].concat()
+    }
+}
+
+/// Keeps the registered multisig accounts in the storage.
+pub(crate) struct MultisigAccounts<'a, S: Storage> {
+    storage: &'a S,
+}
+
+impl<'a, S: Storage> MultisigAccounts<'a, S> {
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self { storage }
+    }
+
+    pub(crate) fn get(&self, address: &AccountAddress) -> Option<MultisigAccount> {
+        let blob = self.storage.get(&Self::key(address))?;
+
+        // Only this module writes under the prefixed keys, so the blob must be valid.
+        bcs::from_bytes(&blob).ok()
+    }
+
+    pub(crate) fn set(&self, account: &MultisigAccount) {
+        let blob = bcs::to_bytes(account).expect("multisig account serialization can't fail");
+        self.storage.set(&Self::key(&account.address()), &blob);
+    }
+
+    fn key(address: &AccountAddress) -> Vec<u8> {
+        [MULTISIG_ACCOUNT_KEY_PREFIX, address.as_slice()].concat()
+    }
+}
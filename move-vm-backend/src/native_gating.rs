@@ -0,0 +1,41 @@
+//! Switching off the selected native functions.
+//!
+//! Chains ship the full stdlib, but some natives may not be ready to be used yet, e.g. the
+//! foreign calls before the target pallets are deployed. The embedder gates such natives with
+//! [`crate::Mvm::set_gated_natives`], typically from a governance-controlled storage item.
+//!
+//! The modules using the gated natives still publish and load. Calling a gated native fails the
+//! transaction with the `FEATURE_UNDER_GATING` status code, until the native is removed from the
+//! gated set again.
+
+use alloc::{collections::BTreeSet, format, sync::Arc};
+use move_binary_format::errors::PartialVMError;
+use move_core_types::{identifier::Identifier, language_storage::ModuleId, vm_status::StatusCode};
+use move_vm_runtime::native_functions::{NativeFunction, NativeFunctionTable};
+
+/// Native function which can be gated, e.g. `(0x1::foreign, call)`.
+pub type GatedNative = (ModuleId, Identifier);
+
+/// Replace the gated natives in the table with the functions failing with the
+/// `FEATURE_UNDER_GATING` status code.
+pub(crate) fn gate_natives(
+    natives: NativeFunctionTable,
+    gated: &BTreeSet<GatedNative>,
+) -> NativeFunctionTable {
+    natives
+        .into_iter()
+        .map(|(address, module, function, native)| {
+            let id = (ModuleId::new(address, module.clone()), function.clone());
+            if !gated.contains(&id) {
+                return (address, module, function, native);
+            }
+
+            let message = format!("Native function {}::{} is gated", id.0, id.1);
+            let native: NativeFunction = Arc::new(move |_, _, _| {
+                Err(PartialVMError::new(StatusCode::FEATURE_UNDER_GATING)
+                    .with_message(message.clone()))
+            });
+            (address, module, function, native)
+        })
+        .collect()
+}
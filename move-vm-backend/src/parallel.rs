@@ -0,0 +1,313 @@
+//! Optimistic block execution with the read/write conflict detection.
+//!
+//! All transactions of the block are first executed speculatively on the storage state from
+//! before the block, while the resources each transaction reads are recorded. The transactions
+//! are then committed in the block order - a transaction which read any resource written by the
+//! already committed transactions is re-executed on the current state. The final state and the
+//! results are therefore identical to the sequential execution.
+//!
+//! The host bindings execute the balance transfers and the foreign calls immediately, so the
+//! speculative execution can't observe them. Transactions accessing the balances, the randomness
+//! or the time, or making the foreign calls are always re-executed.
+//!
+//! With the `parallel` feature, the speculative phase runs on multiple threads. Each thread uses
+//! its own MoveVM instance, since the MoveVM loader caches are not thread-safe.
+//!
+//! Only resources are tracked - scripts can't publish modules, so the modules never change within
+//! the block.
+
+use crate::host::HostBindings;
+use crate::storage::Storage;
+use crate::types::{ExecutionConfig, GasAmount, GasHandler, GasStrategy, Transaction};
+use crate::warehouse::Warehouse;
+use crate::TransactionOutput;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use anyhow::Error;
+use core::cell::{Cell, RefCell};
+use move_binary_format::errors::{PartialVMError, PartialVMResult, VMResult};
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::ChangeSet,
+    language_storage::{ModuleId, StructTag},
+    resolver::{BalanceResolver, ModuleResolver, ResourceResolver},
+    vm_status::StatusCode,
+};
+use move_stdlib::natives::{
+    account::AccountHandler,
+    foreign::{ForeignCallHandler, ForeignCallResponse},
+    hash::HashHandler,
+    host_gas::HostWeightReporter,
+    random::RandomnessHandler,
+    timestamp::TimeHandler,
+    xcm::XcmSender,
+};
+use move_vm_runtime::move_vm::MoveVM;
+
+/// Resource accessed by the transaction.
+type ResourceKey = (AccountAddress, StructTag);
+
+/// Outcome of the speculative transaction execution.
+pub(crate) struct Speculation {
+    /// Execution result - `None` if the transaction must be re-executed during the commit.
+    outcome: Option<(VMResult<TransactionOutput>, GasHandler)>,
+    /// Resources read by the transaction.
+    reads: BTreeSet<ResourceKey>,
+}
+
+impl Speculation {
+    /// A speculation which always requires the re-execution.
+    pub(crate) fn invalid() -> Self {
+        Self {
+            outcome: None,
+            reads: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the speculative outcome if none of the read resources were written in the meantime.
+    pub(crate) fn validate(
+        self,
+        written: &BTreeSet<ResourceKey>,
+    ) -> Option<(VMResult<TransactionOutput>, GasHandler)> {
+        if self.reads.iter().any(|key| written.contains(key)) {
+            return None;
+        }
+
+        self.outcome
+    }
+}
+
+/// Resources written by the changeset.
+pub(crate) fn written_resources(changeset: &ChangeSet) -> impl Iterator<Item = ResourceKey> + '_ {
+    changeset
+        .resources()
+        .map(|(address, tag, _)| (address, tag.clone()))
+}
+
+/// Executes the transaction on the current storage state without applying any changes.
+pub(crate) fn speculate<S: Storage, H: HostBindings>(
+    vm: &MoveVM,
+    warehouse: &Warehouse<S, H>,
+    transaction: Transaction,
+    gas: GasStrategy,
+    config: ExecutionConfig,
+    call_gas_limits: &BTreeMap<ModuleId, GasAmount>,
+) -> Speculation {
+    let snapshot = SnapshotView::new(warehouse);
+    let mut gas_handler = GasHandler::for_execution(gas, config, call_gas_limits);
+    let result = crate::execute_transaction(vm, &snapshot, transaction, &mut gas_handler);
+
+    if snapshot.host_accessed.get() {
+        return Speculation::invalid();
+    }
+
+    Speculation {
+        outcome: Some((result, gas_handler)),
+        reads: snapshot.reads.into_inner(),
+    }
+}
+
+/// Executes all transactions speculatively on multiple threads.
+#[cfg(feature = "parallel")]
+pub(crate) fn speculate_in_parallel<S, H>(
+    warehouse: &Warehouse<S, H>,
+    transactions: &[Transaction],
+    gas: GasStrategy,
+    config: ExecutionConfig,
+    call_gas_limits: &BTreeMap<ModuleId, GasAmount>,
+    gated_natives: &BTreeSet<crate::native_gating::GatedNative>,
+    interrupt: &move_vm_runtime::config::InterruptHandle,
+) -> Vec<Speculation>
+where
+    S: Storage + Sync,
+    H: HostBindings + Sync,
+{
+    let threads = std::thread::available_parallelism().map_or(1, usize::from);
+    let chunk_size = transactions.len().div_ceil(threads).max(1);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = transactions
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    match crate::new_move_vm(gated_natives, config.max_call_depth, interrupt) {
+                        Ok(vm) => chunk
+                            .iter()
+                            .map(|tx| {
+                                speculate(&vm, warehouse, tx.clone(), gas, config, call_gas_limits)
+                            })
+                            .collect(),
+                        // The transactions get executed during the commit instead.
+                        Err(_) => chunk.iter().map(|_| Speculation::invalid()).collect(),
+                    }
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("speculative execution can't panic"))
+            .collect()
+    })
+}
+
+/// Read-only view of the warehouse which records the accessed resources.
+struct SnapshotView<'a, S: Storage, H: HostBindings> {
+    warehouse: &'a Warehouse<S, H>,
+    reads: RefCell<BTreeSet<ResourceKey>>,
+    host_accessed: Cell<bool>,
+}
+
+impl<'a, S: Storage, H: HostBindings> SnapshotView<'a, S, H> {
+    fn new(warehouse: &'a Warehouse<S, H>) -> Self {
+        Self {
+            warehouse,
+            reads: RefCell::new(BTreeSet::new()),
+            host_accessed: Cell::new(false),
+        }
+    }
+
+    /// Aborts the speculative execution which tries to access the host services.
+    fn host_access(&self) -> Result<(), StatusCode> {
+        self.host_accessed.set(true);
+        Err(StatusCode::STORAGE_ERROR)
+    }
+}
+
+impl<'a, S: Storage, H: HostBindings> ModuleResolver for SnapshotView<'a, S, H> {
+    type Error = Error;
+
+    fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.warehouse.get_module(module_id)
+    }
+
+    fn is_module_verified(&self, bytes: &[u8], config_hash: &[u8; 32]) -> bool {
+        self.warehouse.is_module_verified(bytes, config_hash)
+    }
+
+    // The view is read-only - the markers are recorded once the module is loaded during the
+    // sequential execution.
+    fn set_module_verified(&self, _bytes: &[u8], _config_hash: &[u8; 32]) {}
+}
+
+impl<'a, S: Storage, H: HostBindings> ResourceResolver for SnapshotView<'a, S, H> {
+    type Error = Error;
+
+    fn get_resource(
+        &self,
+        address: &AccountAddress,
+        tag: &StructTag,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.reads.borrow_mut().insert((*address, tag.clone()));
+        self.warehouse.get_resource(address, tag)
+    }
+}
+
+impl<'a, S: Storage, H: HostBindings> BalanceResolver for SnapshotView<'a, S, H> {
+    type Error = StatusCode;
+
+    fn transfer(
+        &self,
+        _src: AccountAddress,
+        _dst: AccountAddress,
+        _cheque_amount: u128,
+    ) -> Result<bool, Self::Error> {
+        self.host_access().map(|_| false)
+    }
+
+    fn cheque_amount(&self, _account: AccountAddress) -> Result<u128, Self::Error> {
+        self.host_access().map(|_| 0)
+    }
+
+    fn total_amount(&self, _account: AccountAddress) -> Result<u128, Self::Error> {
+        self.host_access().map(|_| 0)
+    }
+
+    fn total_issuance(&self) -> Result<u128, Self::Error> {
+        self.host_access().map(|_| 0)
+    }
+
+    fn account_exists(&self, _account: AccountAddress) -> Result<bool, Self::Error> {
+        self.host_access().map(|_| false)
+    }
+
+    fn minimum_balance(&self) -> Result<u128, Self::Error> {
+        self.host_access().map(|_| 0)
+    }
+}
+
+impl<'a, S: Storage, H: HostBindings> AccountHandler for SnapshotView<'a, S, H> {
+    fn account_exists(&self, _account: AccountAddress) -> PartialVMResult<bool> {
+        self.host_access()
+            .map(|_| false)
+            .map_err(PartialVMError::new)
+    }
+
+    fn create_account(
+        &self,
+        _payer: AccountAddress,
+        _account: AccountAddress,
+    ) -> PartialVMResult<bool> {
+        self.host_access()
+            .map(|_| false)
+            .map_err(PartialVMError::new)
+    }
+}
+
+impl<'a, S: Storage, H: HostBindings> ForeignCallHandler for SnapshotView<'a, S, H> {
+    fn foreign_call(
+        &self,
+        _target: u64,
+        _payload: &[u8],
+        _gas_limit: u64,
+    ) -> PartialVMResult<ForeignCallResponse> {
+        self.host_access()
+            .map(|_| ForeignCallResponse {
+                gas_used: 0,
+                result: Ok(Vec::new()),
+            })
+            .map_err(PartialVMError::new)
+    }
+}
+
+impl<'a, S: Storage, H: HostBindings> XcmSender for SnapshotView<'a, S, H> {
+    fn send_xcm(&self, _origin: AccountAddress, _message: &[u8]) -> PartialVMResult<bool> {
+        self.host_access()
+            .map(|_| false)
+            .map_err(PartialVMError::new)
+    }
+}
+
+// Every host call aborts the speculation, so there is never any weight to report.
+impl<'a, S: Storage, H: HostBindings> HostWeightReporter for SnapshotView<'a, S, H> {
+    fn take_consumed_weight(&self) -> u64 {
+        0
+    }
+}
+
+// Hashing has no side effects, so the speculation can use it.
+impl<'a, S: Storage, H: HostBindings> HashHandler for SnapshotView<'a, S, H> {
+    fn sha2_256(&self, data: &[u8]) -> [u8; 32] {
+        self.warehouse.sha2_256(data)
+    }
+
+    fn sha3_256(&self, data: &[u8]) -> [u8; 32] {
+        self.warehouse.sha3_256(data)
+    }
+}
+
+impl<'a, S: Storage, H: HostBindings> RandomnessHandler for SnapshotView<'a, S, H> {
+    fn random_seed(&self, _subject: &[u8]) -> PartialVMResult<[u8; 32]> {
+        self.host_access()
+            .map(|_| [0; 32])
+            .map_err(PartialVMError::new)
+    }
+}
+
+impl<'a, S: Storage, H: HostBindings> TimeHandler for SnapshotView<'a, S, H> {
+    fn now_milliseconds(&self) -> PartialVMResult<u64> {
+        self.host_access().map(|_| 0).map_err(PartialVMError::new)
+    }
+}
@@ -0,0 +1,218 @@
+//! Speculative pre-verification of the code waiting in the transaction pool.
+//!
+//! Deserialization and bytecode verification don't depend on the storage state, so they can run
+//! before the block is authored. The [`PreverificationQueue`] is fed with the scripts and modules
+//! of the pending transactions, verifies the new ones on multiple threads and caches the outcome
+//! under the code hash - see [`move_vm_backend_common::code_hash`].
+//!
+//! Before executing the block, the author passes the queue to [`Mvm::preload_verified`], which
+//! hands the verified artifacts to the MoveVM, so the execution only loads and links the
+//! dependencies. Included transactions should be evicted from the queue afterwards.
+//!
+//! Code which failed the verification is cached as well, so the pool can drop such transactions
+//! without re-verifying them.
+//!
+//! [`Mvm::preload_verified`]: crate::Mvm::preload_verified
+
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use move_binary_format::file_format::{CompiledModule, CompiledScript};
+use move_core_types::vm_status::StatusCode;
+use move_vm_backend_common::code_hash::{module_hash, script_hash};
+use move_vm_runtime::config::VMConfig;
+use sha3::{Digest, Sha3_256};
+use std::sync::Mutex;
+
+pub use move_vm_backend_common::code_hash::CodeHash;
+
+/// Key of the script in the MoveVM loader - the SHA3-256 hash of the bytecode.
+pub(crate) type LoaderKey = [u8; 32];
+
+/// Code of a transaction waiting in the pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PendingCode {
+    /// Script bytecode.
+    Script(Vec<u8>),
+    /// Bytecode of a single module - bundles are fed module by module.
+    Module(Vec<u8>),
+}
+
+impl PendingCode {
+    /// Hash of the script or the module.
+    pub fn hash(&self) -> CodeHash {
+        match self {
+            Self::Script(bytecode) => script_hash(bytecode),
+            Self::Module(bytecode) => module_hash(bytecode),
+        }
+    }
+
+    fn loader_key(&self) -> LoaderKey {
+        match self {
+            Self::Script(bytecode) | Self::Module(bytecode) => Sha3_256::digest(bytecode).into(),
+        }
+    }
+}
+
+/// Deserialized code which passed the bytecode verifier.
+#[derive(Debug, Clone)]
+pub enum VerifiedCode {
+    Script(Arc<CompiledScript>),
+    Module(Arc<CompiledModule>),
+}
+
+/// Outcome of the pre-verification - the status code of the failed ones.
+pub type Preverification = Result<VerifiedCode, StatusCode>;
+
+/// Outcome of the pre-verification along with the loader key of the code.
+struct Entry {
+    loader_key: LoaderKey,
+    outcome: Preverification,
+}
+
+/// Cache of the pre-verified code from the transaction pool.
+pub struct PreverificationQueue {
+    config: VMConfig,
+    cache: Mutex<BTreeMap<CodeHash, Entry>>,
+}
+
+impl Default for PreverificationQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PreverificationQueue {
+    /// Create an empty queue using the same verifier config as the [`Mvm`](crate::Mvm).
+    pub fn new() -> Self {
+        Self {
+            config: VMConfig::default(),
+            cache: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Pre-verifies the pool contents which aren't cached yet and returns their number.
+    ///
+    /// The code is verified on all available threads and the cache isn't locked meanwhile.
+    pub fn feed(&self, pool: impl IntoIterator<Item = PendingCode>) -> usize {
+        let mut pending: BTreeMap<CodeHash, PendingCode> = BTreeMap::new();
+        {
+            let cache = self.lock();
+            for code in pool {
+                let hash = code.hash();
+                if !cache.contains_key(&hash) {
+                    pending.insert(hash, code);
+                }
+            }
+        }
+
+        if pending.is_empty() {
+            return 0;
+        }
+
+        let pending: Vec<_> = pending.into_iter().collect();
+        let threads = std::thread::available_parallelism().map_or(1, usize::from);
+        let chunk_size = pending.len().div_ceil(threads).max(1);
+
+        let verified: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = pending
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|(hash, code)| {
+                                let entry = Entry {
+                                    loader_key: code.loader_key(),
+                                    outcome: self.verify(code),
+                                };
+                                (*hash, entry)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("pre-verification can't panic"))
+                .collect()
+        });
+
+        let count = verified.len();
+        self.lock().extend(verified);
+        count
+    }
+
+    /// Outcome of the pre-verification - `None` if the code wasn't fed yet.
+    pub fn get(&self, hash: &CodeHash) -> Option<Preverification> {
+        self.lock().get(hash).map(|entry| entry.outcome.clone())
+    }
+
+    /// Removes the code, e.g. once its transaction is included in a block or dropped from the pool.
+    pub fn evict<'a>(&self, hashes: impl IntoIterator<Item = &'a CodeHash>) {
+        let mut cache = self.lock();
+        for hash in hashes {
+            cache.remove(hash);
+        }
+    }
+
+    /// Removes all cached code.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Number of the cached entries, including the failed ones.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check if the queue has no cached entries.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Verified code among the given hashes with its loader keys - failed and unknown ones are
+    /// skipped.
+    pub(crate) fn verified<'a>(
+        &self,
+        hashes: impl IntoIterator<Item = &'a CodeHash>,
+    ) -> Vec<(LoaderKey, VerifiedCode)> {
+        let cache = self.lock();
+        hashes
+            .into_iter()
+            .filter_map(|hash| match cache.get(hash) {
+                Some(Entry {
+                    loader_key,
+                    outcome: Ok(code),
+                }) => Some((*loader_key, code.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn verify(&self, code: &PendingCode) -> Preverification {
+        let version = self.config.max_binary_format_version;
+        let verifier = &self.config.verifier;
+
+        match code {
+            PendingCode::Script(bytecode) => {
+                let script = CompiledScript::deserialize_with_max_version(bytecode, version)
+                    .map_err(|_| StatusCode::CODE_DESERIALIZATION_ERROR)?;
+                move_bytecode_verifier::verify_script_with_config(verifier, &script)
+                    .map_err(|err| err.major_status())?;
+                Ok(VerifiedCode::Script(Arc::new(script)))
+            }
+            PendingCode::Module(bytecode) => {
+                let module = CompiledModule::deserialize_with_max_version(bytecode, version)
+                    .map_err(|_| StatusCode::CODE_DESERIALIZATION_ERROR)?;
+                move_bytecode_verifier::verify_module_with_config(verifier, &module)
+                    .map_err(|err| err.major_status())?;
+                Ok(VerifiedCode::Module(Arc::new(module)))
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<CodeHash, Entry>> {
+        // The cache stays consistent even if a holder of the lock panicked.
+        self.cache.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
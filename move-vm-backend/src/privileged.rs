@@ -0,0 +1,25 @@
+//! Privileged module publishing at the reserved addresses.
+//!
+//! Nobody holds the keys of the framework addresses like `0x1`, so their modules can only be
+//! deployed or patched through a privileged path, e.g. by the governance or the root origin.
+//! Once an address is reserved with [`crate::Mvm::set_reserved_addresses`], the regular publish
+//! methods reject it and only [`crate::Mvm::publish_module_as`] and
+//! [`crate::Mvm::publish_module_bundle_as`] can publish there.
+//!
+//! The privileged methods require a [`PublishCapability`], which is an embedder-supplied token
+//! type. The embedder should make sure the token can only be constructed after the privileged
+//! origin is verified, e.g. by keeping its constructor private to the governance code:
+//! ```ignore
+//! pub struct RootPublish(());
+//!
+//! impl PublishCapability for RootPublish {}
+//!
+//! fn publish_framework(origin: Origin, module: Vec<u8>) -> Result<(), Error> {
+//!     ensure_root(origin)?;
+//!     let result = mvm.publish_module_as(&RootPublish(()), &module, CORE_CODE_ADDRESS, gas);
+//!     ...
+//! }
+//! ```
+
+/// Proof that the caller may publish modules at any address, including the reserved ones.
+pub trait PublishCapability {}
@@ -0,0 +1,331 @@
+//! Per-function gas profiling.
+//!
+//! The [`ProfilingGasMeter`] wraps the gas meter used by the MoveVM and follows the call stack of
+//! the interpreter: the gas charged between two stack changes is attributed to the function on
+//! top of the stack. The profile contains the gas consumed by each function's own instructions -
+//! the gas consumed by its callees is attributed to the callees.
+
+use crate::types::GasProfile;
+use alloc::{string::String, vec::Vec};
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::{
+    gas_algebra::{InternalGas, NumArgs, NumBytes},
+    language_storage::ModuleId,
+};
+use move_vm_types::{
+    gas::{GasMeter, SimpleInstruction},
+    views::{TypeView, ValueView},
+};
+
+/// Name of the function in the gas profile.
+pub(crate) fn function_name(module_id: &ModuleId, func_name: &str) -> String {
+    alloc::format!("{}::{}", module_id.short_str_lossless(), func_name)
+}
+
+/// Collects the gas consumed by each called function in internal gas units.
+pub(crate) struct GasProfiler {
+    /// Functions currently on the interpreter call stack.
+    stack: Vec<String>,
+    /// Gas balance at the last stack change.
+    checkpoint: u64,
+    /// Gas consumed per function.
+    profile: GasProfile,
+}
+
+impl GasProfiler {
+    /// Starts profiling the execution of the entry function with the given starting balance.
+    pub(crate) fn new(entry: String, balance: InternalGas) -> Self {
+        Self {
+            stack: alloc::vec![entry],
+            checkpoint: balance.into(),
+            profile: GasProfile::new(),
+        }
+    }
+
+    /// Attributes the gas charged since the last checkpoint to the function on top of the stack.
+    fn attribute(&mut self, balance: InternalGas) {
+        let balance: u64 = balance.into();
+        let spent = self.checkpoint.saturating_sub(balance);
+        self.checkpoint = balance;
+
+        if let Some(function) = self.stack.last() {
+            let gas = self.profile.entry(function.clone()).or_default();
+            *gas = gas.saturating_add(spent);
+        }
+    }
+
+    fn enter(&mut self, balance: InternalGas, function: String) {
+        self.attribute(balance);
+        self.stack.push(function);
+    }
+
+    fn exit(&mut self, balance: InternalGas) {
+        self.attribute(balance);
+        self.stack.pop();
+    }
+
+    /// Finishes the profiling and returns the profile in internal gas units.
+    ///
+    /// The gas charged after the last stack change (e.g. before an abort) is attributed to the
+    /// function which was executing at that point.
+    pub(crate) fn finish(mut self, balance: InternalGas) -> GasProfile {
+        self.attribute(balance);
+        self.profile
+    }
+}
+
+/// Gas meter which feeds the [`GasProfiler`] while delegating all charges to the inner meter.
+pub(crate) struct ProfilingGasMeter<'a, G: GasMeter> {
+    pub(crate) meter: &'a mut G,
+    pub(crate) profiler: &'a mut GasProfiler,
+}
+
+impl<G: GasMeter> GasMeter for ProfilingGasMeter<'_, G> {
+    fn balance_internal(&self) -> InternalGas {
+        self.meter.balance_internal()
+    }
+
+    fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
+        self.meter.charge_simple_instr(instr)?;
+
+        if instr == SimpleInstruction::Ret {
+            self.profiler.exit(self.meter.balance_internal());
+        }
+
+        Ok(())
+    }
+
+    fn charge_back_edge(&mut self) -> PartialVMResult<()> {
+        self.meter.charge_back_edge()
+    }
+
+    fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_pop(popped_val)
+    }
+
+    fn charge_call(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_call(module_id, func_name, args, num_locals)?;
+
+        // The call instruction itself is paid by the caller.
+        self.profiler.enter(
+            self.meter.balance_internal(),
+            function_name(module_id, func_name),
+        );
+
+        Ok(())
+    }
+
+    fn charge_call_generic(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_call_generic(module_id, func_name, ty_args, args, num_locals)?;
+
+        self.profiler.enter(
+            self.meter.balance_internal(),
+            function_name(module_id, func_name),
+        );
+
+        Ok(())
+    }
+
+    fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
+        self.meter.charge_ld_const(size)
+    }
+
+    fn charge_ld_const_after_deserialization(
+        &mut self,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_ld_const_after_deserialization(val)
+    }
+
+    fn charge_copy_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_copy_loc(val)
+    }
+
+    fn charge_move_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_move_loc(val)
+    }
+
+    fn charge_store_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_store_loc(val)
+    }
+
+    fn charge_pack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_pack(is_generic, args)
+    }
+
+    fn charge_unpack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_unpack(is_generic, args)
+    }
+
+    fn charge_read_ref(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_read_ref(val)
+    }
+
+    fn charge_write_ref(
+        &mut self,
+        new_val: impl ValueView,
+        old_val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_write_ref(new_val, old_val)
+    }
+
+    fn charge_eq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_eq(lhs, rhs)
+    }
+
+    fn charge_neq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.meter.charge_neq(lhs, rhs)
+    }
+
+    fn charge_borrow_global(
+        &mut self,
+        is_mut: bool,
+        is_generic: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_borrow_global(is_mut, is_generic, ty, is_success)
+    }
+
+    fn charge_exists(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        exists: bool,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_exists(is_generic, ty, exists)
+    }
+
+    fn charge_move_from(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_move_from(is_generic, ty, val)
+    }
+
+    fn charge_move_to(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: impl ValueView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_move_to(is_generic, ty, val, is_success)
+    }
+
+    fn charge_vec_pack<'a>(
+        &mut self,
+        ty: impl TypeView + 'a,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_vec_pack(ty, args)
+    }
+
+    fn charge_vec_len(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.meter.charge_vec_len(ty)
+    }
+
+    fn charge_vec_borrow(
+        &mut self,
+        is_mut: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_vec_borrow(is_mut, ty, is_success)
+    }
+
+    fn charge_vec_push_back(
+        &mut self,
+        ty: impl TypeView,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_vec_push_back(ty, val)
+    }
+
+    fn charge_vec_pop_back(
+        &mut self,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_vec_pop_back(ty, val)
+    }
+
+    fn charge_vec_unpack(
+        &mut self,
+        ty: impl TypeView,
+        expect_num_elements: NumArgs,
+        elems: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_vec_unpack(ty, expect_num_elements, elems)
+    }
+
+    fn charge_vec_swap(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.meter.charge_vec_swap(ty)
+    }
+
+    fn charge_load_resource(
+        &mut self,
+        loaded: Option<(NumBytes, impl ValueView)>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_load_resource(loaded)
+    }
+
+    fn charge_native_function(
+        &mut self,
+        amount: InternalGas,
+        ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView>>,
+    ) -> PartialVMResult<()> {
+        let result = self.meter.charge_native_function(amount, ret_vals);
+
+        // Native functions have no frames, so they return right after being charged.
+        self.profiler.exit(self.meter.balance_internal());
+
+        result
+    }
+
+    fn charge_native_function_before_execution(
+        &mut self,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter
+            .charge_native_function_before_execution(ty_args, args)
+    }
+
+    fn charge_drop_frame(
+        &mut self,
+        locals: impl Iterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.meter.charge_drop_frame(locals)
+    }
+
+    fn charge_dependency(&mut self, module_id: &ModuleId, size: NumBytes) -> PartialVMResult<()> {
+        self.meter.charge_dependency(module_id, size)
+    }
+}
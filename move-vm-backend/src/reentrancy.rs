@@ -0,0 +1,35 @@
+//! Reentrancy guard of the balance calls.
+//!
+//! The balance natives hand the control over to the host, which could call back into the MoveVM
+//! before the balance change is done, e.g. from a transfer hook of the assets pallet. The re-entered
+//! execution would see the balances half-updated, which is the classic reentrancy drain.
+//!
+//! While the host handles a guarded balance call, the MoveVM instance keeps a flag in memory, so
+//! the guard costs no storage writes. Re-entering the instance or making another guarded balance
+//! call in the meantime fails with the `REENTRANT_BALANCE_CALL` status code. The flag isn't shared
+//! between the instances, so the host must re-enter the instance which made the call.
+//!
+//! The strictness is set with [`crate::Mvm::set_reentrancy_guard`].
+
+/// Balance calls guarded against the reentrancy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReentrancyGuard {
+    /// No balance call is guarded.
+    Disabled,
+    /// The balance-affecting calls - transfers and account creation - are guarded.
+    #[default]
+    BalanceChanges,
+    /// All balance calls are guarded, including the balance queries.
+    Strict,
+}
+
+impl ReentrancyGuard {
+    /// Check whether the balance call is guarded.
+    pub(crate) fn guards(self, changes_balance: bool) -> bool {
+        match self {
+            Self::Disabled => false,
+            Self::BalanceChanges => changes_balance,
+            Self::Strict => true,
+        }
+    }
+}
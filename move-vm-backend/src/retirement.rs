@@ -0,0 +1,217 @@
+//! Retirement of the published modules.
+//!
+//! [`crate::Mvm::retire_module`] removes a module from the storage only when nothing can break:
+//! - no other published module depends on it,
+//! - no resource of its types exists, including the resources which use its types as the type
+//!   arguments, e.g. `Coin<Retired::Token>`,
+//! - its account isn't frozen.
+//!
+//! The governance can skip the checks with [`crate::Mvm::force_retire_module`], which requires a
+//! [`crate::privileged::PublishCapability`].
+//!
+//! Storage can't be enumerated, so the checks rely on the indexes kept up to date whenever a
+//! changeset is applied: the dependents of every published module, and the number of the
+//! resources of its types. The dependent sets only ever grow - a dependent which was upgraded
+//! to drop the dependency, or retired, is filtered out when the index is read.
+//!
+//! A module is indexed from its first publication onwards: all its dependents and the resources
+//! of its types can only appear after it. Modules published before the indexes existed have no
+//! resource count, and only the governance can retire them.
+
+use crate::{
+    storage::Storage,
+    storage_key::{DEPENDENTS_KEY_PREFIX, RESOURCE_COUNT_KEY_PREFIX},
+    MAX_BINARY_FORMAT_VERSION,
+};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use core::fmt;
+use move_binary_format::{access::ModuleAccess, CompiledModule};
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::{AccountChangeSet, Op},
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
+
+/// Error of retiring a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RetireError {
+    /// The module isn't published.
+    NotFound,
+    /// Published modules depend on the module.
+    HasDependents(Vec<ModuleId>),
+    /// Resources of the module types exist.
+    HasResources(u64),
+    /// The module was published before the indexes existed, so its users aren't known.
+    NotIndexed,
+    /// The account of the module is frozen.
+    AccountFrozen,
+    /// The storage couldn't be read or written.
+    StorageError,
+}
+
+impl fmt::Display for RetireError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "Module not found"),
+            Self::HasDependents(dependents) => {
+                write!(f, "Module has {} dependent modules", dependents.len())
+            }
+            Self::HasResources(count) => write!(f, "{count} resources of the module types exist"),
+            Self::NotIndexed => write!(f, "Module isn't indexed"),
+            Self::AccountFrozen => write!(f, "Account of the module is frozen"),
+            Self::StorageError => write!(f, "Storage error"),
+        }
+    }
+}
+
+/// Keeps the dependents and the resource counts of the modules in the storage.
+pub(crate) struct ModuleIndex<'a, S: Storage> {
+    storage: &'a S,
+}
+
+impl<'a, S: Storage> ModuleIndex<'a, S> {
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self { storage }
+    }
+
+    /// Modules which depended on the module when they were published.
+    pub(crate) fn dependents(&self, module: &ModuleId) -> BTreeSet<ModuleId> {
+        self.storage
+            .get(&Self::key(DEPENDENTS_KEY_PREFIX, module))
+            .and_then(|bytes| bcs::from_bytes(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Number of the resources of the module types, or `None` if the module isn't indexed.
+    pub(crate) fn resource_count(&self, module: &ModuleId) -> Option<u64> {
+        self.storage
+            .get(&Self::key(RESOURCE_COUNT_KEY_PREFIX, module))
+            .and_then(|bytes| bcs::from_bytes(&bytes).ok())
+    }
+
+    /// Store the index changes of an applied account changeset.
+    pub(crate) fn record(&self, changes: IndexChanges) {
+        for published in changes.published {
+            if published.new && self.resource_count(&published.module).is_none() {
+                self.set(RESOURCE_COUNT_KEY_PREFIX, &published.module, &0u64);
+            }
+
+            for dependency in published.dependencies {
+                let mut dependents = self.dependents(&dependency);
+                if dependents.insert(published.module.clone()) {
+                    self.set(DEPENDENTS_KEY_PREFIX, &dependency, &dependents);
+                }
+            }
+        }
+
+        for (module, (created, deleted)) in changes.resources {
+            // Resources of the modules which aren't indexed can't be counted reliably.
+            let Some(count) = self.resource_count(&module) else {
+                continue;
+            };
+            let count = count.saturating_add(created).saturating_sub(deleted);
+            self.set(RESOURCE_COUNT_KEY_PREFIX, &module, &count);
+        }
+    }
+
+    /// Remove the index entries of the retired module.
+    pub(crate) fn remove(&self, module: &ModuleId) {
+        self.storage
+            .remove(&Self::key(DEPENDENTS_KEY_PREFIX, module));
+        self.storage
+            .remove(&Self::key(RESOURCE_COUNT_KEY_PREFIX, module));
+    }
+
+    fn set<T: serde::Serialize>(&self, prefix: &[u8], module: &ModuleId, value: &T) {
+        let bytes = bcs::to_bytes(value).expect("index entries are always serializable");
+        self.storage.set(&Self::key(prefix, module), &bytes);
+    }
+
+    fn key(prefix: &[u8], module: &ModuleId) -> Vec<u8> {
+        let module = bcs::to_bytes(module).expect("module IDs are always serializable");
+        [prefix, &module].concat()
+    }
+}
+
+/// Index changes of an account changeset - see [`IndexChanges::new`].
+///
+/// They're collected before the changeset is applied, and stored only once it was applied.
+#[derive(Default)]
+pub(crate) struct IndexChanges {
+    published: Vec<PublishedModule>,
+    /// Numbers of the created and deleted resources per module.
+    resources: BTreeMap<ModuleId, (u64, u64)>,
+}
+
+struct PublishedModule {
+    module: ModuleId,
+    /// Published for the first time rather than upgraded.
+    new: bool,
+    dependencies: Vec<ModuleId>,
+}
+
+impl IndexChanges {
+    /// Collect the published modules and the created or deleted resources of the account.
+    pub(crate) fn new(address: AccountAddress, changeset: &AccountChangeSet) -> Self {
+        let mut changes = Self::default();
+        for (name, op) in changeset.modules() {
+            let (new, bytes) = match op {
+                Op::New(bytes) => (true, bytes),
+                Op::Modify(bytes) => (false, bytes),
+                Op::Delete => continue,
+            };
+            // Published modules were deserialized by the MoveVM already.
+            let Ok(module) =
+                CompiledModule::deserialize_with_max_version(bytes, MAX_BINARY_FORMAT_VERSION)
+            else {
+                continue;
+            };
+
+            changes.published.push(PublishedModule {
+                module: ModuleId::new(address, name.clone()),
+                new,
+                dependencies: module.immediate_dependencies(),
+            });
+        }
+
+        for (tag, op) in changeset.resources() {
+            let created = match op {
+                Op::New(_) => true,
+                Op::Delete => false,
+                Op::Modify(_) => continue,
+            };
+
+            let mut modules = BTreeSet::new();
+            collect_modules(tag, &mut modules);
+            for module in modules {
+                let counts = changes.resources.entry(module).or_default();
+                if created {
+                    counts.0 += 1;
+                } else {
+                    counts.1 += 1;
+                }
+            }
+        }
+
+        changes
+    }
+}
+
+/// Modules defining the struct and all structs in its type arguments.
+fn collect_modules(tag: &StructTag, modules: &mut BTreeSet<ModuleId>) {
+    modules.insert(tag.module_id());
+    for param in &tag.type_params {
+        collect_type_modules(param, modules);
+    }
+}
+
+fn collect_type_modules(tag: &TypeTag, modules: &mut BTreeSet<ModuleId>) {
+    match tag {
+        TypeTag::Struct(tag) => collect_modules(tag, modules),
+        TypeTag::Vector(inner) => collect_type_modules(inner, modules),
+        _ => {}
+    }
+}
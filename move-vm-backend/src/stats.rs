@@ -0,0 +1,60 @@
+//! Optional per-module execution statistics.
+//!
+//! Once enabled with [`crate::Mvm::set_module_stats`], every executed entry function call updates
+//! the statistics of its module, so the chain operators can see which modules consume the block
+//! space. Script transactions aren't attributed to any module, and dry runs aren't recorded.
+//!
+//! The statistics are kept in the storage under the metrics namespace, one entry per module.
+
+use crate::storage::Storage;
+use crate::storage_key::MODULE_STATS_KEY_PREFIX;
+use alloc::vec::Vec;
+use move_core_types::language_storage::ModuleId;
+use serde::{Deserialize, Serialize};
+
+/// Aggregate statistics of the module entry function calls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleStats {
+    /// Number of the executed calls.
+    pub calls: u64,
+    /// Total gas used by the calls.
+    pub gas_used: u64,
+    /// Number of the failed calls.
+    pub failures: u64,
+}
+
+/// Keeps the module statistics in the storage.
+pub(crate) struct ModuleStatsRegistry<'a, S: Storage> {
+    storage: &'a S,
+}
+
+impl<'a, S: Storage> ModuleStatsRegistry<'a, S> {
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self { storage }
+    }
+
+    pub(crate) fn get(&self, module: &ModuleId) -> Option<ModuleStats> {
+        let bytes = self.storage.get(&Self::key(module))?;
+        bcs::from_bytes(&bytes).ok()
+    }
+
+    /// Records a single call of the module entry function.
+    pub(crate) fn record(&self, module: &ModuleId, gas_used: u64, failed: bool) {
+        let mut stats = self.get(module).unwrap_or_default();
+        stats.calls = stats.calls.saturating_add(1);
+        stats.gas_used = stats.gas_used.saturating_add(gas_used);
+        stats.failures = stats.failures.saturating_add(failed.into());
+
+        let bytes = bcs::to_bytes(&stats).expect("statistics are always serializable");
+        self.storage.set(&Self::key(module), &bytes);
+    }
+
+    pub(crate) fn reset(&self, module: &ModuleId) {
+        self.storage.remove(&Self::key(module));
+    }
+
+    fn key(module: &ModuleId) -> Vec<u8> {
+        let module = bcs::to_bytes(module).expect("module ids are always serializable");
+        [MODULE_STATS_KEY_PREFIX, module.as_slice()].concat()
+    }
+}
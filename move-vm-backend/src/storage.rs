@@ -15,4 +15,10 @@ pub trait Storage {
 
     /// Remove `key` and its value from the storage.
     fn remove(&self, key: &[u8]);
+
+    /// Persist the writes deferred by the caching layer - see [`crate::storage_cache`].
+    ///
+    /// The MoveVM calls it once the outcome of each transaction is written. Storages writing
+    /// right away can keep the default, which does nothing.
+    fn flush(&self) {}
 }
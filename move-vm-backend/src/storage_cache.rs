@@ -0,0 +1,279 @@
+//! Caching layer between the MoveVM and the storage adapter.
+//!
+//! Every storage access of the MoveVM goes to the host storage, so the same account entries are
+//! often read many times within a transaction and across the transactions of a block. Embedders
+//! can put a [`StorageCache`] in front of their [`Storage`] with [`CachedStorage`] and tune the
+//! memory the cache takes against the redundant host reads:
+//! ```ignore
+//! let storage = CachedStorage::new(host_storage, LruReadCache::new(4 * 1024 * 1024));
+//! let vm = Mvm::new(storage, host)?;
+//! ```
+//!
+//! Two caches are provided:
+//! - [`LruReadCache`] writes through and keeps the recently used entries within a byte budget,
+//! - [`WriteBackCache`] keeps all entries used during the session and defers the writes until
+//!   the session ends, so the entries written several times reach the storage once.
+//!
+//! The MoveVM calls [`Storage::flush`] once the outcome of each transaction is written, which
+//! persists the deferred writes. The cached entries are never invalidated, so the storage must not
+//! be written around the cache while it's in use.
+
+use crate::storage::Storage;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use core::cell::{Cell, RefCell};
+
+/// Entry of the cached key - `None` if the key is known to be absent from the storage.
+pub type CachedEntry = Option<Vec<u8>>;
+
+/// Caching strategy of the [`CachedStorage`].
+pub trait StorageCache {
+    /// Cached entry of the key, or `None` on a cache miss.
+    fn get(&self, key: &[u8]) -> Option<CachedEntry>;
+
+    /// Keep the entry read from the storage after a cache miss.
+    fn fill(&self, key: &[u8], entry: Option<&[u8]>);
+
+    /// Record the write of the entry.
+    ///
+    /// Returns `true` if the write should reach the storage right away, or `false` if the cache
+    /// defers it until the flush.
+    fn write(&self, key: &[u8], entry: Option<&[u8]>) -> bool;
+
+    /// Take the deferred writes in the order they should be applied.
+    fn take_deferred(&self) -> Vec<(Vec<u8>, CachedEntry)>;
+}
+
+/// Number of the storage reads served by the cache and by the storage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Reads served by the cache.
+    pub hits: u64,
+    /// Reads served by the storage.
+    pub misses: u64,
+}
+
+/// Storage with the cache in front of it.
+#[derive(Debug, Default)]
+pub struct CachedStorage<S, C> {
+    storage: S,
+    cache: C,
+    hits: Cell<u64>,
+    misses: Cell<u64>,
+}
+
+impl<S: Storage, C: StorageCache> CachedStorage<S, C> {
+    /// Put the cache in front of the storage.
+    pub fn new(storage: S, cache: C) -> Self {
+        Self {
+            storage,
+            cache,
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+        }
+    }
+
+    /// Number of the reads served by the cache and by the storage so far.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.get(),
+            misses: self.misses.get(),
+        }
+    }
+
+    /// Persist the deferred writes and return the storage.
+    pub fn into_inner(self) -> S {
+        self.flush();
+        self.storage
+    }
+}
+
+impl<S: Storage, C: StorageCache> Storage for CachedStorage<S, C> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        if let Some(entry) = self.cache.get(key) {
+            self.hits.set(self.hits.get() + 1);
+            return entry;
+        }
+
+        self.misses.set(self.misses.get() + 1);
+        let entry = self.storage.get(key);
+        self.cache.fill(key, entry.as_deref());
+        entry
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) {
+        if self.cache.write(key, Some(value)) {
+            self.storage.set(key, value);
+        }
+    }
+
+    fn remove(&self, key: &[u8]) {
+        if self.cache.write(key, None) {
+            self.storage.remove(key);
+        }
+    }
+
+    fn flush(&self) {
+        for (key, entry) in self.cache.take_deferred() {
+            match entry {
+                Some(value) => self.storage.set(&key, &value),
+                None => self.storage.remove(&key),
+            }
+        }
+        self.storage.flush();
+    }
+}
+
+/// Write-through cache of the recently used entries.
+///
+/// The least recently used entries are evicted once the keys and the values of the cached
+/// entries take more than the byte budget. Entries bigger than the whole budget aren't cached.
+#[derive(Debug, Default)]
+pub struct LruReadCache {
+    budget: usize,
+    lru: RefCell<Lru>,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    /// Cached entries with the tick of their last use.
+    entries: BTreeMap<Vec<u8>, (CachedEntry, u64)>,
+    /// Cached keys by the tick of their last use.
+    uses: BTreeMap<u64, Vec<u8>>,
+    /// Tick of the latest use.
+    tick: u64,
+    /// Bytes taken by the cached keys and values.
+    size: usize,
+}
+
+impl LruReadCache {
+    /// Create the cache keeping at most `budget` bytes of the keys and values.
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            lru: RefCell::new(Lru::default()),
+        }
+    }
+
+    /// Bytes taken by the cached keys and values.
+    pub fn size(&self) -> usize {
+        self.lru.borrow().size
+    }
+
+    fn insert(&self, key: &[u8], entry: Option<&[u8]>) {
+        let mut lru = self.lru.borrow_mut();
+        lru.remove(key);
+
+        let size = key.len() + entry.map_or(0, <[u8]>::len);
+        if size > self.budget {
+            return;
+        }
+        while lru.size + size > self.budget {
+            lru.evict();
+        }
+
+        lru.tick += 1;
+        let tick = lru.tick;
+        lru.uses.insert(tick, key.to_vec());
+        lru.entries
+            .insert(key.to_vec(), (entry.map(<[u8]>::to_vec), tick));
+        lru.size += size;
+    }
+}
+
+impl Lru {
+    fn remove(&mut self, key: &[u8]) {
+        if let Some((entry, tick)) = self.entries.remove(key) {
+            self.uses.remove(&tick);
+            self.size -= key.len() + entry.map_or(0, |value| value.len());
+        }
+    }
+
+    fn evict(&mut self) {
+        if let Some((_, key)) = self.uses.pop_first() {
+            self.remove(&key);
+        }
+    }
+}
+
+impl StorageCache for LruReadCache {
+    fn get(&self, key: &[u8]) -> Option<CachedEntry> {
+        let lru = &mut *self.lru.borrow_mut();
+        lru.tick += 1;
+        let tick = lru.tick;
+
+        let (entry, last_use) = lru.entries.get_mut(key)?;
+        let previous_use = core::mem::replace(last_use, tick);
+        let entry = entry.clone();
+
+        lru.uses.remove(&previous_use);
+        lru.uses.insert(tick, key.to_vec());
+        Some(entry)
+    }
+
+    fn fill(&self, key: &[u8], entry: Option<&[u8]>) {
+        self.insert(key, entry);
+    }
+
+    fn write(&self, key: &[u8], entry: Option<&[u8]>) -> bool {
+        self.insert(key, entry);
+        true
+    }
+
+    fn take_deferred(&self) -> Vec<(Vec<u8>, CachedEntry)> {
+        Vec::new()
+    }
+}
+
+/// Write-back cache of the entries used during the session.
+///
+/// The written entries reach the storage on the flush, in the key order, and all entries are
+/// dropped afterwards, so the cache only grows within a session.
+#[derive(Debug, Default)]
+pub struct WriteBackCache {
+    entries: RefCell<BTreeMap<Vec<u8>, CachedEntry>>,
+    dirty: RefCell<BTreeSet<Vec<u8>>>,
+}
+
+impl WriteBackCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of the written entries waiting for the flush.
+    pub fn deferred_writes(&self) -> usize {
+        self.dirty.borrow().len()
+    }
+}
+
+impl StorageCache for WriteBackCache {
+    fn get(&self, key: &[u8]) -> Option<CachedEntry> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    fn fill(&self, key: &[u8], entry: Option<&[u8]>) {
+        self.entries
+            .borrow_mut()
+            .insert(key.to_vec(), entry.map(<[u8]>::to_vec));
+    }
+
+    fn write(&self, key: &[u8], entry: Option<&[u8]>) -> bool {
+        self.fill(key, entry);
+        self.dirty.borrow_mut().insert(key.to_vec());
+        false
+    }
+
+    fn take_deferred(&self) -> Vec<(Vec<u8>, CachedEntry)> {
+        let mut entries = core::mem::take(&mut *self.entries.borrow_mut());
+        core::mem::take(&mut *self.dirty.borrow_mut())
+            .into_iter()
+            .map(|key| {
+                let entry = entries.remove(&key).flatten();
+                (key, entry)
+            })
+            .collect()
+    }
+}
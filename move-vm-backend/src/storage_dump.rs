@@ -0,0 +1,125 @@
+//! Key-value dumps of the MoveVM storage.
+//!
+//! A failing on-chain scenario can be replayed locally against the exact state: the node exports
+//! the MoveVM storage entries into a [`StorageDump`] and the test imports them into an in-memory
+//! storage, e.g. the `StorageMock` of the test utilities:
+//! ```ignore
+//! // On the node, with the entries collected from the pallet storage.
+//! let bytes = StorageDump::new(entries).to_bytes();
+//!
+//! // In the test.
+//! let storage = StorageMock::from_dump(&StorageDump::from_bytes(&bytes)?);
+//! let vm = Mvm::new(storage, HostMock::new())?;
+//! ```
+//!
+//! The [`Storage`] trait can't list the stored keys, so each storage exports its entries itself.
+//! The serialized dump starts with [`DUMP_MAGIC`] and the format version, followed by the BCS
+//! encoded entries ordered by the key.
+
+use crate::storage::Storage;
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt;
+
+/// Prefix of the serialized dumps.
+pub const DUMP_MAGIC: &[u8] = b"MVMDUMP";
+
+/// Version of the serialized dump format.
+pub const DUMP_VERSION: u8 = 1;
+
+/// Error codes for [`StorageDump::from_bytes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpError {
+    /// The bytes don't start with the [`DUMP_MAGIC`].
+    BadMagic,
+    /// The dump was serialized with a format version this build can't read.
+    UnsupportedVersion(u8),
+    /// The entries can't be decoded.
+    Malformed,
+}
+
+impl fmt::Display for DumpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BadMagic => write!(f, "Not a storage dump"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported storage dump version {version}")
+            }
+            Self::Malformed => write!(f, "Malformed storage dump entries"),
+        }
+    }
+}
+
+/// Entries of the storage.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageDump {
+    /// Keys and their values, ordered by the key.
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl StorageDump {
+    /// Collect the entries - the later values of the repeated keys win.
+    pub fn new(entries: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>) -> Self {
+        let entries: BTreeMap<_, _> = entries.into_iter().collect();
+        Self {
+            entries: entries.into_iter().collect(),
+        }
+    }
+
+    /// Keys and their values, ordered by the key.
+    pub fn entries(&self) -> &[(Vec<u8>, Vec<u8>)] {
+        &self.entries
+    }
+
+    /// Value of the key.
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.entries
+            .binary_search_by(|(entry, _)| entry.as_slice().cmp(key))
+            .ok()
+            .map(|idx| self.entries[idx].1.as_slice())
+    }
+
+    /// Number of the entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the dump has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Write all entries to the storage.
+    ///
+    /// The other entries of the storage are left untouched.
+    pub fn apply<S: Storage>(&self, storage: &S) {
+        for (key, value) in &self.entries {
+            storage.set(key, value);
+        }
+    }
+
+    /// Serialize the dump.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = DUMP_MAGIC.to_vec();
+        bytes.push(DUMP_VERSION);
+        bytes.extend(bcs::to_bytes(&self.entries).expect("byte vectors are always serializable"));
+        bytes
+    }
+
+    /// Deserialize the dump.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DumpError> {
+        let bytes = bytes.strip_prefix(DUMP_MAGIC).ok_or(DumpError::BadMagic)?;
+        let (&version, entries) = bytes.split_first().ok_or(DumpError::Malformed)?;
+        if version != DUMP_VERSION {
+            return Err(DumpError::UnsupportedVersion(version));
+        }
+
+        let entries: Vec<(Vec<u8>, Vec<u8>)> =
+            bcs::from_bytes(entries).map_err(|_| DumpError::Malformed)?;
+        // The keys must be unique and ordered, so the dump reads the same on every import.
+        if entries.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+            return Err(DumpError::Malformed);
+        }
+
+        Ok(Self { entries })
+    }
+}
@@ -0,0 +1,154 @@
+//! Storage keys of the modules and resources.
+//!
+//! All modules and resources of an address are kept in a single storage entry under the raw
+//! 32-byte address key. The entry value is the BCS encoding of:
+//!
+//! ```text
+//! struct AccountData {
+//!     modules: BTreeMap<Identifier, Vec<u8>>,
+//!     resources: BTreeMap<StructTag, Vec<u8>>,
+//! }
+//! ```
+//!
+//! The module bytes may be compressed - they start with the `MVZ\x01` tag, followed by the
+//! uncompressed length as the little-endian `u32` and the DEFLATE stream. The uncompressed modules
+//! start with the Move magic. The resources are the BCS-encoded Move values.
+//!
+//! The other entries kept by the MoveVM (e.g. the governance data) use the reserved keys listed
+//! below. An address key is always exactly 32 bytes long, while a reserved key never is - the fixed
+//! keys are shorter and the prefixed keys append either a payload of at least 32 bytes (a hash, an
+//! address or a BCS-encoded key) or the block numbers of the expiry index, which keep them shorter,
+//! to the prefix. The reserved keys therefore never clash with the account data, and the distinct
+//! prefixes keep them apart from each other. New entries must be added to the registry and keep
+//! the length rule.
+//!
+//! The scheme is part of the stable API, so the off-chain indexers can locate and decode the
+//! stored data with [`StorageKey`] instead of re-implementing it. Changing it requires a storage
+//! migration.
+
+use crate::{compression::decompress_module, warehouse::AccountData};
+use alloc::{borrow::ToOwned, vec::Vec};
+use anyhow::{Error, Result};
+use move_core_types::{
+    account_address::AccountAddress, identifier::IdentStr, language_storage::StructTag,
+};
+use move_vm_backend_common::receipt::StatePath;
+
+/// Key of the flag which enables the script allowlist policy - see [`crate::allowlist`].
+pub(crate) const ALLOWLIST_ENABLED_KEY: &[u8] = b"script_allowlist";
+
+/// Key prefix for the allowed script hashes - see [`crate::allowlist`].
+pub(crate) const ALLOWED_SCRIPT_KEY_PREFIX: &[u8] = b"script_allowlist::";
+
+/// Key of the next event sequence number - see [`crate::event_sequence`].
+pub(crate) const EVENT_SEQUENCE_KEY: &[u8] = b"events::sequence";
+
+/// Key prefix for the resource expiries, followed by the BCS-encoded address and struct tag - see
+/// [`crate::expiry`].
+pub(crate) const EXPIRY_KEY_PREFIX: &[u8] = b"expiry::resource::";
+
+/// Key prefix for the expiry index, followed by the big-endian block number and, for the index
+/// entries, the big-endian `u32` position within the block - see [`crate::expiry`].
+pub(crate) const EXPIRY_INDEX_KEY_PREFIX: &[u8] = b"expiry::block::";
+
+/// Key of the expiry sweep cursor - see [`crate::expiry`].
+pub(crate) const EXPIRY_SWEEP_KEY: &[u8] = b"expiry::sweep";
+
+/// Key prefix for the frozen accounts, followed by the address - see [`crate::freeze`].
+pub(crate) const FROZEN_ACCOUNT_KEY_PREFIX: &[u8] = b"governance::frozen::account::";
+
+/// Key prefix for the frozen resources, followed by the BCS-encoded address and struct tag - see
+/// [`crate::freeze`].
+pub(crate) const FROZEN_RESOURCE_KEY_PREFIX: &[u8] = b"governance::frozen::resource::";
+
+/// Key prefix for the registered migrations, followed by the layout hash - see
+/// [`crate::migration`].
+pub(crate) const MIGRATION_KEY_PREFIX: &[u8] = b"migration::";
+
+/// Key prefix for the pending multisig scripts, followed by the script hash - see
+/// [`crate::multisig`].
+pub(crate) const PENDING_SCRIPT_KEY_PREFIX: &[u8] = b"multisig::";
+
+/// Key prefix for the registered multisig accounts, followed by the address - see
+/// [`crate::multisig`].
+pub(crate) const MULTISIG_ACCOUNT_KEY_PREFIX: &[u8] = b"multisig_account::";
+
+/// Key prefix for the dependents of the modules, followed by the module ID - see
+/// [`crate::retirement`].
+pub(crate) const DEPENDENTS_KEY_PREFIX: &[u8] = b"module_dependents::";
+
+/// Key prefix for the number of the resources of the module types, followed by the module ID -
+/// see [`crate::retirement`].
+pub(crate) const RESOURCE_COUNT_KEY_PREFIX: &[u8] = b"module_resources::";
+
+/// Key prefix for the module statistics, followed by the module ID - see [`crate::stats`].
+pub(crate) const MODULE_STATS_KEY_PREFIX: &[u8] = b"metrics::module::";
+
+/// Key prefix for the script templates, followed by the hash of the template name - see
+/// [`crate::templates`].
+pub(crate) const TEMPLATE_KEY_PREFIX: &[u8] = b"script_template::";
+
+/// Key prefix for the storage usage of the addresses, followed by the address.
+pub(crate) const STORAGE_USAGE_KEY_PREFIX: &[u8] = b"storage_usage::";
+
+/// Location of a module or a resource in the storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageKey {
+    address: AccountAddress,
+    path: StatePath,
+}
+
+impl StorageKey {
+    /// Location of the module published under the address.
+    pub fn module(address: AccountAddress, name: &IdentStr) -> Self {
+        Self {
+            address,
+            path: StatePath::Module(name.to_owned()),
+        }
+    }
+
+    /// Location of the resource kept under the address.
+    pub fn resource(address: AccountAddress, tag: StructTag) -> Self {
+        Self {
+            address,
+            path: StatePath::Resource(tag),
+        }
+    }
+
+    /// Address of the module or the resource.
+    pub fn address(&self) -> &AccountAddress {
+        &self.address
+    }
+
+    /// Path of the module or the resource within the account data.
+    pub fn path(&self) -> &StatePath {
+        &self.path
+    }
+
+    /// Key of the storage entry holding the module or the resource.
+    pub fn key(&self) -> &[u8] {
+        account_key(&self.address)
+    }
+
+    /// Reads the module or the resource from the value of the storage entry under [`Self::key`].
+    ///
+    /// The modules are returned decompressed.
+    pub fn read(&self, entry: &[u8]) -> Result<Option<Vec<u8>>> {
+        let mut account: AccountData = bcs::from_bytes(entry).map_err(Error::msg)?;
+
+        // Using remove to get the value since the account is already a copy of the entry.
+        match &self.path {
+            StatePath::Module(name) => account
+                .modules
+                .remove(name)
+                .map(decompress_module)
+                .transpose(),
+            StatePath::Resource(tag) => Ok(account.resources.remove(tag)),
+        }
+    }
+}
+
+/// Key of the storage entry holding all modules and resources of the address.
+pub fn account_key(address: &AccountAddress) -> &[u8] {
+    address.as_slice()
+}
@@ -0,0 +1,93 @@
+//! Streaming of the finalized changes to the embedders.
+//!
+//! Node-side services embedding the backend (e.g. the indexers) can subscribe with
+//! [`crate::Mvm::subscribe`] instead of scanning the storage after every block. The subscribers are
+//! notified once the changes of a call are applied to the storage - with the events of the call
+//! and its storage writes in the canonical order. Dry runs, failed calls and the calls rejected by
+//! the block gas pool are never reported. The expired resources removed by
+//! [`crate::Mvm::sweep_expired`] and the retired modules are reported as well, without events.
+//!
+//! The subscribers are called synchronously, so the slow ones should hand the changes over to
+//! their own threads, e.g. through the [`mpsc::Sender`] subscriber.
+
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+use move_core_types::effects::ChangeSet;
+use move_vm_backend_common::{event::MoveEvent, receipt::StateWrite};
+use std::sync::mpsc;
+
+/// Changes of a single call, applied to the storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalizedChanges {
+    /// Events emitted by the call, with their sequence numbers assigned.
+    pub events: Vec<MoveEvent>,
+    /// Storage writes in the canonical order - see [`StateWrite::from_changeset`].
+    pub writes: Vec<StateWrite>,
+}
+
+/// Receiver of the finalized changes.
+pub trait Subscriber {
+    /// Called once the changes are applied to the storage.
+    fn on_finalized(&self, changes: &FinalizedChanges);
+}
+
+impl<F> Subscriber for F
+where
+    F: Fn(&FinalizedChanges),
+{
+    fn on_finalized(&self, changes: &FinalizedChanges) {
+        self(changes)
+    }
+}
+
+/// Sends the changes to the channel - the changes are dropped once the receiver is gone.
+impl Subscriber for mpsc::Sender<FinalizedChanges> {
+    fn on_finalized(&self, changes: &FinalizedChanges) {
+        let _ = self.send(changes.clone());
+    }
+}
+
+/// Handle of the subscription, used to unsubscribe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SubscriptionId(u64);
+
+/// Subscribers in the order they subscribed.
+#[derive(Default)]
+pub(crate) struct Subscribers {
+    next_id: u64,
+    subscribers: BTreeMap<SubscriptionId, Box<dyn Subscriber>>,
+}
+
+impl Subscribers {
+    pub(crate) fn add(&mut self, subscriber: Box<dyn Subscriber>) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+        self.subscribers.insert(id, subscriber);
+        id
+    }
+
+    pub(crate) fn remove(&mut self, id: SubscriptionId) -> bool {
+        self.subscribers.remove(&id).is_some()
+    }
+
+    /// The storage writes of the changeset, if anyone is subscribed.
+    ///
+    /// Collected before the changeset is applied, which consumes it.
+    pub(crate) fn writes(&self, changeset: &ChangeSet) -> Option<Vec<StateWrite>> {
+        (!self.subscribers.is_empty()).then(|| StateWrite::from_changeset(changeset))
+    }
+
+    /// Notify all subscribers about the applied changes.
+    pub(crate) fn notify(&self, events: &[MoveEvent], writes: Option<Vec<StateWrite>>) {
+        let Some(writes) = writes else {
+            return;
+        };
+
+        let changes = FinalizedChanges {
+            events: events.to_vec(),
+            writes,
+        };
+        for subscriber in self.subscribers.values() {
+            subscriber.on_finalized(&changes);
+        }
+    }
+}
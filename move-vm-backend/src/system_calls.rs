@@ -0,0 +1,29 @@
+//! Gas-exempt system calls of the framework functions.
+//!
+//! Maintenance operations like the resource migrations or the storage sweeps may touch more state
+//! than any user transaction could pay for. The embedder marks such framework functions as
+//! system calls with [`crate::Mvm::set_system_calls`], and the root or genesis context executes
+//! them without any gas limit with [`crate::Mvm::execute_system_call`]. The same functions
+//! called through the regular execution methods are metered as usual.
+//!
+//! The system calls require a [`SystemCallCapability`], which is an embedder-supplied token type
+//! constructed only after the root origin is verified, the same way as the
+//! [`crate::privileged::PublishCapability`]:
+//! ```ignore
+//! pub struct Root(());
+//!
+//! impl SystemCallCapability for Root {}
+//!
+//! fn on_idle(mvm: &Mvm<S, H>) {
+//!     let result = mvm.execute_system_call(&Root(()), module, function, vec![], vec![]);
+//!     ...
+//! }
+//! ```
+
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+
+/// Proof that the caller is the root or the genesis context.
+pub trait SystemCallCapability {}
+
+/// Framework function which can be executed as a system call.
+pub type SystemFunction = (ModuleId, Identifier);
@@ -0,0 +1,244 @@
+//! Registry of the script templates.
+//!
+//! A template is a commonly used script (e.g. a transfer) stored on-chain together with the
+//! documented schema of its parameters. The templates are usually registered at genesis with
+//! [`crate::genesis::VmGenesisConfig::add_script_template`], so the pallet extrinsics and wallets
+//! can refer to the scripts by name instead of submitting the bytecode.
+//!
+//! [`crate::Mvm::execute_template`] binds the typed arguments against the schema before they are
+//! BCS-encoded, so a mistyped or missing argument is rejected before the execution. Templates are
+//! regular scripts otherwise - e.g. the script allowlist still applies to them.
+//!
+//! The templates are kept in the storage under the template namespace, one entry per template.
+
+use crate::{storage::Storage, storage_key::TEMPLATE_KEY_PREFIX, MAX_BINARY_FORMAT_VERSION};
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use move_binary_format::{
+    access::ScriptAccess,
+    file_format::{CompiledScript, SignatureToken},
+};
+use move_core_types::{account_address::AccountAddress, identifier::IdentStr};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+
+/// Script with the documented parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptTemplate {
+    /// Script bytecode.
+    pub bytecode: Vec<u8>,
+    /// What the script does.
+    pub description: String,
+    /// Parameters of the script, in the declaration order.
+    pub params: Vec<TemplateParam>,
+}
+
+/// Documented script parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateParam {
+    /// Parameter name.
+    pub name: String,
+    /// What the parameter means.
+    pub description: String,
+    /// Parameter type - it must match the script signature.
+    pub ty: TemplateParamType,
+}
+
+/// Type of the template parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemplateParamType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Address,
+    /// The `signer` or `&signer` parameter, bound to the signing address.
+    Signer,
+    /// The `vector<u8>` parameter.
+    Bytes,
+}
+
+/// Argument bound to a template parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateArg {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Address(AccountAddress),
+    Signer(AccountAddress),
+    Bytes(Vec<u8>),
+}
+
+impl TemplateArg {
+    fn ty(&self) -> TemplateParamType {
+        match self {
+            Self::Bool(_) => TemplateParamType::Bool,
+            Self::U8(_) => TemplateParamType::U8,
+            Self::U16(_) => TemplateParamType::U16,
+            Self::U32(_) => TemplateParamType::U32,
+            Self::U64(_) => TemplateParamType::U64,
+            Self::U128(_) => TemplateParamType::U128,
+            Self::Address(_) => TemplateParamType::Address,
+            Self::Signer(_) => TemplateParamType::Signer,
+            Self::Bytes(_) => TemplateParamType::Bytes,
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let bytes = match self {
+            Self::Bool(value) => bcs::to_bytes(value),
+            Self::U8(value) => bcs::to_bytes(value),
+            Self::U16(value) => bcs::to_bytes(value),
+            Self::U32(value) => bcs::to_bytes(value),
+            Self::U64(value) => bcs::to_bytes(value),
+            Self::U128(value) => bcs::to_bytes(value),
+            Self::Address(address) | Self::Signer(address) => bcs::to_bytes(address),
+            Self::Bytes(bytes) => bcs::to_bytes(bytes),
+        };
+        bytes.expect("template arguments are always serializable")
+    }
+}
+
+/// Error of registering or binding a script template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// No template is registered under the name.
+    NotFound,
+    /// The template bytecode isn't a valid script.
+    InvalidScript,
+    /// The documented parameters don't match the script signature.
+    SchemaMismatch,
+    /// The number of arguments doesn't match the template parameters.
+    ArgumentCountMismatch {
+        /// Number of template parameters.
+        expected: usize,
+        /// Number of provided arguments.
+        provided: usize,
+    },
+    /// The argument at the position has a different type than the parameter.
+    ArgumentTypeMismatch(usize),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "Script template not found"),
+            Self::InvalidScript => write!(f, "Script template bytecode is invalid"),
+            Self::SchemaMismatch => {
+                write!(f, "Template parameters don't match the script signature")
+            }
+            Self::ArgumentCountMismatch { expected, provided } => write!(
+                f,
+                "Template expects {expected} arguments, {provided} were provided"
+            ),
+            Self::ArgumentTypeMismatch(idx) => {
+                write!(
+                    f,
+                    "Argument {idx} doesn't match the template parameter type"
+                )
+            }
+        }
+    }
+}
+
+impl ScriptTemplate {
+    /// Check that the documented parameters match the script signature.
+    pub fn validate(&self) -> Result<(), TemplateError> {
+        let script =
+            CompiledScript::deserialize_with_max_version(&self.bytecode, MAX_BINARY_FORMAT_VERSION)
+                .map_err(|_| TemplateError::InvalidScript)?;
+        let signature = &script.signature_at(script.parameters).0;
+
+        // The templates are executed without the type arguments.
+        let matches = script.type_parameters.is_empty()
+            && signature.len() == self.params.len()
+            && signature
+                .iter()
+                .zip(&self.params)
+                .all(|(token, param)| param_type(token) == Some(param.ty));
+        if !matches {
+            return Err(TemplateError::SchemaMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Check the arguments against the parameters and BCS-encode them.
+    pub fn bind(&self, args: &[TemplateArg]) -> Result<Vec<Vec<u8>>, TemplateError> {
+        if args.len() != self.params.len() {
+            return Err(TemplateError::ArgumentCountMismatch {
+                expected: self.params.len(),
+                provided: args.len(),
+            });
+        }
+
+        args.iter()
+            .zip(&self.params)
+            .enumerate()
+            .map(|(idx, (arg, param))| {
+                if arg.ty() != param.ty {
+                    return Err(TemplateError::ArgumentTypeMismatch(idx));
+                }
+                Ok(arg.encode())
+            })
+            .collect()
+    }
+}
+
+fn param_type(token: &SignatureToken) -> Option<TemplateParamType> {
+    let ty = match token {
+        SignatureToken::Bool => TemplateParamType::Bool,
+        SignatureToken::U8 => TemplateParamType::U8,
+        SignatureToken::U16 => TemplateParamType::U16,
+        SignatureToken::U32 => TemplateParamType::U32,
+        SignatureToken::U64 => TemplateParamType::U64,
+        SignatureToken::U128 => TemplateParamType::U128,
+        SignatureToken::Address => TemplateParamType::Address,
+        SignatureToken::Signer => TemplateParamType::Signer,
+        SignatureToken::Reference(inner) if **inner == SignatureToken::Signer => {
+            TemplateParamType::Signer
+        }
+        SignatureToken::Vector(inner) if **inner == SignatureToken::U8 => TemplateParamType::Bytes,
+        _ => return None,
+    };
+    Some(ty)
+}
+
+/// Keeps the script templates in the storage.
+pub(crate) struct TemplateRegistry<'a, S: Storage> {
+    storage: &'a S,
+}
+
+impl<'a, S: Storage> TemplateRegistry<'a, S> {
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self { storage }
+    }
+
+    pub(crate) fn get(&self, name: &IdentStr) -> Option<ScriptTemplate> {
+        let bytes = self.storage.get(&Self::key(name))?;
+        bcs::from_bytes(&bytes).ok()
+    }
+
+    pub(crate) fn set(&self, name: &IdentStr, template: &ScriptTemplate) {
+        let bytes = bcs::to_bytes(template).expect("templates are always serializable");
+        self.storage.set(&Self::key(name), &bytes);
+    }
+
+    pub(crate) fn remove(&self, name: &IdentStr) {
+        self.storage.remove(&Self::key(name));
+    }
+
+    // The name is hashed, so the key length never matches the address keys.
+    fn key(name: &IdentStr) -> Vec<u8> {
+        [
+            TEMPLATE_KEY_PREFIX,
+            Sha3_256::digest(name.as_bytes()).as_slice(),
+        ]
+        .concat()
+    }
+}
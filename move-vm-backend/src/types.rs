@@ -1,18 +1,43 @@
+use crate::call_limits::CallGasLimits;
+use crate::warehouse::FreedStorage;
+use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::vec::Vec;
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_binary_format::file_format::{CodeOffset, FunctionDefinitionIndex};
 use move_core_types::account_address::AccountAddress;
-use move_core_types::gas_algebra::GasQuantity;
+use move_core_types::gas_algebra::{InternalGas, InternalGasUnit, ToUnit};
 use move_core_types::identifier::Identifier;
-use move_core_types::language_storage::TypeTag;
-use move_core_types::vm_status::StatusCode;
-use move_vm_backend_common::gas_schedule::{GAS_COST_PER_PUBLISHED_BYTE, INSTRUCTION_COST_TABLE};
-use move_vm_test_utils::gas_schedule::GasStatus;
+use move_core_types::language_storage::{ModuleId, TypeTag};
+use move_core_types::vm_status::{StatusCode, StatusType};
+use move_vm_backend_common::call_builder::{ComposedCall, EntryCall};
+use move_vm_backend_common::error::CanonicalError;
+use move_vm_backend_common::event::MoveEvent;
+use move_vm_backend_common::gas_schedule::{
+    argument_cost, publishing_cost, NumTypeTagNodes, DEFAULT_HOST_WEIGHT_PER_GAS,
+    DEFAULT_MAX_TYPE_TAG_DEPTH, DEFAULT_MEMORY_LIMIT, GAS_COST_PER_TYPE_TAG_NODE,
+    GAS_REFUND_PER_DELETED_RESOURCE, GAS_REFUND_PER_FREED_BYTE, INSTRUCTION_COST_TABLE,
+};
+use move_vm_backend_common::receipt::{self, ExecutionReceipt, ReceiptHash, EMPTY_ROOT};
+use move_vm_backend_common::verification_bound::PublishComplexity;
+#[cfg(feature = "scripts")]
+use move_vm_backend_common::{script_lint::LintWarning, types::ScriptTransaction};
+use move_vm_runtime::config::DEFAULT_MAX_CALL_DEPTH;
+use move_vm_test_utils::gas_schedule::{Gas, GasStatus, GasUnit};
 use move_vm_types::gas::GasMeter;
+use move_vm_types::loaded_data::runtime_types::Type;
+use serde::{Deserialize, Serialize};
+
+pub use move_vm_runtime::config::InterruptHandle;
 
 /// Call type used to determine if we are calling script or function inside some module.
-#[derive(Debug)]
+///
+/// The `Script` variant only exists with the `scripts` feature, so the encoded calls aren't
+/// compatible between the builds with and without the feature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Call {
     /// Script
+    #[cfg(feature = "scripts")]
     Script {
         /// Script bytecode.
         code: Vec<u8>,
@@ -29,7 +54,7 @@ pub enum Call {
 }
 
 /// Transaction struct used in execute_script call.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction {
     /// Call type.
     pub call: Call,
@@ -39,24 +64,114 @@ pub struct Transaction {
     pub args: Vec<Vec<u8>>,
 }
 
+#[cfg(feature = "scripts")]
+impl From<ScriptTransaction> for Transaction {
+    fn from(script: ScriptTransaction) -> Self {
+        Self {
+            call: Call::Script {
+                code: script.bytecode,
+            },
+            type_args: script.type_args,
+            args: script.args,
+        }
+    }
+}
+
+impl From<EntryCall> for Transaction {
+    fn from(call: EntryCall) -> Self {
+        Self {
+            call: Call::ScriptFunction {
+                mod_address: call.mod_address,
+                mod_name: call.mod_name,
+                func_name: call.func_name,
+            },
+            type_args: call.type_args,
+            args: call.args,
+        }
+    }
+}
+
+impl Transaction {
+    /// Transaction of the composed call with its resolved arguments.
+    pub(crate) fn composed(call: ComposedCall, args: Vec<Vec<u8>>) -> Self {
+        Self {
+            call: Call::ScriptFunction {
+                mod_address: call.mod_address,
+                mod_name: call.mod_name,
+                func_name: call.func_name,
+            },
+            type_args: call.type_args,
+            args,
+        }
+    }
+}
+
 /// Result of the execution.
 #[derive(Debug)]
 pub struct VmResult {
     /// Execution status code read from the MoveVM
     pub status_code: StatusCode,
+    /// Abort code of the aborted execution.
+    pub abort_code: Option<u64>,
     /// Optional error message.
     pub error_message: Option<String>,
     /// Gas used.
     pub gas_used: u64,
+    /// Fee for the used gas - see [`crate::fee_policy`].
+    ///
+    /// Zero if no fee policy is set or the transaction was rejected before the execution.
+    pub fee: u128,
+    /// Refundable gas for the storage freed by the execution.
+    ///
+    /// It is never bigger than the used gas - it's up to the caller to credit it back.
+    pub gas_refund: u64,
+    /// Gas consumed per called function - only available when the gas profiling is enabled.
+    pub gas_profile: Option<GasProfile>,
+    /// Indexed events emitted by the successful execution.
+    pub events: Vec<MoveEvent>,
+    /// The fees were already paid by the fee hook - see [`crate::fee_hook`] - or by the fee payer.
+    pub sponsored: bool,
+    /// Account which paid the fees instead of the signers - see
+    /// [`crate::Mvm::execute_with_fee_payer`] and [`crate::Mvm::set_fee_payer`].
+    pub fee_payer: Option<AccountAddress>,
+    /// Failed refund of the unused fee to the fee payer.
+    ///
+    /// The execution outcome and its changes stand - it's up to the caller to settle the refund.
+    pub refund_error: Option<StatusCode>,
+    /// Root of the storage changes of the execution - see [`VmResult::receipt`].
+    ///
+    /// The changes made by the fee hook aren't included.
+    pub state_diff_root: ReceiptHash,
+    /// Index of the call which failed the [`crate::Mvm::execute_calls`] sequence.
+    pub failed_call: Option<usize>,
+    /// Where the MoveVM failed - only available for the errors reported by the MoveVM.
+    pub error_context: Option<ErrorContext>,
 }
 
+/// Gas consumed by the function's own instructions, keyed by the `address::module::function`
+/// name. The entry script is listed as `script`.
+///
+/// Each entry is rounded up separately, so the sum of the entries can slightly exceed the used gas.
+pub type GasProfile = BTreeMap<String, u64>;
+
 impl VmResult {
     /// Create a new VmResult.
     pub fn new(status_code: StatusCode, error_message: Option<String>, gas_used: u64) -> Self {
         Self {
             status_code,
+            abort_code: None,
             error_message,
             gas_used,
+            fee: 0,
+            gas_refund: 0,
+            gas_profile: None,
+            events: Vec::new(),
+            sponsored: false,
+            fee_payer: None,
+            refund_error: None,
+            state_diff_root: EMPTY_ROOT,
+            failed_call: None,
+            error_context: None,
         }
     }
 
@@ -71,16 +186,83 @@ impl VmResult {
     pub fn is_err(&self) -> bool {
         !self.is_ok()
     }
+
+    /// Decode the abort code, if the module follows the `std::error` convention.
+    pub fn canonical_error(&self) -> Option<CanonicalError> {
+        self.abort_code.and_then(CanonicalError::decode)
+    }
+
+    /// Canonical receipt committing to the execution result.
+    pub fn receipt(&self) -> ExecutionReceipt {
+        ExecutionReceipt {
+            status: self.status_code as u64,
+            abort_code: self.abort_code,
+            gas_used: self.gas_used,
+            events_root: receipt::events_root(&self.events),
+            state_diff_root: self.state_diff_root,
+        }
+    }
 }
 
-/// Inner MoveVM gas handling multiplier.
-///
-/// Internally, MoveVM converts the input gas to gas units which are multiplied by this multiplier,
-/// so in order to get used gas at the same input scale, we need to scale it back with this constant.
-const INTERNAL_GAS_MULTIPLIER: u64 = 1000;
+/// Stage of the MoveVM where the error happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorStage {
+    /// Validation of the transaction, e.g. its arguments.
+    Validation,
+    /// Loading of the code - the deserialization, the verification and the linking, including
+    /// the dependencies.
+    Loading,
+    /// Execution of the code, including the aborts.
+    Execution,
+    /// Broken invariant of the MoveVM.
+    InvariantViolation,
+    /// Status code outside of the known ranges.
+    Unknown,
+}
+
+impl From<StatusType> for ErrorStage {
+    fn from(status_type: StatusType) -> Self {
+        match status_type {
+            StatusType::Validation => Self::Validation,
+            StatusType::Verification | StatusType::Deserialization => Self::Loading,
+            StatusType::Execution => Self::Execution,
+            StatusType::InvariantViolation => Self::InvariantViolation,
+            StatusType::Unknown => Self::Unknown,
+        }
+    }
+}
+
+/// Location of the MoveVM error, telling apart e.g. a dependency which failed to load from an
+/// abort of the called code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Stage of the MoveVM where the error happened.
+    pub stage: ErrorStage,
+    /// Module which failed to load or whose code failed.
+    ///
+    /// Missing for the script code and for the errors the MoveVM reports without a location.
+    pub module: Option<ModuleId>,
+    /// Name of the failed function, if its module is published.
+    pub function: Option<Identifier>,
+    /// Index of the failed function definition and the bytecode offset within it.
+    pub frame: Option<(FunctionDefinitionIndex, CodeOffset)>,
+}
+
+/// Report of the script dry-run - see [`crate::Mvm::dry_run_script`].
+#[cfg(feature = "scripts")]
+#[derive(Debug)]
+pub struct DryRunReport {
+    /// Result of the dry-run execution.
+    pub result: VmResult,
+    /// Lint warnings of the script - empty if the script couldn't be deserialized.
+    pub warnings: Vec<LintWarning>,
+}
 
 /// The maximum possible raw gas amount value.
-pub const MAX_GAS_AMOUNT: u64 = u64::MAX / INTERNAL_GAS_MULTIPLIER;
+///
+/// Internally, MoveVM converts the input [`Gas`] to the [`InternalGas`], which is scaled by the
+/// conversion ratio, so the input can't use the whole `u64` range.
+pub const MAX_GAS_AMOUNT: u64 = u64::MAX / <GasUnit as ToUnit<InternalGasUnit>>::MULTIPLIER;
 
 /// Amount of gas.
 #[derive(Debug, Clone, Copy)]
@@ -135,6 +317,51 @@ pub enum GasStrategy {
     ///
     /// This option should be used only for testing and debugging purposes.
     Unmetered,
+    /// It allows to run the Move operations with only the executed instructions being counted.
+    ///
+    /// Every instruction costs exactly one unit, while the cost table, native functions and
+    /// publishing are not charged at all. The execution fails with the out-of-gas error once the
+    /// provided instruction limit is exceeded, and the used gas in the result contains the number
+    /// of executed instructions.
+    ///
+    /// This option should be used for lightweight sandboxes and fuzzers.
+    InstructionCount(u64),
+}
+
+/// Execution settings applied to every executed transaction.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ExecutionConfig {
+    /// Collect the per-function gas profile.
+    pub(crate) gas_profiling: bool,
+    /// Maximum amount of memory in bytes a single transaction can allocate for the VM values.
+    pub(crate) memory_limit: u64,
+    /// Record the per-module execution statistics.
+    pub(crate) module_stats: bool,
+    /// Host weight units charged as one internal gas unit.
+    pub(crate) host_weight_per_gas: u64,
+    /// Maximum number of frames on the call stack.
+    pub(crate) max_call_depth: usize,
+    /// Charge the deserialization of the transaction arguments.
+    pub(crate) arg_metering: bool,
+    /// Maximum size in bytes of the data of a single event.
+    pub(crate) max_event_size: Option<usize>,
+    /// Maximum nesting depth of the caller-supplied type arguments.
+    pub(crate) max_type_tag_depth: usize,
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            gas_profiling: false,
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+            module_stats: false,
+            host_weight_per_gas: DEFAULT_HOST_WEIGHT_PER_GAS,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            arg_metering: false,
+            max_event_size: None,
+            max_type_tag_depth: DEFAULT_MAX_TYPE_TAG_DEPTH,
+        }
+    }
 }
 
 /// Internal gas handler.
@@ -144,7 +371,25 @@ pub(crate) struct GasHandler<'a> {
     /// Dry run shouldn't make any changes to the MoveVM storage.
     pub(crate) dry_run: bool,
     /// An initial gas amount provided for metered gas strategy.
-    starting_gas_amount: Option<u64>,
+    starting_gas_amount: Option<Gas>,
+    /// An instruction limit provided for instruction count gas strategy.
+    instruction_limit: Option<u64>,
+    /// Collect the gas profile during the execution.
+    pub(crate) profiling: bool,
+    /// Maximum amount of memory the execution can allocate for the VM values.
+    pub(crate) memory_limit: u64,
+    /// Gas profile of the execution in internal gas units.
+    pub(crate) profile: Option<GasProfile>,
+    /// Host weight units charged as one internal gas unit.
+    pub(crate) host_weight_per_gas: u64,
+    /// Charge the deserialization of the transaction arguments.
+    pub(crate) arg_metering: bool,
+    /// Maximum nesting depth of the caller-supplied type arguments.
+    pub(crate) max_type_tag_depth: usize,
+    /// Gas ceilings of the calls into the modules in internal gas units.
+    pub(crate) call_gas_limits: CallGasLimits,
+    /// Account paying the fees instead of the signers.
+    pub(crate) fee_payer: Option<AccountAddress>,
 }
 
 impl GasHandler<'_> {
@@ -152,51 +397,214 @@ impl GasHandler<'_> {
     pub(crate) fn new(strategy: GasStrategy) -> Self {
         let dry_run = matches!(strategy, GasStrategy::DryRun);
         let mut starting_gas_amount = None;
+        let mut instruction_limit = None;
 
         let status = match strategy {
             GasStrategy::Metered(GasAmount(amount)) => {
-                starting_gas_amount = Some(amount);
-                GasStatus::new(&INSTRUCTION_COST_TABLE, amount.into())
+                starting_gas_amount = Some(Gas::new(amount));
+                GasStatus::new(&INSTRUCTION_COST_TABLE, Gas::new(amount))
             }
             GasStrategy::DryRun => {
-                starting_gas_amount = Some(MAX_GAS_AMOUNT);
-                GasStatus::new(&INSTRUCTION_COST_TABLE, MAX_GAS_AMOUNT.into())
+                starting_gas_amount = Some(Gas::new(MAX_GAS_AMOUNT));
+                GasStatus::new(&INSTRUCTION_COST_TABLE, Gas::new(MAX_GAS_AMOUNT))
             }
             GasStrategy::Unmetered => GasStatus::new_unmetered(),
+            GasStrategy::InstructionCount(limit) => {
+                instruction_limit = Some(limit);
+                GasStatus::new_instruction_counter(limit)
+            }
         };
 
         Self {
             dry_run,
             status,
             starting_gas_amount,
+            instruction_limit,
+            profiling: false,
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+            profile: None,
+            host_weight_per_gas: DEFAULT_HOST_WEIGHT_PER_GAS,
+            arg_metering: false,
+            max_type_tag_depth: DEFAULT_MAX_TYPE_TAG_DEPTH,
+            call_gas_limits: CallGasLimits::new(),
+            fee_payer: None,
         }
     }
 
-    /// Charges write operations linearly according to the provided byte length.
+    /// Constructs a new [`GasHandler`] for the transaction execution.
+    pub(crate) fn for_execution(
+        strategy: GasStrategy,
+        config: ExecutionConfig,
+        call_gas_limits: &BTreeMap<ModuleId, GasAmount>,
+    ) -> Self {
+        let handler = Self::new(strategy);
+        // Each instruction costs exactly one internal gas unit.
+        let call_gas_limits = call_gas_limits
+            .iter()
+            .map(|(module_id, GasAmount(limit))| {
+                let limit = match handler.instruction_limit {
+                    Some(_) => *limit,
+                    None => Gas::new(*limit).to_unit::<InternalGasUnit>().into(),
+                };
+                (module_id.clone(), limit)
+            })
+            .collect();
+
+        Self {
+            profiling: config.gas_profiling,
+            memory_limit: config.memory_limit,
+            host_weight_per_gas: config.host_weight_per_gas,
+            arg_metering: config.arg_metering,
+            max_type_tag_depth: config.max_type_tag_depth,
+            call_gas_limits,
+            ..handler
+        }
+    }
+
+    /// Charges write operations according to the provided byte length and code complexity - see
+    /// [`publishing_cost`].
     pub(crate) fn charge_publishing_to_storage(
         &mut self,
         num_bytes: usize,
+        complexity: &PublishComplexity,
     ) -> Result<(), VmResult> {
+        // Only the instructions are counted.
+        if self.instruction_limit.is_some() {
+            return Ok(());
+        }
+
         let remaining_gas = self.status.remaining_gas();
-        let amount = GasQuantity::new(num_bytes as u64 * GAS_COST_PER_PUBLISHED_BYTE);
+        let amount = publishing_cost(num_bytes, complexity);
 
         self.status.deduct_gas(amount).map_err(|e| VmResult {
             status_code: e.major_status(),
+            abort_code: None,
             error_message: None,
             gas_used: remaining_gas.into(),
+            fee: 0,
+            gas_refund: 0,
+            gas_profile: None,
+            events: Vec::new(),
+            sponsored: false,
+            fee_payer: None,
+            refund_error: None,
+            state_diff_root: EMPTY_ROOT,
+            failed_call: None,
+            error_context: None,
         })
     }
 
+    /// Charges the caller-supplied type arguments according to their number of nodes.
+    ///
+    /// The type arguments nested deeper than the configured maximum depth are rejected before any
+    /// further processing.
+    pub(crate) fn charge_type_args(&mut self, type_args: &[TypeTag]) -> PartialVMResult<()> {
+        let mut nodes: u64 = 0;
+        let mut pending: Vec<(&TypeTag, usize)> = type_args.iter().map(|tag| (tag, 1)).collect();
+
+        while let Some((tag, depth)) = pending.pop() {
+            if depth > self.max_type_tag_depth {
+                return Err(PartialVMError::new(StatusCode::VM_MAX_TYPE_DEPTH_REACHED));
+            }
+            nodes += 1;
+
+            match tag {
+                TypeTag::Vector(inner) => pending.push((inner, depth + 1)),
+                TypeTag::Struct(tag) => {
+                    pending.extend(tag.type_params.iter().map(|tag| (tag, depth + 1)))
+                }
+                _ => (),
+            }
+        }
+
+        // Only the instructions are counted.
+        if self.instruction_limit.is_some() {
+            return Ok(());
+        }
+
+        let amount = NumTypeTagNodes::new(nodes) * GAS_COST_PER_TYPE_TAG_NODE;
+        self.status.deduct_gas(amount)
+    }
+
+    /// Charges the BCS-encoded arguments according to their byte length and the nesting depth of
+    /// their parameter types - see [`argument_cost`].
+    ///
+    /// Nothing is charged unless the argument metering is enabled.
+    pub(crate) fn charge_args<'t>(
+        &mut self,
+        args: impl IntoIterator<Item = (&'t Type, &'t [u8])>,
+    ) -> PartialVMResult<()> {
+        // Only the instructions are counted.
+        if !self.arg_metering || self.instruction_limit.is_some() {
+            return Ok(());
+        }
+
+        let amount = args
+            .into_iter()
+            .fold(InternalGas::new(0), |amount, (ty, arg)| {
+                amount + argument_cost(arg.len(), type_depth(ty))
+            });
+        self.status.deduct_gas(amount)
+    }
+
     /// Calculates the used gas.
     pub(crate) fn gas_used(&self) -> u64 {
-        let initial_gas = if let Some(amount) = self.starting_gas_amount {
-            // Internally, the gas we provide to GasStatus gets multiplied by the multiplier.
-            amount * INTERNAL_GAS_MULTIPLIER
-        } else {
+        if let Some(limit) = self.instruction_limit {
+            // Each instruction costs exactly one internal gas unit.
+            let remaining_instructions: u64 = self.status.balance_internal().into();
+            return limit - remaining_instructions;
+        }
+
+        let Some(initial_gas) = self.starting_gas_amount else {
             return 0;
         };
 
-        let remaining_gas: u64 = self.status.balance_internal().into();
-        num_integer::div_ceil(initial_gas - remaining_gas, INTERNAL_GAS_MULTIPLIER)
+        let initial_gas: InternalGas = initial_gas.to_unit();
+        let used_gas = initial_gas
+            .checked_sub(self.status.balance_internal())
+            .expect("the balance never exceeds the initial gas");
+        used_gas.to_unit_round_up::<GasUnit>().into()
+    }
+
+    /// Converts the collected gas profile to the same scale as the used gas.
+    pub(crate) fn gas_profile(&self) -> Option<GasProfile> {
+        let mut profile = self.profile.clone()?;
+
+        // Each instruction costs exactly one internal gas unit.
+        if self.instruction_limit.is_none() {
+            for gas in profile.values_mut() {
+                *gas = InternalGas::new(*gas).to_unit_round_up::<GasUnit>().into();
+            }
+        }
+
+        Some(profile)
+    }
+
+    /// Calculates the refundable gas for the freed storage.
+    ///
+    /// The refund is capped at the used gas, so the execution can never end up with a profit.
+    pub(crate) fn gas_refund(&self, freed: FreedStorage) -> u64 {
+        // There is nothing to refund when only the instructions are counted.
+        if self.instruction_limit.is_some() {
+            return 0;
+        }
+
+        let internal_refund = freed.deleted_resources * GAS_REFUND_PER_DELETED_RESOURCE
+            + freed.freed_bytes * GAS_REFUND_PER_FREED_BYTE;
+
+        let refund: u64 = internal_refund.to_unit_round_down::<GasUnit>().into();
+        core::cmp::min(refund, self.gas_used())
+    }
+}
+
+/// Number of the nested layouts the argument of the given type is deserialized through.
+fn type_depth(ty: &Type) -> u64 {
+    match ty {
+        Type::Vector(inner) => 1 + type_depth(inner),
+        Type::StructInstantiation(_, type_args) => {
+            1 + type_args.iter().map(type_depth).max().unwrap_or(0)
+        }
+        Type::Reference(inner) | Type::MutableReference(inner) => type_depth(inner),
+        _ => 1,
     }
 }
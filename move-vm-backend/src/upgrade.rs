@@ -0,0 +1,50 @@
+//! Invalidation of the loaded code after the module upgrades.
+//!
+//! The loader cache keeps the linked modules, so a dependent module loaded before an upgrade
+//! would keep calling into the old code. The cache can't drop single modules, so it's flushed
+//! whole as soon as the upgrade is applied to the storage.
+//!
+//! Embedders keeping their own data derived from the code (e.g. the ABIs or the pre-verified
+//! code) can set an [`UpgradeHook`] with [`crate::Mvm::set_upgrade_hook`]. The hook is called
+//! with the upgraded modules and all their published dependents, directly or transitively,
+//! found with the dependents index of [`crate::retirement`]. The modules published before the
+//! index existed aren't reported.
+
+use alloc::vec::Vec;
+use move_core_types::{
+    effects::{ChangeSet, Op},
+    language_storage::ModuleId,
+};
+
+/// Modules invalidated by the upgrade.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidationReport {
+    /// Upgraded modules.
+    pub upgraded: Vec<ModuleId>,
+    /// Published modules depending on the upgraded ones, directly or transitively.
+    pub dependents: Vec<ModuleId>,
+}
+
+/// Receiver of the invalidation reports.
+pub trait UpgradeHook {
+    /// Called once the upgrade is applied to the storage.
+    fn on_upgrade(&self, report: &InvalidationReport);
+}
+
+impl<F> UpgradeHook for F
+where
+    F: Fn(&InvalidationReport),
+{
+    fn on_upgrade(&self, report: &InvalidationReport) {
+        self(report)
+    }
+}
+
+/// Modules replaced by the changeset.
+pub(crate) fn upgraded_modules(changeset: &ChangeSet) -> Vec<ModuleId> {
+    changeset
+        .modules()
+        .filter(|(_, _, op)| matches!(op, Op::Modify(_)))
+        .map(|(address, name, _)| ModuleId::new(address, name.clone()))
+        .collect()
+}
@@ -1,4 +1,13 @@
-use crate::{balance::BalanceHandler, storage::Storage};
+#[cfg(feature = "module-compression")]
+use crate::compression::compress_module;
+use crate::{
+    compression::decompress_module,
+    host::HostBindings,
+    reentrancy::ReentrancyGuard,
+    retirement::{IndexChanges, ModuleIndex},
+    storage::Storage,
+    storage_key::{account_key, StorageKey, STORAGE_USAGE_KEY_PREFIX},
+};
 use alloc::{
     collections::{
         btree_map::Entry::{Occupied, Vacant},
@@ -8,31 +17,62 @@ use alloc::{
 };
 use anyhow::{bail, Error, Result};
 use core::ops::Deref;
+use core::sync::atomic::{AtomicBool, Ordering};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
 use move_core_types::account_address::AccountAddress;
 use move_core_types::effects::{
     ChangeSet,
     Op::{self, Delete, Modify, New},
 };
+use move_core_types::gas_algebra::NumBytes;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::{ModuleId, StructTag};
 use move_core_types::resolver::{BalanceResolver, ModuleResolver, ResourceResolver};
 use move_core_types::vm_status::StatusCode;
+use move_stdlib::natives::account::AccountHandler;
+use move_stdlib::natives::foreign::{ForeignCallHandler, ForeignCallResponse};
+use move_stdlib::natives::hash::HashHandler;
+use move_stdlib::natives::host_gas::HostWeightReporter;
+use move_stdlib::natives::random::RandomnessHandler;
+use move_stdlib::natives::timestamp::TimeHandler;
+use move_stdlib::natives::xcm::XcmSender;
+use move_vm_backend_common::code_hash::module_hash;
+use move_vm_backend_common::gas_schedule::NumResources;
+use move_vm_backend_common::xcm::XcmMessage;
 use serde::{Deserialize, Serialize};
 
 /// Structure holding account data which is held under one Move address
 /// in Substrate storage).
+///
+/// The layout is a stable API - see the [`crate::storage_key`] module.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
-struct AccountData {
+pub(crate) struct AccountData {
     /// Hashmap of the modules kept under this account.
-    modules: BTreeMap<Identifier, Vec<u8>>,
+    ///
+    /// Modules might be stored compressed - see the [`crate::compression`] module.
+    pub(crate) modules: BTreeMap<Identifier, Vec<u8>>,
     /// Hashmap of the resources kept under this account.
-    resources: BTreeMap<StructTag, Vec<u8>>,
+    pub(crate) resources: BTreeMap<StructTag, Vec<u8>>,
 }
 
 impl AccountData {
-    fn apply_changes<K, V>(
-        map: &mut BTreeMap<K, V>,
-        changes: impl IntoIterator<Item = (K, Op<V>)>,
+    /// Bytes of the stored modules and resources.
+    ///
+    /// Modules are measured as stored, i.e. compressed with the `module-compression` feature.
+    fn stored_bytes(&self) -> u64 {
+        let modules = self.modules.values().map(|module| module.len() as u64);
+        let resources = self
+            .resources
+            .values()
+            .map(|resource| resource.len() as u64);
+        modules.chain(resources).sum()
+    }
+
+    /// Apply the changes to the map, keeping the `usage` in sync with the stored bytes.
+    fn apply_changes<K>(
+        map: &mut BTreeMap<K, Vec<u8>>,
+        changes: impl IntoIterator<Item = (K, Op<Vec<u8>>)>,
+        usage: &mut u64,
     ) -> Result<()>
     where
         K: Ord + core::fmt::Debug,
@@ -46,12 +86,15 @@ impl AccountData {
                     )
                 }
                 (Occupied(entry), Delete) => {
-                    entry.remove();
+                    *usage = usage.saturating_sub(entry.remove().len() as u64);
                 }
                 (Occupied(entry), Modify(val)) => {
-                    *entry.into_mut() = val;
+                    *usage += val.len() as u64;
+                    let old = core::mem::replace(entry.into_mut(), val);
+                    *usage = usage.saturating_sub(old.len() as u64);
                 }
                 (Vacant(entry), New(val)) => {
+                    *usage += val.len() as u64;
                     entry.insert(val);
                 }
                 (Vacant(entry), Delete | Modify(_)) => bail!(
@@ -65,42 +108,257 @@ impl AccountData {
 }
 
 /// Move VM storage implementation for Substrate storage.
-pub(crate) struct Warehouse<S: Storage, B: BalanceHandler> {
+pub(crate) struct Warehouse<S: Storage, H: HostBindings> {
     /// Substrate storage implementing the Storage trait.
     storage: S,
-    /// Balance handler which provides access to the external balance handling mechanism.
-    balance_handler: B,
+    /// Host bindings which provide access to the external host services.
+    host: H,
+    /// Balance calls guarded against the reentrancy.
+    reentrancy_guard: ReentrancyGuard,
+    /// Set while the host handles a foreign call.
+    foreign_call: AtomicBool,
+    /// Set while the host handles a guarded balance call.
+    balance_call: AtomicBool,
 }
 
-impl<S: Storage, B: BalanceHandler> Warehouse<S, B> {
-    pub(crate) fn new(storage: S, balance_handler: B) -> Warehouse<S, B> {
+impl<S: Storage, H: HostBindings> Warehouse<S, H> {
+    pub(crate) fn new(storage: S, host: H) -> Warehouse<S, H> {
         Self {
             storage,
-            balance_handler,
+            host,
+            reentrancy_guard: ReentrancyGuard::default(),
+            foreign_call: AtomicBool::new(false),
+            balance_call: AtomicBool::new(false),
         }
     }
 
+    pub(crate) fn set_reentrancy_guard(&mut self, guard: ReentrancyGuard) {
+        self.reentrancy_guard = guard;
+    }
+
+    /// Persist the storage writes deferred by the caching layer.
+    pub(crate) fn flush(&self) {
+        self.storage.flush();
+    }
+
     pub(crate) fn apply_changes(&self, changeset: ChangeSet) -> Result<()> {
-        for (account, changeset) in changeset.into_inner() {
-            let key = account.as_slice();
+        // All accounts are updated before anything is written, so a changeset which fails to
+        // apply leaves neither the accounts nor the module index partially updated.
+        let mut writes = Vec::new();
+        for (address, changeset) in changeset.into_inner() {
+            let index_changes = IndexChanges::new(address, &changeset);
+
+            let key = account_key(&address);
             let mut account = match self.storage.get(key) {
                 Some(value) => bcs::from_bytes(&value).map_err(Error::msg)?,
                 _ => AccountData::default(),
             };
 
+            let usage_key = Self::storage_usage_key(key);
+            let mut usage = match self.storage.get(&usage_key) {
+                Some(value) => bcs::from_bytes(&value).map_err(Error::msg)?,
+                None => account.stored_bytes(),
+            };
+
             let (modules, resources) = changeset.into_inner();
-            AccountData::apply_changes(&mut account.modules, modules)?;
-            AccountData::apply_changes(&mut account.resources, resources)?;
+            #[cfg(feature = "module-compression")]
+            let modules = modules
+                .into_iter()
+                .map(|(name, op)| (name, op.map(compress_module)));
+            AccountData::apply_changes(&mut account.modules, modules, &mut usage)?;
+            AccountData::apply_changes(&mut account.resources, resources, &mut usage)?;
 
             let account_bytes = bcs::to_bytes(&account).map_err(Error::msg)?;
+            let usage_bytes = bcs::to_bytes(&usage).map_err(Error::msg)?;
+            writes.push((address, account_bytes, usage_bytes, index_changes));
+        }
+
+        let index = ModuleIndex::new(&self.storage);
+        for (address, account_bytes, usage_bytes, index_changes) in writes {
+            let key = account_key(&address);
             self.storage.set(key, &account_bytes);
+            self.storage
+                .set(&Self::storage_usage_key(key), &usage_bytes);
+            index.record(index_changes);
         }
 
         Ok(())
     }
+
+    /// Storage key of the storage usage of the address.
+    ///
+    /// The usage is updated whenever the account data changes, so it can be read without decoding
+    /// the account data.
+    fn storage_usage_key(address: &[u8]) -> Vec<u8> {
+        [STORAGE_USAGE_KEY_PREFIX, address].concat()
+    }
+
+    /// Bytes of the modules and resources stored under the address.
+    ///
+    /// Accounts written before the usage was tracked are measured from their account data.
+    pub(crate) fn storage_usage(&self, address: &AccountAddress) -> Result<u64> {
+        let usage_key = Self::storage_usage_key(account_key(address));
+        if let Some(value) = self.storage.get(&usage_key) {
+            return bcs::from_bytes(&value).map_err(Error::msg);
+        }
+
+        match self.storage.get(account_key(address)) {
+            Some(value) => {
+                let account: AccountData = bcs::from_bytes(&value).map_err(Error::msg)?;
+                Ok(account.stored_bytes())
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Check if a foreign call is in progress, i.e. the host is handling it.
+    pub(crate) fn foreign_call_active(&self) -> bool {
+        self.foreign_call.load(Ordering::Relaxed)
+    }
+
+    /// Check if a guarded balance call is in progress, i.e. the host is handling it.
+    pub(crate) fn balance_call_active(&self) -> bool {
+        self.balance_call.load(Ordering::Relaxed)
+    }
+
+    /// Make the balance call, rejecting it if it re-enters another guarded balance call.
+    fn balance_call<T, E: Into<StatusCode>>(
+        &self,
+        changes_balance: bool,
+        call: impl FnOnce(&H) -> Result<T, E>,
+    ) -> Result<T, StatusCode> {
+        if !self.reentrancy_guard.guards(changes_balance) {
+            return call(&self.host).map_err(Into::into);
+        }
+        if self.balance_call_active() {
+            return Err(StatusCode::REENTRANT_BALANCE_CALL);
+        }
+
+        self.balance_call.store(true, Ordering::Relaxed);
+        let result = call(&self.host);
+        self.balance_call.store(false, Ordering::Relaxed);
+
+        result.map_err(Into::into)
+    }
+
+    /// Withdraw the transaction fee from the fee payer, guarded like the balance transfers.
+    pub(crate) fn withdraw_fee(
+        &self,
+        payer: AccountAddress,
+        fee: u128,
+    ) -> Result<bool, StatusCode> {
+        self.balance_call(true, |host| host.withdraw_fee(payer, fee))
+    }
+
+    /// Return the unused part of the withdrawn fee to the fee payer.
+    pub(crate) fn refund_fee(&self, payer: AccountAddress, refund: u128) -> Result<(), StatusCode> {
+        self.balance_call(true, |host| host.refund_fee(payer, refund))
+    }
+
+    /// All modules published under the address, ordered by name.
+    pub(crate) fn get_modules(&self, address: &AccountAddress) -> Result<Vec<Vec<u8>>> {
+        let Some(raw_account) = self.storage.get(account_key(address)) else {
+            return Ok(Vec::new());
+        };
+
+        let account: AccountData = bcs::from_bytes(&raw_account).map_err(Error::msg)?;
+        account
+            .modules
+            .into_values()
+            .map(decompress_module)
+            .collect()
+    }
+
+    /// All resources under the address whose struct tag belongs to the module, ordered by the tag.
+    ///
+    /// The resources are kept in the account data ordered by the struct tag, which starts with the
+    /// module address and name, so the resources of one module form a contiguous range.
+    pub(crate) fn get_module_resources(
+        &self,
+        address: &AccountAddress,
+        module_id: &ModuleId,
+    ) -> Result<Vec<(StructTag, Vec<u8>)>> {
+        let Some(raw_account) = self.storage.get(account_key(address)) else {
+            return Ok(Vec::new());
+        };
+
+        let account: AccountData = bcs::from_bytes(&raw_account).map_err(Error::msg)?;
+        let in_module = |tag: &StructTag| {
+            tag.address == *module_id.address() && tag.module == *module_id.name()
+        };
+        Ok(account
+            .resources
+            .into_iter()
+            .skip_while(|(tag, _)| !in_module(tag))
+            .take_while(|(tag, _)| in_module(tag))
+            .collect())
+    }
+
+    /// Calculates the resource storage which the changeset frees.
+    ///
+    /// Deleted resources free their whole size, while modified resources only count the amount
+    /// of bytes they shrunk by. Modules are never taken into account.
+    pub(crate) fn freed_storage(&self, changeset: &ChangeSet) -> Result<FreedStorage> {
+        let mut freed = FreedStorage::default();
+
+        for (address, account_changeset) in changeset.accounts() {
+            let resources = account_changeset.resources();
+            let has_freeing_ops = resources
+                .values()
+                .any(|op| matches!(op, Delete | Modify(_)));
+            if !has_freeing_ops {
+                continue;
+            }
+
+            let account: AccountData = match self.storage.get(account_key(address)) {
+                Some(value) => bcs::from_bytes(&value).map_err(Error::msg)?,
+                _ => continue,
+            };
+
+            for (tag, op) in resources {
+                let old_len = match account.resources.get(tag) {
+                    Some(old_value) => NumBytes::new(old_value.len() as u64),
+                    None => continue,
+                };
+
+                match op {
+                    Delete => {
+                        freed.deleted_resources += NumResources::one();
+                        freed.freed_bytes += old_len;
+                    }
+                    Modify(new_value) => {
+                        let new_len = NumBytes::new(new_value.len() as u64);
+                        freed.freed_bytes +=
+                            old_len.checked_sub(new_len).unwrap_or(NumBytes::zero());
+                    }
+                    New(_) => (),
+                }
+            }
+        }
+
+        Ok(freed)
+    }
+}
+
+/// Resource storage freed by a changeset.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FreedStorage {
+    /// Number of deleted resources.
+    pub(crate) deleted_resources: NumResources,
+    /// Number of bytes freed by deleted or shrunk resources.
+    pub(crate) freed_bytes: NumBytes,
+}
+
+impl Default for FreedStorage {
+    fn default() -> Self {
+        Self {
+            deleted_resources: NumResources::zero(),
+            freed_bytes: NumBytes::zero(),
+        }
+    }
 }
 
-impl<S: Storage, B: BalanceHandler> Deref for Warehouse<S, B> {
+impl<S: Storage, H: HostBindings> Deref for Warehouse<S, H> {
     type Target = S;
 
     fn deref(&self) -> &Self::Target {
@@ -108,25 +366,33 @@ impl<S: Storage, B: BalanceHandler> Deref for Warehouse<S, B> {
     }
 }
 
-impl<S: Storage, B: BalanceHandler> ModuleResolver for Warehouse<S, B> {
+impl<S: Storage, H: HostBindings> ModuleResolver for Warehouse<S, H> {
     type Error = Error;
 
     fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
-        let raw_account = self.storage.get(module_id.address().as_slice());
-
-        if let Some(raw_account) = raw_account {
-            let mut account: AccountData = bcs::from_bytes(&raw_account).map_err(Error::msg)?;
+        let key = StorageKey::module(*module_id.address(), module_id.name());
 
-            // Using remove to get the value since the account is already a copy of data from the storage.
-            return Ok(account.modules.remove(module_id.name()));
+        if let Some(raw_account) = self.storage.get(key.key()) {
+            return key.read(&raw_account);
         }
 
         // Even if the account is not found, we still return Ok(None) - it's not an error for MoveVM.
         Ok(None)
     }
+
+    fn is_module_verified(&self, bytes: &[u8], config_hash: &[u8; 32]) -> bool {
+        self.host
+            .verified_module(&module_hash(bytes))
+            .map_or(false, |marker| marker == *config_hash)
+    }
+
+    fn set_module_verified(&self, bytes: &[u8], config_hash: &[u8; 32]) {
+        self.host
+            .set_verified_module(&module_hash(bytes), config_hash);
+    }
 }
 
-impl<S: Storage, B: BalanceHandler> ResourceResolver for Warehouse<S, B> {
+impl<S: Storage, H: HostBindings> ResourceResolver for Warehouse<S, H> {
     type Error = Error;
 
     fn get_resource(
@@ -134,13 +400,10 @@ impl<S: Storage, B: BalanceHandler> ResourceResolver for Warehouse<S, B> {
         address: &AccountAddress,
         tag: &StructTag,
     ) -> Result<Option<Vec<u8>>, Self::Error> {
-        let raw_account = self.storage.get(address.as_slice());
-
-        if let Some(raw_account) = raw_account {
-            let mut account: AccountData = bcs::from_bytes(&raw_account).map_err(Error::msg)?;
+        let key = StorageKey::resource(*address, tag.clone());
 
-            // Using remove to get the value since the account is already a copy of data from the storage.
-            return Ok(account.resources.remove(tag));
+        if let Some(raw_account) = self.storage.get(key.key()) {
+            return key.read(&raw_account);
         }
 
         // Even if the account is not found, we still return Ok(None) - it's not an error for MoveVM.
@@ -148,7 +411,7 @@ impl<S: Storage, B: BalanceHandler> ResourceResolver for Warehouse<S, B> {
     }
 }
 
-impl<S: Storage, B: BalanceHandler> BalanceResolver for Warehouse<S, B> {
+impl<S: Storage, H: HostBindings> BalanceResolver for Warehouse<S, H> {
     type Error = StatusCode;
 
     fn transfer(
@@ -157,20 +420,123 @@ impl<S: Storage, B: BalanceHandler> BalanceResolver for Warehouse<S, B> {
         dst: AccountAddress,
         cheque_amount: u128,
     ) -> Result<bool, Self::Error> {
-        self.balance_handler
-            .transfer(src, dst, cheque_amount)
-            .map_err(Into::into)
+        // The edge cases of the transfer contract are handled here - see `HostBindings::transfer`.
+        if src == dst {
+            return Ok(self.cheque_amount(src)? >= cheque_amount);
+        }
+        if cheque_amount > 0 {
+            if self.total_amount(dst)?.checked_add(cheque_amount).is_none() {
+                return Ok(false);
+            }
+            if cheque_amount < self.minimum_balance()?
+                && !BalanceResolver::account_exists(self, dst)?
+            {
+                return Ok(false);
+            }
+        }
+
+        self.balance_call(true, |host| host.transfer(src, dst, cheque_amount))
     }
 
     fn cheque_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
-        self.balance_handler
-            .cheque_amount(account)
-            .map_err(Into::into)
+        self.balance_call(false, |host| host.cheque_amount(account))
     }
 
     fn total_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
-        self.balance_handler
-            .total_amount(account)
-            .map_err(Into::into)
+        self.balance_call(false, |host| host.total_amount(account))
+    }
+
+    fn total_issuance(&self) -> Result<u128, Self::Error> {
+        self.balance_call(false, |host| host.total_issuance())
+    }
+
+    fn account_exists(&self, account: AccountAddress) -> Result<bool, Self::Error> {
+        self.balance_call(false, |host| host.account_exists(account))
+    }
+
+    fn minimum_balance(&self) -> Result<u128, Self::Error> {
+        self.balance_call(false, |host| host.minimum_balance())
+    }
+}
+
+impl<S: Storage, H: HostBindings> AccountHandler for Warehouse<S, H> {
+    fn account_exists(&self, account: AccountAddress) -> PartialVMResult<bool> {
+        self.balance_call(false, |host| host.account_exists(account))
+            .map_err(PartialVMError::new)
+    }
+
+    fn create_account(
+        &self,
+        payer: AccountAddress,
+        account: AccountAddress,
+    ) -> PartialVMResult<bool> {
+        self.balance_call(true, |host| host.create_account(payer, account))
+            .map_err(PartialVMError::new)
+    }
+}
+
+impl<S: Storage, H: HostBindings> ForeignCallHandler for Warehouse<S, H> {
+    fn foreign_call(
+        &self,
+        target: u64,
+        payload: &[u8],
+        gas_limit: u64,
+    ) -> PartialVMResult<ForeignCallResponse> {
+        if self.foreign_call_active() {
+            return Err(PartialVMError::new(StatusCode::VM_EXTENSION_ERROR)
+                .with_message("Foreign calls can't be nested".into()));
+        }
+
+        self.foreign_call.store(true, Ordering::Relaxed);
+        let response = self.host.foreign_call(target, payload, gas_limit);
+        self.foreign_call.store(false, Ordering::Relaxed);
+
+        response.map_err(|err| PartialVMError::new(err.into()))
+    }
+}
+
+impl<S: Storage, H: HostBindings> HostWeightReporter for Warehouse<S, H> {
+    fn take_consumed_weight(&self) -> u64 {
+        self.host.take_consumed_weight()
+    }
+}
+
+impl<S: Storage, H: HostBindings> XcmSender for Warehouse<S, H> {
+    fn send_xcm(&self, origin: AccountAddress, message: &[u8]) -> PartialVMResult<bool> {
+        // Messages outside of the supported subset are never sent, so the Move code can handle
+        // them as any other undeliverable message.
+        let Ok(message) = XcmMessage::decode(message) else {
+            return Ok(false);
+        };
+
+        self.host
+            .send_xcm(origin, message)
+            .map_err(|err| PartialVMError::new(err.into()))
+    }
+}
+
+impl<S: Storage, H: HostBindings> HashHandler for Warehouse<S, H> {
+    fn sha2_256(&self, data: &[u8]) -> [u8; 32] {
+        self.host.sha2_256(data)
+    }
+
+    fn sha3_256(&self, data: &[u8]) -> [u8; 32] {
+        self.host.sha3_256(data)
+    }
+}
+
+impl<S: Storage, H: HostBindings> RandomnessHandler for Warehouse<S, H> {
+    fn random_seed(&self, subject: &[u8]) -> PartialVMResult<[u8; 32]> {
+        self.host
+            .random_seed(subject)
+            .map_err(|err| PartialVMError::new(err.into()))
+    }
+}
+
+impl<S: Storage, H: HostBindings> TimeHandler for Warehouse<S, H> {
+    fn now_milliseconds(&self) -> PartialVMResult<u64> {
+        self.host
+            .now_milliseconds()
+            .map_err(|err| PartialVMError::new(err.into()))
     }
 }
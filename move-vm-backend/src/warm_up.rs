@@ -0,0 +1,75 @@
+//! Warming up the loader cache at the node startup.
+//!
+//! The first transaction touching a module pays for deserializing, verifying and linking it and
+//! all its dependencies. [`crate::Mvm::warm_up`] does that ahead of time for the modules every
+//! transaction is expected to use, typically the standard library and the chain framework:
+//! ```ignore
+//! let report = vm.warm_up_with_progress(&framework, |progress| {
+//!     log::info!("warm-up {}/{}: {}", progress.done, progress.total, progress.module);
+//! });
+//! ```
+//!
+//! Warming up is free - no gas is charged and the storage is only read, while the verification
+//! markers of the loaded modules are handed to the host - see
+//! [`crate::host::HostBindings::set_verified_module`]. The dependencies loaded along with the
+//! listed modules are counted in the report but not listed.
+//!
+//! The warmed-up modules survive the block boundaries - the block lifecycle calls reload them from
+//! the storage whenever they drop the caches, see [`crate::block`].
+
+use alloc::vec::Vec;
+use move_core_types::{language_storage::ModuleId, vm_status::StatusCode};
+
+/// Outcome of warming up a single module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarmUpOutcome {
+    /// The module was loaded, verified and cached.
+    Loaded,
+    /// The module was cached already.
+    Cached,
+    /// The module couldn't be loaded, e.g. it isn't published.
+    Failed(StatusCode),
+}
+
+/// Progress of the warm-up, reported after each listed module.
+#[derive(Debug, Clone, Copy)]
+pub struct WarmUpProgress<'a> {
+    /// Module just warmed up.
+    pub module: &'a ModuleId,
+    /// Outcome for the module.
+    pub outcome: WarmUpOutcome,
+    /// Number of the listed modules warmed up so far, including this one.
+    pub done: usize,
+    /// Number of the listed modules.
+    pub total: usize,
+}
+
+/// Summary of the warm-up.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WarmUpReport {
+    /// Listed modules loaded by the warm-up.
+    pub loaded: Vec<ModuleId>,
+    /// Listed modules which were cached already.
+    pub cached: Vec<ModuleId>,
+    /// Listed modules which couldn't be loaded.
+    pub failed: Vec<(ModuleId, StatusCode)>,
+    /// Number of the unlisted modules loaded as the dependencies of the listed ones.
+    pub dependencies: usize,
+    /// Number of modules in the loader cache after the warm-up.
+    pub cached_modules: usize,
+}
+
+impl WarmUpReport {
+    /// Whether all listed modules are cached now.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    pub(crate) fn record(&mut self, module: ModuleId, outcome: WarmUpOutcome) {
+        match outcome {
+            WarmUpOutcome::Loaded => self.loaded.push(module),
+            WarmUpOutcome::Cached => self.cached.push(module),
+            WarmUpOutcome::Failed(status) => self.failed.push((module, status)),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+module CafeAccount::Onboarding {
+    use std::account;
+
+    /// Creates the account of the newcomer, which must not exist yet.
+    public entry fun create(payer: &signer, newcomer: address) {
+        account::create(payer, newcomer);
+    }
+
+    /// Makes sure the newcomer's account exists.
+    public entry fun onboard(payer: &signer, newcomer: address) {
+        account::ensure_exists(payer, newcomer);
+        assert!(account::exists_at(newcomer), 0);
+    }
+}
@@ -28,6 +28,12 @@ module CafeAccount::BasicCoin {
         move_to(account, Balance { coin: empty_coin });
     }
 
+    /// Remove the balance resource from `account`'s address and burn any remaining coins.
+    entry public fun destroy_balance(account: &signer) acquires Balance {
+        let Balance { coin } = move_from<Balance>(signer::address_of(account));
+        let Coin { value: _ } = coin;
+    }
+
     /// Mint `amount` tokens to `mint_addr`. Mint must be approved by the module owner.
     public fun mint(module_owner: &signer, mint_addr: address, amount: u64) acquires Balance {
         // Only the owner of the module can initialize this module
@@ -13,3 +13,21 @@ script {
         BasicCoin::mint(&module_owner, rx_addr, amount);
     }
 }
+
+script {
+    use CafeAccount::BasicCoin;
+
+    fun destroy_balance(s: signer) {
+        BasicCoin::destroy_balance(&s);
+    }
+}
+
+script {
+    use CafeAccount::BasicCoin;
+
+    fun publish_balances(a: signer, b: signer, c: signer) {
+        BasicCoin::publish_balance(&a);
+        BasicCoin::publish_balance(&b);
+        BasicCoin::publish_balance(&c);
+    }
+}
@@ -0,0 +1,19 @@
+module CafeAccount::Config {
+    use std::signer;
+
+    const EZERO_FEE: u64 = 1;
+
+    struct Settings has key {
+        fee: u64,
+    }
+
+    /// Stores the initial settings, the fee must not be zero.
+    public fun init(account: &signer, fee: u64) {
+        assert!(fee > 0, EZERO_FEE);
+        move_to(account, Settings { fee });
+    }
+
+    public fun fee(addr: address): u64 acquires Settings {
+        borrow_global<Settings>(addr).fee
+    }
+}
@@ -0,0 +1,8 @@
+module CafeAccount::Registry {
+    use CafeAccount::Config;
+
+    /// Fee of registering the given number of entries.
+    public fun registration_fee(owner: address, entries: u64): u64 {
+        Config::fee(owner) * entries
+    }
+}
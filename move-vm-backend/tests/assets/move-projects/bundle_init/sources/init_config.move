@@ -0,0 +1,7 @@
+script {
+    use CafeAccount::Config;
+
+    fun init_config(account: signer, fee: u64) {
+        Config::init(&account, fee);
+    }
+}
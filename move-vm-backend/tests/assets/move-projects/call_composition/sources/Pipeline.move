@@ -0,0 +1,31 @@
+module CafeAccount::Pipeline {
+    use std::signer;
+
+    const EALREADY_STORED: u64 = 1;
+
+    /// Value stored under the account.
+    struct Stored has key {
+        value: u64
+    }
+
+    /// Returns the double of `value`.
+    entry public fun double(value: u64): u64 {
+        value * 2
+    }
+
+    /// Returns the `value` and its square.
+    entry public fun with_square(value: u64): (u64, u64) {
+        (value, value * value)
+    }
+
+    /// Returns the `value` as the vector of its bytes.
+    entry public fun bytes(value: u8): vector<u8> {
+        vector[value]
+    }
+
+    /// Store the `value` under the `account`'s address.
+    entry public fun store(account: &signer, value: u64) {
+        assert!(!exists<Stored>(signer::address_of(account)), EALREADY_STORED);
+        move_to(account, Stored { value });
+    }
+}
@@ -0,0 +1,21 @@
+/// Resources whose struct tag order differs from the canonical order of their BCS-encoded tags.
+module CafeAccount::Ordering {
+    struct Z has key {
+        value: u64
+    }
+
+    struct Mm has key {
+        value: u64
+    }
+
+    struct Aaa has key {
+        value: u64
+    }
+
+    /// Creates the resources in neither of the orders.
+    public entry fun create_all(account: &signer) {
+        move_to(account, Mm { value: 2 });
+        move_to(account, Aaa { value: 3 });
+        move_to(account, Z { value: 1 });
+    }
+}
@@ -0,0 +1,20 @@
+module CafeAccount::Amounts {
+    use std::chain_config;
+
+    /// Chain constants seen by the contract.
+    struct Seen has key {
+        chain_id: u64,
+        one_token: u128,
+    }
+
+    /// Record the chain ID and the amount of one whole token under the `account`'s address.
+    entry public fun record(account: &signer) {
+        let decimals = chain_config::token_decimals();
+        let one_token = 1u128;
+        while (decimals > 0) {
+            one_token = one_token * 10;
+            decimals = decimals - 1;
+        };
+        move_to(account, Seen { chain_id: chain_config::chain_id(), one_token });
+    }
+}
@@ -0,0 +1,10 @@
+/// The first version of the counter, before the layout change.
+module CafeAccount::Counter {
+    struct Counter has key {
+        value: u64
+    }
+
+    public fun publish(account: &signer, value: u64) {
+        move_to(account, Counter { value });
+    }
+}
@@ -0,0 +1,7 @@
+script {
+    use CafeAccount::Counter;
+
+    fun publish_counter(account: signer, value: u64) {
+        Counter::publish(&account, value);
+    }
+}
@@ -0,0 +1,16 @@
+/// The second version of the counter, which adds a field and changes the struct layout.
+module CafeAccount::Counter {
+    struct Counter has key {
+        value: u64,
+        updates: u64
+    }
+
+    public fun publish(account: &signer, value: u64) {
+        move_to(account, Counter { value, updates: 0 });
+    }
+
+    /// Re-creates the counter from the fields of the first version.
+    public fun migrate(account: &signer, value: u64) {
+        move_to(account, Counter { value, updates: 1 });
+    }
+}
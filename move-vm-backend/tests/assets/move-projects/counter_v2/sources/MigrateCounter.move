@@ -0,0 +1,8 @@
+script {
+    use CafeAccount::Counter;
+
+    /// Migration script for the first version counters - receives the owner and the old fields.
+    fun migrate_counter(account: signer, value: u64) {
+        Counter::migrate(&account, value);
+    }
+}
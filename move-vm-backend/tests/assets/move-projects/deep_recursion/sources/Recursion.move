@@ -0,0 +1,24 @@
+module CafeAccount::Recursion {
+    /// Recurse `depth` calls deep.
+    public fun descend(depth: u64): u64 {
+        if (depth == 0) {
+            return 0
+        };
+        descend(depth - 1) + 1
+    }
+
+    /// Recurse `depth` calls deep, alternating between two functions.
+    public fun ping(depth: u64): u64 {
+        if (depth == 0) {
+            return 0
+        };
+        pong(depth - 1) + 1
+    }
+
+    fun pong(depth: u64): u64 {
+        if (depth == 0) {
+            return 0
+        };
+        ping(depth - 1) + 1
+    }
+}
@@ -0,0 +1,12 @@
+script {
+    use CafeAccount::Recursion;
+
+    fun recurse(depth: u64, mutual: bool) {
+        let reached = if (mutual) {
+            Recursion::ping(depth)
+        } else {
+            Recursion::descend(depth)
+        };
+        assert!(reached == depth, 0);
+    }
+}
@@ -0,0 +1,17 @@
+module CafeAccount::Ping {
+    use std::indexed_event;
+
+    struct Ping has drop, store {
+        n: u64,
+    }
+
+    /// Emit a ping event with the number indexed.
+    public fun emit(n: u64) {
+        indexed_event::emit(Ping { n }, 1);
+    }
+
+    /// Fee hook which pays nothing and emits the ping 1000.
+    entry public fun pay_fee(_sponsor: &signer, _signers: vector<address>, _gas_limit: u64) {
+        emit(1000);
+    }
+}
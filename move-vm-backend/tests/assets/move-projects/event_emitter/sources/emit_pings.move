@@ -0,0 +1,11 @@
+script {
+    use CafeAccount::Ping;
+
+    fun emit_pings(count: u64) {
+        let n = 0;
+        while (n < count) {
+            Ping::emit(n);
+            n = n + 1;
+        }
+    }
+}
@@ -0,0 +1,23 @@
+/// Session data which is removed by the host once it expires.
+module CafeAccount::Session {
+    use std::expiry;
+    use std::signer;
+
+    struct Session has key {
+        nonce: u64
+    }
+
+    entry public fun open(account: &signer, nonce: u64, expires_at: u64) {
+        move_to(account, Session { nonce });
+        expiry::set_expiry<Session>(account, expires_at);
+    }
+
+    /// Keeps the session until it's closed.
+    entry public fun keep_alive(account: &signer) {
+        expiry::clear_expiry<Session>(account);
+    }
+
+    entry public fun close(account: &signer) acquires Session {
+        let Session { nonce: _ } = move_from<Session>(signer::address_of(account));
+    }
+}
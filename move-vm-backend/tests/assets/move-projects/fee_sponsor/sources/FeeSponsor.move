@@ -0,0 +1,26 @@
+/// Fee hook which sponsors the transactions until its budget runs out.
+module CafeAccount::FeeSponsor {
+    use std::signer;
+
+    const EBUDGET_EXHAUSTED: u64 = 1;
+
+    struct Budget has key {
+        remaining: u64
+    }
+
+    entry public fun init(sponsor: &signer, remaining: u64) {
+        move_to(sponsor, Budget { remaining });
+    }
+
+    /// Pays the whole gas limit of the transaction from the budget.
+    entry public fun pay_fee(sponsor: &signer, _signers: vector<address>, gas_limit: u64) acquires Budget {
+        let budget = borrow_global_mut<Budget>(signer::address_of(sponsor));
+        assert!(budget.remaining >= gas_limit, EBUDGET_EXHAUSTED);
+        budget.remaining = budget.remaining - gas_limit;
+    }
+
+    /// Never finishes the validation.
+    entry public fun pay_fee_forever(_sponsor: &signer, _signers: vector<address>, _gas_limit: u64) {
+        loop {}
+    }
+}
@@ -0,0 +1,19 @@
+/// Calls of the host functions registered by the test host.
+module CafeAccount::Bridge {
+    use std::foreign;
+
+    /// Host function which returns the payload unchanged.
+    const ECHO_TARGET: u64 = 1;
+
+    /// The echoed response doesn't match the payload.
+    const EWRONG_RESPONSE: u64 = 1;
+
+    entry public fun echo(payload: vector<u8>, gas_limit: u64) {
+        let response = foreign::call(ECHO_TARGET, copy payload, gas_limit);
+        assert!(response == payload, EWRONG_RESPONSE);
+    }
+
+    entry public fun call(target: u64, payload: vector<u8>, gas_limit: u64) {
+        foreign::call(target, payload, gas_limit);
+    }
+}
@@ -0,0 +1,10 @@
+/// Resources of another module under the same address.
+module CafeAccount::Ledger {
+    struct Entry has key {
+        value: u64,
+    }
+
+    public entry fun record(account: &signer, value: u64) {
+        move_to(account, Entry { value });
+    }
+}
@@ -0,0 +1,23 @@
+/// Vaults instantiated for any coin type.
+module CafeAccount::Vault {
+    use std::signer;
+
+    struct Vault<phantom CoinType> has key {
+        amount: u64,
+    }
+
+    struct Owner has key {}
+
+    public entry fun deposit<CoinType>(account: &signer, amount: u64) acquires Vault {
+        let addr = signer::address_of(account);
+        if (exists<Vault<CoinType>>(addr)) {
+            let vault = borrow_global_mut<Vault<CoinType>>(addr);
+            vault.amount = vault.amount + amount;
+        } else {
+            move_to(account, Vault<CoinType> { amount });
+        };
+        if (!exists<Owner>(addr)) {
+            move_to(account, Owner {});
+        }
+    }
+}
@@ -0,0 +1,29 @@
+/// Draws relying on the randomness, the time and the hashing provided by the host.
+module CafeAccount::Lottery {
+    use std::hash;
+    use std::random;
+    use std::signer;
+    use std::timestamp;
+
+    /// The draw isn't open yet.
+    const ETOO_EARLY: u64 = 1;
+
+    struct Draw has key {
+        /// Hash of the drawn seed.
+        ticket: vector<u8>,
+        /// Winning number out of the `bound` numbers.
+        winner: u64,
+        /// Block time of the draw in seconds.
+        drawn_at: u64,
+    }
+
+    public entry fun draw(account: &signer, opens_at: u64, bound: u64) {
+        let now = timestamp::now_seconds();
+        assert!(now >= opens_at, ETOO_EARLY);
+
+        let ticket = hash::sha2_256(random::seed(b"ticket"));
+        let winner = random::u64_below(b"winner", bound);
+        move_to(account, Draw { ticket, winner, drawn_at: now });
+    }
+
+}
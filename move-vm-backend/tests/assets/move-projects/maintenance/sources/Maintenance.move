@@ -0,0 +1,12 @@
+module CafeAccount::Maintenance {
+    /// Visits the entries one by one, e.g. while sweeping the storage.
+    public entry fun sweep(entries: u64) {
+        let visited = 0;
+        while (visited < entries) {
+            visited = visited + 1;
+        }
+    }
+
+    /// Does nothing at all.
+    public entry fun noop() {}
+}
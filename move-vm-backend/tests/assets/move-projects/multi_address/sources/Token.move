@@ -0,0 +1,13 @@
+module CafeAccount::Token {
+    struct Token has store, drop {
+        value: u64,
+    }
+
+    public fun mint(value: u64): Token {
+        Token { value }
+    }
+
+    public fun value(token: &Token): u64 {
+        token.value
+    }
+}
@@ -0,0 +1,9 @@
+module BobAccount::Vault {
+    use CafeAccount::Token;
+
+    /// Value of the freshly minted token.
+    public fun minted_value(value: u64): u64 {
+        let token = Token::mint(value);
+        Token::value(&token)
+    }
+}
@@ -0,0 +1,6 @@
+/// The first version of the price feed.
+module CafeAccount::Feed {
+    public fun price(): u64 {
+        100
+    }
+}
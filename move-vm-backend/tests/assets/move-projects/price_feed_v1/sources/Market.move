@@ -0,0 +1,12 @@
+/// Depends on the feed through the oracle.
+module CafeAccount::Market {
+    use CafeAccount::Oracle;
+
+    struct Quote has key {
+        price: u64
+    }
+
+    entry public fun record(account: &signer) {
+        move_to(account, Quote { price: Oracle::quote() });
+    }
+}
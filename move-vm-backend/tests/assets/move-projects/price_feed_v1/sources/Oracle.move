@@ -0,0 +1,8 @@
+/// Depends on the feed directly.
+module CafeAccount::Oracle {
+    use CafeAccount::Feed;
+
+    public fun quote(): u64 {
+        Feed::price()
+    }
+}
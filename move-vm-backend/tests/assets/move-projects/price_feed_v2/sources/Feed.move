@@ -0,0 +1,6 @@
+/// The second version of the price feed, compatible with the first one.
+module CafeAccount::Feed {
+    public fun price(): u64 {
+        200
+    }
+}
@@ -0,0 +1,5 @@
+script {
+    fun unchecked_double<T>(x: u64) {
+        let _y = x * 2;
+    }
+}
@@ -0,0 +1,3 @@
+script {
+    fun vector_lengths(_numbers: vector<u64>, _bytes: vector<u8>) {}
+}
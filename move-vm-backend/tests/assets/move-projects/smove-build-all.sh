@@ -4,16 +4,36 @@
 cd $(dirname $0)
 
 build_dir=(
+    "account_lifecycle"
     "address_checks"
     "basic_coin"
+    "bundle_init"
+    "call_composition"
+    "canonical_order"
+    "chain_aware"
+    "counter_v1"
+    "counter_v2"
+    "deep_recursion"
     "depends_on__using_stdlib_full"
     "depends_on__using_stdlib_natives"
     "empty"
+    "event_emitter"
+    "expiring_session"
+    "fee_sponsor"
+    "foreign_bridge"
+    "generic_vaults"
+    "host_environment"
+    "maintenance"
+    "multi_address"
+    "price_feed_v1"
+    "price_feed_v2"
     "simple_scripts"
+    "std_args"
     "using_stdlib_full"
     "substrate_balance"
+    "xcm_sender"
 )
-bundle_dir=("using_stdlib_natives")
+bundle_dir=("bundle_init" "multi_address" "using_stdlib_natives")
 
 # Build simple packages
 for i in "${build_dir[@]}"; do
@@ -0,0 +1,15 @@
+module CafeAccount::Profile {
+    use std::option::Option;
+    use std::string::String;
+
+    struct Profile has key {
+        name: String,
+        age: Option<u64>,
+        tags: vector<String>,
+    }
+
+    /// Creates the profile from the standard library typed arguments.
+    public entry fun create(account: &signer, name: String, age: Option<u64>, tags: vector<String>) {
+        move_to(account, Profile { name, age, tags });
+    }
+}
@@ -46,3 +46,22 @@ script {
         assert!(dst_cheque_amount == amount, 0);
     }
 }
+
+script {
+    use substrate::balance;
+
+    fun transfer_in_loop(src: signer, dst: address, count: u64) {
+        while (count > 0) {
+            assert!(balance::transfer(&src, dst, 0), 0);
+            count = count - 1;
+        }
+    }
+}
+
+script {
+    use substrate::balance;
+
+    fun transfer_with_outcome(src: signer, dst: address, amount: u128, expected: bool) {
+        assert!(balance::transfer(&src, dst, amount) == expected, 0);
+    }
+}
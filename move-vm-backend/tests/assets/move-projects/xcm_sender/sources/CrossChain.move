@@ -0,0 +1,29 @@
+/// Cross-chain messages sent through the test host.
+module CafeAccount::CrossChain {
+    use std::xcm;
+
+    entry public fun transfer_to_sibling(
+        sender: signer,
+        parachain: u32,
+        beneficiary: address,
+        amount: u128,
+    ) {
+        let message = xcm::transfer_asset(
+            xcm::sibling_parachain(parachain),
+            xcm::relay_asset(amount),
+            xcm::account(beneficiary),
+        );
+        xcm::send(&sender, message);
+    }
+
+    entry public fun transact_on_relay(sender: signer, call: vector<u8>) {
+        let message = xcm::transact(
+            xcm::relay_chain(),
+            xcm::origin_sovereign_account(),
+            1_000_000,
+            10_000,
+            call,
+        );
+        xcm::send(&sender, message);
+    }
+}
@@ -0,0 +1,208 @@
+//! Tests for the `pallet-assets` host bindings against a mock runtime.
+#![cfg(feature = "substrate")]
+
+use frame_support::{
+    construct_runtime, derive_impl, parameter_types,
+    traits::{AsEnsureOriginWithArg, ConstU128, ConstU32},
+};
+use frame_system::{EnsureRoot, EnsureSigned};
+use move_core_types::account_address::AccountAddress;
+use move_vm_backend::fungibles::FungiblesAdapter;
+use move_vm_backend::host::HostBindings;
+use move_vm_backend_test_utils::{check_balance_contract, ConformanceAccounts};
+use sp_runtime::{traits::Convert, BuildStorage};
+
+type AccountId = u64;
+type Balance = u128;
+
+const ASSET: u32 = 7;
+const MIN_BALANCE: Balance = 10;
+
+construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        Assets: pallet_assets,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = frame_system::mocking::MockBlock<Test>;
+    type AccountData = pallet_balances::AccountData<Balance>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig as pallet_balances::DefaultConfig)]
+impl pallet_balances::Config for Test {
+    type Balance = Balance;
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+}
+
+impl pallet_assets::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = Balance;
+    type RemoveItemsLimit = ConstU32<1000>;
+    type AssetId = u32;
+    type AssetIdParameter = u32;
+    type Currency = Balances;
+    type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type AssetDeposit = ConstU128<0>;
+    type AssetAccountDeposit = ConstU128<0>;
+    type MetadataDepositBase = ConstU128<0>;
+    type MetadataDepositPerByte = ConstU128<0>;
+    type ApprovalDeposit = ConstU128<0>;
+    type StringLimit = ConstU32<50>;
+    type Freezer = ();
+    type Extra = ();
+    type CallbackHandle = ();
+    type WeightInfo = ();
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = ();
+}
+
+parameter_types! {
+    pub const AssetId: u32 = ASSET;
+}
+
+/// Maps the Move addresses to the runtime accounts by their last eight bytes.
+pub struct AddressToAccount;
+
+impl Convert<AccountAddress, AccountId> for AddressToAccount {
+    fn convert(address: AccountAddress) -> AccountId {
+        let bytes = address.into_bytes();
+        AccountId::from_be_bytes(bytes[AccountAddress::LENGTH - 8..].try_into().unwrap())
+    }
+}
+
+type Adapter = FungiblesAdapter<AccountId, Assets, AssetId, AddressToAccount>;
+
+fn address(account: AccountId) -> AccountAddress {
+    let mut bytes = [0u8; AccountAddress::LENGTH];
+    bytes[AccountAddress::LENGTH - 8..].copy_from_slice(&account.to_be_bytes());
+    AccountAddress::new(bytes)
+}
+
+const ALICE: AccountId = 1;
+const BOB: AccountId = 2;
+const CHARLIE: AccountId = 3;
+
+/// Runtime with the asset where only Alice holds 100 units.
+fn new_test_ext() -> sp_io::TestExternalities {
+    let storage = RuntimeGenesisConfig {
+        system: Default::default(),
+        balances: Default::default(),
+        assets: pallet_assets::GenesisConfig {
+            assets: vec![(ASSET, ALICE, true, MIN_BALANCE)],
+            metadata: vec![],
+            accounts: vec![(ASSET, ALICE, 100)],
+        },
+    }
+    .build_storage()
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+#[test]
+fn transfers_are_limited_by_the_cheques() {
+    new_test_ext().execute_with(|| {
+        let adapter = Adapter::new();
+        let (alice, bob) = (address(ALICE), address(BOB));
+
+        // Nothing can be spent without a cheque.
+        assert_eq!(adapter.cheque_amount(alice), Ok(0));
+        assert_eq!(adapter.transfer(alice, bob, 20), Ok(false));
+
+        adapter.write_cheque(alice, 30);
+        assert_eq!(adapter.cheque_amount(alice), Ok(30));
+        assert_eq!(adapter.transfer(alice, bob, 20), Ok(true));
+        assert_eq!(adapter.cheque_amount(alice), Ok(10));
+        assert_eq!(adapter.total_amount(alice), Ok(80));
+        assert_eq!(adapter.total_amount(bob), Ok(20));
+
+        // The rest of the cheque can't be exceeded.
+        assert_eq!(adapter.transfer(alice, bob, 11), Ok(false));
+        assert_eq!(adapter.total_amount(alice), Ok(80));
+
+        adapter.clear_cheques();
+        assert_eq!(adapter.cheque_amount(alice), Ok(0));
+        assert_eq!(adapter.total_issuance(), Ok(100));
+    });
+}
+
+#[test]
+fn transfers_respect_the_existential_deposit() {
+    new_test_ext().execute_with(|| {
+        let adapter = Adapter::new();
+        let (alice, bob, charlie) = (address(ALICE), address(BOB), address(CHARLIE));
+        assert_eq!(adapter.minimum_balance(), Ok(MIN_BALANCE));
+
+        // The cheque is capped by the amount which keeps the source account alive.
+        adapter.write_cheque(alice, 1000);
+        assert_eq!(adapter.cheque_amount(alice), Ok(100 - MIN_BALANCE));
+        assert_eq!(adapter.transfer(alice, bob, 100), Ok(false));
+        assert_eq!(adapter.total_amount(alice), Ok(100));
+
+        // New accounts must receive at least the existential deposit.
+        assert_eq!(adapter.account_exists(bob), Ok(false));
+        assert_eq!(adapter.transfer(alice, bob, MIN_BALANCE - 1), Ok(false));
+        assert_eq!(adapter.account_exists(bob), Ok(false));
+        assert_eq!(adapter.total_amount(alice), Ok(100));
+
+        assert_eq!(adapter.transfer(alice, bob, MIN_BALANCE), Ok(true));
+        assert_eq!(adapter.account_exists(bob), Ok(true));
+
+        // Existing accounts can receive any amount, but can't drop below the existential deposit.
+        assert_eq!(adapter.transfer(alice, bob, 1), Ok(true));
+        adapter.write_cheque(bob, 1000);
+        assert_eq!(adapter.cheque_amount(bob), Ok(1));
+        assert_eq!(adapter.transfer(bob, charlie, MIN_BALANCE + 1), Ok(false));
+        assert_eq!(adapter.total_amount(bob), Ok(MIN_BALANCE + 1));
+        assert_eq!(adapter.total_issuance(), Ok(100));
+    });
+}
+
+#[test]
+fn accounts_are_created_with_the_existential_deposit() {
+    new_test_ext().execute_with(|| {
+        let adapter = Adapter::new();
+        let (alice, bob) = (address(ALICE), address(BOB));
+
+        // The payer needs a cheque covering the existential deposit.
+        assert_eq!(adapter.create_account(alice, bob), Ok(false));
+        assert_eq!(adapter.account_exists(bob), Ok(false));
+
+        adapter.write_cheque(alice, MIN_BALANCE);
+        assert_eq!(adapter.create_account(alice, bob), Ok(true));
+        assert_eq!(adapter.account_exists(bob), Ok(true));
+        assert_eq!(adapter.total_amount(bob), Ok(MIN_BALANCE));
+
+        // Existing accounts are left untouched.
+        assert_eq!(adapter.create_account(alice, bob), Ok(true));
+        assert_eq!(adapter.total_amount(bob), Ok(MIN_BALANCE));
+        assert_eq!(adapter.total_amount(alice), Ok(100 - MIN_BALANCE));
+    });
+}
+
+#[test]
+fn adapter_follows_the_balance_contract() {
+    new_test_ext().execute_with(|| {
+        let adapter = Adapter::new();
+        let (alice, bob, charlie) = (address(ALICE), address(BOB), address(CHARLIE));
+
+        adapter.write_cheque(alice, 1000);
+        assert_eq!(adapter.transfer(alice, bob, MIN_BALANCE), Ok(true));
+        check_balance_contract(
+            &adapter,
+            &ConformanceAccounts {
+                funded: alice,
+                existing: bob,
+                missing: charlie,
+            },
+        );
+    });
+}
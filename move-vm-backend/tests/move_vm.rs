@@ -5,20 +5,81 @@
 //! Some of these tests use addresses that need to match the address in Move project files -
 //! otherwise executing scripts or publishing won't work as expected.
 //!
-use crate::mock::BalanceMock;
+use crate::mock::HostMock;
 use crate::mock::StorageMock;
+use crate::mock::{ECHO_TARGET, FAILING_TARGET, FAILING_TARGET_ABORT_CODE};
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use move_binary_format::file_format::{
+    empty_module, AddressIdentifierIndex, IdentifierIndex, ModuleHandle,
+};
+use move_binary_format::CompiledModule;
 use move_core_types::account_address::AccountAddress;
-use move_core_types::identifier::Identifier;
-use move_core_types::language_storage::StructTag;
+use move_core_types::gas_algebra::{InternalGas, NumArgs};
+use move_core_types::identifier::{IdentStr, Identifier};
 use move_core_types::language_storage::CORE_CODE_ADDRESS as ADDR_STD;
-use move_vm_backend::balance::BalanceHandler;
-use move_vm_backend::genesis::VmGenesisConfig;
-use move_vm_backend::types::GasAmount;
+use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::vm_status::StatusCode;
+use move_vm_backend::acl::owner_only_metadata;
+use move_vm_backend::allowlist::allowed_script_hash;
+use move_vm_backend::arg_limits::{VectorArgLimits, VectorLimit};
+use move_vm_backend::block::{BlockError, BlockSummary};
+use move_vm_backend::fee_hook::FeeHook;
+use move_vm_backend::fee_policy::{FeeMultiplier, LinearFee};
+use move_vm_backend::freeze::FreezeCapability;
+use move_vm_backend::genesis::{ChainConfigProvider, GenesisSnapshot, VmGenesisConfig};
+use move_vm_backend::host::{ForeignCallResponse, HostBindings, XcmMessage};
+use move_vm_backend::identifier_policy::{check_module_bytes, IdentifierKind, NamingPolicy};
+use move_vm_backend::metrics::Metrics;
+use move_vm_backend::migration::{layout_hash, Migration};
+use move_vm_backend::multisig::{MultisigError, MultisigStatus};
+use move_vm_backend::preverify::{PendingCode, PreverificationQueue, VerifiedCode};
+use move_vm_backend::privileged::PublishCapability;
+use move_vm_backend::reentrancy::ReentrancyGuard;
+use move_vm_backend::retirement::RetireError;
+use move_vm_backend::storage::Storage;
+use move_vm_backend::storage_cache::{
+    CacheStats, CachedStorage, LruReadCache, StorageCache, WriteBackCache,
+};
+use move_vm_backend::storage_dump::{DumpError, StorageDump, DUMP_MAGIC, DUMP_VERSION};
+use move_vm_backend::storage_key::StorageKey;
+use move_vm_backend::subscription::FinalizedChanges;
+use move_vm_backend::system_calls::SystemCallCapability;
+use move_vm_backend::templates::{
+    ScriptTemplate, TemplateArg, TemplateError, TemplateParam, TemplateParamType,
+};
+use move_vm_backend::types::{Call, ErrorStage, GasAmount, Transaction};
+use move_vm_backend::upgrade::InvalidationReport;
+use move_vm_backend::warm_up::WarmUpOutcome;
 use move_vm_backend::Mvm;
-use move_vm_backend_common::types::ModuleBundle;
-
+use move_vm_backend_common::call_builder::{CallBuilder, CallBuilderError, Composition, EntryCall};
+use move_vm_backend_common::chain_config::ChainConfig;
+use move_vm_backend_common::code_hash::{module_hash, script_hash};
+use move_vm_backend_common::gas_schedule::{
+    argument_cost, publishing_cost, DEPENDENCY_BYTE_COST, DEPENDENCY_COST, NATIVE_COST_PARAMS,
+};
+use move_vm_backend_common::receipt::{
+    event_leaf_hash, merkle_root, StatePath, StateWrite, EMPTY_ROOT,
+};
+use move_vm_backend_common::script_lint::LintWarning;
+use move_vm_backend_common::types::{ModuleBundle, ScriptTransaction};
+use move_vm_backend_common::verification_bound::{
+    bundle_publish_complexity, module_publish_complexity,
+};
+use move_vm_backend_common::xcm::{
+    XcmAsset, XcmAssetId, XcmInstruction, XcmLocation, XcmOriginKind,
+};
+use move_vm_backend_test_utils::gas::{fuzz_gas_limits, min_gas_limit};
+use move_vm_backend_test_utils::{
+    check_balance_contract, seeded_accounts, ConformanceAccounts, HostFailure, StorageFailure,
+};
+
+use move_command_line_common::testing::read_env_update_baseline;
 use move_core_types::language_storage::TypeTag;
 use move_vm_backend::types::GasStrategy;
+use move_vm_test_utils::gas_schedule::GasUnit;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::{Rc, Weak};
 
 pub mod mock;
 
@@ -58,12 +119,64 @@ fn read_script_bytes_from_project(project: &str, script_name: &str) -> Vec<u8> {
     read_bytes(&path)
 }
 
-/// Estimate gas for published module / bundle.
-#[inline]
-fn estimate_gas_for_published_bytecode(bytecode: &[u8]) -> u64 {
-    let raw_gas_cost =
-        bytecode.len() as u64 * move_vm_backend_common::gas_schedule::GAS_COST_PER_PUBLISHED_BYTE;
-    num_integer::div_ceil(raw_gas_cost, 1000)
+/// Publishes the `BasicCoin` module, creates and mints balances for a few accounts and returns
+/// the hash of all execution outputs and the final storage state.
+fn basic_coin_outputs_digest() -> [u8; 32] {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store.clone(), HostMock::new()).unwrap();
+    vm.set_gas_profiling(true);
+    let gas = GasStrategy::Metered(GasAmount::max());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let mut results = vec![vm.publish_module(&module, cafe, gas)];
+
+    let publish_balance = read_script_bytes_from_project("basic_coin", "publish_balance");
+    let mint_some = read_script_bytes_from_project("basic_coin", "mint_some");
+    let cafe_param = bcs::to_bytes(&cafe).unwrap();
+    for (who, amount) in [
+        ("0xCAFE", 1u64),
+        ("0xB0B", 20),
+        ("0xA11CE", 300),
+        ("0xBEEF", 4000),
+    ] {
+        let who = bcs::to_bytes(&AccountAddress::from_hex_literal(who).unwrap()).unwrap();
+        let amount = bcs::to_bytes(&amount).unwrap();
+        results.push(vm.execute_script(&publish_balance, vec![], vec![&who], gas));
+        let args: Vec<&[u8]> = vec![&cafe_param, &who, &amount];
+        results.push(vm.execute_script(&mint_some, vec![], args, gas));
+    }
+
+    let mut hasher = Blake2b::<U32>::new();
+    for result in results {
+        assert!(result.is_ok(), "execution failed: {result:?}");
+        hasher.update(bcs::to_bytes(&(result.gas_used, result.gas_refund)).unwrap());
+        hasher.update(bcs::to_bytes(&result.gas_profile).unwrap());
+        hasher.update(bcs::to_bytes(&result.events).unwrap());
+    }
+
+    // The mock storage doesn't keep the order, so the entries are sorted before hashing.
+    let data = store.data.borrow();
+    let entries: std::collections::BTreeMap<_, _> = data.iter().collect();
+    hasher.update(bcs::to_bytes(&entries).unwrap());
+
+    hasher.finalize().into()
+}
+
+/// Estimate gas for published module.
+fn estimate_gas_for_published_module(module: &[u8]) -> u64 {
+    let complexity = module_publish_complexity(module).unwrap();
+    publishing_cost(module.len(), &complexity)
+        .to_unit_round_up::<GasUnit>()
+        .into()
+}
+
+/// Estimate gas for published bundle.
+fn estimate_gas_for_published_bundle(bundle: &[u8]) -> u64 {
+    let complexity = bundle_publish_complexity(bundle).unwrap();
+    publishing_cost(bundle.len(), &complexity)
+        .to_unit_round_up::<GasUnit>()
+        .into()
 }
 
 fn store_preloaded_with_genesis_cfg() -> StorageMock {
@@ -81,7 +194,7 @@ fn store_preloaded_with_genesis_cfg() -> StorageMock {
 #[test]
 fn publish_module_test() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
 
     let address = AccountAddress::from_hex_literal("0xCAFE").unwrap();
     let module = read_module_bytes_from_project("empty", "Empty");
@@ -92,7 +205,7 @@ fn publish_module_test() {
 
     let result = vm.publish_module(&module, address, gas);
 
-    let estimated_gas = estimate_gas_for_published_bytecode(&module);
+    let estimated_gas = estimate_gas_for_published_module(&module);
     assert!(result.is_ok(), "failed to publish the module");
     assert_eq!(result.gas_used, estimated_gas, "invalid gas estimate");
     assert!(
@@ -103,7 +216,7 @@ fn publish_module_test() {
     // Prove that publishing will fail with insufficient gas.
     {
         let store = StorageMock::new();
-        let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+        let vm = Mvm::new(store, HostMock::new()).unwrap();
         let gas = GasStrategy::Metered(GasAmount::new(estimated_gas).unwrap());
         let result = vm.publish_module(&module, address, gas);
         assert!(result.is_ok(), "failed to publish the module");
@@ -112,7 +225,7 @@ fn publish_module_test() {
     // Prove that publishing will succeeded with the exact amount of gas.
     {
         let store = StorageMock::new();
-        let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+        let vm = Mvm::new(store, HostMock::new()).unwrap();
         let gas = GasStrategy::Metered(GasAmount::new(estimated_gas - 1).unwrap());
         let result = vm.publish_module(&module, address, gas);
         assert!(result.is_err(), "failed to publish the module");
@@ -122,7 +235,7 @@ fn publish_module_test() {
 #[test]
 fn publish_module_bundle_from_multiple_module_files() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
     let gas = GasStrategy::Unmetered;
 
     let module_1 = read_module_bytes_from_project("using_stdlib_natives", "Vector");
@@ -138,7 +251,7 @@ fn publish_module_bundle_from_multiple_module_files() {
 
     // Recreate the storage and the MoveVM
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
     // Order matters - we cannot publish module_2 before module_1!
     let modules = ModuleBundle::new(vec![module_2, module_1])
         .encode()
@@ -153,7 +266,7 @@ fn publish_module_bundle_from_multiple_module_files() {
 #[test]
 fn publish_module_bundle_from_bundle_file() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
     let provided_gas_amount = GasAmount::max();
     let gas = GasStrategy::Metered(provided_gas_amount);
 
@@ -163,7 +276,7 @@ fn publish_module_bundle_from_bundle_file() {
     let result = vm.publish_module_bundle(&bundle, addr, gas);
     assert!(result.is_ok(), "failed to publish the bundle");
 
-    let estimated_gas = estimate_gas_for_published_bytecode(&bundle);
+    let estimated_gas = estimate_gas_for_published_bundle(&bundle);
     assert_eq!(result.gas_used, estimated_gas, "invalid gas estimate");
     assert!(
         result.gas_used < provided_gas_amount.inner(),
@@ -174,7 +287,7 @@ fn publish_module_bundle_from_bundle_file() {
 #[test]
 fn publish_module_dependent_on_stdlib_natives() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
     let gas = GasStrategy::Unmetered;
 
     let mod_using_stdlib_natives = read_module_bytes_from_project("using_stdlib_natives", "Vector");
@@ -199,7 +312,7 @@ fn publish_module_dependent_on_stdlib_natives() {
 #[test]
 fn publish_module_using_stdlib_full_fails() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
     let gas = GasStrategy::Unmetered;
 
     let mod_using_stdlib_natives =
@@ -216,7 +329,7 @@ fn publish_module_using_stdlib_full_fails() {
 fn genesis_config_inits_stdlib_so_stdlib_full_can_be_published() {
     let store = store_preloaded_with_genesis_cfg();
 
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
 
     let module = read_module_bytes_from_project("using_stdlib_full", "StringAndVector");
     let address = AccountAddress::from_hex_literal("0x3").unwrap();
@@ -229,7 +342,7 @@ fn genesis_config_inits_stdlib_so_stdlib_full_can_be_published() {
 #[test]
 fn get_module_and_module_abi() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
 
     let module = read_module_bytes_from_project("using_stdlib_natives", "Vector");
     let address = AccountAddress::from_hex_literal("0x2").unwrap();
@@ -249,6 +362,31 @@ fn get_module_and_module_abi() {
     assert!(result.unwrap().is_some(), "failed to get the module abi");
 }
 
+#[test]
+fn get_address_abi_returns_all_modules() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+
+    let std_addr = AccountAddress::ONE;
+    let abis = vm
+        .get_address_abi(std_addr)
+        .expect("failed to get the address abi");
+    assert!(!abis.is_empty(), "stdlib modules are missing");
+
+    let names: Vec<_> = abis.iter().map(|abi| abi.id.name().to_owned()).collect();
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted, "modules are not ordered by name");
+
+    for abi in &abis {
+        let single = vm.get_module_abi(std_addr, abi.id.name().as_str());
+        assert_eq!(single.unwrap().as_ref(), Some(abi));
+    }
+
+    let empty = AccountAddress::from_hex_literal("0xDEAD").unwrap();
+    assert!(vm.get_address_abi(empty).unwrap().is_empty());
+}
+
 #[test]
 fn get_resource() {
     let store = StorageMock::new();
@@ -261,7 +399,7 @@ fn get_resource() {
         "failed to apply the genesis configuration"
     );
 
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
 
     // Publish a module that can create resources.
     let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
@@ -327,6 +465,17 @@ fn get_resource() {
         "failure: the amount of coins shouldn't be the same"
     );
 
+    // The decoded resource follows the canonical schema.
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    let value = vm.get_resource_value(&bob, &tag).unwrap();
+    let value = value.expect("resource not found");
+    assert_eq!(value.to_json(), r#"{"coin":{"value":"5"}}"#);
+
     // ---- an extra test case here ----
     // Make sure the non-existing resource actually doesn't exist.
     let tag = StructTag {
@@ -344,7 +493,7 @@ fn get_resource() {
 #[test]
 fn execute_script_with_no_params_test() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
 
     let script = read_script_bytes_from_project("simple_scripts", "empty_loop");
 
@@ -361,7 +510,7 @@ fn execute_script_with_no_params_test() {
 #[test]
 fn execute_script_params_test() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
 
     let script = read_script_bytes_from_project("simple_scripts", "empty_loop_param");
 
@@ -379,7 +528,7 @@ fn execute_script_params_test() {
 #[test]
 fn execute_script_generics_test() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
 
     let script = read_script_bytes_from_project("simple_scripts", "generic_1");
 
@@ -406,7 +555,7 @@ fn execute_script_generics_test() {
 #[test]
 fn execute_script_generics_incorrect_params_test() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
 
     let script = read_script_bytes_from_project("simple_scripts", "generic_1");
 
@@ -443,7 +592,7 @@ fn execute_function_test() {
         "failed to apply the genesis configuration"
     );
 
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
 
     let address = AccountAddress::from_hex_literal("0xCAFE").unwrap();
     let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
@@ -465,7 +614,7 @@ fn execute_function_test() {
 #[test]
 fn publishing_fails_with_insufficient_gas() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
 
     // Try to publish a bundle
     {
@@ -498,7 +647,7 @@ fn publishing_fails_with_insufficient_gas() {
 #[test]
 fn script_execution_fails_with_insufficient_gas() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
     let gas = GasStrategy::Unmetered;
 
     let stdlib = move_stdlib::move_stdlib_bundle();
@@ -530,7 +679,7 @@ fn script_execution_fails_with_insufficient_gas() {
 #[test]
 fn dry_run_gas_strategy_doesnt_update_storage() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
 
     let module = read_module_bytes_from_project("using_stdlib_natives", "Vector");
     let address = AccountAddress::from_hex_literal("0x2").unwrap();
@@ -539,7 +688,7 @@ fn dry_run_gas_strategy_doesnt_update_storage() {
     let result = vm.publish_module(&module, address, gas);
     assert!(result.is_ok(), "failed to publish the module");
 
-    let estimated_gas = estimate_gas_for_published_bytecode(&module);
+    let estimated_gas = estimate_gas_for_published_module(&module);
     assert_eq!(result.gas_used, estimated_gas, "invalid gas estimate");
 
     let result = vm.get_module(address, "Vector");
@@ -553,7 +702,7 @@ fn dry_run_gas_strategy_doesnt_update_storage() {
 #[test]
 fn manually_publish_substrate_stdlib_bundle() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
     let gas = GasStrategy::Unmetered;
 
     let stdlib = move_stdlib::substrate_stdlib_bundle();
@@ -564,7 +713,7 @@ fn manually_publish_substrate_stdlib_bundle() {
 #[test]
 fn run_scipt_that_simply_tests_balance_api() {
     let store = store_preloaded_with_genesis_cfg();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
     let gas = GasStrategy::Unmetered;
 
     let script = read_script_bytes_from_project("substrate_balance", "balance_simple_api_test");
@@ -586,7 +735,7 @@ fn run_scipt_that_simply_tests_balance_api() {
 #[test]
 fn execute_transfer_script_and_check_balance_updates() {
     let store = store_preloaded_with_genesis_cfg();
-    let mut balance = BalanceMock::new();
+    let mut balance = HostMock::new();
     let vm = Mvm::new(store, balance.clone()).unwrap();
     let gas = GasStrategy::Unmetered;
 
@@ -620,3 +769,3738 @@ fn execute_transfer_script_and_check_balance_updates() {
     let result = vm.execute_script(&script, type_args, params, gas);
     assert!(!result.is_ok(), "managed to execute the script");
 }
+
+#[test]
+fn transfer_survives_injected_host_failures() {
+    let store = store_preloaded_with_genesis_cfg();
+    let accounts = seeded_accounts(7, 2);
+    assert_eq!(accounts, seeded_accounts(7, 2));
+    let (src, dst) = (accounts[0], accounts[1]);
+
+    let balance = HostMock::with_accounts(&accounts, 10);
+    let vm = Mvm::new(store, balance.clone()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let script = read_script_bytes_from_project("substrate_balance", "execute_transfer");
+    let amount_param = bcs::to_bytes(&10u128).unwrap();
+    let src_addr = bcs::to_bytes(&src).unwrap();
+    let dst_addr = bcs::to_bytes(&dst).unwrap();
+    let params: Vec<&[u8]> = vec![&src_addr, &dst_addr, &amount_param];
+
+    balance.inject_failure(HostFailure::Transfer, StatusCode::VM_EXTENSION_ERROR);
+    let result = vm.execute_script(&script, vec![], params.clone(), gas);
+    assert!(!result.is_ok(), "the transfer should fail");
+    assert_eq!(balance.cheque_amount(src).unwrap(), 10);
+    assert_eq!(balance.cheque_amount(dst).unwrap(), 10);
+
+    balance.clear_failures();
+    let result = vm.execute_script(&script, vec![], params, gas);
+    assert!(result.is_ok(), "failed to execute the script");
+    assert_eq!(balance.cheque_amount(src).unwrap(), 0);
+    assert_eq!(balance.cheque_amount(dst).unwrap(), 20);
+}
+
+#[test]
+fn transfer_edge_cases_follow_the_balance_contract() {
+    let store = store_preloaded_with_genesis_cfg();
+    let accounts = seeded_accounts(11, 3);
+    let (src, rich, fresh) = (accounts[0], accounts[1], accounts[2]);
+
+    let mut balance = HostMock::with_accounts(&[src], 100);
+    balance.write_cheque(rich, u128::MAX - 5);
+    balance.set_minimum_balance(10);
+    let vm = Mvm::new(store, balance.clone()).unwrap();
+
+    let script = read_script_bytes_from_project("substrate_balance", "transfer_with_outcome");
+    let transfer = |dst: AccountAddress, amount: u128, expected: bool| {
+        let args = [
+            bcs::to_bytes(&src).unwrap(),
+            bcs::to_bytes(&dst).unwrap(),
+            bcs::to_bytes(&amount).unwrap(),
+            bcs::to_bytes(&expected).unwrap(),
+        ];
+        let args: Vec<&[u8]> = args.iter().map(Vec::as_slice).collect();
+        let result = vm.execute_script(&script, vec![], args, GasStrategy::Unmetered);
+        assert!(result.is_ok(), "unexpected transfer outcome: {result:?}");
+    };
+    let balances = || balance.snapshot();
+    let initial = balances();
+
+    // Zero transfers succeed without creating the destination.
+    transfer(fresh, 0, true);
+    assert_eq!(balances(), initial);
+    assert_eq!(balance.account_exists(fresh), Ok(false));
+
+    // Transfers to self only check the cheque.
+    transfer(src, 100, true);
+    transfer(src, 101, false);
+    assert_eq!(balances(), initial);
+
+    // The destination balance can't overflow.
+    transfer(rich, 6, false);
+    assert_eq!(balances(), initial);
+    transfer(rich, 5, true);
+    assert_eq!(balance.total_amount(rich), Ok(u128::MAX));
+
+    // New accounts must receive at least the minimum balance.
+    transfer(fresh, 9, false);
+    assert_eq!(balance.account_exists(fresh), Ok(false));
+    transfer(fresh, 10, true);
+    assert_eq!(balance.total_amount(fresh), Ok(10));
+
+    // Existing accounts can receive any amount.
+    transfer(fresh, 1, true);
+    assert_eq!(balance.total_amount(src), Ok(84));
+    assert_eq!(balance.total_amount(fresh), Ok(11));
+}
+
+#[test]
+fn balance_mock_follows_the_balance_contract() {
+    let accounts = seeded_accounts(13, 3);
+    for minimum_balance in [0, 1, 10] {
+        let balance = HostMock::with_accounts(&accounts[..2], 100);
+        balance.set_minimum_balance(minimum_balance);
+        check_balance_contract(
+            &balance,
+            &ConformanceAccounts {
+                funded: accounts[0],
+                existing: accounts[1],
+                missing: accounts[2],
+            },
+        );
+    }
+}
+
+#[test]
+fn publishing_with_fuzzed_gas_limits_is_atomic() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store.clone(), HostMock::new()).unwrap();
+    let address = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("empty", "Empty");
+    let estimated_gas = estimate_gas_for_published_module(&module);
+
+    let snapshot = store.snapshot();
+    let mut publish = |gas| {
+        store.restore(&snapshot);
+        vm.publish_module(&module, address, gas)
+    };
+    assert_eq!(
+        min_gas_limit(10 * estimated_gas, &mut publish),
+        Some(estimated_gas)
+    );
+
+    for (limit, result) in fuzz_gas_limits(3, 2 * estimated_gas, 16, &mut publish) {
+        assert_eq!(result.is_ok(), limit >= estimated_gas, "limit {limit}");
+        let published = vm.get_module(address, "Empty").unwrap().is_some();
+        assert_eq!(published, result.is_ok(), "limit {limit}");
+    }
+
+    // The dropped writes leave the storage as it was.
+    store.restore(&snapshot);
+    store.inject_failure(StorageFailure::DroppedWrite, &[]);
+    let result = vm.publish_module(&module, address, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the module");
+    assert_eq!(store.snapshot(), snapshot);
+}
+
+#[test]
+fn deleting_resources_reports_gas_refund() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::max());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+    assert_eq!(result.gas_refund, 0, "publishing shouldn't be refunded");
+
+    let addr_param = bcs::to_bytes(&cafe).unwrap();
+    let params: Vec<&[u8]> = vec![&addr_param];
+
+    let script = read_script_bytes_from_project("basic_coin", "publish_balance");
+    let result = vm.execute_script(&script, vec![], params.clone(), gas);
+    assert!(result.is_ok(), "failed to publish the balance");
+    assert_eq!(
+        result.gas_refund, 0,
+        "creating a resource shouldn't be refunded"
+    );
+
+    let script = read_script_bytes_from_project("basic_coin", "destroy_balance");
+    let result = vm.execute_script(&script, vec![], params.clone(), gas);
+    assert!(result.is_ok(), "failed to destroy the balance");
+    assert!(
+        result.gas_refund > 0,
+        "deleting a resource should be refunded"
+    );
+    assert!(
+        result.gas_refund <= result.gas_used,
+        "refund shouldn't exceed the used gas"
+    );
+
+    // The unmetered strategy never reports a refund.
+    let script = read_script_bytes_from_project("basic_coin", "publish_balance");
+    let result = vm.execute_script(&script, vec![], params.clone(), GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the balance");
+    let script = read_script_bytes_from_project("basic_coin", "destroy_balance");
+    let result = vm.execute_script(&script, vec![], params, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to destroy the balance");
+    assert_eq!(
+        result.gas_refund, 0,
+        "unmetered execution shouldn't be refunded"
+    );
+}
+
+#[test]
+fn multisig_script_executes_after_all_signers_approve() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let transaction = ScriptTransaction {
+        bytecode: read_script_bytes_from_project("basic_coin", "publish_balance"),
+        args: vec![bcs::to_bytes(&cafe).unwrap()],
+        type_args: vec![],
+    }
+    .encode()
+    .unwrap();
+
+    let hash = vm.submit_multisig_script(&transaction).unwrap();
+    assert_eq!(
+        vm.submit_multisig_script(&transaction).unwrap_err(),
+        MultisigError::AlreadyPending
+    );
+
+    let pending = vm
+        .get_multisig_script(&hash)
+        .expect("pending script not found");
+    assert_eq!(pending.remaining_approvals(), 1);
+
+    // Only the script signers can approve the script.
+    let result = vm.approve_multisig_script(&hash, bob, gas);
+    assert_eq!(result.unwrap_err(), MultisigError::NotASigner);
+
+    let result = vm.approve_multisig_script(&hash, cafe, gas).unwrap();
+    match result {
+        MultisigStatus::Executed(result) => assert!(result.is_ok(), "script execution failed"),
+        MultisigStatus::Pending { .. } => panic!("script should have been executed"),
+    }
+    assert!(vm.get_multisig_script(&hash).is_none());
+
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    let resource = vm.get_resource(&cafe, &bcs::to_bytes(&tag).unwrap());
+    assert!(resource.unwrap().is_some(), "resource not found");
+
+    // A discarded script can't be approved anymore.
+    let hash = vm.submit_multisig_script(&transaction).unwrap();
+    assert!(vm.discard_multisig_script(&hash).is_ok());
+    let result = vm.approve_multisig_script(&hash, cafe, gas);
+    assert_eq!(result.unwrap_err(), MultisigError::NotFound);
+}
+
+#[test]
+fn multisig_script_without_signers_is_rejected() {
+    let vm = Mvm::new(StorageMock::new(), HostMock::new()).unwrap();
+
+    let transaction = ScriptTransaction {
+        bytecode: read_script_bytes_from_project("simple_scripts", "empty_loop"),
+        args: vec![],
+        type_args: vec![],
+    }
+    .encode()
+    .unwrap();
+
+    assert_eq!(
+        vm.submit_multisig_script(&transaction).unwrap_err(),
+        MultisigError::NoSigners
+    );
+}
+
+#[test]
+fn multisig_script_needs_the_approval_of_every_signer() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let alice = AccountAddress::from_hex_literal("0xA11CE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let signers = [cafe, bob, alice];
+    let transaction = ScriptTransaction {
+        bytecode: read_script_bytes_from_project("basic_coin", "publish_balances"),
+        args: signers.iter().map(|s| bcs::to_bytes(s).unwrap()).collect(),
+        type_args: vec![],
+    }
+    .encode()
+    .unwrap();
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+
+    // The approving signers can't act on behalf of alice.
+    let hash = vm.submit_multisig_script(&transaction).unwrap();
+    for approver in [cafe, bob] {
+        let result = vm.approve_multisig_script(&hash, approver, gas).unwrap();
+        assert!(
+            matches!(result, MultisigStatus::Pending { .. }),
+            "script shouldn't have been executed yet"
+        );
+    }
+    let pending = vm.get_multisig_script(&hash).unwrap();
+    assert_eq!(pending.remaining_approvals(), 1);
+    let resource = vm.get_resource(&alice, &bcs::to_bytes(&tag).unwrap());
+    assert!(resource.unwrap().is_none(), "alice was impersonated");
+
+    let result = vm.approve_multisig_script(&hash, bob, gas);
+    assert_eq!(result.unwrap_err(), MultisigError::AlreadyApproved);
+
+    let result = vm.approve_multisig_script(&hash, alice, gas).unwrap();
+    match result {
+        MultisigStatus::Executed(result) => assert!(result.is_ok(), "script execution failed"),
+        MultisigStatus::Pending { .. } => panic!("script should have been executed"),
+    }
+    for signer in signers {
+        let resource = vm.get_resource(&signer, &bcs::to_bytes(&tag).unwrap());
+        assert!(resource.unwrap().is_some(), "resource not found");
+    }
+}
+
+#[test]
+fn multisig_account_executes_once_threshold_is_reached() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let alice = AccountAddress::from_hex_literal("0xA11CE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let members = BTreeSet::from([cafe, bob, alice]);
+    for threshold in [0, 4] {
+        assert_eq!(
+            vm.register_multisig_account(members.clone(), threshold)
+                .unwrap_err(),
+            MultisigError::InvalidThreshold
+        );
+    }
+
+    // 2-of-3: the script only gets the signer of the multisig account.
+    let multisig = vm.register_multisig_account(members.clone(), 2).unwrap();
+    assert_eq!(vm.get_multisig_account(&multisig).unwrap().members, members);
+    let transaction = ScriptTransaction {
+        bytecode: read_script_bytes_from_project("basic_coin", "publish_balance"),
+        args: vec![bcs::to_bytes(&multisig).unwrap()],
+        type_args: vec![],
+    }
+    .encode()
+    .unwrap();
+
+    let hash = vm.submit_multisig_script(&transaction).unwrap();
+    let result = vm.approve_multisig_script(&hash, bob, gas).unwrap();
+    match result {
+        MultisigStatus::Pending {
+            remaining_approvals,
+        } => assert_eq!(remaining_approvals, 1),
+        MultisigStatus::Executed(_) => panic!("script shouldn't have been executed yet"),
+    }
+    let result = vm.approve_multisig_script(&hash, bob, gas);
+    assert_eq!(result.unwrap_err(), MultisigError::AlreadyApproved);
+
+    // Nobody holds the key of the multisig account, so it can't approve by itself.
+    let result = vm.approve_multisig_script(&hash, multisig, gas);
+    assert_eq!(result.unwrap_err(), MultisigError::NotASigner);
+
+    let result = vm.approve_multisig_script(&hash, alice, gas).unwrap();
+    match result {
+        MultisigStatus::Executed(result) => assert!(result.is_ok(), "script execution failed"),
+        MultisigStatus::Pending { .. } => panic!("script should have been executed"),
+    }
+    assert!(vm.get_multisig_script(&hash).is_none());
+
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    let resource = vm.get_resource(&multisig, &bcs::to_bytes(&tag).unwrap());
+    assert!(resource.unwrap().is_some(), "resource not found");
+    for member in members {
+        let resource = vm.get_resource(&member, &bcs::to_bytes(&tag).unwrap());
+        assert!(
+            resource.unwrap().is_none(),
+            "member signer was passed to the script"
+        );
+    }
+}
+
+#[test]
+fn instruction_count_gas_strategy_halts_runaway_loops() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+
+    let script = read_script_bytes_from_project("simple_scripts", "empty_loop_param");
+    let type_args: Vec<TypeTag> = vec![];
+
+    let execute_loop = |iter_count: u64, limit: u64| {
+        let iter_count = bcs::to_bytes(&iter_count).unwrap();
+        let params: Vec<&[u8]> = vec![&iter_count];
+        let gas = GasStrategy::InstructionCount(limit);
+        vm.execute_script(&script, type_args.clone(), params, gas)
+    };
+
+    let result_10 = execute_loop(10, u64::MAX);
+    assert!(result_10.is_ok(), "failed to execute the script");
+    let result_20 = execute_loop(20, u64::MAX);
+    assert!(result_20.is_ok(), "failed to execute the script");
+
+    // Each loop iteration executes the same amount of instructions.
+    assert!(result_20.gas_used > result_10.gas_used);
+    assert_eq!(result_10.gas_used, execute_loop(10, u64::MAX).gas_used);
+
+    // The exact instruction count is enough to execute the script.
+    let result = execute_loop(10, result_10.gas_used);
+    assert!(result.is_ok(), "failed to execute the script");
+
+    let result = execute_loop(1_000_000, result_10.gas_used);
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+}
+
+#[test]
+fn execute_call_built_against_on_chain_abi() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    // The signer address has an invalid type.
+    let builder = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("publish_balance")
+        .arg(&1u64)
+        .unwrap();
+    assert!(vm.build_call(builder).is_err(), "invalid call was built");
+
+    let builder = CallBuilder::module("0xCAFE::BasicCoin")
+        .function("publish_balance")
+        .arg(&cafe)
+        .unwrap();
+    let call = vm.build_call(builder).expect("failed to build the call");
+
+    let result = vm.execute_call(call, gas);
+    assert!(result.is_ok(), "failed to execute the call");
+}
+
+#[test]
+fn script_storage_footprint_is_reported() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let script = read_script_bytes_from_project("basic_coin", "publish_balance");
+
+    // The module isn't published yet.
+    assert!(vm.script_storage_footprint(&script, &[]).is_err());
+
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let balance_tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+
+    // Publishing the balance checks its existence and moves it to the storage.
+    let footprint = vm.script_storage_footprint(&script, &[]).unwrap();
+    assert!(footprint.reads.is_empty());
+    assert_eq!(footprint.writes.len(), 1);
+    assert!(footprint.writes.contains(&balance_tag));
+
+    let script = read_script_bytes_from_project("simple_scripts", "empty_loop");
+    let footprint = vm.script_storage_footprint(&script, &[]).unwrap();
+    assert!(footprint.is_empty());
+}
+
+#[test]
+fn execute_block_detects_conflicting_transactions() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::max());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let script = read_script_bytes_from_project("basic_coin", "publish_balance");
+    let publish_balance = |account: &AccountAddress| ScriptTransaction {
+        bytecode: script.clone(),
+        args: vec![bcs::to_bytes(account).unwrap()],
+        type_args: vec![],
+    };
+
+    // The last transaction reads the balance published by the first one.
+    let block = vec![
+        publish_balance(&cafe),
+        publish_balance(&bob),
+        publish_balance(&cafe),
+    ];
+    let results = vm.execute_block(block, gas);
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok(), "failed to publish the balance");
+    assert!(results[1].is_ok(), "failed to publish the balance");
+    assert_eq!(results[2].status_code, StatusCode::ABORTED);
+
+    // The block gives the same results as the sequential execution.
+    let result = vm.execute_script(&script, vec![], vec![&bcs::to_bytes(&bob).unwrap()], gas);
+    assert_eq!(result.status_code, StatusCode::ABORTED);
+
+    // BasicCoin uses the module local `EALREADY_HAS_BALANCE` code without a category.
+    assert_eq!(result.abort_code, Some(2));
+    assert_eq!(result.canonical_error(), None);
+}
+
+#[test]
+fn type_arguments_are_charged_and_depth_limited() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+
+    let script = read_script_bytes_from_project("simple_scripts", "generic_1");
+    let nested_vector =
+        |depth: usize| (1..depth).fold(TypeTag::U8, |tag, _| TypeTag::Vector(Box::new(tag)));
+    // An empty outer vector.
+    let empty_vec = bcs::to_bytes(&Vec::<u8>::new()).unwrap();
+
+    let gas = GasStrategy::Metered(GasAmount::max());
+    let result = vm.execute_script(&script, vec![nested_vector(16)], vec![&empty_vec], gas);
+    assert!(result.is_ok(), "failed to execute the script");
+
+    // The type argument nodes alone exceed the gas limit.
+    let gas = GasStrategy::Metered(GasAmount::new(1).unwrap());
+    let result = vm.execute_script(&script, vec![nested_vector(16)], vec![&empty_vec], gas);
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+
+    // Too deeply nested type arguments are rejected even without metering.
+    let gas = GasStrategy::Unmetered;
+    let result = vm.execute_script(&script, vec![nested_vector(17)], vec![&empty_vec], gas);
+    assert_eq!(result.status_code, StatusCode::VM_MAX_TYPE_DEPTH_REACHED);
+
+    // The maximum depth is configurable.
+    let mut vm = Mvm::new(StorageMock::new(), HostMock::new()).unwrap();
+    vm.set_max_type_tag_depth(17);
+    let result = vm.execute_script(&script, vec![nested_vector(17)], vec![&empty_vec], gas);
+    assert!(result.is_ok(), "failed to execute the script");
+    vm.set_max_type_tag_depth(8);
+    let result = vm.execute_script(&script, vec![nested_vector(9)], vec![&empty_vec], gas);
+    assert_eq!(result.status_code, StatusCode::VM_MAX_TYPE_DEPTH_REACHED);
+}
+
+#[test]
+fn script_allowlist_restricts_only_scripts() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let script = read_script_bytes_from_project("simple_scripts", "empty_loop");
+    let hash = allowed_script_hash(&script);
+    assert_eq!(hash, script_hash(&script));
+
+    vm.set_script_allowlist_enabled(true);
+    assert!(vm.is_script_allowlist_enabled());
+
+    let result = vm.execute_script(&script, vec![], vec![], gas);
+    assert_eq!(result.status_code, StatusCode::UNKNOWN_SCRIPT);
+
+    vm.allow_script(&hash);
+    let result = vm.execute_script(&script, vec![], vec![], gas);
+    assert!(result.is_ok(), "allowed script wasn't executed");
+
+    vm.disallow_script(&hash);
+    let result = vm.execute_script(&script, vec![], vec![], gas);
+    assert_eq!(result.status_code, StatusCode::UNKNOWN_SCRIPT);
+
+    // Module calls are never restricted.
+    let addr_param = bcs::to_bytes(&cafe).unwrap();
+    let mod_name = Identifier::new("BasicCoin").unwrap();
+    let func_name = Identifier::new("publish_balance").unwrap();
+    let result = vm.execute_function(cafe, mod_name, func_name, vec![], vec![&addr_param], gas);
+    assert!(result.is_ok(), "module call was restricted");
+
+    vm.set_script_allowlist_enabled(false);
+    let result = vm.execute_script(&script, vec![], vec![], gas);
+    assert!(
+        result.is_ok(),
+        "script wasn't executed with the policy disabled"
+    );
+}
+
+#[test]
+fn owner_only_resources_require_owner_signature() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+
+    // Mark the balance as owner-only.
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let mut module = CompiledModule::deserialize(&module).unwrap();
+    let balance = Identifier::new("Balance").unwrap();
+    module.metadata.push(owner_only_metadata(&[balance]));
+    let mut module_bytes = vec![];
+    module.serialize(&mut module_bytes).unwrap();
+
+    let result = vm.publish_module(&module_bytes, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let script = read_script_bytes_from_project("basic_coin", "publish_balance");
+    for account in [cafe, bob] {
+        let signer = bcs::to_bytes(&account).unwrap();
+        let result = vm.execute_script(&script, vec![], vec![&signer], gas);
+        assert!(result.is_ok(), "failed to publish the balance");
+    }
+
+    let script = read_script_bytes_from_project("basic_coin", "mint_some");
+    let mint_to = |account: AccountAddress| {
+        let module_owner = bcs::to_bytes(&cafe).unwrap();
+        let rx_addr = bcs::to_bytes(&account).unwrap();
+        let amount = bcs::to_bytes(&10u64).unwrap();
+        vm.execute_script(&script, vec![], vec![&module_owner, &rx_addr, &amount], gas)
+    };
+
+    // The module owner signs the minting to its own balance.
+    let result = mint_to(cafe);
+    assert!(result.is_ok(), "failed to mint to the signer");
+
+    // Bob doesn't sign the modification of his balance.
+    let result = mint_to(bob);
+    assert_eq!(result.status_code, StatusCode::REJECTED_WRITE_SET);
+}
+
+#[test]
+fn gas_profile_reports_gas_per_function() {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::max());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let cafe_param = bcs::to_bytes(&cafe).unwrap();
+    let mod_name = Identifier::new("BasicCoin").unwrap();
+    let func_name = Identifier::new("publish_balance").unwrap();
+    let result = vm.execute_function(cafe, mod_name, func_name, vec![], vec![&cafe_param], gas);
+    assert!(result.is_ok(), "failed to publish the balance");
+    assert!(result.gas_profile.is_none(), "profiling is disabled");
+
+    vm.set_gas_profiling(true);
+
+    let script = read_script_bytes_from_project("basic_coin", "mint_some");
+    let amount = bcs::to_bytes(&100u64).unwrap();
+    let params: Vec<&[u8]> = vec![&cafe_param, &cafe_param, &amount];
+    let result = vm.execute_script(&script, vec![], params, gas);
+    assert!(result.is_ok(), "failed to execute the script");
+
+    let profile = result.gas_profile.expect("profile not reported");
+    for function in [
+        "script",
+        "0xcafe::BasicCoin::mint",
+        "0xcafe::BasicCoin::deposit",
+        "0xcafe::BasicCoin::balance_of",
+        "0x1::signer::address_of",
+    ] {
+        assert!(profile[function] > 0, "{function} wasn't charged");
+    }
+
+    // Each entry is rounded up separately.
+    let total: u64 = profile.values().sum();
+    assert!(total >= result.gas_used);
+    assert!(total <= result.gas_used + profile.len() as u64);
+}
+
+#[test]
+fn memory_limit_is_enforced_independently_of_gas() {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let script = read_script_bytes_from_project("basic_coin", "publish_balance");
+    let cafe_param = bcs::to_bytes(&cafe).unwrap();
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    let tag = bcs::to_bytes(&tag).unwrap();
+
+    // Packing the balance resource alone exceeds the limit.
+    vm.set_memory_limit(8);
+    let result = vm.execute_script(&script, vec![], vec![&cafe_param], gas);
+    assert_eq!(result.status_code, StatusCode::MEMORY_LIMIT_EXCEEDED);
+    assert!(vm.get_resource(&cafe, &tag).unwrap().is_none());
+
+    vm.set_memory_limit(1024);
+    let result = vm.execute_script(&script, vec![], vec![&cafe_param], gas);
+    assert!(result.is_ok(), "failed to execute the script");
+    assert!(vm.get_resource(&cafe, &tag).unwrap().is_some());
+}
+
+#[test]
+fn identifier_policy_applies_to_modules_and_bundles() {
+    let store = StorageMock::new();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+    let addr = AccountAddress::from_hex_literal("0x2").unwrap();
+
+    let vector = read_module_bytes_from_project("using_stdlib_natives", "Vector");
+    let bundle = read_bundle_from_project("using_stdlib_natives", "using_stdlib_natives");
+
+    // The `Vector::sum_after_vector_popping` function name is too long.
+    vm.set_identifier_policy(NamingPolicy {
+        max_length: Some(20),
+        ..Default::default()
+    });
+    let result = vm.publish_module(&vector, addr, gas);
+    assert_eq!(result.status_code, StatusCode::CONSTRAINT_NOT_SATISFIED);
+    let result = vm.publish_module_bundle(&bundle, addr, gas);
+    assert_eq!(result.status_code, StatusCode::CONSTRAINT_NOT_SATISFIED);
+    assert!(vm.get_module(addr, "Vector").unwrap().is_none());
+
+    // Only the `DependsOnVector` module name is reserved, but the whole bundle is rejected.
+    vm.set_identifier_policy(|kind, ident: &IdentStr| match kind {
+        IdentifierKind::Module if ident.as_str().starts_with("Depends") => {
+            Err("reserved module name")
+        }
+        _ => Ok(()),
+    });
+    let result = vm.publish_module_bundle(&bundle, addr, gas);
+    assert_eq!(result.status_code, StatusCode::CONSTRAINT_NOT_SATISFIED);
+    assert!(vm.get_module(addr, "Vector").unwrap().is_none());
+
+    vm.clear_identifier_policy();
+    let result = vm.publish_module_bundle(&bundle, addr, gas);
+    assert!(result.is_ok(), "failed to publish the bundle");
+}
+
+#[test]
+fn identifier_policy_enforces_chain_naming_rules() {
+    let store = StorageMock::new();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+    let addr = AccountAddress::from_hex_literal("0x2").unwrap();
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+
+    let vector = read_module_bytes_from_project("using_stdlib_natives", "Vector");
+    let basic_coin = read_module_bytes_from_project("basic_coin", "BasicCoin");
+
+    let policy = NamingPolicy {
+        forbid_leading_underscore: true,
+        lowercase_module_names: true,
+        ..Default::default()
+    };
+    let err = check_module_bytes(&policy, &vector).unwrap_err();
+    assert!(
+        err.contains("Module name `Vector`"),
+        "unexpected error: {err}"
+    );
+
+    vm.set_identifier_policy(policy);
+    let result = vm.publish_module(&vector, addr, gas);
+    assert_eq!(result.status_code, StatusCode::CONSTRAINT_NOT_SATISFIED);
+    assert!(vm.get_module(addr, "Vector").unwrap().is_none());
+
+    // The field names are checked as well.
+    vm.set_identifier_policy(|kind, ident: &IdentStr| match kind {
+        IdentifierKind::Field if ident.as_str() == "value" => Err("reserved field name"),
+        _ => Ok(()),
+    });
+    let result = vm.publish_module(&basic_coin, cafe, gas);
+    assert_eq!(result.status_code, StatusCode::CONSTRAINT_NOT_SATISFIED);
+
+    let result = vm.publish_module(&vector, addr, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+}
+
+#[test]
+fn resources_are_migrated_after_layout_change() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let beef = AccountAddress::from_hex_literal("0xBEEF").unwrap();
+    let dead = AccountAddress::from_hex_literal("0xDEAD").unwrap();
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("Counter").unwrap(),
+        name: Identifier::new("Counter").unwrap(),
+        type_params: vec![],
+    };
+    let tag_bytes = bcs::to_bytes(&tag).unwrap();
+
+    let module = read_module_bytes_from_project("counter_v1", "Counter");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let script = read_script_bytes_from_project("counter_v1", "publish_counter");
+    for (address, value) in [(cafe, 7u64), (beef, 9u64)] {
+        let address_param = bcs::to_bytes(&address).unwrap();
+        let value_param = bcs::to_bytes(&value).unwrap();
+        let args = vec![address_param.as_slice(), value_param.as_slice()];
+        let result = vm.execute_script(&script, vec![], args, gas);
+        assert!(result.is_ok(), "failed to publish the counter");
+    }
+    let old_layout = vm.resource_layout(&tag).unwrap();
+
+    // The regular upgrade rejects the layout change.
+    let module = read_module_bytes_from_project("counter_v2", "Counter");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_err(), "the layout change was accepted");
+    let result = vm.publish_module_with_layout_changes(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to upgrade the module");
+
+    let new_layout = layout_hash(&vm.resource_layout(&tag).unwrap());
+    assert_ne!(layout_hash(&old_layout), new_layout);
+
+    // Without a registered migration the resources stay untouched.
+    let report = vm.migrate_resources([cafe, beef], &tag, gas).unwrap();
+    assert!(report.migrated.is_empty());
+    assert_eq!(report.failed.len(), 2);
+    assert_eq!(
+        report.failed[0].1.status_code,
+        StatusCode::FAILED_TO_DESERIALIZE_RESOURCE
+    );
+
+    let script = read_script_bytes_from_project("counter_v2", "migrate_counter");
+    vm.register_migration(&new_layout, Migration { old_layout, script });
+
+    // Accounts without the resource are skipped.
+    let report = vm.migrate_resources([cafe, beef, dead], &tag, gas).unwrap();
+    assert_eq!(report.migrated, vec![cafe, beef]);
+    assert!(report.failed.is_empty());
+
+    let counter = vm.get_resource(&beef, &tag_bytes).unwrap().unwrap();
+    assert_eq!(bcs::from_bytes::<(u64, u64)>(&counter).unwrap(), (9, 1));
+
+    // The migrated resources are already in the current layout.
+    let report = vm.migrate_resources([cafe, beef], &tag, gas).unwrap();
+    assert!(report.migrated.is_empty());
+    assert!(report.failed.is_empty());
+}
+
+// Audit of the hash containers on the execution paths:
+// - deserializer: the table kinds are only checked for duplicates,
+// - loader: the module, function and struct maps and the type cache are only used for lookups,
+// - verifier: the type parameters of the instantiation loop graph are ordered now, the other
+//   passes only check membership,
+// - session change sets: the data cache and the `ChangeSet` are ordered maps, and so is the
+//   genesis storage diff.
+#[test]
+fn execution_outputs_are_identical_across_vm_instances() {
+    // Each VM instance gets differently seeded hash containers, so any output depending on their
+    // iteration order would differ between the runs.
+    let digests: Vec<_> = (0..4).map(|_| basic_coin_outputs_digest()).collect();
+    assert!(digests.windows(2).all(|pair| pair[0] == pair[1]));
+}
+
+#[test]
+fn execution_outputs_match_the_baseline() {
+    // Differently seeded runs on one node agreeing isn't enough, the outputs must not change
+    // between the builds either. Run with `UB=1` to update the baseline after intended changes.
+    const BASELINE: &str = "tests/assets/basic_coin_outputs.exp";
+
+    let digest: String = basic_coin_outputs_digest()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+
+    if read_env_update_baseline() {
+        std::fs::write(BASELINE, &digest).unwrap();
+        return;
+    }
+
+    let baseline = std::fs::read_to_string(BASELINE)
+        .unwrap_or_else(|e| panic!("Can't read {BASELINE}: {e} - run the test with `UB=1`"));
+    assert_eq!(digest, baseline.trim(), "execution outputs have changed");
+}
+
+#[test]
+fn fee_hook_validates_and_pays_the_fees() {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let unmetered = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let dave = AccountAddress::from_hex_literal("0xDA4E").unwrap();
+    for (project, name) in [("basic_coin", "BasicCoin"), ("fee_sponsor", "FeeSponsor")] {
+        let module = read_module_bytes_from_project(project, name);
+        let result = vm.publish_module(&module, cafe, unmetered);
+        assert!(result.is_ok(), "failed to publish {name}");
+    }
+
+    let sponsor = Identifier::new("FeeSponsor").unwrap();
+    let budget = bcs::to_bytes(&1_500_000u64).unwrap();
+    let result = vm.execute_function(
+        cafe,
+        sponsor.clone(),
+        Identifier::new("init").unwrap(),
+        vec![],
+        vec![&bcs::to_bytes(&cafe).unwrap(), &budget],
+        unmetered,
+    );
+    assert!(result.is_ok(), "failed to initialize the budget");
+
+    let hook = |function: &str| FeeHook {
+        module: ModuleId::new(cafe, sponsor.clone()),
+        function: Identifier::new(function).unwrap(),
+        validation_gas: GasAmount::new(100_000).unwrap(),
+    };
+    let gas = GasStrategy::Metered(GasAmount::new(1_000_000).unwrap());
+    let publish_balance = |vm: &Mvm<StorageMock, HostMock>, who: AccountAddress| {
+        vm.execute_function(
+            cafe,
+            Identifier::new("BasicCoin").unwrap(),
+            Identifier::new("publish_balance").unwrap(),
+            vec![],
+            vec![&bcs::to_bytes(&who).unwrap()],
+            gas,
+        )
+    };
+    let balance_tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    let budget_tag = StructTag {
+        address: cafe,
+        module: sponsor.clone(),
+        name: Identifier::new("Budget").unwrap(),
+        type_params: vec![],
+    };
+
+    // The hook pays the whole gas limit from the budget.
+    vm.set_fee_hook(hook("pay_fee"));
+    let result = publish_balance(&vm, bob);
+    assert!(
+        result.is_ok(),
+        "failed to execute the sponsored transaction"
+    );
+    assert!(result.sponsored);
+    let remaining = vm.get_resource_value(&cafe, &budget_tag).unwrap().unwrap();
+    assert_eq!(remaining.to_json(), r#"{"remaining":"500000"}"#);
+
+    // The exhausted budget rejects the transaction before it's executed.
+    let result = publish_balance(&vm, dave);
+    assert_eq!(result.status_code, StatusCode::ABORTED);
+    assert_eq!(result.abort_code, Some(1));
+    assert!(!result.sponsored);
+    assert!(vm
+        .get_resource_value(&dave, &balance_tag)
+        .unwrap()
+        .is_none());
+
+    // The validation phase can't exceed its own gas limit.
+    vm.set_fee_hook(hook("pay_fee_forever"));
+    let result = publish_balance(&vm, dave);
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+    assert!(!result.sponsored);
+    assert!(vm
+        .get_resource_value(&dave, &balance_tag)
+        .unwrap()
+        .is_none());
+
+    // Without the hook, the transactions are executed as usual.
+    vm.clear_fee_hook();
+    let result = publish_balance(&vm, dave);
+    assert!(result.is_ok(), "failed to execute the transaction");
+    assert!(!result.sponsored);
+}
+
+#[test]
+fn reserved_addresses_require_the_privileged_publishing() {
+    struct RootPublish;
+    impl PublishCapability for RootPublish {}
+
+    let store = StorageMock::new();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+    vm.set_reserved_addresses([ADDR_STD]);
+
+    let stdlib = move_stdlib::move_stdlib_bundle();
+    let result = vm.publish_module_bundle(stdlib, ADDR_STD, gas);
+    assert_eq!(result.status_code, StatusCode::INVALID_MODULE_PUBLISHER);
+
+    let result = vm.publish_module_bundle_as(&RootPublish, stdlib, ADDR_STD, gas);
+    assert!(result.is_ok(), "failed to publish the stdlib bundle");
+
+    // The patches of the framework modules take the privileged path too.
+    let module = vm.get_module(ADDR_STD, "vector").unwrap().unwrap();
+    let result = vm.publish_module(&module, ADDR_STD, gas);
+    assert_eq!(result.status_code, StatusCode::INVALID_MODULE_PUBLISHER);
+
+    let result = vm.publish_module_as(&RootPublish, &module, ADDR_STD, gas);
+    assert!(result.is_ok(), "failed to patch the module");
+
+    // Other addresses are unaffected.
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+}
+
+#[test]
+fn expired_resources_are_swept() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let dave = AccountAddress::from_hex_literal("0xDA4E").unwrap();
+    let eve = AccountAddress::from_hex_literal("0xE4E").unwrap();
+    let module = read_module_bytes_from_project("expiring_session", "Session");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let call = |function: &str, who: AccountAddress, args: &[u64]| {
+        let mut args: Vec<_> = args.iter().map(|arg| bcs::to_bytes(arg).unwrap()).collect();
+        args.insert(0, bcs::to_bytes(&who).unwrap());
+        let result = vm.execute_function(
+            cafe,
+            Identifier::new("Session").unwrap(),
+            Identifier::new(function).unwrap(),
+            vec![],
+            args.iter().map(Vec::as_slice).collect(),
+            gas,
+        );
+        assert!(result.is_ok(), "failed to execute {function}");
+    };
+    let session_tag = StructTag {
+        address: cafe,
+        module: Identifier::new("Session").unwrap(),
+        name: Identifier::new("Session").unwrap(),
+        type_params: vec![],
+    };
+    let has_session =
+        |who: AccountAddress| vm.get_resource_value(&who, &session_tag).unwrap().is_some();
+
+    call("open", bob, &[1, 10]);
+    call("open", dave, &[2, 20]);
+    call("open", eve, &[3, 15]);
+    assert_eq!(vm.get_resource_expiry(&bob, &session_tag), Some(10));
+
+    // Nothing is removed before the expiry.
+    let report = vm.sweep_expired(9, 10).unwrap();
+    assert!(report.removed.is_empty());
+    assert!(report.complete);
+
+    // Closed sessions lose their expiry and the kept-alive ones don't expire.
+    call("close", eve, &[]);
+    assert_eq!(vm.get_resource_expiry(&eve, &session_tag), None);
+    call("keep_alive", dave, &[]);
+    assert_eq!(vm.get_resource_expiry(&dave, &session_tag), None);
+    call("open", eve, &[4, 15]);
+
+    // The earliest expiries go first and each sweep visits at most `limit` index entries.
+    let report = vm.sweep_expired(20, 1).unwrap();
+    assert_eq!(report.removed, vec![(bob, session_tag.clone())]);
+    assert!(report.freed_bytes > 0);
+    assert!(!report.complete);
+    assert!(!has_session(bob));
+    assert_eq!(vm.get_resource_expiry(&bob, &session_tag), None);
+
+    let report = vm.sweep_expired(20, 20).unwrap();
+    assert_eq!(report.removed, vec![(eve, session_tag.clone())]);
+    assert!(report.complete);
+    assert!(!has_session(eve));
+    assert!(has_session(dave));
+
+    // Without any expiries left, the sweep doesn't visit the blocks in between.
+    let report = vm.sweep_expired(1_000_000, 1).unwrap();
+    assert!(report.removed.is_empty());
+    assert!(report.complete);
+}
+
+#[test]
+fn frozen_expired_resources_are_kept() {
+    struct Governance;
+    impl FreezeCapability for Governance {}
+
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let eve = AccountAddress::from_hex_literal("0xE4E").unwrap();
+    let module = read_module_bytes_from_project("expiring_session", "Session");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let session_tag = StructTag {
+        address: cafe,
+        module: Identifier::new("Session").unwrap(),
+        name: Identifier::new("Session").unwrap(),
+        type_params: vec![],
+    };
+    for (who, nonce) in [(bob, 1u64), (eve, 2)] {
+        let args = [
+            bcs::to_bytes(&who).unwrap(),
+            bcs::to_bytes(&nonce).unwrap(),
+            bcs::to_bytes(&10u64).unwrap(),
+        ];
+        let result = vm.execute_function(
+            cafe,
+            Identifier::new("Session").unwrap(),
+            Identifier::new("open").unwrap(),
+            vec![],
+            args.iter().map(Vec::as_slice).collect(),
+            gas,
+        );
+        assert!(result.is_ok(), "failed to open the session");
+    }
+
+    // Neither the frozen account nor the frozen resource is removed.
+    vm.freeze_account(&Governance, bob);
+    vm.freeze_resource(&Governance, eve, session_tag.clone());
+    let report = vm.sweep_expired(10, 20).unwrap();
+    assert!(report.removed.is_empty());
+    assert_eq!(
+        report.frozen,
+        vec![(bob, session_tag.clone()), (eve, session_tag.clone())]
+    );
+    for who in [bob, eve] {
+        assert!(vm.get_resource_value(&who, &session_tag).unwrap().is_some());
+        assert_eq!(vm.get_resource_expiry(&who, &session_tag), Some(10));
+    }
+
+    // The kept expiries are swept once the resources are unfrozen.
+    vm.unfreeze_account(&Governance, bob);
+    vm.unfreeze_resource(&Governance, eve, session_tag.clone());
+    let report = vm.sweep_expired(11, 20).unwrap();
+    assert_eq!(
+        report.removed,
+        vec![(bob, session_tag.clone()), (eve, session_tag.clone())]
+    );
+    assert!(report.frozen.is_empty());
+    for who in [bob, eve] {
+        assert!(vm.get_resource_value(&who, &session_tag).unwrap().is_none());
+    }
+}
+
+#[test]
+fn receipts_commit_to_the_execution_results() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+
+    let module = read_module_bytes_from_project("using_stdlib_natives", "Vector");
+    let address = AccountAddress::from_hex_literal("0x2").unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(1_000_000).unwrap());
+
+    // The dry run commits to the same result as the real execution.
+    let dry_run = vm.publish_module(&module, address, GasStrategy::DryRun);
+    let result = vm.publish_module(&module, address, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+    assert_eq!(dry_run.receipt(), result.receipt());
+
+    let receipt = result.receipt();
+    assert_eq!(receipt.status, StatusCode::EXECUTED as u64);
+    assert_eq!(receipt.gas_used, result.gas_used);
+    assert_eq!(receipt.events_root, EMPTY_ROOT);
+    assert_ne!(receipt.state_diff_root, EMPTY_ROOT);
+
+    // Failed executions don't change the storage.
+    let result = vm.publish_module(
+        &module,
+        address,
+        GasStrategy::Metered(GasAmount::new(1).unwrap()),
+    );
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+    let failed = result.receipt();
+    assert_eq!(failed.state_diff_root, EMPTY_ROOT);
+    assert_ne!(failed.hash(), receipt.hash());
+}
+
+#[test]
+fn preverified_code_is_cached_until_evicted() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let queue = PreverificationQueue::new();
+
+    let script = read_script_bytes_from_project("simple_scripts", "empty_loop");
+    let module = read_module_bytes_from_project("using_stdlib_natives", "Vector");
+    let invalid = vec![0xBA, 0xD, 0xCA, 0xFE];
+    let pool = vec![
+        PendingCode::Script(script.clone()),
+        PendingCode::Module(module.clone()),
+        PendingCode::Script(invalid.clone()),
+    ];
+
+    // Only the new code gets verified.
+    assert_eq!(queue.feed(pool.clone()), 3);
+    assert_eq!(queue.feed(pool), 0);
+    assert_eq!(queue.len(), 3);
+
+    // The queue is keyed by the public code hashes.
+    let (script_key, module_key) = (script_hash(&script), module_hash(&module));
+    assert_eq!(PendingCode::Script(script.clone()).hash(), script_key);
+    assert!(matches!(
+        queue.get(&script_key),
+        Some(Ok(VerifiedCode::Script(_)))
+    ));
+    assert!(matches!(
+        queue.get(&module_key),
+        Some(Ok(VerifiedCode::Module(_)))
+    ));
+    assert!(matches!(
+        queue.get(&script_hash(&invalid)),
+        Some(Err(StatusCode::CODE_DESERIALIZATION_ERROR))
+    ));
+    assert!(queue.get(&module_hash(&invalid)).is_none());
+
+    // The preloaded code behaves exactly like the one loaded from the bytecode.
+    vm.preload_verified(&queue, [&script_key, &module_key]);
+    let result = vm.execute_script(&script, vec![], vec![], GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to execute the preverified script");
+
+    let address = AccountAddress::from_hex_literal("0x2").unwrap();
+    let result = vm.publish_module(&module, address, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the preverified module");
+
+    // Included transactions are evicted from the queue.
+    queue.evict([&script_key, &module_key]);
+    assert!(queue.get(&script_key).is_none());
+    assert_eq!(queue.len(), 1);
+}
+
+#[test]
+fn oversized_vector_arguments_are_rejected() {
+    let store = StorageMock::new();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    vm.set_vector_arg_limits(
+        VectorArgLimits::new(VectorLimit {
+            max_elements: 4,
+            max_bytes: 64,
+        })
+        .with_type_limit(
+            TypeTag::U8,
+            VectorLimit {
+                max_elements: 16,
+                max_bytes: 17,
+            },
+        ),
+    );
+
+    let script = read_script_bytes_from_project("simple_scripts", "vector_lengths");
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+    let execute = |numbers: Vec<u64>, bytes: Vec<u8>| {
+        let numbers = bcs::to_bytes(&numbers).unwrap();
+        let bytes = bcs::to_bytes(&bytes).unwrap();
+        vm.execute_script(&script, vec![], vec![&numbers, &bytes], gas)
+    };
+
+    let result = execute(vec![1, 2, 3, 4], vec![0; 16]);
+    assert!(result.is_ok(), "failed to execute the script");
+
+    // Vectors of the element types without their own limit use the default one.
+    let result = execute(vec![1, 2, 3, 4, 5], vec![0; 16]);
+    assert_eq!(
+        result.status_code,
+        StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT
+    );
+    assert_eq!(result.gas_used, 0);
+    assert_eq!(
+        result.error_message.as_deref(),
+        Some("Argument 0 of type vector<u64> has 5 elements, the limit is 4")
+    );
+
+    let result = execute(vec![1], vec![0; 17]);
+    assert_eq!(
+        result.status_code,
+        StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT
+    );
+    assert_eq!(
+        result.error_message.as_deref(),
+        Some("Argument 1 of type vector<u8> has 18 bytes, the limit is 17")
+    );
+}
+
+#[test]
+fn genesis_snapshot_matches_the_applied_configuration() {
+    let store = store_preloaded_with_genesis_cfg();
+
+    let snapshot = VmGenesisConfig::default().build_snapshot().unwrap();
+    assert_eq!(snapshot.entries.len(), store.data.borrow().len());
+
+    // The snapshot survives the serialization into the chain specification.
+    let encoded = bcs::to_bytes(&snapshot).unwrap();
+    let snapshot: GenesisSnapshot = bcs::from_bytes(&encoded).unwrap();
+
+    let snapshot_store = StorageMock::new();
+    snapshot.apply(snapshot_store.clone());
+    assert_eq!(*snapshot_store.data.borrow(), *store.data.borrow());
+
+    // The stdlib from the snapshot is usable.
+    let vm = Mvm::new(snapshot_store, HostMock::new()).unwrap();
+    let module = read_module_bytes_from_project("using_stdlib_full", "StringAndVector");
+    let address = AccountAddress::from_hex_literal("0x3").unwrap();
+    let result = vm.publish_module(&module, address, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the module");
+}
+
+#[test]
+fn storage_dumps_replay_the_exact_state() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store.clone(), HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let cafe_param = bcs::to_bytes(&cafe).unwrap();
+    let publish_balance = |vm: &Mvm<_, _>| {
+        let mod_name = Identifier::new("BasicCoin").unwrap();
+        let func_name = Identifier::new("publish_balance").unwrap();
+        vm.execute_function(cafe, mod_name, func_name, vec![], vec![&cafe_param], gas)
+    };
+    let result = publish_balance(&vm);
+    assert!(result.is_ok(), "failed to publish the balance: {result:?}");
+
+    // Capture the state and replay the failing call against it.
+    let bytes = store.dump().to_bytes();
+    let dump = StorageDump::from_bytes(&bytes).unwrap();
+    assert_eq!(dump.len(), store.data.borrow().len());
+    let replay_store = StorageMock::from_dump(&dump);
+    assert_eq!(*replay_store.data.borrow(), *store.data.borrow());
+
+    let replay_vm = Mvm::new(replay_store.clone(), HostMock::new()).unwrap();
+    let expected = publish_balance(&vm);
+    assert!(!expected.is_ok(), "the balance was published twice");
+    let replayed = publish_balance(&replay_vm);
+    assert_eq!(replayed.status_code, expected.status_code);
+    assert_eq!(replayed.gas_used, expected.gas_used);
+    assert_eq!(replay_store.dump(), store.dump());
+
+    // Only the well-formed dumps of the known version are accepted.
+    assert_eq!(
+        StorageDump::from_bytes(&bytes[1..]),
+        Err(DumpError::BadMagic)
+    );
+    let mut newer = bytes.clone();
+    newer[DUMP_MAGIC.len()] = DUMP_VERSION + 1;
+    assert_eq!(
+        StorageDump::from_bytes(&newer),
+        Err(DumpError::UnsupportedVersion(DUMP_VERSION + 1))
+    );
+    assert_eq!(
+        StorageDump::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(DumpError::Malformed)
+    );
+
+    // The repeated keys are merged on export.
+    let dump = StorageDump::new([
+        (b"b".to_vec(), b"1".to_vec()),
+        (b"a".to_vec(), b"2".to_vec()),
+        (b"b".to_vec(), b"3".to_vec()),
+    ]);
+    assert_eq!(dump.get(b"b"), Some(&b"3"[..]));
+    assert_eq!(dump.entries()[0].0, b"a");
+    assert_eq!(StorageDump::from_bytes(&dump.to_bytes()), Ok(dump));
+}
+
+#[test]
+fn module_stats_are_recorded_when_enabled() {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let module_id = ModuleId::new(cafe, Identifier::new("BasicCoin").unwrap());
+    let cafe_param = bcs::to_bytes(&cafe).unwrap();
+    let publish_balance = |vm: &Mvm<_, _>, gas| {
+        let mod_name = Identifier::new("BasicCoin").unwrap();
+        let func_name = Identifier::new("publish_balance").unwrap();
+        vm.execute_function(cafe, mod_name, func_name, vec![], vec![&cafe_param], gas)
+    };
+
+    // Nothing is recorded by default.
+    let result = publish_balance(&vm, GasStrategy::DryRun);
+    assert!(result.is_ok(), "failed to execute the function");
+    assert_eq!(vm.get_module_stats(&module_id), None);
+
+    vm.set_module_stats(true);
+    let first = publish_balance(&vm, gas);
+    assert!(first.is_ok(), "failed to execute the function");
+    // The balance already exists.
+    let second = publish_balance(&vm, gas);
+    assert!(!second.is_ok(), "the balance was published twice");
+    // Dry runs aren't recorded.
+    publish_balance(&vm, GasStrategy::DryRun);
+
+    let stats = vm.get_module_stats(&module_id).unwrap();
+    assert_eq!(stats.calls, 2);
+    assert_eq!(stats.failures, 1);
+    assert_eq!(stats.gas_used, first.gas_used + second.gas_used);
+
+    vm.reset_module_stats(&module_id);
+    assert_eq!(vm.get_module_stats(&module_id), None);
+}
+
+#[test]
+fn gated_natives_fail_until_enabled() {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let foreign = ModuleId::new(ADDR_STD, Identifier::new("foreign").unwrap());
+    let call = Identifier::new("call").unwrap();
+    vm.set_gated_natives([(foreign.clone(), call.clone())])
+        .unwrap();
+    assert!(vm.is_native_gated(&foreign, &call));
+
+    // Modules using the gated natives can still be published.
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("foreign_bridge", "Bridge");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let payload = bcs::to_bytes(&vec![1u8, 2, 3]).unwrap();
+    let gas_limit = bcs::to_bytes(&100u64).unwrap();
+    let echo = |vm: &Mvm<StorageMock, HostMock>| {
+        vm.execute_function(
+            cafe,
+            Identifier::new("Bridge").unwrap(),
+            Identifier::new("echo").unwrap(),
+            vec![],
+            vec![&payload, &gas_limit],
+            gas,
+        )
+    };
+    let result = echo(&vm);
+    assert_eq!(result.status_code, StatusCode::FEATURE_UNDER_GATING);
+
+    vm.set_gated_natives([]).unwrap();
+    assert!(!vm.is_native_gated(&foreign, &call));
+    let result = echo(&vm);
+    assert!(result.is_ok(), "failed to echo the payload");
+}
+
+#[test]
+fn foreign_call_passes_payload_and_gas_to_host() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("foreign_bridge", "Bridge");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let call = |function: &str, args: Vec<Vec<u8>>| {
+        vm.execute_function(
+            cafe,
+            Identifier::new("Bridge").unwrap(),
+            Identifier::new(function).unwrap(),
+            vec![],
+            args.iter().map(Vec::as_slice).collect(),
+            gas,
+        )
+    };
+    let payload = bcs::to_bytes(&vec![1u8, 2, 3]).unwrap();
+    let gas_limit = bcs::to_bytes(&100u64).unwrap();
+
+    let result = call("echo", vec![payload.clone(), gas_limit.clone()]);
+    assert!(result.is_ok(), "failed to echo the payload");
+
+    // The host function gets no more gas than the transaction has left.
+    let unlimited = bcs::to_bytes(&u64::MAX).unwrap();
+    let result = call("echo", vec![payload.clone(), unlimited]);
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+
+    // Failed host functions abort the Move code.
+    let target = bcs::to_bytes(&FAILING_TARGET).unwrap();
+    let result = call("call", vec![target, payload.clone(), gas_limit.clone()]);
+    assert_eq!(result.status_code, StatusCode::ABORTED);
+    assert_eq!(result.abort_code, Some(FAILING_TARGET_ABORT_CODE));
+
+    // Host failures fail the transaction.
+    let target = bcs::to_bytes(&(ECHO_TARGET + 100)).unwrap();
+    let result = call("call", vec![target, payload, gas_limit]);
+    assert_eq!(result.status_code, StatusCode::VM_EXTENSION_ERROR);
+}
+
+/// Host re-entering the MoveVM from the foreign calls, the transfers and the cheque queries.
+struct ReentrantHost {
+    balances: HostMock,
+    vm: Rc<RefCell<Weak<Mvm<StorageMock, ReentrantHost>>>>,
+    reentry: Rc<RefCell<Option<StatusCode>>>,
+    balance_reentries: Rc<RefCell<Vec<StatusCode>>>,
+}
+
+impl ReentrantHost {
+    fn new() -> Self {
+        Self {
+            balances: HostMock::new(),
+            vm: Rc::new(RefCell::new(Weak::new())),
+            reentry: Rc::new(RefCell::new(None)),
+            balance_reentries: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// MoveVM on the `storage` which the host re-enters.
+    fn attach(self, storage: StorageMock, guard: ReentrancyGuard) -> Rc<Mvm<StorageMock, Self>> {
+        let slot = self.vm.clone();
+        let mut vm = Mvm::new(storage, self).unwrap();
+        vm.set_reentrancy_guard(guard);
+
+        let vm = Rc::new(vm);
+        *slot.borrow_mut() = Rc::downgrade(&vm);
+        vm
+    }
+
+    /// Publish a module with the MoveVM instance making the host call.
+    fn reenter(&self) -> StatusCode {
+        let vm = self.vm.borrow().upgrade().expect("the host is attached");
+        let module = read_module_bytes_from_project("empty", "Empty");
+        let address = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+        vm.publish_module(&module, address, GasStrategy::Unmetered)
+            .status_code
+    }
+}
+
+impl HostBindings for ReentrantHost {
+    type Error = StatusCode;
+
+    fn transfer(
+        &self,
+        src: AccountAddress,
+        dst: AccountAddress,
+        cheque_amount: u128,
+    ) -> Result<bool, Self::Error> {
+        self.balance_reentries.borrow_mut().push(self.reenter());
+        self.balances.transfer(src, dst, cheque_amount)
+    }
+
+    fn cheque_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
+        self.balance_reentries.borrow_mut().push(self.reenter());
+        self.balances.cheque_amount(account)
+    }
+
+    fn total_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
+        self.balances.total_amount(account)
+    }
+
+    fn total_issuance(&self) -> Result<u128, Self::Error> {
+        self.balances.total_issuance()
+    }
+
+    fn account_exists(&self, account: AccountAddress) -> Result<bool, Self::Error> {
+        self.balances.account_exists(account)
+    }
+
+    fn minimum_balance(&self) -> Result<u128, Self::Error> {
+        self.balances.minimum_balance()
+    }
+
+    fn create_account(
+        &self,
+        payer: AccountAddress,
+        account: AccountAddress,
+    ) -> Result<bool, Self::Error> {
+        self.balances.create_account(payer, account)
+    }
+
+    fn withdraw_fee(&self, payer: AccountAddress, fee: u128) -> Result<bool, Self::Error> {
+        self.balances.withdraw_fee(payer, fee)
+    }
+
+    fn refund_fee(&self, payer: AccountAddress, refund: u128) -> Result<(), Self::Error> {
+        self.balances.refund_fee(payer, refund)
+    }
+
+    fn foreign_call(
+        &self,
+        target: u64,
+        payload: &[u8],
+        gas_limit: u64,
+    ) -> Result<ForeignCallResponse, Self::Error> {
+        *self.reentry.borrow_mut() = Some(self.reenter());
+
+        self.balances.foreign_call(target, payload, gas_limit)
+    }
+
+    fn send_xcm(&self, origin: AccountAddress, message: XcmMessage) -> Result<bool, Self::Error> {
+        self.balances.send_xcm(origin, message)
+    }
+
+    fn random_seed(&self, subject: &[u8]) -> Result<[u8; 32], Self::Error> {
+        self.balances.random_seed(subject)
+    }
+
+    fn now_milliseconds(&self) -> Result<u64, Self::Error> {
+        self.balances.now_milliseconds()
+    }
+}
+
+#[test]
+fn foreign_call_cannot_reenter_vm() {
+    let store = store_preloaded_with_genesis_cfg();
+    let host = ReentrantHost::new();
+    let reentry = host.reentry.clone();
+    let vm = host.attach(store, ReentrancyGuard::default());
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("foreign_bridge", "Bridge");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let payload = bcs::to_bytes(&vec![1u8, 2, 3]).unwrap();
+    let gas_limit = bcs::to_bytes(&100u64).unwrap();
+    let result = vm.execute_function(
+        cafe,
+        Identifier::new("Bridge").unwrap(),
+        Identifier::new("echo").unwrap(),
+        vec![],
+        vec![&payload, &gas_limit],
+        gas,
+    );
+    assert!(result.is_ok(), "failed to echo the payload");
+    assert_eq!(*reentry.borrow(), Some(StatusCode::VM_EXTENSION_ERROR));
+
+    // The MoveVM is usable once the foreign call returns.
+    let module = read_module_bytes_from_project("empty", "Empty");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+}
+
+#[test]
+fn xcm_messages_are_sent_by_host() {
+    let store = store_preloaded_with_genesis_cfg();
+    let host = HostMock::new();
+    let vm = Mvm::new(store, host.clone()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("xcm_sender", "CrossChain");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let call = |function: &str, args: Vec<Vec<u8>>| {
+        vm.execute_function(
+            cafe,
+            Identifier::new("CrossChain").unwrap(),
+            Identifier::new(function).unwrap(),
+            vec![],
+            args.iter().map(Vec::as_slice).collect(),
+            gas,
+        )
+    };
+    let sender = bcs::to_bytes(&cafe).unwrap();
+    let beneficiary = AccountAddress::from_hex_literal("0xBEEF").unwrap();
+
+    let args = vec![
+        sender.clone(),
+        bcs::to_bytes(&2000u32).unwrap(),
+        bcs::to_bytes(&beneficiary).unwrap(),
+        bcs::to_bytes(&500u128).unwrap(),
+    ];
+    let result = call("transfer_to_sibling", args);
+    assert!(result.is_ok(), "failed to send the transfer");
+
+    let runtime_call = vec![0u8, 7, 42];
+    let args = vec![sender, bcs::to_bytes(&runtime_call).unwrap()];
+    let result = call("transact_on_relay", args);
+    assert!(result.is_ok(), "failed to send the transact");
+
+    let transfer = XcmMessage {
+        destination: XcmLocation {
+            parents: 1,
+            parachain: Some(2000),
+            account: None,
+        },
+        instruction: XcmInstruction::TransferAsset {
+            asset: XcmAsset {
+                id: XcmAssetId {
+                    parents: 1,
+                    parachain: None,
+                    pallet_instance: None,
+                    general_index: None,
+                },
+                amount: 500,
+            },
+            beneficiary: XcmLocation {
+                parents: 0,
+                parachain: None,
+                account: Some(beneficiary),
+            },
+        },
+    };
+    let transact = XcmMessage {
+        destination: XcmLocation {
+            parents: 1,
+            parachain: None,
+            account: None,
+        },
+        instruction: XcmInstruction::Transact {
+            origin_kind: XcmOriginKind::SovereignAccount,
+            ref_time: 1_000_000,
+            proof_size: 10_000,
+            call: runtime_call,
+        },
+    };
+    assert_eq!(host.sent_xcm(), vec![(cafe, transfer), (cafe, transact)]);
+}
+
+#[test]
+fn module_verification_is_recorded_by_host() {
+    let store = store_preloaded_with_genesis_cfg();
+    let host = HostMock::new();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let payload = bcs::to_bytes(&vec![1u8, 2, 3]).unwrap();
+    let gas_limit = bcs::to_bytes(&100u64).unwrap();
+    let echo = |vm: &Mvm<StorageMock, HostMock>| {
+        vm.execute_function(
+            cafe,
+            Identifier::new("Bridge").unwrap(),
+            Identifier::new("echo").unwrap(),
+            vec![],
+            vec![&payload, &gas_limit],
+            gas,
+        )
+    };
+
+    let vm = Mvm::new(store.clone(), host.clone()).unwrap();
+    let module = read_module_bytes_from_project("foreign_bridge", "Bridge");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+    let state = store.data.borrow().clone();
+    assert!(echo(&vm).is_ok(), "failed to echo the payload");
+
+    // The module and its stdlib dependencies are verified under the same config, while the
+    // storage is left untouched, since the markers depend on the loader cache of the node.
+    let markers = host.verified_modules();
+    assert!(markers.len() >= 2);
+    let config_hash = *markers.values().next().unwrap();
+    assert!(markers.values().all(|marker| *marker == config_hash));
+    assert_eq!(*store.data.borrow(), state);
+
+    // Markers of another verifier config are ignored and replaced once the modules get verified
+    // again.
+    for module_hash in markers.keys() {
+        host.set_verified_module(module_hash, &[0; 32]);
+    }
+    let vm = Mvm::new(store.clone(), host.clone()).unwrap();
+    assert!(echo(&vm).is_ok(), "failed to echo the payload");
+    assert_eq!(host.verified_modules(), markers);
+}
+
+#[test]
+fn struct_layouts_are_cached_across_calls() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let tag = StructTag {
+        address: ADDR_STD,
+        module: Identifier::new("string").unwrap(),
+        name: Identifier::new("String").unwrap(),
+        type_params: vec![],
+    };
+
+    let stats = vm.layout_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (0, 0, 0));
+
+    let layout = layout_hash(&vm.resource_layout(&tag).unwrap());
+    let stats = vm.layout_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (0, 1, 1));
+
+    // The second lookup doesn't build the layout again.
+    assert_eq!(layout_hash(&vm.resource_layout(&tag).unwrap()), layout);
+    let stats = vm.layout_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+    assert_eq!(stats.evictions, 0);
+}
+
+#[test]
+fn receipt_follows_canonical_order() {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+    let run = || {
+        let store = store_preloaded_with_genesis_cfg();
+        let vm = Mvm::new(store, HostMock::new()).unwrap();
+        let module = read_module_bytes_from_project("canonical_order", "Ordering");
+        assert!(vm.publish_module(&module, cafe, gas).is_ok());
+
+        let signer = bcs::to_bytes(&cafe).unwrap();
+        let result = vm.execute_function(
+            cafe,
+            Identifier::new("Ordering").unwrap(),
+            Identifier::new("create_all").unwrap(),
+            vec![],
+            vec![&signer],
+            gas,
+        );
+        assert!(result.is_ok(), "execution failed: {result:?}");
+        result.state_diff_root
+    };
+
+    let root = run();
+    assert_eq!(run(), root);
+
+    // The resources are ordered by the BCS-encoded tags, where the shorter names come first.
+    let resource_tag = |name: &str| StructTag {
+        address: cafe,
+        module: Identifier::new("Ordering").unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params: vec![],
+    };
+    let leaves = [("Z", 1u64), ("Mm", 2), ("Aaa", 3)]
+        .into_iter()
+        .map(|(name, value)| StateWrite {
+            address: cafe,
+            path: StatePath::Resource(resource_tag(name)),
+            value: Some(bcs::to_bytes(&value).unwrap()),
+        })
+        .map(|write| write.leaf_hash())
+        .collect();
+    assert_eq!(root, merkle_root(leaves));
+}
+
+#[test]
+fn events_keep_emission_order_after_fee_hook_events() {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("event_emitter", "Ping");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    vm.set_fee_hook(FeeHook {
+        module: ModuleId::new(cafe, Identifier::new("Ping").unwrap()),
+        function: Identifier::new("pay_fee").unwrap(),
+        validation_gas: GasAmount::new(100_000).unwrap(),
+    });
+    let script = read_script_bytes_from_project("event_emitter", "emit_pings");
+    let count = bcs::to_bytes(&3u64).unwrap();
+    let result = vm.execute_script(&script, vec![], vec![&count], gas);
+    assert!(result.is_ok(), "failed to execute the script: {result:?}");
+
+    // The fee hook event comes first, the transaction events follow in the emission order.
+    let pings: Vec<u64> = result
+        .events
+        .iter()
+        .map(|event| bcs::from_bytes(&event.data).unwrap())
+        .collect();
+    assert_eq!(pings, vec![1000, 0, 1, 2]);
+
+    let leaves = result.events.iter().map(event_leaf_hash).collect();
+    assert_eq!(result.receipt().events_root, merkle_root(leaves));
+}
+
+#[test]
+fn accounts_are_created_through_host() {
+    let store = store_preloaded_with_genesis_cfg();
+    let host = HostMock::new();
+    let vm = Mvm::new(store, host.clone()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("account_lifecycle", "Onboarding");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let newcomer = AccountAddress::from_hex_literal("0xBEEF").unwrap();
+    let call = |function: &str| {
+        let payer = bcs::to_bytes(&cafe).unwrap();
+        let newcomer = bcs::to_bytes(&newcomer).unwrap();
+        vm.execute_function(
+            cafe,
+            Identifier::new("Onboarding").unwrap(),
+            Identifier::new(function).unwrap(),
+            vec![],
+            vec![&payer, &newcomer],
+            gas,
+        )
+    };
+
+    assert_eq!(host.account_exists(newcomer), Ok(false));
+    let result = call("create");
+    assert!(result.is_ok(), "failed to create the account: {result:?}");
+    assert_eq!(host.account_exists(newcomer), Ok(true));
+
+    // Existing accounts can't be created again, but they can be ensured.
+    let result = call("create");
+    assert_eq!(result.status_code, StatusCode::ABORTED);
+    assert_eq!(result.abort_code, Some(1));
+    let result = call("onboard");
+    assert!(result.is_ok(), "failed to onboard the account: {result:?}");
+}
+
+#[test]
+fn randomness_time_and_hashing_come_from_host() {
+    let store = store_preloaded_with_genesis_cfg();
+    let host = HostMock::new();
+    host.set_randomness([7; 32]);
+    host.set_now_milliseconds(5_500);
+    let vm = Mvm::new(store, host.clone()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("host_environment", "Lottery");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let draw = |opens_at: u64| {
+        let account = bcs::to_bytes(&cafe).unwrap();
+        let opens_at = bcs::to_bytes(&opens_at).unwrap();
+        let bound = bcs::to_bytes(&10u64).unwrap();
+        vm.execute_function(
+            cafe,
+            Identifier::new("Lottery").unwrap(),
+            Identifier::new("draw").unwrap(),
+            vec![],
+            vec![&account, &opens_at, &bound],
+            gas,
+        )
+    };
+
+    let result = draw(6);
+    assert_eq!(result.status_code, StatusCode::ABORTED);
+    assert_eq!(result.abort_code, Some(1));
+
+    host.inject_failure(HostFailure::Randomness, StatusCode::STORAGE_ERROR);
+    let result = draw(5);
+    assert!(
+        !result.is_ok(),
+        "the draw should fail without the randomness"
+    );
+    host.clear_failures();
+
+    let result = draw(5);
+    assert!(result.is_ok(), "failed to draw: {result:?}");
+
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("Lottery").unwrap(),
+        name: Identifier::new("Draw").unwrap(),
+        type_params: vec![],
+    };
+    let resource = vm
+        .get_resource(&cafe, &bcs::to_bytes(&tag).unwrap())
+        .unwrap()
+        .unwrap();
+    let (ticket, winner, drawn_at): (Vec<u8>, u64, u64) = bcs::from_bytes(&resource).unwrap();
+    let seed = host.random_seed(b"ticket").unwrap();
+    assert_eq!(ticket, host.sha2_256(&seed));
+    assert!(winner < 10);
+    assert_eq!(drawn_at, 5);
+}
+
+#[test]
+fn system_calls_are_exempt_from_gas() {
+    struct Root;
+    impl SystemCallCapability for Root {}
+
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("maintenance", "Maintenance");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let module_id = ModuleId::new(cafe, Identifier::new("Maintenance").unwrap());
+    let sweep = Identifier::new("sweep").unwrap();
+    let noop = Identifier::new("noop").unwrap();
+    vm.set_system_calls([(module_id.clone(), sweep.clone())]);
+    assert!(vm.is_system_call(&module_id, &sweep));
+    assert!(!vm.is_system_call(&module_id, &noop));
+
+    // The user gas limit can't afford the sweep.
+    let entries = bcs::to_bytes(&100_000u64).unwrap();
+    let result = vm.execute_function(
+        cafe,
+        module_id.name().to_owned(),
+        sweep.clone(),
+        vec![],
+        vec![&entries],
+        gas,
+    );
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+
+    let result = vm.execute_system_call(&Root, module_id.clone(), sweep, vec![], vec![&entries]);
+    assert!(result.is_ok(), "system call failed: {result:?}");
+
+    // Only the marked functions are system calls.
+    let result = vm.execute_system_call(&Root, module_id, noop, vec![], vec![]);
+    assert_eq!(result.status_code, StatusCode::NO_ACCOUNT_ROLE);
+}
+
+#[test]
+fn bundle_init_script_is_atomic_with_publishing() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(1_000_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bundle = read_bundle_from_project("bundle_init", "bundle_init");
+    let script = read_script_bytes_from_project("bundle_init", "init_config");
+    let signer = bcs::to_bytes(&cafe).unwrap();
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("Config").unwrap(),
+        name: Identifier::new("Settings").unwrap(),
+        type_params: vec![],
+    };
+    let tag = bcs::to_bytes(&tag).unwrap();
+
+    // The aborting script rolls back the whole bundle.
+    let zero_fee = bcs::to_bytes(&0u64).unwrap();
+    let result = vm.publish_module_bundle_with_init(
+        &bundle,
+        cafe,
+        &script,
+        vec![],
+        vec![&signer, &zero_fee],
+        gas,
+    );
+    assert_eq!(result.status_code, StatusCode::ABORTED);
+    assert_eq!(result.abort_code, Some(1));
+    assert!(vm.get_module(cafe, "Config").unwrap().is_none());
+    assert!(vm.get_module(cafe, "Registry").unwrap().is_none());
+    assert!(vm.get_resource(&cafe, &tag).unwrap().is_none());
+
+    let fee = bcs::to_bytes(&7u64).unwrap();
+    let result = vm.publish_module_bundle_with_init(
+        &bundle,
+        cafe,
+        &script,
+        vec![],
+        vec![&signer, &fee],
+        gas,
+    );
+    assert!(result.is_ok(), "failed to publish the bundle: {result:?}");
+    assert!(vm.get_module(cafe, "Config").unwrap().is_some());
+    assert!(vm.get_module(cafe, "Registry").unwrap().is_some());
+    let settings = vm.get_resource(&cafe, &tag).unwrap().unwrap();
+    assert_eq!(settings, fee);
+}
+
+#[test]
+fn transfers_in_a_loop_are_charged_per_account() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+
+    let script = read_script_bytes_from_project("substrate_balance", "transfer_in_loop");
+    let src = bcs::to_bytes(&AccountAddress::from_hex_literal("0xCAFE").unwrap()).unwrap();
+    let dst = bcs::to_bytes(&AccountAddress::from_hex_literal("0x3EEE").unwrap()).unwrap();
+
+    let transfer = &NATIVE_COST_PARAMS.balance.transfer;
+    let transfer_cost: u64 = (transfer.base + transfer.per_account * NumArgs::new(2))
+        .to_unit_round_down::<GasUnit>()
+        .into();
+    assert!(transfer_cost > 0);
+
+    let run = |count: u64, gas| {
+        let count = bcs::to_bytes(&count).unwrap();
+        vm.execute_script(&script, vec![], vec![&src, &dst, &count], gas)
+    };
+
+    let gas = GasStrategy::Metered(GasAmount::new(1_000_000).unwrap());
+    let single = run(1, gas);
+    assert!(single.is_ok(), "failed to execute the script: {single:?}");
+    let many = run(101, gas);
+    assert!(many.is_ok(), "failed to execute the script: {many:?}");
+    assert!(many.gas_used - single.gas_used >= 100 * transfer_cost);
+
+    // A gas limit which affords a handful of transfers can't be stretched over many.
+    let gas = GasStrategy::Metered(GasAmount::new(single.gas_used + 5 * transfer_cost).unwrap());
+    let result = run(1_000, gas);
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+}
+
+#[test]
+fn frozen_accounts_and_resources_reject_writes() {
+    struct Governance;
+    impl FreezeCapability for Governance {}
+
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let publish_balance = read_script_bytes_from_project("basic_coin", "publish_balance");
+    let mint_some = read_script_bytes_from_project("basic_coin", "mint_some");
+    let cafe_param = bcs::to_bytes(&cafe).unwrap();
+    let bob_param = bcs::to_bytes(&bob).unwrap();
+    let amount = bcs::to_bytes(&10u64).unwrap();
+    let result = vm.execute_script(&publish_balance, vec![], vec![&cafe_param], gas);
+    assert!(result.is_ok(), "failed to publish the balance");
+
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    let tag_bytes = bcs::to_bytes(&tag).unwrap();
+    let balance = vm.get_resource(&cafe, &tag_bytes).unwrap();
+
+    // The frozen resource can still be read, but not written.
+    vm.freeze_resource(&Governance, cafe, tag.clone());
+    assert!(vm.is_resource_frozen(&cafe, &tag));
+    let result = vm.execute_script(
+        &mint_some,
+        vec![],
+        vec![&cafe_param, &cafe_param, &amount],
+        gas,
+    );
+    assert_eq!(result.status_code, StatusCode::RESOURCE_FROZEN);
+    assert_eq!(vm.get_resource(&cafe, &tag_bytes).unwrap(), balance);
+
+    // Each frozen entry is kept on its own.
+    vm.freeze_account(&Governance, bob);
+    vm.unfreeze_account(&Governance, bob);
+    assert!(vm.is_resource_frozen(&cafe, &tag));
+    assert!(!vm.is_account_frozen(&cafe));
+
+    vm.unfreeze_resource(&Governance, cafe, tag.clone());
+    assert!(!vm.is_resource_frozen(&cafe, &tag));
+    let result = vm.execute_script(
+        &mint_some,
+        vec![],
+        vec![&cafe_param, &cafe_param, &amount],
+        gas,
+    );
+    assert!(result.is_ok(), "failed to mint: {result:?}");
+
+    // Nothing can be stored under the frozen account, modules included.
+    vm.freeze_account(&Governance, bob);
+    assert!(vm.is_account_frozen(&bob));
+    let result = vm.execute_script(&publish_balance, vec![], vec![&bob_param], gas);
+    assert_eq!(result.status_code, StatusCode::ACCOUNT_FROZEN);
+
+    vm.unfreeze_account(&Governance, bob);
+    let result = vm.execute_script(&publish_balance, vec![], vec![&bob_param], gas);
+    assert!(result.is_ok(), "failed to publish the balance: {result:?}");
+
+    vm.freeze_account(&Governance, cafe);
+    let result = vm.publish_module(&module, cafe, gas);
+    assert_eq!(result.status_code, StatusCode::ACCOUNT_FROZEN);
+}
+
+#[test]
+fn balance_calls_cannot_be_reentered() {
+    let run = |guard| {
+        let store = store_preloaded_with_genesis_cfg();
+        let host = ReentrantHost::new();
+        let reentries = host.balance_reentries.clone();
+        let vm = host.attach(store, guard);
+
+        let script = read_script_bytes_from_project("substrate_balance", "balance_simple_api_test");
+        let src = bcs::to_bytes(&AccountAddress::from_hex_literal("0xCAFE").unwrap()).unwrap();
+        let dst = bcs::to_bytes(&AccountAddress::from_hex_literal("0x3EEE").unwrap()).unwrap();
+        let amount = bcs::to_bytes(&0u128).unwrap();
+        let args: Vec<&[u8]> = vec![&src, &dst, &amount];
+        let result = vm.execute_script(&script, vec![], args, GasStrategy::Unmetered);
+        assert!(result.is_ok(), "failed to execute the script: {result:?}");
+
+        // The script queries the cheque first and transfers afterwards.
+        reentries.take()
+    };
+
+    use StatusCode::{EXECUTED, REENTRANT_BALANCE_CALL};
+    assert_eq!(run(ReentrancyGuard::Disabled), vec![EXECUTED, EXECUTED]);
+    assert_eq!(
+        run(ReentrancyGuard::BalanceChanges),
+        vec![EXECUTED, REENTRANT_BALANCE_CALL]
+    );
+    assert_eq!(
+        run(ReentrancyGuard::Strict),
+        vec![REENTRANT_BALANCE_CALL, REENTRANT_BALANCE_CALL]
+    );
+}
+
+#[test]
+fn dry_run_reports_script_lint_warnings() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+
+    let script = read_script_bytes_from_project("simple_scripts", "unchecked_double");
+    let param = bcs::to_bytes(&21u64).unwrap();
+    let report = vm.dry_run_script(&script, vec![TypeTag::U8], vec![&param]);
+    assert!(report.result.is_ok(), "failed to dry-run the script");
+
+    assert!(report
+        .warnings
+        .contains(&LintWarning::UnusedTypeParameter(0)));
+    assert!(report
+        .warnings
+        .iter()
+        .any(|warning| matches!(warning, LintWarning::UncheckedArithmetic(_))));
+    assert_eq!(vm.lint_script(&script).unwrap(), report.warnings);
+
+    let report = vm.dry_run_script(&[0xde, 0xad], vec![], vec![]);
+    assert!(report.result.is_err());
+    assert!(report.warnings.is_empty());
+    assert!(vm.lint_script(&[0xde, 0xad]).is_err());
+}
+
+#[test]
+fn storage_usage_tracks_modules_and_resources() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store.clone(), HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    assert_eq!(vm.storage_usage(&cafe).unwrap(), 0);
+
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+    let module_usage = vm.storage_usage(&cafe).unwrap();
+    assert!(module_usage > 0);
+
+    let script = read_script_bytes_from_project("basic_coin", "publish_balance");
+    for who in [cafe, bob] {
+        let addr_param = bcs::to_bytes(&who).unwrap();
+        let result = vm.execute_script(&script, vec![], vec![&addr_param], gas);
+        assert!(result.is_ok(), "script execution failed for {who}");
+    }
+
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    let resource = vm
+        .get_resource(&bob, &bcs::to_bytes(&tag).unwrap())
+        .unwrap()
+        .expect("resource not found");
+    let resource_usage = resource.len() as u64;
+    assert_eq!(vm.storage_usage(&bob).unwrap(), resource_usage);
+    assert_eq!(
+        vm.storage_usage(&cafe).unwrap(),
+        module_usage + resource_usage
+    );
+
+    // Accounts written without the tracked usage are measured from their data.
+    store.remove(&[b"storage_usage::".as_slice(), cafe.as_slice()].concat());
+    assert_eq!(
+        vm.storage_usage(&cafe).unwrap(),
+        module_usage + resource_usage
+    );
+}
+
+#[test]
+fn multi_address_bundle_is_published_atomically() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let bundle = read_bundle_from_project("multi_address", "multi_address");
+
+    // A single sender can't publish the modules of the other address.
+    let result = vm.publish_module_bundle(&bundle, cafe, gas);
+    assert_eq!(
+        result.status_code,
+        StatusCode::MODULE_ADDRESS_DOES_NOT_MATCH_SENDER
+    );
+    let result = vm.publish_multi_address_bundle(&bundle, &[cafe], gas);
+    assert_eq!(
+        result.status_code,
+        StatusCode::MODULE_ADDRESS_DOES_NOT_MATCH_SENDER
+    );
+    assert!(vm.get_module(cafe, "Token").unwrap().is_none());
+    assert!(vm.get_module(bob, "Vault").unwrap().is_none());
+
+    let result = vm.publish_multi_address_bundle(&bundle, &[cafe, bob], gas);
+    assert!(result.is_ok(), "failed to publish the bundle: {result:?}");
+    assert!(vm.get_module(cafe, "Token").unwrap().is_some());
+    assert!(vm.get_module(bob, "Vault").unwrap().is_some());
+}
+
+#[test]
+fn storage_keys_follow_the_golden_layout() {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let golden_key = [[0u8; 30].as_slice(), &[0xca, 0xfe]].concat();
+
+    let module_key = StorageKey::module(cafe, IdentStr::new("M").unwrap());
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("Coin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    let resource_key = StorageKey::resource(cafe, tag);
+    assert_eq!(module_key.key(), golden_key);
+    assert_eq!(resource_key.key(), golden_key);
+
+    // Modules: {"M": [1, 2]}, resources: {0xCAFE::Coin::Balance: [3]}.
+    let golden_entry = [
+        [1, 1, b'M', 2, 1, 2, 1].as_slice(),
+        &golden_key,
+        &[4, b'C', b'o', b'i', b'n'],
+        &[7, b'B', b'a', b'l', b'a', b'n', b'c', b'e', 0],
+        &[1, 3],
+    ]
+    .concat();
+    assert_eq!(module_key.read(&golden_entry).unwrap(), Some(vec![1, 2]));
+    assert_eq!(resource_key.read(&golden_entry).unwrap(), Some(vec![3]));
+    let missing = StorageKey::module(cafe, IdentStr::new("N").unwrap());
+    assert_eq!(missing.read(&golden_entry).unwrap(), None);
+
+    // The keys locate the data written by the MoveVM.
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store.clone(), HostMock::new()).unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let key = StorageKey::module(cafe, IdentStr::new("BasicCoin").unwrap());
+    let entry = store.get(key.key()).expect("account entry not found");
+    assert_eq!(key.read(&entry).unwrap(), Some(module));
+}
+
+#[test]
+fn events_are_numbered_across_calls() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("event_emitter", "Ping");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+    assert_eq!(vm.next_event_sequence(), 0);
+
+    let script = read_script_bytes_from_project("event_emitter", "emit_pings");
+    let emit = |count: u64, gas| {
+        let count = bcs::to_bytes(&count).unwrap();
+        let result = vm.execute_script(&script, vec![], vec![&count], gas);
+        assert!(result.is_ok(), "failed to execute the script: {result:?}");
+        result
+            .events
+            .iter()
+            .map(|event| (event.index, event.sequence_number))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(emit(3, gas), vec![(0, 0), (1, 1), (2, 2)]);
+    assert_eq!(emit(2, gas), vec![(0, 3), (1, 4)]);
+    assert_eq!(vm.next_event_sequence(), 5);
+
+    // Dry runs don't advance the sequence.
+    assert_eq!(emit(1, GasStrategy::DryRun), vec![(0, 5)]);
+    assert_eq!(emit(0, gas), vec![]);
+    assert_eq!(emit(1, gas), vec![(0, 5)]);
+    assert_eq!(vm.next_event_sequence(), 6);
+}
+
+#[test]
+fn oversized_events_fail_the_transaction() {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("event_emitter", "Ping");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    // Each ping carries a single u64.
+    let script = read_script_bytes_from_project("event_emitter", "emit_pings");
+    let count = bcs::to_bytes(&2u64).unwrap();
+
+    vm.set_max_event_size(Some(7));
+    let result = vm.execute_script(&script, vec![], vec![&count], gas);
+    assert_eq!(result.status_code, StatusCode::EVENT_DATA_TOO_LARGE);
+    assert!(result.events.is_empty());
+    assert_eq!(vm.next_event_sequence(), 0);
+
+    vm.set_max_event_size(Some(8));
+    let result = vm.execute_script(&script, vec![], vec![&count], gas);
+    assert!(result.is_ok(), "failed to execute the script: {result:?}");
+    assert_eq!(result.events.len(), 2);
+    assert_eq!(vm.next_event_sequence(), 2);
+}
+
+#[test]
+fn script_templates_bind_the_arguments() {
+    let param = |name: &str, ty| TemplateParam {
+        name: name.to_owned(),
+        description: format!("The {name}"),
+        ty,
+    };
+    let publish_balance = ScriptTemplate {
+        bytecode: read_script_bytes_from_project("basic_coin", "publish_balance"),
+        description: "Publish an empty BasicCoin balance".to_owned(),
+        params: vec![param("owner", TemplateParamType::Signer)],
+    };
+    let name = IdentStr::new("publish_balance").unwrap();
+
+    let mut genesis_cfg = VmGenesisConfig::default();
+    genesis_cfg.add_script_template(name.to_owned(), publish_balance.clone());
+    let store = StorageMock::new();
+    assert!(genesis_cfg.apply(store.clone()).is_ok());
+
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+    assert_eq!(vm.get_script_template(name), Some(publish_balance));
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    assert!(vm.publish_module(&module, cafe, gas).is_ok());
+
+    // The arguments must match the parameters.
+    let missing = IdentStr::new("transfer").unwrap();
+    assert_eq!(
+        vm.execute_template(missing, &[], gas).unwrap_err(),
+        TemplateError::NotFound
+    );
+    assert_eq!(
+        vm.execute_template(name, &[], gas).unwrap_err(),
+        TemplateError::ArgumentCountMismatch {
+            expected: 1,
+            provided: 0
+        }
+    );
+    assert_eq!(
+        vm.execute_template(name, &[TemplateArg::Address(cafe)], gas)
+            .unwrap_err(),
+        TemplateError::ArgumentTypeMismatch(0)
+    );
+
+    let result = vm
+        .execute_template(name, &[TemplateArg::Signer(cafe)], gas)
+        .unwrap();
+    assert!(result.is_ok(), "failed to execute the template: {result:?}");
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    let tag = bcs::to_bytes(&tag).unwrap();
+    assert!(vm.get_resource(&cafe, &tag).unwrap().is_some());
+
+    // The documented parameters must match the script signature.
+    let mint_some = ScriptTemplate {
+        bytecode: read_script_bytes_from_project("basic_coin", "mint_some"),
+        description: "Mint BasicCoins".to_owned(),
+        params: vec![
+            param("module_owner", TemplateParamType::Signer),
+            param("amount", TemplateParamType::U64),
+        ],
+    };
+    let mint_name = IdentStr::new("mint_some").unwrap();
+    assert_eq!(
+        vm.register_script_template(mint_name, &mint_some),
+        Err(TemplateError::SchemaMismatch)
+    );
+
+    vm.remove_script_template(name);
+    assert_eq!(vm.get_script_template(name), None);
+}
+
+#[test]
+fn modules_are_retired_only_when_unused() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let addr = AccountAddress::from_hex_literal("0x2").unwrap();
+    let bundle = read_bundle_from_project("using_stdlib_natives", "using_stdlib_natives");
+    let result = vm.publish_module_bundle(&bundle, addr, gas);
+    assert!(result.is_ok(), "failed to publish the bundle");
+
+    let vector = Identifier::new("Vector").unwrap();
+    let depends_on_vector = Identifier::new("DependsOnVector").unwrap();
+    assert_eq!(
+        vm.retire_module(addr, &vector),
+        Err(RetireError::HasDependents(vec![ModuleId::new(
+            addr,
+            depends_on_vector.clone()
+        )]))
+    );
+
+    // Once the dependent is retired, the module is unused.
+    assert_eq!(vm.retire_module(addr, &depends_on_vector), Ok(()));
+    assert_eq!(vm.retire_module(addr, &vector), Ok(()));
+    assert!(vm.get_module(addr, "Vector").unwrap().is_none());
+    assert_eq!(vm.retire_module(addr, &vector), Err(RetireError::NotFound));
+
+    // The modules whose resources exist need the governance to retire them.
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+    let script = read_script_bytes_from_project("basic_coin", "publish_balance");
+    let who = bcs::to_bytes(&cafe).unwrap();
+    let result = vm.execute_script(&script, vec![], vec![&who], gas);
+    assert!(result.is_ok(), "failed to publish the balance");
+
+    let basic_coin = Identifier::new("BasicCoin").unwrap();
+    assert_eq!(
+        vm.retire_module(cafe, &basic_coin),
+        Err(RetireError::HasResources(1))
+    );
+
+    struct Governance;
+    impl PublishCapability for Governance {}
+    assert_eq!(
+        vm.force_retire_module(&Governance, cafe, &basic_coin),
+        Ok(())
+    );
+    assert!(vm.get_module(cafe, "BasicCoin").unwrap().is_none());
+
+    // The resources are still counted once the module is published again.
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module again");
+    assert_eq!(
+        vm.retire_module(cafe, &basic_coin),
+        Err(RetireError::HasResources(1))
+    );
+}
+
+#[test]
+fn modules_published_before_the_index_are_not_retired() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store.clone(), HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("empty", "Empty");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    // Modules published before the index existed have no resource count.
+    let empty = Identifier::new("Empty").unwrap();
+    let module_id = bcs::to_bytes(&ModuleId::new(cafe, empty.clone())).unwrap();
+    store.remove(&[b"module_resources::".as_slice(), &module_id].concat());
+    assert_eq!(vm.retire_module(cafe, &empty), Err(RetireError::NotIndexed));
+    assert!(vm.get_module(cafe, "Empty").unwrap().is_some());
+
+    struct Governance;
+    impl PublishCapability for Governance {}
+    assert_eq!(vm.force_retire_module(&Governance, cafe, &empty), Ok(()));
+    assert!(vm.get_module(cafe, "Empty").unwrap().is_none());
+}
+
+#[test]
+fn host_reported_weight_is_charged_to_the_caller() {
+    let host = HostMock::new();
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, host.clone()).unwrap();
+
+    let script = read_script_bytes_from_project("substrate_balance", "transfer_in_loop");
+    let src = bcs::to_bytes(&AccountAddress::from_hex_literal("0xCAFE").unwrap()).unwrap();
+    let dst = bcs::to_bytes(&AccountAddress::from_hex_literal("0x3EEE").unwrap()).unwrap();
+    let count = bcs::to_bytes(&10u64).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(1_000_000).unwrap());
+
+    let result = vm.execute_script(&script, vec![], vec![&src, &dst, &count], gas);
+    assert!(result.is_ok(), "failed to execute the script: {result:?}");
+    let baseline = result.gas_used;
+
+    // Every transfer reports a weight worth 5_000 internal gas units.
+    host.set_transfer_weight(50_000);
+    vm.set_host_weight_ratio(10);
+    let weight_cost: u64 = InternalGas::new(10 * 5_000)
+        .to_unit_round_down::<GasUnit>()
+        .into();
+    assert!(weight_cost > 0);
+
+    let result = vm.execute_script(&script, vec![], vec![&src, &dst, &count], gas);
+    assert!(result.is_ok(), "failed to execute the script: {result:?}");
+    assert!(result.gas_used >= baseline + weight_cost);
+    assert!(result.gas_used <= baseline + weight_cost + 1);
+
+    // The reported weight can exhaust the gas.
+    let gas = GasStrategy::Metered(GasAmount::new(baseline + weight_cost / 2).unwrap());
+    let result = vm.execute_script(&script, vec![], vec![&src, &dst, &count], gas);
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+}
+
+#[test]
+fn recursion_is_limited_by_the_call_depth() {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("deep_recursion", "Recursion");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let script = read_script_bytes_from_project("deep_recursion", "recurse");
+    let recurse = |vm: &Mvm<_, _>, depth: u64, mutual: bool| {
+        let depth = bcs::to_bytes(&depth).unwrap();
+        let mutual = bcs::to_bytes(&mutual).unwrap();
+        vm.execute_script(&script, vec![], vec![&depth, &mutual], gas)
+    };
+
+    // Without any gas limit, only the default depth stops the recursion.
+    let result = recurse(&vm, 100, false);
+    assert!(result.is_ok(), "failed to execute the script: {result:?}");
+    for mutual in [false, true] {
+        let result = recurse(&vm, 100_000, mutual);
+        assert_eq!(result.status_code, StatusCode::CALL_STACK_OVERFLOW);
+    }
+
+    // The script and the outermost call take two frames, the running function takes none.
+    vm.set_max_call_depth(50).unwrap();
+    for mutual in [false, true] {
+        let result = recurse(&vm, 49, mutual);
+        assert!(result.is_ok(), "failed to execute the script: {result:?}");
+        let result = recurse(&vm, 50, mutual);
+        assert_eq!(result.status_code, StatusCode::CALL_STACK_OVERFLOW);
+    }
+}
+
+#[test]
+fn call_gas_limits_cap_the_calls_into_the_module() {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("deep_recursion", "Recursion");
+    let result = vm.publish_module(&module, cafe, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let script = read_script_bytes_from_project("deep_recursion", "recurse");
+    let recurse = |vm: &Mvm<_, _>, depth: u64, gas| {
+        let depth = bcs::to_bytes(&depth).unwrap();
+        let mutual = bcs::to_bytes(&false).unwrap();
+        vm.execute_script(&script, vec![], vec![&depth, &mutual], gas)
+    };
+
+    // With the instruction counting, the limit counts the instructions.
+    let gas = GasStrategy::InstructionCount(1_000_000);
+    let recursion = ModuleId::new(cafe, Identifier::new("Recursion").unwrap());
+    vm.set_call_gas_limit(recursion.clone(), GasAmount::new(200).unwrap());
+
+    // The recursive calls share the limit of the outermost call.
+    let result = recurse(&vm, 5, gas);
+    assert!(result.is_ok(), "failed to execute the script: {result:?}");
+    let result = recurse(&vm, 100, gas);
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+    assert!(
+        result.gas_used < 250,
+        "the call used {} gas",
+        result.gas_used
+    );
+
+    // The limit applies on top of the transaction gas.
+    vm.set_call_gas_limit(recursion.clone(), GasAmount::new(1).unwrap());
+    let result = recurse(&vm, 5, GasStrategy::Metered(GasAmount::max()));
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+
+    vm.clear_call_gas_limit(&recursion);
+    let result = recurse(&vm, 100, gas);
+    assert!(result.is_ok(), "failed to execute the script: {result:?}");
+}
+
+#[test]
+fn interrupt_handle_aborts_the_running_execution() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+    let script = read_script_bytes_from_project("simple_scripts", "empty_loop_param");
+    let iterations = bcs::to_bytes(&u64::MAX).unwrap();
+
+    // The loop would run far beyond any deadline without the interruption.
+    let interrupt = vm.interrupt_handle();
+    let interrupter = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        interrupt.interrupt();
+    });
+    let result = vm.execute_script(&script, vec![], vec![&iterations], gas);
+    interrupter.join().unwrap();
+    assert_eq!(result.status_code, StatusCode::EXECUTION_INTERRUPTED);
+    assert_eq!(result.gas_used, 0);
+
+    // The flag stays raised until reset.
+    let iterations = bcs::to_bytes(&10u64).unwrap();
+    let result = vm.execute_script(&script, vec![], vec![&iterations], gas);
+    assert_eq!(result.status_code, StatusCode::EXECUTION_INTERRUPTED);
+
+    vm.interrupt_handle().reset();
+    let result = vm.execute_script(&script, vec![], vec![&iterations], gas);
+    assert!(result.is_ok(), "failed to execute the script: {result:?}");
+}
+
+#[test]
+fn native_table_lists_the_base_costs() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+
+    let natives = vm.list_natives();
+    assert!(natives.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(natives.iter().all(|(address, ..)| *address == ADDR_STD));
+
+    // Every native declares its base cost.
+    for (_, module, function, _) in &natives {
+        assert!(
+            NATIVE_COST_PARAMS
+                .base_cost(module.as_str(), function.as_str())
+                .is_some(),
+            "{module}::{function} has no declared base cost"
+        );
+    }
+
+    let base_cost = |module: &str, function: &str| {
+        natives
+            .iter()
+            .find(|(_, m, f, _)| m.as_str() == module && f.as_str() == function)
+            .map(|(.., cost)| *cost)
+    };
+    assert_eq!(
+        base_cost("balance", "transfer"),
+        Some(NATIVE_COST_PARAMS.balance.transfer.base)
+    );
+    assert_eq!(
+        base_cost("foreign", "call"),
+        Some(NATIVE_COST_PARAMS.foreign.call.base)
+    );
+    assert_eq!(base_cost("bcs", "to_bytes"), Some(InternalGas::zero()));
+    assert_eq!(base_cost("foreign", "missing"), None);
+}
+
+#[test]
+fn blocks_flush_the_caches_and_share_the_gas_pool() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(1_000_000).unwrap());
+
+    assert_eq!(vm.end_block(), Err(BlockError::NotStarted));
+    vm.begin_block(None).unwrap();
+    assert_eq!(vm.begin_block(None), Err(BlockError::AlreadyStarted));
+
+    // Layouts are shared within the block, but not across the blocks.
+    let tag = StructTag {
+        address: ADDR_STD,
+        module: Identifier::new("string").unwrap(),
+        name: Identifier::new("String").unwrap(),
+        type_params: vec![],
+    };
+    vm.resource_layout(&tag).unwrap();
+    assert_eq!(vm.layout_cache_stats().entries, 1);
+    let summary = vm.end_block().unwrap();
+    assert_eq!(summary, BlockSummary::default());
+    assert_eq!(vm.layout_cache_stats().entries, 0);
+
+    let script = read_script_bytes_from_project("substrate_balance", "transfer_in_loop");
+    let src = bcs::to_bytes(&AccountAddress::from_hex_literal("0xCAFE").unwrap()).unwrap();
+    let dst = bcs::to_bytes(&AccountAddress::from_hex_literal("0x3EEE").unwrap()).unwrap();
+    let count = bcs::to_bytes(&1u64).unwrap();
+    let transfer = || vm.execute_script(&script, vec![], vec![&src, &dst, &count], gas);
+
+    let result = transfer();
+    assert!(result.is_ok(), "failed to execute the script: {result:?}");
+    let gas_used = result.gas_used;
+
+    // The pool covers two transfers.
+    vm.begin_block(Some(GasAmount::new(2 * gas_used + gas_used / 2).unwrap()))
+        .unwrap();
+    assert!(transfer().is_ok());
+    assert!(transfer().is_ok());
+    let result = transfer();
+    assert_eq!(result.status_code, StatusCode::EXECUTION_LIMIT_REACHED);
+    assert_eq!(
+        vm.end_block(),
+        Ok(BlockSummary {
+            gas_used: 2 * gas_used,
+            transactions: 2,
+            rejected: 1,
+        })
+    );
+
+    // The next block gets a fresh pool.
+    vm.begin_block(Some(GasAmount::new(gas_used).unwrap()))
+        .unwrap();
+    assert!(transfer().is_ok());
+    assert_eq!(vm.end_block().unwrap().transactions, 1);
+}
+
+#[test]
+fn call_sequences_are_atomic() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(1_000_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let call = |func_name: &str| EntryCall {
+        mod_address: cafe,
+        mod_name: Identifier::new("BasicCoin").unwrap(),
+        func_name: Identifier::new(func_name).unwrap(),
+        type_args: vec![],
+        args: vec![bcs::to_bytes(&bob).unwrap()],
+    };
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    let has_balance = || {
+        vm.get_resource(&bob, &bcs::to_bytes(&tag).unwrap())
+            .unwrap()
+            .is_some()
+    };
+
+    // The second call aborts, so the first one is rolled back as well.
+    let calls = vec![call("publish_balance"), call("publish_balance")];
+    let result = vm.execute_calls(calls, gas);
+    assert_eq!(result.status_code, StatusCode::ABORTED);
+    assert_eq!(result.abort_code, Some(2));
+    assert_eq!(result.failed_call, Some(1));
+    assert!(!has_balance());
+
+    // Each call sees the effects of the previous ones.
+    let calls = vec![
+        call("publish_balance"),
+        call("destroy_balance"),
+        call("publish_balance"),
+    ];
+    let result = vm.execute_calls(calls, gas);
+    assert!(result.is_ok(), "failed to execute the calls: {result:?}");
+    assert_eq!(result.failed_call, None);
+    assert!(has_balance());
+
+    // The sequence shares one gas budget.
+    let single = vm.execute_calls(vec![call("destroy_balance")], gas);
+    assert!(single.is_ok(), "failed to execute the call: {single:?}");
+    let gas = GasStrategy::Metered(GasAmount::new(single.gas_used).unwrap());
+    let calls = vec![call("publish_balance"), call("destroy_balance")];
+    let result = vm.execute_calls(calls, gas);
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+    assert!(!has_balance());
+}
+
+#[test]
+fn composed_calls_pass_return_values() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(1_000_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let module = read_module_bytes_from_project("call_composition", "Pipeline");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let pipeline = || CallBuilder::module("0xCAFE::Pipeline");
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("Pipeline").unwrap(),
+        name: Identifier::new("Stored").unwrap(),
+        type_params: vec![],
+    };
+    let stored = |address: &AccountAddress| {
+        vm.get_resource(address, &bcs::to_bytes(&tag).unwrap())
+            .unwrap()
+            .map(|bytes| bcs::from_bytes::<u64>(&bytes).unwrap())
+    };
+
+    // double(21) is stored under Bob's address.
+    let mut composition = Composition::new();
+    let doubled = vm
+        .compose_call(
+            &mut composition,
+            pipeline().function("double").arg(&21u64).unwrap(),
+        )
+        .unwrap();
+    vm.compose_call(
+        &mut composition,
+        pipeline()
+            .function("store")
+            .arg(&bob)
+            .unwrap()
+            .result_arg(doubled, 0),
+    )
+    .unwrap();
+    let result = vm.execute_composition(composition, gas);
+    assert!(
+        result.is_ok(),
+        "failed to execute the composition: {result:?}"
+    );
+    assert_eq!(stored(&bob), Some(42));
+
+    // The second return value of with_square(3) is stored under Cafe's address.
+    let mut composition = Composition::new();
+    let squared = vm
+        .compose_call(
+            &mut composition,
+            pipeline().function("with_square").arg(&3u64).unwrap(),
+        )
+        .unwrap();
+    vm.compose_call(
+        &mut composition,
+        pipeline()
+            .function("store")
+            .arg(&cafe)
+            .unwrap()
+            .result_arg(squared, 1),
+    )
+    .unwrap();
+    let result = vm.execute_composition(composition, gas);
+    assert!(
+        result.is_ok(),
+        "failed to execute the composition: {result:?}"
+    );
+    assert_eq!(stored(&cafe), Some(9));
+
+    // The return values are type checked against the parameters.
+    let mut composition = Composition::new();
+    let bytes = vm
+        .compose_call(
+            &mut composition,
+            pipeline().function("bytes").arg(&7u8).unwrap(),
+        )
+        .unwrap();
+    let err = vm
+        .compose_call(
+            &mut composition,
+            pipeline().function("double").result_arg(bytes, 0),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        CallBuilderError::ResultTypeMismatch(0).to_string()
+    );
+    assert_eq!(composition.calls().len(), 1);
+
+    // Only the return values of the earlier calls can be referenced.
+    let err = vm
+        .compose_call(
+            &mut composition,
+            pipeline().function("double").result_arg(1, 0),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        CallBuilderError::InvalidResultReference(0).to_string()
+    );
+}
+
+#[test]
+fn fee_policy_converts_gas_with_block_multiplier() {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(1_000_000).unwrap());
+
+    let script = read_script_bytes_from_project("substrate_balance", "transfer_in_loop");
+    let src = bcs::to_bytes(&AccountAddress::from_hex_literal("0xCAFE").unwrap()).unwrap();
+    let dst = bcs::to_bytes(&AccountAddress::from_hex_literal("0x3EEE").unwrap()).unwrap();
+    let count = bcs::to_bytes(&1u64).unwrap();
+    let transfer =
+        |vm: &Mvm<_, _>| vm.execute_script(&script, vec![], vec![&src, &dst, &count], gas);
+
+    // No fees are reported without a policy.
+    let result = transfer(&vm);
+    assert!(result.is_ok(), "failed to execute the script: {result:?}");
+    assert_eq!(result.fee, 0);
+    let gas_used = u128::from(result.gas_used);
+
+    vm.set_fee_policy(LinearFee { price_per_gas: 10 });
+    let result = transfer(&vm);
+    assert_eq!(result.fee, gas_used * 10);
+    assert_eq!(vm.fee_for(100), 1_000);
+
+    // The multiplier only applies within the block.
+    let multiplier = FeeMultiplier::from_millionths(1_500_000);
+    assert_eq!(
+        vm.set_block_fee_multiplier(multiplier),
+        Err(BlockError::NotStarted)
+    );
+    vm.begin_block(None).unwrap();
+    vm.set_block_fee_multiplier(multiplier).unwrap();
+    let result = transfer(&vm);
+    assert_eq!(result.fee, gas_used * 15);
+    assert_eq!(vm.fee_for(1), 15);
+    vm.end_block().unwrap();
+    assert_eq!(vm.fee_for(1), 10);
+
+    // The multiplied fees are rounded up.
+    assert_eq!(FeeMultiplier::from_millionths(1).apply(1), 1);
+    assert_eq!(FeeMultiplier::ONE.apply(u128::MAX), u128::MAX);
+
+    vm.set_fee_policy(|gas_used: u64, _: FeeMultiplier| u128::from(gas_used) + 1);
+    assert_eq!(transfer(&vm).fee, gas_used + 1);
+    vm.clear_fee_policy();
+    assert_eq!(transfer(&vm).fee, 0);
+}
+
+#[test]
+fn chain_config_is_written_at_genesis_and_readable_by_contracts() {
+    struct TestChain;
+
+    impl ChainConfigProvider for TestChain {
+        fn chain_id(&self) -> u64 {
+            42
+        }
+
+        fn token_decimals(&self) -> u8 {
+            12
+        }
+    }
+
+    let store = StorageMock::new();
+    let mut genesis_cfg = VmGenesisConfig::default();
+    genesis_cfg.configure_chain(&TestChain);
+    assert!(genesis_cfg.apply(store.clone()).is_ok());
+
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    assert_eq!(
+        vm.get_chain_config().unwrap(),
+        Some(ChainConfig::new(42, 12))
+    );
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("chain_aware", "Amounts");
+    let result = vm.publish_module(&module, cafe, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let call = EntryCall {
+        mod_address: cafe,
+        mod_name: Identifier::new("Amounts").unwrap(),
+        func_name: Identifier::new("record").unwrap(),
+        type_args: vec![],
+        args: vec![bcs::to_bytes(&cafe).unwrap()],
+    };
+    let result = vm.execute_call(call, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to execute the call: {result:?}");
+
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("Amounts").unwrap(),
+        name: Identifier::new("Seen").unwrap(),
+        type_params: vec![],
+    };
+    let seen = vm
+        .get_resource(&cafe, &bcs::to_bytes(&tag).unwrap())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        bcs::from_bytes::<(u64, u128)>(&seen).unwrap(),
+        (42, 1_000_000_000_000)
+    );
+
+    // Without the configuration, nothing is written.
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    assert_eq!(vm.get_chain_config().unwrap(), None);
+}
+
+#[test]
+fn subscribers_receive_the_finalized_changes() {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(1_000_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+
+    let (sender, receiver) = std::sync::mpsc::channel::<FinalizedChanges>();
+    let id = vm.subscribe(sender);
+
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+    let changes = receiver.try_recv().unwrap();
+    assert!(changes.events.is_empty());
+    assert_eq!(changes.writes.len(), 1);
+    assert_eq!(changes.writes[0].address, cafe);
+    assert_eq!(
+        changes.writes[0].path,
+        StatePath::Module(Identifier::new("BasicCoin").unwrap())
+    );
+
+    let call = EntryCall {
+        mod_address: cafe,
+        mod_name: Identifier::new("BasicCoin").unwrap(),
+        func_name: Identifier::new("publish_balance").unwrap(),
+        type_args: vec![],
+        args: vec![bcs::to_bytes(&bob).unwrap()],
+    };
+
+    // Dry runs and failures are not reported.
+    let result = vm.execute_call(call.clone(), GasStrategy::DryRun);
+    assert!(result.is_ok(), "failed to execute the call: {result:?}");
+    assert!(receiver.try_recv().is_err());
+
+    let result = vm.execute_call(call.clone(), gas);
+    assert!(result.is_ok(), "failed to execute the call: {result:?}");
+    let changes = receiver.try_recv().unwrap();
+    assert_eq!(changes.writes.len(), 1);
+    assert_eq!(changes.writes[0].address, bob);
+    assert!(changes.writes[0].value.is_some());
+
+    let result = vm.execute_call(call.clone(), gas);
+    assert_eq!(result.status_code, StatusCode::ABORTED);
+    assert!(receiver.try_recv().is_err());
+
+    assert!(vm.unsubscribe(id));
+    assert!(!vm.unsubscribe(id));
+    let destroy = EntryCall {
+        func_name: Identifier::new("destroy_balance").unwrap(),
+        ..call
+    };
+    let result = vm.execute_call(destroy, gas);
+    assert!(result.is_ok(), "failed to execute the call: {result:?}");
+    assert!(receiver.try_recv().is_err());
+}
+
+#[test]
+fn find_resources_lists_all_instantiations_of_the_module() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    for name in ["Vault", "Ledger"] {
+        let module = read_module_bytes_from_project("generic_vaults", name);
+        let result = vm.publish_module(&module, cafe, gas);
+        assert!(result.is_ok(), "failed to publish the module: {result:?}");
+    }
+
+    let vault = ModuleId::new(cafe, Identifier::new("Vault").unwrap());
+    let ledger = ModuleId::new(cafe, Identifier::new("Ledger").unwrap());
+    assert!(vm.find_resources(&bob, &vault).unwrap().is_empty());
+
+    let call = |module: &ModuleId, function: &str, type_args, amount: u64| EntryCall {
+        mod_address: cafe,
+        mod_name: module.name().to_owned(),
+        func_name: Identifier::new(function).unwrap(),
+        type_args,
+        args: vec![
+            bcs::to_bytes(&bob).unwrap(),
+            bcs::to_bytes(&amount).unwrap(),
+        ],
+    };
+    for call in [
+        call(&vault, "deposit", vec![TypeTag::U64], 10),
+        call(&vault, "deposit", vec![TypeTag::Bool], 20),
+        call(&ledger, "record", vec![], 30),
+    ] {
+        let result = vm.execute_call(call, gas);
+        assert!(result.is_ok(), "failed to execute the call: {result:?}");
+    }
+
+    let tag = |name: &str, type_params| StructTag {
+        address: cafe,
+        module: vault.name().to_owned(),
+        name: Identifier::new(name).unwrap(),
+        type_params,
+    };
+    let resources = vm.find_resources(&bob, &vault).unwrap();
+    let tags: Vec<_> = resources.iter().map(|(tag, _)| tag.clone()).collect();
+    assert_eq!(
+        tags,
+        vec![
+            tag("Owner", vec![]),
+            tag("Vault", vec![TypeTag::Bool]),
+            tag("Vault", vec![TypeTag::U64]),
+        ]
+    );
+    assert_eq!(resources[2].1, bcs::to_bytes(&10u64).unwrap());
+
+    let resources = vm.find_resources(&bob, &ledger).unwrap();
+    assert_eq!(resources.len(), 1);
+    assert_eq!(resources[0].1, bcs::to_bytes(&30u64).unwrap());
+
+    // Other modules of the same name don't match.
+    let elsewhere = ModuleId::new(bob, vault.name().to_owned());
+    assert!(vm.find_resources(&bob, &elsewhere).unwrap().is_empty());
+}
+
+#[test]
+fn error_context_tells_loading_from_execution() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    // The dependency of the second module isn't published yet.
+    let module_1 = read_module_bytes_from_project("using_stdlib_natives", "Vector");
+    let module_2 = read_module_bytes_from_project("using_stdlib_natives", "DependsOnVector");
+    let addr = AccountAddress::from_hex_literal("0x2").unwrap();
+    let modules = ModuleBundle::new(vec![module_2, module_1])
+        .encode()
+        .unwrap();
+    let result = vm.publish_module_bundle(&modules, addr, gas);
+    let context = result.error_context.expect("missing error context");
+    assert_eq!(context.stage, ErrorStage::Loading);
+    assert_eq!(context.frame, None);
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+    assert_eq!(result.error_context, None);
+
+    let call = EntryCall {
+        mod_address: cafe,
+        mod_name: Identifier::new("BasicCoin").unwrap(),
+        func_name: Identifier::new("publish_balance").unwrap(),
+        type_args: vec![],
+        args: vec![bcs::to_bytes(&cafe).unwrap()],
+    };
+    let result = vm.execute_call(call.clone(), gas);
+    assert!(result.is_ok(), "failed to publish the balance");
+
+    // The second balance aborts in the called module.
+    let result = vm.execute_call(call, gas);
+    assert_eq!(result.status_code, StatusCode::ABORTED);
+    let context = result.error_context.expect("missing error context");
+    assert_eq!(context.stage, ErrorStage::Execution);
+    assert_eq!(
+        context.module,
+        Some(ModuleId::new(cafe, Identifier::new("BasicCoin").unwrap()))
+    );
+    assert_eq!(
+        context.function,
+        Some(Identifier::new("publish_balance").unwrap())
+    );
+    assert!(context.frame.is_some());
+}
+
+#[test]
+fn string_and_option_args_are_validated() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("std_args", "Profile");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module: {result:?}");
+
+    let create = |account: &AccountAddress, name: Vec<u8>, age: Vec<u8>| EntryCall {
+        mod_address: cafe,
+        mod_name: Identifier::new("Profile").unwrap(),
+        func_name: Identifier::new("create").unwrap(),
+        type_args: vec![],
+        args: vec![
+            bcs::to_bytes(account).unwrap(),
+            name,
+            age,
+            bcs::to_bytes(&vec!["admin", "owner"]).unwrap(),
+        ],
+    };
+
+    // The arguments are encoded straight from the Rust values.
+    let alice = AccountAddress::from_hex_literal("0xA11CE").unwrap();
+    let name = bcs::to_bytes("Alice").unwrap();
+    let result = vm.execute_call(
+        create(&alice, name, bcs::to_bytes(&Some(30u64)).unwrap()),
+        gas,
+    );
+    assert!(result.is_ok(), "failed to execute the call: {result:?}");
+
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let name = bcs::to_bytes("Bob").unwrap();
+    let result = vm.execute_call(
+        create(&bob, name, bcs::to_bytes(&None::<u64>).unwrap()),
+        gas,
+    );
+    assert!(result.is_ok(), "failed to execute the call: {result:?}");
+
+    let profile = ModuleId::new(cafe, Identifier::new("Profile").unwrap());
+    let resources = vm.find_resources(&alice, &profile).unwrap();
+    assert_eq!(
+        resources[0].1,
+        bcs::to_bytes(&("Alice", Some(30u64), vec!["admin", "owner"])).unwrap()
+    );
+
+    // Invalid UTF-8 and options with several elements are rejected before the execution.
+    let carol = AccountAddress::from_hex_literal("0xCA01").unwrap();
+    let name = bcs::to_bytes(&vec![0xffu8, 0xfe]).unwrap();
+    let result = vm.execute_call(
+        create(&carol, name, bcs::to_bytes(&None::<u64>).unwrap()),
+        gas,
+    );
+    assert_eq!(
+        result.status_code,
+        StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT
+    );
+
+    let name = bcs::to_bytes("Carol").unwrap();
+    let age = bcs::to_bytes(&vec![30u64, 31]).unwrap();
+    let result = vm.execute_call(create(&carol, name, age), gas);
+    assert_eq!(
+        result.status_code,
+        StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT
+    );
+    assert!(vm.find_resources(&carol, &profile).unwrap().is_empty());
+}
+
+#[test]
+fn upgrades_invalidate_the_transitive_dependents() {
+    let store = StorageMock::new();
+    let mut vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let hook_reports = reports.clone();
+    vm.set_upgrade_hook(move |report: &InvalidationReport| {
+        hook_reports.borrow_mut().push(report.clone())
+    });
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    for name in ["Feed", "Oracle", "Market"] {
+        let module = read_module_bytes_from_project("price_feed_v1", name);
+        let result = vm.publish_module(&module, cafe, gas);
+        assert!(result.is_ok(), "failed to publish the module: {result:?}");
+    }
+    assert!(reports.borrow().is_empty());
+
+    let market = ModuleId::new(cafe, Identifier::new("Market").unwrap());
+    let record = |account: &AccountAddress| EntryCall {
+        mod_address: cafe,
+        mod_name: market.name().to_owned(),
+        func_name: Identifier::new("record").unwrap(),
+        type_args: vec![],
+        args: vec![bcs::to_bytes(account).unwrap()],
+    };
+
+    // Loads the whole chain into the loader cache.
+    let alice = AccountAddress::from_hex_literal("0xA11CE").unwrap();
+    let result = vm.execute_call(record(&alice), gas);
+    assert!(result.is_ok(), "failed to execute the call: {result:?}");
+
+    let feed = read_module_bytes_from_project("price_feed_v2", "Feed");
+    let bundle = ModuleBundle::new(vec![feed]).encode().unwrap();
+    let result = vm.publish_module_bundle(&bundle, cafe, gas);
+    assert!(result.is_ok(), "failed to upgrade the module: {result:?}");
+
+    let module_id = |name: &str| ModuleId::new(cafe, Identifier::new(name).unwrap());
+    assert_eq!(
+        *reports.borrow(),
+        vec![InvalidationReport {
+            upgraded: vec![module_id("Feed")],
+            dependents: vec![module_id("Market"), module_id("Oracle")],
+        }]
+    );
+
+    // The dependents call the upgraded code right away.
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let result = vm.execute_call(record(&bob), gas);
+    assert!(result.is_ok(), "failed to execute the call: {result:?}");
+
+    let price =
+        |account: &AccountAddress| vm.find_resources(account, &market).unwrap()[0].1.clone();
+    assert_eq!(price(&alice), bcs::to_bytes(&100u64).unwrap());
+    assert_eq!(price(&bob), bcs::to_bytes(&200u64).unwrap());
+}
+
+#[test]
+fn linked_modules_are_charged_once_per_session() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let mut chain_bytes = 0;
+    for name in ["Feed", "Oracle", "Market"] {
+        let module = read_module_bytes_from_project("price_feed_v1", name);
+        chain_bytes += module.len() as u64;
+        let result = vm.publish_module(&module, cafe, gas);
+        assert!(result.is_ok(), "failed to publish the module: {result:?}");
+    }
+
+    let record = |account: &str| EntryCall {
+        mod_address: cafe,
+        mod_name: Identifier::new("Market").unwrap(),
+        func_name: Identifier::new("record").unwrap(),
+        type_args: vec![],
+        args: vec![bcs::to_bytes(&AccountAddress::from_hex_literal(account).unwrap()).unwrap()],
+    };
+
+    // The whole chain is charged even when the loader has it cached already.
+    let cold = vm.execute_call(record("0xA11CE"), gas);
+    assert!(cold.is_ok(), "failed to execute the call: {cold:?}");
+    let warm = vm.execute_call(record("0xB0B"), gas);
+    assert!(warm.is_ok(), "failed to execute the call: {warm:?}");
+    assert_eq!(cold.gas_used, warm.gas_used);
+
+    let chain_cost: u64 =
+        InternalGas::new(3 * DEPENDENCY_COST.total() + chain_bytes * DEPENDENCY_BYTE_COST.total())
+            .to_unit_round_down::<GasUnit>()
+            .into();
+    assert!(chain_cost > 0);
+    assert!(cold.gas_used >= chain_cost);
+
+    // Calls within one session share the linked modules.
+    let both = vm.execute_calls(vec![record("0xCA201"), record("0xDA7E")], gas);
+    assert!(both.is_ok(), "failed to execute the calls: {both:?}");
+    assert!(both.gas_used + chain_cost <= 2 * cold.gas_used + 1);
+}
+
+#[test]
+fn arguments_are_charged_when_metering_is_enabled() {
+    let script = read_script_bytes_from_project("simple_scripts", "vector_lengths");
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+    let numbers = bcs::to_bytes(&Vec::<u64>::new()).unwrap();
+    let bytes = bcs::to_bytes(&vec![0u8; 10_000]).unwrap();
+
+    let execute = |arg_metering: bool| {
+        let mut vm = Mvm::new(StorageMock::new(), HostMock::new()).unwrap();
+        vm.set_arg_metering(arg_metering);
+        let result = vm.execute_script(&script, vec![], vec![&numbers, &bytes], gas);
+        assert!(result.is_ok(), "failed to execute the script: {result:?}");
+        result.gas_used
+    };
+
+    let unmetered = execute(false);
+    let metered = execute(true);
+
+    // Both arguments are vectors of primitives, nested two levels deep.
+    let args_cost: u64 = (argument_cost(numbers.len(), 2) + argument_cost(bytes.len(), 2))
+        .to_unit_round_down::<GasUnit>()
+        .into();
+    assert!(args_cost > 0);
+    assert!(
+        (args_cost..=args_cost + 1).contains(&(metered - unmetered)),
+        "unexpected argument cost: {}",
+        metered - unmetered
+    );
+}
+
+#[test]
+fn warm_up_caches_the_listed_modules_and_their_dependencies() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store.clone(), HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    for name in ["Feed", "Oracle", "Market"] {
+        let module = read_module_bytes_from_project("price_feed_v1", name);
+        let result = vm.publish_module(&module, cafe, gas);
+        assert!(result.is_ok(), "failed to publish the module: {result:?}");
+    }
+
+    // A fresh instance, as after the node restart.
+    let vm = Mvm::new(store, HostMock::new()).unwrap();
+    let module = |name: &str| ModuleId::new(cafe, Identifier::new(name).unwrap());
+    let modules = [module("Market"), module("Feed"), module("Missing")];
+
+    let mut progress = vec![];
+    let report = vm.warm_up_with_progress(&modules, |p| {
+        progress.push((p.module.clone(), p.outcome, p.done, p.total))
+    });
+
+    assert_eq!(report.loaded, vec![module("Market")]);
+    assert_eq!(report.cached, vec![module("Feed")]);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0, module("Missing"));
+    assert!(!report.is_complete());
+    assert!(report.dependencies >= 2);
+    assert_eq!(report.cached_modules, vm.cached_modules());
+
+    assert_eq!(progress.len(), 3);
+    assert_eq!(progress[0], (module("Market"), WarmUpOutcome::Loaded, 1, 3));
+    assert_eq!(progress[1], (module("Feed"), WarmUpOutcome::Cached, 2, 3));
+    assert_eq!(progress[2].2, 3);
+
+    // Everything is cached on the second run.
+    let report = vm.warm_up(&modules[..2]);
+    assert_eq!(report.cached, modules[..2].to_vec());
+    assert_eq!(report.dependencies, 0);
+
+    // The block boundaries reload the warmed-up modules after dropping the caches.
+    let cached_modules = vm.cached_modules();
+    vm.begin_block(None).unwrap();
+    assert_eq!(vm.cached_modules(), cached_modules);
+    vm.end_block().unwrap();
+    assert_eq!(vm.cached_modules(), cached_modules);
+    let report = vm.warm_up(&modules[..2]);
+    assert_eq!(report.cached, modules[..2].to_vec());
+}
+
+#[test]
+fn cyclic_bundles_are_rejected_before_linking() {
+    let vm = Mvm::new(StorageMock::new(), HostMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+    let address = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+
+    let module = |name: &str, dependency: &str| {
+        let mut module = empty_module();
+        module.address_identifiers[0] = address;
+        module.identifiers[0] = Identifier::new(name).unwrap();
+        module
+            .identifiers
+            .push(Identifier::new(dependency).unwrap());
+        module.module_handles.push(ModuleHandle {
+            address: AddressIdentifierIndex(0),
+            name: IdentifierIndex(1),
+        });
+        let mut bytes = vec![];
+        module.serialize(&mut bytes).unwrap();
+        bytes
+    };
+
+    let bundle = ModuleBundle::new(vec![module("Ping", "Pong"), module("Pong", "Ping")]);
+    let result = vm.publish_module_bundle(&bundle.encode().unwrap(), address, gas);
+    assert_eq!(result.status_code, StatusCode::CYCLIC_MODULE_DEPENDENCY);
+    assert_eq!(result.gas_used, 0);
+    assert_eq!(
+        result.error_message.as_deref(),
+        Some(
+            "Cyclic dependency between the bundle modules: \
+             0xcafe::Ping -> 0xcafe::Pong -> 0xcafe::Ping"
+        )
+    );
+
+    let result = vm.publish_module(&module("Narcissus", "Narcissus"), address, gas);
+    assert_eq!(result.status_code, StatusCode::CYCLIC_MODULE_DEPENDENCY);
+    assert_eq!(
+        result.error_message.as_deref(),
+        Some("Module 0xcafe::Narcissus depends on itself")
+    );
+}
+
+#[test]
+fn fee_payer_pays_for_the_failed_execution() {
+    let store = store_preloaded_with_genesis_cfg();
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let payer = AccountAddress::from_hex_literal("0xFEE").unwrap();
+    let balance = HostMock::with_accounts(&[cafe, payer], 1_000_000);
+
+    let mut vm = Mvm::new(store, balance.clone()).unwrap();
+    vm.set_fee_policy(LinearFee { price_per_gas: 1 });
+    let gas_limit = 1_000;
+    let gas = GasStrategy::Metered(GasAmount::new(gas_limit).unwrap());
+
+    let script = read_script_bytes_from_project("substrate_balance", "transfer_in_loop");
+    let transaction = Transaction {
+        call: Call::Script { code: script },
+        type_args: vec![],
+        args: vec![
+            bcs::to_bytes(&cafe).unwrap(),
+            bcs::to_bytes(&AccountAddress::from_hex_literal("0x3EEE").unwrap()).unwrap(),
+            bcs::to_bytes(&1_000_000u64).unwrap(),
+        ],
+    };
+
+    // The execution which runs out of gas burns the whole limit, so nothing is refunded.
+    let result = vm.execute_with_fee_payer(transaction, payer, gas);
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+    assert_eq!(result.gas_used, gas_limit);
+    assert_eq!(result.fee, u128::from(gas_limit));
+    assert!(result.sponsored);
+    assert_eq!(
+        balance.cheque_amount(payer).unwrap(),
+        1_000_000 - u128::from(gas_limit)
+    );
+    assert_eq!(balance.cheque_amount(cafe).unwrap(), 1_000_000);
+}
+
+#[test]
+fn fee_payer_pays_for_the_signers() {
+    let store = store_preloaded_with_genesis_cfg();
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let payer = AccountAddress::from_hex_literal("0xFEE").unwrap();
+    let poor_payer = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let mut balance = HostMock::with_accounts(&[cafe, payer], 1_000_000);
+    balance.write_cheque(poor_payer, 10);
+
+    let mut vm = Mvm::new(store, balance.clone()).unwrap();
+    vm.set_fee_policy(LinearFee { price_per_gas: 1 });
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+
+    let script = read_script_bytes_from_project("substrate_balance", "transfer_in_loop");
+    let transaction = Transaction {
+        call: Call::Script {
+            code: script.clone(),
+        },
+        type_args: vec![],
+        args: vec![
+            bcs::to_bytes(&cafe).unwrap(),
+            bcs::to_bytes(&AccountAddress::from_hex_literal("0x3EEE").unwrap()).unwrap(),
+            bcs::to_bytes(&1u64).unwrap(),
+        ],
+    };
+
+    // The signer authorizes the transfer while the fee payer pays only the used gas.
+    let result = vm.execute_with_fee_payer(transaction.clone(), payer, gas);
+    assert!(result.is_ok(), "failed to execute the script: {result:?}");
+    assert!(result.fee > 0);
+    assert!(result.sponsored);
+    assert_eq!(result.fee_payer, Some(payer));
+    assert_eq!(
+        balance.cheque_amount(payer).unwrap(),
+        1_000_000 - result.fee
+    );
+    assert_eq!(balance.cheque_amount(cafe).unwrap(), 1_000_000);
+
+    // The fee payer must afford the whole gas limit.
+    let result = vm.execute_with_fee_payer(transaction.clone(), poor_payer, gas);
+    assert_eq!(
+        result.status_code,
+        StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE
+    );
+    assert_eq!(result.gas_used, 0);
+    assert_eq!(balance.cheque_amount(poor_payer).unwrap(), 10);
+
+    // The fee payer set on the VM pays for the regular executions as well.
+    vm.set_fee_payer(payer);
+    let payer_balance = balance.cheque_amount(payer).unwrap();
+    let args: Vec<&[u8]> = transaction.args.iter().map(Vec::as_slice).collect();
+    let result = vm.execute_script(&script, vec![], args, gas);
+    assert!(result.is_ok(), "failed to execute the script: {result:?}");
+    assert_eq!(result.fee_payer, Some(payer));
+    assert_eq!(result.refund_error, None);
+    assert_eq!(
+        balance.cheque_amount(payer).unwrap(),
+        payer_balance - result.fee
+    );
+    assert_eq!(balance.cheque_amount(cafe).unwrap(), 1_000_000);
+
+    // A failed refund doesn't change the outcome of the already applied execution.
+    balance.inject_failure(HostFailure::RefundFee, StatusCode::VM_EXTENSION_ERROR);
+    let payer_balance = balance.cheque_amount(payer).unwrap();
+    let result = vm.execute_with_fee_payer(transaction, payer, gas);
+    assert_eq!(result.status_code, StatusCode::EXECUTED);
+    assert_eq!(result.refund_error, Some(StatusCode::VM_EXTENSION_ERROR));
+    assert_eq!(
+        balance.cheque_amount(payer).unwrap(),
+        payer_balance - 100_000
+    );
+}
+
+#[test]
+fn lru_read_cache_keeps_the_recent_entries_within_the_budget() {
+    let store = StorageMock::new();
+    store.set(b"a", &[1; 8]);
+    store.set(b"b", &[2; 8]);
+    store.set(b"c", &[3; 8]);
+
+    // Room for two of the entries.
+    let storage = CachedStorage::new(store.clone(), LruReadCache::new(20));
+    assert_eq!(storage.get(b"a"), Some(vec![1; 8]));
+    assert_eq!(storage.get(b"b"), Some(vec![2; 8]));
+    assert_eq!(storage.get(b"a"), Some(vec![1; 8]));
+    assert_eq!(storage.stats(), CacheStats { hits: 1, misses: 2 });
+
+    // The least recently used entry is evicted.
+    assert_eq!(storage.get(b"c"), Some(vec![3; 8]));
+    assert_eq!(storage.get(b"a"), Some(vec![1; 8]));
+    assert_eq!(storage.get(b"b"), Some(vec![2; 8]));
+    assert_eq!(storage.stats(), CacheStats { hits: 2, misses: 4 });
+
+    // The writes go through and the absent keys are cached as well.
+    storage.set(b"d", &[4]);
+    assert_eq!(store.get(b"d"), Some(vec![4]));
+    storage.remove(b"d");
+    assert_eq!(store.get(b"d"), None);
+    assert_eq!(storage.get(b"d"), None);
+    assert_eq!(storage.stats(), CacheStats { hits: 3, misses: 4 });
+
+    let cache = LruReadCache::new(4);
+    cache.fill(b"key", Some(b"too big"));
+    assert_eq!(cache.get(b"key"), None);
+    assert_eq!(cache.size(), 0);
+}
+
+#[test]
+fn write_back_cache_persists_the_writes_at_the_session_end() {
+    let store = StorageMock::new();
+    store.set(b"stale", &[0]);
+
+    let storage = CachedStorage::new(store.clone(), WriteBackCache::new());
+    storage.set(b"key", &[1]);
+    storage.set(b"key", &[2]);
+    storage.remove(b"stale");
+    assert_eq!(storage.get(b"key"), Some(vec![2]));
+    assert_eq!(storage.get(b"stale"), None);
+    assert_eq!(store.get(b"key"), None);
+    assert_eq!(store.get(b"stale"), Some(vec![0]));
+
+    storage.flush();
+    assert_eq!(store.get(b"key"), Some(vec![2]));
+    assert_eq!(store.get(b"stale"), None);
+
+    // The MoveVM flushes the cache after every transaction.
+    let vm = Mvm::new(
+        CachedStorage::new(store.clone(), WriteBackCache::new()),
+        HostMock::new(),
+    )
+    .unwrap();
+    let module = read_module_bytes_from_project("empty", "Empty");
+    let address = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let result = vm.publish_module(&module, address, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the module: {result:?}");
+
+    let key = StorageKey::module(address, IdentStr::new("Empty").unwrap());
+    assert!(store.get(key.key()).is_some());
+}